@@ -5,8 +5,13 @@
 //! "CSS assumes that every font has font metrics that specify a
 //! characteristic height above the baseline and a depth below it."
 
+use std::sync::Arc;
+
 use fontdue::Font;
-use koala_css::FontMetrics;
+use koala_css::{FontFamily, FontMetrics};
+use koala_std::collections::HashMap;
+
+use crate::renderer::{FamilyKey, select_family};
 
 /// Font metrics implementation backed by fontdue's per-glyph metrics.
 ///
@@ -20,20 +25,56 @@ use koala_css::FontMetrics;
 /// `Font::metrics()` (not `Font::rasterize()`) to avoid the cost of
 /// bitmap generation when only measurements are needed.
 pub struct FontdueFontMetrics<'a> {
+    /// The font used when none of a run's families is loaded.
     font: &'a Font,
+    /// Fonts for `font-family` entries, keyed by
+    /// [`family_key`](crate::renderer::family_key).
+    families: Option<&'a HashMap<FamilyKey, Arc<Font>>>,
 }
 
 impl<'a> FontdueFontMetrics<'a> {
     /// Create a new font metrics provider from a fontdue Font.
     #[must_use]
     pub const fn new(font: &'a Font) -> Self {
-        Self { font }
+        Self {
+            font,
+            families: None,
+        }
+    }
+
+    /// Create a provider that measures each run with the font its
+    /// 'font-family' selects from `families`, falling back to `font`.
+    #[must_use]
+    pub const fn with_families(
+        font: &'a Font,
+        families: &'a HashMap<FamilyKey, Arc<Font>>,
+    ) -> Self {
+        Self {
+            font,
+            families: Some(families),
+        }
+    }
+
+    /// [§ 5 Font Matching Algorithm](https://www.w3.org/TR/css-fonts-4/#font-matching-algorithm)
+    ///
+    /// The font `Renderer::draw_text` draws `font_family` text with, so
+    /// that measured and drawn advances agree.
+    fn font_for(&self, font_family: &[FontFamily]) -> &Font {
+        self.families
+            .and_then(|families| select_family(families, font_family))
+            .map_or(self.font, Arc::as_ref)
     }
 }
 
 impl FontMetrics for FontdueFontMetrics<'_> {
     #[allow(clippy::cast_precision_loss)]
-    fn text_width(&self, text: &str, font_size: f32, letter_spacing: f32) -> f32 {
+    fn text_width(
+        &self,
+        text: &str,
+        font_size: f32,
+        letter_spacing: f32,
+        font_family: &[FontFamily],
+    ) -> f32 {
         // Sum per-character advance widths, matching the cursor advancement
         // used in Renderer::draw_text (renderer.rs). Adds
         // `(n_chars - 1) * letter_spacing` between adjacent glyphs;
@@ -43,10 +84,11 @@ impl FontMetrics for FontdueFontMetrics<'_> {
         //
         // Uses Font::metrics() instead of Font::rasterize() to avoid
         // generating bitmaps when only measurements are needed.
+        let font = self.font_for(font_family);
         let mut sum: f32 = 0.0;
         let mut n: usize = 0;
         for ch in text.chars().filter(|ch| !ch.is_control()) {
-            sum += self.font.metrics(ch, font_size).advance_width;
+            sum += font.metrics(ch, font_size).advance_width;
             n += 1;
         }
         sum + n.saturating_sub(1) as f32 * letter_spacing
@@ -0,0 +1,123 @@
+//! Fragment navigation (`page.html#section`).
+//!
+//! [§ 7.4.6.4 Scrolling to a fragment](https://html.spec.whatwg.org/multipage/browsing-the-web.html#scrolling-to-a-fragment)
+//!
+//! After a document loads, the fragment of its URL selects an *indicated
+//! part* of the document, which the user agent scrolls into view. This
+//! module resolves the indicated part and its position in the laid-out
+//! page; the actual scrolling is left to the front end.
+
+use koala_css::{BoxType, LayoutBox};
+use koala_dom::{DomTree, NodeId};
+
+/// [§ 7.4.6.4](https://html.spec.whatwg.org/multipage/browsing-the-web.html#the-indicated-part-of-the-document)
+///
+/// "The indicated part of the document is the one that the fragment, if
+/// any, identifies."
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndicatedPart {
+    /// "the special value top of the document"
+    TopOfDocument,
+    /// An element whose ID (or, for `<a>`, `name`) matches the fragment.
+    Element(NodeId),
+}
+
+/// The fragment of `url`: everything after the first `#`, or `None` if
+/// the URL has no fragment.
+///
+/// [URL Standard § 4.1](https://url.spec.whatwg.org/#concept-url-fragment)
+///
+/// "A URL's fragment is either null or an ASCII string that can be used
+/// for further processing on the resource the URL's other components
+/// identify."
+#[must_use]
+pub fn url_fragment(url: &str) -> Option<&str> {
+    url.split_once('#').map(|(_, fragment)| fragment)
+}
+
+/// [§ 7.4.6.4 select the indicated part](https://html.spec.whatwg.org/multipage/browsing-the-web.html#select-the-indicated-part)
+///
+/// Returns `None` when the fragment identifies nothing, in which case the
+/// user agent does not scroll.
+#[must_use]
+pub fn select_indicated_part(dom: &DomTree, fragment: &str) -> Option<IndicatedPart> {
+    // STEP 1: "If fragment is the empty string, then return the special
+    // value top of the document."
+    if fragment.is_empty() {
+        return Some(IndicatedPart::TopOfDocument);
+    }
+
+    // STEP 2: "Let potentialIndicatedElement be the result of finding a
+    // potential indicated element given document and fragment."
+    // STEP 3: "If potentialIndicatedElement is not null, then return
+    // potentialIndicatedElement."
+    if let Some(element) = find_potential_indicated_element(dom, fragment) {
+        return Some(IndicatedPart::Element(element));
+    }
+
+    // STEP 4: "Let fragmentBytes be the result of percent-decoding
+    // fragment."
+    // STEP 5: "Let decodedFragment be the result of running UTF-8 decode
+    // without BOM on fragmentBytes."
+    let fragment_bytes = koala_common::net::percent_decode(fragment);
+    let decoded_fragment = String::from_utf8_lossy(&fragment_bytes);
+
+    // STEP 6: "Set potentialIndicatedElement to the result of finding a
+    // potential indicated element given document and decodedFragment."
+    // STEP 7: "If potentialIndicatedElement is not null, then return
+    // potentialIndicatedElement."
+    if let Some(element) = find_potential_indicated_element(dom, &decoded_fragment) {
+        return Some(IndicatedPart::Element(element));
+    }
+
+    // STEP 8: "If decodedFragment is an ASCII case-insensitive match for the
+    // string top, then return the top of the document."
+    if decoded_fragment.eq_ignore_ascii_case("top") {
+        return Some(IndicatedPart::TopOfDocument);
+    }
+
+    // STEP 9: "Return null."
+    None
+}
+
+/// [§ 7.4.6.4 find a potential indicated element](https://html.spec.whatwg.org/multipage/browsing-the-web.html#find-a-potential-indicated-element)
+///
+/// "To find a potential indicated element given a Document document and a
+/// string fragment, run these steps:
+///  1. If there is an element in the document tree whose root is document
+///     and that has an ID equal to fragment, then return the first such
+///     element in tree order.
+///  2. If there is an a element in the document tree whose root is
+///     document that has a name attribute whose value is equal to fragment,
+///     then return the first such element in tree order.
+///  3. Return null."
+fn find_potential_indicated_element(dom: &DomTree, fragment: &str) -> Option<NodeId> {
+    dom.get_element_by_id(fragment).or_else(|| {
+        dom.descendants(NodeId::ROOT).find(|&id| {
+            dom.as_element(id).is_some_and(|element| {
+                element.tag_name == "a"
+                    && element
+                        .attrs
+                        .get("name")
+                        .is_some_and(|name| name == fragment)
+            })
+        })
+    })
+}
+
+/// The y coordinate of the top of `node`'s border box in the laid-out
+/// `layout` tree, or `None` if `node` generated no box.
+///
+/// NOTE: Non-replaced inline boxes are not given dimensions of their own,
+/// so for a plain inline element this reports the position the layout
+/// pass left on its box rather than its first line.
+#[must_use]
+pub fn element_offset_top(layout: &LayoutBox, node: NodeId) -> Option<f32> {
+    if matches!(layout.box_type, BoxType::Principal(id) if id == node) {
+        return Some(layout.dimensions.border_box().y);
+    }
+    layout
+        .children
+        .iter()
+        .find_map(|child| element_offset_top(child, node))
+}
@@ -9,6 +9,8 @@
 //!    local file reads into a single function.
 //! 2. **Detect** — [`detect_format()`] determines whether bytes are SVG or
 //!    raster using extension, MIME type, and magic-byte sniffing.
+//!    [`matches_image_type_pattern()`] then keeps bytes that are not a
+//!    known raster format (an HTML error page, say) away from the decoder.
 //! 3. **Decode** — [`ImageDecoder`] trait with [`SvgDecoder`] and
 //!    [`RasterDecoder`] implementations.
 //!
@@ -41,6 +43,11 @@ pub enum ImageError {
     #[error("could not decode image: {0}")]
     RasterDecode(#[source] image::ImageError),
 
+    /// The bytes do not start with the signature of any supported raster
+    /// format, e.g. a `text/html` error page served for an image URL.
+    #[error("response is not a supported image type")]
+    NotAnImage,
+
     /// No decoder is available for the detected image format.
     #[error("no decoder available for format {format:?}")]
     UnsupportedFormat {
@@ -144,6 +151,43 @@ pub fn detect_format(path_for_ext: &str, resolved_url: &str, bytes: &[u8]) -> Im
     ImageFormat::Raster
 }
 
+/// [MIME Sniffing § 6.1 Matching an image type pattern](https://mimesniff.spec.whatwg.org/#matching-an-image-type-pattern)
+///
+/// "To determine which image MIME type byte pattern a byte sequence input
+/// matches, if any, use the following image type pattern matching
+/// algorithm"
+///
+/// Whether `bytes` start with the signature of one of the raster formats
+/// in the spec's table: ICO, CUR, BMP, GIF, WebP, PNG or JPEG.
+#[must_use]
+pub fn matches_image_type_pattern(bytes: &[u8]) -> bool {
+    const SIGNATURES: &[&[u8]] = &[
+        // Windows Icon / Windows Cursor
+        b"\x00\x00\x01\x00",
+        b"\x00\x00\x02\x00",
+        // "The string "BM", a BMP signature."
+        b"BM",
+        // "The string "GIF87a", a GIF signature." Likewise "GIF89a".
+        b"GIF87a",
+        b"GIF89a",
+        // "An error-checking byte followed by the string "PNG" followed by
+        // CR LF SUB LF, the PNG signature."
+        b"\x89PNG\r\n\x1a\n",
+        // "The JPEG Start of Image marker followed by the indicator byte of
+        // another marker."
+        b"\xff\xd8\xff",
+    ];
+
+    // "A string "RIFF" followed by four bytes followed by the string
+    // "WEBPVP"."
+    if bytes.len() >= 14 && bytes.starts_with(b"RIFF") && &bytes[8..14] == b"WEBPVP" {
+        return true;
+    }
+    SIGNATURES
+        .iter()
+        .any(|signature| bytes.starts_with(signature))
+}
+
 /// A decoder that can turn raw bytes into a [`LoadedImage`].
 pub trait ImageDecoder {
     /// Human-readable name (for diagnostics).
@@ -248,7 +292,8 @@ impl ImageLoaderPipeline {
     /// # Errors
     ///
     /// Returns an [`ImageError`] if no decoder supports the detected format or
-    /// if decoding fails.
+    /// if decoding fails, and [`ImageError::NotAnImage`] for raster bytes
+    /// that fail [`matches_image_type_pattern`].
     pub fn decode(
         &self,
         bytes: &[u8],
@@ -256,6 +301,9 @@ impl ImageLoaderPipeline {
         resolved_url: &str,
     ) -> Result<LoadedImage, ImageError> {
         let format = detect_format(path_for_ext, resolved_url, bytes);
+        if format == ImageFormat::Raster && !matches_image_type_pattern(bytes) {
+            return Err(ImageError::NotAnImage);
+        }
 
         for decoder in &self.decoders {
             if decoder.supports(format) {
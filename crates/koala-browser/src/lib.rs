@@ -8,6 +8,8 @@
 //! - **Render Tree** - styled DOM ready for layout
 //! - **Layout Tree** - box tree with computed dimensions
 //! - **Software Rendering** - headless screenshot generation
+//! - **Link Regions** - clickable `<a href>` areas from the laid-out page
+//! - **Fragment Navigation** - the scroll target for a URL's `#fragment`
 //! - **JavaScript Execution** - inline script execution via Boa
 //!
 //! # Not Yet Implemented
@@ -17,14 +19,18 @@
 //! - DOM manipulation from JavaScript
 
 pub mod font_metrics;
+pub mod fragment;
 pub mod image_loader;
+pub mod links;
 pub mod renderer;
+pub mod srcset;
 
 pub use koala_css as css;
 pub use koala_dom as dom;
 pub use koala_html as html;
 pub use koala_js as js;
 
+pub use links::LinkRegion;
 pub use renderer::{Renderer, RendererFonts};
 
 // Re-export LoadedImage from koala-common for backwards compatibility.
@@ -44,17 +50,20 @@ pub use koala_common::warning;
 /// loaders.
 pub use koala_common::net;
 
+use std::sync::Arc;
+
 use image_loader::{
     ImageLoaderPipeline, fetch_image_bytes, strip_url_decorations, warn_url_decorations,
 };
 use koala_css::{
-    ComputedStyle, LayoutBox, Stylesheet, compute_styles, extract_all_stylesheets,
-    extract_style_content,
+    BoxType, ComputedStyle, DEFAULT_FONT_SIZE_PX, LayoutBox, MatchContext, Stylesheet,
+    compute_styles, compute_styles_with_context, extract_all_stylesheets, extract_style_content,
 };
 use koala_dom::{DomTree, NodeId};
 use koala_html::{HTMLParser, HTMLTokenizer, Token};
-use koala_js::JsRuntime;
+use koala_js::{DomHandle, JsRuntime};
 use koala_std::collections::HashMap;
+use srcset::SourceSelectionContext;
 
 /// A fully loaded and parsed document.
 ///
@@ -67,11 +76,22 @@ pub struct LoadedDocument {
     /// Source path or URL
     pub source_path: String,
 
+    /// Base URL that relative URLs in the document resolve against.
+    ///
+    /// `Some` only for documents fetched over `http(s)`; local files have
+    /// no base that makes sense to follow.
+    pub base_url: Option<String>,
+
     /// HTML tokens (for debugging)
     pub tokens: Vec<Token>,
 
-    /// Parsed DOM tree
-    pub dom: DomTree,
+    /// Parsed DOM tree, shared with every [`JsRuntime`] bound to the
+    /// document, so mutations made by scripts land in the tree the
+    /// cascade, layout and renderer read.
+    ///
+    /// Borrow it only for the duration of a read or a mutation; a borrow
+    /// held while a script runs makes that script's DOM calls fail.
+    pub dom: DomHandle,
 
     /// Extracted CSS text
     pub css_text: String,
@@ -79,9 +99,41 @@ pub struct LoadedDocument {
     /// Parsed stylesheet
     pub stylesheet: Stylesheet,
 
+    /// User-agent stylesheet, cascaded at the lowest priority.
+    ///
+    /// [§ 6.1 Cascading Origins](https://www.w3.org/TR/css-cascade-4/#cascade-origin-ua)
+    ///
+    /// "Conforming user agents must apply a default style sheet (or behave
+    /// as if they did)."
+    ///
+    /// [`ua_stylesheet`](koala_css::ua_stylesheet::ua_stylesheet) unless
+    /// the document was loaded with another one, e.g. through
+    /// [`parse_html_string_with_ua_stylesheet`]. Change it with
+    /// [`Self::set_ua_stylesheet`].
+    pub ua_stylesheet: &'static Stylesheet,
+
+    /// User stylesheet, cascaded at the user origin.
+    ///
+    /// [§ 6.1 Cascading Origins](https://www.w3.org/TR/css-cascade-4/#cascade-origin-user)
+    ///
+    /// "The user may be able to specify style information for a particular
+    /// document."
+    ///
+    /// Set with [`Self::set_user_stylesheet`].
+    pub user_stylesheet: Option<Stylesheet>,
+
     /// Computed styles per node
     pub styles: HashMap<NodeId, ComputedStyle>,
 
+    /// The state the cascade matches `:hover`, `:focus`, `:checked` and
+    /// `:target` against.
+    ///
+    /// Starts from [`MatchContext::for_url`] of the document's URL, so its
+    /// fragment names the `:target` element. Front ends update the hover,
+    /// focus and checkedness as the user interacts with the page, then
+    /// call [`Self::restyle_and_relayout`] for the change to show.
+    pub match_context: MatchContext,
+
     /// Layout tree (box tree, dimensions not yet computed)
     pub layout_tree: Option<LayoutBox>,
 
@@ -94,6 +146,249 @@ pub struct LoadedDocument {
     ///
     /// Used by the renderer to draw `DrawImage` commands.
     pub images: HashMap<String, LoadedImage>,
+
+    /// The URL each `<img>` element selected from its `src`/`srcset`.
+    ///
+    /// [§ 4.8.3 The img element](https://html.spec.whatwg.org/multipage/embedded-content.html#dom-img-currentsrc)
+    ///
+    /// "The currentSrc IDL attribute must return the img element's current
+    /// request's current URL."
+    ///
+    /// Values are keys into [`Self::images`].
+    pub current_src: HashMap<NodeId, String>,
+
+    /// `<img loading="lazy">` elements whose fetch is deferred until they
+    /// come near the viewport.
+    ///
+    /// [§ 2.5.7 Lazy loading attributes](https://html.spec.whatwg.org/multipage/urls-and-fetching.html#lazy-loading-attributes)
+    ///
+    /// "Used to defer fetching a resource until some conditions are met."
+    ///
+    /// Drained by [`Self::fetch_deferred_images`].
+    pub deferred_images: Vec<NodeId>,
+}
+
+impl LoadedDocument {
+    /// Assemble a document from an already-built DOM, its author and
+    /// user-agent stylesheets and the computed styles for it, without
+    /// parsing any HTML, running scripts or loading images.
+    ///
+    /// Meant for tests and embedders that want to render a hand-made
+    /// tree. The layout tree is built from `styles` but not laid out;
+    /// every other field starts empty.
+    ///
+    /// NOTE: `styles` is taken as given, so it should come from
+    /// [`compute_styles`] over `dom`, `ua_stylesheet` and `stylesheet` for
+    /// the rendering to match a loaded document.
+    /// [`Self::restyle_and_relayout`] recomputes it from those two sheets
+    /// and [`Self::match_context`], which starts empty.
+    #[must_use]
+    pub fn from_parts(
+        dom: DomTree,
+        ua_stylesheet: &'static Stylesheet,
+        stylesheet: Stylesheet,
+        styles: HashMap<NodeId, ComputedStyle>,
+    ) -> Self {
+        let layout_tree =
+            build_initial_layout_tree(&dom, &styles, &HashMap::new(), &HashMap::new());
+        Self {
+            html_source: String::new(),
+            source_path: String::new(),
+            base_url: None,
+            tokens: Vec::new(),
+            dom: std::rc::Rc::new(std::cell::RefCell::new(dom)),
+            css_text: String::new(),
+            stylesheet,
+            ua_stylesheet,
+            user_stylesheet: None,
+            styles,
+            match_context: MatchContext::default(),
+            layout_tree,
+            parse_issues: Vec::new(),
+            images: HashMap::new(),
+            current_src: HashMap::new(),
+            deferred_images: Vec::new(),
+        }
+    }
+
+    /// Re-run the cascade and rebuild the layout tree against the
+    /// current DOM and [`Self::match_context`].
+    ///
+    /// Call this after mutating [`Self::dom`] (attributes, children,
+    /// text) — e.g. after dispatching an event whose listener changed
+    /// the tree — or the match context, so that [`Self::styles`] and
+    /// [`Self::layout_tree`] stop describing the previous state. The
+    /// rebuilt tree is laid out against `viewport`, the visible part of
+    /// the page in document coordinates: its size is the initial
+    /// containing block, and its position is the scroll offset that
+    /// `content-visibility: auto` boxes are tested against.
+    ///
+    /// NOTE: The author and user stylesheets are reused as-is rather than
+    /// re-extracted, and images are not re-fetched: `<img>` elements
+    /// keep the dimensions of whatever was already loaded for their
+    /// `src`. Both mirror the post-script relayout in
+    /// [`load_document`].
+    pub fn restyle_and_relayout(
+        &mut self,
+        viewport: koala_css::Rect,
+        font_metrics: &dyn koala_css::FontMetrics,
+    ) {
+        self.restyle();
+        if let Some(root) = self.layout_tree.as_mut() {
+            let initial_containing_block = koala_css::Rect {
+                x: 0.0,
+                y: 0.0,
+                ..viewport
+            };
+            root.layout(
+                initial_containing_block,
+                viewport,
+                font_metrics,
+                initial_containing_block,
+            );
+        }
+    }
+
+    /// Cascade `stylesheet` at the user origin, between the UA defaults
+    /// and the author stylesheet, and rebuild [`Self::styles`] and
+    /// [`Self::layout_tree`] against it.
+    ///
+    /// The rebuilt tree is not laid out; call
+    /// [`Self::restyle_and_relayout`] to lay it out against a viewport.
+    ///
+    /// NOTE: Scripts have already run by the time a document is loaded,
+    /// so `getComputedStyle` calls made during load did not see the user
+    /// stylesheet.
+    pub fn set_user_stylesheet(&mut self, stylesheet: Stylesheet) {
+        self.user_stylesheet = Some(stylesheet);
+        self.restyle();
+    }
+
+    /// Cascade `stylesheet` at the user-agent origin in place of
+    /// [`Self::ua_stylesheet`], e.g.
+    /// [`ua_stylesheet_minimal`](koala_css::ua_stylesheet::ua_stylesheet_minimal),
+    /// and rebuild [`Self::styles`] and [`Self::layout_tree`] against it.
+    ///
+    /// The rebuilt tree is not laid out, as with
+    /// [`Self::set_user_stylesheet`]. Scripts that ran during load saw
+    /// the previous sheet; load with
+    /// [`parse_html_string_with_ua_stylesheet`] to avoid that.
+    pub fn set_ua_stylesheet(&mut self, stylesheet: &'static Stylesheet) {
+        self.ua_stylesheet = stylesheet;
+        self.restyle();
+    }
+
+    /// Rebuild [`Self::styles`] and the (not laid out)
+    /// [`Self::layout_tree`] from the current DOM, stylesheets and
+    /// [`Self::match_context`].
+    fn restyle(&mut self) {
+        let dom = self.dom.borrow();
+        let image_dims = loaded_image_dimensions(&dom, &self.images, &self.current_src);
+        let (styles, layout_tree) = recompute_styles_and_layout(
+            &dom,
+            self.ua_stylesheet,
+            self.user_stylesheet.as_ref(),
+            &self.stylesheet,
+            &self.match_context,
+            &image_dims,
+            &self.current_src,
+        );
+        drop(dom);
+        self.styles = styles;
+        self.layout_tree = layout_tree;
+    }
+
+    /// Clickable regions for every rendered `<a href>` in the laid-out
+    /// [`Self::layout_tree`], with hrefs resolved against
+    /// [`Self::base_url`].
+    ///
+    /// Returns an empty list when there is no layout tree. The tree must
+    /// have been laid out (e.g. via [`Self::restyle_and_relayout`]) for the
+    /// regions to have non-empty rects.
+    #[must_use]
+    pub fn link_regions(&self) -> Vec<LinkRegion> {
+        self.layout_tree.as_ref().map_or_else(Vec::new, |layout| {
+            links::collect_link_regions(layout, &self.dom.borrow(), self.base_url.as_deref())
+        })
+    }
+
+    /// [§ 2.5.7 Lazy loading attributes](https://html.spec.whatwg.org/multipage/urls-and-fetching.html#lazy-loading-attributes)
+    ///
+    /// Fetch every [deferred image](Self::deferred_images) whose box in the
+    /// laid-out [`Self::layout_tree`] lies within [`LAZY_LOAD_ROOT_MARGIN`]
+    /// of `viewport`, the visible part of the page in document coordinates.
+    /// Call it after layout and again whenever the page scrolls.
+    ///
+    /// Returns `true` if an image was loaded; its dimensions only reach
+    /// the layout tree after [`Self::restyle_and_relayout`]. Images whose
+    /// fetch fails are not retried, and images without a box (e.g. under
+    /// `display: none`) stay deferred.
+    pub fn fetch_deferred_images(&mut self, viewport: koala_css::Rect) -> bool {
+        let Some(layout) = self.layout_tree.as_ref() else {
+            return false;
+        };
+        fetch_images_near_viewport(
+            layout,
+            &mut self.deferred_images,
+            &self.current_src,
+            self.base_url.as_deref(),
+            &mut self.images,
+            viewport,
+        )
+    }
+
+    /// [§ 8.1.6 JavaScript execution context](https://html.spec.whatwg.org/multipage/webappapis.html)
+    ///
+    /// A [`JsRuntime`] bound to [`Self::dom`], for running scripts after
+    /// load, e.g. in response to user input. The scripts mutate the same
+    /// tree [`Self::restyle_and_relayout`] reads, so call that once
+    /// [`JsRuntime::take_dom_dirty`] reports a change.
+    ///
+    /// NOTE: The runtime starts with fresh globals; state the document's
+    /// own scripts left behind during load is not carried over. Only one
+    /// runtime should be alive on a thread at a time.
+    #[must_use]
+    pub fn js_runtime(&self) -> JsRuntime {
+        let mut runtime = JsRuntime::new(std::rc::Rc::clone(&self.dom));
+        if !self.source_path.is_empty() {
+            runtime.set_location(&document_url(&self.source_path));
+        }
+        runtime
+    }
+
+    /// [§ 5.1 'cursor'](https://www.w3.org/TR/css-ui-4/#cursor)
+    ///
+    /// The cursor a front end should show while the pointer is over `node`,
+    /// from its computed style. Nodes without a computed style (text,
+    /// comments) get the initial value, `auto`.
+    #[must_use]
+    pub fn cursor(&self, node: NodeId) -> koala_css::Cursor {
+        self.styles
+            .get(&node)
+            .and_then(|style| style.cursor)
+            .unwrap_or_default()
+    }
+
+    /// [§ 7.4.6.4 Scrolling to a fragment](https://html.spec.whatwg.org/multipage/browsing-the-web.html#scrolling-to-a-fragment)
+    ///
+    /// The vertical scroll offset for the fragment of [`Self::source_path`]
+    /// (e.g. `page.html#section`): the top of the indicated element's
+    /// border box in the laid-out [`Self::layout_tree`].
+    ///
+    /// Returns `None` — no scroll — when the URL has no fragment, when the
+    /// fragment indicates the top of the document (`#` or `#top`), or when
+    /// no element matches it.
+    #[must_use]
+    pub fn fragment_scroll_offset(&self) -> Option<f32> {
+        let fragment = fragment::url_fragment(&self.source_path)?;
+        let indicated = fragment::select_indicated_part(&self.dom.borrow(), fragment)?;
+        match indicated {
+            fragment::IndicatedPart::TopOfDocument => None,
+            fragment::IndicatedPart::Element(node) => {
+                fragment::element_offset_top(self.layout_tree.as_ref()?, node)
+            }
+        }
+    }
 }
 
 /// Error type for document loading. Every fetch path (HTTP, `data:`,
@@ -202,11 +497,23 @@ pub fn load_document_with_hooks<H: JsHooks>(
     // resolve against an http base, but a file path has no base
     // that makes sense to follow.
     let is_remote = path.starts_with("http://") || path.starts_with("https://");
-    let html_source = net::fetch_text(path)?;
+    // [URL Standard § 4.4](https://url.spec.whatwg.org/#concept-url-serializer)
+    //
+    // The fragment never reaches the server (or the filesystem); it is
+    // kept on `source_path` for fragment navigation after load.
+    let resource = path.split_once('#').map_or(path, |(resource, _)| resource);
+    let html_source = net::fetch_text(resource)?;
     let base_url = if is_remote { Some(path) } else { None };
+    let url = document_url(path);
 
     // Parse the document with base URL for resolving external stylesheets
-    let mut doc = parse_html_with_base_url(&html_source, base_url, hooks);
+    let mut doc = parse_html_with_base_url(
+        &html_source,
+        base_url,
+        Some(&url),
+        koala_css::ua_stylesheet::ua_stylesheet(),
+        hooks,
+    );
     doc.source_path = path.to_string();
 
     Ok(doc)
@@ -218,13 +525,143 @@ pub fn load_document_with_hooks<H: JsHooks>(
 /// Note: External stylesheets cannot be loaded without a base URL.
 #[must_use]
 pub fn parse_html_string(html: &str) -> LoadedDocument {
-    parse_html_with_base_url(html, None, &mut ())
+    parse_html_string_with_ua_stylesheet(html, koala_css::ua_stylesheet::ua_stylesheet())
+}
+
+/// [`parse_html_string`] with `ua_stylesheet` as the document's
+/// [user-agent stylesheet](LoadedDocument::ua_stylesheet), e.g.
+/// [`ua_stylesheet_minimal`](koala_css::ua_stylesheet::ua_stylesheet_minimal).
+///
+/// Scripts run during the parse already see styles cascaded over it.
+#[must_use]
+pub fn parse_html_string_with_ua_stylesheet(
+    html: &str,
+    ua_stylesheet: &'static Stylesheet,
+) -> LoadedDocument {
+    parse_html_with_base_url(html, None, None, ua_stylesheet, &mut ())
+}
+
+/// [URL Standard § 4.1](https://url.spec.whatwg.org/#concept-url)
+///
+/// The URL scripts see in `location` for a document loaded from
+/// `path`. URLs pass through unchanged; a plain filesystem path
+/// becomes a `file:` URL for its absolute form, keeping any
+/// `#fragment`.
+fn document_url(path: &str) -> String {
+    let has_scheme = path.split_once(':').is_some_and(|(scheme, _)| {
+        scheme.len() > 1
+            && scheme
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.'))
+    });
+    if has_scheme {
+        return path.to_string();
+    }
+    let (resource, fragment) = path
+        .split_once('#')
+        .map_or((path, None), |(resource, fragment)| {
+            (resource, Some(fragment))
+        });
+    let absolute =
+        std::path::absolute(resource).unwrap_or_else(|_| std::path::PathBuf::from(resource));
+    let mut url = format!("file://{}", absolute.display());
+    if let Some(fragment) = fragment {
+        url.push('#');
+        url.push_str(fragment);
+    }
+    url
+}
+
+/// A document parsed and styled without running scripts or building a
+/// layout tree, together with everything that went wrong along the way.
+///
+/// Returned by [`parse_document`].
+pub struct ParsedDocument {
+    /// Parsed DOM tree.
+    pub dom: DomTree,
+    /// Every author stylesheet (`<link>` and `<style>`), merged in
+    /// document order for screen media.
+    pub stylesheet: Stylesheet,
+    /// Computed styles per node, cascaded over the UA stylesheet.
+    pub styles: HashMap<NodeId, ComputedStyle>,
+    /// HTML parse errors followed by stylesheet issues, each in document
+    /// order.
+    pub issues: Vec<DocumentIssue>,
+}
+
+/// A problem reported by [`parse_document`].
+#[derive(Debug, Clone)]
+pub enum DocumentIssue {
+    /// [§ 13.2.2 Parse errors](https://html.spec.whatwg.org/multipage/parsing.html#parse-errors)
+    Html(koala_html::ParseIssue),
+    /// An external stylesheet failed to load, or the CSS parser skipped
+    /// part of a stylesheet.
+    Stylesheet(koala_css::StylesheetIssue),
+}
+
+impl std::fmt::Display for DocumentIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Html(issue) => f.write_str(&issue.message),
+            Self::Stylesheet(issue) => issue.fmt(f),
+        }
+    }
+}
+
+/// Parse `html`, load its stylesheets, and run the cascade, collecting
+/// HTML parse errors, failed stylesheet loads and CSS parse issues.
+///
+/// Relative `<link>` URLs resolve against `base_url`. Unlike
+/// [`parse_html_string`], scripts are not run and no layout tree is built.
+#[must_use]
+pub fn parse_document(html: &str, base_url: Option<&str>) -> ParsedDocument {
+    parse_document_with_ua_stylesheet(html, base_url, koala_css::ua_stylesheet::ua_stylesheet())
+}
+
+/// [`parse_document`], cascading over `ua_stylesheet` in place of the
+/// full [`ua_stylesheet`](koala_css::ua_stylesheet::ua_stylesheet).
+#[must_use]
+pub fn parse_document_with_ua_stylesheet(
+    html: &str,
+    base_url: Option<&str>,
+    ua_stylesheet: &Stylesheet,
+) -> ParsedDocument {
+    let mut tokenizer = HTMLTokenizer::new(html.to_string());
+    tokenizer.run();
+    let (tokens, tokenizer_issues) = tokenizer.into_tokens_and_issues();
+    let (dom, html_issues) = HTMLParser::new(tokens)
+        .with_tokenizer_issues(tokenizer_issues)
+        .run_with_issues();
+
+    let (stylesheets, stylesheet_issues) =
+        koala_css::extract_all_stylesheets_with_issues(&dom, base_url);
+    let stylesheet = stylesheets.into_merged_stylesheet();
+    let styles = compute_styles(&dom, ua_stylesheet, None, &stylesheet, DEFAULT_FONT_SIZE_PX);
+
+    let issues = html_issues
+        .into_iter()
+        .map(DocumentIssue::Html)
+        .chain(stylesheet_issues.into_iter().map(DocumentIssue::Stylesheet))
+        .collect();
+
+    ParsedDocument {
+        dom,
+        stylesheet,
+        styles,
+        issues,
+    }
 }
 
 /// Parse an HTML string with an optional base URL for resolving external resources.
+///
+/// `document_url` is the address exposed to scripts through
+/// `location`; it falls back to `base_url` when `None`. Styles are
+/// cascaded over `ua_stylesheet`.
 fn parse_html_with_base_url<H: JsHooks>(
     html: &str,
     base_url: Option<&str>,
+    document_url: Option<&str>,
+    ua_stylesheet: &'static Stylesheet,
     hooks: &mut H,
 ) -> LoadedDocument {
     let (tokens, dom, mut parse_issues) = tokenize_and_parse(html);
@@ -234,26 +671,32 @@ fn parse_html_with_base_url<H: JsHooks>(
     // [§ 6.1 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
     // "Each style rule has a cascade origin... User-Agent origin rules
     // have the lowest priority."
-    let ua = koala_css::ua_stylesheet::ua_stylesheet();
-    let styles = compute_initial_styles(&dom, ua, &stylesheet);
-    let (images, image_dims) = load_images(&dom, base_url);
-    let layout_tree = build_initial_layout_tree(&dom, &styles, &image_dims);
+    let match_context = MatchContext::for_url(document_url.or(base_url).unwrap_or_default());
+    let styles = compute_initial_styles(&dom, ua_stylesheet, &stylesheet, &match_context);
+    let LoadedImages {
+        images,
+        image_dims,
+        current_src,
+        deferred_images,
+    } = load_images(&dom, base_url, SourceSelectionContext::default());
+    let layout_tree = build_initial_layout_tree(&dom, &styles, &image_dims, &current_src);
 
     // Execute JavaScript.
     // [§ 4.12.1.1 Processing model](https://html.spec.whatwg.org/multipage/scripting.html)
     //
-    // Wrap the DOM in `Rc<RefCell<>>` for the duration of script
-    // execution so JsRuntime can hand a shared handle to its
-    // DOM-bridge globals. After the runtime is dropped its handle
-    // clone drops with it, leaving the Rc unique — `into_inner`
-    // recovers the owned `DomTree` for `LoadedDocument`.
+    // The DOM moves behind a shared `Rc<RefCell<>>` handle: the
+    // JsRuntime hands clones of it to its DOM-bridge globals, and
+    // `LoadedDocument` keeps it afterwards, so the tree scripts mutate
+    // is the tree that gets styled and rendered.
     let scripts = load_scripts(&dom, base_url, &mut parse_issues);
-    let dom_cell = std::rc::Rc::new(std::cell::RefCell::new(dom));
-    let dom_was_mutated =
-        execute_document_scripts(&dom_cell, scripts, base_url, hooks, &mut parse_issues);
-    let dom = std::rc::Rc::try_unwrap(dom_cell)
-        .expect("JsRuntime is dropped above; no other holders of the DOM handle")
-        .into_inner();
+    let dom: DomHandle = std::rc::Rc::new(std::cell::RefCell::new(dom));
+    let dom_was_mutated = execute_document_scripts(
+        &dom,
+        scripts,
+        document_url.or(base_url),
+        hooks,
+        &mut parse_issues,
+    );
 
     // If JS mutated the DOM (setAttribute, appendChild, textContent
     // setter, …), the styles + layout tree we built before scripts
@@ -262,7 +705,15 @@ fn parse_html_with_base_url<H: JsHooks>(
     // image loads are network-bound and the post-script DOM rarely
     // adds <img> tags pointing to never-fetched URLs in practice.
     let (styles, layout_tree) = if dom_was_mutated {
-        recompute_styles_and_layout(&dom, ua, &stylesheet, &image_dims)
+        recompute_styles_and_layout(
+            &dom.borrow(),
+            ua_stylesheet,
+            None,
+            &stylesheet,
+            &match_context,
+            &image_dims,
+            &current_src,
+        )
     } else {
         (styles, layout_tree)
     };
@@ -270,14 +721,20 @@ fn parse_html_with_base_url<H: JsHooks>(
     LoadedDocument {
         html_source: html.to_string(),
         source_path: String::new(),
+        base_url: base_url.map(str::to_string),
         tokens,
         dom,
         css_text,
         stylesheet,
+        ua_stylesheet,
+        user_stylesheet: None,
         styles,
+        match_context,
         layout_tree,
         parse_issues,
         images,
+        current_src,
+        deferred_images,
     }
 }
 
@@ -295,8 +752,8 @@ fn parse_html_with_base_url<H: JsHooks>(
 fn tokenize_and_parse(html: &str) -> (Vec<Token>, DomTree, Vec<String>) {
     let mut tokenizer = HTMLTokenizer::new(html.to_string());
     tokenizer.run();
-    let tokens = tokenizer.into_tokens();
-    let parser = HTMLParser::new(tokens.clone());
+    let (tokens, tokenizer_issues) = tokenizer.into_tokens_and_issues();
+    let parser = HTMLParser::new(tokens.clone()).with_tokenizer_issues(tokenizer_issues);
     let (dom, issues) = parser.run_with_issues();
     let parse_issues: Vec<String> = issues.iter().map(|i| i.message.clone()).collect();
     (tokens, dom, parse_issues)
@@ -322,8 +779,16 @@ fn compute_initial_styles(
     dom: &DomTree,
     ua: &Stylesheet,
     stylesheet: &Stylesheet,
+    match_context: &MatchContext,
 ) -> HashMap<NodeId, ComputedStyle> {
-    compute_styles(dom, ua, stylesheet)
+    compute_styles_with_context(
+        dom,
+        ua,
+        None,
+        stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+        match_context,
+    )
 }
 
 /// Initial layout-tree build from the cascade result. Re-run after
@@ -333,8 +798,13 @@ fn build_initial_layout_tree(
     dom: &DomTree,
     styles: &HashMap<NodeId, ComputedStyle>,
     image_dims: &HashMap<NodeId, (f32, f32)>,
+    current_src: &HashMap<NodeId, String>,
 ) -> Option<LayoutBox> {
-    LayoutBox::build_layout_tree(dom, styles, dom.root(), image_dims)
+    let mut layout_tree = LayoutBox::build_layout_tree(dom, styles, dom.root(), image_dims);
+    if let Some(root) = layout_tree.as_mut() {
+        apply_current_src(root, current_src);
+    }
+    layout_tree
 }
 
 /// Cascade + layout-tree-build redone against a post-JS DOM.
@@ -342,14 +812,41 @@ fn build_initial_layout_tree(
 fn recompute_styles_and_layout(
     dom: &DomTree,
     ua: &Stylesheet,
+    user: Option<&Stylesheet>,
     stylesheet: &Stylesheet,
+    match_context: &MatchContext,
     image_dims: &HashMap<NodeId, (f32, f32)>,
+    current_src: &HashMap<NodeId, String>,
 ) -> (HashMap<NodeId, ComputedStyle>, Option<LayoutBox>) {
-    let post_styles = compute_styles(dom, ua, stylesheet);
-    let post_layout = LayoutBox::build_layout_tree(dom, &post_styles, dom.root(), image_dims);
+    let post_styles = compute_styles_with_context(
+        dom,
+        ua,
+        user,
+        stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+        match_context,
+    );
+    let mut post_layout = LayoutBox::build_layout_tree(dom, &post_styles, dom.root(), image_dims);
+    if let Some(root) = post_layout.as_mut() {
+        apply_current_src(root, current_src);
+    }
     (post_styles, post_layout)
 }
 
+/// Point each `<img>` box at the URL its element selected, so paint
+/// looks up the image that was actually fetched rather than the raw
+/// `src` attribute.
+fn apply_current_src(layout_box: &mut LayoutBox, current_src: &HashMap<NodeId, String>) {
+    if let BoxType::Principal(node_id) = layout_box.box_type
+        && let Some(url) = current_src.get(&node_id)
+    {
+        layout_box.replaced_src = Some(url.clone());
+    }
+    for child in &mut layout_box.children {
+        apply_current_src(child, current_src);
+    }
+}
+
 // JS lifecycle phases
 //
 // HTML § 13.2.6 "Stop parsing" lifecycle:
@@ -367,13 +864,13 @@ fn recompute_styles_and_layout(
 /// to decide whether to re-cascade + re-layout.
 #[tracing::instrument(name = "js_execute", skip_all)]
 fn execute_document_scripts<H: JsHooks>(
-    dom_cell: &std::rc::Rc<std::cell::RefCell<DomTree>>,
+    dom_cell: &DomHandle,
     scripts: Vec<LoadedScript>,
-    base_url: Option<&str>,
+    document_url: Option<&str>,
     hooks: &mut H,
     parse_issues: &mut Vec<String>,
 ) -> bool {
-    let mut runtime = init_js_runtime(dom_cell, base_url, hooks);
+    let mut runtime = init_js_runtime(dom_cell, document_url, hooks);
     execute_inline_scripts(&mut runtime, scripts, parse_issues);
     dispatch_dcl(&mut runtime, parse_issues);
     drain_due_tasks(&mut runtime, parse_issues);
@@ -390,12 +887,12 @@ fn execute_document_scripts<H: JsHooks>(
 /// (the WPT testharness bridge, in particular).
 #[tracing::instrument(name = "js_runtime_init", skip_all)]
 fn init_js_runtime<H: JsHooks>(
-    dom_cell: &std::rc::Rc<std::cell::RefCell<DomTree>>,
-    base_url: Option<&str>,
+    dom_cell: &DomHandle,
+    document_url: Option<&str>,
     hooks: &mut H,
 ) -> JsRuntime {
     let mut runtime = JsRuntime::new(std::rc::Rc::clone(dom_cell));
-    if let Some(url) = base_url {
+    if let Some(url) = document_url {
         runtime.set_location(url);
     }
     hooks.before_scripts(&mut runtime);
@@ -484,26 +981,48 @@ fn after_settled<H: JsHooks>(runtime: &mut JsRuntime, hooks: &mut H) {
     hooks.after_settled(runtime);
 }
 
+/// Result of [`load_images`].
+struct LoadedImages {
+    /// Selected URL → decoded image, for the renderer.
+    images: HashMap<String, LoadedImage>,
+    /// `NodeId` → (width, height), for layout intrinsic dimensions.
+    image_dims: HashMap<NodeId, (f32, f32)>,
+    /// `NodeId` → selected URL (the element's `currentSrc`).
+    current_src: HashMap<NodeId, String>,
+    /// `loading="lazy"` images left unfetched.
+    deferred_images: Vec<NodeId>,
+}
+
 /// Load images referenced by `<img>` elements in the DOM.
 ///
 /// [§ 4.8.3 The img element](https://html.spec.whatwg.org/multipage/embedded-content.html#the-img-element)
 ///
-/// Walks the DOM for `<img>` elements with a `src` attribute, fetches the
+/// Walks the DOM for `<img>` elements with a `src` or `srcset`, fetches the
 /// image data (network or filesystem), and decodes it to RGBA pixels.
 ///
 /// Uses [`ImageLoaderPipeline`] to detect format (SVG vs raster) and
 /// dispatch to the appropriate decoder.
 ///
-/// Returns:
-/// - A map of src → `LoadedImage` for the renderer
-/// - A map of `NodeId` → (width, height) for layout intrinsic dimensions
+/// Each element's URL comes from [`srcset::select_image_source`], so a
+/// `srcset` candidate matching `context` wins over the plain `src`.
+///
+/// [§ 2.5.7 Lazy loading attributes](https://html.spec.whatwg.org/multipage/urls-and-fetching.html#lazy-loading-attributes)
+///
+/// "Eager: Used to fetch a resource immediately; the default state."
+///
+/// `loading="lazy"` images are not fetched unless an eager image shares
+/// their URL; they are returned in `deferred_images` for
+/// [`LoadedDocument::fetch_deferred_images`].
 #[tracing::instrument(name = "image_loading", skip_all)]
 fn load_images(
     dom: &DomTree,
     base_url: Option<&str>,
-) -> (HashMap<String, LoadedImage>, HashMap<NodeId, (f32, f32)>) {
+    context: SourceSelectionContext,
+) -> LoadedImages {
     let mut images: HashMap<String, LoadedImage> = HashMap::new();
     let mut image_dims: HashMap<NodeId, (f32, f32)> = HashMap::new();
+    let mut current_src: HashMap<NodeId, String> = HashMap::new();
+    let mut deferred_images = Vec::new();
     let pipeline = ImageLoaderPipeline::new();
 
     for node_id in dom.iter_all() {
@@ -512,13 +1031,17 @@ fn load_images(
                 continue;
             }
 
-            let Some(src) = element.attrs.get("src") else {
+            // [§ 4.8.4.3.6 Selecting an image source](https://html.spec.whatwg.org/multipage/images.html#select-an-image-source)
+            let Some(selected) = srcset::select_image_source(
+                element.attrs.get("src").map(String::as_str),
+                element.attrs.get("srcset").map(String::as_str),
+                element.attrs.get("sizes").map(String::as_str),
+                context,
+            ) else {
                 continue;
             };
-            let src = src.trim();
-            if src.is_empty() {
-                continue;
-            }
+            let src = selected.as_str();
+            let _ = current_src.insert(node_id, selected.clone());
 
             // If we already loaded this src, just record its dims for this node.
             if let Some(existing) = images.get(src) {
@@ -526,45 +1049,189 @@ fn load_images(
                 continue;
             }
 
-            // Resolve URL.
-            let resolved = koala_common::url::resolve_url(src, base_url);
+            // "The attribute's keywords are: lazy ... eager"; matched
+            // ASCII case-insensitively.
+            if element
+                .attrs
+                .get("loading")
+                .is_some_and(|loading| loading.eq_ignore_ascii_case("lazy"))
+            {
+                deferred_images.push(node_id);
+                continue;
+            }
 
-            // Strip query/fragment for extension-based format detection.
-            let path_for_ext = strip_url_decorations(&resolved);
+            if let Some(loaded) = load_image(src, base_url, &pipeline) {
+                let _ = image_dims.insert(node_id, loaded.dimensions_f32());
+                let _ = images.insert(src.to_string(), loaded);
+            }
+        }
+    }
 
-            // Emit warnings for unhandled URL decorations.
-            warn_url_decorations(src, &resolved);
+    LoadedImages {
+        images,
+        image_dims,
+        current_src,
+        deferred_images,
+    }
+}
 
-            // Fetch bytes (HTTP / data URL / local file).
-            let bytes = match fetch_image_bytes(&resolved) {
-                Ok(b) => b,
-                Err(e) => {
-                    if !warning::is_quiet() {
-                        eprintln!("[Koala] Warning: failed to load image '{src}': {e}");
-                    }
-                    continue;
-                }
-            };
+/// Fetch and decode the image at `src`, resolved against `base_url`.
+/// Failures are reported as warnings and yield `None`.
+fn load_image(
+    src: &str,
+    base_url: Option<&str>,
+    pipeline: &ImageLoaderPipeline,
+) -> Option<LoadedImage> {
+    // Resolve URL.
+    let resolved = koala_common::url::resolve_url(src, base_url);
+
+    // Strip query/fragment for extension-based format detection.
+    let path_for_ext = strip_url_decorations(&resolved);
+
+    // Emit warnings for unhandled URL decorations.
+    warn_url_decorations(src, &resolved);
+
+    // Fetch bytes (HTTP / data URL / local file).
+    let bytes = match fetch_image_bytes(&resolved) {
+        Ok(b) => b,
+        Err(e) => {
+            if !warning::is_quiet() {
+                eprintln!("[Koala] Warning: failed to load image '{src}': {e}");
+            }
+            return None;
+        }
+    };
 
-            // Detect format and decode.
-            match pipeline.decode(&bytes, path_for_ext, &resolved) {
-                Ok(loaded) => {
-                    let _ = image_dims.insert(node_id, loaded.dimensions_f32());
-                    let _ = images.insert(src.to_string(), loaded);
-                }
-                Err(e) => {
-                    if !warning::is_quiet() {
-                        eprintln!(
-                            "[Koala] Warning: skipping <img src=\"{src}\">: {e}. \
-                             The page will still render but this image will be missing."
-                        );
-                    }
-                }
+    // Detect format and decode.
+    match pipeline.decode(&bytes, path_for_ext, &resolved) {
+        Ok(loaded) => Some(loaded),
+        Err(e) => {
+            if !warning::is_quiet() {
+                eprintln!(
+                    "[Koala] Warning: skipping <img src=\"{src}\">: {e}. \
+                     The page will still render but this image will be missing."
+                );
             }
+            None
         }
     }
+}
+
+/// [§ 2.5.7 Lazy loading attributes](https://html.spec.whatwg.org/multipage/urls-and-fetching.html#lazy-loading-attributes)
+///
+/// "The lazy load root margin is an implementation-defined value"
+///
+/// The same [`koala_css::VIEWPORT_MARGIN`] that `content-visibility: auto`
+/// boxes are tested with.
+pub const LAZY_LOAD_ROOT_MARGIN: f32 = koala_css::VIEWPORT_MARGIN;
 
-    (images, image_dims)
+/// [§ 2.5.7 Lazy loading attributes](https://html.spec.whatwg.org/multipage/urls-and-fetching.html#lazy-loading-attributes)
+///
+/// [`LoadedDocument::fetch_deferred_images`] over a document's parts, for
+/// hosts that keep their own copy of them: fetch into `images` every node
+/// of `deferred` whose box in the laid-out `layout` lies within
+/// [`LAZY_LOAD_ROOT_MARGIN`] of `viewport`, and drop it from `deferred`.
+///
+/// Each node is fetched from the URL `current_src` holds for it, resolved
+/// against `base_url`. Returns `true` if an image was loaded; pass
+/// `images` to [`build_layout_tree`] for its dimensions to reach layout.
+pub fn fetch_images_near_viewport(
+    layout: &LayoutBox,
+    deferred: &mut Vec<NodeId>,
+    current_src: &HashMap<NodeId, String>,
+    base_url: Option<&str>,
+    images: &mut HashMap<String, LoadedImage>,
+    viewport: koala_css::Rect,
+) -> bool {
+    let (due, still_deferred): (Vec<NodeId>, Vec<NodeId>) =
+        std::mem::take(deferred).into_iter().partition(|&node| {
+            find_principal_box(layout, node).is_some_and(|layout_box| {
+                is_near_viewport(layout_box.dimensions.border_box(), viewport)
+            })
+        });
+    *deferred = still_deferred;
+
+    let pipeline = ImageLoaderPipeline::new();
+    let mut loaded_any = false;
+    for node in due {
+        let Some(src) = current_src.get(&node) else {
+            continue;
+        };
+        if images.contains_key(src) {
+            continue;
+        }
+        if let Some(image) = load_image(src, base_url, &pipeline) {
+            let _ = images.insert(src.clone(), image);
+            loaded_any = true;
+        }
+    }
+    loaded_any
+}
+
+/// Build the (not yet laid out) layout tree for `dom` and `styles`,
+/// sizing each `<img>` box from the image `images` holds for its URL in
+/// `current_src`.
+///
+/// Hosts rebuild with this after [`fetch_images_near_viewport`] loads an
+/// image, as [`LoadedDocument::restyle_and_relayout`] does for a
+/// document.
+#[must_use]
+pub fn build_layout_tree(
+    dom: &DomTree,
+    styles: &HashMap<NodeId, ComputedStyle>,
+    images: &HashMap<String, LoadedImage>,
+    current_src: &HashMap<NodeId, String>,
+) -> Option<LayoutBox> {
+    let image_dims = loaded_image_dimensions(dom, images, current_src);
+    build_initial_layout_tree(dom, styles, &image_dims, current_src)
+}
+
+/// Whether `rect` touches `viewport` grown by [`LAZY_LOAD_ROOT_MARGIN`].
+/// Edges count, so an image with no size yet is near when its position
+/// is.
+fn is_near_viewport(rect: koala_css::Rect, viewport: koala_css::Rect) -> bool {
+    let margin = LAZY_LOAD_ROOT_MARGIN;
+    rect.x <= viewport.x + viewport.width + margin
+        && rect.x + rect.width >= viewport.x - margin
+        && rect.y <= viewport.y + viewport.height + margin
+        && rect.y + rect.height >= viewport.y - margin
+}
+
+/// The principal box `node` generates in `layout`, if any.
+fn find_principal_box(layout: &LayoutBox, node: NodeId) -> Option<&LayoutBox> {
+    if matches!(layout.box_type, BoxType::Principal(id) if id == node) {
+        return Some(layout);
+    }
+    layout
+        .children
+        .iter()
+        .find_map(|child| find_principal_box(child, node))
+}
+
+/// Map every `<img>` in `dom` to the dimensions of its already-loaded
+/// image, without fetching anything.
+///
+/// Used when relayout happens after the initial load, where node ids
+/// may have changed but the src → image cache is still valid.
+fn loaded_image_dimensions(
+    dom: &DomTree,
+    images: &HashMap<String, LoadedImage>,
+    current_src: &HashMap<NodeId, String>,
+) -> HashMap<NodeId, (f32, f32)> {
+    dom.iter_all()
+        .filter_map(|node_id| {
+            let element = dom.as_element(node_id)?;
+            if !element.tag_name.eq_ignore_ascii_case("img") {
+                return None;
+            }
+            let url = current_src.get(&node_id).map_or_else(
+                || element.attrs.get("src").map(|s| s.trim()),
+                |s| Some(s.as_str()),
+            )?;
+            let image = images.get(url)?;
+            Some((node_id, image.dimensions_f32()))
+        })
+        .collect()
 }
 
 /// One script extracted from the document, ready to feed
@@ -670,6 +1337,157 @@ fn fetch_script_source(resolved_url: &str) -> Result<String, String> {
     Ok(String::from_utf8_lossy(&bytes).into_owned())
 }
 
+/// Lay out, paint, and rasterize `doc` into an RGBA pixel buffer.
+///
+/// This is the whole screenshot pipeline in one call: [`render_page`]
+/// with the [shared](RendererFonts::shared) fonts. Deferred images near
+/// the viewport are fetched first, as a front end would after its first
+/// layout, so lazy images above the fold are drawn. `doc` itself is left
+/// untouched: those images are loaded into a copy of [`LoadedDocument::images`].
+///
+/// Returns the buffer (4 bytes per pixel, row-major, no padding, the same
+/// format as [`Renderer::rgba_bytes`]) along with its width and height.
+/// A document without a layout tree renders as a blank white canvas.
+#[must_use]
+pub fn render_to_rgba(doc: &LoadedDocument, width: u32, height: u32) -> (Vec<u8>, u32, u32) {
+    let dom = doc.dom.borrow();
+    let mut images = std::borrow::Cow::Borrowed(&doc.images);
+    let mut rebuilt = None;
+    if let Some(layout_tree) = doc.layout_tree.as_ref()
+        && !doc.deferred_images.is_empty()
+    {
+        let viewport = origin_viewport(width, height);
+        let mut layout = clone_layout_tree(layout_tree);
+        apply_layout_pass(&mut layout, viewport, FontProvider::shared());
+        if fetch_images_near_viewport(
+            &layout,
+            &mut doc.deferred_images.clone(),
+            &doc.current_src,
+            doc.base_url.as_deref(),
+            images.to_mut(),
+            viewport,
+        ) {
+            rebuilt = build_layout_tree(&dom, &doc.styles, &images, &doc.current_src);
+        }
+    }
+    let input = RenderInput {
+        dom: &dom,
+        styles: &doc.styles,
+        layout_tree: rebuilt.as_ref().or(doc.layout_tree.as_ref()),
+        images: &images,
+    };
+    let renderer = render_page(
+        input,
+        width,
+        height,
+        RendererFonts::shared(),
+        FontProvider::shared(),
+    );
+    (renderer.rgba_bytes().to_vec(), width, height)
+}
+
+/// The parts of a document [`render_page`] reads.
+///
+/// Borrowed one by one so that hosts keeping their own copy of them, such
+/// as a GUI that moves a document's DOM and styles to a render thread,
+/// render through the same pipeline as a [`LoadedDocument`].
+#[derive(Clone, Copy)]
+pub struct RenderInput<'a> {
+    /// The DOM, read for the canvas background.
+    pub dom: &'a DomTree,
+    /// Computed styles per node.
+    pub styles: &'a HashMap<NodeId, ComputedStyle>,
+    /// The box tree to lay out. `None` renders only the canvas.
+    pub layout_tree: Option<&'a LayoutBox>,
+    /// Loaded images keyed by URL.
+    pub images: &'a HashMap<String, LoadedImage>,
+}
+
+/// Lay out, paint, and rasterize `input` at a `width` × `height`
+/// viewport.
+///
+/// The layout tree is cloned and laid out with `font_provider`'s
+/// metrics, painted into a display list, and drawn with `fonts` over the
+/// canvas background. Every front end renders through here; pass the
+/// [shared](RendererFonts::shared) fonts rather than loading them per
+/// frame.
+///
+/// The page is drawn from its top, as no front end scrolls yet: the
+/// [`origin_viewport`] is the only one `content-visibility: auto` boxes
+/// are tested against, within [`koala_css::VIEWPORT_MARGIN`].
+///
+/// Per-stage spans: `render_total` for the whole pipeline, then
+/// `layout_clone`, `layout_pass`, `display_list`, `renderer_alloc` and
+/// `rasterize`.
+#[must_use]
+#[tracing::instrument(name = "render_total", skip_all)]
+pub fn render_page(
+    input: RenderInput<'_>,
+    width: u32,
+    height: u32,
+    fonts: &RendererFonts,
+    font_provider: &FontProvider,
+) -> Renderer {
+    let viewport = origin_viewport(width, height);
+
+    let display_list = input.layout_tree.map(|layout_tree| {
+        let mut layout = clone_layout_tree(layout_tree);
+        apply_layout_pass(&mut layout, viewport, font_provider);
+        build_display_list(&layout, input.styles)
+    });
+
+    // `Renderer::new_with_fonts` records its own `renderer_alloc` span
+    // and `Renderer::render` records `rasterize`.
+    let mut renderer = Renderer::new_with_fonts(width, height, input.images.clone(), fonts.clone());
+    if let Some(color) = koala_css::canvas_background(input.dom, input.styles) {
+        renderer.set_canvas_background(&color);
+    }
+    if let Some(display_list) = display_list {
+        renderer.render(&display_list);
+    }
+    renderer
+}
+
+/// A `width` × `height` viewport at the top-left of the page.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub const fn origin_viewport(width: u32, height: u32) -> koala_css::Rect {
+    koala_css::Rect {
+        x: 0.0,
+        y: 0.0,
+        width: width as f32,
+        height: height as f32,
+    }
+}
+
+/// Clone the box tree before the layout pass mutates it, so the caller's
+/// copy stays reusable at other viewport sizes.
+#[tracing::instrument(name = "layout_clone", skip_all)]
+fn clone_layout_tree(tree: &LayoutBox) -> LayoutBox {
+    tree.clone()
+}
+
+/// Compute box dimensions for `viewport`.
+#[tracing::instrument(name = "layout_pass", skip_all)]
+fn apply_layout_pass(
+    layout: &mut LayoutBox,
+    viewport: koala_css::Rect,
+    font_provider: &FontProvider,
+) {
+    let font_metrics = font_provider.metrics();
+    layout.layout(viewport, viewport, &*font_metrics, viewport);
+}
+
+/// Walk the laid-out tree and emit the paint commands the renderer
+/// executes.
+#[tracing::instrument(name = "display_list", skip_all)]
+fn build_display_list(
+    layout: &LayoutBox,
+    styles: &HashMap<NodeId, ComputedStyle>,
+) -> koala_css::DisplayList {
+    koala_css::DisplayListBuilder::new(styles).build(layout)
+}
+
 /// Try to load a system font for text measurement and rendering.
 ///
 /// Searches common system font paths (macOS, Linux, Windows) and returns
@@ -705,7 +1523,10 @@ pub fn create_font_metrics(font: Option<&fontdue::Font>) -> Box<dyn koala_css::F
 /// characteristic height above the baseline and a depth below it."
 pub struct FontProvider {
     /// The loaded system font, if one was found.
-    font: Option<fontdue::Font>,
+    font: Option<Arc<fontdue::Font>>,
+    /// Fonts for `font-family` entries, keyed by
+    /// [`renderer::family_key`].
+    families: HashMap<renderer::FamilyKey, Arc<fontdue::Font>>,
 }
 
 impl FontProvider {
@@ -713,20 +1534,81 @@ impl FontProvider {
     ///
     /// Searches common system font paths and loads the first one found.
     /// If no font is available, [`metrics()`](Self::metrics) will return
-    /// an approximate metrics provider.
+    /// an approximate metrics provider. Fonts for the generic families
+    /// that are installed (such as `monospace`) are loaded alongside it.
     #[must_use]
     pub fn load() -> Self {
         Self {
-            font: Renderer::load_system_font(),
+            font: Renderer::load_system_font().map(Arc::new),
+            families: Renderer::load_generic_families()
+                .into_iter()
+                .map(|(name, font)| (name, Arc::new(font)))
+                .collect(),
         }
     }
 
+    /// A provider measuring with the fonts a [`Renderer`] built from
+    /// `fonts` draws with. The fonts are shared, not reloaded.
+    #[must_use]
+    pub fn from_renderer_fonts(fonts: &RendererFonts) -> Self {
+        Self {
+            font: fonts.regular.clone(),
+            families: fonts.families.clone(),
+        }
+    }
+
+    /// A provider sharing the fonts of [`RendererFonts::shared`], built
+    /// by the first call in the process and shared by every later one.
+    #[must_use]
+    pub fn shared() -> &'static Self {
+        static PROVIDER: std::sync::OnceLock<FontProvider> = std::sync::OnceLock::new();
+        PROVIDER.get_or_init(|| Self::from_renderer_fonts(RendererFonts::shared()))
+    }
+
+    /// Create a provider with `font` as its default and no other
+    /// families.
+    #[must_use]
+    pub fn new(font: Option<fontdue::Font>) -> Self {
+        Self {
+            font: font.map(Arc::new),
+            families: HashMap::new(),
+        }
+    }
+
+    /// Make `font` available under `family`, replacing any font already
+    /// loaded for it. Family names are matched case-insensitively.
+    pub fn add_family(&mut self, family: &koala_css::FontFamily, font: fontdue::Font) {
+        let _ = self
+            .families
+            .insert(renderer::family_key(family), Arc::new(font));
+    }
+
+    /// [§ 5 Font Matching Algorithm](https://www.w3.org/TR/css-fonts-4/#font-matching-algorithm)
+    ///
+    /// The font for text whose 'font-family' is `families`: that of the
+    /// first family this provider has loaded, falling back to the default
+    /// font when it has none of them.
+    #[must_use]
+    pub fn font_for(&self, families: &[koala_css::FontFamily]) -> Option<&fontdue::Font> {
+        renderer::select_family(&self.families, families)
+            .or(self.font.as_ref())
+            .map(Arc::as_ref)
+    }
+
     /// Create a [`FontMetrics`](koala_css::FontMetrics) provider from this font.
     ///
     /// Returns real per-glyph metrics if a font was loaded, or an
-    /// approximation (0.6 × font size per character) otherwise.
+    /// approximation (0.6 × font size per character) otherwise. Text is
+    /// measured with the font its 'font-family' selects, as in
+    /// [`Self::font_for`].
     #[must_use]
     pub fn metrics(&self) -> Box<dyn koala_css::FontMetrics + '_> {
-        create_font_metrics(self.font.as_ref())
+        match &self.font {
+            Some(font) => Box::new(font_metrics::FontdueFontMetrics::with_families(
+                font,
+                &self.families,
+            )),
+            None => Box::new(koala_css::ApproximateFontMetrics),
+        }
     }
 }
@@ -0,0 +1,157 @@
+//! Clickable link regions.
+//!
+//! [§ 4.6.1 Links](https://html.spec.whatwg.org/multipage/links.html#introduction-2)
+//!
+//! "Links are a conceptual construct, created by a, area, form, and link
+//! elements, that represent a connection between two resources."
+//!
+//! After layout, each rendered `<a href>` box, or each line fragment of
+//! an inline one, is turned into a [`LinkRegion`] so a front end can map
+//! a click position to the navigation target.
+
+use koala_css::{BoxType, LayoutBox, Rect};
+use koala_dom::{DomTree, NodeId};
+
+/// The on-screen area of a hyperlink and where it leads.
+#[derive(Debug, Clone)]
+pub struct LinkRegion {
+    /// The `<a>` element that generated the region.
+    pub node: NodeId,
+    /// The border box of the link's principal box, or for an inline link
+    /// one of the line fragments of its contents, in page coordinates.
+    pub rect: Rect,
+    /// The `href` attribute resolved against the document's base URL.
+    pub href: String,
+}
+
+impl LinkRegion {
+    /// Whether the point `(x, y)` falls inside this region.
+    #[must_use]
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.rect.x
+            && x < self.rect.x + self.rect.width
+            && y >= self.rect.y
+            && y < self.rect.y + self.rect.height
+    }
+}
+
+/// Collect a [`LinkRegion`] for every rendered `<a href>` box in `layout`.
+///
+/// [§ 4.5.1 The a element](https://html.spec.whatwg.org/multipage/text-level-semantics.html#the-a-element)
+///
+/// "If the a element has an href attribute, then it represents a hyperlink
+/// (a hypertext anchor) labeled by its contents."
+///
+/// [§ 4.6.4 API for a and area elements](https://html.spec.whatwg.org/multipage/links.html#api-for-a-and-area-elements)
+///
+/// The `href` is resolved against `base_url` the same way other
+/// subresource URLs are. Regions are returned in painting order, so when
+/// regions overlap the last match is the topmost one. `layout` must
+/// already have been laid out.
+///
+/// [§ 9.2.2 Inline-level elements and inline boxes](https://www.w3.org/TR/CSS2/visuren.html#inline-boxes)
+///
+/// Non-replaced inline boxes don't get dimensions of their own: their
+/// contents are placed in the enclosing block's line boxes. An inline
+/// `<a>` gets one region per line fragment inside it instead, so a link
+/// wrapped across lines is clickable on each of them.
+///
+/// NOTE: The fragments cover the link's text and atomic inlines, not the
+/// inline box's own padding and border.
+#[must_use]
+pub fn collect_link_regions(
+    layout: &LayoutBox,
+    dom: &DomTree,
+    base_url: Option<&str>,
+) -> Vec<LinkRegion> {
+    let mut regions = Vec::new();
+    collect_into(layout, None, dom, base_url, &mut regions);
+    regions
+}
+
+/// `container` is the element of the nearest principal box enclosing
+/// `layout_box`, which anonymous blocks take their line boxes from.
+fn collect_into(
+    layout_box: &LayoutBox,
+    container: Option<NodeId>,
+    dom: &DomTree,
+    base_url: Option<&str>,
+    regions: &mut Vec<LinkRegion>,
+) {
+    let container = match layout_box.box_type {
+        BoxType::Principal(node_id) => Some(node_id),
+        _ => container,
+    };
+    if let BoxType::Principal(node_id) = layout_box.box_type
+        && let Some(href) = link_href(dom, node_id)
+    {
+        push_region(
+            regions,
+            node_id,
+            layout_box.dimensions.border_box(),
+            href,
+            base_url,
+        );
+    }
+    for fragment in layout_box
+        .line_boxes
+        .iter()
+        .flat_map(|line| &line.fragments)
+    {
+        if let Some(inline_box) = fragment.inline_box
+            && let Some((node_id, href)) = enclosing_link(dom, inline_box, container)
+        {
+            push_region(regions, node_id, fragment.bounds, href, base_url);
+        }
+    }
+    for child in &layout_box.children {
+        collect_into(child, container, dom, base_url, regions);
+    }
+}
+
+/// The `href` of `node_id` if it is an `<a href>` element.
+fn link_href(dom: &DomTree, node_id: NodeId) -> Option<&str> {
+    let element = dom.as_element(node_id)?;
+    if element.tag_name != "a" {
+        return None;
+    }
+    element.attrs.get("href").map(String::as_str)
+}
+
+/// The innermost `<a href>` among `inline_box` and its ancestors below
+/// `container`, the block container whose line boxes hold `inline_box`'s
+/// fragments.
+fn enclosing_link(
+    dom: &DomTree,
+    inline_box: NodeId,
+    container: Option<NodeId>,
+) -> Option<(NodeId, &str)> {
+    let mut node = Some(inline_box);
+    while let Some(node_id) = node
+        && Some(node_id) != container
+    {
+        if let Some(href) = link_href(dom, node_id) {
+            return Some((node_id, href));
+        }
+        node = dom.parent(node_id);
+    }
+    None
+}
+
+/// Record a region for the link `node_id` covering `rect`, unless it is
+/// empty.
+fn push_region(
+    regions: &mut Vec<LinkRegion>,
+    node_id: NodeId,
+    rect: Rect,
+    href: &str,
+    base_url: Option<&str>,
+) {
+    if rect.width > 0.0 && rect.height > 0.0 {
+        regions.push(LinkRegion {
+            node: node_id,
+            rect,
+            href: koala_common::url::resolve_url(href.trim(), base_url),
+        });
+    }
+}
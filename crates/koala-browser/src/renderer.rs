@@ -19,11 +19,12 @@ use anyhow::Result;
 use fontdue::{Font, FontSettings};
 use image::{ImageBuffer, Rgba, RgbaImage};
 use koala_css::{
-    BorderRadius, ColorValue, DisplayCommand, DisplayList, FontStyle, TextDecorationLine,
+    BorderRadius, ColorValue, DisplayCommand, DisplayList, FontFamily, FontStyle,
+    GenericFontFamily, Rect, TextDecoration, TextDecorationStyle,
 };
 use koala_std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use koala_common::image::LoadedImage;
 
@@ -86,6 +87,90 @@ const FONT_BOLD_ITALIC_SEARCH_PATHS: &[&str] = &[
     "C:\\Windows\\Fonts\\arialbi.ttf",
 ];
 
+/// System font paths for the generic families that have a font of their
+/// own. `sans-serif` and the rest fall back to the default font.
+///
+/// [§ 3.1.1 Generic font families](https://www.w3.org/TR/css-fonts-4/#generic-font-families)
+const GENERIC_FAMILY_SEARCH_PATHS: &[(GenericFontFamily, &[&str])] = &[
+    (
+        GenericFontFamily::Monospace,
+        &[
+            // macOS
+            "/System/Library/Fonts/Menlo.ttc",
+            "/System/Library/Fonts/Supplemental/Courier New.ttf",
+            // Linux
+            "/usr/share/fonts/truetype/dejavu/DejaVuSansMono.ttf",
+            "/usr/share/fonts/TTF/DejaVuSansMono.ttf",
+            "/usr/share/fonts/truetype/liberation/LiberationMono-Regular.ttf",
+            "/usr/share/fonts/truetype/freefont/FreeMono.ttf",
+            // Windows
+            "C:\\Windows\\Fonts\\consola.ttf",
+            "C:\\Windows\\Fonts\\cour.ttf",
+        ],
+    ),
+    (
+        GenericFontFamily::Serif,
+        &[
+            // macOS
+            "/System/Library/Fonts/Supplemental/Times New Roman.ttf",
+            "/Library/Fonts/Times New Roman.ttf",
+            // Linux
+            "/usr/share/fonts/truetype/dejavu/DejaVuSerif.ttf",
+            "/usr/share/fonts/TTF/DejaVuSerif.ttf",
+            "/usr/share/fonts/truetype/liberation/LiberationSerif-Regular.ttf",
+            "/usr/share/fonts/truetype/freefont/FreeSerif.ttf",
+            // Windows
+            "C:\\Windows\\Fonts\\times.ttf",
+        ],
+    ),
+];
+
+/// [§ 5 Font Matching Algorithm](https://www.w3.org/TR/css-fonts-4/#font-matching-algorithm)
+///
+/// "If the family name is a generic family keyword, the user agent looks
+/// up the appropriate font family name to be used... If a font family
+/// match occurs, the user agent assembles the set of font faces in that
+/// family... If no matching face exists... the user agent performs the
+/// same font selection step with the next font family name."
+///
+/// Pick the font of the first family in `families` that `loaded` holds,
+/// or `None` if it holds none of them. `loaded` is keyed by
+/// [`family_key`]; "font family names are matched case-insensitively".
+#[must_use]
+pub fn select_family<'a, F>(
+    loaded: &'a HashMap<FamilyKey, F>,
+    families: &[FontFamily],
+) -> Option<&'a F> {
+    families
+        .iter()
+        .find_map(|family| loaded.get(&family_key(family)))
+}
+
+/// The key a family is stored under in a family map.
+///
+/// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+///
+/// "Font family names that happen to be the same as a keyword value...
+/// must be quoted to prevent confusion with the keywords with the same
+/// names." A quoted `"monospace"` names a family called monospace, not
+/// the generic family, so the two get different keys.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FamilyKey {
+    /// A family name, lowercased.
+    Named(String),
+    /// A generic family keyword.
+    Generic(GenericFontFamily),
+}
+
+/// The key `family` is stored under in a family map.
+#[must_use]
+pub fn family_key(family: &FontFamily) -> FamilyKey {
+    match family {
+        FontFamily::Named(name) => FamilyKey::Named(name.to_ascii_lowercase()),
+        FontFamily::Generic(generic) => FamilyKey::Generic(*generic),
+    }
+}
+
 /// A set of already-loaded fonts that a [`Renderer`] can draw with.
 ///
 /// Loading a font from disk costs ~55 ms per variant on macOS, and the
@@ -111,6 +196,9 @@ pub struct RendererFonts {
     /// Bold+italic variant. Falls back to `bold`, then `italic`, then
     /// `regular` at draw time if missing.
     pub bold_italic: Option<Arc<Font>>,
+    /// Fonts for `font-family` entries, keyed by [`family_key`]. Text
+    /// whose family list names none of them uses the variants above.
+    pub families: HashMap<FamilyKey, Arc<Font>>,
 }
 
 impl RendererFonts {
@@ -130,8 +218,20 @@ impl RendererFonts {
                 "bold-italic",
             )
             .map(Arc::new),
+            families: Renderer::load_generic_families()
+                .into_iter()
+                .map(|(name, font)| (name, Arc::new(font)))
+                .collect(),
         }
     }
+
+    /// The fonts [`Self::from_system`] finds, loaded by the first call in
+    /// the process and shared by every later one.
+    #[must_use]
+    pub fn shared() -> &'static Self {
+        static FONTS: OnceLock<RendererFonts> = OnceLock::new();
+        FONTS.get_or_init(Self::from_system)
+    }
 }
 
 /// Software renderer that executes a display list to a pixel buffer.
@@ -153,14 +253,17 @@ pub struct Renderer {
     font_italic: Option<Arc<Font>>,
     /// Bold-italic font variant (None falls back to bold or italic or regular)
     font_bold_italic: Option<Arc<Font>>,
+    /// Fonts for `font-family` entries, keyed by [`family_key`].
+    font_families: HashMap<FamilyKey, Arc<Font>>,
     /// Loaded images keyed by src attribute. Used for `DrawImage` commands.
     images: HashMap<String, LoadedImage>,
-    /// Stack of active clip rectangles for overflow: hidden.
+    /// Stack of active clip regions for overflow: hidden.
     ///
     /// [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
     ///
-    /// Each entry is (x, y, width, height) in pixel coordinates.
-    clip_stack: Vec<(f32, f32, f32, f32)>,
+    /// Each entry is a rectangle in pixel coordinates and, for a rounded
+    /// clip, its corner radii.
+    clip_stack: Vec<(Rect, Option<BorderRadius>)>,
 }
 
 impl Renderer {
@@ -218,11 +321,25 @@ impl Renderer {
             font_bold: fonts.bold,
             font_italic: fonts.italic,
             font_bold_italic: fonts.bold_italic,
+            font_families: fonts.families,
             images,
             clip_stack: Vec::new(),
         }
     }
 
+    /// Load a font for each generic family in
+    /// `GENERIC_FAMILY_SEARCH_PATHS` that has one installed, keyed by
+    /// [`FamilyKey::Generic`].
+    pub(crate) fn load_generic_families() -> HashMap<FamilyKey, Font> {
+        GENERIC_FAMILY_SEARCH_PATHS
+            .iter()
+            .filter_map(|(generic, paths)| {
+                Self::load_font_from_paths(paths, generic.keyword())
+                    .map(|font| (FamilyKey::Generic(*generic), font))
+            })
+            .collect()
+    }
+
     /// Try to load a font from a list of filesystem paths.
     fn load_font_from_paths(paths: &[&str], label: &str) -> Option<Font> {
         for path in paths {
@@ -257,6 +374,9 @@ impl Renderer {
     /// when we start the glyph-cache work.
     #[tracing::instrument(name = "rasterize", skip_all)]
     pub fn render(&mut self, display_list: &DisplayList) {
+        // NOTE: Clips never outlive the display list that pushed them,
+        // so a renderer reused across frames starts each one unclipped.
+        self.clip_stack.clear();
         for command in display_list.commands() {
             self.execute_command(command);
         }
@@ -365,6 +485,7 @@ impl Renderer {
                 color,
                 font_weight,
                 font_style,
+                font_family,
                 text_decoration,
                 letter_spacing,
             } => {
@@ -376,17 +497,13 @@ impl Renderer {
                     color,
                     *font_weight,
                     *font_style,
-                    *text_decoration,
+                    font_family,
+                    text_decoration,
                     *letter_spacing,
                 );
             }
-            DisplayCommand::PushClip {
-                x,
-                y,
-                width,
-                height,
-            } => {
-                self.clip_stack.push((*x, *y, *width, *height));
+            DisplayCommand::PushClip(rect, radius) => {
+                self.clip_stack.push((*rect, *radius));
             }
             DisplayCommand::PopClip => {
                 let _ = self.clip_stack.pop();
@@ -394,16 +511,29 @@ impl Renderer {
         }
     }
 
-    /// Check if a pixel is within all active clip rectangles.
+    /// Check if a pixel is within all active clip regions.
     ///
     /// [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
+    ///
+    /// [§ 5.3 Corner Clipping](https://www.w3.org/TR/css-backgrounds-3/#corner-clipping)
+    ///
+    /// Pixels cut off by a rounded clip's corner curves are not visible.
     #[allow(clippy::cast_precision_loss)]
     fn is_visible(&self, px: i32, py: i32) -> bool {
         let fx = px as f32;
         let fy = py as f32;
-        self.clip_stack
-            .iter()
-            .all(|&(cx, cy, cw, ch)| fx >= cx && fx < cx + cw && fy >= cy && fy < cy + ch)
+        self.clip_stack.iter().all(|(rect, radius)| {
+            rect.contains(fx, fy)
+                && radius.as_ref().is_none_or(|radius| {
+                    !outside_rounded_corner(
+                        fx - rect.x,
+                        fy - rect.y,
+                        rect.width,
+                        rect.height,
+                        radius,
+                    )
+                })
+        })
     }
 
     /// Fill a rectangle with the given color, optionally with rounded corners.
@@ -454,55 +584,10 @@ impl Renderer {
                 }
 
                 // Check if pixel is inside rounded corners
-                if has_radius {
-                    let fx = dx as f32;
-                    let fy = dy as f32;
-                    let fw = width;
-                    let fh = height;
-
-                    // Top-left corner
-                    let r = border_radius.top_left;
-                    if r > 0.0 && fx < r && fy < r {
-                        let cx = r;
-                        let cy = r;
-                        let dist_sq = (fx - cx).mul_add(fx - cx, (fy - cy) * (fy - cy));
-                        if dist_sq > r * r {
-                            continue;
-                        }
-                    }
-
-                    // Top-right corner
-                    let r = border_radius.top_right;
-                    if r > 0.0 && fx >= fw - r && fy < r {
-                        let cx = fw - r;
-                        let cy = r;
-                        let dist_sq = (fx - cx).mul_add(fx - cx, (fy - cy) * (fy - cy));
-                        if dist_sq > r * r {
-                            continue;
-                        }
-                    }
-
-                    // Bottom-left corner
-                    let r = border_radius.bottom_left;
-                    if r > 0.0 && fx < r && fy >= fh - r {
-                        let cx = r;
-                        let cy = fh - r;
-                        let dist_sq = (fx - cx).mul_add(fx - cx, (fy - cy) * (fy - cy));
-                        if dist_sq > r * r {
-                            continue;
-                        }
-                    }
-
-                    // Bottom-right corner
-                    let r = border_radius.bottom_right;
-                    if r > 0.0 && fx >= fw - r && fy >= fh - r {
-                        let cx = fw - r;
-                        let cy = fh - r;
-                        let dist_sq = (fx - cx).mul_add(fx - cx, (fy - cy) * (fy - cy));
-                        if dist_sq > r * r {
-                            continue;
-                        }
-                    }
+                if has_radius
+                    && outside_rounded_corner(dx as f32, dy as f32, width, height, border_radius)
+                {
+                    continue;
                 }
 
                 self.buffer.put_pixel(px as u32, py as u32, rgba);
@@ -598,15 +683,23 @@ impl Renderer {
         color: &ColorValue,
         font_weight: u16,
         font_style: FontStyle,
-        text_decoration: TextDecorationLine,
+        font_family: &[FontFamily],
+        text_decoration: &TextDecoration,
         letter_spacing: f32,
     ) {
-        // Select the best available font for the given weight and style,
+        // [§ 5 Font Matching Algorithm](https://www.w3.org/TR/css-fonts-4/#font-matching-algorithm)
+        //
+        // The first loaded family in the list wins. Otherwise select the
+        // best available default font for the given weight and style,
         // falling back through: exact match → partial match → regular.
+        //
+        // NOTE: Families other than the default have a single face, used
+        // for every weight and style.
         let is_bold = font_weight >= 700;
         let is_italic = font_style != FontStyle::Normal;
 
-        let font = match (is_bold, is_italic) {
+        let family_font = select_family(&self.font_families, font_family).map(Arc::as_ref);
+        let font = family_font.or_else(|| match (is_bold, is_italic) {
             (true, true) => self
                 .font_bold_italic
                 .as_deref()
@@ -615,7 +708,7 @@ impl Renderer {
             (true, false) => self.font_bold.as_deref().or(self.font.as_deref()),
             (false, true) => self.font_italic.as_deref().or(self.font.as_deref()),
             (false, false) => self.font.as_deref(),
-        };
+        });
 
         let Some(font) = font else {
             return;
@@ -684,28 +777,100 @@ impl Renderer {
 
         // [§ 3 Text Decoration Lines](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
         //
-        // Draw text decoration lines after the glyphs.
+        // Draw text decoration lines after the glyphs, in the decoration's
+        // own style and color rather than the text color.
         let total_advance = cursor_x - x;
-        if total_advance > 0.0
-            && (text_decoration.underline || text_decoration.overline || text_decoration.line_through)
-        {
+        let line = text_decoration.line;
+        if total_advance > 0.0 && line.any() {
             let line_thickness = (font_size / 16.0).max(1.0);
+            let mut draw = |fraction: f32| {
+                self.draw_decoration_line(
+                    x,
+                    font_size.mul_add(fraction, y),
+                    total_advance,
+                    line_thickness,
+                    text_decoration.style,
+                    &text_decoration.color,
+                );
+            };
 
-            if text_decoration.underline {
+            if line.underline {
                 // Underline: just below the baseline.
-                let line_y = font_size.mul_add(0.9, y);
-                self.fill_rect(x, line_y, total_advance, line_thickness, color, &BorderRadius::default());
+                draw(0.9);
             }
-            if text_decoration.line_through {
+            if line.line_through {
                 // Line-through: through the middle of the text.
-                let line_y = font_size.mul_add(0.55, y);
-                self.fill_rect(x, line_y, total_advance, line_thickness, color, &BorderRadius::default());
+                draw(0.55);
             }
-            if text_decoration.overline {
+            if line.overline {
                 // Overline: at the top of the text.
-                let line_y = font_size.mul_add(0.1, y);
-                self.fill_rect(x, line_y, total_advance, line_thickness, color, &BorderRadius::default());
+                draw(0.1);
+            }
+        }
+    }
+
+    /// [§ 2.2 'text-decoration-style'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-style-property)
+    ///
+    /// Draw one decoration line of `thickness` starting at (`x`, `y`) and
+    /// spanning `width` pixels.
+    ///
+    /// NOTE: Dots are square, and the wave is a sine whose amplitude and
+    /// wavelength scale with the thickness.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn draw_decoration_line(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        thickness: f32,
+        style: TextDecorationStyle,
+        color: &ColorValue,
+    ) {
+        let no_radius = BorderRadius::default();
+        // (segment length, gap length) for the broken styles.
+        let pattern = match style {
+            TextDecorationStyle::Solid => {
+                self.fill_rect(x, y, width, thickness, color, &no_radius);
+                return;
+            }
+            // "Two parallel solid lines with some space between them."
+            TextDecorationStyle::Double => {
+                self.fill_rect(x, y, width, thickness, color, &no_radius);
+                self.fill_rect(
+                    x,
+                    thickness.mul_add(2.0, y),
+                    width,
+                    thickness,
+                    color,
+                    &no_radius,
+                );
+                return;
+            }
+            TextDecorationStyle::Wavy => {
+                let amplitude = thickness * 1.5;
+                let wavelength = thickness * 6.0;
+                for column in 0..width.ceil() as u32 {
+                    let dx = column as f32;
+                    let phase = dx / wavelength * std::f32::consts::TAU;
+                    let offset = amplitude * phase.sin();
+                    self.fill_rect(x + dx, y + offset, 1.0, thickness, color, &no_radius);
+                }
+                return;
             }
+            TextDecorationStyle::Dotted => (thickness, thickness),
+            TextDecorationStyle::Dashed => (thickness * 3.0, thickness * 2.0),
+        };
+
+        let (segment, gap) = pattern;
+        let count = (width / (segment + gap)).ceil() as u32;
+        for i in 0..count {
+            let start = i as f32 * (segment + gap);
+            let length = segment.min(width - start);
+            self.fill_rect(x + start, y, length, thickness, color, &no_radius);
         }
     }
 
@@ -956,6 +1121,50 @@ impl Renderer {
     }
 }
 
+/// Whether the point `(fx, fy)`, relative to the top-left corner of a
+/// `width` x `height` box, lies outside one of the box's rounded corners.
+///
+/// [§ 5 'border-radius'](https://www.w3.org/TR/css-backgrounds-3/#border-radius)
+///
+/// A point in a corner's `r` x `r` square is outside when it is farther
+/// than `r` from the center of that corner's quarter circle.
+fn outside_rounded_corner(
+    fx: f32,
+    fy: f32,
+    width: f32,
+    height: f32,
+    border_radius: &BorderRadius,
+) -> bool {
+    let outside = |r: f32, in_corner: bool, cx: f32, cy: f32| {
+        in_corner && (fx - cx).mul_add(fx - cx, (fy - cy) * (fy - cy)) > r * r
+    };
+
+    // Top-left corner
+    let r = border_radius.top_left;
+    if r > 0.0 && outside(r, fx < r && fy < r, r, r) {
+        return true;
+    }
+    // Top-right corner
+    let r = border_radius.top_right;
+    if r > 0.0 && outside(r, fx >= width - r && fy < r, width - r, r) {
+        return true;
+    }
+    // Bottom-left corner
+    let r = border_radius.bottom_left;
+    if r > 0.0 && outside(r, fx < r && fy >= height - r, r, height - r) {
+        return true;
+    }
+    // Bottom-right corner
+    let r = border_radius.bottom_right;
+    r > 0.0
+        && outside(
+            r,
+            fx >= width - r && fy >= height - r,
+            width - r,
+            height - r,
+        )
+}
+
 /// Allocate the RGBA pixel buffer, prefilled with opaque white.
 /// Its own function so `#[tracing::instrument]` can name the span
 /// for the buffer-pool optimisation work (Tier 1 item #1 in the
@@ -0,0 +1,370 @@
+//! Responsive image source selection for `<img srcset sizes>`.
+//!
+//! [§ 4.8.4.3 Processing model](https://html.spec.whatwg.org/multipage/images.html#update-the-image-data)
+//!
+//! An `<img>` may offer several candidate URLs through its `srcset`
+//! attribute, each annotated with a width (`480w`) or pixel density
+//! (`2x`) descriptor. This module parses those candidates, resolves
+//! width descriptors against the `sizes` attribute, and picks the one
+//! the user agent should fetch. The chosen URL is the element's
+//! `currentSrc`.
+
+/// [§ 4.8.4.2.2 Srcset attributes](https://html.spec.whatwg.org/multipage/images.html#srcset-attributes)
+///
+/// The descriptor attached to an image candidate string.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Descriptor {
+    /// "A width descriptor, consisting of: ASCII whitespace, a valid
+    /// non-negative integer giving a number greater than zero
+    /// representing the width descriptor value, and a U+0077 LATIN SMALL
+    /// LETTER W character."
+    Width(u32),
+    /// "A pixel density descriptor, consisting of: ASCII whitespace, a
+    /// valid floating-point number giving a number greater than or equal
+    /// to zero representing the pixel density descriptor value, and a
+    /// U+0078 LATIN SMALL LETTER X character."
+    Density(f32),
+}
+
+/// One entry of a parsed `srcset` attribute.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageCandidate {
+    /// The candidate's URL, exactly as written in the attribute.
+    pub url: String,
+    /// The candidate's descriptor. `None` means the candidate had no
+    /// descriptor, which is equivalent to `1x`.
+    pub descriptor: Option<Descriptor>,
+}
+
+/// The environment an image source is selected for.
+///
+/// [§ 4.8.4.3.6 Selecting an image source](https://html.spec.whatwg.org/multipage/images.html#select-an-image-source)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceSelectionContext {
+    /// Width of the viewport in CSS pixels, used to resolve `vw` lengths
+    /// and media conditions in `sizes`.
+    pub viewport_width: f32,
+    /// Device pixels per CSS pixel.
+    pub device_pixel_ratio: f32,
+}
+
+impl Default for SourceSelectionContext {
+    /// NOTE: The document is loaded before any window exists, so there is
+    /// no real viewport yet. These defaults match the headless renderer's
+    /// usual 800px-wide surface at 1x density.
+    fn default() -> Self {
+        Self {
+            viewport_width: 800.0,
+            device_pixel_ratio: 1.0,
+        }
+    }
+}
+
+/// [§ 4.8.4.3.10 Parsing a srcset attribute](https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute)
+///
+/// Candidates with invalid descriptors are dropped, as the spec's
+/// "error" outcome requires.
+#[must_use]
+pub fn parse_srcset(input: &str) -> Vec<ImageCandidate> {
+    let mut candidates = Vec::new();
+    let mut rest = input;
+
+    loop {
+        // STEP 4: "Splitting loop: Collect a sequence of code points that
+        // are ASCII whitespace or U+002C COMMA characters from input."
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+
+        // STEP 5: "If position is past the end of input, return
+        // candidates."
+        if rest.is_empty() {
+            return candidates;
+        }
+
+        // STEP 6: "Collect a sequence of code points that are not ASCII
+        // whitespace from input, and let that be url."
+        let url_end = rest
+            .find(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(rest.len());
+        let (raw_url, after_url) = rest.split_at(url_end);
+        rest = after_url;
+
+        // STEP 8: "If url ends with U+002C (,), then: Remove all trailing
+        // U+002C COMMA characters from url. If this removed more than one
+        // character, that is a parse error."
+        //
+        // A URL ending in a comma has no descriptors.
+        let descriptors = if raw_url.ends_with(',') {
+            Vec::new()
+        } else {
+            let (tokens, remaining) = tokenize_descriptors(rest);
+            rest = remaining;
+            tokens
+        };
+        let url = raw_url.trim_end_matches(',');
+        if url.is_empty() {
+            continue;
+        }
+
+        // STEP 9: "Descriptor parser"
+        if let Ok(descriptor) = parse_descriptors(&descriptors) {
+            candidates.push(ImageCandidate {
+                url: url.to_string(),
+                descriptor,
+            });
+        }
+    }
+}
+
+/// [§ 4.8.4.3.10](https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute)
+/// STEP 8.3: "Tokenize: ..."
+///
+/// Splits descriptors on whitespace up to the next top-level comma,
+/// treating parenthesized text as part of the current descriptor.
+/// Returns the descriptor tokens and the input after the comma.
+fn tokenize_descriptors(input: &str) -> (Vec<&str>, &str) {
+    let mut tokens = Vec::new();
+    let mut token_start: Option<usize> = None;
+    let mut in_parens = false;
+
+    for (i, c) in input.char_indices() {
+        if in_parens {
+            if c == ')' {
+                in_parens = false;
+            }
+            continue;
+        }
+        match c {
+            ',' => {
+                if let Some(start) = token_start {
+                    tokens.push(&input[start..i]);
+                }
+                return (tokens, &input[i + 1..]);
+            }
+            '(' => {
+                in_parens = true;
+                let _ = token_start.get_or_insert(i);
+            }
+            c if c.is_ascii_whitespace() => {
+                if let Some(start) = token_start.take() {
+                    tokens.push(&input[start..i]);
+                }
+            }
+            _ => {
+                let _ = token_start.get_or_insert(i);
+            }
+        }
+    }
+
+    if let Some(start) = token_start {
+        tokens.push(&input[start..]);
+    }
+    (tokens, "")
+}
+
+/// [§ 4.8.4.3.10](https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute)
+/// STEP 9: "Descriptor parser"
+///
+/// Returns `Err` if the spec's "error" flag would be set, and `Ok(None)`
+/// for a candidate without descriptors.
+fn parse_descriptors(tokens: &[&str]) -> Result<Option<Descriptor>, ()> {
+    let mut descriptor = None;
+    // "Let future-compat-h be absent."
+    let mut future_compat_h = false;
+
+    for token in tokens {
+        let Some(suffix) = token.chars().last() else {
+            continue;
+        };
+        let value = &token[..token.len() - suffix.len_utf8()];
+        let next = match suffix {
+            // "If the descriptor consists of a valid non-negative integer
+            // followed by a U+0077 LATIN SMALL LETTER W character ... If
+            // width and density are not both absent, then let error be
+            // yes. ... If the result is zero, let error be yes."
+            'w' => match value.parse::<u32>() {
+                Ok(w) if w > 0 && value.bytes().all(|b| b.is_ascii_digit()) => Descriptor::Width(w),
+                _ => return Err(()),
+            },
+            // "If the descriptor consists of a valid floating-point number
+            // followed by a U+0078 LATIN SMALL LETTER X character ... If
+            // width, density and future-compat-h are not all absent, then
+            // let error be yes. ... If the result is less than zero, let
+            // error be yes."
+            'x' => match value.parse::<f32>() {
+                Ok(x) if x >= 0.0 && x.is_finite() && !future_compat_h => Descriptor::Density(x),
+                _ => return Err(()),
+            },
+            // "If the descriptor consists of a valid non-negative integer
+            // followed by a U+0068 LATIN SMALL LETTER H character ... If
+            // future-compat-h and density are not both absent, then let
+            // error be yes. ... If the result is 0, let error be yes."
+            //
+            // NOTE: The height is only validated; it never affects
+            // selection.
+            'h' => match value.parse::<u32>() {
+                Ok(h)
+                    if h > 0
+                        && value.bytes().all(|b| b.is_ascii_digit())
+                        && !future_compat_h
+                        && !matches!(descriptor, Some(Descriptor::Density(_))) =>
+                {
+                    future_compat_h = true;
+                    continue;
+                }
+                _ => return Err(()),
+            },
+            _ => return Err(()),
+        };
+        if descriptor.is_some() {
+            return Err(());
+        }
+        descriptor = Some(next);
+    }
+
+    // "If future-compat-h is not absent and width is absent, let error be
+    // yes."
+    if future_compat_h && !matches!(descriptor, Some(Descriptor::Width(_))) {
+        return Err(());
+    }
+    Ok(descriptor)
+}
+
+/// [§ 4.8.4.3.11 Parsing a sizes attribute](https://html.spec.whatwg.org/multipage/images.html#parse-a-sizes-attribute)
+///
+/// Returns the source size in CSS pixels: the length of the first entry
+/// whose media condition matches, or `100vw` if none does.
+///
+/// NOTE: Only `(min-width: …)` and `(max-width: …)` media conditions are
+/// understood; an entry with any other condition is skipped. Lengths may
+/// be in `px`, `vw` or `em` (against a 16px root font size).
+#[must_use]
+pub fn parse_sizes(input: &str, viewport_width: f32) -> f32 {
+    for entry in input.split(',') {
+        let entry = entry.trim();
+        // "If the last component value in unparsed size is a valid
+        // non-negative <source-size-value>, let size be its value and
+        // remove the component value from unparsed size."
+        let (condition, size) = entry
+            .rsplit_once(|c: char| c.is_ascii_whitespace())
+            .unwrap_or(("", entry));
+        let Some(size) = parse_source_size_value(size, viewport_width) else {
+            continue;
+        };
+        let condition = condition.trim();
+        // "If unparsed size is now empty, ... return size."
+        if condition.is_empty() || media_condition_matches(condition, viewport_width) {
+            return size;
+        }
+    }
+
+    // "Return 100vw."
+    viewport_width
+}
+
+fn parse_source_size_value(value: &str, viewport_width: f32) -> Option<f32> {
+    let (number, factor) = if let Some(n) = value.strip_suffix("px") {
+        (n, 1.0)
+    } else if let Some(n) = value.strip_suffix("vw") {
+        (n, viewport_width / 100.0)
+    } else if let Some(n) = value.strip_suffix("em") {
+        (n, 16.0)
+    } else if value == "0" {
+        ("0", 0.0)
+    } else {
+        return None;
+    };
+    let number: f32 = number.parse().ok()?;
+    (number >= 0.0).then_some(number * factor)
+}
+
+fn media_condition_matches(condition: &str, viewport_width: f32) -> bool {
+    let Some(inner) = condition
+        .strip_prefix('(')
+        .and_then(|c| c.strip_suffix(')'))
+    else {
+        return false;
+    };
+    let Some((feature, value)) = inner.split_once(':') else {
+        return false;
+    };
+    let Some(value) = parse_source_size_value(value.trim(), viewport_width) else {
+        return false;
+    };
+    match feature.trim().to_ascii_lowercase().as_str() {
+        "min-width" => viewport_width >= value,
+        "max-width" => viewport_width <= value,
+        _ => false,
+    }
+}
+
+/// [§ 4.8.4.3.6 Selecting an image source](https://html.spec.whatwg.org/multipage/images.html#select-an-image-source)
+///
+/// Pick the URL to fetch for an `<img>` with the given `src`, `srcset`
+/// and `sizes` attribute values. Returns `None` when the element offers
+/// no usable URL.
+///
+/// The spec leaves the final choice to the user agent; we take the
+/// candidate with the smallest density that is at least the device
+/// pixel ratio, or the densest candidate if none is dense enough.
+#[must_use]
+pub fn select_image_source(
+    src: Option<&str>,
+    srcset: Option<&str>,
+    sizes: Option<&str>,
+    context: SourceSelectionContext,
+) -> Option<String> {
+    let src = src.map(str::trim).filter(|s| !s.is_empty());
+    let candidates = srcset.map(parse_srcset).unwrap_or_default();
+
+    // [§ 4.8.4.3.7 Creating a source set](https://html.spec.whatwg.org/multipage/images.html#create-a-source-set)
+    //
+    // "For each image source in source set: If the image source has a
+    // pixel density descriptor, continue to the next image source.
+    // Otherwise, if the image source has a width descriptor, replace the
+    // width descriptor with a pixel density descriptor with a value of
+    // the width descriptor value divided by source size and a unit of x.
+    // Otherwise, give the image source a pixel density descriptor of 1x."
+    let has_width = candidates
+        .iter()
+        .any(|c| matches!(c.descriptor, Some(Descriptor::Width(_))));
+    let source_size = if has_width {
+        sizes.map_or(context.viewport_width, |s| {
+            parse_sizes(s, context.viewport_width)
+        })
+    } else {
+        context.viewport_width
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let mut source_set: Vec<(&str, f32)> = candidates
+        .iter()
+        .map(|c| {
+            let density = match c.descriptor {
+                Some(Descriptor::Density(x)) => x,
+                Some(Descriptor::Width(w)) if source_size > 0.0 => w as f32 / source_size,
+                Some(Descriptor::Width(_)) => f32::INFINITY,
+                None => 1.0,
+            };
+            (c.url.as_str(), density)
+        })
+        .collect();
+
+    // "If el has a src attribute whose value is not the empty string and
+    // source set does not contain an image source with a pixel density
+    // descriptor value of 1, and no image source with a width descriptor,
+    // append el's src attribute value to source set."
+    if let Some(src) = src {
+        #[allow(clippy::float_cmp)]
+        let has_1x = source_set.iter().any(|&(_, density)| density == 1.0);
+        if !has_width && !has_1x {
+            source_set.push((src, 1.0));
+        }
+    }
+
+    let dense_enough = source_set
+        .iter()
+        .filter(|&&(_, density)| density >= context.device_pixel_ratio)
+        .min_by(|a, b| a.1.total_cmp(&b.1));
+    let chosen = dense_enough.or_else(|| source_set.iter().max_by(|a, b| a.1.total_cmp(&b.1)));
+
+    chosen.map(|&(url, _)| url.to_string())
+}
@@ -0,0 +1,39 @@
+//! Shared fixtures for koala-browser integration tests.
+//!
+//! Each `tests/*.rs` is its own crate, so helpers go under
+//! `tests/common/` and are pulled in with `mod common;`. The
+//! `common/` subdirectory keeps it from being interpreted as a
+//! standalone test file by cargo.
+
+#![allow(dead_code)] // fixtures may be used from only some test files
+
+use koala_std::collections::HashMap;
+use std::sync::Arc;
+
+use fontdue::{Font, FontSettings};
+use koala_browser::{Renderer, RendererFonts};
+
+/// Inter-Regular baked at compile time so the tests are independent of
+/// whatever fonts happen to be installed on the host. The four Inter
+/// variants live in `res/fonts/` (OFL-licensed, v4.1).
+pub const INTER_REGULAR_TTF: &[u8] = include_bytes!("../../../../res/fonts/Inter-Regular.ttf");
+
+/// A freshly loaded `Inter-Regular`.
+pub fn inter() -> Font {
+    Font::from_bytes(INTER_REGULAR_TTF, FontSettings::default())
+        .expect("Inter-Regular.ttf is a valid font file")
+}
+
+/// Build a `Renderer` whose only loaded font is `Inter-Regular`. The
+/// other three variants stay `None`, so tests should paint regular text
+/// only to keep the fallback chain out of the picture.
+pub fn make_renderer(width: u32, height: u32) -> Renderer {
+    let fonts = RendererFonts {
+        regular: Some(Arc::new(inter())),
+        bold: None,
+        italic: None,
+        bold_italic: None,
+        families: HashMap::new(),
+    };
+    Renderer::new_with_fonts(width, height, HashMap::new(), fonts)
+}
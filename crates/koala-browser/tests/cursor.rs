@@ -0,0 +1,35 @@
+//! Tests for the computed `cursor` a front end shows over each node.
+
+use koala_browser::css::Cursor;
+use koala_browser::{LoadedDocument, parse_html_string};
+use koala_dom::NodeId;
+
+fn element(doc: &LoadedDocument, id: &str) -> NodeId {
+    doc.dom
+        .borrow()
+        .get_element_by_id(id)
+        .expect("element exists")
+}
+
+#[test]
+fn links_get_pointer_from_ua_stylesheet() {
+    let doc = parse_html_string(
+        "<html><body><a id=\"link\" href=\"next.html\"><span id=\"inner\">Next</span></a>\
+         <a id=\"anchor\">No href</a><p id=\"para\">Text</p></body></html>",
+    );
+    assert_eq!(doc.cursor(element(&doc, "link")), Cursor::Pointer);
+    // "Inherited: yes"
+    assert_eq!(doc.cursor(element(&doc, "inner")), Cursor::Pointer);
+    assert_eq!(doc.cursor(element(&doc, "anchor")), Cursor::Auto);
+    assert_eq!(doc.cursor(element(&doc, "para")), Cursor::Auto);
+}
+
+#[test]
+fn author_cursor_overrides_ua_pointer() {
+    let doc = parse_html_string(
+        "<html><head><style>a { cursor: text; } p { cursor: url(hand.cur), not-allowed; }</style></head>\
+         <body><a id=\"link\" href=\"next.html\">Next</a><p id=\"para\">Text</p></body></html>",
+    );
+    assert_eq!(doc.cursor(element(&doc, "link")), Cursor::Text);
+    assert_eq!(doc.cursor(element(&doc, "para")), Cursor::NotAllowed);
+}
@@ -243,9 +243,9 @@ fn timer_callbacks_run_after_inline_script_returns() {
         </body></html>"#,
     );
 
-    let injected: Option<NodeId> = doc.dom.iter_all().find(|&id| {
-        doc.dom
-            .as_element(id)
+    let dom = doc.dom.borrow();
+    let injected: Option<NodeId> = dom.iter_all().find(|&id| {
+        dom.as_element(id)
             .and_then(|e| e.id())
             .is_some_and(|got| got == "timer-injected")
     });
@@ -279,9 +279,9 @@ fn dom_mutations_trigger_re_layout() {
 
     // Find the NodeId of the new element by walking the DOM. The
     // arena order means it's just whatever element has id="injected".
-    let injected_id: Option<NodeId> = doc.dom.iter_all().find(|&id| {
-        doc.dom
-            .as_element(id)
+    let dom = doc.dom.borrow();
+    let injected_id: Option<NodeId> = dom.iter_all().find(|&id| {
+        dom.as_element(id)
             .and_then(|e| e.id())
             .is_some_and(|got| got == "injected")
     });
@@ -322,3 +322,67 @@ fn script_can_mutate_attributes_and_observe_via_get_attribute() {
         </body></html>"#,
     );
 }
+
+#[test]
+fn location_of_a_file_document_is_a_file_url() {
+    let path = std::env::temp_dir().join(format!("koala-location-{}.html", std::process::id()));
+    std::fs::write(
+        &path,
+        "<body><script>document.body.setAttribute('data-url', \
+         location.protocol + location.pathname + location.hash)</script></body>",
+    )
+    .unwrap();
+
+    let result = koala_browser::load_document(&format!("{}#frag", path.display()));
+    let _ = std::fs::remove_file(&path);
+
+    let doc = result.unwrap();
+    let dom = doc.dom.borrow();
+    let body = dom
+        .iter_all()
+        .find(|&id| dom.as_element(id).is_some_and(|el| el.tag_name == "body"))
+        .unwrap();
+    assert_eq!(
+        dom.as_element(body)
+            .unwrap()
+            .attrs
+            .get("data-url")
+            .map(String::as_str),
+        Some(format!("file:{}#frag", path.display()).as_str()),
+    );
+}
+
+#[test]
+fn runtime_after_load_mutates_the_documents_dom() {
+    // `LoadedDocument::dom` is the handle the runtime shares, so a
+    // script run after load appends straight into the document's tree
+    // and a restyle picks the new node up.
+    let mut doc = parse_html_string("<!DOCTYPE html><html><body><p>one</p></body></html>");
+
+    let mut runtime = doc.js_runtime();
+    let _ = runtime
+        .execute(
+            "var p = document.createElement('p');\
+             p.setAttribute('id', 'late');\
+             document.body.appendChild(p);",
+        )
+        .expect("script runs");
+    assert!(runtime.take_dom_dirty());
+    drop(runtime);
+
+    let late = doc
+        .dom
+        .borrow()
+        .get_element_by_id("late")
+        .expect("appended element is in the shared DOM");
+    doc.restyle_and_relayout(
+        koala_browser::css::Rect {
+            x: 0.0,
+            y: 0.0,
+            width: 800.0,
+            height: 600.0,
+        },
+        &koala_browser::css::ApproximateFontMetrics,
+    );
+    assert!(doc.styles.contains_key(&late));
+}
@@ -0,0 +1,127 @@
+//! Tests for `font-family` fallback through [`FontProvider`].
+//!
+//! [§ 5 Font Matching Algorithm](https://www.w3.org/TR/css-fonts-4/#font-matching-algorithm)
+
+mod common;
+use common::inter;
+
+use fontdue::{Font, FontSettings};
+use koala_browser::FontProvider;
+use koala_css::{FontFamily, GenericFontFamily};
+
+const MONOSPACE: FontFamily = FontFamily::Generic(GenericFontFamily::Monospace);
+
+/// Whether `a` and `b` are the same loaded font.
+fn same_font(a: Option<&Font>, b: Option<&Font>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => std::ptr::eq(a, b),
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+#[test]
+fn test_font_for_picks_first_loaded_family() {
+    let mut provider = FontProvider::new(Some(inter()));
+    let default = provider.font_for(&[]).map(std::ptr::from_ref);
+    assert!(default.is_some());
+
+    let families = [
+        FontFamily::Named("No Such Font".to_string()),
+        FontFamily::Named("Fira Code".to_string()),
+        MONOSPACE,
+    ];
+    // Nothing in the list is loaded, so the default font is used.
+    assert_eq!(
+        provider.font_for(&families).map(std::ptr::from_ref),
+        default
+    );
+
+    provider.add_family(&MONOSPACE, inter());
+    let monospace = provider.font_for(&families).map(std::ptr::from_ref);
+    assert!(monospace.is_some() && monospace != default);
+
+    // An earlier family wins, and names match case-insensitively.
+    provider.add_family(&FontFamily::Named("Fira Code".to_string()), inter());
+    let fira = provider.font_for(&families).map(std::ptr::from_ref);
+    assert!(fira.is_some() && fira != monospace && fira != default);
+    assert_eq!(
+        provider
+            .font_for(&[FontFamily::Named("FIRA code".to_string())])
+            .map(std::ptr::from_ref),
+        fira
+    );
+}
+
+#[test]
+fn test_quoted_generic_keyword_names_a_family_not_the_generic() {
+    // [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+    //
+    // "Font family names that happen to be the same as a keyword value...
+    // must be quoted to prevent confusion with the keywords with the same
+    // names."
+    let mut provider = FontProvider::new(Some(inter()));
+    let default = provider.font_for(&[]).map(std::ptr::from_ref);
+    provider.add_family(&MONOSPACE, inter());
+    let monospace = provider.font_for(&[MONOSPACE]).map(std::ptr::from_ref);
+    assert!(monospace.is_some() && monospace != default);
+
+    // `font-family: "monospace"` names a family nobody loaded, so the
+    // default font is used rather than the generic monospace font.
+    let quoted = [FontFamily::Named("monospace".to_string())];
+    assert_eq!(provider.font_for(&quoted).map(std::ptr::from_ref), default);
+}
+
+#[test]
+fn test_monospace_family_selects_monospace_font_or_falls_back() {
+    // [§ 3.1.1 Generic font families](https://www.w3.org/TR/css-fonts-4/#generic-font-families)
+    //
+    // "The sole criterion of a monospace font is that all glyphs have the
+    // same fixed width."
+    let provider = FontProvider::load();
+    let default = provider.font_for(&[]);
+    let monospace = provider.font_for(&[FontFamily::Named("No Such Font".to_string()), MONOSPACE]);
+
+    if same_font(monospace, default) {
+        // No monospace font is installed: the default is used instead.
+        return;
+    }
+    let font = monospace.expect("a loaded monospace font");
+    let narrow = font.metrics('i', 16.0).advance_width;
+    let wide = font.metrics('W', 16.0).advance_width;
+    assert!(
+        (narrow - wide).abs() < f32::EPSILON,
+        "monospace glyphs should share one advance, got {narrow} and {wide}"
+    );
+}
+
+#[test]
+fn test_metrics_measure_with_the_selected_family() {
+    // Layout must measure a run with the font the renderer draws it with,
+    // or lines wrap at widths that don't match the painted text.
+    const INTER_BOLD_TTF: &[u8] = include_bytes!("../../../res/fonts/Inter-Bold.ttf");
+    let bold = Font::from_bytes(INTER_BOLD_TTF, FontSettings::default())
+        .expect("Inter-Bold.ttf is a valid font file");
+    let expected: f32 = "Wide text"
+        .chars()
+        .map(|ch| bold.metrics(ch, 16.0).advance_width)
+        .sum();
+    let mut provider = FontProvider::new(Some(inter()));
+    let wide = [FontFamily::Named("Wide".to_string())];
+    provider.add_family(&wide[0], bold);
+    let metrics = provider.metrics();
+
+    let measured = metrics.text_width("Wide text", 16.0, 0.0, &wide);
+    assert!(
+        (measured - expected).abs() < 0.01,
+        "expected {expected}, got {measured}"
+    );
+    assert!(measured > metrics.text_width("Wide text", 16.0, 0.0, &[]));
+    // An unloaded family falls back to the default font.
+    assert!(
+        (metrics.text_width("Wide text", 16.0, 0.0, &[MONOSPACE])
+            - metrics.text_width("Wide text", 16.0, 0.0, &[]))
+        .abs()
+            < f32::EPSILON
+    );
+}
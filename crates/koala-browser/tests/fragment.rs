@@ -0,0 +1,137 @@
+//! Tests for fragment (`#id`) navigation after load.
+
+use std::fs;
+
+use koala_browser::css::{ApproximateFontMetrics, Rect};
+use koala_browser::fragment::{IndicatedPart, select_indicated_part, url_fragment};
+use koala_browser::{LoadedDocument, load_document, parse_html_string};
+
+const VIEWPORT: Rect = Rect {
+    x: 0.0,
+    y: 0.0,
+    width: 800.0,
+    height: 600.0,
+};
+
+const PAGE: &str = "<html><head><style>\
+     body { margin: 0; }\
+     div { height: 100px; margin: 0; }\
+     </style></head>\
+     <body><div id=\"first\"></div><div id=\"second\"></div>\
+     <div id=\"caf%C3%A9\"></div><div id=\"café\"></div>\
+     <a name=\"anchor\"></a></body></html>";
+
+fn laid_out(source_path: &str) -> LoadedDocument {
+    let mut doc = parse_html_string(PAGE);
+    doc.source_path = source_path.to_string();
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    doc
+}
+
+#[test]
+fn fragment_resolves_to_element_layout_offset() {
+    assert_eq!(
+        laid_out("page.html#first").fragment_scroll_offset(),
+        Some(0.0)
+    );
+    assert_eq!(
+        laid_out("page.html#second").fragment_scroll_offset(),
+        Some(100.0)
+    );
+}
+
+#[test]
+fn fragment_matches_raw_id_before_percent_decoding() {
+    // The raw fragment is tried first, then its percent-decoded form.
+    assert_eq!(
+        laid_out("page.html#caf%C3%A9").fragment_scroll_offset(),
+        Some(200.0)
+    );
+    // No raw match, so the decoded "second" is used.
+    assert_eq!(
+        laid_out("page.html#sec%6Fnd").fragment_scroll_offset(),
+        Some(100.0)
+    );
+}
+
+#[test]
+fn fragment_falls_back_to_a_name() {
+    let doc = laid_out("page.html#anchor");
+    assert!(matches!(
+        select_indicated_part(&doc.dom.borrow(), "anchor"),
+        Some(IndicatedPart::Element(_))
+    ));
+}
+
+#[test]
+fn top_missing_and_absent_fragments_do_not_scroll() {
+    let doc = laid_out("page.html");
+    assert_eq!(
+        select_indicated_part(&doc.dom.borrow(), ""),
+        Some(IndicatedPart::TopOfDocument)
+    );
+    assert_eq!(
+        select_indicated_part(&doc.dom.borrow(), "TOP"),
+        Some(IndicatedPart::TopOfDocument)
+    );
+    assert_eq!(select_indicated_part(&doc.dom.borrow(), "missing"), None);
+
+    assert_eq!(doc.fragment_scroll_offset(), None);
+    assert_eq!(laid_out("page.html#").fragment_scroll_offset(), None);
+    assert_eq!(laid_out("page.html#top").fragment_scroll_offset(), None);
+    assert_eq!(laid_out("page.html#missing").fragment_scroll_offset(), None);
+}
+
+#[test]
+fn url_fragment_splits_on_first_hash() {
+    assert_eq!(url_fragment("page.html"), None);
+    assert_eq!(url_fragment("page.html#"), Some(""));
+    assert_eq!(url_fragment("page.html#a#b"), Some("a#b"));
+}
+
+#[test]
+fn load_document_strips_fragment_before_fetching() {
+    let path = std::env::temp_dir().join(format!("koala-fragment-{}.html", std::process::id()));
+    fs::write(&path, PAGE).unwrap();
+
+    let result = load_document(&format!("{}#second", path.display()));
+    let _ = fs::remove_file(&path);
+
+    let mut doc = result.expect("fragment should not be part of the fetched path");
+    assert!(doc.source_path.ends_with("#second"));
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    assert_eq!(doc.fragment_scroll_offset(), Some(100.0));
+}
+
+#[test]
+fn target_pseudo_class_matches_the_loaded_fragment() {
+    let path = std::env::temp_dir().join(format!("koala-target-{}.html", std::process::id()));
+    fs::write(
+        &path,
+        "<html><head><style>p { color: blue; } :target { color: red; }</style></head>\
+         <body><p id=\"intro\">a</p><p id=\"details\">b</p></body></html>",
+    )
+    .unwrap();
+
+    let result = load_document(&format!("{}#details", path.display()));
+    let _ = fs::remove_file(&path);
+    let doc = result.expect("document loads");
+
+    assert_eq!(doc.match_context.target.as_deref(), Some("details"));
+    let dom = doc.dom.borrow();
+    let red = |id: &str| {
+        let node = dom
+            .iter_all()
+            .find(|&node| {
+                dom.as_element(node)
+                    .is_some_and(|e| e.id().is_some_and(|v| v == id))
+            })
+            .expect("element with id");
+        doc.styles
+            .get(&node)
+            .and_then(|style| style.color.as_ref())
+            .is_some_and(|c| (c.r, c.g, c.b) == (255, 0, 0))
+    };
+    assert!(red("details"));
+    assert!(!red("intro"));
+}
@@ -0,0 +1,53 @@
+//! Tests for image type sniffing ahead of decoding.
+
+use koala_browser::image_loader::{ImageError, ImageLoaderPipeline, matches_image_type_pattern};
+
+/// A 1×1 opaque red PNG.
+const RED_PIXEL_PNG: &[u8] = &[
+    0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0x00, 0x00, 0x0D, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x02, 0x00, 0x00, 0x00, 0x90, 0x77, 0x53,
+    0xDE, 0x00, 0x00, 0x00, 0x0C, 0x49, 0x44, 0x41, 0x54, 0x08, 0xD7, 0x63, 0xF8, 0xCF, 0xC0, 0x00,
+    0x00, 0x03, 0x01, 0x01, 0x00, 0x18, 0xDD, 0x8D, 0xB0, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4E,
+    0x44, 0xAE, 0x42, 0x60, 0x82,
+];
+
+#[test]
+fn html_body_is_not_passed_to_the_decoder() {
+    let body = b"<!DOCTYPE html><html><body>404 Not Found</body></html>";
+    assert!(!matches_image_type_pattern(body));
+
+    let result =
+        ImageLoaderPipeline::new().decode(body, "photo.png", "https://example.com/photo.png");
+    assert!(
+        matches!(result, Err(ImageError::NotAnImage)),
+        "expected NotAnImage, got: {:?}",
+        result.err(),
+    );
+}
+
+#[test]
+fn known_signatures_are_recognized() {
+    assert!(matches_image_type_pattern(RED_PIXEL_PNG));
+    assert!(matches_image_type_pattern(b"GIF89a\x01\x00\x01\x00"));
+    assert!(matches_image_type_pattern(b"\xff\xd8\xff\xe0\x00\x10JFIF"));
+    assert!(matches_image_type_pattern(b"RIFF\x24\x00\x00\x00WEBPVP8 "));
+    assert!(!matches_image_type_pattern(b"RIFF\x24\x00\x00\x00WAVEfmt "));
+    assert!(!matches_image_type_pattern(b""));
+}
+
+#[test]
+fn png_still_decodes() {
+    let image = ImageLoaderPipeline::new()
+        .decode(RED_PIXEL_PNG, "red.png", "red.png")
+        .expect("a valid PNG should decode");
+    assert_eq!((image.width(), image.height()), (1, 1));
+}
+
+#[test]
+fn svg_is_not_subject_to_the_raster_sniff() {
+    let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" width="2" height="3"></svg>"#;
+    let image = ImageLoaderPipeline::new()
+        .decode(svg, "icon", "icon")
+        .expect("SVG should decode");
+    assert_eq!((image.width(), image.height()), (2, 3));
+}
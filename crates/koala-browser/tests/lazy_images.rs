@@ -0,0 +1,57 @@
+//! Tests for deferring `<img loading="lazy">` fetches until the image
+//! nears the viewport.
+
+use koala_browser::css::{ApproximateFontMetrics, Rect};
+use koala_browser::parse_html_string;
+
+/// A 1×1 red PNG.
+const PIXEL: &str = "data:image/png;base64,\
+     iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAIAAACQd1PeAAAADElEQVQI12P4z8AAAAMBAQAY3Y2wAAAAAElFTkSuQmCC";
+
+/// A 2×2 SVG, so the two eagerly loaded images have different URLs.
+const SQUARE: &str =
+    "data:image/svg+xml,%3Csvg xmlns='http://www.w3.org/2000/svg' width='2' height='2'/%3E";
+
+const fn viewport_at(y: f32) -> Rect {
+    Rect {
+        x: 0.0,
+        y,
+        width: 800.0,
+        height: 600.0,
+    }
+}
+
+#[test]
+fn lazy_image_below_the_fold_is_fetched_once_in_view() {
+    let mut doc = parse_html_string(&format!(
+        "<body style=\"margin: 0\">\
+         <div style=\"height: 5000px\"></div>\
+         <img loading=\"lazy\" src=\"{PIXEL}\" width=\"10\" height=\"10\">\
+         </body>"
+    ));
+    assert!(doc.images.is_empty(), "lazy image fetched during load");
+    assert_eq!(doc.deferred_images.len(), 1);
+
+    doc.restyle_and_relayout(viewport_at(0.0), &ApproximateFontMetrics);
+    assert!(!doc.fetch_deferred_images(viewport_at(0.0)));
+    assert!(
+        doc.images.is_empty(),
+        "image 5000px down fetched at the top"
+    );
+
+    // Scrolled to just above the image: within the root margin.
+    assert!(doc.fetch_deferred_images(viewport_at(4200.0)));
+    assert!(doc.images.contains_key(PIXEL));
+    assert!(doc.deferred_images.is_empty());
+}
+
+#[test]
+fn eager_and_default_images_are_fetched_during_load() {
+    let doc = parse_html_string(&format!(
+        "<div style=\"height: 5000px\"></div>\
+         <img loading=\"eager\" src=\"{PIXEL}\">\
+         <img src=\"{SQUARE}\">"
+    ));
+    assert_eq!(doc.images.len(), 2);
+    assert!(doc.deferred_images.is_empty());
+}
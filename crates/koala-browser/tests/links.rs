@@ -0,0 +1,101 @@
+//! Tests for link region collection from the laid-out document.
+
+use koala_browser::css::{ApproximateFontMetrics, FontMetrics, Rect};
+use koala_browser::parse_html_string;
+
+const VIEWPORT: Rect = Rect {
+    x: 0.0,
+    y: 0.0,
+    width: 800.0,
+    height: 600.0,
+};
+
+#[test]
+fn block_link_region_matches_border_box_with_absolute_href() {
+    let mut doc = parse_html_string(
+        "<html><head><style>\
+         body { margin: 0; }\
+         a { display: block; width: 100px; height: 20px; padding: 5px; border: 2px solid black; margin: 10px; }\
+         </style></head>\
+         <body><a href=\"next/page.html\">Next</a></body></html>",
+    );
+    doc.base_url = Some("https://example.com/docs/index.html".to_string());
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+
+    let regions = doc.link_regions();
+    assert_eq!(regions.len(), 1);
+    let region = &regions[0];
+    assert_eq!(region.href, "https://example.com/docs/next/page.html");
+    assert_eq!(
+        (
+            region.rect.x,
+            region.rect.y,
+            region.rect.width,
+            region.rect.height
+        ),
+        (10.0, 10.0, 114.0, 34.0)
+    );
+    assert!(region.contains(15.0, 15.0));
+    assert!(!region.contains(5.0, 5.0));
+}
+
+#[test]
+fn anchors_without_href_produce_no_region() {
+    let mut doc = parse_html_string(
+        "<html><head><style>a { display: block; height: 20px; }</style></head>\
+         <body><a name=\"top\">Top</a></body></html>",
+    );
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+
+    assert!(doc.link_regions().is_empty());
+}
+
+#[test]
+fn inline_block_link_region_is_reported() {
+    let mut doc = parse_html_string(
+        "<html><head><style>a { display: inline-block; width: 50px; height: 10px; }</style></head>\
+         <body><p>See <a href=\"/about\">about us</a>.</p></body></html>",
+    );
+    doc.base_url = Some("http://example.com/docs/index.html".to_string());
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+
+    let regions = doc.link_regions();
+    assert_eq!(regions.len(), 1);
+    assert_eq!(regions[0].href, "http://example.com/about");
+    assert_eq!(
+        (regions[0].rect.width, regions[0].rect.height),
+        (50.0, 10.0)
+    );
+}
+
+#[test]
+fn inline_link_in_paragraph_covers_its_text() {
+    let mut doc = parse_html_string(
+        "<html><head><style>body { margin: 0; } p { margin: 0; }</style></head>\
+         <body><p>Read <a href=\"/docs\"><em>the</em> docs</a> first.</p></body></html>",
+    );
+    doc.base_url = Some("http://example.com/index.html".to_string());
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+
+    let regions = doc.link_regions();
+    assert!(!regions.is_empty(), "an inline link gets regions");
+    assert!(regions.iter().all(|r| r.href == "http://example.com/docs"));
+    assert!(regions.windows(2).all(|w| w[0].node == w[1].node));
+
+    // The regions start after "Read " and stop before " first.", so a
+    // click on the surrounding text doesn't follow the link.
+    let left = regions.iter().map(|r| r.rect.x).fold(f32::MAX, f32::min);
+    let right = regions
+        .iter()
+        .map(|r| r.rect.x + r.rect.width)
+        .fold(0.0, f32::max);
+    let read = ApproximateFontMetrics.text_width("Read ", 16.0, 0.0, &[]);
+    let link = ApproximateFontMetrics.text_width("the docs", 16.0, 0.0, &[]);
+    assert!((left - read).abs() < 1.0, "link starts at {left}");
+    assert!((right - (read + link)).abs() < 1.0, "link ends at {right}");
+
+    let y = regions[0].rect.y + 1.0;
+    assert!(regions.iter().any(|r| r.contains(read + 2.0, y)));
+    assert!(!regions.iter().any(|r| r.contains(1.0, y)));
+    assert!(!regions.iter().any(|r| r.contains(read + link + 10.0, y)));
+}
@@ -0,0 +1,58 @@
+//! Tests for the `parse_document` API and its combined issue list.
+
+use koala_browser::css::StylesheetIssue;
+use koala_browser::{DocumentIssue, parse_document};
+
+#[test]
+fn html_parse_errors_propagate() {
+    let parsed = parse_document("<!DOCTYPE html><body><table> hi </table></body>", None);
+    assert!(
+        parsed.issues.iter().any(|issue| matches!(
+            issue,
+            DocumentIssue::Html(issue) if issue.message == "non-whitespace text in table context"
+        )),
+        "got {:?}",
+        parsed.issues
+    );
+    assert!(parsed.dom.body().is_some());
+}
+
+#[test]
+fn stylesheet_issues_follow_html_issues() {
+    let parsed = parse_document(
+        "<!DOCTYPE html><head>\
+         <link rel=stylesheet href=\"koala-missing-stylesheet.css\">\
+         <style>@layer base; p { color: red; }</style>\
+         </head><body><table>x</table><p>text</p></body>",
+        None,
+    );
+
+    let kinds: Vec<&str> = parsed
+        .issues
+        .iter()
+        .map(|issue| match issue {
+            DocumentIssue::Html(_) => "html",
+            DocumentIssue::Stylesheet(StylesheetIssue::LoadFailed { .. }) => "load",
+            DocumentIssue::Stylesheet(StylesheetIssue::Parse { .. }) => "css",
+        })
+        .collect();
+    assert_eq!(kinds, ["html", "load", "css"]);
+    assert!(
+        parsed.issues[1]
+            .to_string()
+            .contains("koala-missing-stylesheet.css")
+    );
+
+    // The rest of the inline sheet still applies.
+    let p = parsed
+        .dom
+        .descendants(parsed.dom.root())
+        .find(|&id| parsed.dom.as_element(id).is_some_and(|e| e.tag_name == "p"))
+        .expect("p element");
+    let color = parsed
+        .styles
+        .get(&p)
+        .and_then(|s| s.color.as_ref())
+        .expect("color");
+    assert_eq!((color.r, color.g, color.b), (255, 0, 0));
+}
@@ -0,0 +1,224 @@
+//! Tests for the `render_to_rgba` screenshot pipeline.
+
+use koala_browser::{load_system_font, parse_html_string, render_to_rgba};
+
+/// The RGBA value of the pixel at `(x, y)` in a `width`-wide buffer.
+fn pixel(buffer: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+    let i = ((y * width + x) * 4) as usize;
+    [buffer[i], buffer[i + 1], buffer[i + 2], buffer[i + 3]]
+}
+
+#[test]
+fn test_render_to_rgba_paints_colored_page() {
+    let doc = parse_html_string(
+        "<style>body { background-color: #ff0000; } \
+         div { width: 20px; height: 20px; background-color: #0000ff; }</style>\
+         <div></div>",
+    );
+    let (buffer, width, height) = render_to_rgba(&doc, 64, 48);
+
+    assert_eq!((width, height), (64, 48));
+    assert_eq!(buffer.len(), 64 * 48 * 4);
+    // The body background propagates to the canvas, and the div sits
+    // inside the body's default 8px margin.
+    assert_eq!(pixel(&buffer, width, 60, 44), [255, 0, 0, 255]);
+    assert_eq!(pixel(&buffer, width, 10, 10), [0, 0, 255, 255]);
+}
+
+#[test]
+fn test_render_to_rgba_leaves_document_unlaid() {
+    let doc = parse_html_string("<p>text</p>");
+    let before = doc
+        .layout_tree
+        .as_ref()
+        .map(|root| root.dimensions.content.width);
+    let (buffer, _, _) = render_to_rgba(&doc, 32, 32);
+    assert_eq!(buffer.len(), 32 * 32 * 4);
+    assert_eq!(
+        doc.layout_tree
+            .as_ref()
+            .map(|root| root.dimensions.content.width),
+        before
+    );
+}
+
+#[test]
+fn test_render_to_rgba_draws_heading_text() {
+    // Text is rasterized with a system font; without one there are no
+    // glyphs to look for.
+    if load_system_font().is_none() {
+        return;
+    }
+    let doc = parse_html_string(
+        "<style>body { background-color: #ffffff; color: #000000; }</style><h1>Hello</h1>",
+    );
+    let (buffer, width, _) = render_to_rgba(&doc, 200, 80);
+
+    // The heading's line box sits below the body's 8px margin and the
+    // h1's 0.67em top margin, at the left edge of the content box.
+    let inked = (8..80)
+        .flat_map(|x| (20..55).map(move |y| (x, y)))
+        .filter(|&(x, y)| pixel(&buffer, width, x, y) != [255, 255, 255, 255])
+        .count();
+    assert!(
+        inked > 50,
+        "expected glyph pixels in the heading, got {inked}"
+    );
+    // Past the end of the word the line stays background.
+    assert_eq!(pixel(&buffer, width, 190, 40), [255, 255, 255, 255]);
+}
+
+#[test]
+fn test_render_to_rgba_clips_overflow_per_axis() {
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    // [§ 3 overflow-x, overflow-y](https://www.w3.org/TR/css-overflow-3/#overflow-properties)
+    //
+    // The 40x40 child overflows its 20x20 parent on both axes; only the
+    // horizontal overflow is clipped.
+    let doc = parse_html_string(
+        "<style>body { margin: 0; background-color: #ffffff; } \
+         .clip { overflow-x: hidden; overflow-y: visible; width: 20px; height: 20px; } \
+         .child { width: 40px; height: 40px; background-color: #0000ff; }</style>\
+         <div class=\"clip\"><div class=\"child\"></div></div>",
+    );
+    let (buffer, width, _) = render_to_rgba(&doc, 64, 64);
+    assert_eq!(pixel(&buffer, width, 10, 10), BLUE);
+    // Below the box: vertical overflow stays visible.
+    assert_eq!(pixel(&buffer, width, 10, 30), BLUE);
+    // Right of the box: horizontal overflow is clipped.
+    assert_eq!(pixel(&buffer, width, 30, 10), WHITE);
+    assert_eq!(pixel(&buffer, width, 30, 30), WHITE);
+}
+
+#[test]
+fn test_render_to_rgba_clips_overflow_to_padding_box() {
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    // [§ 3 overflow-x, overflow-y](https://www.w3.org/TR/css-overflow-3/#overflow-properties)
+    //
+    // Every value other than 'visible' clips to the padding edge, so the
+    // 60x60 child never paints outside its parent's 20x20 box plus 5px
+    // of padding.
+    for overflow in ["hidden", "scroll", "auto"] {
+        let doc = parse_html_string(&format!(
+            "<style>body {{ margin: 0; background-color: #ffffff; }} \
+             .clip {{ overflow: {overflow}; width: 20px; height: 20px; padding: 5px; }} \
+             .child {{ width: 60px; height: 60px; background-color: #0000ff; }}</style>\
+             <div class=\"clip\"><div class=\"child\"></div></div>",
+        ));
+        let (buffer, width, _) = render_to_rgba(&doc, 64, 64);
+        assert_eq!(pixel(&buffer, width, 10, 10), BLUE, "overflow: {overflow}");
+        // The child reaches into the padding box up to its edge...
+        assert_eq!(pixel(&buffer, width, 29, 29), BLUE, "overflow: {overflow}");
+        // ...but none of it shows past the padding edge.
+        for (x, y) in [(30, 10), (10, 30), (30, 30), (50, 50)] {
+            assert_eq!(
+                pixel(&buffer, width, x, y),
+                WHITE,
+                "overflow: {overflow} at ({x}, {y})"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_render_to_rgba_clips_overflow_to_rounded_corners() {
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    // [§ 5.3 Corner Clipping](https://www.w3.org/TR/css-backgrounds-3/#corner-clipping)
+    //
+    // "Other effects that clip to the border or padding edge (such as
+    // 'overflow' other than 'visible') also must clip to the curve."
+    let doc = parse_html_string(
+        "<style>body { margin: 0; background-color: #ffffff; } \
+         .clip { overflow: hidden; width: 40px; height: 40px; border-radius: 20px; } \
+         .child { width: 40px; height: 40px; background-color: #0000ff; }</style>\
+         <div class=\"clip\"><div class=\"child\"></div></div>",
+    );
+    let (buffer, width, _) = render_to_rgba(&doc, 64, 64);
+    assert_eq!(pixel(&buffer, width, 20, 20), BLUE);
+    assert_eq!(pixel(&buffer, width, 20, 1), BLUE);
+    // The child's square corners fall outside the circle.
+    for (x, y) in [(1, 1), (38, 1), (1, 38), (38, 38)] {
+        assert_eq!(pixel(&buffer, width, x, y), WHITE, "corner at ({x}, {y})");
+    }
+}
+
+#[test]
+fn test_render_to_rgba_renders_document_from_parts() {
+    use koala_browser::LoadedDocument;
+    use koala_browser::css::{
+        ApproximateFontMetrics, Rect, Stylesheet, compute_styles,
+        ua_stylesheet::ua_stylesheet_minimal,
+    };
+    use koala_browser::dom::{AttributesMap, DomTree, ElementData, NodeId, NodeType};
+
+    fn element(tag: &str) -> NodeType {
+        NodeType::Element(ElementData {
+            tag_name: tag.to_string(),
+            attrs: AttributesMap::default(),
+        })
+    }
+
+    // html > body > div, built without going through the HTML parser.
+    let mut dom = DomTree::new();
+    let html = dom.alloc(element("html"));
+    let body = dom.alloc(element("body"));
+    let div = dom.alloc(element("div"));
+    dom.append_child(NodeId::ROOT, html);
+    dom.append_child(html, body);
+    dom.append_child(body, div);
+
+    // The minimal UA sheet gives the body no margin, so the div sits at
+    // the top-left corner.
+    let stylesheet = Stylesheet::from_str(
+        "body { background-color: #ffffff; } \
+         div { width: 20px; height: 20px; background-color: #00ff00; }",
+    );
+    let ua = ua_stylesheet_minimal();
+    let styles = compute_styles(&dom, ua, None, &stylesheet, 16.0);
+    let mut doc = LoadedDocument::from_parts(dom, ua, stylesheet, styles);
+    assert!(doc.layout_tree.is_some());
+    assert!(doc.tokens.is_empty() && doc.images.is_empty());
+
+    let (buffer, width, _) = render_to_rgba(&doc, 32, 32);
+    assert_eq!(pixel(&buffer, width, 2, 2), [0, 255, 0, 255]);
+    assert_eq!(pixel(&buffer, width, 25, 25), [255, 255, 255, 255]);
+
+    // Restyling cascades over the same UA sheet, so nothing moves.
+    let viewport = Rect {
+        x: 0.0,
+        y: 0.0,
+        width: 32.0,
+        height: 32.0,
+    };
+    doc.restyle_and_relayout(viewport, &ApproximateFontMetrics);
+    let (restyled, _, _) = render_to_rgba(&doc, 32, 32);
+    assert!(restyled == buffer, "restyling changed the rendering");
+}
+
+#[test]
+fn test_render_to_rgba_fetches_lazy_images_in_view() {
+    // [§ 2.5.7 Lazy loading attributes](https://html.spec.whatwg.org/multipage/urls-and-fetching.html#lazy-loading-attributes)
+    //
+    // A lazy image above the fold is fetched once the first layout shows
+    // it is near the viewport, and drawn in the same screenshot.
+    const PIXEL: &str = "data:image/png;base64,\
+         iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAIAAACQd1PeAAAADElEQVQI12P4z8AAAAMBAQAY3Y2wAAAAAElFTkSuQmCC";
+    let doc = parse_html_string(&format!(
+        "<body style=\"margin: 0; background-color: #ffffff\">\
+         <img loading=\"lazy\" src=\"{PIXEL}\" style=\"display: block; width: 16px; height: 16px\">\
+         </body>"
+    ));
+    assert_eq!(doc.deferred_images.len(), 1);
+
+    let (buffer, width, _) = render_to_rgba(&doc, 32, 32);
+    assert_eq!(pixel(&buffer, width, 8, 8), [255, 0, 0, 255]);
+    // The document's own images are left alone.
+    assert!(doc.images.is_empty());
+    assert_eq!(doc.deferred_images.len(), 1);
+}
@@ -19,32 +19,12 @@
 //! adds, so a passing test confirms the layout and render layers
 //! agree on text width.
 
-use koala_std::collections::HashMap;
-use std::sync::Arc;
+mod common;
+use common::make_renderer;
 
-use fontdue::{Font, FontSettings};
-use koala_browser::{Renderer, RendererFonts};
-use koala_css::{ColorValue, DisplayCommand, DisplayList, FontStyle, TextDecorationLine};
-
-/// Inter-Regular baked at compile time so the test is independent of
-/// whatever fonts happen to be installed on the host. The four Inter
-/// variants live in `res/fonts/` (OFL-licensed, v4.1).
-const INTER_REGULAR_TTF: &[u8] = include_bytes!("../../../res/fonts/Inter-Regular.ttf");
-
-/// Build a `Renderer` whose only loaded font is `Inter-Regular`. The
-/// other three variants stay `None`; the test only paints regular
-/// text so the fallback chain never fires.
-fn make_renderer(width: u32, height: u32) -> Renderer {
-    let font = Font::from_bytes(INTER_REGULAR_TTF, FontSettings::default())
-        .expect("Inter-Regular.ttf is a valid font file");
-    let fonts = RendererFonts {
-        regular: Some(Arc::new(font)),
-        bold: None,
-        italic: None,
-        bold_italic: None,
-    };
-    Renderer::new_with_fonts(width, height, HashMap::new(), fonts)
-}
+use koala_css::{
+    ColorValue, DisplayCommand, DisplayList, FontStyle, TextDecoration, TextDecorationLine,
+};
 
 /// Construct a `DisplayList` with exactly one underlined `DrawText`
 /// command at the given `letter_spacing`. All other text properties
@@ -71,10 +51,14 @@ fn underlined_drawtext(
         },
         font_weight: 400,
         font_style: FontStyle::Normal,
-        text_decoration: TextDecorationLine {
-            underline: true,
-            overline: false,
-            line_through: false,
+        font_family: Vec::new(),
+        text_decoration: TextDecoration {
+            line: TextDecorationLine {
+                underline: true,
+                overline: false,
+                line_through: false,
+            },
+            ..TextDecoration::default()
         },
         letter_spacing,
     });
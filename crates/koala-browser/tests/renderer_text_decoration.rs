@@ -0,0 +1,84 @@
+//! Render-layer verification for `text-decoration-style` and
+//! `text-decoration-color`.
+//!
+//! [CSS Text Decoration Module Level 3 § 2](https://www.w3.org/TR/css-text-decoration-3/#line-decoration)
+//!
+//! Paints an underlined `DrawText` whose decoration is dotted and red
+//! while the text itself is blue, then reads the underline band back from
+//! the buffer: it must be red, never blue, and broken into dots. The text
+//! is whitespace so that no glyph pixels overlap the underline.
+
+mod common;
+use common::make_renderer;
+
+use koala_css::{
+    ColorValue, DisplayCommand, DisplayList, FontStyle, TextDecoration, TextDecorationLine,
+    TextDecorationStyle,
+};
+
+const WIDTH: u32 = 400;
+
+/// The underline is painted at `y + font_size * 0.9` with a thickness of
+/// `max(1, font_size / 16)`: rows 38–39 for a 32px font at y = 10.
+const UNDERLINE_ROW: u32 = 39;
+
+/// The RGB value of every pixel in `row`.
+fn row_pixels(rgba: &[u8], row: u32) -> Vec<[u8; 3]> {
+    let start = (row * WIDTH * 4) as usize;
+    rgba[start..start + (WIDTH * 4) as usize]
+        .chunks_exact(4)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect()
+}
+
+#[test]
+fn test_dotted_red_underline_is_independent_of_text_color() {
+    let mut list = DisplayList::new();
+    list.push(DisplayCommand::DrawText {
+        x: 10.0,
+        y: 10.0,
+        text: "        ".to_string(),
+        font_size: 32.0,
+        color: ColorValue {
+            r: 0,
+            g: 0,
+            b: 255,
+            a: 255,
+        },
+        font_weight: 400,
+        font_style: FontStyle::Normal,
+        font_family: Vec::new(),
+        text_decoration: TextDecoration {
+            line: TextDecorationLine {
+                underline: true,
+                ..TextDecorationLine::default()
+            },
+            style: TextDecorationStyle::Dotted,
+            color: ColorValue {
+                r: 255,
+                g: 0,
+                b: 0,
+                a: 255,
+            },
+        },
+        letter_spacing: 0.0,
+    });
+    let mut renderer = make_renderer(WIDTH, 80);
+    renderer.render(&list);
+
+    let row = row_pixels(renderer.rgba_bytes(), UNDERLINE_ROW);
+    let red_columns: Vec<usize> = (0..row.len()).filter(|&x| row[x] == [255, 0, 0]).collect();
+    assert!(!red_columns.is_empty(), "the underline must be painted red");
+    assert!(
+        row.iter().all(|&[r, g, b]| !(b > 200 && r < 50 && g < 50)),
+        "the underline must not use the blue text color"
+    );
+
+    // Dotted: the painted span has unpainted gaps inside it.
+    let (start, end) = (red_columns[0], red_columns[red_columns.len() - 1]);
+    let gaps = (start..=end).filter(|&x| row[x] == [255, 255, 255]).count();
+    assert!(
+        gaps > 0,
+        "a dotted underline must leave gaps between its dots"
+    );
+}
@@ -0,0 +1,195 @@
+//! Tests for `LoadedDocument::restyle_and_relayout` after DOM mutation.
+
+use koala_browser::css::{ApproximateFontMetrics, Rect};
+use koala_browser::dom::NodeId;
+use koala_browser::{LoadedDocument, parse_html_string, parse_html_string_with_ua_stylesheet};
+
+const VIEWPORT: Rect = Rect {
+    x: 0.0,
+    y: 0.0,
+    width: 800.0,
+    height: 600.0,
+};
+
+fn find_element_by_id(doc: &LoadedDocument, id: &str) -> NodeId {
+    let dom = doc.dom.borrow();
+    dom.iter_all()
+        .find(|&node_id| {
+            dom.as_element(node_id)
+                .is_some_and(|e| e.id().is_some_and(|v| v == id))
+        })
+        .expect("element with id")
+}
+
+fn color_of(doc: &LoadedDocument, node_id: NodeId) -> (u8, u8, u8) {
+    let color = doc
+        .styles
+        .get(&node_id)
+        .expect("node is styled")
+        .color
+        .as_ref()
+        .expect("color is set");
+    (color.r, color.g, color.b)
+}
+
+#[test]
+fn class_change_updates_computed_color_after_restyle() {
+    let mut doc = parse_html_string(
+        "<html><head><style>div { color: blue; } .alert { color: red; }</style></head>\
+         <body><div id=\"target\">hi</div></body></html>",
+    );
+    let target = find_element_by_id(&doc, "target");
+    assert_eq!(color_of(&doc, target), (0, 0, 255));
+
+    let _ = doc
+        .dom
+        .borrow_mut()
+        .as_element_mut(target)
+        .expect("target is an element")
+        .attrs
+        .insert("class".to_string(), "alert".to_string());
+
+    // Styles are a snapshot until the caller asks for a restyle.
+    assert_eq!(color_of(&doc, target), (0, 0, 255));
+
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    assert_eq!(color_of(&doc, target), (255, 0, 0));
+}
+
+#[test]
+fn restyle_lays_out_appended_children() {
+    let mut doc = parse_html_string(
+        "<html><head><style>p { height: 40px; margin: 0; }</style></head>\
+         <body style=\"margin: 0\"><div id=\"list\"><p>one</p></div></body></html>",
+    );
+    let list = find_element_by_id(&doc, "list");
+    let mut dom = doc.dom.borrow_mut();
+    let extra = dom.alloc(koala_browser::dom::NodeType::Element(
+        koala_browser::dom::ElementData {
+            tag_name: "p".to_string(),
+            attrs: koala_browser::dom::AttributesMap::default(),
+        },
+    ));
+    dom.append_child(list, extra);
+    drop(dom);
+
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+
+    assert!(doc.styles.contains_key(&extra));
+    let root = doc.layout_tree.as_ref().expect("layout tree");
+    let html = &root.children[0];
+    let body = &html.children[0];
+    let div = &body.children[0];
+    assert_eq!(div.children.len(), 2);
+    assert!((div.dimensions.content.height - 80.0).abs() < 0.01);
+}
+
+#[test]
+fn hover_and_checked_state_reach_the_cascade() {
+    let mut doc = parse_html_string(
+        "<html><head><style>div { color: blue; } div:hover { color: red; } \
+         input { color: blue; } input:checked { color: green; }</style></head>\
+         <body><div id=\"box\"><span>hi</span></div>\
+         <input id=\"box2\" type=\"checkbox\"></body></html>",
+    );
+    let div = find_element_by_id(&doc, "box");
+    let input = find_element_by_id(&doc, "box2");
+    assert_eq!(color_of(&doc, div), (0, 0, 255));
+    assert_eq!(color_of(&doc, input), (0, 0, 255));
+
+    let _ = doc.match_context.hovered.insert(div);
+    let _ = doc.match_context.checked.insert(input, true);
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    assert_eq!(color_of(&doc, div), (255, 0, 0));
+    assert_eq!(color_of(&doc, input), (0, 128, 0));
+
+    doc.match_context.hovered.clear();
+    let _ = doc.match_context.checked.insert(input, false);
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    assert_eq!(color_of(&doc, div), (0, 0, 255));
+    assert_eq!(color_of(&doc, input), (0, 0, 255));
+}
+
+#[test]
+fn focus_state_reaches_the_cascade() {
+    let mut doc = parse_html_string(
+        "<html><head><style>input { color: blue; } input:focus { color: red; } \
+         input:focus-visible { color: green; }</style></head>\
+         <body><input id=\"first\"><input id=\"second\"></body></html>",
+    );
+    let first = find_element_by_id(&doc, "first");
+    let second = find_element_by_id(&doc, "second");
+    assert_eq!(color_of(&doc, first), (0, 0, 255));
+
+    // Focus from a pointer: `:focus` matches, `:focus-visible` does not.
+    doc.match_context.focused = Some(first);
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    assert_eq!(color_of(&doc, first), (255, 0, 0));
+    assert_eq!(color_of(&doc, second), (0, 0, 255));
+
+    // Focus moved with the keyboard.
+    doc.match_context.focused = Some(second);
+    doc.match_context.focus_visible = true;
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    assert_eq!(color_of(&doc, first), (0, 0, 255));
+    assert_eq!(color_of(&doc, second), (0, 128, 0));
+}
+
+#[test]
+fn scrolled_viewport_lays_out_content_visibility_auto_boxes_it_reaches() {
+    // [§ 4.4 Relevant To The User](https://www.w3.org/TR/css-contain-2/#relevant-to-the-user)
+    //
+    // The viewport's position is the scroll offset: it decides which
+    // `content-visibility: auto` boxes are on-screen without moving the
+    // initial containing block the page is laid out in.
+    let mut doc = parse_html_string(
+        "<html><head><style>.spacer { height: 3000px; } p { margin: 0; } \
+         .lazy { content-visibility: auto; contain-intrinsic-size: 100px 50px; }</style></head>\
+         <body style=\"margin: 0\"><div class=\"spacer\"></div>\
+         <div class=\"lazy\"><p>far away</p></div></body></html>",
+    );
+    let lazy_box = |doc: &LoadedDocument| {
+        let root = doc.layout_tree.as_ref().expect("layout tree");
+        root.children[0].children[0].children[1].clone()
+    };
+
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    let lazy = lazy_box(&doc);
+    assert!(lazy.children.is_empty(), "off-screen contents are skipped");
+    assert!((lazy.dimensions.content.y - 3000.0).abs() < 0.01);
+
+    let scrolled = Rect {
+        y: 2800.0,
+        ..VIEWPORT
+    };
+    doc.restyle_and_relayout(scrolled, &ApproximateFontMetrics);
+    let lazy = lazy_box(&doc);
+    assert_eq!(lazy.children.len(), 1, "the <p> is laid out once in view");
+    assert!((lazy.dimensions.content.y - 3000.0).abs() < 0.01);
+}
+
+#[test]
+fn chosen_ua_stylesheet_is_kept_across_restyles() {
+    use koala_browser::css::ua_stylesheet::{ua_stylesheet, ua_stylesheet_minimal};
+
+    let body_x = |doc: &LoadedDocument| {
+        let root = doc.layout_tree.as_ref().expect("layout tree");
+        root.children[0].children[0].dimensions.content.x
+    };
+    let mut doc = parse_html_string_with_ua_stylesheet("<p>hi</p>", ua_stylesheet_minimal());
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    assert!(
+        body_x(&doc).abs() < 0.01,
+        "the minimal sheet has no body margin"
+    );
+
+    doc.set_user_stylesheet(koala_browser::css::Stylesheet::from_str(
+        "p { color: red; }",
+    ));
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    assert!(body_x(&doc).abs() < 0.01);
+
+    doc.set_ua_stylesheet(ua_stylesheet());
+    doc.restyle_and_relayout(VIEWPORT, &ApproximateFontMetrics);
+    assert!((body_x(&doc) - 8.0).abs() < 0.01);
+}
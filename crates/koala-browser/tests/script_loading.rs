@@ -50,7 +50,7 @@ fn data_url_script_runs_and_mutates_the_dom() {
     let doc = parse_html_string(html);
     assert!(js_errors(&doc).is_empty(), "unexpected issues: {:?}", doc.parse_issues);
 
-    let marker = find_marker_attr(&doc.dom, "data-loaded");
+    let marker = find_marker_attr(&doc.dom.borrow(), "data-loaded");
     assert_eq!(
         marker.as_deref(),
         Some("yes"),
@@ -77,7 +77,7 @@ fn inline_and_external_scripts_execute_in_document_order() {
     let doc = parse_html_string(html);
     assert!(js_errors(&doc).is_empty(), "unexpected issues: {:?}", doc.parse_issues);
 
-    let order = find_marker_attr(&doc.dom, "data-order");
+    let order = find_marker_attr(&doc.dom.borrow(), "data-order");
     assert_eq!(order.as_deref(), Some("a,b,c"));
 }
 
@@ -112,7 +112,7 @@ fn local_file_script_loads_and_executes() {
 
     assert!(js_errors(&doc).is_empty(), "unexpected issues: {:?}", doc.parse_issues);
     assert_eq!(
-        find_marker_attr(&doc.dom, "data-fs").as_deref(),
+        find_marker_attr(&doc.dom.borrow(), "data-fs").as_deref(),
         Some("loaded"),
     );
 }
@@ -145,7 +145,7 @@ fn missing_src_records_parse_issue_but_does_not_abort() {
 
     // … but the trailing data: URL script still mutated the DOM.
     assert_eq!(
-        find_marker_attr(&doc.dom, "data-after").as_deref(),
+        find_marker_attr(&doc.dom.borrow(), "data-after").as_deref(),
         Some("ok"),
     );
 }
@@ -161,9 +161,9 @@ fn empty_src_is_ignored() {
         </body></html>"#;
     let doc = parse_html_string(html);
     assert!(js_errors(&doc).is_empty(), "unexpected issues: {:?}", doc.parse_issues);
-    assert!(find_by_id(&doc.dom, "root").is_some());
+    assert!(find_by_id(&doc.dom.borrow(), "root").is_some());
     assert_eq!(
-        find_marker_attr(&doc.dom, "data-after").as_deref(),
+        find_marker_attr(&doc.dom.borrow(), "data-after").as_deref(),
         Some("ok"),
     );
 }
@@ -0,0 +1,196 @@
+//! Tests for `srcset`/`sizes` parsing and image source selection.
+
+#![allow(clippy::float_cmp)]
+
+use koala_browser::srcset::{
+    Descriptor, ImageCandidate, SourceSelectionContext, parse_sizes, parse_srcset,
+    select_image_source,
+};
+
+const fn context(viewport_width: f32, device_pixel_ratio: f32) -> SourceSelectionContext {
+    SourceSelectionContext {
+        viewport_width,
+        device_pixel_ratio,
+    }
+}
+
+#[test]
+fn parses_density_and_width_descriptors() {
+    assert_eq!(
+        parse_srcset("a.png, b.png 2x,c.png 480w"),
+        vec![
+            ImageCandidate {
+                url: "a.png".to_string(),
+                descriptor: None,
+            },
+            ImageCandidate {
+                url: "b.png".to_string(),
+                descriptor: Some(Descriptor::Density(2.0)),
+            },
+            ImageCandidate {
+                url: "c.png".to_string(),
+                descriptor: Some(Descriptor::Width(480)),
+            },
+        ]
+    );
+}
+
+#[test]
+fn drops_candidates_with_invalid_descriptors() {
+    let candidates = parse_srcset("bad.png 0w, worse.png 1x 2x, good.png 1.5x");
+    assert_eq!(
+        candidates,
+        vec![ImageCandidate {
+            url: "good.png".to_string(),
+            descriptor: Some(Descriptor::Density(1.5)),
+        }]
+    );
+}
+
+#[test]
+fn height_descriptor_requires_a_width_descriptor() {
+    // [§ 4.8.4.3.10](https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute)
+    //
+    // "If future-compat-h is not absent and width is absent, let error be
+    // yes."
+    let candidates =
+        parse_srcset("a.png 100h, b.png 2x 50h, c.png foo-h, d.png 0h 10w, e.png 10w 20h");
+    assert_eq!(
+        candidates,
+        vec![ImageCandidate {
+            url: "e.png".to_string(),
+            descriptor: Some(Descriptor::Width(10)),
+        }]
+    );
+}
+
+#[test]
+fn url_with_trailing_comma_has_no_descriptors() {
+    let candidates = parse_srcset("a.png,, b.png 2x");
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0].url, "a.png");
+    assert_eq!(candidates[0].descriptor, None);
+    assert_eq!(candidates[1].descriptor, Some(Descriptor::Density(2.0)));
+}
+
+#[test]
+fn commas_inside_a_url_are_kept() {
+    // The URL is everything up to whitespace, so an embedded comma does
+    // not split candidates.
+    let candidates = parse_srcset("a.png,b.png 2x");
+    assert_eq!(
+        candidates,
+        vec![ImageCandidate {
+            url: "a.png,b.png".to_string(),
+            descriptor: Some(Descriptor::Density(2.0)),
+        }]
+    );
+}
+
+#[test]
+fn density_matching_candidate_beats_src() {
+    let srcset = Some("small.png 1x, large.png 2x");
+    assert_eq!(
+        select_image_source(Some("base.png"), srcset, None, context(800.0, 2.0)).as_deref(),
+        Some("large.png")
+    );
+    assert_eq!(
+        select_image_source(Some("base.png"), srcset, None, context(800.0, 1.0)).as_deref(),
+        Some("small.png")
+    );
+}
+
+#[test]
+fn src_is_the_implicit_1x_candidate() {
+    let srcset = Some("retina.png 2x");
+    assert_eq!(
+        select_image_source(Some("base.png"), srcset, None, context(800.0, 1.0)).as_deref(),
+        Some("base.png")
+    );
+    assert_eq!(
+        select_image_source(Some("base.png"), srcset, None, context(800.0, 2.0)).as_deref(),
+        Some("retina.png")
+    );
+}
+
+#[test]
+fn falls_back_to_densest_candidate() {
+    assert_eq!(
+        select_image_source(
+            None,
+            Some("a.png 1x, b.png 1.5x"),
+            None,
+            context(800.0, 3.0)
+        )
+        .as_deref(),
+        Some("b.png")
+    );
+}
+
+#[test]
+fn width_descriptors_use_sizes() {
+    let srcset = Some("s.png 400w, m.png 800w, l.png 1600w");
+    // 400px slot at 1x: 400w is exactly 1x.
+    assert_eq!(
+        select_image_source(None, srcset, Some("400px"), context(1000.0, 1.0)).as_deref(),
+        Some("s.png")
+    );
+    // Default sizes is 100vw = 1000px: 1600w is the first with density >= 1.
+    assert_eq!(
+        select_image_source(Some("base.png"), srcset, None, context(1000.0, 1.0)).as_deref(),
+        Some("l.png")
+    );
+}
+
+#[test]
+fn sizes_media_conditions() {
+    let sizes = "(max-width: 600px) 100vw, (min-width: 601px) 50vw";
+    assert_eq!(parse_sizes(sizes, 500.0), 500.0);
+    assert_eq!(parse_sizes(sizes, 1000.0), 500.0);
+    assert_eq!(
+        parse_sizes("(orientation: portrait) 10px, 20em", 1000.0),
+        320.0
+    );
+    assert_eq!(parse_sizes("garbage", 700.0), 700.0);
+}
+
+#[test]
+fn no_usable_url() {
+    assert_eq!(
+        select_image_source(None, None, None, context(800.0, 1.0)),
+        None
+    );
+    assert_eq!(
+        select_image_source(Some("  "), Some(""), None, context(800.0, 1.0)),
+        None
+    );
+}
+
+fn find_replaced(layout_box: &koala_browser::css::LayoutBox) -> Option<&str> {
+    if layout_box.is_replaced {
+        return layout_box.replaced_src.as_deref();
+    }
+    layout_box.children.iter().find_map(find_replaced)
+}
+
+#[test]
+fn load_records_current_src_and_paints_it() {
+    let one_x = "data:image/svg+xml,%3Csvg%20xmlns='http://www.w3.org/2000/svg'%20width='10'%20height='20'/%3E";
+    let two_x = "data:image/svg+xml,%3Csvg%20xmlns='http://www.w3.org/2000/svg'%20width='30'%20height='40'/%3E";
+    let html = format!(
+        "<html><body><img id=\"pic\" src=\"missing.png\" srcset=\"{one_x} 1x, {two_x} 2x\"></body></html>"
+    );
+    let doc = koala_browser::parse_html_string(&html);
+
+    let dom = doc.dom.borrow();
+    let img = dom
+        .iter_all()
+        .find(|&id| dom.as_element(id).is_some_and(|e| e.tag_name == "img"))
+        .expect("img element");
+    assert_eq!(doc.current_src.get(&img).map(String::as_str), Some(one_x));
+    assert!(doc.images.contains_key(one_x));
+    assert!(!doc.images.contains_key("missing.png"));
+
+    let root = doc.layout_tree.as_ref().expect("layout tree");
+    assert_eq!(find_replaced(root), Some(one_x));
+}
@@ -21,10 +21,16 @@
 //! sender (defaulting to [`DefaultSender`] when none is installed), preserved
 //! so existing call sites don't need to know about the trait.
 //!
+//! A maximum response size can be installed the same way with
+//! [`install_fetch_limit`]. [`fetch_bytes`] rejects any body over the
+//! limit, and [`DefaultSender`] stops reading a response as soon as it
+//! goes over, so an oversized resource is never held in memory whole.
+//!
 //! TODO: Implement proper Fetch Standard (<https://fetch.spec.whatwg.org/>).
 use base64::Engine;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use koala_std::collections::HashMap;
+use std::io::Read;
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -69,7 +75,7 @@ pub enum FetchError {
         url: String,
         /// The underlying I/O or decoding error.
         #[source]
-        source: reqwest::Error,
+        source: std::io::Error,
     },
 
     /// The data URL is malformed (e.g. missing the `,` separator).
@@ -93,6 +99,16 @@ pub enum FetchError {
         #[source]
         source: std::io::Error,
     },
+
+    /// The response body is larger than the limit installed with
+    /// [`install_fetch_limit`].
+    #[error("response from '{url}' exceeds the {limit}-byte fetch limit")]
+    TooLarge {
+        /// The URL that was requested.
+        url: String,
+        /// The maximum number of bytes a response may have.
+        limit: u64,
+    },
 }
 
 /// A parsed `data:` URL that can be decoded into raw bytes.
@@ -157,8 +173,9 @@ impl DataURL {
     }
 }
 
-/// Lenient percent-decode of `s` per RFC 3986 § 2.1. `%XX`
-/// triples where `XX` is two hex digits decode to a single byte;
+/// Lenient percent-decode of `s` per RFC 3986 § 2.1.
+///
+/// `%XX` triples where `XX` is two hex digits decode to a single byte;
 /// every other byte is copied through unchanged, including stray
 /// `%` characters whose two-character tail isn't valid hex.
 ///
@@ -168,7 +185,8 @@ impl DataURL {
 /// `application/x-www-form-urlencoded` rule: `+` is left as-is
 /// rather than turned into a space, matching RFC 2397's
 /// reference to RFC 2396 percent-encoding.
-fn percent_decode(s: &str) -> Vec<u8> {
+#[must_use]
+pub fn percent_decode(s: &str) -> Vec<u8> {
     let bytes = s.as_bytes();
     let mut out = Vec::with_capacity(bytes.len());
     let mut i = 0;
@@ -224,7 +242,8 @@ pub trait RequestSender {
 ///
 /// - `data:` → decode in-process via [`DataURL`].
 /// - `http://` / `https://` → blocking HTTP GET via `reqwest`, honoring
-///   the WPT [`hosts`](crate::hosts) overrides.
+///   the WPT [`hosts`](crate::hosts) overrides. Compressed responses are
+///   decoded transparently (see [`http_fetch`]).
 /// - anything else → treated as a filesystem path (with an optional
 ///   `file://` prefix stripped).
 ///
@@ -241,7 +260,11 @@ impl RequestSender for DefaultSender {
             return http_fetch(url);
         }
         let path = url.strip_prefix("file://").unwrap_or(url);
-        std::fs::read(path).map_err(|e| FetchError::LocalRead {
+        let file = std::fs::File::open(path).map_err(|e| FetchError::LocalRead {
+            path: url.to_string(),
+            source: e,
+        })?;
+        read_limited(file, url, |e| FetchError::LocalRead {
             path: url.to_string(),
             source: e,
         })
@@ -322,6 +345,59 @@ impl Drop for SenderGuard {
     }
 }
 
+thread_local! {
+    /// Thread-local maximum response size in bytes. `None` means
+    /// unlimited. Set via [`install_fetch_limit`].
+    static FETCH_LIMIT: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Limit every response fetched on this thread to `max_bytes`, until the
+/// returned [`FetchLimitGuard`] is dropped. Guards nest like
+/// [`SenderGuard`]s.
+///
+/// [`fetch_bytes`] and [`fetch_text`] fail with [`FetchError::TooLarge`]
+/// for a larger body, whichever sender produced it.
+#[must_use = "the guard restores the previous limit on drop"]
+pub fn install_fetch_limit(max_bytes: u64) -> FetchLimitGuard {
+    let previous = FETCH_LIMIT.replace(Some(max_bytes));
+    FetchLimitGuard { previous }
+}
+
+/// RAII guard returned by [`install_fetch_limit`]. Restores the previous
+/// limit on drop.
+pub struct FetchLimitGuard {
+    previous: Option<u64>,
+}
+
+impl Drop for FetchLimitGuard {
+    fn drop(&mut self) {
+        FETCH_LIMIT.set(self.previous);
+    }
+}
+
+/// Read `reader` to the end, giving up with [`FetchError::TooLarge`] as
+/// soon as more bytes than the active fetch limit arrive. Read errors are
+/// turned into a [`FetchError`] by `read_error`.
+fn read_limited(
+    reader: impl Read,
+    url: &str,
+    read_error: impl FnOnce(std::io::Error) -> FetchError,
+) -> Result<Vec<u8>, FetchError> {
+    let limit = FETCH_LIMIT.get();
+    let mut body = Vec::new();
+    let _ = reader
+        .take(limit.map_or(u64::MAX, |limit| limit.saturating_add(1)))
+        .read_to_end(&mut body)
+        .map_err(read_error)?;
+    match limit {
+        Some(limit) if body.len() as u64 > limit => Err(FetchError::TooLarge {
+            url: url.to_string(),
+            limit,
+        }),
+        _ => Ok(body),
+    }
+}
+
 /// Run `f` with a reference to the currently-active sender — the one
 /// installed by [`install_sender`] on this thread, falling back to
 /// [`DefaultSender`] if none is installed.
@@ -334,6 +410,16 @@ fn with_active_sender<R>(f: impl FnOnce(&dyn RequestSender) -> R) -> R {
 
 /// Shared HTTP body fetch used by [`DefaultSender`]. Separated so the
 /// trait impl reads as a three-arm scheme dispatch.
+///
+/// [RFC 9110 § 12.5.3 Accept-Encoding](https://www.rfc-editor.org/rfc/rfc9110#section-12.5.3)
+///
+/// The client is built with reqwest's `gzip`, `deflate` and `brotli`
+/// features, so every request advertises `Accept-Encoding: gzip, deflate,
+/// br` and a response with a matching `Content-Encoding` is decompressed
+/// before its bytes are returned. Callers always see the decoded body.
+///
+/// Under a fetch limit, a `Content-Length` over it fails the fetch before
+/// the body is read, and reading stops once the decoded body exceeds it.
 fn http_fetch(url: &str) -> Result<Vec<u8>, FetchError> {
     let client = crate::hosts::apply(reqwest::blocking::Client::builder().timeout(TIMEOUT))
         .build()
@@ -355,13 +441,19 @@ fn http_fetch(url: &str) -> Result<Vec<u8>, FetchError> {
         });
     }
 
-    response
-        .bytes()
-        .map(|b| b.to_vec())
-        .map_err(|e| FetchError::ResponseBody {
+    if let (Some(limit), Some(length)) = (FETCH_LIMIT.get(), response.content_length())
+        && length > limit
+    {
+        return Err(FetchError::TooLarge {
             url: url.to_string(),
-            source: e,
-        })
+            limit,
+        });
+    }
+
+    read_limited(response, url, |e| FetchError::ResponseBody {
+        url: url.to_string(),
+        source: e,
+    })
 }
 
 /// Fetch the resource at `url` and return its body as text. Delegates
@@ -383,7 +475,14 @@ pub fn fetch_text(url: &str) -> Result<String, FetchError> {
 ///
 /// Returns a [`FetchError`] if the underlying fetch fails.
 pub fn fetch_bytes(url: &str) -> Result<Vec<u8>, FetchError> {
-    with_active_sender(|s| s.fetch(url))
+    let body = with_active_sender(|s| s.fetch(url))?;
+    match FETCH_LIMIT.get() {
+        Some(limit) if body.len() as u64 > limit => Err(FetchError::TooLarge {
+            url: url.to_string(),
+            limit,
+        }),
+        _ => Ok(body),
+    }
 }
 
 /// Decode a `data:` URL directly, bypassing the active sender. Kept as
@@ -472,3 +571,122 @@ mod data_url_tests {
         );
     }
 }
+
+#[cfg(test)]
+mod compression_tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    const CSS: &str = "body { color: #123456; }\np::before { content: \"\u{2713}\"; }\n";
+
+    /// Serve a single HTTP response with the given `Content-Encoding` and
+    /// body from a local socket. Returns the URL to fetch and a receiver
+    /// for the request's `Accept-Encoding` header.
+    fn serve_once(encoding: &'static str, body: Vec<u8>) -> (String, mpsc::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind local listener");
+        let url = format!("http://{}/style.css", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+        let _ = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("accept connection");
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut accept_encoding = String::new();
+            loop {
+                let mut line = String::new();
+                let _ = reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("accept-encoding")
+                {
+                    accept_encoding = value.trim().to_string();
+                }
+            }
+            let _ = sender.send(accept_encoding);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/css\r\nContent-Encoding: {encoding}\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(&body).unwrap();
+        });
+        (url, receiver)
+    }
+
+    #[test]
+    fn gzip_response_is_decoded() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(CSS.as_bytes()).unwrap();
+        let (url, accept_encoding) = serve_once("gzip", encoder.finish().unwrap());
+
+        assert_eq!(fetch_text(&url).expect("fetch should succeed"), CSS);
+        let advertised = accept_encoding.recv().unwrap();
+        for encoding in ["gzip", "deflate", "br"] {
+            assert!(
+                advertised.contains(encoding),
+                "Accept-Encoding should list {encoding}, got: {advertised}",
+            );
+        }
+    }
+
+    #[test]
+    fn deflate_response_is_decoded() {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(CSS.as_bytes()).unwrap();
+        let (url, _) = serve_once("deflate", encoder.finish().unwrap());
+
+        assert_eq!(
+            fetch_bytes(&url).expect("fetch should succeed"),
+            CSS.as_bytes()
+        );
+    }
+}
+
+#[cfg(test)]
+mod fetch_limit_tests {
+    use super::*;
+
+    /// `data:,` followed by `len` bytes of payload.
+    fn data_url(len: usize) -> String {
+        format!("data:,{}", "x".repeat(len))
+    }
+
+    #[test]
+    fn response_over_the_limit_is_rejected() {
+        let _limit = install_fetch_limit(16);
+        let err = fetch_bytes(&data_url(17)).unwrap_err();
+        assert!(
+            matches!(err, FetchError::TooLarge { limit: 16, .. }),
+            "expected TooLarge, got: {err}",
+        );
+    }
+
+    #[test]
+    fn response_at_the_limit_is_returned() {
+        let _limit = install_fetch_limit(16);
+        assert_eq!(fetch_bytes(&data_url(16)).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn local_file_read_stops_at_the_limit() {
+        let path = std::env::temp_dir().join(format!("koala-fetch-limit-{}", std::process::id()));
+        std::fs::write(&path, [0_u8; 64]).unwrap();
+        let url = path.to_string_lossy().into_owned();
+
+        let err = {
+            let _limit = install_fetch_limit(32);
+            DefaultSender.fetch(&url).unwrap_err()
+        };
+        assert!(matches!(err, FetchError::TooLarge { limit: 32, .. }));
+
+        // Dropping the guard lifts the limit again.
+        assert_eq!(fetch_bytes(&url).unwrap().len(), 64);
+        let _ = std::fs::remove_file(path);
+    }
+}
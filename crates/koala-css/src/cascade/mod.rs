@@ -3,13 +3,17 @@
 //! This module implements style computation per
 //! [CSS Cascading and Inheritance Level 4](https://www.w3.org/TR/css-cascade-4/).
 
+use std::fmt::Write as _;
+
 use koala_std::collections::HashMap;
 
-use crate::parser::{Rule, StyleRule, Stylesheet};
-use crate::selector::{ParsedSelector, Specificity, parse_selector};
-use crate::style::ComputedStyle;
+use crate::media::{MediaType, media_query_list_matches};
+use crate::parser::{Declaration, Rule, StyleRule, Stylesheet};
+use crate::selector::{MatchContext, ParsedSelector, PseudoElement, Specificity, parse_selector};
+use crate::style::{ComputedStyle, LengthValue};
+use crate::supports::supports_condition_matches;
 use koala_common::warning::warn_once;
-use koala_dom::{DomTree, NodeId, NodeType};
+use koala_dom::{DomTree, ElementData, NodeId, NodeType};
 
 /// [§ 6.1 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
 ///
@@ -21,16 +25,20 @@ use koala_dom::{DomTree, NodeId, NodeType};
 /// Origin and Importance > Context > Element-Attached Styles >
 /// Specificity > Order of Appearance"
 ///
-/// `UserAgent` (0) < `Author` (1): author rules always override UA rules
-/// regardless of specificity.
+/// `UserAgent` (0) < `User` (1) < `Author` (2): for normal declarations a
+/// later origin always overrides an earlier one regardless of specificity.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum CascadeOrigin {
     /// [§ 6.1](https://www.w3.org/TR/css-cascade-4/#cascade-origin-ua)
     /// "The user agent's default styles."
     UserAgent = 0,
+    /// [§ 6.1](https://www.w3.org/TR/css-cascade-4/#cascade-origin-user)
+    /// "The user may be able to specify style information for a particular
+    /// document."
+    User = 1,
     /// [§ 6.1](https://www.w3.org/TR/css-cascade-4/#cascade-origin-author)
     /// "The author specifies style sheets for a source document."
-    Author = 1,
+    Author = 2,
 }
 
 /// [§ 6 Cascading](https://www.w3.org/TR/css-cascade-4/#cascading)
@@ -62,11 +70,11 @@ struct ParsedRule<'a> {
 /// Each valid selector in a rule produces a separate `ParsedRule` entry
 /// so that matching checks every selector independently.
 fn parse_stylesheet_rules<'a>(
-    stylesheet: &'a Stylesheet,
+    rules: &'a [Rule],
     origin: CascadeOrigin,
     out: &mut Vec<ParsedRule<'a>>,
 ) {
-    for rule in &stylesheet.rules {
+    for rule in rules {
         match rule {
             Rule::Style(style_rule) => {
                 let mut any_parsed = false;
@@ -98,7 +106,27 @@ fn parse_stylesheet_rules<'a>(
                     );
                 }
             }
-            Rule::At(_) => {} // Skip at-rules for MVP
+            // [§ 2 Processing of conditional group rules](https://www.w3.org/TR/css-conditional-3/#processing)
+            //
+            // Rules inside a matching `@media` rule apply as if they were
+            // written in its place. Rendering is for a screen; other media
+            // are handled by resolving the stylesheet with
+            // `Stylesheet::for_media` before the cascade.
+            Rule::At(at_rule) if at_rule.name.eq_ignore_ascii_case("media") => {
+                if media_query_list_matches(&at_rule.prelude, MediaType::Screen) {
+                    parse_stylesheet_rules(&at_rule.rules, origin, out);
+                }
+            }
+            // [§ 6 Feature queries](https://www.w3.org/TR/css-conditional-3/#at-supports)
+            //
+            // Rules inside `@supports` apply only when its condition is
+            // true for this engine.
+            Rule::At(at_rule) if at_rule.name.eq_ignore_ascii_case("supports") => {
+                if supports_condition_matches(&at_rule.prelude) {
+                    parse_stylesheet_rules(&at_rule.rules, origin, out);
+                }
+            }
+            Rule::At(_) => {} // Skip other at-rules for MVP
         }
     }
 }
@@ -108,32 +136,105 @@ fn parse_stylesheet_rules<'a>(
 /// "The cascade takes an unordered list of declared values for a given property
 /// on a given element, sorts them by their declaration's precedence..."
 ///
-/// Compute styles for the entire DOM tree given UA and author stylesheets.
-/// Returns a map from `NodeId` to computed style.
+/// Compute styles for the entire DOM tree given UA, optional user, and
+/// author stylesheets. Returns a map from `NodeId` to computed style.
 ///
 /// [§ 6.1 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
 ///
-/// UA rules are always overridden by author rules (origin beats specificity).
+/// Normal UA rules are overridden by user rules, which are overridden by
+/// author rules (origin beats specificity); `!important` reverses that, so
+/// an important UA declaration beats every user and author declaration.
+///
+/// The UA stylesheet is normally [`ua_stylesheet`]; pass
+/// [`ua_stylesheet_minimal`] to style a document with only the `display`
+/// defaults, or any other sheet for a custom UA profile.
+///
+/// `initial_font_size` is the initial value of `font-size` in pixels — what
+/// `medium` means to the embedder ([`DEFAULT_FONT_SIZE_PX`] in browsers by
+/// default). It is the root element's font size unless a rule overrides it,
+/// and therefore what `rem` resolves to by default.
+///
+/// `@media` rules are evaluated for a screen. To style for another medium,
+/// pass stylesheets resolved with [`Stylesheet::for_media`].
+///
+/// Nothing is hovered, focused or targeted; use
+/// [`compute_styles_with_context`] to match those pseudo-classes.
+///
+/// [`DEFAULT_FONT_SIZE_PX`]: crate::DEFAULT_FONT_SIZE_PX
+/// [`ua_stylesheet`]: crate::ua_stylesheet::ua_stylesheet
+/// [`ua_stylesheet_minimal`]: crate::ua_stylesheet::ua_stylesheet_minimal
 #[must_use]
 #[allow(clippy::implicit_hasher)]
 pub fn compute_styles(
     tree: &DomTree,
     ua_stylesheet: &Stylesheet,
+    user_stylesheet: Option<&Stylesheet>,
     author_stylesheet: &Stylesheet,
+    initial_font_size: f64,
+) -> HashMap<NodeId, ComputedStyle> {
+    compute_styles_with_context(
+        tree,
+        ua_stylesheet,
+        user_stylesheet,
+        author_stylesheet,
+        initial_font_size,
+        &MatchContext::default(),
+    )
+}
+
+/// Like [`compute_styles`], but `:hover`, `:focus`, `:checked`, `:target`
+/// and the other state-dependent pseudo-classes are matched against
+/// `ctx`.
+#[must_use]
+#[allow(clippy::implicit_hasher)]
+pub fn compute_styles_with_context(
+    tree: &DomTree,
+    ua_stylesheet: &Stylesheet,
+    user_stylesheet: Option<&Stylesheet>,
+    author_stylesheet: &Stylesheet,
+    initial_font_size: f64,
+    ctx: &MatchContext,
 ) -> HashMap<NodeId, ComputedStyle> {
     let mut styles = HashMap::new();
 
     // Parse all selectors upfront, tagged with their origin.
     let mut parsed_rules = Vec::new();
-    parse_stylesheet_rules(ua_stylesheet, CascadeOrigin::UserAgent, &mut parsed_rules);
-    parse_stylesheet_rules(author_stylesheet, CascadeOrigin::Author, &mut parsed_rules);
+    parse_stylesheet_rules(
+        &ua_stylesheet.rules,
+        CascadeOrigin::UserAgent,
+        &mut parsed_rules,
+    );
+    if let Some(user_stylesheet) = user_stylesheet {
+        parse_stylesheet_rules(
+            &user_stylesheet.rules,
+            CascadeOrigin::User,
+            &mut parsed_rules,
+        );
+    }
+    parse_stylesheet_rules(
+        &author_stylesheet.rules,
+        CascadeOrigin::Author,
+        &mut parsed_rules,
+    );
 
     // Start with default inherited style (none)
-    let initial_style = ComputedStyle::default();
+    //
+    // [§ 3.5 'font-size'](https://www.w3.org/TR/css-fonts-4/#font-size-prop)
+    // "Initial: medium"
+    //
+    // [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+    // "When specified on the font-size property of the root element, the
+    // rem units refer to the property's initial value."
+    let initial_style = ComputedStyle {
+        font_size: Some(LengthValue::Px(initial_font_size)),
+        root_font_size: Some(initial_font_size),
+        ..ComputedStyle::default()
+    };
     compute_node_styles(
         tree,
         tree.root(),
         &parsed_rules,
+        ctx,
         &initial_style,
         &mut styles,
     );
@@ -149,6 +250,7 @@ fn compute_node_styles(
     tree: &DomTree,
     id: NodeId,
     rules: &[ParsedRule],
+    ctx: &MatchContext,
     inherited: &ComputedStyle,
     styles: &mut HashMap<NodeId, ComputedStyle>,
 ) {
@@ -164,7 +266,7 @@ fn compute_node_styles(
             // Find all matching rules using tree-aware matching for combinator support
             let mut matched: Vec<MatchedRule> = rules
                 .iter()
-                .filter(|pr| pr.selector.matches_in_tree(tree, id))
+                .filter(|pr| pr.selector.matches_in_tree_ctx(tree, id, ctx))
                 .map(|pr| MatchedRule {
                     origin: pr.origin,
                     specificity: pr.selector.specificity,
@@ -172,27 +274,7 @@ fn compute_node_styles(
                 })
                 .collect();
 
-            // [§ 6.1 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
-            //
-            // "The cascading process sorts declarations according to the following
-            // criteria, in descending order of priority:
-            // Origin and Importance > ... > Specificity > Order of Appearance"
-            //
-            // Sort by (origin, specificity) — UA rules sort before author rules,
-            // so author rules always override UA rules regardless of specificity.
-            // Within the same origin, higher specificity wins.
-            matched.sort_by(|a, b| {
-                a.origin
-                    .cmp(&b.origin)
-                    .then_with(|| a.specificity.cmp(&b.specificity))
-            });
-
-            // Apply declarations in order (lowest priority first, highest last wins)
-            for m in matched {
-                for decl in &m.rule.declarations {
-                    computed.apply_declaration(decl);
-                }
-            }
+            sort_matched_rules(&mut matched);
 
             // [§ 6.1 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
             //
@@ -201,42 +283,339 @@ fn compute_node_styles(
             // "Element-attached declarations from the style attribute have
             // Author origin and are always more specific than any selector."
             //
-            // Apply inline style declarations last so they override all
+            // Inline style declarations go last so they override all
             // stylesheet rules (they have the highest cascade priority
             // among author-level declarations).
-            if let Some(style_attr) = element_data.attrs.get("style") {
-                let mut tokenizer = crate::tokenizer::CSSTokenizer::new(style_attr.clone());
-                tokenizer.run();
-                let mut parser = crate::parser::CSSParser::new(tokenizer.into_tokens());
-                let declarations = parser.parse_declaration_list();
-                for decl in &declarations {
-                    computed.apply_declaration(decl);
-                }
-            }
+            let inline_declarations = element_data
+                .attrs
+                .get("style")
+                .map(|style_attr| {
+                    let tokens = crate::tokenizer::CSSTokenizer::tokenize(style_attr);
+                    let mut parser = crate::parser::CSSParser::new(tokens);
+                    parser.parse_declaration_list()
+                })
+                .unwrap_or_default();
 
-            // [§ 2.3](https://www.w3.org/TR/css-variables-1/#cycles)
+            // [§ 15.2 The CSS user agent style sheet and presentational hints](https://html.spec.whatwg.org/multipage/rendering.html#presentational-hints)
             //
-            // "Custom properties resolve any var() functions in their values
-            // at computed-value time, which occurs before the value is inherited."
-            computed.resolve_custom_properties();
+            // "The presentational hints are then treated as author-level
+            // rules with a specificity of zero that are placed at the start
+            // of the author style sheet."
+            let hint_declarations = presentational_hints(element_data);
+
+            // Declarations in cascade order (lowest priority first, highest last wins)
+            let author_declarations = hint_declarations
+                .iter()
+                .chain(origin_declarations(&matched, CascadeOrigin::Author))
+                .chain(inline_declarations.iter());
+
+            // Important author declarations go after every normal one, so
+            // `!important` in a stylesheet beats a normal inline declaration,
+            // and an important inline declaration beats both.
+            let declarations = declarations_in_cascade_order(
+                origin_declarations(&matched, CascadeOrigin::UserAgent),
+                origin_declarations(&matched, CascadeOrigin::User),
+                author_declarations,
+            );
+
+            apply_declarations(&mut computed, &declarations);
+
+            // [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+            //
+            // "rem unit: Equal to the computed value of font-size on the root
+            // element."
+            //
+            // Once the root element's font-size is known, every descendant
+            // resolves rem against it.
+            //
+            // NOTE: Only absolute font sizes are taken; a font-size the
+            // cascade could not resolve to px (e.g. a percentage) keeps the
+            // initial value rather than collapsing rem to zero.
+            if tree.document_element() == Some(id)
+                && let Some(LengthValue::Px(px)) = computed.font_size
+            {
+                computed.root_font_size = Some(px);
+            }
+
+            // [§ 4.1 ::before and ::after](https://www.w3.org/TR/css-pseudo-4/#generated-content)
+            computed.before =
+                pseudo_element_style(tree, id, rules, ctx, &computed, PseudoElement::Before);
+            computed.after =
+                pseudo_element_style(tree, id, rules, ctx, &computed, PseudoElement::After);
+            // [§ 2 Typographic Pseudo-elements](https://www.w3.org/TR/css-pseudo-4/#typographic-pseudos)
+            computed.first_line =
+                pseudo_element_style(tree, id, rules, ctx, &computed, PseudoElement::FirstLine);
+            computed.first_letter =
+                pseudo_element_style(tree, id, rules, ctx, &computed, PseudoElement::FirstLetter);
 
             // Store the computed style
             let _ = styles.insert(id, computed.clone());
 
             // Recurse to children with this element's computed style as inherited
             for &child_id in tree.children(id) {
-                compute_node_styles(tree, child_id, rules, &computed, styles);
+                compute_node_styles(tree, child_id, rules, ctx, &computed, styles);
             }
         }
         NodeType::Document => {
             // Document doesn't have styles itself, but pass through to children
             for &child_id in tree.children(id) {
-                compute_node_styles(tree, child_id, rules, inherited, styles);
+                compute_node_styles(tree, child_id, rules, ctx, inherited, styles);
             }
         }
         // Text and comment nodes don't have styles applied directly.
         // They inherit from their parent element when rendered.
-        NodeType::Text(_) | NodeType::Comment(_) => {}
+        // Doctypes are never rendered.
+        NodeType::Text(_) | NodeType::Comment(_) | NodeType::DocumentType { .. } => {}
+    }
+}
+
+/// [§ 6.1 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
+///
+/// "The cascading process sorts declarations according to the following
+/// criteria, in descending order of priority:
+/// Origin and Importance > ... > Specificity > Order of Appearance"
+///
+/// Sort by (origin, specificity) — UA rules sort before author rules,
+/// so author rules always override UA rules regardless of specificity.
+/// Within the same origin, higher specificity wins. The sort is stable,
+/// so order of appearance breaks ties.
+fn sort_matched_rules(matched: &mut [MatchedRule]) {
+    matched.sort_by(|a, b| {
+        a.origin
+            .cmp(&b.origin)
+            .then_with(|| a.specificity.cmp(&b.specificity))
+    });
+}
+
+/// [§ 6.4.1 Cascade Origins and Importance](https://www.w3.org/TR/css-cascade-4/#cascade-origin)
+///
+/// "Declarations from origins earlier in this list win over declarations
+/// from later origins:
+///   1. Transition declarations
+///   2. Important user agent declarations
+///   3. Important user declarations
+///   4. Important author declarations
+///   5. Animation declarations
+///   6. Normal author declarations
+///   7. Normal user declarations
+///   8. Normal user agent declarations"
+///
+/// Order the declarations of each origin so that the last one applied
+/// wins: UA normal, user normal, author normal, author important, user
+/// important, then UA important. Importance reverses the origin order, so
+/// an important UA declaration beats even an important author one. Each
+/// iterator must already be in specificity and source order, which is
+/// kept within each tier.
+fn declarations_in_cascade_order<'a>(
+    ua: impl Iterator<Item = &'a Declaration> + Clone,
+    user: impl Iterator<Item = &'a Declaration> + Clone,
+    author: impl Iterator<Item = &'a Declaration> + Clone,
+) -> Vec<&'a Declaration> {
+    ua.clone()
+        .filter(|d| !d.important)
+        .chain(user.clone().filter(|d| !d.important))
+        .chain(author.clone().filter(|d| !d.important))
+        .chain(author.filter(|d| d.important))
+        .chain(user.filter(|d| d.important))
+        .chain(ua.filter(|d| d.important))
+        .collect()
+}
+
+/// Apply `declarations`, given in cascade order, to `style`.
+///
+/// [§ 2.3](https://www.w3.org/TR/css-variables-1/#cycles)
+///
+/// "Custom properties resolve any `var()` functions in their values
+/// at computed-value time, which occurs before the value is inherited."
+///
+/// Custom properties are cascaded and resolved before any other
+/// property, so a `var()` sees this element's own custom
+/// properties no matter where they appear in source order.
+///
+/// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+///
+/// "em unit: Equal to the computed value of the font-size property of the
+/// element on which it is used."
+///
+/// Likewise the font size is computed before the remaining properties, so
+/// `padding: 1em` uses this element's font-size wherever it is declared.
+/// Only the font size is taken from that early pass: every declaration,
+/// including the `font` shorthand, is then applied in cascade order, so a
+/// later shorthand still resets the longhands declared before it.
+fn apply_declarations(style: &mut ComputedStyle, declarations: &[&Declaration]) {
+    let is_custom = |d: &&&Declaration| d.name.starts_with("--");
+    let sets_font_size = |d: &&&Declaration| {
+        d.name.eq_ignore_ascii_case("font-size") || d.name.eq_ignore_ascii_case("font")
+    };
+
+    for decl in declarations.iter().filter(is_custom) {
+        style.apply_declaration(decl);
+    }
+    style.resolve_custom_properties();
+
+    // STEP 1: Resolve the font size the last valid `font-size` or `font`
+    // declaration gives, on a scratch copy.
+    let mut font_declarations = declarations.iter().filter(sets_font_size).peekable();
+    if font_declarations.peek().is_some() {
+        let mut scratch = style.clone();
+        for decl in font_declarations {
+            scratch.apply_declaration(decl);
+        }
+        style.font_size = scratch.font_size;
+    }
+
+    // STEP 2: Apply everything in order, keeping the resolved font size
+    // while earlier font declarations are applied.
+    let font_size = style.font_size;
+    for decl in declarations.iter().filter(|d| !is_custom(d)) {
+        style.apply_declaration(decl);
+        if sets_font_size(&decl) {
+            style.font_size = font_size;
+        }
+    }
+}
+
+/// The declarations of the sorted `matched` rules from `origin`, in
+/// specificity and source order.
+fn origin_declarations<'a>(
+    matched: &'a [MatchedRule<'a>],
+    origin: CascadeOrigin,
+) -> impl Iterator<Item = &'a Declaration> + Clone {
+    matched
+        .iter()
+        .filter(move |m| m.origin == origin)
+        .flat_map(|m| m.rule.declarations.iter())
+}
+
+/// [§ 4.1 ::before and ::after](https://www.w3.org/TR/css-pseudo-4/#generated-content)
+///
+/// "When their computed content value is not none, these pseudo-elements
+/// generate boxes as if they were immediately-contained children of their
+/// originating element"
+///
+/// Cascade the rules targeting `pseudo` of the element `id`. The
+/// pseudo-element inherits from its originating element's `originating`
+/// style. Returns `None` when no rule targets the pseudo-element, or when
+/// a `::before`/`::after` has a `content` that computes to `normal`/`none`,
+/// since no box is generated then.
+///
+/// NOTE: `::first-letter` should inherit from `::first-line` when both
+/// apply; both inherit from the originating element here.
+fn pseudo_element_style(
+    tree: &DomTree,
+    id: NodeId,
+    rules: &[ParsedRule],
+    ctx: &MatchContext,
+    originating: &ComputedStyle,
+    pseudo: PseudoElement,
+) -> Option<Box<ComputedStyle>> {
+    let mut matched: Vec<MatchedRule> = rules
+        .iter()
+        .filter(|pr| {
+            pr.selector
+                .matches_pseudo_element_in_tree_ctx(tree, id, pseudo, ctx)
+        })
+        .map(|pr| MatchedRule {
+            origin: pr.origin,
+            specificity: pr.selector.specificity,
+            rule: pr.rule,
+        })
+        .collect();
+    if matched.is_empty() {
+        return None;
+    }
+    sort_matched_rules(&mut matched);
+
+    let mut style = inherit_styles(originating);
+    let declarations = declarations_in_cascade_order(
+        origin_declarations(&matched, CascadeOrigin::UserAgent),
+        origin_declarations(&matched, CascadeOrigin::User),
+        origin_declarations(&matched, CascadeOrigin::Author),
+    );
+    apply_declarations(&mut style, &declarations);
+
+    let generates_box = match pseudo {
+        PseudoElement::Before | PseudoElement::After => style.content.is_some(),
+        PseudoElement::FirstLine | PseudoElement::FirstLetter => true,
+    };
+    generates_box.then(|| Box::new(style))
+}
+
+/// [§ 15.2 Presentational hints](https://html.spec.whatwg.org/multipage/rendering.html#presentational-hints)
+///
+/// Build the declarations that presentational attributes map to.
+///
+/// [§ 15.4.3 Attributes for embedded content and images](https://html.spec.whatwg.org/multipage/rendering.html#attributes-for-embedded-content-and-images)
+///
+/// "The width and height attributes on an img element's dimension attribute
+/// source map to the dimension properties width and height on the img
+/// element respectively."
+///
+/// NOTE: Only `<img>` is handled; the same mapping for `<video>`,
+/// `<iframe>` and friends is not implemented because layout does not
+/// treat them as replaced elements yet.
+fn presentational_hints(element: &ElementData) -> Vec<Declaration> {
+    if element.tag_name != "img" {
+        return Vec::new();
+    }
+
+    let mut css = String::new();
+    for property in ["width", "height"] {
+        if let Some(value) = element
+            .attrs
+            .get(property)
+            .and_then(|v| parse_dimension_value(v))
+        {
+            let _ = write!(css, "{property}: {value};");
+        }
+    }
+    if css.is_empty() {
+        return Vec::new();
+    }
+
+    let mut parser = crate::parser::CSSParser::new(crate::tokenizer::CSSTokenizer::tokenize(&css));
+    parser.parse_declaration_list()
+}
+
+/// [§ 2.3.4.4 Dimension values](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#rules-for-parsing-dimension-values)
+///
+/// Parse an attribute value with the rules for parsing dimension values,
+/// returning the equivalent CSS `<length-percentage>` text.
+fn parse_dimension_value(input: &str) -> Option<String> {
+    // STEP 3: "Skip ASCII whitespace within input given position."
+    let input = input.trim_start_matches(|c: char| c.is_ascii_whitespace());
+
+    // STEP 4-6: "If position is past the end of input or the code point at
+    // position within input is not an ASCII digit, then return failure."
+    // "Collect a sequence of code points that are ASCII digits..."
+    let digits_end = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    if digits_end == 0 {
+        return None;
+    }
+    let mut end = digits_end;
+
+    // STEP 7: "If position is not past the end of input and the code point at
+    // position within input is U+002E (.), then ... collect a sequence of
+    // ASCII digits" for the fractional part.
+    if input[end..].starts_with('.') {
+        let fraction = &input[end + 1..];
+        let fraction_len = fraction
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        if fraction_len > 0 {
+            end += 1 + fraction_len;
+        }
+    }
+    let value: f64 = input[..end].parse().ok()?;
+
+    // STEP 8: "If position is not past the end of input and the code point at
+    // position within input is U+0025 (%), then return value as a percentage."
+    if input[end..].starts_with('%') {
+        Some(format!("{value}%"))
+    } else {
+        Some(format!("{value}px"))
     }
 }
 
@@ -279,6 +658,10 @@ fn inherit_styles(parent: &ComputedStyle) -> ComputedStyle {
         // "Inherited: yes"
         writing_mode: parent.writing_mode,
 
+        // [§ 2.1 direction](https://www.w3.org/TR/css-writing-modes-4/#direction)
+        // "Inherited: yes"
+        direction: parent.direction,
+
         // [§ 16.2 text-align](https://www.w3.org/TR/CSS2/text.html#alignment-prop)
         // "Inherited: yes"
         text_align: parent.text_align,
@@ -293,12 +676,27 @@ fn inherit_styles(parent: &ComputedStyle) -> ComputedStyle {
         // computed-value-time resolution.
         custom_properties: parent.custom_properties.clone(),
 
+        // [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+        // Not a property: every element resolves rem against the same
+        // root font size, so it is passed down unchanged.
+        root_font_size: parent.root_font_size,
+        // `em` in the element's own font-size refers to the parent's.
+        parent_font_size: parent.font_size.as_ref().map(LengthValue::to_px),
+        // `bolder`/`lighter` step from the parent's weight.
+        parent_font_weight: parent.font_weight,
+
         // Non-inherited properties start as None
         //
         // [§ 3 text-decoration-line](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
         // "Inherited: no"
         text_decoration_line: None,
 
+        // [§ 2.2 text-decoration-style](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-style-property)
+        // [§ 2.3 text-decoration-color](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-color-property)
+        // "Inherited: no"
+        text_decoration_style: None,
+        text_decoration_color: None,
+
         // [§ 2 display](https://www.w3.org/TR/css-display-3/#the-display-properties)
         // "Inherited: no"
         display: None,
@@ -307,6 +705,12 @@ fn inherit_styles(parent: &ComputedStyle) -> ComputedStyle {
         // [§ 3.2 background-color](https://www.w3.org/TR/css-backgrounds-3/#background-color)
         // "Inherited: no"
         background_color: None,
+        // [§ 3.3-3.9 background-*](https://www.w3.org/TR/css-backgrounds-3/#backgrounds)
+        // "Inherited: no"
+        background_image: None,
+        background_repeat: None,
+        background_position: None,
+        background_size: None,
 
         // [§ 6 Box Model](https://www.w3.org/TR/css-box-4/)
         // "Inherited: no"
@@ -343,6 +747,10 @@ fn inherit_styles(parent: &ComputedStyle) -> ComputedStyle {
         min_height: None,
         max_height: None,
 
+        // [§ 5.1 aspect-ratio](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+        // "Inherited: no"
+        aspect_ratio: None,
+
         // [§ 5.1 flex-direction](https://www.w3.org/TR/css-flexbox-1/#flex-direction-property)
         // [§ 8.2 justify-content](https://www.w3.org/TR/css-flexbox-1/#justify-content-property)
         // [§ 7 Flexibility](https://www.w3.org/TR/css-flexbox-1/#flexibility)
@@ -390,26 +798,72 @@ fn inherit_styles(parent: &ComputedStyle) -> ComputedStyle {
         bottom: None,
         left: None,
 
-        // [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
+        // [§ 3 overflow-x, overflow-y](https://www.w3.org/TR/css-overflow-3/#overflow-properties)
         // "Inherited: no"
-        overflow: None,
+        overflow_x: None,
+        overflow_y: None,
 
         // [§ 4.4 box-sizing](https://www.w3.org/TR/css-box-4/#box-sizing)
         // "Inherited: no"
         box_sizing_border_box: None,
 
+        // [§ 5.5 object-fit](https://www.w3.org/TR/css-images-3/#the-object-fit)
+        // "Inherited: no"
+        object_fit: None,
+
+        // [§ 5.6 object-position](https://www.w3.org/TR/css-images-3/#the-object-position)
+        // "Inherited: no"
+        object_position: None,
+
         // [§ 16.6 white-space](https://www.w3.org/TR/CSS2/text.html#white-space-prop)
         // "Inherited: yes"
         white_space: parent.white_space,
 
+        // [§ 4.2 tab-size](https://www.w3.org/TR/css-text-3/#tab-size-property)
+        // "Inherited: yes"
+        tab_size: parent.tab_size,
+
+        // [§ 5.2 text-overflow](https://www.w3.org/TR/css-ui-3/#text-overflow)
+        // "Inherited: no"
+        text_overflow: None,
+
+        // [§ 10.8.1 vertical-align](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+        // "Inherited: no"
+        vertical_align: None,
+
+        // [§ 17.4.1 caption-side](https://www.w3.org/TR/CSS2/tables.html#caption-position)
+        // "Inherited: yes"
+        caption_side: parent.caption_side,
+
+        // [§ 17.5.2 table-layout](https://www.w3.org/TR/CSS2/tables.html#width-layout)
+        // "Inherited: no"
+        table_layout: None,
+
+        // [§ 17.6 border-collapse](https://www.w3.org/TR/CSS2/tables.html#borders)
+        // "Inherited: yes"
+        border_collapse: parent.border_collapse,
+
+        // [§ 17.6.1 border-spacing](https://www.w3.org/TR/CSS2/tables.html#separated-borders)
+        // "Inherited: yes"
+        border_spacing: parent.border_spacing,
+
         // [§ 11.2 visibility](https://www.w3.org/TR/CSS2/visufx.html#visibility)
         // "Inherited: yes"
         visibility: parent.visibility,
 
+        // [§ 5.1 cursor](https://www.w3.org/TR/css-ui-4/#cursor)
+        // "Inherited: yes"
+        cursor: parent.cursor,
+
         // [§ 3.2 opacity](https://www.w3.org/TR/css-color-4/#transparency)
         // "Inherited: no"
         opacity: None,
 
+        // [CSS Containment 2 § 4 content-visibility](https://www.w3.org/TR/css-contain-2/#content-visibility)
+        // "Inherited: no"
+        content_visibility: None,
+        contain_intrinsic_size: None,
+
         // [§ 6.1 box-shadow](https://www.w3.org/TR/css-backgrounds-3/#box-shadow)
         // "Inherited: no"
         box_shadow: None,
@@ -418,6 +872,26 @@ fn inherit_styles(parent: &ComputedStyle) -> ComputedStyle {
         // "Inherited: no"
         border_radius: None,
 
+        // [CSS UI 4 § 5 Outline properties](https://www.w3.org/TR/css-ui-4/#outline-props)
+        // "Inherited: no"
+        outline: None,
+        outline_offset: None,
+
+        // [§ 2 content](https://www.w3.org/TR/css-content-3/#content-property)
+        // "Inherited: no"
+        content: None,
+
+        // [§ 4 Automatic Numbering With Counters](https://www.w3.org/TR/css-lists-3/#auto-numbering)
+        // "Inherited: no"
+        counter_reset: None,
+        counter_increment: None,
+
+        // Pseudo-element styles belong to their originating element only.
+        before: None,
+        after: None,
+        first_line: None,
+        first_letter: None,
+
         // Source order tracking for cascade resolution (not inherited, per-element)
         margin_top_source_order: None,
         margin_right_source_order: None,
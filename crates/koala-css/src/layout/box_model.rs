@@ -21,7 +21,7 @@ pub struct BoxDimensions {
 /// A rectangle positioned in 2D space.
 ///
 /// [§ 3 The CSS Box Model](https://www.w3.org/TR/css-box-3/#box-model)
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct Rect {
     /// Horizontal position of the top-left corner.
     pub x: f32,
@@ -33,6 +33,28 @@ pub struct Rect {
     pub height: f32,
 }
 
+impl Rect {
+    /// Whether `(x, y)` lies inside this rectangle.
+    ///
+    /// The left and top edges are inside and the right and bottom edges
+    /// are outside, so adjacent rectangles never both contain a point.
+    #[must_use]
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// This rectangle grown outward by `edges` on each side.
+    #[must_use]
+    pub fn expanded_by(&self, edges: EdgeSizes) -> Self {
+        Self {
+            x: self.x - edges.left,
+            y: self.y - edges.top,
+            width: self.width + edges.left + edges.right,
+            height: self.height + edges.top + edges.bottom,
+        }
+    }
+}
+
 /// Edge sizes for padding, border, or margin.
 ///
 /// [§ 3 The CSS Box Model](https://www.w3.org/TR/css-box-3/#box-model)
@@ -107,24 +129,7 @@ impl BoxDimensions {
     /// ```
     #[must_use]
     pub fn margin_box(&self) -> Rect {
-        Rect {
-            x: self.content.x - self.padding.left - self.border.left - self.margin.left,
-            y: self.content.y - self.padding.top - self.border.top - self.margin.top,
-            width: self.content.width
-                + self.padding.left
-                + self.padding.right
-                + self.border.left
-                + self.border.right
-                + self.margin.left
-                + self.margin.right,
-            height: self.content.height
-                + self.padding.top
-                + self.padding.bottom
-                + self.border.top
-                + self.border.bottom
-                + self.margin.top
-                + self.margin.bottom,
-        }
+        self.border_box().expanded_by(self.margin)
     }
 
     /// [§ 3.2 Padding](https://www.w3.org/TR/css-box-3/#paddings)
@@ -145,12 +150,7 @@ impl BoxDimensions {
     /// ```
     #[must_use]
     pub fn padding_box(&self) -> Rect {
-        Rect {
-            x: self.content.x - self.padding.left,
-            y: self.content.y - self.padding.top,
-            width: self.content.width + self.padding.left + self.padding.right,
-            height: self.content.height + self.padding.top + self.padding.bottom,
-        }
+        self.content.expanded_by(self.padding)
     }
 
     /// [§ 3.3 Borders](https://www.w3.org/TR/css-box-3/#borders)
@@ -176,21 +176,9 @@ impl BoxDimensions {
     /// ```
     #[must_use]
     pub fn border_box(&self) -> Rect {
-        Rect {
-            x: self.content.x - self.padding.left - self.border.left,
-            y: self.content.y - self.padding.top - self.border.top,
-            width: self.content.width
-                + self.padding.left
-                + self.padding.right
-                + self.border.left
-                + self.border.right,
-            height: self.content.height
-                + self.padding.top
-                + self.padding.bottom
-                + self.border.top
-                + self.border.bottom,
-        }
+        self.padding_box().expanded_by(self.border)
     }
+
     /// [§ 3 The CSS Box Model](https://www.w3.org/TR/css-box-3/#box-model)
     /// "The content box contains the actual content of the element."
     #[must_use]
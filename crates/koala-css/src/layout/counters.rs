@@ -0,0 +1,168 @@
+//! CSS counters.
+//!
+//! [CSS Lists and Counters Module Level 3 § 4 Automatic Numbering With Counters](https://www.w3.org/TR/css-lists-3/#auto-numbering)
+//!
+//! "CSS counters are an extensible and powerful mechanism for numbering
+//! lists, sections, and other document elements."
+//!
+//! Counters are maintained while the layout tree is built, visiting
+//! elements (and their `::before`/`::after` pseudo-elements) in document
+//! order.
+
+use crate::style::ComputedStyle;
+
+/// The name of the counter that numbers list items.
+///
+/// [§ 4.5 The Implicit list-item Counter](https://www.w3.org/TR/css-lists-3/#list-item-counter)
+///
+/// "In addition to any explicitly defined counters that authors write in
+/// their styles, list items automatically increment a special list-item
+/// counter, which is used when generating the default marker string on
+/// list items."
+pub const LIST_ITEM: &str = "list-item";
+
+/// One counter in scope.
+struct Counter {
+    name: String,
+    value: i32,
+    /// Tree depth of the element that instantiated the counter. Its
+    /// following siblings share the depth and therefore the counter.
+    depth: usize,
+}
+
+/// [§ 4.4 Nested Counters and Scope](https://www.w3.org/TR/css-lists-3/#nested-counters)
+///
+/// "Counters are 'self-nesting'; instantiating a new counter on an element
+/// which inherited an identically-named counter from its parent creates a
+/// new counter of the same name, nested inside the existing counter."
+///
+/// "The scope of a counter therefore starts at the first element in the
+/// document that instantiates that counter and includes the element's
+/// descendants and its following siblings with their descendants."
+///
+/// The counters in scope form a stack, innermost last. Counters an
+/// element's children instantiate are dropped once all of its children
+/// have been visited.
+#[derive(Default)]
+pub struct CounterState {
+    counters: Vec<Counter>,
+    depth: usize,
+}
+
+impl CounterState {
+    /// Start visiting the children of the current element. Pass the
+    /// returned mark to [`CounterState::leave_children`] afterwards.
+    pub const fn enter_children(&mut self) -> usize {
+        self.depth += 1;
+        self.counters.len()
+    }
+
+    /// Finish visiting the children of an element, ending the scope of
+    /// every counter they instantiated.
+    pub fn leave_children(&mut self, mark: usize) {
+        self.counters.truncate(mark);
+        self.depth -= 1;
+    }
+
+    /// [§ 4.2 Creating Counters](https://www.w3.org/TR/css-lists-3/#counter-reset)
+    ///
+    /// "If element has a counter of the given name that it inherited from
+    /// its preceding sibling, remove that counter. Then instantiate a new
+    /// counter of the given name on element with the given value."
+    pub fn reset(&mut self, name: &str, value: i32) {
+        let sibling = self
+            .counters
+            .iter()
+            .rposition(|c| c.depth == self.depth && c.name == name);
+        if let Some(index) = sibling {
+            let _ = self.counters.remove(index);
+        }
+        self.counters.push(Counter {
+            name: name.to_string(),
+            value,
+            depth: self.depth,
+        });
+    }
+
+    /// [§ 4.3 Manipulating Counter Values](https://www.w3.org/TR/css-lists-3/#increment-set)
+    ///
+    /// "If there is not currently a counter of the given name on the
+    /// element, the element instantiates a new counter of the given name
+    /// with a starting value of 0 before setting or incrementing its value."
+    pub fn increment(&mut self, name: &str, amount: i32) {
+        let counter = self.innermost_or_instantiate(name);
+        counter.value = counter.value.saturating_add(amount);
+    }
+
+    /// [§ 4.3 Manipulating Counter Values](https://www.w3.org/TR/css-lists-3/#increment-set)
+    ///
+    /// "The counter-set property sets the value of an element's counters
+    /// to the specified integer."
+    pub fn set(&mut self, name: &str, value: i32) {
+        self.innermost_or_instantiate(name).value = value;
+    }
+
+    /// The value of the innermost counter named `name`, if one is in scope.
+    pub fn value(&self, name: &str) -> Option<i32> {
+        self.counters
+            .iter()
+            .rev()
+            .find(|c| c.name == name)
+            .map(|c| c.value)
+    }
+
+    /// The values of every counter named `name` in scope, outermost first.
+    pub fn values(&self, name: &str) -> Vec<i32> {
+        self.counters
+            .iter()
+            .filter(|c| c.name == name)
+            .map(|c| c.value)
+            .collect()
+    }
+
+    /// [§ 4.1 Introduction](https://www.w3.org/TR/css-lists-3/#counters)
+    ///
+    /// Apply an element's or pseudo-element's `counter-reset`, then its
+    /// `counter-increment`.
+    ///
+    /// [§ 4.5 The Implicit list-item Counter](https://www.w3.org/TR/css-lists-3/#list-item-counter)
+    ///
+    /// "...list items automatically increment a special list-item counter.
+    /// Unless the counter-increment property explicitly specifies a
+    /// different increment for the list-item counter, it must be treated as
+    /// if it includes list-item 1 for all list items."
+    pub fn apply(&mut self, style: Option<&ComputedStyle>, is_list_item: bool) {
+        for (name, value) in style
+            .and_then(|s| s.counter_reset.as_ref())
+            .into_iter()
+            .flatten()
+        {
+            self.reset(name, *value);
+        }
+        let increments = style
+            .and_then(|s| s.counter_increment.as_deref())
+            .unwrap_or_default();
+        for (name, amount) in increments {
+            self.increment(name, *amount);
+        }
+        if is_list_item && !increments.iter().any(|(name, _)| name == LIST_ITEM) {
+            self.increment(LIST_ITEM, 1);
+        }
+    }
+
+    fn innermost_or_instantiate(&mut self, name: &str) -> &mut Counter {
+        let index = self
+            .counters
+            .iter()
+            .rposition(|c| c.name == name)
+            .unwrap_or_else(|| {
+                self.counters.push(Counter {
+                    name: name.to_string(),
+                    value: 0,
+                    depth: self.depth,
+                });
+                self.counters.len() - 1
+            });
+        &mut self.counters[index]
+    }
+}
@@ -13,8 +13,8 @@
 
 use koala_dom::NodeId;
 
-use crate::style::ColorValue;
-use crate::style::values::{FontStyle, TextAlign, TextDecorationLine};
+use crate::style::{ColorValue, Direction};
+use crate::style::values::{FontFamily, FontStyle, LineHeight, TextAlign, TextDecoration, VerticalAlign};
 
 use super::box_model::Rect;
 
@@ -39,7 +39,19 @@ pub trait FontMetrics {
     /// `letter_spacing` is allowed and may produce a total smaller
     /// than the sum of glyph widths (or even negative); callers
     /// must not clamp.
-    fn text_width(&self, text: &str, font_size: f32, letter_spacing: f32) -> f32;
+    ///
+    /// [§ 5 Font Matching Algorithm](https://www.w3.org/TR/css-fonts-4/#font-matching-algorithm)
+    ///
+    /// `font_family` is the text's 'font-family' list. Providers that
+    /// load several families measure with the first one they have, the
+    /// font the renderer draws the text with.
+    fn text_width(
+        &self,
+        text: &str,
+        font_size: f32,
+        letter_spacing: f32,
+        font_family: &[FontFamily],
+    ) -> f32;
 
     /// Calculate the line height for a given font size.
     ///
@@ -50,6 +62,42 @@ pub trait FontMetrics {
     fn line_height(&self, font_size: f32) -> f32;
 }
 
+/// [§ 9.3 'letter-spacing'](https://www.w3.org/TR/css-text-3/#letter-spacing-property)
+///
+/// "This property specifies additional spacing between adjacent
+/// typographic character units."
+///
+/// The advance width of `text` set at `font_size` in the first loaded
+/// family of `font_family`, with `letter_spacing` added between
+/// adjacent characters but not after the last one.
+#[must_use]
+pub fn measure_text(
+    text: &str,
+    font_size: f32,
+    letter_spacing: f32,
+    font_family: &[FontFamily],
+    font_metrics: &dyn FontMetrics,
+) -> f32 {
+    font_metrics.text_width(text, font_size, letter_spacing, font_family)
+}
+
+/// The total advance width of `runs` laid end to end, each measured with
+/// its own font family, font size and letter spacing.
+#[must_use]
+pub fn measure_text_runs(runs: &[TextRun], font_metrics: &dyn FontMetrics) -> f32 {
+    runs.iter()
+        .map(|run| {
+            measure_text(
+                &run.text,
+                run.font_size,
+                run.letter_spacing,
+                &run.font_family,
+                font_metrics,
+            )
+        })
+        .sum()
+}
+
 /// Approximate font metrics using fixed ratios.
 ///
 /// [§ 10.8 Line height calculations](https://www.w3.org/TR/CSS2/visudet.html#line-height)
@@ -68,7 +116,13 @@ pub struct ApproximateFontMetrics;
 
 impl FontMetrics for ApproximateFontMetrics {
     #[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
-    fn text_width(&self, text: &str, font_size: f32, letter_spacing: f32) -> f32 {
+    fn text_width(
+        &self,
+        text: &str,
+        font_size: f32,
+        letter_spacing: f32,
+        _font_family: &[FontFamily],
+    ) -> f32 {
         const CHAR_WIDTH_RATIO: f32 = 0.6;
         let n = text.chars().count();
         n as f32 * font_size * CHAR_WIDTH_RATIO
@@ -81,6 +135,74 @@ impl FontMetrics for ApproximateFontMetrics {
     }
 }
 
+/// [§ 4 White Space Processing & Control Characters](https://www.w3.org/TR/css-text-3/#white-space-processing)
+///
+/// "White space processing in CSS affects only the document white space
+/// characters: spaces (U+0020), tabs (U+0009), and segment breaks."
+///
+/// Carriage returns and form feeds are included as they are HTML ASCII
+/// whitespace. U+00A0 NO-BREAK SPACE is deliberately excluded: it is never
+/// collapsed or trimmed at a line edge, and it is not a soft wrap
+/// opportunity, so `&nbsp;` glues its neighbours together.
+pub(crate) const fn is_collapsible_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\r' | '\x0C')
+}
+
+/// [§ 4.1.1 Phase I: Collapsing and Transformation](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+///
+/// White space collapsing for `white-space: pre-line`, where newlines are
+/// preserved as segment breaks. For `normal` and `nowrap` the caller first
+/// converts newlines to spaces, so they collapse like any other space:
+///
+/// "Any sequence of collapsible spaces and tabs immediately preceding or
+/// following a segment break is removed."
+///
+/// "Every collapsible tab is converted to a collapsible space (U+0020)."
+///
+/// "Any collapsible space immediately following another collapsible
+/// space—even one outside the boundary of the inline containing that
+/// space, provided both spaces are within the same inline formatting
+/// context—is collapsed to have zero advance width."
+///
+/// [§ 4.1.2 Phase II: Trimming and Positioning](https://www.w3.org/TR/css-text-3/#white-space-phase-2)
+///
+/// "A sequence of collapsible spaces at the beginning of a line is
+/// removed." Leading spaces are trimmed when `trim_leading` is set, which
+/// the caller also does when the previous run in the same inline
+/// formatting context ended in a collapsible space — so the space ending
+/// `<b>a </b>` swallows the one starting `<b> b</b>`.
+fn collapse_spaces_around_newlines(text: &str, trim_leading: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let segments: Vec<&str> = text.split('\n').collect();
+    let last = segments.len() - 1;
+    for (i, segment) in segments.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let is_space = |c: char| c != '\n' && is_collapsible_space(c);
+        let mut segment = *segment;
+        if i > 0 || trim_leading {
+            segment = segment.trim_start_matches(is_space);
+        }
+        if i < last {
+            segment = segment.trim_end_matches(is_space);
+        }
+        let mut previous_was_space = false;
+        for c in segment.chars() {
+            if is_space(c) {
+                if !previous_was_space {
+                    out.push(' ');
+                }
+                previous_was_space = true;
+            } else {
+                out.push(c);
+                previous_was_space = false;
+            }
+        }
+    }
+    out
+}
+
 /// [§ 9.4.2 Inline formatting contexts](https://www.w3.org/TR/CSS2/visuren.html#inline-formatting)
 ///
 /// "The rectangular area that contains the boxes that form a line is called
@@ -138,6 +260,14 @@ pub struct LineFragment {
     /// "The 'vertical-align' property affects the vertical positioning
     /// inside a line box of the boxes generated by an inline-level element."
     pub vertical_align: VerticalAlign,
+
+    /// [§ 9.2.2 Inline-level elements and inline boxes](https://www.w3.org/TR/CSS2/visuren.html#inline-boxes)
+    ///
+    /// The innermost inline box whose contents this fragment is part of,
+    /// or `None` for content directly in the block container. Inline
+    /// boxes have no dimensions of their own, so this is how their
+    /// on-screen area is recovered (e.g. for an inline `<a>`).
+    pub inline_box: Option<NodeId>,
 }
 
 /// The content of a line fragment.
@@ -158,7 +288,15 @@ pub enum FragmentContent {
     ///
     /// Stores the `NodeId` so the corresponding child `LayoutBox` can be
     /// repositioned after line finalization.
-    InlineBlock(NodeId),
+    InlineBlock {
+        /// The node whose principal box this fragment places.
+        node_id: NodeId,
+        /// [§ 10.8.1 Leading and half-leading](https://www.w3.org/TR/CSS2/visudet.html#leading)
+        ///
+        /// Distance from the top of the box's margin box to its baseline,
+        /// used for `vertical-align: baseline`.
+        baseline: f32,
+    },
 }
 
 /// A contiguous run of text within a line fragment.
@@ -186,8 +324,15 @@ pub struct TextRun {
     pub font_weight: u16,
     /// [§ 3.3 'font-style'](https://www.w3.org/TR/css-fonts-4/#font-style-prop)
     pub font_style: FontStyle,
-    /// [§ 3 'text-decoration-line'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
-    pub text_decoration: TextDecorationLine,
+    /// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+    ///
+    /// The family list to draw this run with, most preferred first. Empty
+    /// means the default font.
+    pub font_family: Vec<FontFamily>,
+    /// [§ 2 Line Decorations](https://www.w3.org/TR/css-text-decoration-3/#line-decoration)
+    ///
+    /// The decoration of the box this run's text belongs to.
+    pub text_decoration: TextDecoration,
 
     /// [§ 9.3 'letter-spacing'](https://www.w3.org/TR/css-text-3/#letter-spacing-property)
     ///
@@ -197,69 +342,49 @@ pub struct TextRun {
     pub letter_spacing: f32,
 }
 
-/// [§ 10.8.1 Leading and half-leading](https://www.w3.org/TR/CSS2/visudet.html#leading)
-///
-/// "The 'vertical-align' property affects the vertical positioning inside
-/// a line box of the boxes generated by an inline-level element."
-///
-/// "Values for this property have different meanings in the context of
-/// tables. Please consult the section on vertical alignment in tables
-/// for details."
+/// [§ 2 Typographic Pseudo-elements](https://www.w3.org/TR/css-pseudo-4/#typographic-pseudos)
 ///
-/// "Values (for inline elements) have the following meanings:
-///
-/// baseline
-///   Align the baseline of the box with the baseline of the parent box.
-///
-/// middle
-///   Align the vertical midpoint of the box with the baseline of the
-///   parent box plus half the x-height of the parent.
-///
-/// sub
-///   Lower the baseline of the box to the proper position for subscripts.
-///
-/// super
-///   Raise the baseline of the box to the proper position for superscripts.
-///
-/// text-top
-///   Align the top of the box with the top of the parent's content area.
-///
-/// text-bottom
-///   Align the bottom of the box with the bottom of the parent's content area.
+/// The text properties of a `::first-line` or `::first-letter`
+/// pseudo-element, resolved like those of a principal box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStyle {
+    /// Font size in pixels.
+    pub font_size: f32,
+    /// [§ 3.1 'color'](https://www.w3.org/TR/css-color-4/#the-color-property)
+    pub color: ColorValue,
+    /// [§ 3.2 'font-weight'](https://www.w3.org/TR/css-fonts-4/#font-weight-prop)
+    pub font_weight: u16,
+    /// [§ 3.3 'font-style'](https://www.w3.org/TR/css-fonts-4/#font-style-prop)
+    pub font_style: FontStyle,
+    /// [§ 2 Line Decorations](https://www.w3.org/TR/css-text-decoration-3/#line-decoration)
+    pub text_decoration: TextDecoration,
+}
+
+/// [§ 2.2 ::first-letter](https://www.w3.org/TR/css-pseudo-4/#first-letter-pseudo)
 ///
-/// top
-///   Align the top of the aligned subtree with the top of the line box.
+/// "The first letter must occur on the first formatted line."
 ///
-/// bottom
-///   Align the bottom of the aligned subtree with the bottom of the line box.
+/// "Punctuation (i.e, characters that belong to the Punctuation (P*)
+/// Unicode general category) that precedes or follows the first letter
+/// must be included."
 ///
-/// `<percentage>`
-///   Raise (positive) or lower (negative) the box by this distance
-///   (a percentage of the 'line-height' value).
+/// The byte range of the first typographic letter unit in `text`, skipping
+/// leading white space. `None` when `text` holds no letter.
 ///
-/// `<length>`
-///   Raise (positive) or lower (negative) the box by this distance."
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
-pub enum VerticalAlign {
-    /// "Align the baseline of the box with the baseline of the parent box."
-    #[default]
-    Baseline,
-    /// "Align the vertical midpoint of the box with the baseline plus half x-height."
-    Middle,
-    /// "Lower the baseline of the box for subscripts."
-    Sub,
-    /// "Raise the baseline of the box for superscripts."
-    Super,
-    /// "Align the top with the top of the parent's content area."
-    TextTop,
-    /// "Align the bottom with the bottom of the parent's content area."
-    TextBottom,
-    /// "Align the top of the aligned subtree with the top of the line box."
-    Top,
-    /// "Align the bottom of the aligned subtree with the bottom of the line box."
-    Bottom,
-    /// Offset from baseline by a specific pixel amount.
-    Length(f32),
+/// NOTE: Only ASCII punctuation is recognized.
+fn first_letter_range(text: &str) -> Option<(usize, usize)> {
+    let start = text.find(|c: char| !c.is_whitespace())?;
+    let letter = text[start..].find(|c: char| !c.is_whitespace() && !c.is_ascii_punctuation())?;
+    let letter = start + letter;
+    // Punctuation only reaches the letter with no white space in between.
+    if text[start..letter].contains(char::is_whitespace) {
+        return None;
+    }
+    let after = letter + text[letter..].chars().next()?.len_utf8();
+    let end = text[after..]
+        .find(|c: char| !c.is_ascii_punctuation())
+        .map_or(text.len(), |i| after + i);
+    Some((start, end))
 }
 
 /// Inline formatting context that manages line box construction.
@@ -320,6 +445,67 @@ pub struct InlineLayout {
     /// of being collapsed. Set when `white-space` is `pre`, `pre-wrap`, or
     /// `pre-line`.
     pub preserve_newlines: bool,
+
+    /// [§ 4.1.1 Phase I: Collapsing and Transformation](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+    ///
+    /// When true, runs of spaces and tabs collapse to a single space, also
+    /// across the boundaries of inline boxes. Set when `white-space` is
+    /// `normal` or `nowrap`, where newlines collapse too, or `pre-line`,
+    /// where spaces next to a preserved newline are removed instead.
+    pub collapse_spaces: bool,
+
+    /// [§ 4.2 'tab-size'](https://www.w3.org/TR/css-text-3/#tab-size-property)
+    ///
+    /// "This property determines the tab size used to render preserved tab
+    /// characters (U+0009)."
+    ///
+    /// When `Some`, tab characters are preserved and expanded to the next
+    /// multiple of this many columns. Set when `white-space` is `pre` or
+    /// `pre-wrap`.
+    pub tab_size: Option<u32>,
+
+    /// [§ 2.1 'direction'](https://www.w3.org/TR/css-writing-modes-4/#direction)
+    ///
+    /// The inline base direction. In `rtl` each finished line is mirrored
+    /// so that its first fragment sits at the right edge.
+    pub direction: Direction,
+
+    /// [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+    ///
+    /// The alignment given to text runs and inline boxes added from now
+    /// on. The caller sets it while laying out the contents of an inline
+    /// box whose `vertical-align` isn't `baseline`, so the whole box moves
+    /// together.
+    pub vertical_align: VerticalAlign,
+
+    /// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+    ///
+    /// The 'line-height' of the box whose text is being added. Like
+    /// `vertical_align`, the caller swaps it while laying out the contents
+    /// of each inline box.
+    pub line_height: LineHeight,
+
+    /// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+    ///
+    /// The 'font-family' list of the box whose text is being added, copied
+    /// onto each of its text runs. Swapped by the caller like
+    /// `line_height`.
+    pub font_family: Vec<FontFamily>,
+
+    /// [§ 9.2.2 Inline-level elements and inline boxes](https://www.w3.org/TR/CSS2/visuren.html#inline-boxes)
+    ///
+    /// The inline box whose contents are being added, recorded on each
+    /// fragment as [`LineFragment::inline_box`]. Swapped by the caller
+    /// like `font_family`.
+    pub inline_box: Option<NodeId>,
+
+    /// [§ 2.2 ::first-letter](https://www.w3.org/TR/css-pseudo-4/#first-letter-pseudo)
+    ///
+    /// The style of the block container's `::first-letter`, while its first
+    /// letter has not been placed yet. Taken by the first text containing a
+    /// letter, and dropped once other in-flow content or a forced line
+    /// break comes first.
+    pub first_letter: Option<TextStyle>,
 }
 
 impl InlineLayout {
@@ -343,9 +529,26 @@ impl InlineLayout {
             left_offset: 0.0,
             no_wrap: false,
             preserve_newlines: false,
+            collapse_spaces: false,
+            tab_size: None,
+            direction: Direction::Ltr,
+            vertical_align: VerticalAlign::Baseline,
+            line_height: LineHeight::Normal,
+            font_family: Vec::new(),
+            inline_box: None,
+            first_letter: None,
         }
     }
 
+    /// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+    ///
+    /// The used line height of text at `font_size` in the current box.
+    /// `normal` falls back to the font's own line height.
+    fn used_line_height(&self, font_size: f32, font_metrics: &dyn FontMetrics) -> f32 {
+        self.line_height
+            .to_px(font_size, font_metrics.line_height(font_size))
+    }
+
     /// [§ 9.4.2](https://www.w3.org/TR/CSS2/visuren.html#inline-formatting)
     ///
     /// Add a text run to the inline formatting context.
@@ -363,45 +566,74 @@ impl InlineLayout {
         color: &ColorValue,
         font_weight: u16,
         font_style: FontStyle,
-        text_decoration: TextDecorationLine,
+        text_decoration: &TextDecoration,
         letter_spacing: f32,
         font_metrics: &dyn FontMetrics,
     ) {
-        // STEP 0: Handle preserved newlines.
-        // [§ 16.6 'white-space'](https://www.w3.org/TR/CSS2/text.html#white-space-prop)
-        //
-        // "If 'white-space' is set to 'pre', 'pre-wrap', or 'pre-line',
-        // line breaks are forced at preserved newline characters."
-        //
-        // [§ 4.1.1 Phase I: Collapsing and Transformation](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+        // [§ 2.2 ::first-letter](https://www.w3.org/TR/css-pseudo-4/#first-letter-pseudo)
         //
-        // "A preserved newline forces a line break."
-        if self.preserve_newlines
-            && let Some(nl_pos) = text.find('\n')
+        // Split the first letter off into a run of its own, styled by the
+        // pseudo-element. Text without a letter leaves it pending.
+        if self.first_letter.is_some()
+            && let Some((start, end)) = first_letter_range(text)
+            && let Some(first_letter) = self.first_letter.take()
         {
-                // Place text before the newline on the current line.
-                let before = &text[..nl_pos];
-                if !before.is_empty() {
-                    let lh = font_metrics.line_height(font_size);
-                    self.place_text_fragment(
-                        before,
+            for (part, style) in [
+                (&text[..start], None),
+                (&text[start..end], Some(&first_letter)),
+                (&text[end..], None),
+            ] {
+                if part.is_empty() {
+                    continue;
+                }
+                match style {
+                    Some(style) => self.add_text(
+                        part,
+                        style.font_size,
+                        &style.color,
+                        style.font_weight,
+                        style.font_style,
+                        &style.text_decoration,
+                        letter_spacing,
+                        font_metrics,
+                    ),
+                    None => self.add_text(
+                        part,
                         font_size,
-                        lh,
                         color,
                         font_weight,
                         font_style,
                         text_decoration,
                         letter_spacing,
                         font_metrics,
-                    );
+                    ),
                 }
-                // Force line break at the newline character.
-                self.force_line_break(font_size, font_metrics);
-                // Continue with text after the newline.
-                let after = &text[nl_pos + 1..];
-                if !after.is_empty() {
+            }
+            return;
+        }
+
+        // [§ 4.1.1 Phase I: Collapsing and Transformation](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+        //
+        // Collapse the run first; the result is collapsed already, so the
+        // recursive call goes straight on to layout.
+        if self.collapse_spaces {
+            // The previous run on the line may belong to another inline box;
+            // an atomic inline in between keeps both spaces.
+            let trim_leading = self.current_line_fragments.last().is_none_or(|f| {
+                matches!(&f.content, FragmentContent::Text(run) if run.text.ends_with(is_collapsible_space))
+            });
+            // "Any collapsible segment break immediately following another
+            // collapsible segment break is removed. Then any remaining
+            // segment break is ... transformed into a space (U+0020)."
+            let collapsed = if self.preserve_newlines {
+                collapse_spaces_around_newlines(text, trim_leading)
+            } else {
+                collapse_spaces_around_newlines(&text.replace('\n', " "), trim_leading)
+            };
+            if collapsed != text {
+                if !collapsed.is_empty() {
                     self.add_text(
-                        after,
+                        &collapsed,
                         font_size,
                         color,
                         font_weight,
@@ -412,6 +644,81 @@ impl InlineLayout {
                     );
                 }
                 return;
+            }
+        }
+
+        // [§ 4.2 'tab-size'](https://www.w3.org/TR/css-text-3/#tab-size-property)
+        //
+        // "Integers represent the measure as multiples of the space
+        // character's advance width (U+0020) including its associated
+        // letter-spacing and word-spacing."
+        //
+        // Preserved tabs are replaced with the spaces needed to reach the
+        // next tab stop, so measurement, line breaking and painting all see
+        // the expanded run.
+        if let Some(tab_size) = self.tab_size
+            && text.contains('\t')
+        {
+            let expanded =
+                self.expand_tabs(text, tab_size, font_size, letter_spacing, font_metrics);
+            self.add_text(
+                &expanded,
+                font_size,
+                color,
+                font_weight,
+                font_style,
+                text_decoration,
+                letter_spacing,
+                font_metrics,
+            );
+            return;
+        }
+
+        // STEP 0: Handle preserved newlines.
+        // [§ 16.6 'white-space'](https://www.w3.org/TR/CSS2/text.html#white-space-prop)
+        //
+        // "If 'white-space' is set to 'pre', 'pre-wrap', or 'pre-line',
+        // line breaks are forced at preserved newline characters."
+        //
+        // [§ 4.1.1 Phase I: Collapsing and Transformation](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+        //
+        // "A preserved newline forces a line break."
+        if self.preserve_newlines
+            && let Some(nl_pos) = text.find('\n')
+        {
+            // Place text before the newline on the current line.
+            let before = &text[..nl_pos];
+            if !before.is_empty() {
+                let lh = self.used_line_height(font_size, font_metrics);
+                self.place_text_fragment(
+                    before,
+                    font_size,
+                    lh,
+                    color,
+                    font_weight,
+                    font_style,
+                    text_decoration,
+                    letter_spacing,
+                    font_metrics,
+                );
+            }
+            // Force line break at the newline character.
+            self.force_line_break(font_size, font_metrics);
+            // Continue with text after the newline.
+            let after = &text[nl_pos + 1..];
+            if !after.is_empty() {
+                self.add_text(
+                    after,
+                    font_size,
+                    color,
+                    font_weight,
+                    font_style,
+                    text_decoration,
+                    letter_spacing,
+                    font_metrics,
+                );
+            }
+            return;
         }
 
         // STEP 1: Measure the text width.
@@ -422,8 +729,14 @@ impl InlineLayout {
         //
         // The width comes from summing per-glyph advance widths via FontMetrics.
         // The height contribution is the line-height from FontMetrics.
-        let text_width = font_metrics.text_width(text, font_size, letter_spacing);
-        let line_height = font_metrics.line_height(font_size);
+        let text_width = measure_text(
+            text,
+            font_size,
+            letter_spacing,
+            &self.font_family,
+            font_metrics,
+        );
+        let line_height = self.used_line_height(font_size, font_metrics);
 
         // STEP 2: Check if text fits on the current line.
         // [§ 9.4.2](https://www.w3.org/TR/CSS2/visuren.html#inline-formatting)
@@ -443,9 +756,8 @@ impl InlineLayout {
         //
         // When no_wrap is true, text always fits on the current line
         // (no soft wrapping occurs).
-        let fits_on_current_line = self.no_wrap
-            || self.current_x + text_width <= self.available_width
-            || self.current_x == 0.0;
+        let fits_on_current_line =
+            self.no_wrap || self.current_x + text_width <= self.available_width;
 
         if !fits_on_current_line {
             // STEP 3: Handle line breaking.
@@ -463,6 +775,7 @@ impl InlineLayout {
                 remaining_width,
                 font_size,
                 letter_spacing,
+                &self.font_family,
                 font_metrics,
             ) {
                 // Split at the break point: place the first part on the
@@ -472,7 +785,7 @@ impl InlineLayout {
                 // Trim trailing whitespace from the first part per spec:
                 // [§ 4.1.3](https://www.w3.org/TR/css-text-3/#white-space-phase-2)
                 // "A sequence of collapsible spaces at the end of a line is removed."
-                let first_trimmed = first.trim_end();
+                let first_trimmed = first.trim_end_matches(is_collapsible_space);
                 if !first_trimmed.is_empty() {
                     self.place_text_fragment(
                         first_trimmed,
@@ -492,7 +805,7 @@ impl InlineLayout {
 
                 // Trim leading whitespace from the remainder per spec:
                 // "A sequence of collapsible spaces at the beginning of a line is removed."
-                let rest_trimmed = rest.trim_start();
+                let rest_trimmed = rest.trim_start_matches(is_collapsible_space);
                 if !rest_trimmed.is_empty() {
                     self.add_text(
                         rest_trimmed,
@@ -510,21 +823,23 @@ impl InlineLayout {
 
             // No break opportunity found that fits — wrap the entire text
             // to a new line. If the line is not empty, finish it first.
-            // The `current_x == 0.0` guard in `fits_on_current_line` above
-            // prevents infinite recursion: on a fresh line we always place
-            // the text even if it overflows.
-            self.finish_line();
-            self.add_text(
-                text,
-                font_size,
-                color,
-                font_weight,
-                font_style,
-                text_decoration,
-                letter_spacing,
-                font_metrics,
-            );
-            return;
+            // On a fresh line there is nowhere better to go, so the text is
+            // placed even though it overflows; this also keeps the
+            // recursion from looping.
+            if self.current_x > 0.0 {
+                self.finish_line();
+                self.add_text(
+                    text,
+                    font_size,
+                    color,
+                    font_weight,
+                    font_style,
+                    text_decoration,
+                    letter_spacing,
+                    font_metrics,
+                );
+                return;
+            }
         }
 
         // STEP 4: Place fragment on the current line.
@@ -541,6 +856,57 @@ impl InlineLayout {
         );
     }
 
+    /// Replace each tab in `text` with spaces up to the next tab stop.
+    ///
+    /// [§ 4.2 Tab Character Size](https://www.w3.org/TR/css-text-3/#tab-size-property)
+    ///
+    /// Tab stops are every `tab_size` columns from the start of the line,
+    /// where a column is one space advance. The starting column is derived
+    /// from the current pen position, so runs that follow earlier content
+    /// on the same line line up with the same stops; a preserved newline
+    /// starts again from column 0.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn expand_tabs(
+        &self,
+        text: &str,
+        tab_size: u32,
+        font_size: f32,
+        letter_spacing: f32,
+        font_metrics: &dyn FontMetrics,
+    ) -> String {
+        let space_advance =
+            measure_text(" ", font_size, 0.0, &self.font_family, font_metrics) + letter_spacing;
+        let mut column = if space_advance > 0.0 {
+            (self.current_x / space_advance).round() as u32
+        } else {
+            0
+        };
+
+        let mut expanded = String::with_capacity(text.len());
+        for c in text.chars() {
+            if c == '\n' {
+                expanded.push(c);
+                column = 0;
+            } else if c == '\t' {
+                // "If the tab size is zero, preserved tabs are not rendered."
+                if tab_size == 0 {
+                    continue;
+                }
+                let spaces = tab_size - column % tab_size;
+                expanded.extend(std::iter::repeat_n(' ', spaces as usize));
+                column += spaces;
+            } else {
+                expanded.push(c);
+                column += 1;
+            }
+        }
+        expanded
+    }
+
     /// Place a text fragment at the current position on the current line.
     ///
     /// This is the shared placement logic used by `add_text` after measurement
@@ -554,11 +920,17 @@ impl InlineLayout {
         color: &ColorValue,
         font_weight: u16,
         font_style: FontStyle,
-        text_decoration: TextDecorationLine,
+        text_decoration: &TextDecoration,
         letter_spacing: f32,
         font_metrics: &dyn FontMetrics,
     ) {
-        let text_width = font_metrics.text_width(text, font_size, letter_spacing);
+        let text_width = measure_text(
+            text,
+            font_size,
+            letter_spacing,
+            &self.font_family,
+            font_metrics,
+        );
 
         // [§ 9.4.2](https://www.w3.org/TR/CSS2/visuren.html#inline-formatting)
         //
@@ -582,10 +954,12 @@ impl InlineLayout {
                 color: color.clone(),
                 font_weight,
                 font_style,
-                text_decoration,
+                font_family: self.font_family.clone(),
+                text_decoration: text_decoration.clone(),
                 letter_spacing
             }),
-            vertical_align: VerticalAlign::Baseline,
+            vertical_align: self.vertical_align,
+            inline_box: self.inline_box,
         };
         self.current_line_fragments.push(fragment);
 
@@ -607,6 +981,7 @@ impl InlineLayout {
     /// or tops may be aligned, or the baselines of text within them may be
     /// aligned."
     pub fn add_inline_box(&mut self, width: f32, height: f32) {
+        self.first_letter = None;
         // STEP 1: Check if box fits on current line.
         // [§ 9.4.2](https://www.w3.org/TR/CSS2/visuren.html#inline-formatting)
         //
@@ -640,7 +1015,8 @@ impl InlineLayout {
                 height,
             },
             content: FragmentContent::InlineBox,
-            vertical_align: VerticalAlign::Baseline,
+            vertical_align: self.vertical_align,
+            inline_box: self.inline_box,
         };
         self.current_line_fragments.push(fragment);
 
@@ -663,7 +1039,21 @@ impl InlineLayout {
     ///
     /// Unlike regular inline boxes, inline-blocks are atomic — they cannot
     /// be split across lines.
-    pub fn add_inline_block(&mut self, node_id: NodeId, width: f32, height: f32) {
+    ///
+    /// `baseline` is measured from the top of the margin box (see
+    /// [`FragmentContent::InlineBlock`]). `vertical_align` is the box's own
+    /// 'vertical-align'; when it is `baseline` the box follows the
+    /// alignment of the inline box it sits in.
+    pub fn add_inline_block(
+        &mut self,
+        node_id: NodeId,
+        width: f32,
+        height: f32,
+        baseline: f32,
+        vertical_align: VerticalAlign,
+    ) {
+        self.first_letter = None;
+
         // STEP 1: Check if the inline-block fits on the current line.
         //
         // [§ 9.4.2](https://www.w3.org/TR/CSS2/visuren.html#inline-formatting)
@@ -688,8 +1078,13 @@ impl InlineLayout {
                 width,
                 height,
             },
-            content: FragmentContent::InlineBlock(node_id),
-            vertical_align: VerticalAlign::Baseline,
+            content: FragmentContent::InlineBlock { node_id, baseline },
+            vertical_align: if vertical_align == VerticalAlign::Baseline {
+                self.vertical_align
+            } else {
+                vertical_align
+            },
+            inline_box: self.inline_box,
         };
         self.current_line_fragments.push(fragment);
 
@@ -788,7 +1183,8 @@ impl InlineLayout {
     /// must advance by at least one line-height (the "strut"). This ensures
     /// `<br>` and preserved newlines produce visible vertical space.
     fn force_line_break(&mut self, font_size: f32, font_metrics: &dyn FontMetrics) {
-        let line_height = font_metrics.line_height(font_size);
+        self.first_letter = None;
+        let line_height = self.used_line_height(font_size, font_metrics);
         if line_height > self.current_line_max_height {
             self.current_line_max_height = line_height;
         }
@@ -817,12 +1213,14 @@ impl InlineLayout {
                     color: ColorValue::BLACK,
                     font_weight: 400,
                     font_style: FontStyle::Normal,
-                    text_decoration: TextDecorationLine::default(),
+                    font_family: Vec::new(),
+                    text_decoration: TextDecoration::default(),
                     // A strut carries no visible glyphs, so there's
                     // no inter-character spacing to apply.
                     letter_spacing: 0.0,
                 }),
                 vertical_align: VerticalAlign::Baseline,
+                inline_box: self.inline_box,
             };
             self.current_line_fragments.push(fragment);
         }
@@ -882,26 +1280,38 @@ impl InlineLayout {
         let mut max_ascent: f32 = 0.0;
         let mut max_descent: f32 = 0.0;
 
+        // Fragments aligned to an edge of the line box (or of the parent's
+        // content area) are placed once the baseline is known, so they
+        // only require the line box to be at least as tall as they are.
+        let mut max_edge_aligned_height: f32 = 0.0;
+
         for frag in &self.current_line_fragments {
-            match frag.vertical_align {
-                VerticalAlign::Top | VerticalAlign::Bottom => {
-                    // Top/bottom-aligned fragments don't participate in
-                    // baseline calculation — they are positioned after
-                    // the baseline is established.
+            let (ascent, descent) = Self::fragment_ascent_descent(frag, ASCENDER_RATIO);
+            // The part of the fragment above and below the baseline once
+            // it is aligned.
+            let (ascent, descent) = match frag.vertical_align {
+                VerticalAlign::Top
+                | VerticalAlign::Bottom
+                | VerticalAlign::TextTop
+                | VerticalAlign::TextBottom => {
+                    max_edge_aligned_height = max_edge_aligned_height.max(frag.bounds.height);
+                    continue;
                 }
-                _ => {
-                    let (ascent, descent) = Self::fragment_ascent_descent(frag, ASCENDER_RATIO);
-                    if ascent > max_ascent {
-                        max_ascent = ascent;
-                    }
-                    if descent > max_descent {
-                        max_descent = descent;
-                    }
+                // The midpoint sits on the baseline (see STEP 2), so half
+                // the box is on each side.
+                VerticalAlign::Middle => (frag.bounds.height / 2.0, frag.bounds.height / 2.0),
+                VerticalAlign::Length(offset) => (ascent + offset, descent - offset),
+                VerticalAlign::Baseline | VerticalAlign::Sub | VerticalAlign::Super => {
+                    (ascent, descent)
                 }
-            }
+            };
+            max_ascent = max_ascent.max(ascent);
+            max_descent = max_descent.max(descent);
         }
 
-        let line_height = (max_ascent + max_descent).max(self.current_line_max_height);
+        let line_height = (max_ascent + max_descent)
+            .max(max_edge_aligned_height)
+            .max(self.current_line_max_height);
         let baseline = max_ascent;
 
         // STEP 2: Apply vertical alignment.
@@ -955,12 +1365,28 @@ impl InlineLayout {
             };
         }
 
-        // STEP 3: Apply text-align.
+        // STEP 3: Apply the inline base direction.
+        // [§ 2.1 'direction'](https://www.w3.org/TR/css-writing-modes-4/#direction)
+        //
+        // "rtl: Right-to-left directionality."
+        //
+        // Fragments were placed left to right in content order. For rtl,
+        // mirror them within the occupied part of the line so the first
+        // fragment ends up rightmost; text-align then moves the whole run.
+        let line_width = self.current_x;
+        if self.direction == Direction::Rtl {
+            let line_left = self.start_x + self.left_offset;
+            for frag in &mut self.current_line_fragments {
+                frag.bounds.x =
+                    line_left + line_width - (frag.bounds.x - line_left) - frag.bounds.width;
+            }
+        }
+
+        // STEP 4: Apply text-align.
         // [§ 16.2 Alignment: the 'text-align' property](https://www.w3.org/TR/CSS2/text.html#alignment-prop)
         //
         // "This property describes how inline-level content of a block
         // container is aligned."
-        let line_width = self.current_x;
         let x_offset = match self.text_align {
             // "Inline-level content is aligned to the left line edge."
             //
@@ -981,7 +1407,7 @@ impl InlineLayout {
             }
         }
 
-        // STEP 4: Create line box and advance Y.
+        // STEP 5: Create line box and advance Y.
         let fragments = std::mem::take(&mut self.current_line_fragments);
         let line_box = LineBox {
             bounds: Rect {
@@ -1021,11 +1447,15 @@ impl InlineLayout {
 
         let font_size = match &frag.content {
             FragmentContent::Text(run) => run.font_size,
-            // For non-text fragments, treat the full height as the "font size"
-            // so ascent = height × ascender_ratio.
-            FragmentContent::InlineBox
-            | FragmentContent::ReplacedElement
-            | FragmentContent::InlineBlock(_) => frag_height,
+            // Atomic inlines know where their baseline is; everything below
+            // it is descent.
+            FragmentContent::InlineBlock { baseline, .. } => {
+                let ascent = baseline.clamp(0.0, frag_height);
+                return (ascent, frag_height - ascent);
+            }
+            // For other non-text fragments, treat the full height as the
+            // "font size" so ascent = height × ascender_ratio.
+            FragmentContent::InlineBox | FragmentContent::ReplacedElement => frag_height,
         };
 
         let half_leading = (frag_height - font_size) / 2.0;
@@ -1060,6 +1490,7 @@ impl InlineLayout {
         max_width: f32,
         font_size: f32,
         letter_spacing: f32,
+        font_family: &[FontFamily],
         font_metrics: &dyn FontMetrics,
     ) -> Option<usize> {
         // STEP 1: Find all soft wrap opportunities.
@@ -1080,13 +1511,20 @@ impl InlineLayout {
         // that point fits within max_width.
         let mut prev_was_whitespace = false;
         for (byte_idx, ch) in text.char_indices() {
+            // U+00A0 is not whitespace here, so no opportunity exists on
+            // either side of a no-break space.
             let is_whitespace = ch == ' ' || ch == '\t';
 
             // A break opportunity exists at the transition from whitespace
             // to non-whitespace (i.e., the start of a new word).
             if !is_whitespace && prev_was_whitespace {
-                let prefix_width =
-                    font_metrics.text_width(&text[..byte_idx], font_size, letter_spacing);
+                let prefix_width = measure_text(
+                    &text[..byte_idx],
+                    font_size,
+                    letter_spacing,
+                    font_family,
+                    font_metrics,
+                );
                 if prefix_width <= max_width {
                     last_fitting_break = Some(byte_idx);
                 } else {
@@ -1100,7 +1538,8 @@ impl InlineLayout {
 
         // Also consider breaking at the end of trailing whitespace.
         if prev_was_whitespace {
-            let prefix_width = font_metrics.text_width(text, font_size, letter_spacing);
+            let prefix_width =
+                measure_text(text, font_size, letter_spacing, font_family, font_metrics);
             if prefix_width <= max_width {
                 last_fitting_break = Some(text.len());
             }
@@ -1120,3 +1559,97 @@ impl InlineLayout {
         self.line_boxes.iter().map(|lb| lb.line_height).sum()
     }
 }
+
+/// [§ 5.2 'text-overflow'](https://www.w3.org/TR/css-ui-3/#text-overflow)
+///
+/// Truncate `line` so an ellipsis fits before `line_right` when its
+/// content overflows that edge.
+///
+/// "Implementations must hide characters and atomic inline-level elements
+/// at the end edge of the line as necessary to fit the ellipsis."
+///
+/// The ellipsis takes the style of the text run it is appended to. A line
+/// with no text run to carry it is left unchanged.
+pub(crate) fn apply_ellipsis(line: &mut LineBox, line_right: f32, font_metrics: &dyn FontMetrics) {
+    const ELLIPSIS: char = '\u{2026}';
+
+    // STEP 1: Nothing to do unless some fragment crosses the end edge.
+    if line
+        .fragments
+        .iter()
+        .all(|frag| frag.bounds.x + frag.bounds.width <= line_right)
+    {
+        return;
+    }
+    let Some((font_size, font_family)) =
+        line.fragments.iter().find_map(|frag| match &frag.content {
+            FragmentContent::Text(run) => Some((run.font_size, run.font_family.clone())),
+            _ => None,
+        })
+    else {
+        return;
+    };
+
+    // STEP 2: Hide every fragment after the first one that leaves no room
+    // for the ellipsis.
+    let ellipsis_width = measure_text(
+        &ELLIPSIS.to_string(),
+        font_size,
+        0.0,
+        &font_family,
+        font_metrics,
+    );
+    if let Some(cut) = line
+        .fragments
+        .iter()
+        .position(|frag| frag.bounds.x + frag.bounds.width > line_right - ellipsis_width)
+    {
+        line.fragments.truncate(cut + 1);
+    }
+
+    // STEP 3: Walking back from the end, drop atomic fragments until a text
+    // run is found, then keep as many of its characters as fit alongside the
+    // ellipsis.
+    //
+    // NOTE: A hidden inline-block fragment keeps its laid-out box, which the
+    // block's overflow clip still hides.
+    while let Some(frag) = line.fragments.last_mut() {
+        if let FragmentContent::Text(run) = &mut frag.content {
+            let ellipsis_width = measure_text(
+                &ELLIPSIS.to_string(),
+                run.font_size,
+                run.letter_spacing,
+                &run.font_family,
+                font_metrics,
+            );
+            let available = line_right - frag.bounds.x - ellipsis_width - run.letter_spacing;
+            let mut kept = run
+                .text
+                .char_indices()
+                .map(|(i, c)| i + c.len_utf8())
+                .take_while(|&end| {
+                    measure_text(
+                        &run.text[..end],
+                        run.font_size,
+                        run.letter_spacing,
+                        &run.font_family,
+                        font_metrics,
+                    ) <= available
+                })
+                .last()
+                .map_or_else(String::new, |end| run.text[..end].to_string());
+            kept.push(ELLIPSIS);
+            run.width = measure_text(
+                &kept,
+                run.font_size,
+                run.letter_spacing,
+                &run.font_family,
+                font_metrics,
+            );
+            run.text = kept;
+            frag.bounds.width = run.width;
+            return;
+        }
+        let _ = line.fragments.pop();
+    }
+}
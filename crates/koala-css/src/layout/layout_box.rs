@@ -10,22 +10,28 @@ use std::cell::Cell;
 use koala_dom::{DomTree, NodeId, NodeType};
 
 use crate::style::computed::{
-    AlignItems, AlignSelf, FlexDirection, FlexWrap, GridAutoFlow, GridLine, JustifyContent,
-    ListStyleType, TrackList, Visibility, WhiteSpace,
+    AlignItems, AlignSelf, BorderCollapse, CaptionSide, ContentItem, ContentVisibility,
+    FlexDirection, FlexWrap, GridAutoFlow, GridLine, JustifyContent, ListStyleType, Overflow,
+    TableLayoutAlgorithm, TextOverflow, TrackList, Visibility, WhiteSpace,
 };
 use crate::style::{
-    AutoLength, BorderRadius, BoxShadow, ColorValue, ComputedStyle, DisplayValue,
-    InnerDisplayType, LengthValue, OuterDisplayType,
+    AutoLength, BorderRadius, BoxShadow, ColorValue, ComputedStyle, Direction, DisplayValue,
+    InnerDisplayType, IntrinsicSize, LengthValue, OuterDisplayType,
 };
 
 use crate::style::values::{
-    ClearSide, FloatSide, FontStyle, PositionType, TextAlign, TextDecorationLine,
+    ClearSide, FloatSide, FontFamily, FontStyle, LineHeight, PositionType, TextAlign,
+    TextDecoration, VerticalAlign,
 };
 
 use super::box_model::{BoxDimensions, Rect};
+use super::counters::{CounterState, LIST_ITEM};
 use super::default_display_for_element;
 use super::float::FloatContext;
-use super::inline::{FontMetrics, FragmentContent, InlineLayout, LineBox};
+use super::inline::{
+    FontMetrics, FragmentContent, InlineLayout, LineBox, TextStyle, apply_ellipsis,
+    is_collapsible_space, measure_text,
+};
 use super::positioned::{BoxOffsets, PositionedLayout};
 use super::values::{AutoOr, UnresolvedAutoEdgeSizes, UnresolvedEdgeSizes};
 
@@ -34,6 +40,18 @@ thread_local! {
     static LAYOUT_DEPTH: Cell<usize> = const { Cell::new(0) };
 }
 
+/// The distance, in pixels, that the viewport is grown by on every side
+/// when deciding what is close enough to the screen to prepare ahead of
+/// scrolling.
+///
+/// It serves as both user-agent defined margins: the one around the
+/// viewport in [§ 4.4 Relevant To The User](https://www.w3.org/TR/css-contain-2/#relevant-to-the-user),
+/// which decides whether a `content-visibility: auto` box lays out its
+/// contents, and HTML's lazy load root margin, which decides whether a
+/// `loading=lazy` image is fetched. Sharing one value keeps an image
+/// inside a just-revealed box from being fetched a scroll step late.
+pub const VIEWPORT_MARGIN: f32 = 300.0;
+
 /// [§ 8.3.1 Collapsing margins](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
 ///
 /// "When two or more margins collapse, the resulting margin width is the
@@ -93,7 +111,7 @@ fn layout_inline_content(
     inherited_color: &ColorValue,
     inherited_font_weight: u16,
     inherited_font_style: FontStyle,
-    inherited_text_decoration: TextDecorationLine,
+    inherited_text_decoration: &TextDecoration,
     inherited_letter_spacing: f32,
     viewport: Rect,
     font_metrics: &dyn FontMetrics,
@@ -160,7 +178,7 @@ fn layout_inline_content(
             }
             BoxType::Principal(node_id)
                 if child.display.outer == OuterDisplayType::Inline
-                    && child.display.inner == InnerDisplayType::FlowRoot =>
+                    && (child.display.inner == InnerDisplayType::FlowRoot || child.is_replaced) =>
             {
                 // [§ 10.3.9 'Inline-block', non-replaced elements in normal flow](https://www.w3.org/TR/CSS2/visudet.html#inlineblock-width)
                 //
@@ -171,10 +189,16 @@ fn layout_inline_content(
                 //
                 // "Inline-block elements participate in their parent's inline
                 // formatting context as a single opaque box."
+                //
+                // Inline replaced elements (e.g., `<img>`) are atomic too, but
+                // size themselves from their intrinsic dimensions in
+                // layout_replaced() instead of shrink-to-fit.
                 let node_id = *node_id;
 
                 // STEP 1: Resolve width. If auto, use shrink-to-fit.
-                if child.width.is_none() || matches!(child.width, Some(AutoLength::Auto)) {
+                if !child.is_replaced
+                    && (child.width.is_none() || matches!(child.width, Some(AutoLength::Auto)))
+                {
                     let stf = child.shrink_to_fit_width(content_rect, viewport, font_metrics);
                     child.width = Some(AutoLength::Length(LengthValue::Px(f64::from(stf))));
                 }
@@ -192,12 +216,19 @@ fn layout_inline_content(
 
                 // STEP 3: Record margin box and place on the inline line.
                 let mb = child.dimensions.margin_box();
-                inline_layout.add_inline_block(node_id, mb.width, mb.height);
+                let baseline = child.atomic_inline_baseline();
+                inline_layout.add_inline_block(
+                    node_id,
+                    mb.width,
+                    mb.height,
+                    baseline,
+                    child.vertical_align,
+                );
 
                 // Record the temporary position for post-layout repositioning.
                 inline_block_positions.push((node_id, mb));
             }
-            BoxType::Principal(_) if child.display.outer == OuterDisplayType::Inline => {
+            BoxType::Principal(node_id) if child.display.outer == OuterDisplayType::Inline => {
                 // [§ 9.2.2 Inline-level elements and inline boxes](https://www.w3.org/TR/CSS2/visuren.html#inline-boxes)
                 //
                 // "An inline box is one that is both inline-level and whose
@@ -235,6 +266,20 @@ fn layout_inline_content(
                 // The child element's own font-size and color are used for
                 // its descendants. These were resolved from ComputedStyle
                 // during build_layout_tree().
+                //
+                // [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+                //
+                // A non-baseline 'vertical-align' moves the box's content as
+                // a unit, so its fragments carry the box's alignment.
+                let outer_vertical_align = inline_layout.vertical_align;
+                if child.vertical_align != VerticalAlign::Baseline {
+                    inline_layout.vertical_align = child.vertical_align;
+                }
+                let outer_line_height = inline_layout.line_height;
+                inline_layout.line_height = child.line_height;
+                let outer_font_family =
+                    std::mem::replace(&mut inline_layout.font_family, child.font_family.clone());
+                let outer_inline_box = inline_layout.inline_box.replace(*node_id);
                 layout_inline_content(
                     &mut child.children,
                     inline_layout,
@@ -242,7 +287,7 @@ fn layout_inline_content(
                     &child.color,
                     child.font_weight,
                     child.font_style,
-                    child.text_decoration,
+                    &child.text_decoration,
                     child.letter_spacing,
                     viewport,
                     font_metrics,
@@ -250,6 +295,10 @@ fn layout_inline_content(
                     abs_cb,
                     inline_block_positions,
                 );
+                inline_layout.vertical_align = outer_vertical_align;
+                inline_layout.line_height = outer_line_height;
+                inline_layout.font_family = outer_font_family;
+                inline_layout.inline_box = outer_inline_box;
 
                 // STEP 4: Close the inline box (apply right edge).
                 inline_layout.end_inline_box(right_mbp);
@@ -410,6 +459,12 @@ pub struct LayoutBox {
     /// None means initial (none — no maximum constraint).
     pub max_height: Option<LengthValue>,
 
+    /// [§ 5.1 'aspect-ratio'](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+    ///
+    /// The preferred aspect ratio as width divided by height. `None` means
+    /// `auto`.
+    pub aspect_ratio: Option<f32>,
+
     /// [§ 3.5 'font-size'](https://www.w3.org/TR/css-fonts-4/#font-size-prop)
     ///
     /// "This property indicates the desired height of glyphs from the font."
@@ -434,6 +489,12 @@ pub struct LayoutBox {
     /// establishes an inline formatting context.
     pub text_align: TextAlign,
 
+    /// [§ 2.1 'direction'](https://www.w3.org/TR/css-writing-modes-4/#direction)
+    ///
+    /// The inline base direction. Passed to `InlineLayout` so `rtl` lines
+    /// are filled from the right edge.
+    pub direction: Direction,
+
     /// [§ 3.2 'font-weight'](https://www.w3.org/TR/css-fonts-4/#font-weight-prop)
     ///
     /// "This property specifies the weight of glyphs in the font."
@@ -448,11 +509,20 @@ pub struct LayoutBox {
     /// Inherited from `ComputedStyle`.
     pub font_style: FontStyle,
 
-    /// [§ 3 'text-decoration-line'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
+    /// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+    ///
+    /// "This property specifies a prioritized list of font family names or
+    /// generic family names."
+    ///
+    /// Inherited from `ComputedStyle`. Empty means the renderer's default
+    /// font.
+    pub font_family: Vec<FontFamily>,
+
+    /// [§ 2 Line Decorations](https://www.w3.org/TR/css-text-decoration-3/#line-decoration)
     ///
-    /// "Specifies what line decorations, if any, are added to the element."
+    /// The line, style and used color of this box's text decoration.
     /// Propagated through inline formatting context to text runs.
-    pub text_decoration: TextDecorationLine,
+    pub text_decoration: TextDecoration,
 
     /// [§ 9.3 'letter-spacing'](https://www.w3.org/TR/css-text-3/#letter-spacing-property)
     ///
@@ -462,6 +532,12 @@ pub struct LayoutBox {
     /// inherited value (and ultimately `0.0` for `normal`).
     pub letter_spacing: f32,
 
+    /// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+    ///
+    /// The preferred height of line boxes and of the inline boxes of text
+    /// descended from this box. Inherited through the cascade.
+    pub line_height: LineHeight,
+
     /// [§ 9.4.2 Inline formatting contexts](https://www.w3.org/TR/CSS2/visuren.html#inline-formatting)
     ///
     /// Completed line boxes from inline layout. Populated when this box
@@ -646,12 +722,72 @@ pub struct LayoutBox {
     /// Initial: normal
     pub white_space: WhiteSpace,
 
+    /// [§ 4.2 'tab-size'](https://www.w3.org/TR/css-text-3/#tab-size-property)
+    ///
+    /// "This property determines the tab size used to render preserved tab
+    /// characters (U+0009)."
+    /// Measured in space advances. Initial: 8
+    pub tab_size: u32,
+
+    /// [§ 5.2 'text-overflow'](https://www.w3.org/TR/css-ui-3/#text-overflow)
+    ///
+    /// How overflowing inline content is rendered at the end edge of each
+    /// line box. Resolved to `clip` when 'overflow' is `visible`, since the
+    /// property only applies to blocks that clip their content.
+    pub text_overflow: TextOverflow,
+
+    /// [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+    ///
+    /// How this inline-level box is positioned vertically within its line
+    /// box.
+    pub vertical_align: VerticalAlign,
+
+    /// [§ 17.4.1 'caption-side'](https://www.w3.org/TR/CSS2/tables.html#caption-position)
+    ///
+    /// Whether a `<caption>` box is placed above or below its table.
+    pub caption_side: CaptionSide,
+
+    /// [§ 17.5.2 'table-layout'](https://www.w3.org/TR/CSS2/tables.html#width-layout)
+    ///
+    /// Which algorithm sizes the columns of a table box.
+    pub table_layout: TableLayoutAlgorithm,
+
+    /// [§ 17.6 'border-collapse'](https://www.w3.org/TR/CSS2/tables.html#borders)
+    ///
+    /// Which border model a table box uses.
+    pub border_collapse: BorderCollapse,
+
+    /// [§ 17.6.1 'border-spacing'](https://www.w3.org/TR/CSS2/tables.html#separated-borders)
+    ///
+    /// The (horizontal, vertical) gap between adjoining cell borders of a
+    /// table box, resolved to px.
+    pub border_spacing: (f32, f32),
+
     /// [§ 11.2 'visibility'](https://www.w3.org/TR/CSS2/visufx.html#visibility)
     ///
     /// "Invisible boxes still affect layout."
     /// Initial: visible
     pub visibility: Visibility,
 
+    /// [CSS Containment 2 § 4 'content-visibility'](https://www.w3.org/TR/css-contain-2/#content-visibility)
+    ///
+    /// Whether this box's contents are laid out and painted.
+    /// Initial: visible
+    pub content_visibility: ContentVisibility,
+
+    /// [CSS Sizing 4 § 4.1 'contain-intrinsic-size'](https://www.w3.org/TR/css-sizing-4/#intrinsic-size-override)
+    ///
+    /// The (width, height) in px this box is sized as while its contents
+    /// are skipped; `None` sizes it as if it were empty.
+    pub contain_intrinsic_size: Option<(f32, f32)>,
+
+    /// [CSS Containment 2 § 4.1 Using content-visibility: auto](https://www.w3.org/TR/css-contain-2/#using-cv-auto)
+    ///
+    /// The children `content-visibility` skipped during the last layout.
+    /// They generate no fragments and are moved back into `children` when
+    /// a later layout finds the box relevant to the user again.
+    pub skipped_children: Vec<Self>,
+
     /// [§ 3.2 'opacity'](https://www.w3.org/TR/css-color-4/#transparency)
     ///
     /// "Opacity specifies how to blend the offscreen rendering."
@@ -691,6 +827,18 @@ pub struct LayoutBox {
     /// None for non-list-item boxes or `list-style-type: none`.
     pub marker_text: Option<String>,
 
+    /// [§ 2.1 ::first-line](https://www.w3.org/TR/css-pseudo-4/#first-line-pseudo)
+    ///
+    /// Style of the box's `::first-line`, applied to the text runs of its
+    /// first line box once lines are broken.
+    pub first_line: Option<TextStyle>,
+
+    /// [§ 2.2 ::first-letter](https://www.w3.org/TR/css-pseudo-4/#first-letter-pseudo)
+    ///
+    /// Style of the box's `::first-letter`, which lays out the first letter
+    /// of its inline content as a run of its own.
+    pub first_letter: Option<TextStyle>,
+
     // ===== Table fields =====
     /// [§ 17 Tables](https://www.w3.org/TR/CSS2/tables.html)
     ///
@@ -703,9 +851,70 @@ pub struct LayoutBox {
     ///
     /// HTML `colspan` attribute value. Default: 1.
     pub colspan: u32,
+
+    /// [§ 4.9.11 Attributes common to td and th elements](https://html.spec.whatwg.org/multipage/tables.html#attributes-common-to-td-and-th-elements)
+    ///
+    /// HTML `rowspan` attribute value. Default: 1. Zero means the cell
+    /// spans the rest of its row group.
+    pub rowspan: u32,
 }
 
 impl LayoutBox {
+    // ── Hit testing ────────────────────────────────────────────────────
+
+    /// [CSS 2.1 Appendix E.2 Painting order](https://www.w3.org/TR/CSS2/zindex.html#painting-order)
+    ///
+    /// Find the DOM node whose principal box is topmost at `point`, in the
+    /// same coordinates as the laid-out `dimensions`. Used to route pointer
+    /// events, e.g. to compute the hovered node for `:hover`.
+    ///
+    /// Boxes are visited in reverse painting order, so a box painted later
+    /// (a following sibling, a descendant, or an absolutely positioned box)
+    /// wins over the boxes beneath it. A box is hit when its border box
+    /// contains the point; anonymous boxes are skipped in favor of their
+    /// nearest principal ancestor.
+    ///
+    /// [§ 11.2 Visibility](https://www.w3.org/TR/CSS2/visufx.html#visibility)
+    ///
+    /// Boxes with `visibility: hidden` are not hit, but their visible
+    /// descendants still are.
+    ///
+    /// NOTE: Non-atomic inline boxes (e.g. `<span>`) are only placed as line
+    /// fragments and have no box geometry of their own, so points over them
+    /// hit their containing block instead.
+    #[must_use]
+    pub fn hit_test(&self, point: (f32, f32)) -> Option<NodeId> {
+        // STEP 1: Children painted on top of this box, last painted first.
+        //
+        // Mirrors the display list builder: in-flow children are painted in
+        // tree order, then absolutely positioned children above them.
+        let is_out_of_flow = |child: &&Self| {
+            matches!(
+                child.position_type,
+                PositionType::Absolute | PositionType::Fixed
+            )
+        };
+        let positioned = self.children.iter().rev().filter(is_out_of_flow);
+        let in_flow = self.children.iter().rev().filter(|c| !is_out_of_flow(c));
+        if let Some(hit) = positioned
+            .chain(in_flow)
+            .find_map(|child| child.hit_test(point))
+        {
+            return Some(hit);
+        }
+
+        // STEP 2: This box itself.
+        match self.box_type {
+            BoxType::Principal(node_id)
+                if self.visibility == Visibility::Visible
+                    && self.dimensions.border_box().contains(point.0, point.1) =>
+            {
+                Some(node_id)
+            }
+            _ => None,
+        }
+    }
+
     // ── Margin collapsing helpers ──────────────────────────────────────
 
     /// [§ 8.3.1 Collapsing margins](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
@@ -751,83 +960,117 @@ impl LayoutBox {
 
     // ── List marker helpers ──────────────────────────────────────────
 
-    /// Compute the ordinal position of a list item element among its siblings.
-    ///
-    /// [§ 4.2 Automatic Numbering](https://www.w3.org/TR/css-lists-3/#auto-numbering)
-    ///
-    /// Counts preceding `<li>` siblings (elements only, not text nodes) to
-    /// determine the 1-based ordinal. Also respects:
-    /// - `<ol start="N">` on the parent element
-    /// - `<li value="N">` on the element itself
-    fn compute_list_ordinal(tree: &DomTree, node_id: NodeId) -> i32 {
-        // Check if this <li> has an explicit value attribute
-        if let Some(data) = tree.as_element(node_id)
-            && let Some(val_str) = data.attrs.get("value")
-            && let Ok(val) = val_str.parse::<i32>()
-        {
-            return val;
-        }
-
-        // Get the parent element to check for <ol start="N">
-        let parent_id = tree.parent(node_id);
-        let start = parent_id
-            .and_then(|pid| {
-                if let Some(pdata) = tree.as_element(pid)
-                    && pdata.tag_name.eq_ignore_ascii_case("ol")
-                {
-                    return pdata.attrs.get("start").and_then(|s| s.parse::<i32>().ok());
-                }
-                None
-            })
-            .unwrap_or(1);
-
-        // Count preceding <li> siblings
-        let mut ordinal = start;
-        if let Some(pid) = parent_id {
-            for &sibling_id in tree.children(pid) {
-                if sibling_id == node_id {
-                    break;
-                }
-                if let Some(sib_data) = tree.as_element(sibling_id)
-                    && sib_data.tag_name.eq_ignore_ascii_case("li")
-                {
-                    ordinal += 1;
-                }
-            }
-        }
-
-        ordinal
-    }
-
     /// Generate the marker string for a given list-style-type and ordinal.
     ///
     /// [§ 3.1 'list-style-type'](https://www.w3.org/TR/css-lists-3/#list-style-type)
     fn generate_marker_string(list_style_type: ListStyleType, ordinal: i32) -> String {
         match list_style_type {
-            ListStyleType::Circle => "\u{25CB} ".to_string(),
-            ListStyleType::Square => "\u{25A0} ".to_string(),
-            ListStyleType::Decimal => format!("{ordinal}. "),
-            ListStyleType::LowerAlpha => {
-                let ch = Self::ordinal_to_alpha(ordinal, b'a');
-                format!("{ch}. ")
-            }
-            ListStyleType::UpperAlpha => {
-                let ch = Self::ordinal_to_alpha(ordinal, b'A');
-                format!("{ch}. ")
-            }
-            ListStyleType::LowerRoman => {
-                let roman = Self::ordinal_to_roman(ordinal, false);
-                format!("{roman}. ")
+            ListStyleType::Circle | ListStyleType::Square => {
+                format!(
+                    "{} ",
+                    Self::counter_representation(list_style_type, ordinal)
+                )
             }
-            ListStyleType::UpperRoman => {
-                let roman = Self::ordinal_to_roman(ordinal, true);
-                format!("{roman}. ")
+            ListStyleType::Decimal
+            | ListStyleType::LowerAlpha
+            | ListStyleType::UpperAlpha
+            | ListStyleType::LowerRoman
+            | ListStyleType::UpperRoman => {
+                format!(
+                    "{}. ",
+                    Self::counter_representation(list_style_type, ordinal)
+                )
             }
             // Disc is the default; None should not reach here (filtered earlier)
             ListStyleType::Disc | ListStyleType::None => "\u{2022} ".to_string(),
         }
     }
 
+    /// [§ 1 Counter Styles](https://www.w3.org/TR/css-counter-styles-3/#counter-styles)
+    ///
+    /// "A counter style defines how to convert a counter value into a
+    /// string."
+    ///
+    /// The representation of `value` in a predefined counter style, without
+    /// the suffix a list marker adds. `none` represents every value as the
+    /// empty string.
+    fn counter_representation(style: ListStyleType, value: i32) -> String {
+        match style {
+            ListStyleType::Disc => "\u{2022}".to_string(),
+            ListStyleType::Circle => "\u{25CB}".to_string(),
+            ListStyleType::Square => "\u{25A0}".to_string(),
+            ListStyleType::Decimal => value.to_string(),
+            ListStyleType::LowerAlpha => Self::ordinal_to_alpha(value, b'a'),
+            ListStyleType::UpperAlpha => Self::ordinal_to_alpha(value, b'A'),
+            ListStyleType::LowerRoman => Self::ordinal_to_roman(value, false),
+            ListStyleType::UpperRoman => Self::ordinal_to_roman(value, true),
+            ListStyleType::None => String::new(),
+        }
+    }
+
+    /// [§ 2 Typographic Pseudo-elements](https://www.w3.org/TR/css-pseudo-4/#typographic-pseudos)
+    ///
+    /// Resolve the text properties of a `::first-line` or `::first-letter`
+    /// style, with the same defaults as a principal box.
+    fn pseudo_text_style(style: &ComputedStyle) -> TextStyle {
+        let color = style.color.clone().unwrap_or(ColorValue::BLACK);
+        #[allow(clippy::cast_possible_truncation)]
+        TextStyle {
+            font_size: style
+                .font_size
+                .as_ref()
+                .map_or(16.0, |fs| fs.to_px() as f32),
+            font_weight: style.font_weight.unwrap_or(400),
+            font_style: style.font_style.unwrap_or_default(),
+            text_decoration: TextDecoration {
+                line: style.text_decoration_line.unwrap_or_default(),
+                style: style.text_decoration_style.unwrap_or_default(),
+                color: style
+                    .text_decoration_color
+                    .clone()
+                    .unwrap_or_else(|| color.clone()),
+            },
+            color,
+        }
+    }
+
+    /// [§ 2 'content'](https://www.w3.org/TR/css-content-3/#content-property)
+    ///
+    /// The text a `::before`/`::after` content list generates, with
+    /// counters read from `counters`.
+    ///
+    /// [§ 4.8 Counters in Generated Content](https://www.w3.org/TR/css-lists-3/#counter-functions)
+    ///
+    /// "If no counter of that name exists on the element, then it
+    /// instantiates a new counter of that name with value 0 on the element."
+    fn generated_text(items: &[ContentItem], counters: &mut CounterState) -> String {
+        let mut text = String::new();
+        for item in items {
+            match item {
+                ContentItem::String(s) => text.push_str(s),
+                ContentItem::Counter { name, style } => {
+                    counters.increment(name, 0);
+                    let value = counters.value(name).unwrap_or_default();
+                    text.push_str(&Self::counter_representation(*style, value));
+                }
+                ContentItem::Counters {
+                    name,
+                    separator,
+                    style,
+                } => {
+                    counters.increment(name, 0);
+                    let values: Vec<String> = counters
+                        .values(name)
+                        .into_iter()
+                        .map(|value| Self::counter_representation(*style, value))
+                        .collect();
+                    text.push_str(&values.join(separator));
+                }
+            }
+        }
+        text
+    }
+
     /// Convert an ordinal (1-based) to an alphabetic marker character.
     ///
     /// [§ 7.1 Predefined Counter Styles — alphabetic](https://www.w3.org/TR/css-counter-styles-3/#simple-alphabetic)
@@ -893,7 +1136,8 @@ impl LayoutBox {
 
         // height must be zero or auto.
         let height_zero_or_auto = match &self.height {
-            None | Some(AutoLength::Auto) => true,
+            // Intrinsic block sizes are the content height, as with 'auto'.
+            None | Some(AutoLength::Auto | AutoLength::Intrinsic(_)) => true,
             Some(AutoLength::Length(l)) => l.to_px() == 0.0,
         };
         if !height_zero_or_auto {
@@ -939,7 +1183,13 @@ impl LayoutBox {
     ) -> f32 {
         // Case 1: Text nodes — measure text width on a single line (max-content).
         if let BoxType::AnonymousInline(ref text) = self.box_type {
-            return font_metrics.text_width(text, self.font_size, self.letter_spacing);
+            return measure_text(
+                text,
+                self.font_size,
+                self.letter_spacing,
+                &self.font_family,
+                font_metrics,
+            );
         }
 
         // Case 2: Replaced elements — use intrinsic width or fallback.
@@ -976,6 +1226,10 @@ impl LayoutBox {
             + resolved_border.left
             + resolved_border.right;
 
+        if let Some(width) = self.size_contained_width() {
+            return width + extra;
+        }
+
         if self.children.is_empty() {
             return extra;
         }
@@ -1001,6 +1255,186 @@ impl LayoutBox {
         block_max + extra
     }
 
+    /// Compute intrinsic min-content width without performing full layout.
+    ///
+    /// [§ 5.1 Intrinsic Sizes](https://www.w3.org/TR/css-sizing-3/#intrinsic-sizes)
+    ///
+    /// "The min-content inline size of a box is the smallest inline size it
+    /// can take without overflowing its content, if all soft wrap
+    /// opportunities within the box were taken."
+    ///
+    /// Like [`Self::measure_content_size`], this is a read-only measurement
+    /// that never calls `layout()`. Words are separated at collapsible
+    /// white space, so a no-break space (U+00A0) joins the words on either
+    /// side of it; `white-space: nowrap` and `pre` suppress wrapping, so their inline
+    /// content measures at its max-content width.
+    ///
+    /// NOTE: Each inline child is measured on its own, so a word split
+    /// across two inline boxes (e.g. `foo<b>bar</b>`) counts as two words.
+    #[must_use]
+    pub fn measure_min_content_size(&self, viewport: Rect, font_metrics: &dyn FontMetrics) -> f32 {
+        self.measure_min_content_size_inner(viewport, font_metrics, 0)
+    }
+
+    fn measure_min_content_size_inner(
+        &self,
+        viewport: Rect,
+        font_metrics: &dyn FontMetrics,
+        depth: usize,
+    ) -> f32 {
+        // Case 1: Text nodes — the widest word.
+        if let BoxType::AnonymousInline(ref text) = self.box_type {
+            return text
+                .split_ascii_whitespace()
+                .map(|word| {
+                    measure_text(
+                        word,
+                        self.font_size,
+                        self.letter_spacing,
+                        &self.font_family,
+                        font_metrics,
+                    )
+                })
+                .fold(0.0_f32, f32::max);
+        }
+
+        // Case 2: Replaced elements — use intrinsic width or fallback.
+        if self.is_replaced {
+            return self.intrinsic_width.unwrap_or(300.0);
+        }
+
+        // Case 3: Explicit width — resolve and return.
+        // NOTE: No containing block in intrinsic sizing; percentages resolve to 0.
+        if let Some(ref w) = self.width {
+            let resolved = UnresolvedAutoEdgeSizes::resolve_auto_length(w, viewport, 0.0);
+            if !resolved.is_auto() {
+                return resolved.to_px_or(0.0);
+            }
+        }
+
+        // Depth guard, as in measure_content_size_inner().
+        if depth >= Self::MAX_MEASURE_DEPTH {
+            return 0.0;
+        }
+
+        // Case 4: Auto width — the widest child, plus padding and border.
+        let resolved_padding = self.padding.resolve(viewport, 0.0);
+        let resolved_border = self.border_width.resolve(viewport, 0.0);
+        let extra = resolved_padding.left
+            + resolved_padding.right
+            + resolved_border.left
+            + resolved_border.right;
+
+        if let Some(width) = self.size_contained_width() {
+            return width + extra;
+        }
+
+        // [§ 16.6 'white-space'](https://www.w3.org/TR/CSS2/text.html#white-space-prop)
+        //
+        // 'nowrap' and 'pre' suppress line breaks, so the inline content
+        // cannot get any narrower than a single line.
+        if self.all_children_inline()
+            && matches!(self.white_space, WhiteSpace::Nowrap | WhiteSpace::Pre)
+        {
+            return self.measure_content_size_inner(viewport, font_metrics, depth);
+        }
+
+        let widest = self
+            .children
+            .iter()
+            .map(|c| c.measure_min_content_size_inner(viewport, font_metrics, depth + 1))
+            .fold(0.0_f32, f32::max);
+        widest + extra
+    }
+
+    /// [CSS Containment 2 § 3.2 Size Containment](https://www.w3.org/TR/css-contain-2/#containment-size)
+    ///
+    /// "...the intrinsic sizes of the size containment box are determined
+    /// as if the element had no content."
+    ///
+    /// The content width intrinsic sizing uses while `content-visibility`
+    /// skips this box's contents, taken from `contain-intrinsic-size`.
+    fn size_contained_width(&self) -> Option<f32> {
+        (self.content_visibility == ContentVisibility::Hidden || !self.skipped_children.is_empty())
+            .then(|| self.contain_intrinsic_size.map_or(0.0, |(w, _)| w))
+    }
+
+    /// [§ 3.1 New Keywords for width and height](https://www.w3.org/TR/css-sizing-3/#sizing-values)
+    ///
+    /// The used `width` for an intrinsic sizing keyword, measured in the
+    /// box that `box-sizing` selects.
+    fn intrinsic_width(
+        &self,
+        size: IntrinsicSize,
+        containing_block: Rect,
+        viewport: Rect,
+        font_metrics: &dyn FontMetrics,
+    ) -> f32 {
+        // The measurements include padding and border resolved without a
+        // containing block; subtract them to get content widths.
+        let resolved_padding = self.padding.resolve(viewport, 0.0);
+        let resolved_border = self.border_width.resolve(viewport, 0.0);
+        let extra = resolved_padding.left
+            + resolved_padding.right
+            + resolved_border.left
+            + resolved_border.right;
+        let max_content = self.measure_content_size(viewport, font_metrics) - extra;
+        let min_content = self.measure_min_content_size(viewport, font_metrics) - extra;
+
+        let content_width = match size {
+            IntrinsicSize::MinContent => min_content,
+            IntrinsicSize::MaxContent => max_content,
+            // [CSS Sizing 4 § 3.1](https://www.w3.org/TR/css-sizing-4/#sizing-values)
+            //
+            // "min(max-content size, max(min-content size, stretch-fit size))"
+            IntrinsicSize::FitContent => max_content
+                .min(min_content.max(self.available_content_width(containing_block, viewport))),
+        };
+        let content_width = content_width.max(0.0);
+
+        if self.box_sizing_border_box {
+            content_width + extra
+        } else {
+            content_width
+        }
+    }
+
+    /// [CSS Containment 2 § 4.1 Using content-visibility: auto](https://www.w3.org/TR/css-contain-2/#using-cv-auto)
+    ///
+    /// Whether `content-visibility` skips this box's contents in a layout
+    /// against `viewport`. Must be called once the box is positioned.
+    ///
+    /// [§ 4.4 Relevant To The User](https://www.w3.org/TR/css-contain-2/#relevant-to-the-user)
+    ///
+    /// "The element is 'on-screen': its paint containment box (i.e. its
+    /// overflow clip edge) intersects with the viewport, or a user-agent
+    /// defined margin around the viewport."
+    ///
+    /// The box's height is taken as the larger of its previous layout's
+    /// height and its `contain-intrinsic-size`, and the margin is
+    /// [`VIEWPORT_MARGIN`]. `viewport` is in document coordinates, so its
+    /// origin is the scroll position.
+    ///
+    /// NOTE: Only the vertical extent is compared, and the other ways of
+    /// being relevant (focus, selection, top layer) are not tracked.
+    fn skips_contents(&self, viewport: Rect) -> bool {
+        match self.content_visibility {
+            ContentVisibility::Visible => false,
+            ContentVisibility::Hidden => true,
+            ContentVisibility::Auto => {
+                let d = &self.dimensions;
+                let height = d
+                    .content
+                    .height
+                    .max(self.contain_intrinsic_size.map_or(0.0, |(_, h)| h));
+                let top = d.content.y - d.padding.top - d.border.top;
+                let bottom = d.content.y + height + d.padding.bottom + d.border.bottom;
+                let margin = VIEWPORT_MARGIN;
+                top >= viewport.y + viewport.height + margin || bottom <= viewport.y - margin
+            }
+        }
+    }
+
     /// [§ 9.2 Controlling box generation](https://www.w3.org/TR/CSS2/visuren.html#box-gen)
     ///
     /// "The display property, determines the type of box or boxes that
@@ -1015,6 +1449,29 @@ impl LayoutBox {
         styles: &HashMap<NodeId, ComputedStyle>,
         node_id: NodeId,
         image_dimensions: &HashMap<NodeId, (f32, f32)>,
+    ) -> Option<Self> {
+        Self::build_box(
+            tree,
+            styles,
+            node_id,
+            image_dimensions,
+            &mut CounterState::default(),
+        )
+    }
+
+    /// Build the box for `node_id` and its descendants.
+    ///
+    /// [§ 4 Automatic Numbering With Counters](https://www.w3.org/TR/css-lists-3/#auto-numbering)
+    ///
+    /// Nodes are visited in document order, so `counters` holds the
+    /// counters in scope at each element.
+    #[allow(clippy::too_many_lines)]
+    fn build_box(
+        tree: &DomTree,
+        styles: &HashMap<NodeId, ComputedStyle>,
+        node_id: NodeId,
+        image_dimensions: &HashMap<NodeId, (f32, f32)>,
+        counters: &mut CounterState,
     ) -> Option<Self> {
         let node = tree.get(node_id)?;
 
@@ -1029,13 +1486,15 @@ impl LayoutBox {
             // establishes the root of the layout tree.
             NodeType::Document => {
                 let mut children = Vec::new();
+                let mark = counters.enter_children();
                 for &child_id in tree.children(node_id) {
                     if let Some(child_box) =
-                        Self::build_layout_tree(tree, styles, child_id, image_dimensions)
+                        Self::build_box(tree, styles, child_id, image_dimensions, counters)
                     {
                         children.push(child_box);
                     }
                 }
+                counters.leave_children(mark);
                 Some(Self {
                     box_type: BoxType::Principal(node_id),
                     dimensions: BoxDimensions::default(),
@@ -1051,13 +1510,17 @@ impl LayoutBox {
                     max_width: None,
                     min_height: None,
                     max_height: None,
+                    aspect_ratio: None,
                     font_size: 16.0,
                     color: ColorValue::BLACK,
                     text_align: TextAlign::default(),
+                    direction: Direction::default(),
                     font_weight: 400,
                     font_style: FontStyle::Normal,
-                    text_decoration: TextDecorationLine::default(),
+                    font_family: Vec::new(),
+                    text_decoration: TextDecoration::default(),
                     letter_spacing: 0.0,
+                    line_height: LineHeight::Normal,
                     line_boxes: Vec::new(),
                     collapsed_margin_top: None,
                     collapsed_margin_bottom: None,
@@ -1088,14 +1551,27 @@ impl LayoutBox {
                     float_side: None,
                     clear_side: None,
                     white_space: WhiteSpace::default(),
+                    tab_size: 8,
+                    text_overflow: TextOverflow::default(),
+                    vertical_align: VerticalAlign::default(),
+                    caption_side: CaptionSide::default(),
+                    table_layout: TableLayoutAlgorithm::default(),
+                    border_collapse: BorderCollapse::default(),
+                    border_spacing: (0.0, 0.0),
                     visibility: Visibility::default(),
+                    content_visibility: ContentVisibility::default(),
+                    contain_intrinsic_size: None,
+                    skipped_children: Vec::new(),
                     opacity: 1.0,
                     box_shadow: Vec::new(),
                     border_radius: BorderRadius::default(),
                     list_style_type: None,
                     marker_text: None,
+                    first_line: None,
+                    first_letter: None,
                     tag_name: None,
                     colspan: 1,
+                    rowspan: 1,
                 })
             }
             // [§ 9.2 Controlling box generation](https://www.w3.org/TR/CSS2/visuren.html#box-gen)
@@ -1128,16 +1604,75 @@ impl LayoutBox {
                     .and_then(|s| s.display)
                     .or_else(|| default_display_for_element(&tag))?;
 
-                // Build children recursively
+                // [§ 4 Automatic Numbering With Counters](https://www.w3.org/TR/css-lists-3/#auto-numbering)
+                //
+                // Counters are reset and incremented before the element's
+                // marker and generated content read them.
+                let is_list_item = display.outer == OuterDisplayType::ListItem;
+                counters.apply(style, is_list_item);
+
+                // [§ 4.4.5 The ol element](https://html.spec.whatwg.org/multipage/grouping-content.html#the-ol-element)
+                //
+                // "The start attribute, if present, must be a valid integer
+                // giving the ordinal value of the first list item."
+                if tag == "ol"
+                    && let Some(start) = data.attrs.get("start").and_then(|s| s.parse::<i32>().ok())
+                {
+                    counters.reset(LIST_ITEM, start.saturating_sub(1));
+                }
+                // [§ 4.4.8 The li element](https://html.spec.whatwg.org/multipage/grouping-content.html#the-li-element)
+                //
+                // "The value attribute, if present, must be a valid integer
+                // giving the ordinal value of the list item."
+                if is_list_item
+                    && let Some(value) = data.attrs.get("value").and_then(|v| v.parse::<i32>().ok())
+                {
+                    counters.set(LIST_ITEM, value);
+                }
+                let ordinal = counters.value(LIST_ITEM).unwrap_or(1);
+
+                // [§ 4.1 ::before and ::after](https://www.w3.org/TR/css-pseudo-4/#generated-content)
+                //
+                // "When their computed content value is not none, these
+                // pseudo-elements generate boxes as if they were
+                // immediately-contained children of their originating
+                // element, and can be styled exactly like any normal
+                // document-sourced element in the document tree."
+                //
+                // NOTE: The generated text is laid out as an anonymous
+                // inline box, so it takes the originating element's font and
+                // color rather than the pseudo-element's own. Replaced
+                // elements (`<img>`) have no children for the boxes to join,
+                // so they get none.
+                let generated = style.filter(|_| tag != "img");
                 let mut children = Vec::new();
+                let mark = counters.enter_children();
+                if let Some(before) = generated.and_then(|s| s.before.as_deref())
+                    && let Some(content) = &before.content
+                {
+                    counters.apply(Some(before), false);
+                    let text = Self::generated_text(content, counters);
+                    children.push(Self::anonymous_inline(text));
+                }
+
+                // Build children recursively
                 for &child_id in tree.children(node_id) {
                     if let Some(child_box) =
-                        Self::build_layout_tree(tree, styles, child_id, image_dimensions)
+                        Self::build_box(tree, styles, child_id, image_dimensions, counters)
                     {
                         children.push(child_box);
                     }
                 }
 
+                if let Some(after) = generated.and_then(|s| s.after.as_deref())
+                    && let Some(content) = &after.content
+                {
+                    counters.apply(Some(after), false);
+                    let text = Self::generated_text(content, counters);
+                    children.push(Self::anonymous_inline(text));
+                }
+                counters.leave_children(mark);
+
                 // Extract style values from computed style
                 // [§ 8 Box model](https://www.w3.org/TR/CSS2/box.html)
                 let (margin, padding, border_width, width, height) =
@@ -1165,7 +1700,11 @@ impl LayoutBox {
                 // container is aligned."
                 // "Initial value: a nameless value that acts as 'left' if
                 // 'direction' is 'ltr', 'right' if 'direction' is 'rtl'."
-                let text_align = style.and_then(|s| s.text_align).unwrap_or_default();
+                let direction = style.map(|s| s.direction).unwrap_or_default();
+                let text_align = style.and_then(|s| s.text_align).unwrap_or(match direction {
+                    Direction::Ltr => TextAlign::Left,
+                    Direction::Rtl => TextAlign::Right,
+                });
 
                 // [§ 3.2 'font-weight'](https://www.w3.org/TR/css-fonts-4/#font-weight-prop)
                 //
@@ -1179,11 +1718,28 @@ impl LayoutBox {
                 // be selected."
                 let font_style = style.and_then(|s| s.font_style).unwrap_or_default();
 
-                // [§ 3 'text-decoration-line'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
-                let text_decoration = style
-                    .and_then(|s| s.text_decoration_line)
+                // [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+                let font_family = style
+                    .and_then(|s| s.font_family.clone())
                     .unwrap_or_default();
 
+                // [§ 3 'text-decoration-line'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
+                // [§ 2.2 'text-decoration-style'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-style-property)
+                // [§ 2.3 'text-decoration-color'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-color-property)
+                //
+                // "Initial: currentcolor" — the element's own text color.
+                let text_decoration = TextDecoration {
+                    line: style
+                        .and_then(|s| s.text_decoration_line)
+                        .unwrap_or_default(),
+                    style: style
+                        .and_then(|s| s.text_decoration_style)
+                        .unwrap_or_default(),
+                    color: style
+                        .and_then(|s| s.text_decoration_color.clone())
+                        .unwrap_or_else(|| color.clone()),
+                };
+
                 // [§ 9.3 'letter-spacing'](https://www.w3.org/TR/css-text-3/#letter-spacing-property)
                 //
                 // The cascade already inherits this for elements with a
@@ -1192,6 +1748,9 @@ impl LayoutBox {
                 // `normal` collapses to zero.
                 let letter_spacing = style.and_then(|s| s.letter_spacing).unwrap_or(0.0);
 
+                // [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+                let line_height = style.and_then(|s| s.line_height).unwrap_or_default();
+
                 // [§ 5.1 'flex-direction'](https://www.w3.org/TR/css-flexbox-1/#flex-direction-property)
                 let flex_direction = style.and_then(|s| s.flex_direction).unwrap_or_default();
                 // [§ 8.2 'justify-content'](https://www.w3.org/TR/css-flexbox-1/#justify-content-property)
@@ -1211,8 +1770,38 @@ impl LayoutBox {
 
                 // [§ 16.6 'white-space'](https://www.w3.org/TR/CSS2/text.html#white-space-prop)
                 let white_space = style.and_then(|s| s.white_space).unwrap_or_default();
+                // [§ 4.2 'tab-size'](https://www.w3.org/TR/css-text-3/#tab-size-property)
+                let tab_size = style.and_then(|s| s.tab_size).unwrap_or(8);
+                // [§ 5.2 'text-overflow'](https://www.w3.org/TR/css-ui-3/#text-overflow)
+                //
+                // Lines overflow along the inline axis, horizontal here.
+                let text_overflow = style
+                    .filter(|s| s.overflow_x.is_some_and(|o| o != Overflow::Visible))
+                    .and_then(|s| s.text_overflow)
+                    .unwrap_or_default();
+                // [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+                let vertical_align = style.and_then(|s| s.vertical_align).unwrap_or_default();
+                // [§ 17.4.1 'caption-side'](https://www.w3.org/TR/CSS2/tables.html#caption-position)
+                let caption_side = style.and_then(|s| s.caption_side).unwrap_or_default();
+                // [§ 17.5.2 'table-layout'](https://www.w3.org/TR/CSS2/tables.html#width-layout)
+                let table_layout = style.and_then(|s| s.table_layout).unwrap_or_default();
+                // [§ 17.6 'border-collapse'](https://www.w3.org/TR/CSS2/tables.html#borders)
+                let border_collapse = style.and_then(|s| s.border_collapse).unwrap_or_default();
+                // [§ 17.6.1 'border-spacing'](https://www.w3.org/TR/CSS2/tables.html#separated-borders)
+                #[allow(clippy::cast_possible_truncation)]
+                let border_spacing = style
+                    .and_then(|s| s.border_spacing)
+                    .map_or((0.0, 0.0), |(h, v)| (h.to_px() as f32, v.to_px() as f32));
                 // [§ 11.2 'visibility'](https://www.w3.org/TR/CSS2/visufx.html#visibility)
                 let visibility = style.and_then(|s| s.visibility).unwrap_or_default();
+                // [CSS Containment 2 § 4 'content-visibility'](https://www.w3.org/TR/css-contain-2/#content-visibility)
+                let content_visibility =
+                    style.and_then(|s| s.content_visibility).unwrap_or_default();
+                // [CSS Sizing 4 § 4.1 'contain-intrinsic-size'](https://www.w3.org/TR/css-sizing-4/#intrinsic-size-override)
+                #[allow(clippy::cast_possible_truncation)]
+                let contain_intrinsic_size = style
+                    .and_then(|s| s.contain_intrinsic_size)
+                    .map(|(w, h)| (w.to_px() as f32, h.to_px() as f32));
                 // [§ 3.2 'opacity'](https://www.w3.org/TR/css-color-4/#transparency)
                 let opacity = style.and_then(|s| s.opacity).unwrap_or(1.0);
                 // [§ 6.1 'box-shadow'](https://www.w3.org/TR/css-backgrounds-3/#box-shadow)
@@ -1264,6 +1853,12 @@ impl LayoutBox {
                 let min_height = style.and_then(|s| s.min_height);
                 let max_height = style.and_then(|s| s.max_height);
 
+                // [§ 5.1 'aspect-ratio'](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+                #[allow(clippy::cast_possible_truncation)]
+                let aspect_ratio = style
+                    .and_then(|s| s.aspect_ratio)
+                    .map(|(width, height)| (width / height) as f32);
+
                 // [§ 9.3.1 'position'](https://www.w3.org/TR/CSS2/visuren.html#choose-position)
                 //
                 // "Values: static | relative | absolute | fixed | sticky"
@@ -1320,23 +1915,23 @@ impl LayoutBox {
                 #[allow(clippy::cast_possible_truncation)]
                 let offsets = BoxOffsets {
                     top: style.and_then(|s| s.top.as_ref()).and_then(|al| match al {
-                        AutoLength::Auto => None,
+                        AutoLength::Auto | AutoLength::Intrinsic(_) => None,
                         AutoLength::Length(l) => Some(l.to_px() as f32),
                     }),
                     right: style
                         .and_then(|s| s.right.as_ref())
                         .and_then(|al| match al {
-                            AutoLength::Auto => None,
+                            AutoLength::Auto | AutoLength::Intrinsic(_) => None,
                             AutoLength::Length(l) => Some(l.to_px() as f32),
                         }),
                     bottom: style
                         .and_then(|s| s.bottom.as_ref())
                         .and_then(|al| match al {
-                            AutoLength::Auto => None,
+                            AutoLength::Auto | AutoLength::Intrinsic(_) => None,
                             AutoLength::Length(l) => Some(l.to_px() as f32),
                         }),
                     left: style.and_then(|s| s.left.as_ref()).and_then(|al| match al {
-                        AutoLength::Auto => None,
+                        AutoLength::Auto | AutoLength::Intrinsic(_) => None,
                         AutoLength::Length(l) => Some(l.to_px() as f32),
                     }),
                 };
@@ -1356,10 +1951,13 @@ impl LayoutBox {
                 // intrinsic dimensions and src attribute for layout and paint.
                 let (is_replaced, replaced_src, intrinsic_width, intrinsic_height) = if tag == "img"
                 {
+                    // NOTE: An <img> with only a `srcset` is still replaced;
+                    // the embedder overwrites `replaced_src` with the URL it
+                    // selected from the candidates.
                     let src = data.attrs.get("src").cloned();
                     let dims = image_dimensions.get(&node_id);
                     (
-                        src.is_some(),
+                        src.is_some() || data.attrs.contains_key("srcset"),
                         src,
                         dims.map(|(w, _)| *w),
                         dims.map(|(_, h)| *h),
@@ -1388,15 +1986,13 @@ impl LayoutBox {
                 // [§ 3 Markers](https://www.w3.org/TR/css-lists-3/#markers)
                 //
                 // For `display: list-item`, generate marker text based on the
-                // resolved list-style-type and the ordinal position among siblings.
-                let marker_text = if display.outer == OuterDisplayType::ListItem {
+                // resolved list-style-type and the value of the `list-item`
+                // counter.
+                let marker_text = if is_list_item {
                     let lst = list_style_type.unwrap_or_default();
                     if lst == ListStyleType::None {
                         None
                     } else {
-                        // Determine ordinal position by counting preceding <li> siblings
-                        // in the DOM parent.
-                        let ordinal = Self::compute_list_ordinal(tree, node_id);
                         Some(Self::generate_marker_string(lst, ordinal))
                     }
                 } else {
@@ -1417,13 +2013,17 @@ impl LayoutBox {
                     max_width,
                     min_height,
                     max_height,
+                    aspect_ratio,
                     font_size,
                     color: color.clone(),
                     text_align,
+                    direction,
                     font_weight,
                     font_style,
+                    font_family,
                     text_decoration,
                     letter_spacing,
+                    line_height,
                     line_boxes: Vec::new(),
                     collapsed_margin_top: None,
                     collapsed_margin_bottom: None,
@@ -1454,14 +2054,38 @@ impl LayoutBox {
                     float_side,
                     clear_side,
                     white_space,
+                    tab_size,
+                    text_overflow,
+                    vertical_align,
+                    caption_side,
+                    table_layout,
+                    border_collapse,
+                    border_spacing,
                     visibility,
+                    content_visibility,
+                    contain_intrinsic_size,
+                    skipped_children: Vec::new(),
                     opacity,
                     box_shadow,
                     border_radius,
                     list_style_type,
                     marker_text,
+                    first_line: style
+                        .and_then(|s| s.first_line.as_deref())
+                        .map(Self::pseudo_text_style),
+                    first_letter: style
+                        .and_then(|s| s.first_letter.as_deref())
+                        .map(Self::pseudo_text_style),
                     tag_name: Some(tag),
                     colspan: data.attrs.get("colspan").and_then(|v| v.parse().ok()).unwrap_or(1),
+                    // "The td and th elements may also have a rowspan content
+                    // attribute specified, whose value must be a valid
+                    // non-negative integer less than or equal to 65534."
+                    rowspan: data
+                        .attrs
+                        .get("rowspan")
+                        .and_then(|v| v.parse::<u32>().ok())
+                        .map_or(1, |n| n.min(65534)),
                 })
             }
             // [§ 9.2.1.1 Anonymous inline boxes](https://www.w3.org/TR/CSS2/visuren.html#anonymous-inline)
@@ -1493,80 +2117,226 @@ impl LayoutBox {
                 // "For white-space values 'normal' and 'nowrap', any sequence
                 // of collapsible white space is collapsed."
                 //
-                // Skip whitespace-only text nodes when white-space collapses.
+                // Skip whitespace-only text nodes when white-space collapses,
+                // unless they separate two pieces of inline content: the space
+                // in `<b>a</b> <b>b</b>` must still render. Inline layout
+                // collapses it against any neighbouring spaces.
                 // When white-space preserves (pre, pre-wrap), keep them.
-                if !preserve_whitespace && text.trim().is_empty() {
+                if !preserve_whitespace
+                    && text.chars().all(is_collapsible_space)
+                    && !Self::is_between_inline_content(tree, styles, node_id)
+                {
                     return None;
                 }
-                Some(Self {
-                    box_type: BoxType::AnonymousInline(text.clone()),
-                    dimensions: BoxDimensions::default(),
-                    display: DisplayValue::inline(),
-                    children: Vec::new(),
-                    // Anonymous inline boxes have no margin/padding/border (all None = 0 when resolved)
-                    margin: UnresolvedAutoEdgeSizes::default(),
-                    padding: UnresolvedEdgeSizes::default(),
-                    border_width: UnresolvedEdgeSizes::default(),
-                    width: None,
-                    height: None,
-                    min_width: None,
-                    max_width: None,
-                    min_height: None,
-                    max_height: None,
-                    // [§ 4 Inheritance](https://www.w3.org/TR/css-cascade-4/#inheriting)
-                    //
-                    // Text nodes inherit font-size and color from their parent.
-                    // These defaults are overridden during inline layout by the
-                    // parent's resolved values.
-                    font_size: 16.0,
-                    color: ColorValue::BLACK,
-                    text_align: TextAlign::default(),
-                    font_weight: 400,
-                    font_style: FontStyle::Normal,
-                    text_decoration: TextDecorationLine::default(),
-                    letter_spacing: 0.0,
-                    line_boxes: Vec::new(),
-                    collapsed_margin_top: None,
-                    collapsed_margin_bottom: None,
-                    is_replaced: false,
-                    replaced_src: None,
-                    intrinsic_width: None,
-                    intrinsic_height: None,
-                    flex_direction: FlexDirection::Row,
-                    justify_content: JustifyContent::FlexStart,
-                    align_items: AlignItems::Stretch,
-                    align_self: AlignSelf::Auto,
-                    flex_grow: 0.0,
-                    flex_shrink: 1.0,
-                    flex_basis: None,
-                    flex_wrap: FlexWrap::default(),
-                    grid_template_columns: TrackList::default(),
-                    grid_template_rows: TrackList::default(),
-                    grid_auto_flow: GridAutoFlow::default(),
-                    row_gap: 0.0,
-                    column_gap: 0.0,
-                    grid_column_start: GridLine::Auto,
-                    grid_column_end: GridLine::Auto,
-                    grid_row_start: GridLine::Auto,
-                    grid_row_end: GridLine::Auto,
-                    position_type: PositionType::Static,
-                    offsets: BoxOffsets::default(),
-                    box_sizing_border_box: false,
-                    float_side: None,
-                    clear_side: None,
-                    white_space: WhiteSpace::default(),
-                    visibility: Visibility::default(),
-                    opacity: 1.0,
-                    box_shadow: Vec::new(),
-                    border_radius: BorderRadius::default(),
-                    list_style_type: None,
-                    marker_text: None,
-                    tag_name: None,
-                    colspan: 1,
-                })
+                Some(Self::anonymous_inline(text.clone()))
             }
-            // Comments do not generate boxes and are not part of the render tree.
-            NodeType::Comment(_) => None,
+            // Comments and doctypes do not generate boxes and are not part of
+            // the render tree.
+            NodeType::Comment(_) | NodeType::DocumentType { .. } => None,
+        }
+    }
+
+    /// [§ 4.1.1 Phase I: Collapsing and Transformation](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+    ///
+    /// "Any collapsible space immediately following another collapsible
+    /// space—even one outside the boundary of the inline containing that
+    /// space, provided both spaces are within the same inline formatting
+    /// context—is collapsed to have zero advance width."
+    ///
+    /// Whether the text node `node_id` has inline-level content on both
+    /// sides, so that its white space sits inside a line rather than
+    /// between blocks. Comments, other white space and `display: none`
+    /// elements are skipped, and the search continues past the start or
+    /// end of an enclosing inline box, as in `a<span> </span>b`.
+    fn is_between_inline_content(
+        tree: &DomTree,
+        styles: &HashMap<NodeId, ComputedStyle>,
+        node_id: NodeId,
+    ) -> bool {
+        let neighbour_is_inline = |step: fn(&DomTree, NodeId) -> Option<NodeId>| {
+            let mut from = node_id;
+            loop {
+                let mut cursor = step(tree, from);
+                while let Some(id) = cursor {
+                    match tree.get(id).map(|n| &n.node_type) {
+                        Some(NodeType::Text(text)) if !text.chars().all(is_collapsible_space) => {
+                            return true;
+                        }
+                        Some(NodeType::Element(data)) => {
+                            let style = styles.get(&id);
+                            if style.is_some_and(|s| s.display_none) {
+                                cursor = step(tree, id);
+                                continue;
+                            }
+                            // [§ 17.2.1 Anonymous table objects](https://www.w3.org/TR/CSS2/tables.html#anonymous-boxes)
+                            //
+                            // "Anonymous inline boxes that contain only white space and
+                            // are between two immediate siblings each of which is a
+                            // table-non-root box, are treated as if they had
+                            // 'display: none'."
+                            //
+                            // NOTE: Table parts are recognized by tag, the same way
+                            // table layout finds them.
+                            if matches!(
+                                data.tag_name.as_str(),
+                                "caption"
+                                    | "col"
+                                    | "colgroup"
+                                    | "thead"
+                                    | "tbody"
+                                    | "tfoot"
+                                    | "tr"
+                                    | "td"
+                                    | "th"
+                            ) {
+                                return false;
+                            }
+                            let display = style
+                                .and_then(|s| s.display)
+                                .or_else(|| default_display_for_element(&data.tag_name));
+                            let out_of_flow = style.is_some_and(|s| {
+                                s.float.is_some()
+                                    || matches!(
+                                        s.position,
+                                        Some(PositionType::Absolute | PositionType::Fixed)
+                                    )
+                            });
+                            return display.is_some_and(|d| d.outer == OuterDisplayType::Inline)
+                                && !out_of_flow;
+                        }
+                        Some(_) => cursor = step(tree, id),
+                        None => return false,
+                    }
+                }
+                // [§ 9.2.2 Inline-level elements and inline boxes](https://www.w3.org/TR/CSS2/visuren.html#inline-boxes)
+                //
+                // The contents of an inline box take part in the enclosing
+                // inline formatting context, so past the edge of one the
+                // neighbours of the box itself are next.
+                match tree.parent(from) {
+                    Some(parent) if Self::is_inline_box_element(tree, styles, parent) => {
+                        from = parent;
+                    }
+                    _ => return false,
+                }
+            }
+        };
+        neighbour_is_inline(DomTree::prev_sibling) && neighbour_is_inline(DomTree::next_sibling)
+    }
+
+    /// Whether `node_id` is an element generating an in-flow inline box,
+    /// whose contents join its parent's inline formatting context.
+    fn is_inline_box_element(
+        tree: &DomTree,
+        styles: &HashMap<NodeId, ComputedStyle>,
+        node_id: NodeId,
+    ) -> bool {
+        let Some(data) = tree.as_element(node_id) else {
+            return false;
+        };
+        let style = styles.get(&node_id);
+        let display = style
+            .and_then(|s| s.display)
+            .or_else(|| default_display_for_element(&data.tag_name));
+        let out_of_flow = style.is_some_and(|s| {
+            s.float.is_some()
+                || matches!(
+                    s.position,
+                    Some(PositionType::Absolute | PositionType::Fixed)
+                )
+        });
+        display.is_some_and(|d| {
+            d.outer == OuterDisplayType::Inline && d.inner == InnerDisplayType::Flow
+        }) && !out_of_flow
+    }
+
+    /// [§ 9.2.1.1 Anonymous inline boxes](https://www.w3.org/TR/CSS2/visuren.html#anonymous-inline)
+    ///
+    /// An anonymous inline box holding `text`. Its font and color are taken
+    /// from the parent during inline layout.
+    fn anonymous_inline(text: String) -> Self {
+        Self {
+            box_type: BoxType::AnonymousInline(text),
+            dimensions: BoxDimensions::default(),
+            display: DisplayValue::inline(),
+            children: Vec::new(),
+            // Anonymous inline boxes have no margin/padding/border (all None = 0 when resolved)
+            margin: UnresolvedAutoEdgeSizes::default(),
+            padding: UnresolvedEdgeSizes::default(),
+            border_width: UnresolvedEdgeSizes::default(),
+            width: None,
+            height: None,
+            min_width: None,
+            max_width: None,
+            min_height: None,
+            max_height: None,
+            aspect_ratio: None,
+            // [§ 4 Inheritance](https://www.w3.org/TR/css-cascade-4/#inheriting)
+            //
+            // Text nodes inherit font-size and color from their parent.
+            // These defaults are overridden during inline layout by the
+            // parent's resolved values.
+            font_size: 16.0,
+            color: ColorValue::BLACK,
+            text_align: TextAlign::default(),
+            direction: Direction::default(),
+            font_weight: 400,
+            font_style: FontStyle::Normal,
+            font_family: Vec::new(),
+            text_decoration: TextDecoration::default(),
+            letter_spacing: 0.0,
+            line_height: LineHeight::Normal,
+            line_boxes: Vec::new(),
+            collapsed_margin_top: None,
+            collapsed_margin_bottom: None,
+            is_replaced: false,
+            replaced_src: None,
+            intrinsic_width: None,
+            intrinsic_height: None,
+            flex_direction: FlexDirection::Row,
+            justify_content: JustifyContent::FlexStart,
+            align_items: AlignItems::Stretch,
+            align_self: AlignSelf::Auto,
+            flex_grow: 0.0,
+            flex_shrink: 1.0,
+            flex_basis: None,
+            flex_wrap: FlexWrap::default(),
+            grid_template_columns: TrackList::default(),
+            grid_template_rows: TrackList::default(),
+            grid_auto_flow: GridAutoFlow::default(),
+            row_gap: 0.0,
+            column_gap: 0.0,
+            grid_column_start: GridLine::Auto,
+            grid_column_end: GridLine::Auto,
+            grid_row_start: GridLine::Auto,
+            grid_row_end: GridLine::Auto,
+            position_type: PositionType::Static,
+            offsets: BoxOffsets::default(),
+            box_sizing_border_box: false,
+            float_side: None,
+            clear_side: None,
+            white_space: WhiteSpace::default(),
+            tab_size: 8,
+            text_overflow: TextOverflow::default(),
+            vertical_align: VerticalAlign::default(),
+            caption_side: CaptionSide::default(),
+            table_layout: TableLayoutAlgorithm::default(),
+            border_collapse: BorderCollapse::default(),
+            border_spacing: (0.0, 0.0),
+            visibility: Visibility::default(),
+            content_visibility: ContentVisibility::default(),
+            contain_intrinsic_size: None,
+            skipped_children: Vec::new(),
+            opacity: 1.0,
+            box_shadow: Vec::new(),
+            border_radius: BorderRadius::default(),
+            list_style_type: None,
+            marker_text: None,
+            first_line: None,
+            first_letter: None,
+            tag_name: None,
+            colspan: 1,
+            rowspan: 1,
         }
     }
 
@@ -1669,6 +2439,9 @@ impl LayoutBox {
     ///
     /// This method lays out this box and all its descendants.
     /// The viewport is needed to resolve viewport-relative units (vw, vh).
+    /// It is the visible part of the page in document coordinates, so a
+    /// scrolled viewport is offset by the scroll position; that offset only
+    /// decides which `content-visibility: auto` boxes are skipped.
     /// [§ 10.1 Definition of containing block](https://www.w3.org/TR/CSS2/visudet.html#containing-block-details)
     ///
     /// `abs_cb` is the padding box of the nearest positioned ancestor.
@@ -1825,6 +2598,19 @@ impl LayoutBox {
         // = width of containing block"
         //
         // For now, we use the full containing block width (auto width behavior).
+        //
+        // [§ 3.1 New Keywords for width and height](https://www.w3.org/TR/css-sizing-3/#sizing-values)
+        //
+        // Intrinsic sizing keywords need the content measured, which
+        // calculate_block_width() cannot do, so they are resolved to a
+        // length here for the duration of the width calculation.
+        //
+        // NOTE: Flex, grid and table layout treat the keywords as 'auto'.
+        let specified_width = self.width;
+        if let Some(AutoLength::Intrinsic(size)) = self.width {
+            let used = self.intrinsic_width(size, containing_block, viewport, font_metrics);
+            self.width = Some(AutoLength::Length(LengthValue::Px(f64::from(used))));
+        }
         self.calculate_block_width(containing_block, viewport);
 
         // [§ 10.4](https://www.w3.org/TR/CSS2/visudet.html#min-max-widths)
@@ -1832,6 +2618,7 @@ impl LayoutBox {
         // Apply min-width/max-width constraints after the tentative width
         // has been calculated.
         self.apply_min_max_width(containing_block, viewport);
+        self.width = specified_width;
 
         // STEP 2: Calculate horizontal position
         // [§ 9.4.1](https://www.w3.org/TR/CSS2/visuren.html#block-formatting)
@@ -1855,6 +2642,34 @@ impl LayoutBox {
             abs_cb
         };
 
+        // [CSS Containment 2 § 4 'content-visibility'](https://www.w3.org/TR/css-contain-2/#content-visibility)
+        //
+        // "hidden: The element skips its contents."
+        // "auto: ...if the element is not relevant to the user, it also
+        // skips its contents."
+        //
+        // Skipped children are set aside instead of laid out, so they
+        // generate no fragments and are neither painted nor hit-tested.
+        // Size containment sizes the box as if its contents were its
+        // `contain-intrinsic-size`. Children skipped by an earlier layout
+        // are laid out on demand once the box is relevant again.
+        if self.skips_contents(viewport) {
+            self.skipped_children.append(&mut self.children);
+            self.line_boxes.clear();
+            self.calculate_block_height(containing_block, viewport, font_metrics);
+            if matches!(
+                self.height,
+                None | Some(AutoLength::Auto | AutoLength::Intrinsic(_))
+            ) && self.aspect_ratio.is_none()
+            {
+                self.dimensions.content.height =
+                    self.contain_intrinsic_size.map_or(0.0, |(_, h)| h);
+            }
+            self.apply_min_max_height(containing_block, viewport);
+            return;
+        }
+        self.children.append(&mut self.skipped_children);
+
         // STEP 3: Generate anonymous block boxes for mixed content.
         // [§ 9.2.1.1 Anonymous block boxes](https://www.w3.org/TR/CSS2/visuren.html#anonymous-block-level)
         //
@@ -2008,6 +2823,27 @@ impl LayoutBox {
             UnresolvedAutoEdgeSizes::resolve_auto_length(al, viewport, containing_block.width)
         });
 
+        // [§ 5.1 'aspect-ratio'](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+        //
+        // "...the box's automatic sizes are calculated using the same
+        // formula as for a replaced element with a natural aspect ratio"
+        //
+        // [§ 10.3.2 Inline, replaced elements](https://www.w3.org/TR/CSS2/visudet.html#inline-replaced-width)
+        //
+        // "If 'width' has a computed value of 'auto', 'height' has some
+        // other computed value, and the element does have an intrinsic
+        // ratio then the used value of 'width' is:
+        //     (used height) * (intrinsic ratio)"
+        //
+        // The ratio applies to the box `box-sizing` selects, so the derived
+        // width goes through the same border-box conversion below.
+        if width.is_auto()
+            && let Some(ratio) = self.aspect_ratio
+            && let Some(height) = self.definite_height(containing_block, viewport)
+        {
+            width = AutoOr::Length(height * ratio);
+        }
+
         // [§ 4.4 box-sizing](https://www.w3.org/TR/css-box-4/#box-sizing)
         //
         // "If box-sizing is border-box, the specified width includes padding
@@ -2146,6 +2982,32 @@ impl LayoutBox {
         self.dimensions.border.right = border_right;
     }
 
+    /// [§ 10.5 Content height](https://www.w3.org/TR/CSS2/visudet.html#the-height-property)
+    ///
+    /// The used value of a specified `height`, or `None` when it is `auto`
+    /// or a percentage that computes to `auto` because the containing
+    /// block's height is not specified explicitly.
+    fn definite_height(&self, containing_block: Rect, viewport: Rect) -> Option<f32> {
+        let Some(AutoLength::Length(l)) = &self.height else {
+            return None;
+        };
+        if matches!(l, LengthValue::Percent(_))
+            && containing_block.height >= f32::MAX / 2.0
+            && !matches!(
+                self.position_type,
+                PositionType::Absolute | PositionType::Fixed
+            )
+        {
+            return None;
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        Some(l.to_px_with_containing_block(
+            f64::from(containing_block.height),
+            f64::from(viewport.width),
+            f64::from(viewport.height),
+        ) as f32)
+    }
+
     /// [§ 9.4.1 Block formatting contexts](https://www.w3.org/TR/CSS2/visuren.html#block-formatting)
     ///
     /// Calculate the position of a block-level box.
@@ -2260,8 +3122,11 @@ impl LayoutBox {
         // "...boxes are laid out one after the other, vertically, beginning at
         // the top of a containing block."
         //
-        // Start at the top of our content box (y = 0 relative to content area,
-        // but we pass absolute coordinates to children).
+        // `current_y` is the bottom border edge of the previous in-flow box
+        // (initially the top of our content box). Margins below it are not
+        // added eagerly; they accumulate in `pending_margin` until the next
+        // box with a border edge is placed, so that every adjoining margin
+        // collapses into one.
         let mut current_y = content_box.y;
 
         // STEP 3: Layout each child with margin collapsing.
@@ -2273,9 +3138,9 @@ impl LayoutBox {
         // "When two or more margins collapse, the resulting margin width is the
         // maximum of the collapsing margins' widths."
         //
-        // Track the previous sibling's bottom margin so we can collapse it
-        // with the current sibling's top margin.
-        let mut prev_margin_bottom: Option<f32> = None;
+        // The collapsed value of the margins adjoining `current_y` so far,
+        // or `None` if no margin has been seen since the last border edge.
+        let mut pending_margin: Option<f32> = None;
 
         // [§ 8.3.1 Parent-child margin collapsing](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
         //
@@ -2283,17 +3148,27 @@ impl LayoutBox {
         // first in-flow block-level child's top margin value if the element
         // has no top border, no top padding, and the child has no clearance."
         //
+        // "Margins of elements that establish new block formatting contexts
+        // (such as floats and elements with 'overflow' other than 'visible')
+        // do not collapse with their in-flow children."
+        //
+        // "Margins of the root element's box do not collapse."
+        //
         // Pre-compute the condition; dimensions.border/padding are already
         // resolved by calculate_block_position() before this method runs.
+        let collapses_with_children = !self.establishes_independent_formatting_context();
         let no_top_separator =
             self.dimensions.border.top == 0.0 && self.dimensions.padding.top == 0.0;
         let parent_margin_top = self.dimensions.margin.top;
-        let child_count = self.children.len();
+        self.collapsed_margin_top = None;
+        self.collapsed_margin_bottom = None;
 
-        // Track whether we've seen the first in-flow child for parent-child
-        // top margin collapsing purposes.
-        let mut first_inflow = true;
+        // While true, margins seen so far adjoin our own top margin and are
+        // carried up to the parent instead of separating our children from
+        // our content edge.
+        let mut collapsing_with_parent_top = collapses_with_children && no_top_separator;
 
+        let child_count = self.children.len();
         for i in 0..child_count {
             let child = &mut self.children[i];
             // [§ 9.3 Positioning schemes](https://www.w3.org/TR/CSS2/visuren.html#positioning-scheme)
@@ -2311,15 +3186,28 @@ impl LayoutBox {
                 continue;
             }
 
+            // The top of the next box's margin area if the pending margins
+            // were resolved now.
+            let flow_y = if collapsing_with_parent_top {
+                current_y
+            } else {
+                current_y + pending_margin.unwrap_or(0.0)
+            };
+
             // [§ 9.5.2 Clear](https://www.w3.org/TR/CSS2/visuren.html#flow-control)
             //
             // "This property indicates which sides of an element's box(es)
             // may not be adjacent to an earlier floating box."
             //
-            // Clear is applied before margin collapsing and before float
-            // placement.
+            // "Clearance inhibits margin collapsing and acts as spacing above
+            // the margin-top of an element."
             if let Some(clear) = child.clear_side {
-                current_y = float_ctx.clear(clear, current_y);
+                let cleared_y = float_ctx.clear(clear, flow_y);
+                if cleared_y > flow_y {
+                    current_y = cleared_y;
+                    pending_margin = None;
+                    collapsing_with_parent_top = false;
+                }
             }
 
             // [§ 9.5 Floats](https://www.w3.org/TR/CSS2/visuren.html#floats)
@@ -2346,9 +3234,10 @@ impl LayoutBox {
 
                 // Layout the float child at a temporary position to determine
                 // its dimensions.
+                let float_y = flow_y.max(current_y);
                 let temp_cb = Rect {
                     x: content_box.x,
-                    y: current_y,
+                    y: float_y,
                     width: content_box.width,
                     height: f32::MAX,
                 };
@@ -2357,7 +3246,7 @@ impl LayoutBox {
                 // Place the float using its margin box dimensions.
                 let child_mb = child.dimensions.margin_box();
                 let placed =
-                    float_ctx.place_float(float_side, child_mb.width, child_mb.height, current_y);
+                    float_ctx.place_float(float_side, child_mb.width, child_mb.height, float_y);
 
                 // Relocate the child from its temporary position to the
                 // placed position. The shift is the difference between
@@ -2374,57 +3263,30 @@ impl LayoutBox {
                 continue;
             }
 
-            // STEP 3a: Parent-first-child top margin collapsing.
-            //
-            // [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
-            //
-            // When this is the first child and the parent has no top
-            // border/padding, the child's top margin collapses with the
-            // parent's top margin. The parent already occupies space for
-            // its own margin-top; we pull current_y up by the child's
-            // margin-top so that calculate_block_position() (which adds
-            // child_mt) places the child flush at the parent's content top.
-            // The parent's effective margin becomes the collapsed value.
-            if first_inflow
-                && no_top_separator
-                && matches!(
-                    child.display.outer,
-                    OuterDisplayType::Block | OuterDisplayType::ListItem
-                )
-            {
-                let child_mt = child.margin.resolve(viewport, content_box.width).top.to_px_or(0.0);
-                current_y -= child_mt;
-                self.collapsed_margin_top = Some(collapse_two_margins(parent_margin_top, child_mt));
+            // Only block-level children take part in parent-child collapsing.
+            if !matches!(
+                child.display.outer,
+                OuterDisplayType::Block | OuterDisplayType::ListItem
+            ) {
+                collapsing_with_parent_top = false;
             }
-            first_inflow = false;
 
-            // STEP 3b: Collapse margins between adjacent siblings.
-            //
-            // [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
-            //
-            // "Two margins are adjoining if and only if:
-            //   - both belong to in-flow block-level boxes that participate
-            //     in the same block formatting context
-            //   - no line boxes, no clearance, no padding and no border
-            //     separate them"
+            // STEP 3a: Lay out the child.
             //
-            // Pre-resolve the child's margin-top from the unresolved value.
-            // This is safe because UnresolvedAutoEdgeSizes::resolve() is a
-            // pure function of the viewport dimensions — identical to what
-            // calculate_block_position() will compute internally.
-            if let Some(prev_mb) = prev_margin_bottom {
-                let child_mt = child.margin.resolve(viewport, content_box.width).top.to_px_or(0.0);
-                let collapsed = collapse_two_margins(prev_mb, child_mt);
-                // current_y already includes the previous child's margin-bottom
-                // (from margin_box().height). The child will add its own
-                // margin-top during calculate_block_position(). Without
-                // collapsing the total gap would be prev_mb + child_mt.
-                // We want the gap to be `collapsed`, so subtract the overlap.
-                let overlap = prev_mb + child_mt - collapsed;
-                current_y -= overlap;
-            }
+            // The child is laid out as if nothing preceded it at current_y;
+            // calculate_block_position() places its border edge below its own
+            // margin-top. It is moved to its collapsed position afterwards,
+            // once its effective (possibly collapsed-through-children) top
+            // margin is known.
+            let child_containing_block = Rect {
+                x: content_box.x,
+                y: current_y,
+                width: content_box.width,
+                height: f32::MAX, // Height is unconstrained for normal flow
+            };
+            child.layout(child_containing_block, viewport, font_metrics, abs_cb);
 
-            // STEP 3c: Handle empty box self-collapsing.
+            // STEP 3b: Handle empty box self-collapsing.
             //
             // [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
             //
@@ -2434,49 +3296,77 @@ impl LayoutBox {
             // and it contains no in-flow content."
             //
             // An empty box takes up zero content height; its top and bottom
-            // margins collapse into a single margin that participates in
-            // sibling collapsing with its neighbours.
+            // margins collapse into a single margin that joins the pending
+            // margins, so the boxes on either side of it collapse through it.
             if child.is_empty_collapsible_box() {
-                let child_margin_top = child.margin.resolve(viewport, content_box.width).top.to_px_or(0.0);
-                let child_margin_bottom = child.margin.resolve(viewport, content_box.width).bottom.to_px_or(0.0);
-                let self_collapsed = collapse_two_margins(child_margin_top, child_margin_bottom);
+                let self_collapsed = collapse_two_margins(
+                    child.dimensions.margin.top,
+                    child.dimensions.margin.bottom,
+                );
+                pending_margin = Some(pending_margin.map_or(self_collapsed, |pending| {
+                    collapse_two_margins(pending, self_collapsed)
+                }));
 
-                // Lay out the child so its dimensions are resolved (even
-                // though it has zero content).
-                let child_containing_block = Rect {
-                    x: content_box.x,
-                    y: current_y,
-                    width: content_box.width,
-                    height: f32::MAX,
+                // "The position of the element's top border edge is the same
+                // as it would have been if the element had a non-zero bottom
+                // border."
+                let top = if collapsing_with_parent_top {
+                    current_y
+                } else {
+                    current_y + pending_margin.unwrap_or(0.0)
                 };
-                child.layout(child_containing_block, viewport, font_metrics, abs_cb);
-
-                // The empty box's self-collapsed margin merges with the
-                // accumulated prev_margin_bottom for subsequent sibling
-                // collapsing.
-                prev_margin_bottom = Some(prev_margin_bottom.map_or(self_collapsed, |prev_mb| {
-                    collapse_two_margins(prev_mb, self_collapsed)
-                }));
+                let dy = top - (current_y + child.dimensions.margin.top);
+                if dy != 0.0 {
+                    Self::shift_box_tree(child, 0.0, dy);
+                }
                 continue;
             }
 
-            // STEP 3d: Create containing block and lay out the child.
-            let child_containing_block = Rect {
-                x: content_box.x,
-                y: current_y,
-                width: content_box.width,
-                height: f32::MAX, // Height is unconstrained for normal flow
-            };
+            // STEP 3c: Collapse the child's top margin with the pending margins.
+            //
+            // [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
+            //
+            // "Two margins are adjoining if and only if:
+            //   - both belong to in-flow block-level boxes that participate
+            //     in the same block formatting context
+            //   - no line boxes, no clearance, no padding and no border
+            //     separate them"
+            let child_margin_top = child.effective_margin_top();
+            let collapsed_top = pending_margin.map_or(child_margin_top, |pending| {
+                collapse_two_margins(pending, child_margin_top)
+            });
 
-            child.layout(child_containing_block, viewport, font_metrics, abs_cb);
+            // STEP 3d: Parent-first-child top margin collapsing.
+            //
+            // When the child's top margin adjoins our own, the collapsed
+            // margin becomes ours: the child sits flush at our content top
+            // and our parent spaces us by the combined value.
+            let border_top = if collapsing_with_parent_top {
+                self.collapsed_margin_top =
+                    Some(collapse_two_margins(parent_margin_top, collapsed_top));
+                current_y
+            } else {
+                current_y + collapsed_top
+            };
+            collapsing_with_parent_top = false;
+
+            // Shift relative to where calculate_block_position() placed the
+            // border edge (current_y + own margin-top), so any relative
+            // positioning offset applied during layout is preserved.
+            let dy = border_top - (current_y + child.dimensions.margin.top);
+            if dy != 0.0 {
+                Self::shift_box_tree(child, 0.0, dy);
+            }
 
             // STEP 4: Advance the Y position.
             // [§ 9.4.1](https://www.w3.org/TR/CSS2/visuren.html#block-formatting)
             //
             // "The vertical distance between two sibling boxes is determined by the
             // 'margin' properties."
-            current_y += child.dimensions.margin_box().height;
-            prev_margin_bottom = Some(child.effective_margin_bottom());
+            //
+            // Use the static position: relative offsets do not affect flow.
+            current_y = border_top + child.dimensions.border_box().height;
+            pending_margin = Some(child.effective_margin_bottom());
         }
 
         // STEP 5: Parent-last-child bottom margin collapsing.
@@ -2496,21 +3386,45 @@ impl LayoutBox {
                 PositionType::Absolute | PositionType::Fixed
             )
         });
-        if no_bottom_separator
+        if collapses_with_children
+            && no_bottom_separator
             && self.height.is_none()
             && let Some(last) = last_inflow
             && matches!(
                 last.display.outer,
                 OuterDisplayType::Block | OuterDisplayType::ListItem
             )
+            && let Some(pending) = pending_margin
         {
             let parent_margin_bottom = self.dimensions.margin.bottom;
-            let last_child_mb = last.effective_margin_bottom();
             self.collapsed_margin_bottom =
-                Some(collapse_two_margins(parent_margin_bottom, last_child_mb));
+                Some(collapse_two_margins(parent_margin_bottom, pending));
         }
     }
 
+    /// [§ 9.4.1 Block formatting contexts](https://www.w3.org/TR/CSS2/visuren.html#block-formatting)
+    ///
+    /// "Floats, absolutely positioned elements, block containers (such as
+    /// inline-blocks, table-cells, and table-captions) that are not block
+    /// boxes, and block boxes with 'overflow' other than 'visible' ...
+    /// establish new block formatting contexts for their contents."
+    ///
+    /// The document box and the root element are included too: "Margins of
+    /// the root element's box do not collapse."
+    ///
+    /// NOTE: 'overflow' is not carried on layout boxes, so a block with
+    /// non-visible overflow is not detected here.
+    fn establishes_independent_formatting_context(&self) -> bool {
+        self.float_side.is_some()
+            || matches!(
+                self.position_type,
+                PositionType::Absolute | PositionType::Fixed
+            )
+            || self.display.inner != InnerDisplayType::Flow
+            || matches!(self.box_type, BoxType::Principal(id) if id == NodeId::ROOT)
+            || self.tag_name.as_deref() == Some("html")
+    }
+
     /// [§ 10.6.3 Block-level, non-replaced elements in normal flow when 'overflow' computes to 'visible'](https://www.w3.org/TR/CSS2/visudet.html#normal-block)
     ///
     /// Calculate the height of a block-level box.
@@ -2586,6 +3500,32 @@ impl LayoutBox {
             } // end else (non-percentage or explicit CB height)
         }
 
+        // [§ 5.1 'aspect-ratio'](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+        //
+        // [§ 10.6.2 Inline replaced elements](https://www.w3.org/TR/CSS2/visudet.html#inline-replaced-height)
+        //
+        // "Otherwise, if 'height' has a computed value of 'auto', and the
+        // element has an intrinsic ratio then the used value of 'height' is:
+        //     (used width) / (intrinsic ratio)"
+        //
+        // The ratio applies to the box `box-sizing` selects. Content taller
+        // than the derived height overflows; min-height and max-height
+        // still clamp it afterwards.
+        if let Some(ratio) = self.aspect_ratio {
+            let d = &self.dimensions;
+            let (horizontal, vertical) = if self.box_sizing_border_box {
+                (
+                    d.padding.left + d.padding.right + d.border.left + d.border.right,
+                    d.padding.top + d.padding.bottom + d.border.top + d.border.bottom,
+                )
+            } else {
+                (0.0, 0.0)
+            };
+            self.dimensions.content.height =
+                ((d.content.width + horizontal) / ratio - vertical).max(0.0);
+            return;
+        }
+
         // STEP 2: Handle anonymous inline boxes (text content).
         // [§ 9.2.1.1 Anonymous inline boxes](https://www.w3.org/TR/CSS2/visuren.html#anonymous-inline)
         //
@@ -2601,7 +3541,7 @@ impl LayoutBox {
         // "The height of the inline box encloses all glyphs and their half-leading
         // on each side and is thus exactly 'line-height'."
         if let BoxType::AnonymousInline(ref text) = self.box_type
-            && !text.trim().is_empty()
+            && !text.chars().all(is_collapsible_space)
         {
             // [§ 10.8.1 Leading and half-leading](https://www.w3.org/TR/CSS2/visudet.html#leading)
             //
@@ -2668,20 +3608,22 @@ impl LayoutBox {
             )
         });
         if let Some(last) = last_inflow {
-            let last_mb = last.dimensions.margin_box();
-            let mut height = (last_mb.y + last_mb.height) - self.dimensions.content.y;
+            let last_border_box = last.dimensions.border_box();
+            let mut height =
+                (last_border_box.y + last_border_box.height) - self.dimensions.content.y;
 
             // [§ 8.3.1 Parent-child bottom margin collapsing](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
             //
             // When the last child's bottom margin collapses with the
             // parent's bottom margin, it is no longer part of the parent's
             // content height — it becomes part of the parent's own margin.
-            // Exclude it from the auto height calculation.
-            if self.collapsed_margin_bottom.is_some() {
-                height -= last.effective_margin_bottom();
+            // Otherwise the child's (possibly collapsed) bottom margin is
+            // inside our content box.
+            if self.collapsed_margin_bottom.is_none() {
+                height += last.effective_margin_bottom();
             }
 
-            self.dimensions.content.height = height;
+            self.dimensions.content.height = height.max(0.0);
         }
     }
 
@@ -3021,13 +3963,17 @@ impl LayoutBox {
             max_width: None,
             min_height: None,
             max_height: None,
+            aspect_ratio: None,
             font_size: 16.0,
             color: ColorValue::BLACK,
             text_align: TextAlign::default(),
+            direction: Direction::default(),
             font_weight: 400,
             font_style: FontStyle::Normal,
-            text_decoration: TextDecorationLine::default(),
+            font_family: Vec::new(),
+            text_decoration: TextDecoration::default(),
             letter_spacing: 0.0,
+            line_height: LineHeight::Normal,
             line_boxes: Vec::new(),
             collapsed_margin_top: None,
             collapsed_margin_bottom: None,
@@ -3058,14 +4004,27 @@ impl LayoutBox {
             float_side: None,
             clear_side: None,
             white_space: WhiteSpace::default(),
+            tab_size: 8,
+            text_overflow: TextOverflow::default(),
+            vertical_align: VerticalAlign::default(),
+            caption_side: CaptionSide::default(),
+            table_layout: TableLayoutAlgorithm::default(),
+            border_collapse: BorderCollapse::default(),
+            border_spacing: (0.0, 0.0),
             visibility: Visibility::default(),
+            content_visibility: ContentVisibility::default(),
+            contain_intrinsic_size: None,
+            skipped_children: Vec::new(),
             opacity: 1.0,
             box_shadow: Vec::new(),
             border_radius: BorderRadius::default(),
             list_style_type: None,
             marker_text: None,
+            first_line: None,
+            first_letter: None,
             tag_name: None,
             colspan: 1,
+            rowspan: 1,
         }
     }
 
@@ -3154,7 +4113,9 @@ impl LayoutBox {
         // V1 simplification: query float intrusion once for the entire IFC
         // using the content area's top edge. Per-line queries are a v2
         // enhancement.
-        let line_height = font_metrics.line_height(self.font_size);
+        let line_height = self
+            .line_height
+            .to_px(self.font_size, font_metrics.line_height(self.font_size));
         let (left_offset, avail_width) =
             float_ctx.available_width_at(self.dimensions.content.y, line_height);
 
@@ -3172,6 +4133,9 @@ impl LayoutBox {
             self.text_align,
         );
         inline_layout.left_offset = left_offset;
+        inline_layout.direction = self.direction;
+        inline_layout.line_height = self.line_height;
+        inline_layout.font_family.clone_from(&self.font_family);
 
         // [§ 16.6 'white-space'](https://www.w3.org/TR/CSS2/text.html#white-space-prop)
         //
@@ -3195,6 +4159,29 @@ impl LayoutBox {
             WhiteSpace::Pre | WhiteSpace::PreWrap | WhiteSpace::PreLine
         );
 
+        // [§ 3 White Space and Wrapping: the white-space property](https://www.w3.org/TR/css-text-3/#white-space-property)
+        //
+        // "pre-line: Like normal, this value collapses consecutive white
+        // space characters and allows wrapping, but it preserves segment
+        // breaks in the source as forced line breaks."
+        //
+        // [§ 4.1.1 Phase I: Collapsing and Transformation](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+        //
+        // "If white-space is set to normal, nowrap, or pre-line, white space
+        // characters are considered collapsible..."
+        inline_layout.collapse_spaces = matches!(
+            self.white_space,
+            WhiteSpace::Normal | WhiteSpace::Nowrap | WhiteSpace::PreLine
+        );
+
+        // [§ 4.2 'tab-size'](https://www.w3.org/TR/css-text-3/#tab-size-property)
+        //
+        // Tabs are only preserved (and so expanded to tab stops) under
+        // `pre` and `pre-wrap`; `pre-line` collapses them like spaces.
+        if matches!(self.white_space, WhiteSpace::Pre | WhiteSpace::PreWrap) {
+            inline_layout.tab_size = Some(self.tab_size);
+        }
+
         // STEP 2: Recursively add all inline content to the inline layout.
         // [§ 9.4.2](https://www.w3.org/TR/CSS2/visuren.html#inline-formatting)
         //
@@ -3224,12 +4211,22 @@ impl LayoutBox {
                 &self.color,
                 self.font_weight,
                 self.font_style,
-                self.text_decoration,
+                &self.text_decoration,
                 self.letter_spacing,
                 font_metrics,
             );
         }
 
+        // [§ 2.2 ::first-letter](https://www.w3.org/TR/css-pseudo-4/#first-letter-pseudo)
+        //
+        // "If an element is a list item (display: list-item), the
+        // ::first-letter applies to the first letter in the principal box
+        // after the marker."
+        //
+        // NOTE: The letter is its own run, so a line may break between it
+        // and the rest of its word.
+        inline_layout.first_letter.clone_from(&self.first_letter);
+
         let mut inline_block_positions: Vec<(NodeId, Rect)> = Vec::new();
 
         layout_inline_content(
@@ -3239,7 +4236,7 @@ impl LayoutBox {
             &self.color,
             self.font_weight,
             self.font_style,
-            self.text_decoration,
+            &self.text_decoration,
             self.letter_spacing,
             viewport,
             font_metrics,
@@ -3255,6 +4252,48 @@ impl LayoutBox {
         // final line box.
         inline_layout.finish_line();
 
+        // [§ 2.1 ::first-line](https://www.w3.org/TR/css-pseudo-4/#first-line-pseudo)
+        //
+        // "The ::first-line pseudo-element's generated box behaves similar
+        // to that of an inline-level box, but with certain restrictions."
+        //
+        // Runs on the first line that inherited this box's color take the
+        // pseudo-element's instead, and its text decoration is drawn under
+        // all of them.
+        //
+        // NOTE: Lines are broken before the style applies, so only the
+        // paint-time properties `color` and `text-decoration` are used.
+        // A descendant that sets the same color as this box explicitly is
+        // recolored too.
+        if let Some(first_line) = &self.first_line
+            && let Some(line) = inline_layout.line_boxes.first_mut()
+        {
+            for fragment in &mut line.fragments {
+                if let FragmentContent::Text(run) = &mut fragment.content {
+                    if run.color == self.color {
+                        run.color = first_line.color.clone();
+                    }
+                    if first_line.text_decoration.line.any() {
+                        run.text_decoration = first_line.text_decoration.clone();
+                    }
+                }
+            }
+        }
+
+        // [§ 5.2 'text-overflow'](https://www.w3.org/TR/css-ui-3/#text-overflow)
+        //
+        // "ellipsis: Render an ellipsis character (U+2026) to represent
+        // clipped inline content."
+        //
+        // NOTE: Only the right edge is treated as the end edge, so rtl
+        // lines are left clipped.
+        if self.text_overflow == TextOverflow::Ellipsis && self.direction == Direction::Ltr {
+            let line_right = self.dimensions.content.x + self.dimensions.content.width;
+            for line in &mut inline_layout.line_boxes {
+                apply_ellipsis(line, line_right, font_metrics);
+            }
+        }
+
         // STEP 4: Set content height.
         // [§ 10.6.3](https://www.w3.org/TR/CSS2/visudet.html#normal-block)
         //
@@ -3287,7 +4326,11 @@ impl LayoutBox {
         if !inline_block_positions.is_empty() {
             for line_box in &self.line_boxes {
                 for fragment in &line_box.fragments {
-                    if let FragmentContent::InlineBlock(frag_node_id) = &fragment.content {
+                    if let FragmentContent::InlineBlock {
+                        node_id: frag_node_id,
+                        ..
+                    } = &fragment.content
+                    {
                         // Find the temp position for this node_id.
                         if let Some((_, temp_mb)) = inline_block_positions
                             .iter()
@@ -3340,98 +4383,111 @@ impl LayoutBox {
 
         // STEP 2: Compute intrinsic ratio.
         let intrinsic_ratio = match (self.intrinsic_width, self.intrinsic_height) {
-            (Some(w), Some(h)) if h > 0.0 => Some(w / h),
+            (Some(w), Some(h)) if w > 0.0 && h > 0.0 => Some(w / h),
             _ => None,
         };
 
-        // STEP 3: Resolve width.
-        // [§ 10.3.2](https://www.w3.org/TR/CSS2/visudet.html#inline-replaced-width)
+        // STEP 3: Resolve the specified width and height (None = 'auto').
         //
-        // "If 'width' has a computed value of 'auto', and the element has an
-        // intrinsic width, then that intrinsic width is the used value of 'width'."
-        let width_is_auto = matches!(self.width, None | Some(AutoLength::Auto));
-        let height_is_auto = matches!(self.height, None | Some(AutoLength::Auto));
-
-        let used_width = if width_is_auto {
-            if let Some(iw) = self.intrinsic_width {
-                iw
-            } else if let (Some(ratio), false) = (intrinsic_ratio, height_is_auto) {
-                // "If 'width' has a computed value of 'auto', but none of the
-                // conditions above are met, then the used value of 'width'
-                // becomes... height * ratio"
-                let h = self.height.as_ref().map_or(150.0, |al| {
-                    UnresolvedAutoEdgeSizes::resolve_auto_length(al, viewport, containing_block.height).to_px_or(150.0)
-                });
-                h * ratio
+        // [§ 4.4 box-sizing](https://www.w3.org/TR/css-box-4/#box-sizing)
+        //
+        // "If box-sizing is border-box, the explicit width includes
+        // padding and border. Convert to content width."
+        let horizontal_edges = self.dimensions.padding.left
+            + self.dimensions.padding.right
+            + self.dimensions.border.left
+            + self.dimensions.border.right;
+        let vertical_edges = self.dimensions.padding.top
+            + self.dimensions.padding.bottom
+            + self.dimensions.border.top
+            + self.dimensions.border.bottom;
+        let specified_width = match &self.width {
+            None | Some(AutoLength::Auto) => None,
+            Some(al) => Some(
+                UnresolvedAutoEdgeSizes::resolve_auto_length(al, viewport, containing_block.width)
+                    .to_px_or(300.0),
+            ),
+        }
+        .map(|w| {
+            if self.box_sizing_border_box {
+                (w - horizontal_edges).max(0.0)
             } else {
-                // [§ 10.3.2] Fallback: 300px
-                300.0
+                w
             }
-        } else {
-            let mut w = self.width.as_ref().map_or(300.0, |al| {
-                UnresolvedAutoEdgeSizes::resolve_auto_length(al, viewport, containing_block.width).to_px_or(300.0)
-            });
-            // [§ 4.4 box-sizing](https://www.w3.org/TR/css-box-4/#box-sizing)
-            //
-            // "If box-sizing is border-box, the explicit width includes
-            // padding and border. Convert to content width."
+        });
+        let specified_height = match &self.height {
+            None | Some(AutoLength::Auto) => None,
+            Some(al) => Some(
+                UnresolvedAutoEdgeSizes::resolve_auto_length(al, viewport, containing_block.height)
+                    .to_px_or(150.0),
+            ),
+        }
+        .map(|h| {
             if self.box_sizing_border_box {
-                w -= self.dimensions.padding.left
-                    + self.dimensions.padding.right
-                    + self.dimensions.border.left
-                    + self.dimensions.border.right;
-                w = w.max(0.0);
+                (h - vertical_edges).max(0.0)
+            } else {
+                h
             }
+        });
+
+        // STEP 4: Resolve the used width.
+        // [§ 10.3.2](https://www.w3.org/TR/CSS2/visudet.html#inline-replaced-width)
+        let used_width = if let Some(w) = specified_width {
             w
+        } else if let (None, Some(iw)) = (specified_height, self.intrinsic_width) {
+            // "If 'height' and 'width' both have computed values of 'auto' and
+            // the element also has an intrinsic width, then that intrinsic
+            // width is the used value of 'width'."
+            iw
+        } else if let (Some(ratio), Some(h)) =
+            (intrinsic_ratio, specified_height.or(self.intrinsic_height))
+        {
+            // "If 'height' and 'width' both have computed values of 'auto' and
+            // the element has no intrinsic width, but does have an intrinsic
+            // height and intrinsic ratio; or if 'width' has a computed value of
+            // 'auto', 'height' has some other computed value, and the element
+            // does have an intrinsic ratio; then the used value of 'width' is:
+            // (used height) * (intrinsic ratio)"
+            h * ratio
+        } else {
+            // "Otherwise, if 'width' has a computed value of 'auto', and the
+            // element has an intrinsic width, then that intrinsic width is the
+            // used value of 'width'."
+            //
+            // "Otherwise, if 'width' has a computed value of 'auto', and the
+            // above conditions are not met, then the used value of 'width'
+            // becomes 300px."
+            self.intrinsic_width.unwrap_or(300.0)
         };
 
-        // STEP 4: Resolve height.
+        // STEP 5: Resolve the used height.
         // [§ 10.6.2](https://www.w3.org/TR/CSS2/visudet.html#inline-replaced-height)
-        //
-        // "If 'height' has a computed value of 'auto', and the element has an
-        // intrinsic height, then that intrinsic height is the used value of 'height'."
-        let used_height = if height_is_auto {
-            self.intrinsic_height.map_or_else(
-                || {
-                    // "Otherwise, if 'height' has a computed value of 'auto', and
-                    // the element has an intrinsic ratio then the used value of
-                    // 'height' is: used width / ratio"
-                    intrinsic_ratio.map_or(
-                        // [§ 10.6.2] Fallback: 150px
-                        150.0,
-                        |ratio| {
-                            if ratio > 0.0 {
-                                used_width / ratio
-                            } else {
-                                150.0
-                            }
-                        },
-                    )
-                },
-                |ih| ih,
-            )
+        let used_height = if let Some(h) = specified_height {
+            h
+        } else if let (None, Some(ih)) = (specified_width, self.intrinsic_height) {
+            // "If 'height' and 'width' both have computed values of 'auto' and
+            // the element also has an intrinsic height, then that intrinsic
+            // height is the used value of 'height'."
+            ih
+        } else if let Some(ratio) = intrinsic_ratio {
+            // "Otherwise, if 'height' has a computed value of 'auto', and the
+            // element has an intrinsic ratio then the used value of 'height'
+            // is: (used width) / (intrinsic ratio)"
+            used_width / ratio
         } else {
-            let mut h = self.height.as_ref().map_or(150.0, |al| {
-                UnresolvedAutoEdgeSizes::resolve_auto_length(al, viewport, containing_block.height).to_px_or(150.0)
-            });
-            // [§ 4.4 box-sizing](https://www.w3.org/TR/css-box-4/#box-sizing)
+            // "Otherwise, if 'height' has a computed value of 'auto', and the
+            // element has an intrinsic height, then that intrinsic height is
+            // the used value of 'height'."
             //
-            // "If box-sizing is border-box, the explicit height includes
-            // padding and border. Convert to content height."
-            if self.box_sizing_border_box {
-                h -= self.dimensions.padding.top
-                    + self.dimensions.padding.bottom
-                    + self.dimensions.border.top
-                    + self.dimensions.border.bottom;
-                h = h.max(0.0);
-            }
-            h
+            // NOTE: The final fallback is simplified to 150px rather than the
+            // largest 2:1 rectangle that fits the device width.
+            self.intrinsic_height.unwrap_or(150.0)
         };
 
         self.dimensions.content.width = used_width;
         self.dimensions.content.height = used_height;
 
-        // STEP 5: Position the content box.
+        // STEP 6: Position the content box.
         // [§ 9.4.1](https://www.w3.org/TR/CSS2/visuren.html#block-formatting)
         //
         // Same positioning as calculate_block_position.
@@ -3446,6 +4502,47 @@ impl LayoutBox {
             + self.dimensions.padding.top;
     }
 
+    /// [§ 10.8.1 Leading and half-leading](https://www.w3.org/TR/CSS2/visudet.html#leading)
+    ///
+    /// "The baseline of an 'inline-block' is the baseline of its last line
+    /// box in the normal flow, unless it has either no in-flow line boxes or
+    /// if its 'overflow' property has a computed value other than 'visible',
+    /// in which case the baseline is the bottom margin edge."
+    ///
+    /// Replaced elements have no line boxes, so their baseline is likewise
+    /// the bottom margin edge. Returns the offset from the top of the margin
+    /// box.
+    ///
+    /// NOTE: 'overflow' is not carried on layout boxes, so an inline-block
+    /// with non-visible overflow still uses its last line box.
+    fn atomic_inline_baseline(&self) -> f32 {
+        let margin_box = self.dimensions.margin_box();
+        if self.is_replaced {
+            return margin_box.height;
+        }
+        self.last_line_baseline()
+            .map_or(margin_box.height, |y| y - margin_box.y)
+    }
+
+    /// The absolute y coordinate of the baseline of the last line box in
+    /// this box's normal flow, searching in-flow descendants from the end.
+    fn last_line_baseline(&self) -> Option<f32> {
+        if let Some(line) = self.line_boxes.last() {
+            return Some(line.bounds.y + line.baseline);
+        }
+        self.children
+            .iter()
+            .rev()
+            .filter(|child| {
+                child.float_side.is_none()
+                    && !matches!(
+                        child.position_type,
+                        PositionType::Absolute | PositionType::Fixed
+                    )
+            })
+            .find_map(Self::last_line_baseline)
+    }
+
     /// Recursively shift a box and all its descendants by `(dx, dy)`.
     ///
     /// Used to relocate float children from their temporary layout position
@@ -3545,16 +4642,7 @@ impl LayoutBox {
         //
         // "Find the available width: this is found by solving for 'width'
         // after setting 'left' (in case 2) or 'right' (in case 4) to 0."
-        let resolved_padding = self.padding.resolve(viewport, containing_block.width);
-        let resolved_border = self.border_width.resolve(viewport, containing_block.width);
-        let resolved_margin = self.margin.resolve(viewport, containing_block.width);
-        let available_width = containing_block.width
-            - resolved_margin.left.to_px_or(0.0)
-            - resolved_margin.right.to_px_or(0.0)
-            - resolved_border.left
-            - resolved_border.right
-            - resolved_padding.left
-            - resolved_padding.right;
+        let available_width = self.available_content_width(containing_block, viewport);
 
         // STEP 4: Compute shrink-to-fit width.
         // [§ 10.3.5](https://www.w3.org/TR/CSS2/visudet.html#float-width)
@@ -3566,6 +4654,21 @@ impl LayoutBox {
             .min(preferred_width)
     }
 
+    /// The content width left in `containing_block` after this box's
+    /// margins, borders and padding; 'auto' margins count as 0.
+    fn available_content_width(&self, containing_block: Rect, viewport: Rect) -> f32 {
+        let resolved_padding = self.padding.resolve(viewport, containing_block.width);
+        let resolved_border = self.border_width.resolve(viewport, containing_block.width);
+        let resolved_margin = self.margin.resolve(viewport, containing_block.width);
+        containing_block.width
+            - resolved_margin.left.to_px_or(0.0)
+            - resolved_margin.right.to_px_or(0.0)
+            - resolved_border.left
+            - resolved_border.right
+            - resolved_padding.left
+            - resolved_padding.right
+    }
+
     /// [§ 9.3 Positioning schemes](https://www.w3.org/TR/CSS2/visuren.html#positioning-scheme)
     ///
     /// "In the absolute positioning model, a box is removed from the normal
@@ -14,6 +14,7 @@
 //! # Module Structure
 //!
 //! - [`box_model`] - Box dimensions, rectangles, and edge sizes
+//! - [`counters`] - CSS counters for list numbering and generated content
 //! - [`values`] - Unresolved and auto value types
 //! - [`formatting_context`] - Block and inline formatting contexts
 //! - [`layout_box`] - Layout box types and layout algorithms
@@ -24,6 +25,7 @@
 //! - [`table`] - Table layout algorithm
 
 pub mod box_model;
+mod counters;
 pub mod flex;
 pub mod float;
 pub mod grid;
@@ -31,25 +33,28 @@ pub mod formatting_context;
 pub mod inline;
 pub mod layout_box;
 pub mod positioned;
+pub mod replaced;
 pub mod stacking;
 pub mod table;
 pub mod values;
 
 // Re-exports for convenience.
 pub use crate::style::values::{
-    ClearSide, FloatSide, FontStyle, PositionType, TextAlign, TextDecorationLine,
+    ClearSide, FloatSide, FontStyle, PositionType, TextAlign, TextDecoration, TextDecorationLine,
+    TextDecorationStyle, VerticalAlign,
 };
 pub use box_model::{BoxDimensions, EdgeSizes, Rect};
 pub use float::FloatContext;
 pub use formatting_context::{BlockFormattingContext, InlineFormattingContext};
 pub use inline::{
     ApproximateFontMetrics, FontMetrics, FragmentContent, InlineLayout, LineBox, LineFragment,
-    TextRun, VerticalAlign,
+    TextRun, TextStyle, measure_text, measure_text_runs,
 };
-pub use layout_box::{BoxType, LayoutBox};
+pub use layout_box::{BoxType, VIEWPORT_MARGIN, LayoutBox};
 pub use positioned::{BoxOffsets, PositionedLayout};
+pub use replaced::object_fit_rect;
 pub use stacking::{StackingContext, ZIndex};
-pub use table::TableLayout;
+pub use table::{TableLayout, collapsed_column_edges};
 pub use values::{AutoEdgeSizes, AutoOr, UnresolvedAutoEdgeSizes, UnresolvedEdgeSizes};
 
 use crate::style::DisplayValue;
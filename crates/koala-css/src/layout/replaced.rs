@@ -0,0 +1,87 @@
+//! Replaced element content sizing.
+//!
+//! [CSS Images Module Level 3 § 5 Sizing Objects](https://www.w3.org/TR/css-images-3/#sizing)
+
+use super::box_model::Rect;
+use crate::style::computed::ObjectFit;
+use crate::style::values::BackgroundPosition;
+use crate::style::LengthValue;
+
+/// [§ 5.5 'object-fit'](https://www.w3.org/TR/css-images-3/#the-object-fit)
+///
+/// Compute the rectangle the replaced content is drawn into, given the
+/// element's content box and the content's natural dimensions.
+///
+/// "The 'object-fit' property specifies how the contents of a replaced
+/// element should be fitted to the box established by its used height and
+/// width."
+///
+/// The result may extend past `content_box` (for `cover` and `none`); the
+/// caller is responsible for clipping to the content box.
+///
+/// [§ 5.6 'object-position'](https://www.w3.org/TR/css-images-3/#the-object-position)
+///
+/// "The 'object-position' property determines the alignment of the
+/// replaced element inside its box."
+///
+/// `position` places the fitted content within `content_box`; pass
+/// [`BackgroundPosition::CENTER`] for the initial value.
+#[must_use]
+pub fn object_fit_rect(
+    fit: ObjectFit,
+    position: BackgroundPosition,
+    content_box: Rect,
+    natural_width: f32,
+    natural_height: f32,
+) -> Rect {
+    // Content without a usable natural size has nothing to preserve;
+    // stretching it is the only sensible fit.
+    if natural_width <= 0.0 || natural_height <= 0.0 {
+        return content_box;
+    }
+
+    // [§ 5.3 Concrete Object Size Resolution](https://www.w3.org/TR/css-images-3/#concrete-size-resolution)
+    //
+    // "A contain constraint is resolved by setting the concrete object size
+    // to the largest rectangle that has the object's intrinsic aspect ratio
+    // and additionally has neither width nor height larger than the
+    // constraint rectangle's width and height, respectively."
+    //
+    // "A cover constraint is resolved by setting the concrete object size to
+    // the smallest rectangle that has the object's intrinsic aspect ratio and
+    // additionally has neither width nor height smaller than the constraint
+    // rectangle's width and height, respectively."
+    let scale_x = content_box.width / natural_width;
+    let scale_y = content_box.height / natural_height;
+    let scale = match fit {
+        ObjectFit::Fill => return content_box,
+        ObjectFit::Contain => scale_x.min(scale_y),
+        ObjectFit::Cover => scale_x.max(scale_y),
+        ObjectFit::None => 1.0,
+        // "whichever would result in a smaller concrete object size"
+        ObjectFit::ScaleDown => scale_x.min(scale_y).min(1.0),
+    };
+
+    let width = natural_width * scale;
+    let height = natural_height * scale;
+    Rect {
+        x: content_box.x + position_offset(position.x, content_box.width - width),
+        y: content_box.y + position_offset(position.y, content_box.height - height),
+        width,
+        height,
+    }
+}
+
+/// [§ 5.6 'object-position'](https://www.w3.org/TR/css-images-3/#the-object-position)
+///
+/// Offsets work as in 'background-position': a percentage aligns that
+/// point of the content with the same point of the box, so it is a
+/// percentage of the free space `free`, while a length is an offset from
+/// the box's edge.
+#[allow(clippy::cast_possible_truncation)]
+fn position_offset(offset: LengthValue, free: f32) -> f32 {
+    match offset {
+        LengthValue::Percent(percent) => free * percent as f32 / 100.0,
+        length => length.to_px() as f32,
+    }
+}
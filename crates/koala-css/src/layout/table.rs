@@ -2,16 +2,21 @@
 //!
 //! [§ 17 Tables](https://www.w3.org/TR/CSS2/tables.html)
 //!
-//! This module implements the automatic table layout algorithm (§ 17.5.2):
+//! This module implements the table layout algorithms (§ 17.5.2):
 //! - `<table>`, `<tr>`, `<td>`, `<th>` basic layout
 //! - `<thead>`, `<tbody>`, `<tfoot>` as row groups
-//! - `colspan` attribute
+//! - `colspan` and `rowspan` attributes, placed on a slot grid
+//! - `<caption>` placement per `caption-side`
 //! - Automatic column width sizing
-//! - `border-spacing: 2px` hardcoded
+//! - `table-layout: fixed` column widths from the first row
+//! - `border-spacing` gaps in the separated borders model
 //!
-//! Not yet implemented: `rowspan`, `border-collapse: collapse`, `<caption>`,
-//! `table-layout: fixed`, `vertical-align` within cells.
+//! Not yet implemented: painting for `border-collapse: collapse` (only the
+//! spacing is dropped and the shared edge widths computed, see
+//! [`collapsed_column_edges`]), `<col>` widths, `vertical-align` within
+//! cells.
 
+use crate::style::computed::{BorderCollapse, CaptionSide, TableLayoutAlgorithm};
 use crate::style::{AutoLength, LengthValue};
 
 use super::box_model::Rect;
@@ -25,8 +30,15 @@ use super::layout_box::LayoutBox;
 /// "The 'border-spacing' property specifies the distance that separates
 /// adjoining cell borders."
 ///
-/// Default value per UA stylesheet is 2px.
-const BORDER_SPACING: f32 = 2.0;
+/// The (horizontal, vertical) spacing `container` lays its cells out with.
+/// [§ 17.6.2](https://www.w3.org/TR/CSS2/tables.html#collapsing-borders)
+/// has no spacing: in the collapsing model, adjoining cells share borders.
+const fn border_spacing(container: &LayoutBox) -> (f32, f32) {
+    match container.border_collapse {
+        BorderCollapse::Separate => container.border_spacing,
+        BorderCollapse::Collapse => (0.0, 0.0),
+    }
+}
 
 /// [§ 17.5 Visual layout of table contents](https://www.w3.org/TR/CSS2/tables.html#table-layout)
 ///
@@ -55,6 +67,11 @@ struct CellInfo {
     cell_index: usize,
     /// HTML `colspan` attribute value. Default: 1.
     colspan: u32,
+    /// Number of rows the cell covers, starting at its own. Clamped to the
+    /// end of the row group by [`assign_cell_slots`].
+    rowspan: usize,
+    /// The first grid column the cell occupies, set by [`assign_cell_slots`].
+    column: usize,
 }
 
 /// Main entry point for table layout.
@@ -92,6 +109,7 @@ pub fn layout_table(
     };
 
     let content_box = container.dimensions.content_box();
+    let (spacing_x, spacing_y) = border_spacing(container);
 
     // STEP 3 (§ 17.5.2): Identify table structure.
     //
@@ -103,15 +121,31 @@ pub fn layout_table(
     // Walk container.children and categorize by tag_name:
     // - <thead>, <tbody>, <tfoot> → row groups (walk their children for <tr>)
     // - <tr> → direct rows
-    // - <caption> → deferred (not implemented)
-    let rows = collect_table_rows(container);
+    // - <caption> → placed above or below the rows (see below)
+    let mut rows = collect_table_rows(container);
+    assign_cell_slots(&mut rows);
 
-    if rows.is_empty() {
-        // No rows found — nothing to lay out. Set height to 0.
-        container.dimensions.content.height = 0.0;
-        container.layout_absolute_children(viewport, font_metrics, child_abs_cb);
-        return;
-    }
+    // [§ 17.4 Tables in the visual formatting model](https://www.w3.org/TR/CSS2/tables.html#model)
+    //
+    // "The caption boxes are block-level boxes that retain their own
+    // content, padding, margin, and border areas, and are rendered as
+    // normal block boxes inside the table wrapper box."
+    //
+    // NOTE: There is no separate wrapper box; the table's content box plays
+    // that role. Top captions are stacked at its top edge, the rows follow,
+    // and bottom captions come last, each spanning the table's width.
+    let top_captions_height = layout_captions(
+        container,
+        CaptionSide::Top,
+        content_box,
+        viewport,
+        font_metrics,
+        child_abs_cb,
+    );
+    let grid_box = Rect {
+        y: content_box.y + top_captions_height,
+        ..content_box
+    };
 
     // STEP 4 (§ 17.5.2.1): Determine the column count.
     //
@@ -119,7 +153,16 @@ pub fn layout_table(
     let num_cols = determine_column_count(&rows, container);
 
     if num_cols == 0 {
-        container.dimensions.content.height = 0.0;
+        // No rows found — nothing to lay out besides the captions.
+        let bottom_captions_height = layout_captions(
+            container,
+            CaptionSide::Bottom,
+            grid_box,
+            viewport,
+            font_metrics,
+            child_abs_cb,
+        );
+        container.dimensions.content.height = top_captions_height + bottom_captions_height;
         container.layout_absolute_children(viewport, font_metrics, child_abs_cb);
         return;
     }
@@ -132,14 +175,31 @@ pub fn layout_table(
     // and maximum width of each cell ... For each column, determine a
     // minimum and maximum column width from the cells that span only that
     // column."
-    let column_widths = determine_column_widths(
-        container,
-        &rows,
-        num_cols,
-        content_box.width,
-        viewport,
-        font_metrics,
-    );
+    //
+    // [§ 17.5.2.1 Fixed table layout](https://www.w3.org/TR/CSS2/tables.html#fixed-table-layout)
+    //
+    // "With this (fast) algorithm, the horizontal layout of the table does
+    // not depend on the contents of the cells; it only depends on the
+    // table's width, the width of the columns, and borders or cell spacing."
+    let column_widths = match container.table_layout {
+        TableLayoutAlgorithm::Auto => determine_column_widths(
+            container,
+            &rows,
+            num_cols,
+            content_box.width,
+            spacing_x,
+            viewport,
+            font_metrics,
+        ),
+        TableLayoutAlgorithm::Fixed => determine_fixed_column_widths(
+            container,
+            &rows,
+            num_cols,
+            content_box.width,
+            spacing_x,
+            viewport,
+        ),
+    };
 
     // STEP 6 (§ 17.5.2): Layout cells at determined widths.
     //
@@ -152,6 +212,7 @@ pub fn layout_table(
         &rows,
         &column_widths,
         num_cols,
+        (spacing_x, spacing_y),
         viewport,
         font_metrics,
         child_abs_cb,
@@ -166,7 +227,8 @@ pub fn layout_table(
         &rows,
         &column_widths,
         &row_heights,
-        content_box,
+        (spacing_x, spacing_y),
+        grid_box,
         viewport,
         font_metrics,
         child_abs_cb,
@@ -184,30 +246,80 @@ pub fn layout_table(
     let total_border_spacing_y = if row_heights.is_empty() {
         0.0
     } else {
-        BORDER_SPACING * (row_heights.len() + 1) as f32
+        spacing_y * (row_heights.len() + 1) as f32
     };
     let content_height: f32 = row_heights.iter().sum::<f32>() + total_border_spacing_y;
 
-    if let Some(AutoLength::Length(ref l)) = container.height {
+    let grid_height = if let Some(AutoLength::Length(ref l)) = container.height {
         #[allow(clippy::cast_possible_truncation)]
-        {
-            let explicit_h = l.to_px_with_containing_block(
-                f64::from(containing_block.height),
-                f64::from(viewport.width),
-                f64::from(viewport.height),
-            ) as f32;
-            // Use the larger of explicit height and content height
-            // (tables expand to fit content).
-            container.dimensions.content.height = explicit_h.max(content_height);
-        }
+        let explicit_h = l.to_px_with_containing_block(
+            f64::from(containing_block.height),
+            f64::from(viewport.width),
+            f64::from(viewport.height),
+        ) as f32;
+        // Use the larger of explicit height and content height
+        // (tables expand to fit content).
+        explicit_h.max(content_height)
     } else {
-        container.dimensions.content.height = content_height;
-    }
+        content_height
+    };
+
+    // [§ 17.4.1 Caption position and alignment](https://www.w3.org/TR/CSS2/tables.html#caption-position)
+    //
+    // "bottom: Positions the caption box below the table box."
+    let bottom_captions_height = layout_captions(
+        container,
+        CaptionSide::Bottom,
+        Rect {
+            y: grid_box.y + grid_height,
+            ..grid_box
+        },
+        viewport,
+        font_metrics,
+        child_abs_cb,
+    );
+    container.dimensions.content.height =
+        top_captions_height + grid_height + bottom_captions_height;
 
     // STEP 9: Layout absolutely positioned children.
     container.layout_absolute_children(viewport, font_metrics, child_abs_cb);
 }
 
+/// [§ 17.4.1 Caption position and alignment](https://www.w3.org/TR/CSS2/tables.html#caption-position)
+///
+/// Lay out the table's `<caption>` children whose `caption-side` is `side`
+/// as block boxes stacked downward from the top of `area`, each as wide as
+/// `area`. Returns the total height they occupy.
+fn layout_captions(
+    container: &mut LayoutBox,
+    side: CaptionSide,
+    area: Rect,
+    viewport: Rect,
+    font_metrics: &dyn FontMetrics,
+    abs_cb: Rect,
+) -> f32 {
+    let mut current_y = area.y;
+    for child in &mut container.children {
+        if child.tag_name.as_deref() != Some("caption")
+            || child.caption_side != side
+            || matches!(
+                child.position_type,
+                PositionType::Absolute | PositionType::Fixed
+            )
+        {
+            continue;
+        }
+        let caption_cb = Rect {
+            y: current_y,
+            height: f32::MAX,
+            ..area
+        };
+        child.layout(caption_cb, viewport, font_metrics, abs_cb);
+        current_y += child.dimensions.margin_box().height;
+    }
+    current_y - area.y
+}
+
 /// [§ 17.2 The CSS table model](https://www.w3.org/TR/CSS2/tables.html#table-display)
 ///
 /// Collect table rows from the container's children. Handles both direct
@@ -273,25 +385,77 @@ fn collect_cells_from_row(tr: &LayoutBox) -> Vec<CellInfo> {
             cells.push(CellInfo {
                 cell_index: cell_idx,
                 colspan: cell.colspan.max(1),
+                rowspan: cell.rowspan as usize,
+                column: 0,
             });
         }
     }
     cells
 }
 
-/// [§ 17.5.2.1](https://www.w3.org/TR/CSS2/tables.html#auto-table-layout)
+/// [§ 4.9.12.1 Forming a table](https://html.spec.whatwg.org/multipage/tables.html#forming-a-table)
 ///
-/// Determine the column count: the maximum number of column slots across all
-/// rows, accounting for colspan.
-fn determine_column_count(rows: &[TableRow], container: &LayoutBox) -> usize {
-    let mut max_cols: usize = 0;
-    for row in rows {
-        let mut col_slots: usize = 0;
-        for cell_info in &row.cells {
-            col_slots += cell_info.colspan as usize;
+/// Place every cell on the table's slot grid. A cell takes the first
+/// column of its row not already covered by a `rowspan` cell from an
+/// earlier row, and covers `colspan` columns and `rowspan` rows from there.
+///
+/// "If rowspan is zero ... set cell grows downward to true and set rowspan
+/// to 1." Growing cells and other spans are clamped to the end of their
+/// row group, since "ending a row group" finishes every cell in it.
+fn assign_cell_slots(rows: &mut [TableRow]) {
+    // For each column, how many more rows are covered by a cell that
+    // started in an earlier row.
+    let mut covered: Vec<usize> = Vec::new();
+
+    for row_idx in 0..rows.len() {
+        let group = rows[row_idx].row_group_index;
+        if row_idx > 0 && rows[row_idx - 1].row_group_index != group {
+            covered.clear();
+        }
+        let rows_left_in_group = rows[row_idx..]
+            .iter()
+            .take_while(|row| row.row_group_index == group)
+            .count();
+
+        let mut column: usize = 0;
+        for cell_info in &mut rows[row_idx].cells {
+            while covered.get(column).is_some_and(|&rows_left| rows_left > 0) {
+                column += 1;
+            }
+            cell_info.column = column;
+            cell_info.rowspan = if cell_info.rowspan == 0 {
+                rows_left_in_group
+            } else {
+                cell_info.rowspan.min(rows_left_in_group)
+            };
+
+            let end = column + cell_info.colspan as usize;
+            if covered.len() < end {
+                covered.resize(end, 0);
+            }
+            for rows_left in &mut covered[column..end] {
+                *rows_left = cell_info.rowspan;
+            }
+            column = end;
+        }
+
+        for rows_left in &mut covered {
+            *rows_left = rows_left.saturating_sub(1);
         }
-        max_cols = max_cols.max(col_slots);
     }
+}
+
+/// [§ 17.5.2.1](https://www.w3.org/TR/CSS2/tables.html#auto-table-layout)
+///
+/// Determine the column count: the rightmost column slot any cell
+/// occupies, accounting for colspan and cells shifted by rowspan.
+fn determine_column_count(rows: &[TableRow], container: &LayoutBox) -> usize {
+    let max_cols = rows
+        .iter()
+        .flat_map(|row| &row.cells)
+        .map(|cell_info| cell_info.column + cell_info.colspan as usize)
+        .max()
+        .unwrap_or(0);
     // Also check the table's width attribute hint — but for now, just use row data.
     let _ = container; // suppress unused warning
     max_cols
@@ -308,6 +472,7 @@ fn determine_column_widths(
     rows: &[TableRow],
     num_cols: usize,
     available_width: f32,
+    spacing_x: f32,
     viewport: Rect,
     font_metrics: &dyn FontMetrics,
 ) -> Vec<f32> {
@@ -316,9 +481,9 @@ fn determine_column_widths(
 
     for row in rows {
         let tr = get_tr(container, row);
-        let mut col_cursor: usize = 0;
 
         for cell_info in &row.cells {
+            let col_cursor = cell_info.column;
             let cell = &tr.children[cell_info.cell_index];
             let cell_content_width = cell.measure_content_size(viewport, font_metrics);
 
@@ -345,13 +510,11 @@ fn determine_column_widths(
             }
             // Multi-span cells: distribute later (simplified: skip for now,
             // rely on the shrink-to-fit pass to handle them).
-
-            col_cursor += cell_info.colspan as usize;
         }
     }
 
     // Total border-spacing on the horizontal axis.
-    let total_border_spacing_x = BORDER_SPACING * (num_cols + 1) as f32;
+    let total_border_spacing_x = spacing_x * (num_cols + 1) as f32;
     let max_content_width: f32 = col_max_widths.iter().sum::<f32>() + total_border_spacing_x;
 
     // Determine whether the table has an explicit width.
@@ -404,26 +567,99 @@ fn determine_column_widths(
     col_max_widths.iter().map(|w| w * scale).collect()
 }
 
+/// [§ 17.5.2.1 Fixed table layout](https://www.w3.org/TR/CSS2/tables.html#fixed-table-layout)
+///
+/// "In the fixed table layout algorithm, the width of each column is
+/// determined as follows:
+///  1. A column element with a value other than 'auto' for the 'width'
+///     property sets the width for that column.
+///  2. Otherwise, a cell in the first row with a value other than 'auto'
+///     for the 'width' property determines the width for that column. If
+///     the cell spans more than one column, the width is divided over the
+///     columns.
+///  3. Any remaining columns equally divide the remaining horizontal table
+///     space (minus borders or cell spacing)."
+///
+/// NOTE: `<col>` elements are not parsed yet, so step 1 never applies. The
+/// table is not widened when the columns need more than `available_width`.
+#[allow(clippy::cast_precision_loss)]
+fn determine_fixed_column_widths(
+    container: &LayoutBox,
+    rows: &[TableRow],
+    num_cols: usize,
+    available_width: f32,
+    spacing_x: f32,
+    viewport: Rect,
+) -> Vec<f32> {
+    let mut specified: Vec<Option<f32>> = vec![None; num_cols];
+
+    // STEP 2: Widths of the first row's cells.
+    if let Some(first_row) = rows.first() {
+        let tr = get_tr(container, first_row);
+        for cell_info in &first_row.cells {
+            let col_cursor = cell_info.column;
+            if col_cursor >= num_cols {
+                break;
+            }
+            let span = (cell_info.colspan as usize).min(num_cols - col_cursor);
+            if let Some(ref w) = tr.children[cell_info.cell_index].width {
+                let resolved = super::values::UnresolvedAutoEdgeSizes::resolve_auto_length(
+                    w,
+                    viewport,
+                    available_width,
+                );
+                if !resolved.is_auto() {
+                    let per_col = resolved.to_px_or(0.0) / span as f32;
+                    for column in &mut specified[col_cursor..col_cursor + span] {
+                        *column = Some(per_col);
+                    }
+                }
+            }
+        }
+    }
+
+    // STEP 3: "Any remaining columns equally divide the remaining horizontal
+    // table space (minus borders or cell spacing)."
+    let total_border_spacing_x = spacing_x * (num_cols + 1) as f32;
+    let space_for_columns = (available_width - total_border_spacing_x).max(0.0);
+    let assigned: f32 = specified.iter().flatten().sum();
+    let remaining = (space_for_columns - assigned).max(0.0);
+    let unassigned = specified.iter().filter(|w| w.is_none()).count();
+    if unassigned > 0 {
+        let per_col = remaining / unassigned as f32;
+        return specified.iter().map(|w| w.unwrap_or(per_col)).collect();
+    }
+
+    // "If the table is wider than the columns, the extra space should be
+    // distributed over the columns."
+    let bonus = remaining / num_cols as f32;
+    specified.iter().flatten().map(|w| w + bonus).collect()
+}
+
 /// Layout each cell at its determined column width and measure row heights.
 ///
 /// Returns a vector of row heights (one per row in `rows`).
+#[allow(clippy::too_many_arguments)]
 fn layout_cells_and_measure_row_heights(
     container: &mut LayoutBox,
     rows: &[TableRow],
     column_widths: &[f32],
     num_cols: usize,
+    (spacing_x, spacing_y): (f32, f32),
     viewport: Rect,
     font_metrics: &dyn FontMetrics,
     abs_cb: Rect,
 ) -> Vec<f32> {
     let mut row_heights = Vec::with_capacity(rows.len());
+    // (first row, rowspan, height) of each cell spanning several rows.
+    let mut spanning_cells = Vec::new();
 
-    for row in rows {
+    for (row_idx, row) in rows.iter().enumerate() {
         let tr = get_tr_mut(container, row);
         let mut max_cell_height: f32 = 0.0;
-        let mut col_cursor: usize = 0;
 
         for cell_info in &row.cells {
+            let col_cursor = cell_info.column;
             if col_cursor >= num_cols {
                 break;
             }
@@ -433,7 +669,7 @@ fn layout_cells_and_measure_row_heights(
             // Calculate cell width from column widths + border-spacing for
             // multi-column spans.
             let span = (cell_info.colspan as usize).min(num_cols - col_cursor);
-            let cell_width = cell_span_width(column_widths, col_cursor, span);
+            let cell_width = cell_span_width(column_widths, col_cursor, span, spacing_x);
 
             // Override the cell's width with the resolved column width.
             cell.width = Some(AutoLength::Length(LengthValue::Px(
@@ -450,17 +686,40 @@ fn layout_cells_and_measure_row_heights(
             cell.layout(temp_cb, viewport, font_metrics, abs_cb);
 
             let cell_height = cell.dimensions.margin_box().height;
-            max_cell_height = max_cell_height.max(cell_height);
-
-            col_cursor += span;
+            if cell_info.rowspan > 1 {
+                spanning_cells.push((row_idx, cell_info.rowspan, cell_height));
+            } else {
+                max_cell_height = max_cell_height.max(cell_height);
+            }
         }
 
         row_heights.push(max_cell_height);
     }
 
+    // [§ 17.5.3 Table height algorithms](https://www.w3.org/TR/CSS2/tables.html#height-layout)
+    //
+    // A cell spanning several rows must fit in their combined height; any
+    // shortfall is added to the last row it spans.
+    for (first_row, rowspan, cell_height) in spanning_cells {
+        let last_row = first_row + rowspan - 1;
+        let spanned_height = rows_span_height(&row_heights, first_row, rowspan, spacing_y);
+        if cell_height > spanned_height {
+            row_heights[last_row] += cell_height - spanned_height;
+        }
+    }
+
     row_heights
 }
 
+/// The height of `rowspan` rows starting at `first_row`, including the
+/// border-spacing between them.
+fn rows_span_height(row_heights: &[f32], first_row: usize, rowspan: usize, spacing_y: f32) -> f32 {
+    let spanned = &row_heights[first_row..first_row + rowspan];
+    #[allow(clippy::cast_precision_loss)]
+    let spacing = spacing_y * (spanned.len() - 1) as f32;
+    spanned.iter().sum::<f32>() + spacing
+}
+
 /// Position all cells at their final coordinates.
 ///
 /// [§ 17.5.2](https://www.w3.org/TR/CSS2/tables.html#auto-table-layout)
@@ -473,6 +732,7 @@ fn position_cells(
     rows: &[TableRow],
     column_widths: &[f32],
     row_heights: &[f32],
+    (spacing_x, spacing_y): (f32, f32),
     content_box: Rect,
     viewport: Rect,
     font_metrics: &dyn FontMetrics,
@@ -482,23 +742,23 @@ fn position_cells(
 
     // Precompute column x-offsets (left edge of each column, relative to
     // the table content box).
-    let col_offsets = compute_column_offsets(column_widths, content_box.x);
+    let col_offsets = compute_column_offsets(column_widths, content_box.x, spacing_x);
 
-    let mut current_y = content_box.y + BORDER_SPACING;
+    let mut current_y = content_box.y + spacing_y;
 
     for (row_idx, row) in rows.iter().enumerate() {
         let tr = get_tr_mut(container, row);
         let row_height = row_heights[row_idx];
-        let mut col_cursor: usize = 0;
 
         for cell_info in &row.cells {
+            let col_cursor = cell_info.column;
             if col_cursor >= num_cols {
                 break;
             }
 
             let cell = &mut tr.children[cell_info.cell_index];
             let span = (cell_info.colspan as usize).min(num_cols - col_cursor);
-            let cell_width = cell_span_width(column_widths, col_cursor, span);
+            let cell_width = cell_span_width(column_widths, col_cursor, span, spacing_x);
             let cell_x = col_offsets[col_cursor];
 
             // Override the cell's width for the final layout.
@@ -506,16 +766,15 @@ fn position_cells(
                 f64::from(cell_width),
             )));
 
-            // Layout the cell at its final position.
+            // Layout the cell at its final position. A cell with a rowspan
+            // covers the rows below it as well.
             let cell_cb = Rect {
                 x: cell_x,
                 y: current_y,
                 width: cell_width,
-                height: row_height,
+                height: rows_span_height(row_heights, row_idx, cell_info.rowspan, spacing_y),
             };
             cell.layout(cell_cb, viewport, font_metrics, abs_cb);
-
-            col_cursor += span;
         }
 
         // Also set the <tr> box dimensions so painting traversal works.
@@ -525,7 +784,7 @@ fn position_cells(
         tr.dimensions.content.width = content_box.width;
         tr.dimensions.content.height = row_height;
 
-        current_y += row_height + BORDER_SPACING;
+        current_y += row_height + spacing_y;
     }
 
     // Also set row-group dimensions (<thead>, <tbody>, <tfoot>) to
@@ -573,19 +832,19 @@ fn set_row_group_dimensions(
 ///
 /// Each column starts after border-spacing from the previous column's right
 /// edge (or from the table content box left edge for the first column).
-fn compute_column_offsets(column_widths: &[f32], start_x: f32) -> Vec<f32> {
+fn compute_column_offsets(column_widths: &[f32], start_x: f32, spacing_x: f32) -> Vec<f32> {
     let mut offsets = Vec::with_capacity(column_widths.len());
-    let mut x = start_x + BORDER_SPACING;
+    let mut x = start_x + spacing_x;
     for &w in column_widths {
         offsets.push(x);
-        x += w + BORDER_SPACING;
+        x += w + spacing_x;
     }
     offsets
 }
 
 /// Calculate the width of a cell that spans `span` columns starting at
 /// `col_start`, including intervening border-spacing.
-fn cell_span_width(column_widths: &[f32], col_start: usize, span: usize) -> f32 {
+fn cell_span_width(column_widths: &[f32], col_start: usize, span: usize, spacing_x: f32) -> f32 {
     let col_end = (col_start + span).min(column_widths.len());
     let mut width: f32 = 0.0;
     for col_width in &column_widths[col_start..col_end] {
@@ -594,11 +853,50 @@ fn cell_span_width(column_widths: &[f32], col_start: usize, span: usize) -> f32
     // Add border-spacing between spanned columns (span-1 gaps).
     #[allow(clippy::cast_precision_loss)]
     if span > 1 {
-        width += BORDER_SPACING * (span - 1) as f32;
+        width += spacing_x * (span - 1) as f32;
     }
     width
 }
 
+/// [§ 17.6.2 The collapsing border model](https://www.w3.org/TR/CSS2/tables.html#collapsing-borders)
+///
+/// "In the collapsing border model, it is possible to specify borders that
+/// surround all or part of a cell, row, row group, column, and column
+/// group."
+///
+/// The width of each vertical grid line of `table` once the borders of the
+/// cells on either side of it are collapsed into one: entry `i` is the
+/// line before column `i`, so there is one more entry than columns.
+///
+/// [§ 17.6.2.1 Border conflict resolution](https://www.w3.org/TR/CSS2/tables.html#border-conflict-resolution)
+///
+/// "If none of the styles are 'hidden' and at least one of them is not
+/// 'none', then narrow borders are discarded in favor of wider ones."
+///
+/// NOTE: Only the cells' own borders take part; row, row group and table
+/// borders, and the `hidden` style, are not considered yet.
+#[must_use]
+pub fn collapsed_column_edges(table: &LayoutBox, viewport: Rect) -> Vec<f32> {
+    let mut rows = collect_table_rows(table);
+    assign_cell_slots(&mut rows);
+    let num_cols = determine_column_count(&rows, table);
+
+    let mut edges = vec![0.0_f32; num_cols + 1];
+    for row in &rows {
+        let tr = get_tr(table, row);
+        for cell_info in &row.cells {
+            let border = tr.children[cell_info.cell_index]
+                .border_width
+                .resolve(viewport, 0.0);
+            let start = cell_info.column;
+            let end = start + cell_info.colspan as usize;
+            edges[start] = edges[start].max(border.left);
+            edges[end] = edges[end].max(border.right);
+        }
+    }
+    edges
+}
+
 /// Get an immutable reference to the `<tr>` `LayoutBox` for a given row.
 fn get_tr<'a>(container: &'a LayoutBox, row: &TableRow) -> &'a LayoutBox {
     row.row_group_index.map_or_else(
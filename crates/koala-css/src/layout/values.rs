@@ -124,7 +124,11 @@ impl UnresolvedAutoEdgeSizes {
             // [§ 10.3.3](https://www.w3.org/TR/CSS2/visudet.html#blockwidth)
             //
             // 'auto' is preserved - it will be resolved during width calculation.
-            AutoLength::Auto => AutoOr::Auto,
+            //
+            // Intrinsic sizing keywords are resolved by the layout code that
+            // can measure content (see `LayoutBox::layout_block`); anywhere
+            // else they behave as 'auto'.
+            AutoLength::Auto | AutoLength::Intrinsic(_) => AutoOr::Auto,
             // Resolve length using viewport for vw/vh units and CB width for percentages.
             #[allow(clippy::cast_possible_truncation)]
             AutoLength::Length(len) => AutoOr::Length(
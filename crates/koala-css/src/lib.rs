@@ -39,7 +39,7 @@
 //! - `rgb()`, `hsl()` color functions
 //! - Pseudo-classes and pseudo-elements
 //! - Attribute selectors
-//! - Media queries
+//! - Media features in media queries (only media types are evaluated)
 //! - Full layout algorithm
 
 /// CSS backgrounds per [CSS Backgrounds and Borders Level 3](https://www.w3.org/TR/css-backgrounds-3/).
@@ -48,6 +48,10 @@ pub mod backgrounds;
 pub mod cascade;
 /// Box model and layout structures per [CSS Display Level 3](https://www.w3.org/TR/css-display-3/).
 pub mod layout;
+/// Media query evaluation per [Media Queries Level 4](https://www.w3.org/TR/mediaqueries-4/).
+pub mod media;
+/// `@page` rules per [CSS Paged Media Level 3](https://www.w3.org/TR/css-page-3/).
+pub mod page;
 /// Display list and painting per [CSS 2.1 Appendix E](https://www.w3.org/TR/CSS2/zindex.html).
 pub mod paint;
 /// CSS parser per [§ 5 Parsing](https://www.w3.org/TR/css-syntax-3/#parsing).
@@ -56,6 +60,8 @@ pub mod parser;
 pub mod selector;
 /// Computed style representation per [CSS Cascading Level 4](https://www.w3.org/TR/css-cascade-4/).
 pub mod style;
+/// Feature query evaluation per [CSS Conditional Rules Level 3](https://www.w3.org/TR/css-conditional-3/).
+pub mod supports;
 /// CSS tokenizer per [§ 4 Tokenization](https://www.w3.org/TR/css-syntax-3/#tokenization).
 pub mod tokenizer;
 /// User-agent stylesheet per [WHATWG HTML § 15 Rendering](https://html.spec.whatwg.org/multipage/rendering.html).
@@ -67,24 +73,35 @@ pub mod vendor_prefixes;
 
 // Re-exports for convenience
 pub use backgrounds::canvas_background;
-pub use cascade::compute_styles;
+pub use cascade::{compute_styles, compute_styles_with_context};
 pub use layout::{
     ApproximateFontMetrics, BoxDimensions, BoxType, EdgeSizes, FontMetrics, FontStyle,
-    FragmentContent, LayoutBox, PositionType, Rect, TextDecorationLine, TextRun,
+    FragmentContent, LayoutBox, PositionType, Rect, TextDecoration, TextDecorationLine,
+    TextDecorationStyle, TextRun, TextStyle, VIEWPORT_MARGIN, measure_text, measure_text_runs,
+    object_fit_rect,
+};
+pub use media::{MediaType, media_attribute_matches, media_query_list_matches};
+pub use page::{PageMargins, PagePseudoClass, PageRule, PageSelector, PageSize, page_rules};
+pub use paint::{DisplayCommand, DisplayItemId, DisplayList, DisplayListBuilder};
+pub use parser::{
+    CSSParser, ComponentValue, Declaration, ParseIssue, Rule, Stylesheet, StylesheetStats,
 };
-pub use paint::{DisplayCommand, DisplayList, DisplayListBuilder};
-pub use parser::{CSSParser, ComponentValue, Declaration, Rule, Stylesheet};
 pub use selector::{
-    AttributeSelector, ParsedSelector, PseudoClass, SimpleSelector, Specificity, parse_selector,
+    AnPlusB, AttributeSelector, MatchContext, ParsedSelector, PseudoClass, PseudoElement,
+    SimpleSelector, Specificity, parse_selector,
 };
 pub use style::ComputedStyle;
 pub use style::computed::{
-    AlignItems, AlignSelf, FlexDirection, FlexWrap, GridAutoFlow, GridLine, JustifyContent,
-    ListStyleType, Overflow, TrackList, TrackSize, Visibility, WhiteSpace,
+    AlignItems, AlignSelf, BorderCollapse, CaptionSide, ContentItem, ContentVisibility, Cursor,
+    FlexDirection, FlexWrap, GridAutoFlow, GridLine, JustifyContent, ListStyleType, ObjectFit,
+    Overflow, TableLayoutAlgorithm, TextOverflow, TrackList, TrackSize, Visibility, WhiteSpace,
 };
 pub use style::{
-    AutoLength, BorderRadius, BorderValue, BoxShadow, ColorValue, DEFAULT_FONT_SIZE_PX,
-    DisplayValue, InnerDisplayType, LengthValue, OuterDisplayType,
+    AutoLength, BackgroundImage, BackgroundLayer, BackgroundPosition, BackgroundRepeat,
+    BackgroundSize, BorderRadius, BorderValue, BoxShadow, ChangedProperty, ColorValue,
+    DEFAULT_FONT_SIZE_PX, Direction, DisplayValue, FontFamily, GenericFontFamily, InnerDisplayType,
+    IntrinsicSize, LengthUnit, LengthValue, LineHeight, MathFunction, MathFunctionKind,
+    OuterDisplayType, RepeatStyle, StyleDamage,
 };
 pub use style::values::{parse_letter_spacing, parse_single_length};
 pub use tokenizer::{CSSToken, CSSTokenizer};
@@ -96,6 +113,7 @@ pub use koala_common::url::resolve_url;
 // NOTE: fetch_external_stylesheet() is stubbed with todo!() - implement to enable external CSS
 
 use koala_dom::{DomTree, ElementData, NodeId, NodeType};
+use koala_std::collections::HashSet;
 
 /// [HTML Standard § 4.2.6 The style element](https://html.spec.whatwg.org/multipage/semantics.html#the-style-element)
 ///
@@ -112,7 +130,8 @@ fn collect_style_content(tree: &DomTree, id: NodeId, css: &mut String) {
     let Some(node) = tree.get(id) else { return };
 
     match &node.node_type {
-        NodeType::Element(data) if data.tag_name.eq_ignore_ascii_case("style") => {
+        NodeType::Element(data) if skips_stylesheets(data) => return,
+        NodeType::Element(data) if is_applicable_style_element(data, MediaType::Screen) => {
             // Collect text content of style element
             for &child_id in tree.children(id) {
                 if let Some(text) = tree.as_text(child_id) {
@@ -132,6 +151,42 @@ fn collect_style_content(tree: &DomTree, id: NodeId, css: &mut String) {
     }
 }
 
+/// Whether stylesheets inside `data`'s subtree are ignored.
+///
+/// [§ 4.12.3 The template element](https://html.spec.whatwg.org/multipage/scripting.html#the-template-element)
+///
+/// "Templates provide a method for declaring inert DOM subtrees." Template
+/// contents are not in the document, so their `<style>` elements don't
+/// create style sheets.
+///
+/// [§ 4.12.2 The noscript element](https://html.spec.whatwg.org/multipage/scripting.html#the-noscript-element)
+///
+/// "The noscript element represents nothing if scripting is enabled."
+///
+/// NOTE: Scripts are executed after parsing, so scripting is treated as
+/// enabled here even though the tree builder parses `<noscript>` content
+/// as if it were disabled.
+fn skips_stylesheets(data: &ElementData) -> bool {
+    data.tag_name.eq_ignore_ascii_case("template") || data.tag_name.eq_ignore_ascii_case("noscript")
+}
+
+/// Whether `data` is a `<style>` element whose style sheet applies to
+/// `media`.
+///
+/// [§ 4.2.6 The style element](https://html.spec.whatwg.org/multipage/semantics.html#the-style-element)
+///
+/// "The media attribute says which media the styles apply to. The value
+/// must be a valid media query list. The user agent must apply the styles
+/// when the media attribute's value matches the environment and the other
+/// relevant conditions apply, and must not apply them otherwise."
+fn is_applicable_style_element(data: &ElementData, media: MediaType) -> bool {
+    data.tag_name.eq_ignore_ascii_case("style")
+        && data
+            .attrs
+            .get("media")
+            .is_none_or(|value| media_attribute_matches(value, media))
+}
+
 // ============================================================================
 // External Stylesheet Support
 // ============================================================================
@@ -168,6 +223,52 @@ pub struct SourcedStylesheet {
     pub stylesheet: Stylesheet,
     /// Where the stylesheet came from.
     pub source: StylesheetSource,
+    /// The `media` attribute of the `<link>` or `<style>` element, if any.
+    ///
+    /// [§ 4.2.4 The link element](https://html.spec.whatwg.org/multipage/semantics.html#attr-link-media)
+    ///
+    /// "The media attribute says which media the resource applies to."
+    pub media: Option<String>,
+}
+
+/// A problem found while gathering a document's stylesheets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StylesheetIssue {
+    /// An external stylesheet could not be fetched and was treated as
+    /// empty.
+    ///
+    /// [§ 4.2.4](https://html.spec.whatwg.org/multipage/semantics.html#the-link-element)
+    LoadFailed {
+        /// The `href` of the `<link>` element.
+        href: String,
+        /// Why the fetch failed.
+        error: String,
+    },
+    /// The CSS parser skipped part of a stylesheet.
+    Parse {
+        /// The stylesheet the issue was found in.
+        source: StylesheetSource,
+        /// What was skipped.
+        issue: ParseIssue,
+    },
+}
+
+impl std::fmt::Display for StylesheetIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LoadFailed { href, error } => {
+                write!(f, "failed to load stylesheet '{href}': {error}")
+            }
+            Self::Parse {
+                source: StylesheetSource::External { href },
+                issue,
+            } => write!(f, "{href}: {}", issue.message),
+            Self::Parse {
+                source: StylesheetSource::Inline,
+                issue,
+            } => write!(f, "<style>: {}", issue.message),
+        }
+    }
 }
 
 /// [§ 6.1 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
@@ -191,15 +292,32 @@ impl DocumentStylesheets {
     /// "Declarations from style sheets independently linked by the originating document
     /// are treated as if they were concatenated in linking order."
     ///
-    /// Merge all stylesheets into a single stylesheet for cascade processing.
-    /// Rules are concatenated in document order.
+    /// Merge all stylesheets into a single stylesheet for cascade processing,
+    /// for screen rendering. Rules are concatenated in document order.
     #[must_use]
     pub fn into_merged_stylesheet(self) -> Stylesheet {
-        let mut all_rules = Vec::new();
-        for sheet in self.sheets {
-            all_rules.extend(sheet.stylesheet.rules);
-        }
-        Stylesheet { rules: all_rules }
+        self.into_merged_stylesheet_for(MediaType::Screen)
+    }
+
+    /// Like [`into_merged_stylesheet`](Self::into_merged_stylesheet), but
+    /// for rendering to `media` (e.g. [`MediaType::Print`] for a print
+    /// preview).
+    ///
+    /// Sheets whose `media` attribute doesn't match are dropped, and
+    /// `@media` rules are resolved with [`Stylesheet::for_media`].
+    #[must_use]
+    pub fn into_merged_stylesheet_for(self, media: MediaType) -> Stylesheet {
+        self.sheets
+            .into_iter()
+            .filter(|sheet| {
+                sheet
+                    .media
+                    .as_deref()
+                    .is_none_or(|value| media_attribute_matches(value, media))
+            })
+            .fold(Stylesheet { rules: Vec::new() }, |merged, sheet| {
+                merged.merge(sheet.stylesheet.for_media(media))
+            })
     }
 }
 
@@ -218,13 +336,26 @@ impl DocumentStylesheets {
 /// "The last declaration in document order wins."
 #[must_use]
 pub fn collect_stylesheet_sources(tree: &DomTree) -> Vec<StylesheetSource> {
+    collect_sources_with_media(tree)
+        .into_iter()
+        .map(|(source, _)| source)
+        .collect()
+}
+
+/// Stylesheet sources in document order, each with its element's `media`
+/// attribute.
+fn collect_sources_with_media(tree: &DomTree) -> Vec<(StylesheetSource, Option<String>)> {
     let mut sources = Vec::new();
     collect_sources_recursive(tree, tree.root(), &mut sources);
     sources
 }
 
 /// Recursively collect stylesheet sources in document order.
-fn collect_sources_recursive(tree: &DomTree, id: NodeId, sources: &mut Vec<StylesheetSource>) {
+fn collect_sources_recursive(
+    tree: &DomTree,
+    id: NodeId,
+    sources: &mut Vec<(StylesheetSource, Option<String>)>,
+) {
     let Some(node) = tree.get(id) else { return };
 
     if let NodeType::Element(data) = &node.node_type {
@@ -247,7 +378,10 @@ fn collect_sources_recursive(tree: &DomTree, id: NodeId, sources: &mut Vec<Style
                 if let Some(href) = data.attrs.get("href")
                     && !href.trim().is_empty()
                 {
-                    sources.push(StylesheetSource::External { href: href.clone() });
+                    sources.push((
+                        StylesheetSource::External { href: href.clone() },
+                        data.attrs.get("media").cloned(),
+                    ));
                 }
             }
         }
@@ -255,7 +389,9 @@ fn collect_sources_recursive(tree: &DomTree, id: NodeId, sources: &mut Vec<Style
         //
         // "The style element allows authors to embed CSS style sheets in their documents."
         else if data.tag_name.eq_ignore_ascii_case("style") {
-            sources.push(StylesheetSource::Inline);
+            sources.push((StylesheetSource::Inline, data.attrs.get("media").cloned()));
+        } else if skips_stylesheets(data) {
+            return;
         }
     }
 
@@ -389,6 +525,13 @@ pub fn fetch_external_stylesheet(
 ///
 /// This function preserves document order so the cascade can be applied correctly.
 ///
+/// # Duplicate Links
+///
+/// A stylesheet linked more than once (by the same resolved URL) is
+/// fetched and applied only once, at the position of its first link.
+/// Applying it again would duplicate every rule and, being later in
+/// document order, let the copies override sheets linked in between.
+///
 /// # Arguments
 ///
 /// * `tree` - The DOM tree to extract stylesheets from
@@ -399,29 +542,64 @@ pub fn fetch_external_stylesheet(
 /// A `DocumentStylesheets` containing all stylesheets in document order.
 #[must_use]
 pub fn extract_all_stylesheets(tree: &DomTree, base_url: Option<&str>) -> DocumentStylesheets {
+    extract_all_stylesheets_with_issues(tree, base_url).0
+}
+
+/// Like [`extract_all_stylesheets`], but also returns the external
+/// stylesheets that failed to load and the CSS parse issues of every
+/// sheet, in document order.
+#[must_use]
+pub fn extract_all_stylesheets_with_issues(
+    tree: &DomTree,
+    base_url: Option<&str>,
+) -> (DocumentStylesheets, Vec<StylesheetIssue>) {
     let mut sheets = Vec::new();
+    let mut issues = Vec::new();
     let mut inline_style_index = 0;
+    // Resolved URLs of the external sheets already seen, with the
+    // `media` they were linked for, so each one is fetched and applied
+    // once per media.
+    let mut linked_urls = HashSet::new();
 
     // STEP 1: Collect all stylesheet sources in document order.
     //
     // [§ 6.1](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
     // "The last declaration in document order wins."
-    let sources = collect_stylesheet_sources(tree);
+    let sources = collect_sources_with_media(tree);
 
     // STEP 2: Process each source to get the stylesheet content.
-    for source in sources {
+    //
+    // Sheets for other media are kept (with their `media` attribute) so a
+    // print preview can merge them; into_merged_stylesheet() drops them.
+    for (source, media) in sources {
         match &source {
             // STEP 2a: External stylesheet - fetch and parse.
             //
             // [§ 4.2.4](https://html.spec.whatwg.org/multipage/semantics.html#the-link-element)
             StylesheetSource::External { href } => {
+                // NOTE: A repeat link for the same media applies at the
+                // first one's cascade position. A link for other media
+                // is kept, so `media=print` doesn't hide a later screen
+                // link to the same sheet.
+                if !linked_urls.insert((resolve_url(href, base_url), media.clone())) {
+                    continue;
+                }
                 match fetch_external_stylesheet(href, base_url) {
                     Ok(css_text) => {
                         // Parse the CSS
-                        let stylesheet = parse_css_text(&css_text);
+                        let (stylesheet, parse_issues) =
+                            CSSParser::new(CSSTokenizer::tokenize(&css_text))
+                                .parse_stylesheet_with_issues();
+                        issues.extend(parse_issues.into_iter().map(|issue| {
+                            StylesheetIssue::Parse {
+                                source: source.clone(),
+                                issue,
+                            }
+                        }));
                         sheets.push(SourcedStylesheet {
                             stylesheet,
                             source: source.clone(),
+                            media,
                         });
                     }
                     Err(e) => {
@@ -433,6 +611,10 @@ pub fn extract_all_stylesheets(tree: &DomTree, base_url: Option<&str>) -> Docume
                             "Koala CSS",
                             &format!("Failed to load stylesheet '{href}': {e}"),
                         );
+                        issues.push(StylesheetIssue::LoadFailed {
+                            href: href.clone(),
+                            error: e.to_string(),
+                        });
                         // Continue without this stylesheet (empty stylesheet per spec)
                     }
                 }
@@ -447,17 +629,28 @@ pub fn extract_all_stylesheets(tree: &DomTree, base_url: Option<&str>) -> Docume
                 inline_style_index += 1;
 
                 if !css_text.is_empty() {
-                    let stylesheet = parse_css_text(&css_text);
+                    let (stylesheet, parse_issues) =
+                        CSSParser::new(CSSTokenizer::tokenize(&css_text))
+                            .parse_stylesheet_with_issues();
+                    issues.extend(
+                        parse_issues
+                            .into_iter()
+                            .map(|issue| StylesheetIssue::Parse {
+                                source: source.clone(),
+                                issue,
+                            }),
+                    );
                     sheets.push(SourcedStylesheet {
                         stylesheet,
                         source: source.clone(),
+                        media,
                     });
                 }
             }
         }
     }
 
-    DocumentStylesheets { sheets }
+    (DocumentStylesheets { sheets }, issues)
 }
 
 /// Extract the content of the nth `<style>` element in document order.
@@ -480,19 +673,24 @@ fn extract_nth_style_recursive(
         return false;
     };
 
-    if let NodeType::Element(data) = &node.node_type
-        && data.tag_name.eq_ignore_ascii_case("style")
-    {
-        if *count == target {
-            // Found the target style element - extract its content
-            for &child_id in tree.children(id) {
-                if let Some(text) = tree.as_text(child_id) {
-                    css.push_str(text);
+    if let NodeType::Element(data) = &node.node_type {
+        // Must skip exactly the elements collect_sources_recursive() skips,
+        // so the nth inline source lines up with the nth style element.
+        if skips_stylesheets(data) {
+            return false;
+        }
+        if data.tag_name.eq_ignore_ascii_case("style") {
+            if *count == target {
+                // Found the target style element - extract its content
+                for &child_id in tree.children(id) {
+                    if let Some(text) = tree.as_text(child_id) {
+                        css.push_str(text);
+                    }
                 }
+                return true; // Stop searching
             }
-            return true; // Stop searching
+            *count += 1;
         }
-        *count += 1;
     }
 
     // Continue searching children
@@ -504,11 +702,3 @@ fn extract_nth_style_recursive(
 
     false
 }
-
-/// Helper to parse CSS text into a Stylesheet.
-fn parse_css_text(css: &str) -> Stylesheet {
-    let mut tokenizer = CSSTokenizer::new(css.to_string());
-    tokenizer.run();
-    let mut parser = CSSParser::new(tokenizer.into_tokens());
-    parser.parse_stylesheet()
-}
@@ -0,0 +1,126 @@
+//! Media queries.
+//!
+//! [Media Queries Level 4](https://www.w3.org/TR/mediaqueries-4/)
+//!
+//! "A media query is a method of testing certain aspects of the user agent
+//! or device that the document is being displayed in."
+//!
+//! Only media types are evaluated. Media features (`(min-width: 600px)`,
+//! `(color)`, ...) are assumed to match, so a query is decided by its
+//! media type and any `not` prefix.
+
+use crate::parser::{CSSParser, ComponentValue};
+use crate::tokenizer::{CSSToken, CSSTokenizer};
+
+/// [§ 2.3 Media Types](https://www.w3.org/TR/mediaqueries-4/#media-types)
+///
+/// "A media type is a broad category of user-agent devices on which a
+/// document may be displayed."
+///
+/// The kind of device a document is being rendered for. Rendering defaults
+/// to [`MediaType::Screen`]; [`MediaType::Print`] is for print previews.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MediaType {
+    /// "Matches all devices that aren't matched by print."
+    #[default]
+    Screen,
+    /// "Matches printers, and devices intended to reproduce a printed
+    /// display, such as a web browser showing a document in "Print
+    /// Preview"."
+    Print,
+}
+
+impl MediaType {
+    /// [§ 2.3 Media Types](https://www.w3.org/TR/mediaqueries-4/#media-types)
+    ///
+    /// Whether the media type named `name` in a query matches this device.
+    ///
+    /// "all: Matches all devices."
+    ///
+    /// Unknown media types don't match. For the deprecated ones (`tv`,
+    /// `handheld`, ...), user agents "must make them match nothing".
+    #[must_use]
+    pub fn matches(self, name: &str) -> bool {
+        match name.to_ascii_lowercase().as_str() {
+            "all" => true,
+            "screen" => self == Self::Screen,
+            "print" => self == Self::Print,
+            _ => false,
+        }
+    }
+}
+
+/// [§ 2.1 Combining Media Queries](https://www.w3.org/TR/mediaqueries-4/#mq-list)
+///
+/// Evaluate a media query list, such as an `@media` prelude, for `media`.
+///
+/// "A media query list is true if any of its component media queries are
+/// true, and false only if all of its component media queries are false."
+#[must_use]
+pub fn media_query_list_matches(list: &[ComponentValue], media: MediaType) -> bool {
+    // "An empty media query list evaluates to true."
+    if list.iter().all(is_whitespace) {
+        return true;
+    }
+    list.split(|value| matches!(value, ComponentValue::Token(CSSToken::Comma)))
+        .any(|query| media_query_matches(query, media))
+}
+
+/// [HTML § 2.4.11 Media queries](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#mq)
+///
+/// Whether a `media` attribute value (on `<style>` or `<link>`) matches
+/// the environment.
+///
+/// "A string matches the environment of the user if it is the empty
+/// string, a string consisting of only ASCII whitespace, or is a media
+/// query list that matches the user's environment according to the
+/// definitions given in Media Queries."
+#[must_use]
+pub fn media_attribute_matches(value: &str, media: MediaType) -> bool {
+    let list = CSSParser::new(CSSTokenizer::tokenize(value)).parse_component_values();
+    media_query_list_matches(&list, media)
+}
+
+/// [§ 3 Syntax](https://www.w3.org/TR/mediaqueries-4/#mq-syntax)
+///
+/// `<media-query> = <media-condition> | [ not | only ]? <media-type> [ and <media-condition-without-or> ]?`
+fn media_query_matches(query: &[ComponentValue], media: MediaType) -> bool {
+    let mut parts = query
+        .iter()
+        .filter(|value| !is_whitespace(value))
+        .peekable();
+
+    // "An individual media query can have its result negated by prefixing
+    // it with the keyword not."
+    // "The only keyword has no effect on the media query's result"
+    let mut negated = false;
+    if let Some(keyword) = parts.peek().and_then(|value| ident(value)) {
+        if keyword.eq_ignore_ascii_case("not") {
+            negated = true;
+            let _ = parts.next();
+        } else if keyword.eq_ignore_ascii_case("only") {
+            let _ = parts.next();
+        }
+    }
+
+    let matches = match parts.next() {
+        Some(value) => ident(value).is_none_or(|media_type| media.matches(media_type)),
+        // [§ 3.2 Error Handling](https://www.w3.org/TR/mediaqueries-4/#error-handling)
+        //
+        // "A media query that does not match the grammar in the previous
+        // section must be replaced by not all during parsing."
+        None => return false,
+    };
+    matches != negated
+}
+
+const fn is_whitespace(value: &ComponentValue) -> bool {
+    matches!(value, ComponentValue::Token(CSSToken::Whitespace))
+}
+
+fn ident(value: &ComponentValue) -> Option<&str> {
+    match value {
+        ComponentValue::Token(CSSToken::Ident(name)) => Some(name),
+        _ => None,
+    }
+}
@@ -0,0 +1,364 @@
+//! Paged media.
+//!
+//! [CSS Paged Media Module Level 3](https://www.w3.org/TR/css-page-3/)
+//!
+//! "The page context is the set of properties applied to the page box and
+//! its margin boxes. ... The @page rule is used to specify properties of
+//! the page box."
+//!
+//! `@page` rules are parsed into [`PageRule`]s exposing the page margins
+//! and size. They never affect screen rendering; they describe the page
+//! box for a paginated (print/PDF) output mode.
+
+use crate::parser::{AtRule, CSSParser, ComponentValue, Rule, Stylesheet};
+use crate::style::values::parse_single_length;
+use crate::style::LengthValue;
+use crate::tokenizer::CSSToken;
+
+/// [§ 4.1 Page Selectors and Page Context](https://www.w3.org/TR/css-page-3/#page-selectors)
+///
+/// "The :first, :left, :right and :blank pseudo-classes ... are used to
+/// match pages."
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PagePseudoClass {
+    /// "The :first pseudo-class matches the first page of the document."
+    First,
+    /// "All pages are automatically classified by user agents into either
+    /// the :left or :right pseudo-class."
+    Left,
+    /// See [`PagePseudoClass::Left`].
+    Right,
+    /// "The :blank pseudo-class matches a page that has no content from
+    /// the document flow."
+    Blank,
+}
+
+/// [§ 4.1 Page Selectors and Page Context](https://www.w3.org/TR/css-page-3/#page-selectors)
+///
+/// `<page-selector> = [ <ident-token>? <pseudo-page>* ]!`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageSelector {
+    /// The page type name, e.g. `wide` in `@page wide:first`.
+    pub name: Option<String>,
+    /// The page pseudo-classes, in source order.
+    pub pseudo_classes: Vec<PagePseudoClass>,
+}
+
+/// [§ 7.1 Page-based Properties](https://www.w3.org/TR/css-page-3/#page-properties)
+///
+/// The margins of the page box. `None` leaves a side at the user agent's
+/// default page margin.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PageMargins {
+    /// `margin-top`.
+    pub top: Option<LengthValue>,
+    /// `margin-right`.
+    pub right: Option<LengthValue>,
+    /// `margin-bottom`.
+    pub bottom: Option<LengthValue>,
+    /// `margin-left`.
+    pub left: Option<LengthValue>,
+}
+
+/// [§ 7.2 Page size: the size property](https://www.w3.org/TR/css-page-3/#page-size-prop)
+///
+/// `size: <length [0,∞]>{1,2} | auto | [ <page-size> || [ portrait | landscape ] ]`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PageSize {
+    /// "The page box will be set to a size and orientation chosen by the UA."
+    Auto,
+    /// "Specifies that the page's content be printed in portrait
+    /// orientation. The page box is the same size as the target, and the
+    /// shorter sides are horizontal."
+    Portrait,
+    /// "Specifies that the page's content be printed in landscape
+    /// orientation. The page box is the same size as the target, and the
+    /// longer sides are horizontal."
+    Landscape,
+    /// An explicit page box size, from lengths or a `<page-size>` name.
+    Fixed {
+        /// The width of the page box.
+        width: LengthValue,
+        /// The height of the page box.
+        height: LengthValue,
+    },
+}
+
+/// [§ 4 Page Selectors and Page Context](https://www.w3.org/TR/css-page-3/#at-page-rule)
+///
+/// A parsed `@page` rule.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageRule {
+    /// The page selector list. Empty when the rule applies to every page.
+    pub selectors: Vec<PageSelector>,
+    /// The page box margins.
+    pub margin: PageMargins,
+    /// The page box size, if the rule sets one.
+    pub size: Option<PageSize>,
+}
+
+impl PageRule {
+    /// [§ 4 Page Selectors and Page Context](https://www.w3.org/TR/css-page-3/#at-page-rule)
+    ///
+    /// Interpret `at_rule` as an `@page` rule.
+    ///
+    /// Returns `None` if it is not an `@page` rule, has no block, or its
+    /// prelude doesn't match `<page-selector-list>?`. Declarations the page
+    /// context doesn't understand, and margin at-rules such as
+    /// `@top-center`, are skipped.
+    #[must_use]
+    pub fn from_at_rule(at_rule: &AtRule) -> Option<Self> {
+        if !at_rule.name.eq_ignore_ascii_case("page") {
+            return None;
+        }
+        let block = at_rule.block.as_ref()?;
+        let mut rule = Self {
+            selectors: parse_page_selector_list(&at_rule.prelude)?,
+            ..Self::default()
+        };
+        // "@page <page-selector-list>? { <declaration-rule-list> }"
+        //
+        // [§ 4.2 Page-Margin Boxes](https://www.w3.org/TR/css-page-3/#margin-boxes)
+        // Nested margin at-rules such as `@top-center` are skipped by the
+        // declaration list parser.
+        for declaration in CSSParser::from_component_values(block).parse_declaration_list() {
+            let value: Vec<ComponentValue> = declaration
+                .value
+                .into_iter()
+                .filter(|v| !is_whitespace(v))
+                .collect();
+            rule.apply_declaration(&declaration.name, &value);
+        }
+        Some(rule)
+    }
+
+    /// [§ 7.1 Page-based Properties](https://www.w3.org/TR/css-page-3/#page-properties)
+    ///
+    /// Apply a single declaration from the page context. Invalid values are
+    /// ignored, leaving the previous value in place.
+    fn apply_declaration(&mut self, name: &str, value: &[ComponentValue]) {
+        match name.to_ascii_lowercase().as_str() {
+            // [§ 8.3 Margin properties](https://www.w3.org/TR/CSS2/box.html#margin-properties)
+            //
+            // "If there is only one component value, it applies to all sides.
+            // If there are two values, the top and bottom margins are set to
+            // the first value and the right and left margins are set to the
+            // second. If there are three values, the top is set to the first
+            // value, the left and right are set to the second, and the bottom
+            // is set to the third. If there are four values, they apply to
+            // the top, right, bottom, and left, respectively."
+            "margin" => {
+                let Some(lengths) = lengths(value) else {
+                    return;
+                };
+                let (top, right, bottom, left) = match lengths.as_slice() {
+                    [all] => (all, all, all, all),
+                    [vertical, horizontal] => (vertical, horizontal, vertical, horizontal),
+                    [top, horizontal, bottom] => (top, horizontal, bottom, horizontal),
+                    [top, right, bottom, left] => (top, right, bottom, left),
+                    _ => return,
+                };
+                self.margin = PageMargins {
+                    top: Some(*top),
+                    right: Some(*right),
+                    bottom: Some(*bottom),
+                    left: Some(*left),
+                };
+            }
+            "margin-top" => set_single_length(&mut self.margin.top, value),
+            "margin-right" => set_single_length(&mut self.margin.right, value),
+            "margin-bottom" => set_single_length(&mut self.margin.bottom, value),
+            "margin-left" => set_single_length(&mut self.margin.left, value),
+            "size" => {
+                if let Some(size) = parse_page_size(value) {
+                    self.size = Some(size);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// [§ 4 Page Selectors and Page Context](https://www.w3.org/TR/css-page-3/#at-page-rule)
+///
+/// The `@page` rules of `stylesheet`, in document order.
+///
+/// Only top-level rules are returned: resolve `@media` first with
+/// [`Stylesheet::for_media`] (e.g. for [`MediaType::Print`](crate::MediaType::Print))
+/// to include `@page` rules nested in matching media queries.
+#[must_use]
+pub fn page_rules(stylesheet: &Stylesheet) -> Vec<PageRule> {
+    stylesheet
+        .rules
+        .iter()
+        .filter_map(|rule| match rule {
+            Rule::At(at_rule) => PageRule::from_at_rule(at_rule),
+            Rule::Style(_) => None,
+        })
+        .collect()
+}
+
+/// [§ 4.1 Page Selectors and Page Context](https://www.w3.org/TR/css-page-3/#page-selectors)
+///
+/// ```text
+/// <page-selector-list> = <page-selector>#
+/// <page-selector> = [ <ident-token>? <pseudo-page>* ]!
+/// <pseudo-page> = : [ left | right | first | blank ]
+/// ```
+///
+/// An empty prelude is an empty list. Returns `None` if the prelude
+/// doesn't match the grammar.
+fn parse_page_selector_list(prelude: &[ComponentValue]) -> Option<Vec<PageSelector>> {
+    if prelude.iter().all(is_whitespace) {
+        return Some(Vec::new());
+    }
+    prelude
+        .split(|value| matches!(value, ComponentValue::Token(CSSToken::Comma)))
+        .map(parse_page_selector)
+        .collect()
+}
+
+fn parse_page_selector(values: &[ComponentValue]) -> Option<PageSelector> {
+    let start = values.iter().position(|v| !is_whitespace(v))?;
+    let end = values.iter().rposition(|v| !is_whitespace(v))? + 1;
+    let mut parts = values[start..end].iter();
+    let mut selector = PageSelector::default();
+
+    let mut next = parts.next();
+    if let Some(ComponentValue::Token(CSSToken::Ident(name))) = next {
+        selector.name = Some(name.clone());
+        next = parts.next();
+    }
+    while let Some(value) = next {
+        // Whitespace is not allowed between the parts of a page selector.
+        let ComponentValue::Token(CSSToken::Colon) = value else {
+            return None;
+        };
+        let Some(ComponentValue::Token(CSSToken::Ident(pseudo))) = parts.next() else {
+            return None;
+        };
+        selector
+            .pseudo_classes
+            .push(match pseudo.to_ascii_lowercase().as_str() {
+                "first" => PagePseudoClass::First,
+                "left" => PagePseudoClass::Left,
+                "right" => PagePseudoClass::Right,
+                "blank" => PagePseudoClass::Blank,
+                _ => return None,
+            });
+        next = parts.next();
+    }
+    Some(selector)
+}
+
+/// [§ 7.2 Page size: the size property](https://www.w3.org/TR/css-page-3/#page-size-prop)
+fn parse_page_size(value: &[ComponentValue]) -> Option<PageSize> {
+    if value.is_empty() {
+        return None;
+    }
+
+    // "<length [0,∞]>{1,2}: The first length represents the width of the
+    // page box and the second length represents its height. If only one
+    // length value is specified, it sets both the width and height of the
+    // page box (i.e., the box is a square)."
+    if let Some(lengths) = lengths(value) {
+        return match lengths.as_slice() {
+            [size] => Some(PageSize::Fixed {
+                width: *size,
+                height: *size,
+            }),
+            [width, height] => Some(PageSize::Fixed {
+                width: *width,
+                height: *height,
+            }),
+            _ => None,
+        };
+    }
+
+    let mut named = None;
+    let mut orientation = None;
+    for part in value {
+        let ComponentValue::Token(CSSToken::Ident(keyword)) = part else {
+            return None;
+        };
+        let keyword = keyword.to_ascii_lowercase();
+        match keyword.as_str() {
+            "auto" if value.len() == 1 => return Some(PageSize::Auto),
+            "portrait" | "landscape" if orientation.is_none() => orientation = Some(keyword),
+            _ if named.is_none() => named = Some(named_page_size(&keyword)?),
+            _ => return None,
+        }
+    }
+    let landscape = orientation.as_deref() == Some("landscape");
+    match named {
+        // "If a <page-size> is specified, and one of portrait or landscape
+        // is specified, the <page-size> determines the dimensions, and the
+        // portrait or landscape keyword determines the orientation."
+        Some((short, long)) => {
+            let (width, height) = if landscape {
+                (long, short)
+            } else {
+                (short, long)
+            };
+            Some(PageSize::Fixed {
+                width: LengthValue::Px(width),
+                height: LengthValue::Px(height),
+            })
+        }
+        None if landscape => Some(PageSize::Landscape),
+        None => Some(PageSize::Portrait),
+    }
+}
+
+/// [§ 7.2.1 Page size: the <page-size> values](https://www.w3.org/TR/css-page-3/#typedef-page-size-page-size)
+///
+/// The `(short, long)` side lengths in px of the named page size.
+fn named_page_size(name: &str) -> Option<(f64, f64)> {
+    let (short, long) = match name {
+        // "A5: Equivalent to the international paper size A5, 148mm wide and 210mm high."
+        "a5" => (LengthValue::Mm(148.0), LengthValue::Mm(210.0)),
+        "a4" => (LengthValue::Mm(210.0), LengthValue::Mm(297.0)),
+        "a3" => (LengthValue::Mm(297.0), LengthValue::Mm(420.0)),
+        "b5" => (LengthValue::Mm(176.0), LengthValue::Mm(250.0)),
+        "b4" => (LengthValue::Mm(250.0), LengthValue::Mm(353.0)),
+        "jis-b5" => (LengthValue::Mm(182.0), LengthValue::Mm(257.0)),
+        "jis-b4" => (LengthValue::Mm(257.0), LengthValue::Mm(364.0)),
+        // "letter: Equivalent to the North American letter paper size,
+        // 8.5in wide and 11in high."
+        "letter" => (LengthValue::In(8.5), LengthValue::In(11.0)),
+        "legal" => (LengthValue::In(8.5), LengthValue::In(14.0)),
+        "ledger" => (LengthValue::In(11.0), LengthValue::In(17.0)),
+        _ => return None,
+    };
+    Some((short.absolute_px()?, long.absolute_px()?))
+}
+
+/// Parse every component of `value` as a length. Returns `None` if any
+/// of them isn't one.
+fn lengths(value: &[ComponentValue]) -> Option<Vec<LengthValue>> {
+    if value.is_empty() {
+        return None;
+    }
+    value.iter().map(page_length).collect()
+}
+
+fn set_single_length(side: &mut Option<LengthValue>, value: &[ComponentValue]) {
+    if let [single] = value
+        && let Some(length) = page_length(single)
+    {
+        *side = Some(length);
+    }
+}
+
+/// [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+///
+/// A `<length>` in the page context. Print stylesheets mostly use physical
+/// units, which are converted to px here.
+fn page_length(value: &ComponentValue) -> Option<LengthValue> {
+    let length = parse_single_length(value)?;
+    Some(length.absolute_px().map_or(length, LengthValue::Px))
+}
+
+const fn is_whitespace(value: &ComponentValue) -> bool {
+    matches!(value, ComponentValue::Token(CSSToken::Whitespace))
+}
@@ -5,16 +5,20 @@
 //! The display list is the output of the painting phase. It contains all the
 //! drawing commands needed to render a page, in the correct z-order.
 
+use koala_dom::NodeId;
+use koala_std::collections::HashMap;
+
 use crate::ColorValue;
+use crate::layout::Rect;
 use crate::style::BorderRadius;
-use crate::style::values::{FontStyle, TextDecorationLine};
+use crate::style::values::{FontFamily, FontStyle, TextDecoration};
 
 /// A single drawing command.
 ///
 /// [CSS 2.1 Appendix E.2 Painting order](https://www.w3.org/TR/CSS2/zindex.html#painting-order)
 ///
 /// Commands are added to the display list in painting order (back to front).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum DisplayCommand {
     /// Draw a box shadow (outer or inset).
     ///
@@ -110,8 +114,15 @@ pub enum DisplayCommand {
         font_weight: u16,
         /// [§ 3.3 'font-style'](https://www.w3.org/TR/css-fonts-4/#font-style-prop)
         font_style: FontStyle,
-        /// [§ 3 'text-decoration-line'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
-        text_decoration: TextDecorationLine,
+        /// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+        ///
+        /// Families to try in order. The renderer draws with the first one
+        /// it has loaded, or its default font if none is.
+        font_family: Vec<FontFamily>,
+        /// [§ 2 Line Decorations](https://www.w3.org/TR/css-text-decoration-3/#line-decoration)
+        ///
+        /// Lines to draw across the text, in their own style and color.
+        text_decoration: TextDecoration,
         /// [§ 9.3 'letter-spacing'](https://www.w3.org/TR/css-text-3/#letter-spacing-property)
         ///
         /// Additional pixels inserted between adjacent glyph advances
@@ -122,27 +133,43 @@ pub enum DisplayCommand {
         letter_spacing: f32,
     },
 
-    /// Push a clip rectangle onto the clip stack.
+    /// Push a clip region onto the clip stack.
     ///
     /// [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
     ///
-    /// All subsequent drawing commands are clipped to the intersection of
-    /// all active clip rectangles. Used for `overflow: hidden`.
-    PushClip {
-        /// X coordinate of the clip rectangle.
-        x: f32,
-        /// Y coordinate of the clip rectangle.
-        y: f32,
-        /// Width of the clip rectangle.
-        width: f32,
-        /// Height of the clip rectangle.
-        height: f32,
-    },
+    /// All subsequent drawing commands, up to the matching
+    /// [`DisplayCommand::PopClip`], are clipped to the intersection of all
+    /// active clip regions. Used for `overflow: hidden` and replaced
+    /// content that overflows its content box.
+    ///
+    /// [§ 5.3 Corner Clipping](https://www.w3.org/TR/css-backgrounds-3/#corner-clipping)
+    ///
+    /// With corner radii, the region is the rectangle with its corners
+    /// rounded off, as for an `overflow: hidden` box with `border-radius`.
+    PushClip(Rect, Option<BorderRadius>),
 
-    /// Pop the most recent clip rectangle from the clip stack.
+    /// Pop the most recent clip region from the clip stack.
     PopClip,
 }
 
+/// Stable identity of a command in a retained display list.
+///
+/// A command is identified by the DOM node whose box emitted it and its
+/// sequence number among that node's commands. Anonymous boxes share the
+/// identity space of their nearest element ancestor, and commands emitted
+/// outside any element box have no owner.
+///
+/// Because painting walks the layout tree in a fixed order, rebuilding the
+/// list for the same tree assigns the same ID to the same command, so IDs
+/// can be used to line up commands across frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisplayItemId {
+    /// The node whose box emitted the command.
+    pub owner: Option<NodeId>,
+    /// Position of the command among the commands emitted for `owner`.
+    pub sequence: u32,
+}
+
 /// A list of drawing commands in painting order.
 ///
 /// [CSS 2.1 Appendix E.2 Painting order](https://www.w3.org/TR/CSS2/zindex.html#painting-order)
@@ -150,22 +177,57 @@ pub enum DisplayCommand {
 /// The display list contains all commands needed to render a page. Commands
 /// are stored in back-to-front order, so the renderer can simply iterate
 /// and execute each command.
+///
+/// A *retained* display list additionally records a [`DisplayItemId`] for
+/// every command, so two frames can be compared with [`DisplayList::diff`]
+/// and only the changed commands repainted.
 #[derive(Debug, Clone, Default)]
 pub struct DisplayList {
     commands: Vec<DisplayCommand>,
+    /// One ID per command when the list is retained, `None` otherwise.
+    ids: Option<Vec<DisplayItemId>>,
+    /// Next sequence number for each owner, used while building.
+    sequences: HashMap<Option<NodeId>, u32>,
 }
 
 impl DisplayList {
     /// Create an empty display list.
     #[must_use]
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty retained display list, which records a stable
+    /// [`DisplayItemId`] for every command pushed to it.
+    #[must_use]
+    pub fn retained() -> Self {
         Self {
-            commands: Vec::new(),
+            ids: Some(Vec::new()),
+            ..Self::default()
         }
     }
 
     /// Add a command to the display list.
+    ///
+    /// In a retained list the command is recorded without an owner; use
+    /// [`DisplayList::push_for`] to attribute it to a node.
     pub fn push(&mut self, command: DisplayCommand) {
+        self.push_for(None, command);
+    }
+
+    /// Add a command emitted by `owner`'s box to the display list.
+    ///
+    /// In a retained list the command is assigned the next
+    /// [`DisplayItemId`] for `owner`.
+    pub fn push_for(&mut self, owner: Option<NodeId>, command: DisplayCommand) {
+        if let Some(ids) = &mut self.ids {
+            let sequence = self.sequences.entry(owner).or_insert(0);
+            ids.push(DisplayItemId {
+                owner,
+                sequence: *sequence,
+            });
+            *sequence += 1;
+        }
         self.commands.push(command);
     }
 
@@ -175,6 +237,18 @@ impl DisplayList {
         &self.commands
     }
 
+    /// Get the command IDs in painting order, if this list is retained.
+    #[must_use]
+    pub fn ids(&self) -> Option<&[DisplayItemId]> {
+        self.ids.as_deref()
+    }
+
+    /// Whether this list records a [`DisplayItemId`] per command.
+    #[must_use]
+    pub const fn is_retained(&self) -> bool {
+        self.ids.is_some()
+    }
+
     /// Get the number of commands.
     #[must_use]
     pub const fn len(&self) -> usize {
@@ -186,4 +260,41 @@ impl DisplayList {
     pub const fn is_empty(&self) -> bool {
         self.commands.is_empty()
     }
+
+    /// Commands of `other` (the next frame) that differ from this list
+    /// (the previous frame).
+    ///
+    /// When both lists are retained, commands are matched by
+    /// [`DisplayItemId`]: a command is reported if no command with the same
+    /// ID exists here, or if the command with that ID is different.
+    /// Otherwise commands are compared by position, and every command past
+    /// the end of this list is reported.
+    ///
+    /// The result is in `other`'s painting order. An identical frame yields
+    /// an empty diff.
+    ///
+    /// NOTE: Commands present only in this list are not reported, as there
+    /// is no new command to return for them. Consumers that need to erase
+    /// removed content can compare [`DisplayList::ids`] directly.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Vec<DisplayCommand> {
+        if let (Some(old_ids), Some(new_ids)) = (&self.ids, &other.ids) {
+            let previous: HashMap<DisplayItemId, &DisplayCommand> =
+                old_ids.iter().copied().zip(&self.commands).collect();
+            return new_ids
+                .iter()
+                .zip(&other.commands)
+                .filter(|(id, command)| previous.get(*id) != Some(command))
+                .map(|(_, command)| command.clone())
+                .collect();
+        }
+
+        other
+            .commands
+            .iter()
+            .enumerate()
+            .filter(|(index, command)| self.commands.get(*index) != Some(*command))
+            .map(|(_, command)| command.clone())
+            .collect()
+    }
 }
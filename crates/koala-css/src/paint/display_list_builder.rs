@@ -13,15 +13,45 @@ use koala_std::collections::HashMap;
 use koala_dom::NodeId;
 
 use crate::layout::inline::FragmentContent;
+use crate::layout::object_fit_rect;
 use crate::style::ComputedStyle;
-use crate::style::values::PositionType;
+use crate::style::values::{BackgroundPosition, PositionType, TextDecoration};
 use crate::style::BorderRadius;
-use crate::{BoxType, LayoutBox};
+use crate::{BoxDimensions, BoxType, LayoutBox, Rect};
 
 use crate::ColorValue;
 
 use super::{DisplayCommand, DisplayList};
 
+/// Start and extent of a clip rectangle along an axis that does not clip.
+/// Half of `f32::MAX` on either side of the origin, so `start + extent`
+/// stays finite.
+const UNBOUNDED_CLIP_START: f32 = -f32::MAX / 2.0;
+const UNBOUNDED_CLIP_EXTENT: f32 = f32::MAX;
+
+/// [§ 5.2 Corner Shaping](https://www.w3.org/TR/css-backgrounds-3/#corner-shaping)
+///
+/// "The padding edge (inner border) radius is the outer border radius
+/// minus the corresponding border thickness. In the case where this
+/// results in a negative value, the inner radius is zero."
+///
+/// NOTE: The spec's inner curve is elliptical when the two adjacent
+/// borders differ in width. Corners are circular here, so each one takes
+/// the smaller of the two reduced radii, which keeps the clip inside the
+/// curve. Returns `None` when no corner is rounded.
+fn padding_edge_radius(outer: BorderRadius, dims: &BoxDimensions) -> Option<BorderRadius> {
+    let border = dims.border;
+    let inner =
+        |radius: f32, horizontal: f32, vertical: f32| (radius - horizontal.max(vertical)).max(0.0);
+    let radius = BorderRadius {
+        top_left: inner(outer.top_left, border.left, border.top),
+        top_right: inner(outer.top_right, border.right, border.top),
+        bottom_right: inner(outer.bottom_right, border.right, border.bottom),
+        bottom_left: inner(outer.bottom_left, border.left, border.bottom),
+    };
+    (radius != BorderRadius::default()).then_some(radius)
+}
+
 /// Apply opacity to a color by multiplying its alpha channel.
 ///
 /// [§ 3.2 'opacity'](https://www.w3.org/TR/css-color-4/#transparency)
@@ -52,13 +82,30 @@ fn apply_opacity(color: &ColorValue, opacity: f32) -> ColorValue {
 pub struct DisplayListBuilder<'a> {
     /// Computed styles for each node, used to get colors, fonts, etc.
     styles: &'a HashMap<NodeId, ComputedStyle>,
+    /// Whether to build a retained list with stable command IDs.
+    retained: bool,
 }
 
 impl<'a> DisplayListBuilder<'a> {
     /// Create a new builder with access to computed styles.
     #[must_use]
     pub const fn new(styles: &'a HashMap<NodeId, ComputedStyle>) -> Self {
-        Self { styles }
+        Self {
+            styles,
+            retained: false,
+        }
+    }
+
+    /// Build a retained display list, where every command carries a stable
+    /// [`DisplayItemId`](super::DisplayItemId).
+    ///
+    /// Lists built from the same layout tree get the same IDs, so frames
+    /// can be compared with [`DisplayList::diff`] to repaint only the
+    /// commands that changed.
+    #[must_use]
+    pub const fn retained(mut self, retained: bool) -> Self {
+        self.retained = retained;
+        self
     }
 
     /// Walk the layout tree and return a complete `DisplayList`.
@@ -69,8 +116,12 @@ impl<'a> DisplayListBuilder<'a> {
     /// the tree needs, in the correct back-to-front painting order.
     #[must_use]
     pub fn build(&self, layout: &LayoutBox) -> DisplayList {
-        let mut display_list = DisplayList::new();
-        self.paint_box(layout, &mut display_list, None);
+        let mut display_list = if self.retained {
+            DisplayList::retained()
+        } else {
+            DisplayList::new()
+        };
+        self.paint_box(layout, &mut display_list, None, None);
         display_list
     }
 
@@ -90,6 +141,7 @@ impl<'a> DisplayListBuilder<'a> {
         layout_box: &LayoutBox,
         display_list: &mut DisplayList,
         parent_style: Option<&ComputedStyle>,
+        parent_owner: Option<NodeId>,
     ) {
         // [§ 11.2 'visibility'](https://www.w3.org/TR/CSS2/visufx.html#visibility)
         //
@@ -118,6 +170,13 @@ impl<'a> DisplayListBuilder<'a> {
             _ => None,
         };
 
+        // Commands from anonymous boxes are attributed to the nearest
+        // element box, which keeps retained IDs stable across frames.
+        let owner = match &layout_box.box_type {
+            BoxType::Principal(node_id) => Some(*node_id),
+            _ => parent_owner,
+        };
+
         // Use own style or inherit from parent for certain properties
         let effective_style = style.or(parent_style);
 
@@ -159,30 +218,36 @@ impl<'a> DisplayListBuilder<'a> {
 
             for shadow in layout_box.box_shadow.iter().rev() {
                 if !shadow.inset {
-                    display_list.push(DisplayCommand::DrawBoxShadow {
-                        border_box_x,
-                        border_box_y,
-                        border_box_width,
-                        border_box_height,
-                        offset_x: shadow.offset_x,
-                        offset_y: shadow.offset_y,
-                        blur_radius: shadow.blur_radius,
-                        spread_radius: shadow.spread_radius,
-                        color: apply_opacity(&shadow.color, opacity),
-                        inset: false,
-                    });
+                    display_list.push_for(
+                        owner,
+                        DisplayCommand::DrawBoxShadow {
+                            border_box_x,
+                            border_box_y,
+                            border_box_width,
+                            border_box_height,
+                            offset_x: shadow.offset_x,
+                            offset_y: shadow.offset_y,
+                            blur_radius: shadow.blur_radius,
+                            spread_radius: shadow.spread_radius,
+                            color: apply_opacity(&shadow.color, opacity),
+                            inset: false,
+                        },
+                    );
                 }
             }
 
             if let Some(bg) = &style.background_color {
-                display_list.push(DisplayCommand::FillRect {
-                    x: border_box_x,
-                    y: border_box_y,
-                    width: border_box_width,
-                    height: border_box_height,
-                    color: apply_opacity(bg, opacity),
-                    border_radius: layout_box.border_radius,
-                });
+                display_list.push_for(
+                    owner,
+                    DisplayCommand::FillRect {
+                        x: border_box_x,
+                        y: border_box_y,
+                        width: border_box_width,
+                        height: border_box_height,
+                        color: apply_opacity(bg, opacity),
+                        border_radius: layout_box.border_radius,
+                    },
+                );
             }
 
             // [CSS 2.1 Appendix E.2 Step 2](https://www.w3.org/TR/CSS2/zindex.html#painting-order)
@@ -194,6 +259,7 @@ impl<'a> DisplayListBuilder<'a> {
                 padding_width,
                 padding_height,
                 display_list,
+                owner,
                 opacity,
             );
 
@@ -204,35 +270,73 @@ impl<'a> DisplayListBuilder<'a> {
             // but below the content (including the border)."
             for shadow in layout_box.box_shadow.iter().rev() {
                 if shadow.inset {
-                    display_list.push(DisplayCommand::DrawBoxShadow {
-                        border_box_x,
-                        border_box_y,
-                        border_box_width,
-                        border_box_height,
-                        offset_x: shadow.offset_x,
-                        offset_y: shadow.offset_y,
-                        blur_radius: shadow.blur_radius,
-                        spread_radius: shadow.spread_radius,
-                        color: apply_opacity(&shadow.color, opacity),
-                        inset: true,
-                    });
+                    display_list.push_for(
+                        owner,
+                        DisplayCommand::DrawBoxShadow {
+                            border_box_x,
+                            border_box_y,
+                            border_box_width,
+                            border_box_height,
+                            offset_x: shadow.offset_x,
+                            offset_y: shadow.offset_y,
+                            blur_radius: shadow.blur_radius,
+                            spread_radius: shadow.spread_radius,
+                            color: apply_opacity(&shadow.color, opacity),
+                            inset: true,
+                        },
+                    );
                 }
             }
         }
 
-        // [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
-        // "When overflow is not 'visible', content is clipped to the padding edge."
-        let needs_clip = style.is_some_and(|s| {
-            s.overflow
-                .is_some_and(|o| o != crate::style::computed::Overflow::Visible)
-        });
+        // [§ 3 overflow-x, overflow-y](https://www.w3.org/TR/css-overflow-3/#overflow-properties)
+        // "These properties specify whether a box's content (including any
+        // ink overflow) is clipped to its padding edge"
+        //
+        // Each axis clips on its own; along an axis that stays visible the
+        // clip rectangle is effectively unbounded.
+        let clips = |overflow: Option<crate::style::computed::Overflow>| {
+            overflow.is_some_and(|o| o != crate::style::computed::Overflow::Visible)
+        };
+        let clip_x = style.is_some_and(|s| clips(s.overflow_x));
+        let clip_y = style.is_some_and(|s| clips(s.overflow_y));
+        let needs_clip = clip_x || clip_y;
         if needs_clip {
-            display_list.push(DisplayCommand::PushClip {
-                x: padding_x,
-                y: padding_y,
-                width: padding_width,
-                height: padding_height,
-            });
+            let (x, width) = if clip_x {
+                (padding_x, padding_width)
+            } else {
+                (UNBOUNDED_CLIP_START, UNBOUNDED_CLIP_EXTENT)
+            };
+            let (y, height) = if clip_y {
+                (padding_y, padding_height)
+            } else {
+                (UNBOUNDED_CLIP_START, UNBOUNDED_CLIP_EXTENT)
+            };
+            // [§ 5.3 Corner Clipping](https://www.w3.org/TR/css-backgrounds-3/#corner-clipping)
+            //
+            // "A box's backgrounds, but not its border-image, are clipped
+            // to the appropriate curve... Other effects that clip to the
+            // border or padding edge (such as 'overflow' other than
+            // 'visible') also must clip to the curve."
+            //
+            // Only a box clipped on both axes has corners to round.
+            let radius = if clip_x && clip_y {
+                padding_edge_radius(layout_box.border_radius, dims)
+            } else {
+                None
+            };
+            display_list.push_for(
+                owner,
+                DisplayCommand::PushClip(
+                    Rect {
+                        x,
+                        y,
+                        width,
+                        height,
+                    },
+                    radius,
+                ),
+            );
         }
 
         // Only paint content (images, text) if the box is visible.
@@ -242,14 +346,45 @@ impl<'a> DisplayListBuilder<'a> {
             if layout_box.is_replaced
                 && let Some(ref src) = layout_box.replaced_src
             {
-                display_list.push(DisplayCommand::DrawImage {
-                    x: dims.content.x,
-                    y: dims.content.y,
-                    width: dims.content.width,
-                    height: dims.content.height,
-                    src: src.clone(),
-                    opacity: layout_box.opacity,
-                });
+                // [§ 5.5 'object-fit'](https://www.w3.org/TR/css-images-3/#the-object-fit)
+                //
+                // Without natural dimensions the image simply fills the
+                // content box, which is also what 'fill' does.
+                let fit = style.and_then(|s| s.object_fit).unwrap_or_default();
+                // [§ 5.6 'object-position'](https://www.w3.org/TR/css-images-3/#the-object-position)
+                let position = style
+                    .and_then(|s| s.object_position)
+                    .unwrap_or(BackgroundPosition::CENTER);
+                let image_rect = match (layout_box.intrinsic_width, layout_box.intrinsic_height) {
+                    (Some(w), Some(h)) => object_fit_rect(fit, position, dims.content, w, h),
+                    _ => dims.content,
+                };
+
+                // NOTE: The UA stylesheet gives replaced elements
+                // 'overflow: clip' with a content-box clip margin, so
+                // content that overflows (cover, none) is clipped to the
+                // content box.
+                let overflows = image_rect.x < dims.content.x
+                    || image_rect.y < dims.content.y
+                    || image_rect.x + image_rect.width > dims.content.x + dims.content.width
+                    || image_rect.y + image_rect.height > dims.content.y + dims.content.height;
+                if overflows {
+                    display_list.push_for(owner, DisplayCommand::PushClip(dims.content, None));
+                }
+                display_list.push_for(
+                    owner,
+                    DisplayCommand::DrawImage {
+                        x: image_rect.x,
+                        y: image_rect.y,
+                        width: image_rect.width,
+                        height: image_rect.height,
+                        src: src.clone(),
+                        opacity: layout_box.opacity,
+                    },
+                );
+                if overflows {
+                    display_list.push_for(owner, DisplayCommand::PopClip);
+                }
             }
 
             // [CSS 2.1 Appendix E.2 Step 7](https://www.w3.org/TR/CSS2/zindex.html#painting-order)
@@ -259,17 +394,28 @@ impl<'a> DisplayListBuilder<'a> {
                 for line_box in &layout_box.line_boxes {
                     for fragment in &line_box.fragments {
                         if let FragmentContent::Text(text_run) = &fragment.content {
-                            display_list.push(DisplayCommand::DrawText {
-                                x: fragment.bounds.x,
-                                y: fragment.bounds.y,
-                                text: text_run.text.clone(),
-                                font_size: text_run.font_size,
-                                color: apply_opacity(&text_run.color, opacity),
-                                font_weight: text_run.font_weight,
-                                font_style: text_run.font_style,
-                                text_decoration: text_run.text_decoration,
-                                letter_spacing: text_run.letter_spacing
-                            });
+                            display_list.push_for(
+                                owner,
+                                DisplayCommand::DrawText {
+                                    x: fragment.bounds.x,
+                                    y: fragment.bounds.y,
+                                    text: text_run.text.clone(),
+                                    font_size: text_run.font_size,
+                                    color: apply_opacity(&text_run.color, opacity),
+                                    font_weight: text_run.font_weight,
+                                    font_style: text_run.font_style,
+                                    font_family: text_run.font_family.clone(),
+                                    text_decoration: TextDecoration {
+                                        line: text_run.text_decoration.line,
+                                        style: text_run.text_decoration.style,
+                                        color: apply_opacity(
+                                            &text_run.text_decoration.color,
+                                            opacity,
+                                        ),
+                                    },
+                                    letter_spacing: text_run.letter_spacing,
+                                },
+                            );
                         }
                     }
                 }
@@ -298,7 +444,7 @@ impl<'a> DisplayListBuilder<'a> {
                 child.position_type,
                 PositionType::Absolute | PositionType::Fixed
             ) {
-                self.paint_box(child, display_list, effective_style);
+                self.paint_box(child, display_list, effective_style, owner);
             }
         }
         for child in &layout_box.children {
@@ -306,12 +452,103 @@ impl<'a> DisplayListBuilder<'a> {
                 child.position_type,
                 PositionType::Absolute | PositionType::Fixed
             ) {
-                self.paint_box(child, display_list, effective_style);
+                self.paint_box(child, display_list, effective_style, owner);
             }
         }
 
         if needs_clip {
-            display_list.push(DisplayCommand::PopClip);
+            display_list.push_for(owner, DisplayCommand::PopClip);
+        }
+
+        // [CSS 2.1 Appendix E.2 Step 10](https://www.w3.org/TR/CSS2/zindex.html#painting-order)
+        // "Finally, implementations that do not draw outlines in steps above
+        // must draw outlines from this stacking context at this stage."
+        if let Some(style) = style
+            && is_visible
+        {
+            self.paint_outline(
+                style,
+                border_box_x,
+                border_box_y,
+                border_box_width,
+                border_box_height,
+                display_list,
+                owner,
+                layout_box.opacity,
+            );
+        }
+    }
+
+    /// Paint the outline of a box.
+    ///
+    /// [CSS UI 4 § 5 Outline properties](https://www.w3.org/TR/css-ui-4/#outline-props)
+    ///
+    /// "The outline created with the outline properties is drawn 'over' a
+    /// box, i.e., the outline is always on top, and doesn't influence the
+    /// position or size of the box, or of any other boxes."
+    ///
+    /// The outline is a ring of `outline-width` drawn `outline-offset`
+    /// outside the border box. Like borders, every style renders as solid.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::unused_self,
+        clippy::too_many_arguments
+    )]
+    fn paint_outline(
+        &self,
+        style: &ComputedStyle,
+        border_box_x: f32,
+        border_box_y: f32,
+        border_box_width: f32,
+        border_box_height: f32,
+        display_list: &mut DisplayList,
+        owner: Option<NodeId>,
+        opacity: f32,
+    ) {
+        let Some(outline) = &style.outline else {
+            return;
+        };
+        let width = outline.width.to_px() as f32;
+        if outline.style == "none" || width <= 0.0 {
+            return;
+        }
+        let offset = style.outline_offset.map_or(0.0, |o| o.to_px() as f32);
+        let color = apply_opacity(&outline.color, opacity);
+
+        // The outer edge of the ring: the border box grown by the offset
+        // and the outline width on every side.
+        let grow = offset + width;
+        let outer_x = border_box_x - grow;
+        let outer_y = border_box_y - grow;
+        let outer_width = border_box_width + grow + grow;
+        let outer_height = border_box_height + grow + grow;
+        let side_height = outer_height - width - width;
+
+        let sides = [
+            // Top and bottom span the full outer width, including corners.
+            (outer_x, outer_y, outer_width, width),
+            (outer_x, outer_y + outer_height - width, outer_width, width),
+            // Left and right fill in between.
+            (outer_x, outer_y + width, width, side_height),
+            (
+                outer_x + outer_width - width,
+                outer_y + width,
+                width,
+                side_height,
+            ),
+        ];
+        for (x, y, w, h) in sides {
+            display_list.push_for(
+                owner,
+                DisplayCommand::FillRect {
+                    x,
+                    y,
+                    width: w,
+                    height: h,
+                    color: color.clone(),
+                    border_radius: BorderRadius::default(),
+                },
+            );
         }
     }
 
@@ -331,6 +568,7 @@ impl<'a> DisplayListBuilder<'a> {
         padding_width: f32,
         padding_height: f32,
         display_list: &mut DisplayList,
+        owner: Option<NodeId>,
         opacity: f32,
     ) {
         // Get border widths (default to 0 if not set)
@@ -355,56 +593,68 @@ impl<'a> DisplayListBuilder<'a> {
         if let Some(border) = &style.border_top
             && top_width > 0.0
         {
-            display_list.push(DisplayCommand::FillRect {
-                x: padding_x - left_width,
-                y: padding_y - top_width,
-                width: padding_width + left_width + right_width,
-                height: top_width,
-                color: apply_opacity(&border.color, opacity),
-                border_radius: BorderRadius::default(),
-            });
+            display_list.push_for(
+                owner,
+                DisplayCommand::FillRect {
+                    x: padding_x - left_width,
+                    y: padding_y - top_width,
+                    width: padding_width + left_width + right_width,
+                    height: top_width,
+                    color: apply_opacity(&border.color, opacity),
+                    border_radius: BorderRadius::default(),
+                },
+            );
         }
 
         // Bottom border: spans full width including corners
         if let Some(border) = &style.border_bottom
             && bottom_width > 0.0
         {
-            display_list.push(DisplayCommand::FillRect {
-                x: padding_x - left_width,
-                y: padding_y + padding_height,
-                width: padding_width + left_width + right_width,
-                height: bottom_width,
-                color: apply_opacity(&border.color, opacity),
-                border_radius: BorderRadius::default(),
-            });
+            display_list.push_for(
+                owner,
+                DisplayCommand::FillRect {
+                    x: padding_x - left_width,
+                    y: padding_y + padding_height,
+                    width: padding_width + left_width + right_width,
+                    height: bottom_width,
+                    color: apply_opacity(&border.color, opacity),
+                    border_radius: BorderRadius::default(),
+                },
+            );
         }
 
         // Left border: between top and bottom borders
         if let Some(border) = &style.border_left
             && left_width > 0.0
         {
-            display_list.push(DisplayCommand::FillRect {
-                x: padding_x - left_width,
-                y: padding_y,
-                width: left_width,
-                height: padding_height,
-                color: apply_opacity(&border.color, opacity),
-                border_radius: BorderRadius::default(),
-            });
+            display_list.push_for(
+                owner,
+                DisplayCommand::FillRect {
+                    x: padding_x - left_width,
+                    y: padding_y,
+                    width: left_width,
+                    height: padding_height,
+                    color: apply_opacity(&border.color, opacity),
+                    border_radius: BorderRadius::default(),
+                },
+            );
         }
 
         // Right border: between top and bottom borders
         if let Some(border) = &style.border_right
             && right_width > 0.0
         {
-            display_list.push(DisplayCommand::FillRect {
-                x: padding_x + padding_width,
-                y: padding_y,
-                width: right_width,
-                height: padding_height,
-                color: apply_opacity(&border.color, opacity),
-                border_radius: BorderRadius::default(),
-            });
+            display_list.push_for(
+                owner,
+                DisplayCommand::FillRect {
+                    x: padding_x + padding_width,
+                    y: padding_y,
+                    width: right_width,
+                    height: padding_height,
+                    color: apply_opacity(&border.color, opacity),
+                    border_radius: BorderRadius::default(),
+                },
+            );
         }
     }
 }
@@ -24,5 +24,5 @@
 mod display_list;
 mod display_list_builder;
 
-pub use display_list::{DisplayCommand, DisplayList};
+pub use display_list::{DisplayCommand, DisplayItemId, DisplayList};
 pub use display_list_builder::DisplayListBuilder;
@@ -3,7 +3,11 @@
 //! "The input to the parsing stage is a stream of tokens from the tokenization stage."
 //! This is a basic implementation that parses style rules.
 
-use crate::tokenizer::CSSToken;
+use koala_std::collections::HashSet;
+
+use crate::media::{MediaType, media_query_list_matches};
+use crate::selector::{SimpleSelector, parse_selector};
+use crate::tokenizer::{CSSToken, CSSTokenizer};
 
 /// [§ 5.4.4 Consume a declaration](https://www.w3.org/TR/css-syntax-3/#consume-a-declaration)
 ///
@@ -82,8 +86,58 @@ pub struct AtRule {
     pub name: String,
     /// The prelude component values.
     pub prelude: Vec<ComponentValue>,
-    /// The optional block contents.
+    /// The optional block contents, for at-rules whose block is not parsed
+    /// into [`rules`](Self::rules).
     pub block: Option<Vec<ComponentValue>>,
+    /// [§ 2 Processing of conditional group rules](https://www.w3.org/TR/css-conditional-3/#processing)
+    ///
+    /// Rules nested in the block of a conditional group rule such as
+    /// `@media`; empty for every other at-rule.
+    pub rules: Vec<Rule>,
+}
+
+/// [§ 2 Processing of conditional group rules](https://www.w3.org/TR/css-conditional-3/#processing)
+const fn is_conditional_group_rule_name(name: &str) -> bool {
+    name.eq_ignore_ascii_case("media") || name.eq_ignore_ascii_case("supports")
+}
+
+/// Whether the cascade applies at-rules named `name`. Any other at-rule is
+/// parsed (so the rules after it are unaffected) and then ignored.
+const fn is_supported_at_rule_name(name: &str) -> bool {
+    is_conditional_group_rule_name(name)
+}
+
+/// [§ 2.2 Error Handling](https://www.w3.org/TR/css-syntax-3/#error-handling)
+///
+/// "When errors occur in CSS, the parser attempts to recover gracefully,
+/// throwing away only the minimum amount of content before returning to
+/// parsing as normal."
+///
+/// A piece of input the parser recovered from by skipping it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseIssue {
+    /// Description of what was skipped.
+    pub message: String,
+    /// Index into the token stream where the skipped input starts.
+    pub token_index: usize,
+}
+
+/// A summary of a stylesheet's contents, from [`Stylesheet::stats`].
+///
+/// Rules nested in `@media` and `@supports` are counted, whatever their
+/// condition.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StylesheetStats {
+    /// Style rules and at-rules.
+    pub rules: usize,
+    /// Declarations in all style rules.
+    pub declarations: usize,
+    /// Distinct property names across all declarations, ignoring case.
+    pub distinct_properties: usize,
+    /// Style rules with at least one selector the engine cannot match,
+    /// either because it fails to parse or because it uses an unsupported
+    /// pseudo-class or pseudo-element such as `:has()`.
+    pub unsupported_selector_rules: usize,
 }
 
 /// [§ 5.3.3 Consume a list of rules](https://www.w3.org/TR/css-syntax-3/#consume-list-of-rules)
@@ -106,6 +160,174 @@ pub struct Stylesheet {
     pub rules: Vec<Rule>,
 }
 
+impl Stylesheet {
+    /// [§ 5.3.3 Parse a stylesheet](https://www.w3.org/TR/css-syntax-3/#parse-stylesheet)
+    ///
+    /// Tokenize and parse CSS source text into a stylesheet.
+    ///
+    /// CSS parsing never fails: invalid rules and declarations are dropped
+    /// during error recovery, so this returns a `Stylesheet` rather than a
+    /// `Result` and does not implement [`std::str::FromStr`].
+    #[must_use]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(css: &str) -> Self {
+        let mut parser = CSSParser::new(CSSTokenizer::tokenize(css));
+        parser.parse_stylesheet()
+    }
+
+    /// [§ 6.1 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
+    ///
+    /// "Declarations from style sheets independently linked by the originating
+    /// document are treated as if they were concatenated in linking order."
+    ///
+    /// Append `other`'s rules after this stylesheet's rules. Declarations in
+    /// `other` are renumbered so their `source_order` stays greater than every
+    /// declaration already in `self`.
+    #[must_use]
+    pub fn merge(mut self, mut other: Self) -> Self {
+        let offset = max_source_order(&self.rules).map_or(0, |order| order + 1);
+        renumber_declarations(&mut other.rules, offset);
+        self.rules.append(&mut other.rules);
+        self
+    }
+
+    /// [§ 2 Processing of conditional group rules](https://www.w3.org/TR/css-conditional-3/#processing)
+    ///
+    /// This stylesheet as it applies to `media`: the rules of every `@media`
+    /// rule whose query list matches are spliced in place of it, and
+    /// non-matching `@media` rules are dropped. `@media` rules nested in
+    /// `@supports` are resolved the same way. Declarations keep their
+    /// `source_order`, so the cascade still sees document order.
+    #[must_use]
+    pub fn for_media(&self, media: MediaType) -> Self {
+        Self {
+            rules: rules_for_media(&self.rules, media),
+        }
+    }
+
+    /// Count this stylesheet's rules, declarations and properties, and the
+    /// rules whose selectors the engine does not support, for debugging
+    /// why styles don't apply.
+    #[must_use]
+    pub fn stats(&self) -> StylesheetStats {
+        let mut stats = StylesheetStats::default();
+        let mut properties = HashSet::new();
+        collect_stats(&self.rules, &mut stats, &mut properties);
+        stats.distinct_properties = properties.len();
+        stats
+    }
+}
+
+fn collect_stats(rules: &[Rule], stats: &mut StylesheetStats, properties: &mut HashSet<String>) {
+    for rule in rules {
+        stats.rules += 1;
+        match rule {
+            Rule::Style(style_rule) => {
+                stats.declarations += style_rule.declarations.len();
+                for decl in &style_rule.declarations {
+                    let _ = properties.insert(decl.name.to_ascii_lowercase());
+                }
+                if !style_rule.selectors.iter().all(is_supported_selector) {
+                    stats.unsupported_selector_rules += 1;
+                }
+            }
+            Rule::At(at_rule) => collect_stats(&at_rule.rules, stats, properties),
+        }
+    }
+}
+
+/// Whether `selector` parses and contains no [`SimpleSelector::NeverMatch`].
+fn is_supported_selector(selector: &Selector) -> bool {
+    parse_selector(&selector.text).is_some_and(|parsed| {
+        std::iter::once(&parsed.complex.subject)
+            .chain(
+                parsed
+                    .complex
+                    .combinators
+                    .iter()
+                    .map(|(_, compound)| compound),
+            )
+            .flat_map(|compound| &compound.simple_selectors)
+            .all(|simple| !matches!(simple, SimpleSelector::NeverMatch))
+    })
+}
+
+fn rules_for_media(rules: &[Rule], media: MediaType) -> Vec<Rule> {
+    let mut out = Vec::with_capacity(rules.len());
+    for rule in rules {
+        match rule {
+            Rule::At(at_rule) if at_rule.name.eq_ignore_ascii_case("media") => {
+                if media_query_list_matches(&at_rule.prelude, media) {
+                    out.extend(rules_for_media(&at_rule.rules, media));
+                }
+            }
+            Rule::At(at_rule) if at_rule.name.eq_ignore_ascii_case("supports") => {
+                out.push(Rule::At(AtRule {
+                    rules: rules_for_media(&at_rule.rules, media),
+                    ..at_rule.clone()
+                }));
+            }
+            _ => out.push(rule.clone()),
+        }
+    }
+    out
+}
+
+/// The largest `source_order` of any declaration in `rules`, including
+/// rules nested in conditional group rules.
+fn max_source_order(rules: &[Rule]) -> Option<u32> {
+    rules
+        .iter()
+        .filter_map(|rule| match rule {
+            Rule::Style(style_rule) => style_rule
+                .declarations
+                .iter()
+                .map(|decl| decl.source_order)
+                .max(),
+            Rule::At(at_rule) => max_source_order(&at_rule.rules),
+        })
+        .max()
+}
+
+/// Add `offset` to the `source_order` of every declaration in `rules`,
+/// including rules nested in conditional group rules.
+fn renumber_declarations(rules: &mut [Rule], offset: u32) {
+    for rule in rules {
+        match rule {
+            Rule::Style(style_rule) => {
+                for decl in &mut style_rule.declarations {
+                    decl.source_order += offset;
+                }
+            }
+            Rule::At(at_rule) => renumber_declarations(&mut at_rule.rules, offset),
+        }
+    }
+}
+
+/// Append the tokens `values` were consumed from to `tokens`.
+fn push_component_value_tokens(values: &[ComponentValue], tokens: &mut Vec<CSSToken>) {
+    for value in values {
+        match value {
+            ComponentValue::Token(token) => tokens.push(token.clone()),
+            ComponentValue::Function { name, value } => {
+                tokens.push(CSSToken::Function(name.clone()));
+                push_component_value_tokens(value, tokens);
+                tokens.push(CSSToken::RightParen);
+            }
+            ComponentValue::Block { token, value } => {
+                let (open, close) = match token {
+                    '{' => (CSSToken::LeftBrace, CSSToken::RightBrace),
+                    '[' => (CSSToken::LeftBracket, CSSToken::RightBracket),
+                    _ => (CSSToken::LeftParen, CSSToken::RightParen),
+                };
+                tokens.push(open);
+                push_component_value_tokens(value, tokens);
+                tokens.push(close);
+            }
+        }
+    }
+}
+
 /// CSS parser
 pub struct CSSParser {
     tokens: Vec<CSSToken>,
@@ -116,6 +338,8 @@ pub struct CSSParser {
     /// time a declaration is parsed, providing a total ordering of all
     /// declarations in the stylesheet for cascade resolution.
     declaration_counter: u32,
+    /// Input skipped during error recovery, in the order it was found.
+    issues: Vec<ParseIssue>,
 }
 
 impl CSSParser {
@@ -126,9 +350,24 @@ impl CSSParser {
             tokens,
             position: 0,
             declaration_counter: 0,
+            issues: Vec::new(),
         }
     }
 
+    /// [§ 5.3 Parser Entry Points](https://www.w3.org/TR/css-syntax-3/#parser-entry-points)
+    ///
+    /// "If input is a list of CSS tokens or component values, return input."
+    ///
+    /// Create a parser over component values that were already consumed,
+    /// such as an [`AtRule::block`]. The values are turned back into the
+    /// tokens they were consumed from.
+    #[must_use]
+    pub fn from_component_values(values: &[ComponentValue]) -> Self {
+        let mut tokens = Vec::new();
+        push_component_value_tokens(values, &mut tokens);
+        Self::new(tokens)
+    }
+
     /// [§ 5.3.3 Parse a stylesheet](https://www.w3.org/TR/css-syntax-3/#parse-stylesheet)
     ///
     /// "To parse a stylesheet from input..."
@@ -138,6 +377,29 @@ impl CSSParser {
         Stylesheet { rules }
     }
 
+    /// [`parse_stylesheet`](Self::parse_stylesheet), also returning the
+    /// [`ParseIssue`]s recorded for input that was skipped: at-rules the
+    /// cascade does not support, malformed declarations, and rules that
+    /// are never applied.
+    pub fn parse_stylesheet_with_issues(&mut self) -> (Stylesheet, Vec<ParseIssue>) {
+        let stylesheet = self.parse_stylesheet();
+        (stylesheet, std::mem::take(&mut self.issues))
+    }
+
+    /// [§ 5.3.9 Parse a list of component values](https://www.w3.org/TR/css-syntax-3/#parse-list-of-component-values)
+    ///
+    /// Parse the whole input as component values, e.g. a media query list
+    /// taken from an HTML `media` attribute. Commas are kept as tokens.
+    pub fn parse_component_values(&mut self) -> Vec<ComponentValue> {
+        let mut values = Vec::new();
+        while !matches!(self.peek(), None | Some(CSSToken::EOF)) {
+            if let Some(value) = self.consume_component_value() {
+                values.push(value);
+            }
+        }
+        values
+    }
+
     /// [§ 5.3.6 Parse a list of declarations](https://www.w3.org/TR/css-syntax-3/#parse-list-of-declarations)
     ///
     /// Parse declarations from a style attribute or similar.
@@ -165,6 +427,12 @@ impl CSSParser {
                     return rules;
                 }
 
+                // The closing brace of a conditional group rule's block ends
+                // its nested list of rules; the caller consumes it.
+                Some(CSSToken::RightBrace) if !top_level => {
+                    return rules;
+                }
+
                 // "<CDO-token>" or "<CDC-token>"
                 Some(CSSToken::CDO | CSSToken::CDC) => {
                     if top_level {
@@ -202,12 +470,22 @@ impl CSSParser {
 
     /// [§ 5.4.2 Consume an at-rule](https://www.w3.org/TR/css-syntax-3/#consume-at-rule)
     fn consume_at_rule(&mut self) -> Option<AtRule> {
+        let token_index = self.position;
         // "Consume the next input token."
         let Some(CSSToken::AtKeyword(name)) = self.consume() else {
             return None;
         };
         let name = name.clone();
 
+        // The whole at-rule is still consumed below, so an unsupported one
+        // only costs itself and never the rules that follow it.
+        if !is_supported_at_rule_name(&name) {
+            self.issues.push(ParseIssue {
+                message: format!("unsupported at-rule '@{name}' ignored"),
+                token_index,
+            });
+        }
+
         // "Create a new at-rule with its name set to the value of the current input
         // token, its prelude initially set to an empty list, and its value initially
         // set to nothing."
@@ -223,6 +501,7 @@ impl CSSParser {
                         name,
                         prelude,
                         block: None,
+                        rules: Vec::new(),
                     });
                 }
 
@@ -233,6 +512,26 @@ impl CSSParser {
                         name,
                         prelude,
                         block: None,
+                        rules: Vec::new(),
+                    });
+                }
+
+                // [§ 2 Processing of conditional group rules](https://www.w3.org/TR/css-conditional-3/#processing)
+                //
+                // The block of a conditional group rule is parsed as a list
+                // of rules, so its declarations are numbered in document
+                // order along with the surrounding rules.
+                Some(CSSToken::LeftBrace) if is_conditional_group_rule_name(&name) => {
+                    let _ = self.consume(); // {
+                    let rules = self.consume_list_of_rules(false);
+                    if self.peek() == Some(&CSSToken::RightBrace) {
+                        let _ = self.consume();
+                    }
+                    return Some(AtRule {
+                        name,
+                        prelude,
+                        block: None,
+                        rules,
                     });
                 }
 
@@ -245,6 +544,7 @@ impl CSSParser {
                         name,
                         prelude,
                         block: Some(block),
+                        rules: Vec::new(),
                     });
                 }
 
@@ -262,6 +562,7 @@ impl CSSParser {
 
     /// [§ 5.4.3 Consume a qualified rule](https://www.w3.org/TR/css-syntax-3/#consume-qualified-rule)
     fn consume_qualified_rule(&mut self) -> Option<StyleRule> {
+        let token_index = self.position;
         // "Create a new qualified rule with its prelude initially set to an empty list,
         // and its value initially set to nothing."
         let mut prelude_tokens = Vec::new();
@@ -271,6 +572,11 @@ impl CSSParser {
                 // "<EOF-token>"
                 // "This is a parse error. Return nothing."
                 None | Some(CSSToken::EOF) => {
+                    self.issues.push(ParseIssue {
+                        message: "rule without a '{' block at the end of the stylesheet dropped"
+                            .to_string(),
+                        token_index,
+                    });
                     return None;
                 }
 
@@ -284,6 +590,12 @@ impl CSSParser {
                     // [§ 5.1 Selector Lists](https://www.w3.org/TR/selectors-4/#selector-list)
                     // "A selector list is a comma-separated list of selectors"
                     let selectors = split_selector_list(&prelude_tokens);
+                    if selectors.is_empty() {
+                        self.issues.push(ParseIssue {
+                            message: "rule without a selector never applies".to_string(),
+                            token_index,
+                        });
+                    }
 
                     // Parse declarations from block contents
                     let declarations = self.consume_style_block_contents();
@@ -374,11 +686,20 @@ impl CSSParser {
                 }
 
                 // "<ident-token>"
-                // "Consume a declaration. If anything was returned, append it to
-                // the list of declarations."
+                // "Initialize a temporary list initially filled with the current
+                // input token. As long as the next input token is anything other
+                // than a <semicolon-token> or <EOF-token>, consume a component
+                // value and append it to the temporary list. Consume a
+                // declaration from the temporary list. If anything was returned,
+                // append it to the list of declarations."
+                //
+                // A declaration is consumed in place instead; when it fails, the
+                // rest of its temporary list is thrown away here.
                 Some(CSSToken::Ident(_)) => {
                     if let Some(decl) = self.consume_declaration() {
                         declarations.push(decl);
+                    } else {
+                        self.skip_to_declaration_end();
                     }
                 }
 
@@ -387,20 +708,35 @@ impl CSSParser {
                 // the next input token is anything other than a <semicolon-token> or
                 // <EOF-token>, consume a component value and throw away the returned value."
                 Some(_) => {
+                    self.issues.push(ParseIssue {
+                        message: "declaration not starting with a property name dropped"
+                            .to_string(),
+                        token_index: self.position,
+                    });
                     let _ = self.consume();
-                    while !matches!(
-                        self.peek(),
-                        None | Some(CSSToken::Semicolon | CSSToken::RightBrace | CSSToken::EOF)
-                    ) {
-                        let _ = self.consume_component_value();
-                    }
+                    self.skip_to_declaration_end();
                 }
             }
         }
     }
 
+    /// Throw away component values up to the `;` or `}` that ends the
+    /// current declaration, or the end of input.
+    fn skip_to_declaration_end(&mut self) {
+        while !matches!(
+            self.peek(),
+            None | Some(CSSToken::Semicolon | CSSToken::RightBrace | CSSToken::EOF)
+        ) {
+            let _ = self.consume_component_value();
+        }
+    }
+
     /// [§ 5.4.6 Consume a declaration](https://www.w3.org/TR/css-syntax-3/#consume-declaration)
+    ///
+    /// Declarations without a colon or with an empty value are dropped and
+    /// recorded as [`ParseIssue`]s. Custom properties may be empty.
     fn consume_declaration(&mut self) -> Option<Declaration> {
+        let token_index = self.position;
         // "Consume the next input token."
         let Some(CSSToken::Ident(name)) = self.consume() else {
             return None;
@@ -415,6 +751,10 @@ impl CSSParser {
         // "If the next input token is anything other than a <colon-token>, this is a parse error.
         // Return nothing."
         if self.peek() != Some(&CSSToken::Colon) {
+            self.issues.push(ParseIssue {
+                message: format!("declaration '{name}' without a ':' dropped"),
+                token_index,
+            });
             return None;
         }
         let _ = self.consume(); // :
@@ -442,6 +782,18 @@ impl CSSParser {
         // Remove trailing whitespace and !important from value
         let value = trim_important(value);
 
+        // [§ 2.2 Error Handling](https://www.w3.org/TR/css-syntax-3/#error-handling)
+        //
+        // No property accepts an empty value, so the declaration is invalid
+        // and ignored; only custom properties may be empty.
+        if value.is_empty() && !name.starts_with("--") {
+            self.issues.push(ParseIssue {
+                message: format!("declaration '{name}' with an empty value dropped"),
+                token_index,
+            });
+            return None;
+        }
+
         // Assign source order and increment counter
         let source_order = self.declaration_counter;
         self.declaration_counter += 1;
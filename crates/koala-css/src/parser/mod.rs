@@ -4,5 +4,6 @@
 pub mod css_parser;
 
 pub use css_parser::{
-    AtRule, CSSParser, ComponentValue, Declaration, Rule, Selector, StyleRule, Stylesheet,
+    AtRule, CSSParser, ComponentValue, Declaration, ParseIssue, Rule, Selector, StyleRule,
+    Stylesheet, StylesheetStats,
 };
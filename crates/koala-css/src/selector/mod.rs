@@ -4,6 +4,7 @@
 //! [Selectors Level 4](https://www.w3.org/TR/selectors-4/).
 
 use koala_dom::{DomTree, ElementData, NodeId, NodeType};
+use koala_std::collections::{HashMap, HashSet};
 
 /// [§ 5 Elemental selectors](https://www.w3.org/TR/selectors-4/#elemental-selectors)
 /// [§ 6 Attribute selectors](https://www.w3.org/TR/selectors-4/#attribute-selectors)
@@ -41,19 +42,20 @@ pub enum SimpleSelector {
 
     /// Pseudo-class or pseudo-element that always fails to match.
     /// Used for interactive states (`:hover`, `:focus`, `:active`, `:visited`, etc.)
-    /// and pseudo-elements (`::before`, `::after`, etc.) that are irrelevant
-    /// to static rendering but whose presence should not cause the entire
-    /// rule to be dropped.
+    /// and unsupported pseudo-elements (`::marker`, `::placeholder`, etc.)
+    /// that are irrelevant to static rendering but whose presence should
+    /// not cause the entire rule to be dropped.
     ///
-    /// Examples: `:hover`, `:focus`, `:active`, `:visited`, `::before`, `::after`,
-    /// `::placeholder`, `:nth-child(2)`, `:not(.foo)`
+    /// Examples: `:hover`, `:focus`, `:active`, `:visited`, `::marker`,
+    /// `::placeholder`, `:not(.foo)`
     NeverMatch,
 
     /// [§ 4 Pseudo-classes](https://www.w3.org/TR/selectors-4/#pseudo-classes)
     /// Structural pseudo-class that requires DOM tree context to match.
     ///
     /// Examples: `:root`, `:first-child`, `:last-child`, `:empty`, `:only-child`,
-    /// `:first-of-type`, `:last-of-type`, `:link`, `:enabled`, `:disabled`
+    /// `:first-of-type`, `:last-of-type`, `:nth-child(2n+1)`, `:link`,
+    /// `:enabled`, `:disabled`
     PseudoClass(PseudoClass),
 
     /// [§ 6.4 Attribute selectors](https://www.w3.org/TR/selectors-4/#attribute-selectors)
@@ -101,12 +103,61 @@ pub enum PseudoClass {
     /// Example: `p:last-of-type` — matches the last `<p>` among its siblings
     LastOfType,
 
+    /// [§ 4.13.1 :nth-child()](https://www.w3.org/TR/selectors-4/#the-nth-child-pseudo)
+    /// "The :nth-child(An+B [of S]? ) pseudo-class notation represents
+    /// elements that are among An+Bth elements from the list composed of
+    /// their inclusive siblings that match the selector list S."
+    ///
+    /// Example: `li:nth-child(2n+1)` — matches the 1st, 3rd, 5th... `<li>`
+    NthChild(AnPlusB),
+
+    /// [§ 4.13.2 :nth-last-child()](https://www.w3.org/TR/selectors-4/#the-nth-last-child-pseudo)
+    /// "The :nth-last-child(An+B [of S]? ) pseudo-class notation represents
+    /// elements that are among An+Bth elements from the list composed of
+    /// their inclusive siblings that match the selector list S, counting
+    /// backwards from the end."
+    ///
+    /// Example: `li:nth-last-child(1)` — same as `li:last-child`
+    NthLastChild(AnPlusB),
+
+    /// [§ 4.14.1 :nth-of-type()](https://www.w3.org/TR/selectors-4/#the-nth-of-type-pseudo)
+    /// "The :nth-of-type(An+B) pseudo-class notation represents elements
+    /// that are among An+Bth elements from the list composed of their
+    /// inclusive siblings that are of the same type."
+    ///
+    /// Example: `p:nth-of-type(2)` — matches the second `<p>`, ignoring
+    /// siblings of other types
+    NthOfType(AnPlusB),
+
+    /// [§ 4.14.2 :nth-last-of-type()](https://www.w3.org/TR/selectors-4/#the-nth-last-of-type-pseudo)
+    /// "The :nth-last-of-type(An+B) pseudo-class notation represents
+    /// elements that are among An+Bth elements from the list composed of
+    /// their inclusive siblings that are of the same type, counting
+    /// backwards from the end."
+    ///
+    /// Example: `p:nth-last-of-type(2)` — matches the second-to-last `<p>`
+    NthLastOfType(AnPlusB),
+
     /// [§ 4.12 :only-child](https://www.w3.org/TR/selectors-4/#the-only-child-pseudo)
     /// "The :only-child pseudo-class represents an element that has no siblings."
     ///
     /// Example: `p:only-child` — matches `<p>` in `<div><p>alone</p></div>`
     OnlyChild,
 
+    /// [§ 4.14.5 :only-of-type](https://www.w3.org/TR/selectors-4/#the-only-of-type-pseudo)
+    /// "The :only-of-type pseudo-class represents an element that has no
+    /// siblings with the same expanded element name."
+    ///
+    /// Example: `h1:only-of-type` — matches `<h1>` in `<div><h1/><p/><p/></div>`
+    OnlyOfType,
+
+    /// [§ 8.2 :target](https://www.w3.org/TR/selectors-4/#the-target-pseudo)
+    /// "The :target pseudo-class matches the document's target elements."
+    ///
+    /// Example: `section:target` — matches `<section id="intro">` when the
+    /// document was loaded as `page.html#intro`
+    Target,
+
     /// [§ 4.5 :empty](https://www.w3.org/TR/selectors-4/#the-empty-pseudo)
     /// "The :empty pseudo-class represents an element that has no children at all."
     ///
@@ -129,6 +180,238 @@ pub enum PseudoClass {
     ///
     /// Example: `input:enabled` — matches `<input>` (no disabled attribute)
     Enabled,
+
+    /// [§ 9.3 :focus](https://www.w3.org/TR/selectors-4/#the-focus-pseudo)
+    /// "The :focus pseudo-class applies while an element (as defined by the
+    /// host language) has the focus (accepts keyboard or mouse events, or
+    /// other forms of input)."
+    ///
+    /// Example: `input:focus` — matches the [`MatchContext::focused`] element
+    Focus,
+
+    /// [§ 9.4 :focus-visible](https://www.w3.org/TR/selectors-4/#the-focus-visible-pseudo)
+    /// "The :focus-visible pseudo-class applies while an element matches the
+    /// :focus pseudo-class and the user agent determines via heuristics that
+    /// the focus should be made evident on the element."
+    ///
+    /// Example: `button:focus-visible` — matches the focused element only
+    /// when [`MatchContext::focus_visible`] is set
+    FocusVisible,
+
+    /// [§ 9.2 :hover](https://www.w3.org/TR/selectors-4/#the-hover-pseudo)
+    /// "The :hover pseudo-class applies while the user designates an element
+    /// with a pointing device, but does not necessarily activate it."
+    ///
+    /// Example: `li:hover` — matches an element in [`MatchContext::hovered`]
+    /// or an ancestor of one
+    Hover,
+
+    /// [§ 4.16.3 Pseudo-classes](https://html.spec.whatwg.org/multipage/semantics-other.html#selector-checked)
+    /// ":checked must match any element falling into one of the following
+    /// categories: input elements whose type attribute is in the Checkbox
+    /// state and whose checkedness is true; input elements whose type
+    /// attribute is in the Radio Button state and whose checkedness is
+    /// true; option elements whose selectedness is true"
+    ///
+    /// Example: `input:checked` — matches `<input type="checkbox" checked>`
+    Checked,
+
+    /// [§ 7.2 :lang()](https://www.w3.org/TR/selectors-4/#the-lang-pseudo)
+    /// "The `:lang()` pseudo-class represents an element that is in one of the
+    /// languages listed in its argument."
+    ///
+    /// Example: `:lang(en)` — matches every element under
+    /// `<html lang="en-US">`, but nothing under `<html lang="fr">`
+    Lang(String),
+}
+
+/// [CSS Syntax § 6 The An+B microsyntax](https://www.w3.org/TR/css-syntax-3/#anb-microsyntax)
+///
+/// "The An+B notation defines an integer step (A) and offset (B), and
+/// represents the An+Bth elements in a list, for every positive integer or
+/// zero value of n, with the first element in the list having index 1 (not
+/// 0)."
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnPlusB {
+    /// The step, A.
+    pub a: i32,
+    /// The offset, B.
+    pub b: i32,
+}
+
+impl AnPlusB {
+    /// Parse the argument of an `:nth-*()` pseudo-class, e.g. `odd`,
+    /// `2n+1`, `-n + 3` or `4`. Returns `None` for anything else, including
+    /// the `of S` form.
+    #[must_use]
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim().to_ascii_lowercase();
+
+        // "In addition to this, the keywords odd and even can be used:
+        // odd has the same meaning as 2n+1, and even has the same meaning
+        // as 2n."
+        match input.as_str() {
+            "odd" => return Some(Self { a: 2, b: 1 }),
+            "even" => return Some(Self { a: 2, b: 0 }),
+            _ => {}
+        }
+
+        // "When A is 0, the An part may be omitted (unless the B part is
+        // already omitted)."
+        let Some((step, offset)) = input.split_once('n') else {
+            return Some(Self {
+                a: 0,
+                b: parse_signed_integer(&input)?,
+            });
+        };
+
+        // "When A is 1 or -1, the 1 may be omitted from the rule."
+        let a = match step {
+            "" | "+" => 1,
+            "-" => -1,
+            _ => parse_signed_integer(step)?,
+        };
+
+        // "When B is 0, the B part may be omitted." Whitespace is allowed
+        // on both sides of the sign between An and B, but not after a
+        // sign that belongs to B alone.
+        let offset = offset.trim_start();
+        let b = if offset.is_empty() {
+            0
+        } else {
+            let (sign, digits) = offset.split_at(1);
+            let digits = digits.trim_start();
+            if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+                return None;
+            }
+            match sign {
+                "+" => parse_signed_integer(digits)?,
+                "-" => -parse_signed_integer(digits)?,
+                _ => return None,
+            }
+        };
+        Some(Self { a, b })
+    }
+
+    /// Whether the element at 1-based `index` is one of the An+Bth
+    /// elements, for some n ≥ 0.
+    #[must_use]
+    pub fn matches(self, index: usize) -> bool {
+        let Ok(index) = i64::try_from(index) else {
+            return false;
+        };
+        let (a, b) = (i64::from(self.a), i64::from(self.b));
+        if a == 0 {
+            return index == b;
+        }
+        let steps = index - b;
+        steps % a == 0 && steps / a >= 0
+    }
+}
+
+/// Split the argument of an `:nth-*()` pseudo-class into its An+B part
+/// and, for the `An+B of S` form, the selector list `S` after the `of`
+/// keyword.
+fn split_of_selector(argument: &str) -> (&str, Option<&str>) {
+    let mut rest = argument;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_whitespace()) {
+        let after = rest[start..].trim_start();
+        if let Some(keyword) = after.get(..2)
+            && keyword.eq_ignore_ascii_case("of")
+            && after[2..].starts_with(|c: char| c.is_ascii_whitespace())
+        {
+            let an_b_len = argument.len() - rest.len() + start;
+            return (&argument[..an_b_len], Some(after[2..].trim()));
+        }
+        rest = after;
+    }
+    (argument, None)
+}
+
+/// An optionally signed run of ASCII digits, as in An+B's `<integer>`s.
+fn parse_signed_integer(input: &str) -> Option<i32> {
+    let digits = input.strip_prefix(['+', '-']).unwrap_or(input);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    input.parse().ok()
+}
+
+/// Document state that user-action pseudo-classes match against.
+///
+/// [§ 9 User Action Pseudo-classes](https://www.w3.org/TR/selectors-4/#useraction-pseudos)
+///
+/// The default context has nothing focused or hovered and leaves every
+/// control in its default checkedness, which is what headless rendering
+/// uses. The GUI fills it in from user input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchContext {
+    /// The element that has the focus, matched by `:focus`.
+    pub focused: Option<NodeId>,
+    /// Whether the focus should be made evident, e.g. because it was moved
+    /// with the keyboard. `:focus-visible` matches only when this is set.
+    pub focus_visible: bool,
+    /// The elements the pointing device designates, matched by `:hover`
+    /// together with their ancestors.
+    pub hovered: HashSet<NodeId>,
+    /// Checkedness (or, for `<option>`, selectedness) changed by user
+    /// input, matched by `:checked`. Controls missing from the map keep
+    /// the default given by their `checked` or `selected` attribute.
+    pub checked: HashMap<NodeId, bool>,
+    /// The fragment of the document's URL, without the `#`. The element
+    /// whose id equals it is matched by `:target`.
+    pub target: Option<String>,
+}
+
+impl MatchContext {
+    /// A context for a document loaded from `url`, whose fragment (if
+    /// any) names the `:target` element.
+    #[must_use]
+    pub fn for_url(url: &str) -> Self {
+        Self {
+            target: url
+                .split_once('#')
+                .map(|(_, fragment)| fragment.to_string()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Tree-abiding pseudo-elements per [CSS Pseudo-Elements § 4](https://www.w3.org/TR/css-pseudo-4/#generated-content)
+///
+/// "Pseudo-elements represent abstract elements of the document beyond
+/// those elements explicitly created by the document language."
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PseudoElement {
+    /// [§ 4.1 ::before](https://www.w3.org/TR/css-pseudo-4/#selectordef-before)
+    /// "Represents a styleable child pseudo-element immediately before the
+    /// originating element's actual content."
+    ///
+    /// Example: `li::before { content: "• "; }`
+    Before,
+
+    /// [§ 4.1 ::after](https://www.w3.org/TR/css-pseudo-4/#selectordef-after)
+    /// "Represents a styleable child pseudo-element immediately after the
+    /// originating element's actual content."
+    ///
+    /// Example: `a::after { content: " →"; }`
+    After,
+
+    /// [§ 2.1 ::first-line](https://www.w3.org/TR/css-pseudo-4/#first-line-pseudo)
+    /// "The `::first-line` pseudo-element describes the contents of the first
+    /// formatted line of its originating element."
+    ///
+    /// Example: `p::first-line { color: red; }`
+    FirstLine,
+
+    /// [§ 2.2 ::first-letter](https://www.w3.org/TR/css-pseudo-4/#first-letter-pseudo)
+    /// "The `::first-letter` pseudo-element represents the first typographic
+    /// letter unit on the first formatted line of its originating element,
+    /// if it is not preceded by any other in-flow content (such as images
+    /// or inline tables) on its line."
+    ///
+    /// Example: `p::first-letter { font-size: 2em; }`
+    FirstLetter,
 }
 
 /// Attribute selectors per [§ 6.4](https://www.w3.org/TR/selectors-4/#attribute-selectors)
@@ -274,6 +557,14 @@ pub struct ParsedSelector {
     pub complex: ComplexSelector,
     /// The specificity of this selector.
     pub specificity: Specificity,
+    /// [§ 3.6.1 Pseudo-elements](https://www.w3.org/TR/selectors-4/#pseudo-elements)
+    ///
+    /// "Pseudo-elements ... represent elements not directly present in the
+    /// document tree."
+    ///
+    /// When set, the selector matches this pseudo-element of the elements
+    /// its complex selector matches, and never the elements themselves.
+    pub pseudo_element: Option<PseudoElement>,
 }
 
 impl ParsedSelector {
@@ -293,6 +584,11 @@ impl ParsedSelector {
     /// with combinators, use `matches_in_tree` which has access to DOM context.
     #[must_use]
     pub fn matches(&self, element: &ElementData) -> bool {
+        // A pseudo-element selector never matches a real element.
+        if self.pseudo_element.is_some() {
+            return false;
+        }
+
         // First, the subject (rightmost compound) must match the element
         let subject_matches = self
             .complex
@@ -327,10 +623,66 @@ impl ParsedSelector {
     ///
     /// # Returns
     /// `true` if the selector matches the element
+    ///
+    /// Nothing is considered focused; use [`Self::matches_in_tree_ctx`] to
+    /// match user-action pseudo-classes.
     #[must_use]
     pub fn matches_in_tree(&self, tree: &DomTree, node_id: NodeId) -> bool {
+        self.matches_in_tree_ctx(tree, node_id, &MatchContext::default())
+    }
+
+    /// Like [`Self::matches_in_tree`], but user-action pseudo-classes such as
+    /// `:focus` are matched against `ctx`.
+    #[must_use]
+    pub fn matches_in_tree_ctx(&self, tree: &DomTree, node_id: NodeId, ctx: &MatchContext) -> bool {
+        // A pseudo-element selector never matches a real element.
+        if self.pseudo_element.is_some() {
+            return false;
+        }
+        self.matches_originating_element(tree, node_id, ctx)
+    }
+
+    /// [§ 3.6.1 Pseudo-elements](https://www.w3.org/TR/selectors-4/#pseudo-elements)
+    ///
+    /// "A pseudo-element is always attached to another element in the
+    /// document tree, called its originating element."
+    ///
+    /// Whether this selector matches the `pseudo` pseudo-element of the
+    /// element `node_id`.
+    #[must_use]
+    pub fn matches_pseudo_element_in_tree(
+        &self,
+        tree: &DomTree,
+        node_id: NodeId,
+        pseudo: PseudoElement,
+    ) -> bool {
+        self.matches_pseudo_element_in_tree_ctx(tree, node_id, pseudo, &MatchContext::default())
+    }
+
+    /// Like [`Self::matches_pseudo_element_in_tree`], but user-action
+    /// pseudo-classes on the originating element are matched against
+    /// `ctx`.
+    #[must_use]
+    pub fn matches_pseudo_element_in_tree_ctx(
+        &self,
+        tree: &DomTree,
+        node_id: NodeId,
+        pseudo: PseudoElement,
+        ctx: &MatchContext,
+    ) -> bool {
+        self.pseudo_element == Some(pseudo) && self.matches_originating_element(tree, node_id, ctx)
+    }
+
+    /// Match the complex selector against `node_id`, ignoring any
+    /// pseudo-element.
+    fn matches_originating_element(
+        &self,
+        tree: &DomTree,
+        node_id: NodeId,
+        ctx: &MatchContext,
+    ) -> bool {
         // First, the subject (rightmost compound) must match the element
-        if !compound_matches_in_tree(&self.complex.subject, tree, node_id) {
+        if !compound_matches_in_tree(&self.complex.subject, tree, node_id, ctx) {
             return false;
         }
 
@@ -340,7 +692,7 @@ impl ParsedSelector {
         }
 
         // Complex selectors require DOM traversal
-        self.matches_combinators(tree, node_id)
+        self.matches_combinators(tree, node_id, ctx)
     }
 
     /// [§ 16 Combinators](https://www.w3.org/TR/selectors-4/#combinators)
@@ -355,10 +707,11 @@ impl ParsedSelector {
     /// # Arguments
     /// * `tree` - The DOM tree
     /// * `subject_id` - The `NodeId` of the subject element (already matched)
+    /// * `ctx` - User-action state for `:focus` and friends
     ///
     /// # Returns
     /// `true` if all combinator relationships are satisfied
-    fn matches_combinators(&self, tree: &DomTree, subject_id: NodeId) -> bool {
+    fn matches_combinators(&self, tree: &DomTree, subject_id: NodeId, ctx: &MatchContext) -> bool {
         let mut current_id = subject_id;
 
         // Walk the combinator chain (right-to-left, from subject outward)
@@ -369,9 +722,9 @@ impl ParsedSelector {
                 // arbitrary descendant of some ancestor element A."
                 Combinator::Descendant => {
                     // Find any ancestor that matches the compound selector
-                    let matched_ancestor = tree
-                        .ancestors(current_id)
-                        .find(|&ancestor_id| compound_matches_in_tree(compound, tree, ancestor_id));
+                    let matched_ancestor = tree.ancestors(current_id).find(|&ancestor_id| {
+                        compound_matches_in_tree(compound, tree, ancestor_id, ctx)
+                    });
 
                     match matched_ancestor {
                         Some(ancestor_id) => current_id = ancestor_id,
@@ -388,7 +741,7 @@ impl ParsedSelector {
                         return false;
                     };
 
-                    if !compound_matches_in_tree(compound, tree, parent_id) {
+                    if !compound_matches_in_tree(compound, tree, parent_id, ctx) {
                         return false;
                     }
 
@@ -405,7 +758,7 @@ impl ParsedSelector {
                         return false;
                     };
 
-                    if !compound_matches_in_tree(compound, tree, prev_id) {
+                    if !compound_matches_in_tree(compound, tree, prev_id, ctx) {
                         return false;
                     }
 
@@ -420,7 +773,7 @@ impl ParsedSelector {
                 Combinator::SubsequentSibling => {
                     // Find any preceding element sibling that matches
                     let matched_sibling =
-                        find_matching_preceding_sibling(tree, current_id, compound);
+                        find_matching_preceding_sibling(tree, current_id, compound, ctx);
 
                     match matched_sibling {
                         Some(sibling_id) => current_id = sibling_id,
@@ -437,24 +790,30 @@ impl ParsedSelector {
 
 /// Check if a compound selector matches an element, with optional tree context
 /// for structural pseudo-class matching.
-fn compound_matches_in_tree(compound: &CompoundSelector, tree: &DomTree, node_id: NodeId) -> bool {
+fn compound_matches_in_tree(
+    compound: &CompoundSelector,
+    tree: &DomTree,
+    node_id: NodeId,
+    ctx: &MatchContext,
+) -> bool {
     let Some(element) = tree.as_element(node_id) else {
         return false;
     };
     compound.simple_selectors.iter().all(|simple| match simple {
-        SimpleSelector::PseudoClass(pc) => pseudo_class_matches(pc, tree, node_id, element),
+        SimpleSelector::PseudoClass(pc) => pseudo_class_matches(pc, tree, node_id, element, ctx),
         _ => simple.matches(element),
     })
 }
 
 /// [§ 4 Pseudo-classes](https://www.w3.org/TR/selectors-4/#pseudo-classes)
 ///
-/// Match a structural pseudo-class against an element with full DOM tree context.
+/// Match a pseudo-class against an element with full DOM tree context.
 fn pseudo_class_matches(
     pc: &PseudoClass,
     tree: &DomTree,
     node_id: NodeId,
     element: &ElementData,
+    ctx: &MatchContext,
 ) -> bool {
     match pc {
         // [§ 4.4 :root](https://www.w3.org/TR/selectors-4/#the-root-pseudo)
@@ -503,6 +862,29 @@ fn pseudo_class_matches(
             }) == Some(&node_id)
         }),
 
+        // [§ 4.13 Child-indexed Pseudo-classes](https://www.w3.org/TR/selectors-4/#child-index)
+        // "The pseudo-classes defined in this section select elements based
+        // on their index amongst their inclusive siblings."
+        PseudoClass::NthChild(an_b) => {
+            element_index(tree, node_id, None, false).is_some_and(|i| an_b.matches(i))
+        }
+        PseudoClass::NthLastChild(an_b) => {
+            element_index(tree, node_id, None, true).is_some_and(|i| an_b.matches(i))
+        }
+
+        // [§ 4.14 Typed Child-indexed Pseudo-classes](https://www.w3.org/TR/selectors-4/#typed-child-index)
+        // "The pseudo-classes in this section are similar to the Child Index
+        // Pseudo-classes, but they resolve based on an element's inclusive
+        // siblings that have the same type (tag name)."
+        PseudoClass::NthOfType(an_b) => {
+            element_index(tree, node_id, Some(&element.tag_name), false)
+                .is_some_and(|i| an_b.matches(i))
+        }
+        PseudoClass::NthLastOfType(an_b) => {
+            element_index(tree, node_id, Some(&element.tag_name), true)
+                .is_some_and(|i| an_b.matches(i))
+        }
+
         // [§ 4.12 :only-child](https://www.w3.org/TR/selectors-4/#the-only-child-pseudo)
         // "The :only-child pseudo-class represents an element that has no siblings."
         PseudoClass::OnlyChild => tree.parent(node_id).is_some_and(|parent| {
@@ -513,6 +895,35 @@ fn pseudo_class_matches(
                 == 1
         }),
 
+        // [§ 4.14.5 :only-of-type](https://www.w3.org/TR/selectors-4/#the-only-of-type-pseudo)
+        // "Same as :first-of-type:last-of-type or :nth-of-type(1):nth-last-of-type(1)"
+        PseudoClass::OnlyOfType => tree.parent(node_id).is_some_and(|parent| {
+            tree.children(parent)
+                .iter()
+                .filter(|&&c| {
+                    tree.as_element(c)
+                        .is_some_and(|e| e.tag_name.eq_ignore_ascii_case(&element.tag_name))
+                })
+                .count()
+                == 1
+        }),
+
+        // [§ 8.2 :target](https://www.w3.org/TR/selectors-4/#the-target-pseudo)
+        //
+        // [§ 7.4.6.4 Scrolling to a fragment](https://html.spec.whatwg.org/multipage/browsing-the-web.html#the-indicated-part-of-the-document)
+        // "If there is an element in the document tree whose root is
+        // document and that has an ID equal to fragment, then return the
+        // first such element in tree order."
+        //
+        // An empty fragment indicates the top of the document, not an element.
+        // NOTE: Percent-decoding the fragment and the `<a name>` fallback
+        // are not implemented.
+        PseudoClass::Target => ctx.target.as_deref().is_some_and(|fragment| {
+            !fragment.is_empty()
+                && element.id().map(String::as_str) == Some(fragment)
+                && first_element_with_id(tree, fragment) == Some(node_id)
+        }),
+
         // [§ 4.5 :empty](https://www.w3.org/TR/selectors-4/#the-empty-pseudo)
         // "The :empty pseudo-class represents an element that has no children at all.
         // In terms of the document tree, only element nodes and content nodes...
@@ -542,9 +953,112 @@ fn pseudo_class_matches(
 
         // :enabled — element does not have the disabled attribute
         PseudoClass::Enabled => !element.attrs.contains_key("disabled"),
+
+        // [§ 9.3 :focus](https://www.w3.org/TR/selectors-4/#the-focus-pseudo)
+        PseudoClass::Focus => ctx.focused == Some(node_id),
+
+        // [§ 9.4 :focus-visible](https://www.w3.org/TR/selectors-4/#the-focus-visible-pseudo)
+        // The "heuristics" are left to the GUI, which sets `focus_visible`
+        // when focus came from the keyboard.
+        PseudoClass::FocusVisible => ctx.focus_visible && ctx.focused == Some(node_id),
+
+        // [§ 9.2 :hover](https://www.w3.org/TR/selectors-4/#the-hover-pseudo)
+        // "An element also matches :hover if one of its descendants in the
+        // flat tree (including non-element nodes, such as text nodes)
+        // matches the above conditions."
+        PseudoClass::Hover => ctx
+            .hovered
+            .iter()
+            .any(|&hovered| hovered == node_id || tree.ancestors(hovered).any(|a| a == node_id)),
+
+        // [§ 4.16.3 Pseudo-classes](https://html.spec.whatwg.org/multipage/semantics-other.html#selector-checked)
+        PseudoClass::Checked => {
+            let is_toggle = element.tag_name.eq_ignore_ascii_case("input")
+                && element.attrs.get("type").is_some_and(|kind| {
+                    kind.eq_ignore_ascii_case("checkbox") || kind.eq_ignore_ascii_case("radio")
+                });
+            let is_option = element.tag_name.eq_ignore_ascii_case("option");
+            // "The checked content attribute is a boolean attribute that
+            // gives the default checkedness of the input element."
+            let default = if is_toggle {
+                element.attrs.contains_key("checked")
+            } else {
+                element.attrs.contains_key("selected")
+            };
+            (is_toggle || is_option) && ctx.checked.get(&node_id).copied().unwrap_or(default)
+        }
+
+        // [§ 7.2 :lang()](https://www.w3.org/TR/selectors-4/#the-lang-pseudo)
+        // "A language range ... matches a language tag if, case-insensitively,
+        // it exactly equals the tag, or if it exactly equals a prefix of the
+        // tag such that the first character following the prefix is "-"."
+        //
+        // NOTE: This is the `|=` attribute match applied to the content
+        // language; wildcards and extended filtering are not implemented.
+        PseudoClass::Lang(range) => content_language(tree, node_id).is_some_and(|tag| {
+            tag.eq_ignore_ascii_case(range)
+                || (tag.as_bytes().get(range.len()) == Some(&b'-')
+                    && tag[..range.len()].eq_ignore_ascii_case(range))
+        }),
     }
 }
 
+/// [§ 3.2.6.2 The lang and xml:lang attributes](https://html.spec.whatwg.org/multipage/dom.html#the-lang-and-xml:lang-attributes)
+///
+/// "To determine the language of a node, user agents must use the first
+/// appropriate step in the following list:"
+///
+/// "If the node is an element that has a lang attribute: The value of that
+/// attribute."
+///
+/// "If the node's parent is not null: The language of the node's parent."
+///
+/// "If the value of the attribute is the empty string (lang=""), the
+/// language of the node is explicitly unknown", so `None` is returned for
+/// it as for a document without any `lang`.
+fn content_language(tree: &DomTree, node_id: NodeId) -> Option<&str> {
+    std::iter::once(node_id)
+        .chain(tree.ancestors(node_id))
+        .find_map(|id| tree.as_element(id)?.attrs.get("lang"))
+        .map(String::as_str)
+        .filter(|lang| !lang.is_empty())
+}
+
+/// The first element in tree order whose id is `id`.
+fn first_element_with_id(tree: &DomTree, id: &str) -> Option<NodeId> {
+    tree.descendants(tree.root()).find(|&n| {
+        tree.as_element(n)
+            .is_some_and(|e| e.id().map(String::as_str) == Some(id))
+    })
+}
+
+/// The 1-based index of `node_id` among its element siblings, counted from
+/// the last sibling when `from_end` is set. With `tag_name`, only siblings
+/// of that type are counted. `None` for an element without a parent.
+fn element_index(
+    tree: &DomTree,
+    node_id: NodeId,
+    tag_name: Option<&str>,
+    from_end: bool,
+) -> Option<usize> {
+    let parent = tree.parent(node_id)?;
+    let children = tree.children(parent);
+    let counted = |&&c: &&NodeId| {
+        tree.as_element(c)
+            .is_some_and(|e| tag_name.is_none_or(|tag| e.tag_name.eq_ignore_ascii_case(tag)))
+    };
+    let position = if from_end {
+        children
+            .iter()
+            .rev()
+            .filter(counted)
+            .position(|&c| c == node_id)
+    } else {
+        children.iter().filter(counted).position(|&c| c == node_id)
+    };
+    position.map(|p| p + 1)
+}
+
 /// [§ 16.3 Next-sibling combinator](https://www.w3.org/TR/selectors-4/#adjacent-sibling-combinators)
 ///
 /// Find the immediately preceding element sibling (skipping text/comment nodes).
@@ -562,10 +1076,11 @@ fn find_matching_preceding_sibling(
     tree: &DomTree,
     node_id: NodeId,
     compound: &CompoundSelector,
+    ctx: &MatchContext,
 ) -> Option<NodeId> {
     tree.preceding_siblings(node_id).find(|&sibling_id| {
         tree.as_element(sibling_id).is_some()
-            && compound_matches_in_tree(compound, tree, sibling_id)
+            && compound_matches_in_tree(compound, tree, sibling_id, ctx)
     })
 }
 
@@ -800,8 +1315,18 @@ pub fn parse_selector(raw: &str) -> Option<ParsedSelector> {
     let mut chars = trimmed.chars().peekable();
     let mut current_compound = Vec::new();
     let mut current_ident = String::new();
+    let mut pseudo_element = None;
 
     while let Some(c) = chars.next() {
+        // [§ 3.6.1 Pseudo-elements](https://www.w3.org/TR/selectors-4/#pseudo-elements)
+        //
+        // "Pseudo-elements ... may only appear once in the last compound
+        // selector". Anything but trailing whitespace after one makes the
+        // selector unmatchable.
+        if pseudo_element.is_some() && !c.is_ascii_whitespace() {
+            current_compound.push(SimpleSelector::NeverMatch);
+        }
+
         match c {
             // [§ 6.6 Class selector](https://www.w3.org/TR/selectors-4/#class-html)
             // "The class selector is given as a full stop (. U+002E)
@@ -981,10 +1506,12 @@ pub fn parse_selector(raw: &str) -> Option<ParsedSelector> {
                 }
 
                 // If followed by '(', consume balanced parentheses
-                // (for :nth-child(...), :not(...), etc.)
-                if chars.peek() == Some(&'(') {
+                // (for :nth-child(...), :not(...), etc.), keeping the
+                // argument between them
+                let argument = if chars.peek() == Some(&'(') {
                     let _ = chars.next(); // consume '('
                     let mut depth = 1u32;
+                    let mut text = String::new();
                     for ch in chars.by_ref() {
                         match ch {
                             '(' => depth += 1,
@@ -996,16 +1523,44 @@ pub fn parse_selector(raw: &str) -> Option<ParsedSelector> {
                             }
                             _ => {}
                         }
+                        text.push(ch);
                     }
                     if depth != 0 {
                         return None; // unbalanced parentheses
                     }
-                }
+                    Some(text)
+                } else {
+                    None
+                };
 
                 let pseudo_lower = pseudo_name.to_ascii_lowercase();
 
-                if is_pseudo_element {
-                    // All pseudo-elements → NeverMatch (we don't render ::before, ::after, etc.)
+                // [§ 4.1 ::before and ::after](https://www.w3.org/TR/css-pseudo-4/#generated-content)
+                // [§ 2 Typographic Pseudo-elements](https://www.w3.org/TR/css-pseudo-4/#typographic-pseudos)
+                //
+                // CSS 2.1's single-colon spellings `:before`, `:after`,
+                // `:first-line` and `:first-letter` are still accepted for
+                // compatibility.
+                let generated = match pseudo_lower.as_str() {
+                    "before" => Some(PseudoElement::Before),
+                    "after" => Some(PseudoElement::After),
+                    "first-line" => Some(PseudoElement::FirstLine),
+                    "first-letter" => Some(PseudoElement::FirstLetter),
+                    _ => None,
+                };
+
+                if let Some(pseudo) = generated
+                    && pseudo_element.is_none()
+                {
+                    // `::before` alone applies to every element, as if
+                    // written `*::before`.
+                    if current_compound.is_empty() {
+                        current_compound.push(SimpleSelector::Universal);
+                    }
+                    pseudo_element = Some(pseudo);
+                } else if is_pseudo_element {
+                    // Other pseudo-elements (`::marker`, `::placeholder`,
+                    // ...) are not generated → NeverMatch.
                     current_compound.push(SimpleSelector::NeverMatch);
                 } else {
                     // Dispatch pseudo-class by name
@@ -1024,6 +1579,11 @@ pub fn parse_selector(raw: &str) -> Option<ParsedSelector> {
                             .push(SimpleSelector::PseudoClass(PseudoClass::LastOfType)),
                         "only-child" => current_compound
                             .push(SimpleSelector::PseudoClass(PseudoClass::OnlyChild)),
+                        "only-of-type" => current_compound
+                            .push(SimpleSelector::PseudoClass(PseudoClass::OnlyOfType)),
+                        "target" => {
+                            current_compound.push(SimpleSelector::PseudoClass(PseudoClass::Target));
+                        }
                         "empty" => {
                             current_compound.push(SimpleSelector::PseudoClass(PseudoClass::Empty));
                         }
@@ -1035,10 +1595,66 @@ pub fn parse_selector(raw: &str) -> Option<ParsedSelector> {
                         "enabled" => {
                             current_compound.push(SimpleSelector::PseudoClass(PseudoClass::Enabled));
                         }
+                        // User-action pseudo-classes, matched against a `MatchContext`
+                        "focus" => {
+                            current_compound.push(SimpleSelector::PseudoClass(PseudoClass::Focus));
+                        }
+                        "focus-visible" => current_compound
+                            .push(SimpleSelector::PseudoClass(PseudoClass::FocusVisible)),
+                        "hover" => {
+                            current_compound.push(SimpleSelector::PseudoClass(PseudoClass::Hover));
+                        }
+                        "checked" => {
+                            current_compound.push(SimpleSelector::PseudoClass(PseudoClass::Checked));
+                        }
 
-                        // Everything else: interactive states, legacy pseudo-elements
-                        // (:before, :after), functional pseudo-classes (:nth-child, :not,
-                        // :is, :where, :has), and unknown → NeverMatch (graceful degradation)
+                        // [§ 7.2 :lang()](https://www.w3.org/TR/selectors-4/#the-lang-pseudo)
+                        // The language range may be an identifier or a string.
+                        // NOTE: Only a single range is supported.
+                        "lang" => {
+                            let range = argument
+                                .as_deref()?
+                                .trim()
+                                .trim_matches(|c| c == '"' || c == '\'');
+                            if range.is_empty() || range.contains(',') {
+                                return None;
+                            }
+                            current_compound.push(SimpleSelector::PseudoClass(PseudoClass::Lang(
+                                range.to_string(),
+                            )));
+                        }
+
+                        // [§ 4.13 Child-indexed Pseudo-classes](https://www.w3.org/TR/selectors-4/#child-index)
+                        // An invalid An+B argument makes the whole selector invalid.
+                        name @ ("nth-child" | "nth-last-child" | "nth-of-type"
+                        | "nth-last-of-type") => {
+                            let (an_b, of_selector) = split_of_selector(argument.as_deref()?);
+                            let an_b = AnPlusB::parse(an_b)?;
+                            if let Some(of_selector) = of_selector {
+                                // "The :nth-child(An+B [of S]? ) pseudo-class notation"
+                                // NOTE: Only :nth-child() and :nth-last-child() take
+                                // `of S`. Filtering by S is not supported, so the
+                                // pseudo-class never matches.
+                                if of_selector.is_empty()
+                                    || !matches!(name, "nth-child" | "nth-last-child")
+                                {
+                                    return None;
+                                }
+                                current_compound.push(SimpleSelector::NeverMatch);
+                                continue;
+                            }
+                            let pseudo_class = match name {
+                                "nth-child" => PseudoClass::NthChild(an_b),
+                                "nth-last-child" => PseudoClass::NthLastChild(an_b),
+                                "nth-of-type" => PseudoClass::NthOfType(an_b),
+                                _ => PseudoClass::NthLastOfType(an_b),
+                            };
+                            current_compound.push(SimpleSelector::PseudoClass(pseudo_class));
+                        }
+
+                        // Everything else: interactive states, functional pseudo-classes
+                        // (:not, :is, :where, :has), and unknown → NeverMatch
+                        // (graceful degradation)
                         _ => {
                             current_compound.push(SimpleSelector::NeverMatch);
                         }
@@ -1168,10 +1784,17 @@ pub fn parse_selector(raw: &str) -> Option<ParsedSelector> {
 
     // [§ 17 Calculating Specificity](https://www.w3.org/TR/selectors-4/#specificity-rules)
     // Calculate specificity by summing all simple selectors in the complex selector
-    let specificity = complex.calculate_specificity();
+    let mut specificity = complex.calculate_specificity();
+
+    // "count the number of type selectors and pseudo-elements in the
+    // selector (= C)"
+    if pseudo_element.is_some() {
+        specificity.2 += 1;
+    }
 
     Some(ParsedSelector {
         complex,
         specificity,
+        pseudo_element,
     })
 }
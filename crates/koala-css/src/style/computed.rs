@@ -5,15 +5,22 @@
 
 use super::display::{DisplayValue, is_display_none, parse_display_value};
 use super::values::{
-    DEFAULT_FONT_SIZE_PX, parse_auto_length_value, parse_color_value, parse_font_family,
-    parse_font_weight, parse_length_value, parse_letter_spacing, parse_line_height,
-    parse_single_auto_length, parse_single_color, parse_single_length,
+    DEFAULT_FONT_SIZE_PX, bolder_font_weight, lighter_font_weight, parse_auto_length_value,
+    parse_color_value, parse_font_family, parse_font_weight, parse_length_value,
+    parse_letter_spacing, parse_line_height, parse_single_auto_length, parse_single_color,
+    parse_single_length, parse_single_size, parse_vertical_align,
+};
+use super::writing_mode::{
+    Direction, PhysicalSide, WritingMode, parse_direction, parse_writing_mode,
 };
-use super::writing_mode::{PhysicalSide, WritingMode, parse_writing_mode};
 use crate::parser::{ComponentValue, Declaration};
 use crate::style::substitute::{contains_var, substitute_var};
 use crate::style::values::{
-    ClearSide, FloatSide, FontStyle, PositionType, TextAlign, TextDecorationLine,
+    BackgroundImage, BackgroundPosition, BackgroundRepeat, BackgroundSize, ClearSide, FloatSide,
+    FontFamily, FontStyle, LineHeight, PositionType, TextAlign, TextDecorationLine,
+    TextDecorationStyle, VerticalAlign, parse_background_image, parse_background_layer,
+    parse_background_list, parse_background_position, parse_background_repeat,
+    parse_background_size, split_background_layers,
 };
 use crate::tokenizer::CSSToken;
 use crate::{AutoLength, BorderRadius, BorderValue, BoxShadow, ColorValue, LengthValue};
@@ -194,6 +201,75 @@ pub enum WhiteSpace {
     PreLine,
 }
 
+/// [§ 5.2 'text-overflow'](https://www.w3.org/TR/css-ui-3/#text-overflow)
+///
+/// "This property specifies rendering when inline content overflows its end
+/// line box edge in the inline progression direction of its block container
+/// element ("the block") that has overflow other than visible."
+///
+/// Values: clip | ellipsis
+/// Initial: clip
+/// Inherited: no
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum TextOverflow {
+    /// "Clip inline content that overflows its block container element."
+    #[default]
+    Clip,
+    /// "Render an ellipsis character (U+2026) to represent clipped inline
+    /// content."
+    Ellipsis,
+}
+
+/// [§ 17.4.1 Caption position and alignment](https://www.w3.org/TR/CSS2/tables.html#caption-position)
+///
+/// "This property specifies the position of the caption box with respect
+/// to the table box."
+///
+/// Values: top | bottom
+/// Initial: top
+/// Inherited: yes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum CaptionSide {
+    /// "Positions the caption box above the table box."
+    #[default]
+    Top,
+    /// "Positions the caption box below the table box."
+    Bottom,
+}
+
+/// [§ 17.5.2 Table width algorithms: the 'table-layout' property](https://www.w3.org/TR/CSS2/tables.html#width-layout)
+///
+/// "The 'table-layout' property controls the algorithm used to lay out the
+/// table cells, rows, and columns."
+///
+/// Values: auto | fixed
+/// Initial: auto
+/// Inherited: no
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum TableLayoutAlgorithm {
+    /// "Use any automatic table layout algorithm"
+    #[default]
+    Auto,
+    /// "Use the fixed table layout algorithm"
+    Fixed,
+}
+
+/// [§ 17.6 Borders](https://www.w3.org/TR/CSS2/tables.html#borders)
+///
+/// "This property selects a table's border model."
+///
+/// Values: collapse | separate
+/// Initial: separate
+/// Inherited: yes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum BorderCollapse {
+    /// "The value 'separate' selects the separated borders border model."
+    #[default]
+    Separate,
+    /// "The value 'collapse' selects the collapsing borders model."
+    Collapse,
+}
+
 /// [§ 11.2 'visibility'](https://www.w3.org/TR/CSS2/visufx.html#visibility)
 ///
 /// "The 'visibility' property specifies whether the boxes generated by an
@@ -215,6 +291,151 @@ pub enum Visibility {
     Collapse,
 }
 
+/// [CSS Containment 2 § 4 'content-visibility'](https://www.w3.org/TR/css-contain-2/#content-visibility)
+///
+/// "The content-visibility property controls whether or not an element
+/// renders its contents at all, along with forcing a strong set of
+/// containments, allowing user agents to potentially omit large swathes of
+/// layout and rendering work until it becomes needed."
+///
+/// Values: visible | auto | hidden
+/// Initial: visible
+/// Inherited: no
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum ContentVisibility {
+    /// "No effect. The element's contents are laid out and rendered as
+    /// normal."
+    #[default]
+    Visible,
+    /// "The element's contents are skipped" while the element is not
+    /// relevant to the user, i.e. off-screen.
+    Auto,
+    /// "The element skips its contents. The skipped contents must not be
+    /// accessible to user-agent features... nor be focusable or
+    /// selectable."
+    Hidden,
+}
+
+/// [§ 5.1 'cursor'](https://www.w3.org/TR/css-ui-4/#cursor)
+///
+/// "This property specifies the type of cursor to be displayed for the
+/// pointing device when over the element's border area."
+///
+/// Only the predefined keywords are represented; `<url>` cursor images are
+/// skipped in favor of the mandatory keyword fallback.
+///
+/// Initial: auto
+/// Inherited: yes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum Cursor {
+    /// The UA determines the cursor to display based on the current context.
+    #[default]
+    Auto,
+    /// The platform-dependent default cursor. Often rendered as an arrow.
+    Default,
+    /// No cursor is rendered for the element.
+    None,
+    /// A context menu is available for the object under the cursor.
+    ContextMenu,
+    /// Help is available for the object under the cursor.
+    Help,
+    /// The cursor is a pointer that indicates a link.
+    Pointer,
+    /// A progress indicator: the program is busy, but unlike `wait` the
+    /// user may still interact with it.
+    Progress,
+    /// Indicates that the program is busy and the user should wait.
+    Wait,
+    /// A cell or set of cells may be selected.
+    Cell,
+    /// A simple crosshair (e.g., short line segments resembling a "+" sign).
+    Crosshair,
+    /// Indicates text that may be selected.
+    Text,
+    /// Indicates vertical-text that may be selected.
+    VerticalText,
+    /// Indicates an alias of/shortcut to something is to be created.
+    Alias,
+    /// Indicates something is to be copied.
+    Copy,
+    /// Indicates something is to be moved.
+    Move,
+    /// Indicates that the dragged item cannot be dropped at the current
+    /// cursor location.
+    NoDrop,
+    /// Indicates that the requested action will not be carried out.
+    NotAllowed,
+    /// Indicates that something can be grabbed (dragged to be moved).
+    Grab,
+    /// Indicates that something is being grabbed (dragged to be moved).
+    Grabbing,
+    /// The east edge or corner is to be moved.
+    EResize,
+    /// The north edge or corner is to be moved.
+    NResize,
+    /// The north-east edge or corner is to be moved.
+    NeResize,
+    /// The north-west edge or corner is to be moved.
+    NwResize,
+    /// The south edge or corner is to be moved.
+    SResize,
+    /// The south-east edge or corner is to be moved.
+    SeResize,
+    /// The south-west edge or corner is to be moved.
+    SwResize,
+    /// The west edge or corner is to be moved.
+    WResize,
+    /// Bidirectional resize cursor.
+    EwResize,
+    /// Bidirectional resize cursor.
+    NsResize,
+    /// Bidirectional resize cursor.
+    NeswResize,
+    /// Bidirectional resize cursor.
+    NwseResize,
+    /// Indicates that the item/column can be resized horizontally.
+    ColResize,
+    /// Indicates that the item/row can be resized vertically.
+    RowResize,
+    /// Indicates that something can be scrolled in any direction.
+    AllScroll,
+    /// Indicates that something can be zoomed (magnified) in.
+    ZoomIn,
+    /// Indicates that something can be zoomed (magnified) out.
+    ZoomOut,
+}
+
+/// [§ 5.5 'object-fit'](https://www.w3.org/TR/css-images-3/#the-object-fit)
+///
+/// "The 'object-fit' property specifies how the contents of a replaced
+/// element should be fitted to the box established by its used height and
+/// width."
+///
+/// Values: fill | contain | cover | none | scale-down
+/// Initial: fill
+/// Inherited: no
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum ObjectFit {
+    /// "The replaced content is sized to fill the element's content box.
+    /// The entire object will completely fill the box. If the object's
+    /// natural aspect ratio does not match the aspect ratio of the box,
+    /// then the object will be stretched to fit."
+    #[default]
+    Fill,
+    /// "The replaced content is scaled to maintain its aspect ratio while
+    /// fitting within the element's content box."
+    Contain,
+    /// "The replaced content is sized to maintain its aspect ratio while
+    /// filling the element's entire content box."
+    Cover,
+    /// "The replaced content is not resized to fit inside the element's
+    /// content box."
+    None,
+    /// "Size the content as if 'none' or 'contain' were specified, whichever
+    /// would result in a smaller concrete object size."
+    ScaleDown,
+}
+
 /// [§ 7.2 Explicit Track Sizing](https://www.w3.org/TR/css-grid-1/#track-sizing)
 ///
 /// "A track sizing function can be specified as a length, a percentage of the
@@ -307,6 +528,40 @@ pub enum ListStyleType {
     /// No marker.
     None,
 }
+
+/// [§ 2 'content'](https://www.w3.org/TR/css-content-3/#content-property)
+///
+/// One item of a `<content-list>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub enum ContentItem {
+    /// "`<string>`: Inserts the given string."
+    String(String),
+    /// [§ 4.8 Counters in Generated Content](https://www.w3.org/TR/css-lists-3/#counter-functions)
+    ///
+    /// "`counter()`: Represents the value of the innermost counter in the
+    /// element's CSS counters set named `<counter-name>` using the counter
+    /// style named `<counter-style>`."
+    Counter {
+        /// The `<counter-name>`.
+        name: String,
+        /// The `<counter-style>`. Defaults to `decimal`.
+        style: ListStyleType,
+    },
+    /// [§ 4.8 Counters in Generated Content](https://www.w3.org/TR/css-lists-3/#counter-functions)
+    ///
+    /// "`counters()`: Represents the values of all the counters in the
+    /// element's CSS counters set named `<counter-name>` using the counter
+    /// style named `<counter-style>`, sorted in outermost-first to
+    /// innermost-last order and joined by the specified `<string>`."
+    Counters {
+        /// The `<counter-name>`.
+        name: String,
+        /// The `<string>` placed between the values.
+        separator: String,
+        /// The `<counter-style>`. Defaults to `decimal`.
+        style: ListStyleType,
+    },
+}
 /// Computed styles for an element.
 ///
 /// [§ 4.4 Computed Values](https://www.w3.org/TR/css-cascade-4/#computed)
@@ -344,10 +599,22 @@ pub struct ComputedStyle {
     /// Inherited: yes
     pub writing_mode: WritingMode,
 
+    /// [§ 2.1 'direction'](https://www.w3.org/TR/css-writing-modes-4/#direction)
+    ///
+    /// "This property specifies the inline base direction or directionality
+    /// of any bidi paragraph, embedding, isolate, or override established by
+    /// the box."
+    ///
+    /// Initial: ltr
+    /// Inherited: yes
+    pub direction: Direction,
+
     /// [§ 3.1 'color'](https://www.w3.org/TR/css-color-4/#the-color-property)
     pub color: Option<ColorValue>,
     /// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
-    pub font_family: Option<String>,
+    ///
+    /// The prioritized family list, most preferred first.
+    pub font_family: Option<Vec<FontFamily>>,
     /// [§ 3.5 'font-size'](https://www.w3.org/TR/css-fonts-4/#font-size-prop)
     pub font_size: Option<LengthValue>,
     /// [§ 3.2 'font-weight'](https://www.w3.org/TR/css-fonts-4/#font-weight-prop)
@@ -366,8 +633,34 @@ pub struct ComputedStyle {
     /// Inherited: no (but decorations propagate visually to inline descendants)
     pub text_decoration_line: Option<TextDecorationLine>,
 
+    /// [§ 2.2 'text-decoration-style'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-style-property)
+    ///
+    /// "This property specifies the style of the line(s) drawn for text
+    /// decoration specified on the element."
+    /// Values: solid | double | dotted | dashed | wavy
+    /// Initial: solid
+    /// Inherited: no
+    pub text_decoration_style: Option<TextDecorationStyle>,
+
+    /// [§ 2.3 'text-decoration-color'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-color-property)
+    ///
+    /// "This property specifies the color of text decoration (underlines,
+    /// overlines, and line-throughs) set on the element."
+    /// Values: `<color>`
+    /// Initial: currentcolor
+    /// Inherited: no
+    /// None means initial (currentcolor — the element's `color`).
+    pub text_decoration_color: Option<ColorValue>,
+
     /// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
-    pub line_height: Option<f64>,
+    ///
+    /// Values: normal | `<number>` | `<length-percentage>`
+    /// Initial: normal
+    /// Inherited: yes
+    ///
+    /// Lengths and percentages are stored resolved to pixels; numbers stay
+    /// factors (see [`LineHeight`]).
+    pub line_height: Option<LineHeight>,
 
     /// [§ 9.3 'letter-spacing'](https://www.w3.org/TR/css-text-3/#letter-spacing-property)
     ///
@@ -387,6 +680,24 @@ pub struct ComputedStyle {
     /// [§ 3.2 'background-color'](https://www.w3.org/TR/css-backgrounds-3/#background-color)
     pub background_color: Option<ColorValue>,
 
+    /// [§ 3.3 'background-image'](https://www.w3.org/TR/css-backgrounds-3/#background-image)
+    ///
+    /// One entry per background layer, topmost first.
+    /// Initial: none
+    pub background_image: Option<Vec<BackgroundImage>>,
+    /// [§ 3.4 'background-repeat'](https://www.w3.org/TR/css-backgrounds-3/#background-repeat)
+    ///
+    /// One entry per background layer. Initial: repeat
+    pub background_repeat: Option<Vec<BackgroundRepeat>>,
+    /// [§ 3.6 'background-position'](https://www.w3.org/TR/css-backgrounds-3/#background-position)
+    ///
+    /// One entry per background layer. Initial: 0% 0%
+    pub background_position: Option<Vec<BackgroundPosition>>,
+    /// [§ 3.9 'background-size'](https://www.w3.org/TR/css-backgrounds-3/#background-size)
+    ///
+    /// One entry per background layer. Initial: auto
+    pub background_size: Option<Vec<BackgroundSize>>,
+
     /// [§ 6.1 'margin-top'](https://www.w3.org/TR/css-box-4/#margin-physical)
     ///
     /// Can be 'auto' or a specific length. 'auto' is resolved during layout.
@@ -473,6 +784,20 @@ pub struct ComputedStyle {
     /// None means initial (none — no maximum constraint).
     pub max_height: Option<LengthValue>,
 
+    /// [§ 5.1 'aspect-ratio'](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+    ///
+    /// "This property sets a preferred aspect ratio for the box, which will
+    /// be used in the calculation of auto sizes and some other layout
+    /// functions."
+    ///
+    /// The (width, height) numbers of the ratio.
+    ///
+    /// Values: auto || `<ratio>`
+    /// Initial: auto
+    /// Inherited: no
+    /// None means initial (auto — no preferred aspect ratio).
+    pub aspect_ratio: Option<(f64, f64)>,
+
     // [§ 2 Flex Layout Box Model](https://www.w3.org/TR/css-flexbox-1/#box-model)
     /// [§ 5.1 'flex-direction'](https://www.w3.org/TR/css-flexbox-1/#flex-direction-property)
     ///
@@ -675,15 +1000,28 @@ pub struct ComputedStyle {
     // These fields track which declaration (by source_order) set each physical margin,
     // allowing proper cascade resolution when both logical and physical properties
     // target the same computed value.
-    /// [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
+    /// [§ 3 overflow-x](https://www.w3.org/TR/css-overflow-3/#overflow-properties)
     ///
-    /// "This property specifies whether content of a block container element
-    /// is clipped when it overflows the element's box."
+    /// "These properties specify whether a box's content (including any
+    /// ink overflow) is clipped to its padding edge, and if so, whether it
+    /// is a scroll container that allows the user to scroll clipped parts
+    /// of its scrollable overflow area into view."
+    ///
+    /// The horizontal axis.
     ///
     /// Values: visible | hidden | scroll | auto
     /// Initial: visible
     /// Inherited: no
-    pub overflow: Option<Overflow>,
+    pub overflow_x: Option<Overflow>,
+
+    /// [§ 3 overflow-y](https://www.w3.org/TR/css-overflow-3/#overflow-properties)
+    ///
+    /// The vertical counterpart of [`Self::overflow_x`].
+    ///
+    /// NOTE: The rule that `visible` computes to `auto` when the other axis
+    /// is not `visible` is not applied; without scroll containers each axis
+    /// clips independently.
+    pub overflow_y: Option<Overflow>,
 
     /// [§ 4.4 box-sizing](https://www.w3.org/TR/css-box-4/#box-sizing)
     ///
@@ -698,6 +1036,21 @@ pub struct ComputedStyle {
     /// Some(true) = border-box, Some(false) = content-box.
     pub box_sizing_border_box: Option<bool>,
 
+    /// [§ 5.5 'object-fit'](https://www.w3.org/TR/css-images-3/#the-object-fit)
+    ///
+    /// "Inherited: no"
+    /// "Initial: fill"
+    pub object_fit: Option<ObjectFit>,
+
+    /// [§ 5.6 'object-position'](https://www.w3.org/TR/css-images-3/#the-object-position)
+    ///
+    /// Where the replaced content sits within the content box. Uses the
+    /// same `<position>` value as 'background-position'.
+    ///
+    /// "Inherited: no"
+    /// "Initial: 50% 50%"
+    pub object_position: Option<BackgroundPosition>,
+
     /// [§ 16.6 'white-space'](https://www.w3.org/TR/CSS2/text.html#white-space-prop)
     ///
     /// "This property declares how white space inside the element is handled."
@@ -707,6 +1060,63 @@ pub struct ComputedStyle {
     /// Inherited: yes
     pub white_space: Option<WhiteSpace>,
 
+    /// [§ 4.2 'tab-size'](https://www.w3.org/TR/css-text-3/#tab-size-property)
+    ///
+    /// "This property determines the tab size used to render preserved tab
+    /// characters (U+0009)."
+    ///
+    /// Stored as a number of space advances. `<length>` values are not
+    /// supported.
+    ///
+    /// Initial: 8
+    /// Inherited: yes
+    pub tab_size: Option<u32>,
+
+    /// [§ 5.2 'text-overflow'](https://www.w3.org/TR/css-ui-3/#text-overflow)
+    ///
+    /// Values: clip | ellipsis
+    /// Initial: clip
+    /// Inherited: no
+    pub text_overflow: Option<TextOverflow>,
+
+    /// [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+    ///
+    /// Values: baseline | sub | super | top | text-top | middle | bottom |
+    /// text-bottom | `<length>`
+    /// Initial: baseline
+    /// Inherited: no
+    pub vertical_align: Option<VerticalAlign>,
+
+    /// [§ 17.4.1 'caption-side'](https://www.w3.org/TR/CSS2/tables.html#caption-position)
+    ///
+    /// Values: top | bottom
+    /// Initial: top
+    /// Inherited: yes
+    pub caption_side: Option<CaptionSide>,
+
+    /// [§ 17.5.2 'table-layout'](https://www.w3.org/TR/CSS2/tables.html#width-layout)
+    ///
+    /// Values: auto | fixed
+    /// Initial: auto
+    /// Inherited: no
+    pub table_layout: Option<TableLayoutAlgorithm>,
+
+    /// [§ 17.6 'border-collapse'](https://www.w3.org/TR/CSS2/tables.html#borders)
+    ///
+    /// Values: collapse | separate
+    /// Initial: separate
+    /// Inherited: yes
+    pub border_collapse: Option<BorderCollapse>,
+
+    /// [§ 17.6.1 'border-spacing'](https://www.w3.org/TR/CSS2/tables.html#separated-borders)
+    ///
+    /// The (horizontal, vertical) spacing between adjoining cell borders.
+    ///
+    /// Values: <length> <length>?
+    /// Initial: 0
+    /// Inherited: yes
+    pub border_spacing: Option<(LengthValue, LengthValue)>,
+
     /// [§ 11.2 'visibility'](https://www.w3.org/TR/CSS2/visufx.html#visibility)
     ///
     /// "The 'visibility' property specifies whether the boxes generated by an
@@ -717,6 +1127,34 @@ pub struct ComputedStyle {
     /// Inherited: yes
     pub visibility: Option<Visibility>,
 
+    /// [CSS Containment 2 § 4 'content-visibility'](https://www.w3.org/TR/css-contain-2/#content-visibility)
+    ///
+    /// Whether the element's contents are laid out and painted.
+    ///
+    /// Values: visible | auto | hidden
+    /// Initial: visible
+    /// Inherited: no
+    pub content_visibility: Option<ContentVisibility>,
+
+    /// [CSS Sizing 4 § 4.1 'contain-intrinsic-size'](https://www.w3.org/TR/css-sizing-4/#intrinsic-size-override)
+    ///
+    /// The (width, height) an element under size containment is laid out
+    /// as if its contents had, e.g. while `content-visibility` skips them.
+    ///
+    /// Values: none | [ auto? <length> ]{1,2}
+    /// Initial: none
+    /// Inherited: no
+    pub contain_intrinsic_size: Option<(LengthValue, LengthValue)>,
+
+    /// [§ 5.1 'cursor'](https://www.w3.org/TR/css-ui-4/#cursor)
+    ///
+    /// "This property specifies the type of cursor to be displayed for the
+    /// pointing device when over the element's border area."
+    ///
+    /// Initial: auto
+    /// Inherited: yes
+    pub cursor: Option<Cursor>,
+
     /// [§ 3.2 'opacity'](https://www.w3.org/TR/css-color-4/#transparency)
     ///
     /// "Opacity can be thought of as a postprocessing operation. Conceptually,
@@ -748,6 +1186,115 @@ pub struct ComputedStyle {
     /// Inherited: no
     pub border_radius: Option<BorderRadius>,
 
+    /// [CSS UI 4 § 5.1 'outline'](https://www.w3.org/TR/css-ui-4/#outline)
+    ///
+    /// "Outlines differ from borders in the following ways: Outlines do not
+    /// take up space. ... Outlines may be non-rectangular."
+    ///
+    /// The `outline-width`, `outline-style` and `outline-color` longhands,
+    /// stored together like a border side.
+    ///
+    /// Initial value: medium none auto
+    /// Inherited: no
+    pub outline: Option<BorderValue>,
+
+    /// [CSS UI 4 § 5.5 'outline-offset'](https://www.w3.org/TR/css-ui-4/#outline-offset)
+    ///
+    /// "By default, the outline is drawn starting just outside the border
+    /// edge. However, it is possible to offset the outline and draw it
+    /// beyond the border edge."
+    ///
+    /// Initial value: 0
+    /// Inherited: no
+    pub outline_offset: Option<LengthValue>,
+
+    /// [§ 2 'content'](https://www.w3.org/TR/css-content-3/#content-property)
+    ///
+    /// "The content property determines what is rendered inside an element
+    /// or pseudo-element."
+    ///
+    /// Only lists of strings, `counter()` and `counters()` are supported.
+    /// `None` means `normal` (or `none`), which generates no box for
+    /// `::before`/`::after`.
+    ///
+    /// Initial: normal
+    /// Inherited: no
+    pub content: Option<Vec<ContentItem>>,
+
+    /// [§ 4.2 Creating Counters: the counter-reset property](https://www.w3.org/TR/css-lists-3/#counter-reset)
+    ///
+    /// "The counter-reset property instantiates new counters on an element
+    /// and sets them to the specified integer values."
+    ///
+    /// `(name, value)` pairs in declaration order. An empty list is `none`.
+    ///
+    /// Initial: none
+    /// Inherited: no
+    pub counter_reset: Option<Vec<(String, i32)>>,
+
+    /// [§ 4.3 Manipulating Counter Values: the counter-increment property](https://www.w3.org/TR/css-lists-3/#increment-set)
+    ///
+    /// "The counter-increment property increments the values of an
+    /// element's counters by the specified integer."
+    ///
+    /// `(name, amount)` pairs in declaration order. An empty list is `none`.
+    ///
+    /// Initial: none
+    /// Inherited: no
+    pub counter_increment: Option<Vec<(String, i32)>>,
+
+    /// [§ 4.1 ::before](https://www.w3.org/TR/css-pseudo-4/#selectordef-before)
+    ///
+    /// Computed style of the element's `::before` pseudo-element. Only set
+    /// when it generates a box, i.e. its `content` is not `normal`/`none`.
+    #[serde(skip)]
+    pub before: Option<Box<Self>>,
+
+    /// [§ 4.1 ::after](https://www.w3.org/TR/css-pseudo-4/#selectordef-after)
+    ///
+    /// Computed style of the element's `::after` pseudo-element. Only set
+    /// when it generates a box, i.e. its `content` is not `normal`/`none`.
+    #[serde(skip)]
+    pub after: Option<Box<Self>>,
+
+    /// [§ 2.1 ::first-line](https://www.w3.org/TR/css-pseudo-4/#first-line-pseudo)
+    ///
+    /// Computed style of the element's `::first-line` pseudo-element. Only
+    /// set when a rule targets it.
+    #[serde(skip)]
+    pub first_line: Option<Box<Self>>,
+
+    /// [§ 2.2 ::first-letter](https://www.w3.org/TR/css-pseudo-4/#first-letter-pseudo)
+    ///
+    /// Computed style of the element's `::first-letter` pseudo-element.
+    /// Only set when a rule targets it.
+    #[serde(skip)]
+    pub first_letter: Option<Box<Self>>,
+
+    /// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+    ///
+    /// The font size in pixels that `rem` lengths resolve against: the
+    /// computed font-size of the root element, or the initial font size
+    /// while the root element itself is being styled. `None` means
+    /// [`DEFAULT_FONT_SIZE_PX`].
+    #[serde(skip)]
+    pub root_font_size: Option<f64>,
+
+    /// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+    ///
+    /// The parent element's computed font-size in pixels, which `em` in
+    /// this element's own `font-size` resolves against. `None` means the
+    /// root font size.
+    #[serde(skip)]
+    pub parent_font_size: Option<f64>,
+
+    /// [§ 2.2.1 Relative Weights](https://www.w3.org/TR/css-fonts-4/#relative-weights)
+    ///
+    /// The parent element's computed font-weight, which `bolder` and
+    /// `lighter` step from. `None` means the initial `normal` (400).
+    #[serde(skip)]
+    pub parent_font_weight: Option<u16>,
+
     /// [§ 2 Custom Properties](https://www.w3.org/TR/css-variables-1/#defining-variables)
     ///
     /// "A custom property is any property whose name starts with two dashes."
@@ -773,6 +1320,17 @@ pub struct ComputedStyle {
 impl ComputedStyle {
     /// Apply a CSS declaration to update this computed style.
     pub fn apply_declaration(&mut self, decl: &Declaration) {
+        let _ = self.try_apply_declaration(decl);
+    }
+
+    /// [§ 4.1.1 Declarations](https://www.w3.org/TR/css-syntax-3/#consume-a-declaration)
+    ///
+    /// Like [`Self::apply_declaration`], but returns whether the property
+    /// is known and its value is valid for it. A recognized declaration
+    /// can still leave the style unchanged, e.g. when its value is the
+    /// current one or it loses to a logical margin from later in the
+    /// cascade.
+    pub fn try_apply_declaration(&mut self, decl: &Declaration) -> bool {
         // [§ 2 Custom Properties](https://www.w3.org/TR/css-variables-1/#defining-variables)
         //
         // "A custom property is any property whose name starts with two dashes."
@@ -783,7 +1341,7 @@ impl ComputedStyle {
             let _ = self
                 .custom_properties
                 .insert(decl.name.clone(), decl.value.clone());
-            return;
+            return true;
         }
 
         // [§ 3](https://www.w3.org/TR/css-variables-1/#using-variables)
@@ -799,7 +1357,7 @@ impl ComputedStyle {
                     resolved_values = v;
                     &resolved_values
                 }
-                None => return, // Invalid at computed-value time
+                None => return false, // Invalid at computed-value time
             }
         } else {
             &decl.value
@@ -818,6 +1376,8 @@ impl ComputedStyle {
                     // "The element and its descendants generate no boxes or text runs."
                     self.display = None;
                     self.display_none = true;
+                } else {
+                    return false;
                 }
             }
             // [§ 2 Block Flow Direction](https://www.w3.org/TR/css-writing-modes-4/#block-flow)
@@ -828,62 +1388,142 @@ impl ComputedStyle {
             // Values: horizontal-tb | vertical-rl | vertical-lr
             // Initial: horizontal-tb
             "writing-mode" => {
-                if let Some(wm) = parse_writing_mode(values) {
-                    self.writing_mode = wm;
-                }
+                let Some(wm) = parse_writing_mode(values) else {
+                    return false;
+                };
+                self.writing_mode = wm;
+            }
+            // [§ 2.1 'direction'](https://www.w3.org/TR/css-writing-modes-4/#direction)
+            //
+            // Values: ltr | rtl
+            // Initial: ltr
+            "direction" => {
+                let Some(direction) = parse_direction(values) else {
+                    return false;
+                };
+                self.direction = direction;
             }
             "color" => {
-                if let Some(color) = parse_color_value(values) {
-                    self.color = Some(color);
-                }
+                let Some(color) = parse_color_value(values) else {
+                    return false;
+                };
+                self.color = Some(color);
             }
             "background-color" => {
-                if let Some(color) = parse_color_value(values) {
-                    self.background_color = Some(color);
-                }
+                let Some(color) = parse_color_value(values) else {
+                    return false;
+                };
+                self.background_color = Some(color);
+            }
+            // [§ 3.3 'background-image'](https://www.w3.org/TR/css-backgrounds-3/#background-image)
+            "background-image" => {
+                let parse = |layer: &[&ComponentValue]| {
+                    parse_background_image(layer.first()?).map(|image| (image, 1))
+                };
+                let Some(images) = parse_background_list(values, parse) else {
+                    return false;
+                };
+                self.background_image = Some(images);
+            }
+            // [§ 3.4 'background-repeat'](https://www.w3.org/TR/css-backgrounds-3/#background-repeat)
+            "background-repeat" => {
+                let Some(repeats) = parse_background_list(values, parse_background_repeat) else {
+                    return false;
+                };
+                self.background_repeat = Some(repeats);
+            }
+            // [§ 3.6 'background-position'](https://www.w3.org/TR/css-backgrounds-3/#background-position)
+            "background-position" => {
+                let Some(positions) = parse_background_list(values, parse_background_position)
+                else {
+                    return false;
+                };
+                self.background_position = Some(
+                    positions
+                        .into_iter()
+                        .map(|p| self.resolve_background_position(p))
+                        .collect(),
+                );
+            }
+            // [§ 3.9 'background-size'](https://www.w3.org/TR/css-backgrounds-3/#background-size)
+            "background-size" => {
+                let Some(sizes) = parse_background_list(values, parse_background_size) else {
+                    return false;
+                };
+                self.background_size = Some(
+                    sizes
+                        .into_iter()
+                        .map(|s| self.resolve_background_size(s))
+                        .collect(),
+                );
             }
             "font-family" => {
-                if let Some(family) = parse_font_family(values) {
-                    self.font_family = Some(family);
-                }
-            }
+                let Some(family) = parse_font_family(values) else {
+                    return false;
+                };
+                self.font_family = Some(family);
+            }
+            // NOTE: Like `em` lengths elsewhere, a percentage or `em` line
+            // height resolves against the font size this element has when
+            // the declaration is applied.
             "line-height" => {
-                if let Some(lh) = parse_line_height(values) {
-                    self.line_height = Some(lh);
-                }
+                let Some(lh) = parse_line_height(values) else {
+                    return false;
+                };
+                self.line_height = Some(self.resolve_line_height(lh));
             }
             "letter-spacing" => {
-                if let Some(ls) = parse_letter_spacing(values) {
-                    self.letter_spacing = Some(ls);
-                }
+                let Some(ls) = parse_letter_spacing(values) else {
+                    return false;
+                };
+                self.letter_spacing = Some(ls);
             }
             // [§ 3.2 font-weight](https://www.w3.org/TR/css-fonts-4/#font-weight-prop)
+            //
+            // "bolder / lighter: Specifies a bolder/lighter weight than the
+            // inherited value."
             "font-weight" => {
-                if let Some(weight) = parse_font_weight(values) {
-                    self.font_weight = Some(weight);
-                }
+                let Some(weight) =
+                    parse_font_weight(values, self.parent_font_weight.unwrap_or(400))
+                else {
+                    return false;
+                };
+                self.font_weight = Some(weight);
             }
             // [§ 3.3 font-style](https://www.w3.org/TR/css-fonts-4/#font-style-prop)
             //
             // "This property allows italic or oblique faces to be selected."
             // Values: normal | italic | oblique
             "font-style" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "normal" => self.font_style = Some(FontStyle::Normal),
-                        "italic" => self.font_style = Some(FontStyle::Italic),
-                        "oblique" => self.font_style = Some(FontStyle::Oblique),
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "normal" => self.font_style = Some(FontStyle::Normal),
+                    "italic" => self.font_style = Some(FontStyle::Italic),
+                    "oblique" => self.font_style = Some(FontStyle::Oblique),
+                    _ => return false,
                 }
             }
             // [§ 3 'text-decoration-line'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
             //
             // "Specifies what line decorations, if any, are added to the element."
             // Values: none | [ underline || overline || line-through ]
+            "text-decoration-line" => {
+                let Some(line) = Self::parse_text_decoration_line(values) else {
+                    return false;
+                };
+                self.text_decoration_line = Some(line);
+            }
+            // [§ 2.4 'text-decoration'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-property)
+            //
+            // "Value: <'text-decoration-line'> || <'text-decoration-style'> ||
+            // <'text-decoration-color'>"
             //
-            // 'text-decoration' is a shorthand that, in its simplest form,
-            // sets text-decoration-line (style and color are later additions).
+            // "The text-decoration property is a shorthand for setting
+            // text-decoration-line, text-decoration-style, and
+            // text-decoration-color in one declaration. Omitted values are
+            // set to their initial values."
             //
             // '-webkit-text-decoration' is the legacy WebKit prefix for the
             // same property. The unprefixed form is the CSS Text Decoration
@@ -892,25 +1532,63 @@ impl ComputedStyle {
             // path. This implements the category-1 note in
             // [`crate::vendor_prefixes`] about aliasing legacy prefixes whose
             // standard counterpart we already support.
-            "text-decoration" | "text-decoration-line" | "-webkit-text-decoration" => {
-                let mut result = TextDecorationLine::default();
-                let mut found_none = false;
+            //
+            // NOTE: Tokens matching none of the three longhands (such as a
+            // Level 4 `text-decoration-thickness` length) are ignored.
+            "text-decoration" | "-webkit-text-decoration" => {
+                let line = Self::parse_text_decoration_line(values);
+                let mut style = None;
+                let mut color = None;
+                let mut current_color = false;
                 for val in values {
-                    if let ComponentValue::Token(CSSToken::Ident(ident)) = val {
-                        match ident.to_ascii_lowercase().as_str() {
-                            "none" => found_none = true,
-                            "underline" => result.underline = true,
-                            "overline" => result.overline = true,
-                            "line-through" => result.line_through = true,
-                            // Ignore text-decoration-style / color tokens for now
-                            _ => {}
-                        }
+                    if let ComponentValue::Token(CSSToken::Ident(ident)) = val
+                        && let Some(keyword_style) = TextDecorationStyle::from_keyword(ident)
+                    {
+                        style = Some(keyword_style);
+                    } else if let Some(parsed) = parse_single_color(val) {
+                        color = Some(parsed);
+                    } else if let ComponentValue::Token(CSSToken::Ident(ident)) = val
+                        && ident.eq_ignore_ascii_case("currentcolor")
+                    {
+                        color = None;
+                        current_color = true;
                     }
                 }
-                if found_none {
-                    self.text_decoration_line = Some(TextDecorationLine::default());
+                if line.is_none() && style.is_none() && color.is_none() && !current_color {
+                    return false;
+                }
+                let style = style.unwrap_or_default();
+                self.text_decoration_line = Some(line.unwrap_or_default());
+                self.text_decoration_style = Some(style);
+                self.text_decoration_color = color;
+            }
+            // [§ 2.2 'text-decoration-style'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-style-property)
+            //
+            // "Value: solid | double | dotted | dashed | wavy"
+            "text-decoration-style" => {
+                let Some(style) = values.first().and_then(|v| match v {
+                    ComponentValue::Token(CSSToken::Ident(ident)) => {
+                        TextDecorationStyle::from_keyword(ident)
+                    }
+                    _ => None,
+                }) else {
+                    return false;
+                };
+                self.text_decoration_style = Some(style);
+            }
+            // [§ 2.3 'text-decoration-color'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-color-property)
+            //
+            // "Value: <color>"
+            // Initial: currentcolor
+            "text-decoration-color" => {
+                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first()
+                    && ident.eq_ignore_ascii_case("currentcolor")
+                {
+                    self.text_decoration_color = None;
+                } else if let Some(color) = parse_color_value(values) {
+                    self.text_decoration_color = Some(color);
                 } else {
-                    self.text_decoration_line = Some(result);
+                    return false;
                 }
             }
             // [§ 3.2 'appearance'](https://www.w3.org/TR/css-ui-4/#appearance-switching)
@@ -968,20 +1646,19 @@ impl ComputedStyle {
             //
             // "Value: left | right | center | justify | inherit"
             "text-align" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "left" => self.text_align = Some(TextAlign::Left),
-                        "right" => self.text_align = Some(TextAlign::Right),
-                        "center" => self.text_align = Some(TextAlign::Center),
-                        "justify" => self.text_align = Some(TextAlign::Justify),
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "left" => self.text_align = Some(TextAlign::Left),
+                    "right" => self.text_align = Some(TextAlign::Right),
+                    "center" => self.text_align = Some(TextAlign::Center),
+                    "justify" => self.text_align = Some(TextAlign::Justify),
+                    _ => return false,
                 }
             }
             // [§ 9.2 Shorthand properties](https://www.w3.org/TR/css-cascade-4/#shorthand)
-            "margin" => {
-                self.apply_margin_shorthand(values);
-            }
+            "margin" => return self.apply_margin_shorthand(values),
             // [§ 8.3 Margin properties](https://www.w3.org/TR/CSS2/box.html#margin-properties)
             //
             // "Value: <margin-width> | inherit"
@@ -992,33 +1669,37 @@ impl ComputedStyle {
             // Physical and logical properties compete in the cascade. We track
             // source_order to determine which declaration wins.
             "margin-top" => {
-                if let Some(al) = parse_auto_length_value(values)
-                    && self.should_update_margin(PhysicalSide::Top, decl.source_order)
-                {
+                let Some(al) = parse_auto_length_value(values) else {
+                    return false;
+                };
+                if self.should_update_margin(PhysicalSide::Top, decl.source_order) {
                     self.margin_top = Some(self.resolve_auto_length(al));
                     self.margin_top_source_order = Some(decl.source_order);
                 }
             }
             "margin-right" => {
-                if let Some(al) = parse_auto_length_value(values)
-                    && self.should_update_margin(PhysicalSide::Right, decl.source_order)
-                {
+                let Some(al) = parse_auto_length_value(values) else {
+                    return false;
+                };
+                if self.should_update_margin(PhysicalSide::Right, decl.source_order) {
                     self.margin_right = Some(self.resolve_auto_length(al));
                     self.margin_right_source_order = Some(decl.source_order);
                 }
             }
             "margin-bottom" => {
-                if let Some(al) = parse_auto_length_value(values)
-                    && self.should_update_margin(PhysicalSide::Bottom, decl.source_order)
-                {
+                let Some(al) = parse_auto_length_value(values) else {
+                    return false;
+                };
+                if self.should_update_margin(PhysicalSide::Bottom, decl.source_order) {
                     self.margin_bottom = Some(self.resolve_auto_length(al));
                     self.margin_bottom_source_order = Some(decl.source_order);
                 }
             }
             "margin-left" => {
-                if let Some(al) = parse_auto_length_value(values)
-                    && self.should_update_margin(PhysicalSide::Left, decl.source_order)
-                {
+                let Some(al) = parse_auto_length_value(values) else {
+                    return false;
+                };
+                if self.should_update_margin(PhysicalSide::Left, decl.source_order) {
                     self.margin_left = Some(self.resolve_auto_length(al));
                     self.margin_left_source_order = Some(decl.source_order);
                 }
@@ -1035,58 +1716,60 @@ impl ComputedStyle {
                 // STEP 1: Parse the value.
                 //   [§ 4.2](https://drafts.csswg.org/css-logical-1/#margin-properties)
                 //   "Value: <'margin-top'>"
-                if let Some(al) = parse_auto_length_value(values) {
-                    // STEP 2: Map to the physical side based on writing-mode.
-                    let physical_side = self.writing_mode.block_start_physical();
-
-                    // STEP 3: Check cascade - only update if we win.
-                    //   [§ 4 Logical Property Groups](https://drafts.csswg.org/css-logical-1/#logical-property-groups)
-                    if self.should_update_margin(physical_side, decl.source_order) {
-                        // STEP 4: Apply to both the logical field (for reference)
-                        // and the corresponding physical property.
-                        self.margin_block_start = Some(self.resolve_auto_length(al));
-                        self.set_margin_for_side(physical_side, al, decl.source_order);
-                    }
+                let Some(al) = parse_auto_length_value(values) else {
+                    return false;
+                };
+                // STEP 2: Map to the physical side based on writing-mode.
+                let physical_side = self.writing_mode.block_start_physical();
+
+                // STEP 3: Check cascade - only update if we win.
+                //   [§ 4 Logical Property Groups](https://drafts.csswg.org/css-logical-1/#logical-property-groups)
+                if self.should_update_margin(physical_side, decl.source_order) {
+                    // STEP 4: Apply to both the logical field (for reference)
+                    // and the corresponding physical property.
+                    self.margin_block_start = Some(self.resolve_auto_length(al));
+                    self.set_margin_for_side(physical_side, al, decl.source_order);
                 }
             }
             // [§ 4.2 Flow-Relative Margins](https://drafts.csswg.org/css-logical-1/#margin-properties)
             "margin-block-end" => {
-                if let Some(al) = parse_auto_length_value(values) {
-                    let physical_side = self.writing_mode.block_end_physical();
-
-                    if self.should_update_margin(physical_side, decl.source_order) {
-                        self.margin_block_end = Some(self.resolve_auto_length(al));
-                        self.set_margin_for_side(physical_side, al, decl.source_order);
-                    }
+                let Some(al) = parse_auto_length_value(values) else {
+                    return false;
+                };
+                let physical_side = self.writing_mode.block_end_physical();
+
+                if self.should_update_margin(physical_side, decl.source_order) {
+                    self.margin_block_end = Some(self.resolve_auto_length(al));
+                    self.set_margin_for_side(physical_side, al, decl.source_order);
                 }
             }
 
-            "padding" => {
-                self.apply_padding_shorthand(values);
-            }
+            "padding" => return self.apply_padding_shorthand(values),
             "padding-top" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.padding_top = Some(self.resolve_length(len));
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.padding_top = Some(self.resolve_length(len));
             }
             "padding-right" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.padding_right = Some(self.resolve_length(len));
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.padding_right = Some(self.resolve_length(len));
             }
             "padding-bottom" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.padding_bottom = Some(self.resolve_length(len));
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.padding_bottom = Some(self.resolve_length(len));
             }
             "padding-left" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.padding_left = Some(self.resolve_length(len));
-                }
-            }
-            "border" => {
-                self.apply_border_shorthand(values);
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.padding_left = Some(self.resolve_length(len));
             }
+            "border" => return self.apply_border_shorthand(values),
             // [§ 4.4 border-top](https://www.w3.org/TR/css-backgrounds-3/#border-shorthands)
             //
             // "The 'border-top' shorthand property sets the width, style, and color
@@ -1095,27 +1778,31 @@ impl ComputedStyle {
             // Syntax: <line-width> || <line-style> || <color>
             // (values can appear in any order)
             "border-top" => {
-                if let Some(border) = self.parse_border_side(values) {
-                    self.border_top = Some(border);
-                }
+                let Some(border) = self.parse_border_side(values) else {
+                    return false;
+                };
+                self.border_top = Some(border);
             }
             // [§ 4.4 border-right](https://www.w3.org/TR/css-backgrounds-3/#border-shorthands)
             "border-right" => {
-                if let Some(border) = self.parse_border_side(values) {
-                    self.border_right = Some(border);
-                }
+                let Some(border) = self.parse_border_side(values) else {
+                    return false;
+                };
+                self.border_right = Some(border);
             }
             // [§ 4.4 border-bottom](https://www.w3.org/TR/css-backgrounds-3/#border-shorthands)
             "border-bottom" => {
-                if let Some(border) = self.parse_border_side(values) {
-                    self.border_bottom = Some(border);
-                }
+                let Some(border) = self.parse_border_side(values) else {
+                    return false;
+                };
+                self.border_bottom = Some(border);
             }
             // [§ 4.4 border-left](https://www.w3.org/TR/css-backgrounds-3/#border-shorthands)
             "border-left" => {
-                if let Some(border) = self.parse_border_side(values) {
-                    self.border_left = Some(border);
-                }
+                let Some(border) = self.parse_border_side(values) else {
+                    return false;
+                };
+                self.border_left = Some(border);
             }
             // [§ 4.1 'border-top-color', etc.](https://www.w3.org/TR/css-backgrounds-3/#border-color)
             //
@@ -1123,48 +1810,56 @@ impl ComputedStyle {
             // specified by the border-top, border-right, border-bottom,
             // and border-left properties respectively."
             "border-top-color" => {
-                if let Some(color) = parse_color_value(values) {
-                    self.ensure_border_top().color = color;
-                }
+                let Some(color) = parse_color_value(values) else {
+                    return false;
+                };
+                self.ensure_border_top().color = color;
             }
             "border-right-color" => {
-                if let Some(color) = parse_color_value(values) {
-                    self.ensure_border_right().color = color;
-                }
+                let Some(color) = parse_color_value(values) else {
+                    return false;
+                };
+                self.ensure_border_right().color = color;
             }
             "border-bottom-color" => {
-                if let Some(color) = parse_color_value(values) {
-                    self.ensure_border_bottom().color = color;
-                }
+                let Some(color) = parse_color_value(values) else {
+                    return false;
+                };
+                self.ensure_border_bottom().color = color;
             }
             "border-left-color" => {
-                if let Some(color) = parse_color_value(values) {
-                    self.ensure_border_left().color = color;
-                }
+                let Some(color) = parse_color_value(values) else {
+                    return false;
+                };
+                self.ensure_border_left().color = color;
             }
             // [§ 4.3 'border-top-width', etc.](https://www.w3.org/TR/css-backgrounds-3/#border-width)
             //
             // "These properties set the thickness of the border."
             // "<line-width> = <length [0,∞]> | thin | medium | thick"
             "border-top-width" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.ensure_border_top().width = self.resolve_length(len);
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.ensure_border_top().width = self.resolve_length(len);
             }
             "border-right-width" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.ensure_border_right().width = self.resolve_length(len);
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.ensure_border_right().width = self.resolve_length(len);
             }
             "border-bottom-width" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.ensure_border_bottom().width = self.resolve_length(len);
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.ensure_border_bottom().width = self.resolve_length(len);
             }
             "border-left-width" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.ensure_border_left().width = self.resolve_length(len);
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.ensure_border_left().width = self.resolve_length(len);
             }
             // [§ 4.2 'border-top-style', etc.](https://www.w3.org/TR/css-backgrounds-3/#border-style)
             //
@@ -1172,95 +1867,89 @@ impl ComputedStyle {
             // "<line-style> = none | hidden | dotted | dashed | solid | double |
             //                 groove | ridge | inset | outset"
             "border-top-style" => {
-                if let Some(first) = values.first()
-                    && let Some(s) = Self::parse_border_style(first)
-                {
-                    self.ensure_border_top().style = s;
-                }
+                let Some(s) = values.first().and_then(Self::parse_border_style) else {
+                    return false;
+                };
+                self.ensure_border_top().style = s;
             }
             "border-right-style" => {
-                if let Some(first) = values.first()
-                    && let Some(s) = Self::parse_border_style(first)
-                {
-                    self.ensure_border_right().style = s;
-                }
+                let Some(s) = values.first().and_then(Self::parse_border_style) else {
+                    return false;
+                };
+                self.ensure_border_right().style = s;
             }
             "border-bottom-style" => {
-                if let Some(first) = values.first()
-                    && let Some(s) = Self::parse_border_style(first)
-                {
-                    self.ensure_border_bottom().style = s;
-                }
+                let Some(s) = values.first().and_then(Self::parse_border_style) else {
+                    return false;
+                };
+                self.ensure_border_bottom().style = s;
             }
             "border-left-style" => {
-                if let Some(first) = values.first()
-                    && let Some(s) = Self::parse_border_style(first)
-                {
-                    self.ensure_border_left().style = s;
-                }
+                let Some(s) = values.first().and_then(Self::parse_border_style) else {
+                    return false;
+                };
+                self.ensure_border_left().style = s;
             }
             // [§ 4.1 'border-color'](https://www.w3.org/TR/css-backgrounds-3/#border-color)
             //
             // "The 'border-color' property is a shorthand for setting
             // 'border-top-color', 'border-right-color', 'border-bottom-color',
             // and 'border-left-color'."
-            "border-color" => {
-                self.apply_border_color_shorthand(values);
-            }
+            "border-color" => return self.apply_border_color_shorthand(values),
             // [§ 4.3 'border-width'](https://www.w3.org/TR/css-backgrounds-3/#border-width)
             //
             // "The 'border-width' property is a shorthand for setting
             // 'border-top-width', 'border-right-width', 'border-bottom-width',
             // and 'border-left-width'."
-            "border-width" => {
-                self.apply_border_width_shorthand(values);
-            }
+            "border-width" => return self.apply_border_width_shorthand(values),
             // [§ 4.2 'border-style'](https://www.w3.org/TR/css-backgrounds-3/#border-style)
             //
             // "The 'border-style' property is a shorthand for setting
             // 'border-top-style', 'border-right-style', 'border-bottom-style',
             // and 'border-left-style'."
-            "border-style" => {
-                self.apply_border_style_shorthand(values);
-            }
-            "background" => {
-                self.apply_background_shorthand(values);
-            }
+            "border-style" => return self.apply_border_style_shorthand(values),
+            "background" => return self.apply_background_shorthand(values),
             "font-size" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.font_size = Some(self.resolve_length(len));
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.font_size = Some(self.resolve_font_size(len));
             }
             // [§ 10.2 'width'](https://www.w3.org/TR/CSS2/visudet.html#the-width-property)
             //
             // "This property specifies the content width of boxes."
             // "Value: `<length>` | `<percentage>` | auto | inherit"
+            //
+            // [§ 3.1](https://www.w3.org/TR/css-sizing-3/#sizing-values)
+            // adds the min-content, max-content and fit-content keywords.
             "width" => {
-                if let Some(first) = values.first()
-                    && let Some(auto_len) = parse_single_auto_length(first)
-                {
-                    self.width = Some(self.resolve_auto_length(auto_len));
-                }
+                let Some(auto_len) = values.first().and_then(parse_single_size) else {
+                    return false;
+                };
+                self.width = Some(self.resolve_auto_length(auto_len));
             }
             // [§ 10.5 'height'](https://www.w3.org/TR/CSS2/visudet.html#the-height-property)
             //
             // "This property specifies the content height of boxes."
             // "Value: `<length>` | `<percentage>` | auto | inherit"
+            //
+            // [§ 3.1](https://www.w3.org/TR/css-sizing-3/#sizing-values)
+            // adds the min-content, max-content and fit-content keywords.
             "height" => {
-                if let Some(first) = values.first()
-                    && let Some(auto_len) = parse_single_auto_length(first)
-                {
-                    self.height = Some(self.resolve_auto_length(auto_len));
-                }
+                let Some(auto_len) = values.first().and_then(parse_single_size) else {
+                    return false;
+                };
+                self.height = Some(self.resolve_auto_length(auto_len));
             }
             // [§ 10.4 'min-width'](https://www.w3.org/TR/CSS2/visudet.html#min-max-widths)
             //
             // "Value: <length> | <percentage> | inherit"
             // Initial: 0
             "min-width" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.min_width = Some(self.resolve_length(len));
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.min_width = Some(self.resolve_length(len));
             }
             // [§ 10.4 'max-width'](https://www.w3.org/TR/CSS2/visudet.html#min-max-widths)
             //
@@ -1273,6 +1962,8 @@ impl ComputedStyle {
                     self.max_width = None;
                 } else if let Some(len) = parse_length_value(values) {
                     self.max_width = Some(self.resolve_length(len));
+                } else {
+                    return false;
                 }
             }
             // [§ 10.7 'min-height'](https://www.w3.org/TR/CSS2/visudet.html#min-max-heights)
@@ -1280,9 +1971,10 @@ impl ComputedStyle {
             // "Value: <length> | <percentage> | inherit"
             // Initial: 0
             "min-height" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.min_height = Some(self.resolve_length(len));
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.min_height = Some(self.resolve_length(len));
             }
             // [§ 10.7 'max-height'](https://www.w3.org/TR/CSS2/visudet.html#min-max-heights)
             //
@@ -1295,70 +1987,105 @@ impl ComputedStyle {
                     self.max_height = None;
                 } else if let Some(len) = parse_length_value(values) {
                     self.max_height = Some(self.resolve_length(len));
+                } else {
+                    return false;
+                }
+            }
+            // [§ 5.1 'aspect-ratio'](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+            //
+            // "Value: auto || <ratio>"
+            //
+            // "If the <ratio> is degenerate, the property instead behaves as
+            // auto."
+            //
+            // NOTE: `auto && <ratio>` only differs from `<ratio>` for
+            // replaced elements with a natural aspect ratio, which size
+            // from that ratio regardless, so the `auto` is dropped.
+            "aspect-ratio" => {
+                let mut tokens: Vec<&ComponentValue> = values
+                    .iter()
+                    .filter(|v| !matches!(v, ComponentValue::Token(CSSToken::Whitespace)))
+                    .collect();
+                let is_auto = |v: &&ComponentValue| matches!(v, ComponentValue::Token(CSSToken::Ident(ident)) if ident.eq_ignore_ascii_case("auto"));
+                let has_auto =
+                    tokens.first().is_some_and(is_auto) || tokens.last().is_some_and(is_auto);
+                tokens.retain(|v| !is_auto(v));
+                if tokens.is_empty() && has_auto {
+                    self.aspect_ratio = None;
+                } else if let Some((width, height)) = Self::parse_ratio(&tokens) {
+                    // "A <ratio> is degenerate if either of its numbers
+                    // is zero"
+                    self.aspect_ratio = (width > 0.0 && height > 0.0).then_some((width, height));
+                } else {
+                    return false;
                 }
             }
             // [§ 5.1 'flex-direction'](https://www.w3.org/TR/css-flexbox-1/#flex-direction-property)
             //
             // "Values: row | row-reverse | column | column-reverse"
             "flex-direction" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "row" => self.flex_direction = Some(FlexDirection::Row),
-                        "row-reverse" => self.flex_direction = Some(FlexDirection::RowReverse),
-                        "column" => self.flex_direction = Some(FlexDirection::Column),
-                        "column-reverse" => {
-                            self.flex_direction = Some(FlexDirection::ColumnReverse);
-                        }
-                        _ => {}
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "row" => self.flex_direction = Some(FlexDirection::Row),
+                    "row-reverse" => self.flex_direction = Some(FlexDirection::RowReverse),
+                    "column" => self.flex_direction = Some(FlexDirection::Column),
+                    "column-reverse" => {
+                        self.flex_direction = Some(FlexDirection::ColumnReverse);
                     }
+                    _ => return false,
                 }
             }
             // [§ 8.2 'justify-content'](https://www.w3.org/TR/css-flexbox-1/#justify-content-property)
             //
             // "Values: flex-start | flex-end | center | space-between | space-around"
             "justify-content" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "flex-start" => self.justify_content = Some(JustifyContent::FlexStart),
-                        "flex-end" => self.justify_content = Some(JustifyContent::FlexEnd),
-                        "center" => self.justify_content = Some(JustifyContent::Center),
-                        "space-between" => {
-                            self.justify_content = Some(JustifyContent::SpaceBetween);
-                        }
-                        "space-around" => self.justify_content = Some(JustifyContent::SpaceAround),
-                        _ => {}
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "flex-start" => self.justify_content = Some(JustifyContent::FlexStart),
+                    "flex-end" => self.justify_content = Some(JustifyContent::FlexEnd),
+                    "center" => self.justify_content = Some(JustifyContent::Center),
+                    "space-between" => {
+                        self.justify_content = Some(JustifyContent::SpaceBetween);
                     }
+                    "space-around" => self.justify_content = Some(JustifyContent::SpaceAround),
+                    _ => return false,
                 }
             }
             // [§ 8.3 'align-items'](https://www.w3.org/TR/css-flexbox-1/#align-items-property)
             //
             // "Values: flex-start | flex-end | center | baseline | stretch"
             "align-items" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "flex-start" | "start" => self.align_items = Some(AlignItems::FlexStart),
-                        "flex-end" | "end" => self.align_items = Some(AlignItems::FlexEnd),
-                        "center" => self.align_items = Some(AlignItems::Center),
-                        "baseline" => self.align_items = Some(AlignItems::Baseline),
-                        "stretch" => self.align_items = Some(AlignItems::Stretch),
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "flex-start" | "start" => self.align_items = Some(AlignItems::FlexStart),
+                    "flex-end" | "end" => self.align_items = Some(AlignItems::FlexEnd),
+                    "center" => self.align_items = Some(AlignItems::Center),
+                    "baseline" => self.align_items = Some(AlignItems::Baseline),
+                    "stretch" => self.align_items = Some(AlignItems::Stretch),
+                    _ => return false,
                 }
             }
             // [§ 8.3 'align-self'](https://www.w3.org/TR/css-flexbox-1/#align-items-property)
             //
             // "Values: auto | flex-start | flex-end | center | baseline | stretch"
             "align-self" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "auto" => self.align_self = Some(AlignSelf::Auto),
-                        "flex-start" | "start" => self.align_self = Some(AlignSelf::FlexStart),
-                        "flex-end" | "end" => self.align_self = Some(AlignSelf::FlexEnd),
-                        "center" => self.align_self = Some(AlignSelf::Center),
-                        "baseline" => self.align_self = Some(AlignSelf::Baseline),
-                        "stretch" => self.align_self = Some(AlignSelf::Stretch),
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "auto" => self.align_self = Some(AlignSelf::Auto),
+                    "flex-start" | "start" => self.align_self = Some(AlignSelf::FlexStart),
+                    "flex-end" | "end" => self.align_self = Some(AlignSelf::FlexEnd),
+                    "center" => self.align_self = Some(AlignSelf::Center),
+                    "baseline" => self.align_self = Some(AlignSelf::Baseline),
+                    "stretch" => self.align_self = Some(AlignSelf::Stretch),
+                    _ => return false,
                 }
             }
             // [§ 7.2 'flex-grow'](https://www.w3.org/TR/css-flexbox-1/#flex-grow-property)
@@ -1367,13 +2094,15 @@ impl ComputedStyle {
             // `<number>`. Negative values are invalid."
             #[allow(clippy::cast_possible_truncation)]
             "flex-grow" => {
-                if let Some(ComponentValue::Token(CSSToken::Number { value, .. })) = values.first()
-                {
-                    let val = *value as f32;
-                    if val >= 0.0 {
-                        self.flex_grow = Some(val);
-                    }
+                let Some(ComponentValue::Token(CSSToken::Number { value, .. })) = values.first()
+                else {
+                    return false;
+                };
+                let val = *value as f32;
+                if val < 0.0 {
+                    return false;
                 }
+                self.flex_grow = Some(val);
             }
             // [§ 7.3 'flex-shrink'](https://www.w3.org/TR/css-flexbox-1/#flex-shrink-property)
             //
@@ -1381,23 +2110,24 @@ impl ComputedStyle {
             // `<number>`. Negative values are invalid."
             #[allow(clippy::cast_possible_truncation)]
             "flex-shrink" => {
-                if let Some(ComponentValue::Token(CSSToken::Number { value, .. })) = values.first()
-                {
-                    let val = *value as f32;
-                    if val >= 0.0 {
-                        self.flex_shrink = Some(val);
-                    }
+                let Some(ComponentValue::Token(CSSToken::Number { value, .. })) = values.first()
+                else {
+                    return false;
+                };
+                let val = *value as f32;
+                if val < 0.0 {
+                    return false;
                 }
+                self.flex_shrink = Some(val);
             }
             // [§ 7.1 'flex-basis'](https://www.w3.org/TR/css-flexbox-1/#flex-basis-property)
             //
             // "Values: auto | <length>"
             "flex-basis" => {
-                if let Some(first) = values.first()
-                    && let Some(auto_len) = parse_single_auto_length(first)
-                {
-                    self.flex_basis = Some(self.resolve_auto_length(auto_len));
-                }
+                let Some(auto_len) = values.first().and_then(parse_single_auto_length) else {
+                    return false;
+                };
+                self.flex_basis = Some(self.resolve_auto_length(auto_len));
             }
             // [§ 7 'flex' shorthand](https://www.w3.org/TR/css-flexbox-1/#flex-property)
             //
@@ -1411,20 +2141,19 @@ impl ComputedStyle {
             //   flex: auto  → flex: 1 1 auto
             //   flex: <number> → flex: <number> 1 0 (note: basis is 0, not auto!)
             #[allow(clippy::cast_possible_truncation)]
-            "flex" => {
-                self.parse_flex_shorthand(values);
-            }
+            "flex" => return self.parse_flex_shorthand(values),
             // [§ 5.2 'flex-wrap'](https://www.w3.org/TR/css-flexbox-1/#flex-wrap-property)
             //
             // "Values: nowrap | wrap | wrap-reverse"
             "flex-wrap" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "nowrap" => self.flex_wrap = Some(FlexWrap::Nowrap),
-                        "wrap" => self.flex_wrap = Some(FlexWrap::Wrap),
-                        "wrap-reverse" => self.flex_wrap = Some(FlexWrap::WrapReverse),
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "nowrap" => self.flex_wrap = Some(FlexWrap::Nowrap),
+                    "wrap" => self.flex_wrap = Some(FlexWrap::Wrap),
+                    "wrap-reverse" => self.flex_wrap = Some(FlexWrap::WrapReverse),
+                    _ => return false,
                 }
             }
             // [§ 5.3 'flex-flow' shorthand](https://www.w3.org/TR/css-flexbox-1/#flex-flow-property)
@@ -1432,6 +2161,11 @@ impl ComputedStyle {
             // "Value: <'flex-direction'> || <'flex-wrap'>"
             "flex-flow" => {
                 for cv in values {
+                    match cv {
+                        ComponentValue::Token(CSSToken::Whitespace) => continue,
+                        ComponentValue::Token(CSSToken::Ident(_)) => {}
+                        _ => return false,
+                    }
                     if let ComponentValue::Token(CSSToken::Ident(ident)) = cv {
                         match ident.to_ascii_lowercase().as_str() {
                             "row" => self.flex_direction = Some(FlexDirection::Row),
@@ -1443,7 +2177,7 @@ impl ComputedStyle {
                             "nowrap" => self.flex_wrap = Some(FlexWrap::Nowrap),
                             "wrap" => self.flex_wrap = Some(FlexWrap::Wrap),
                             "wrap-reverse" => self.flex_wrap = Some(FlexWrap::WrapReverse),
-                            _ => {}
+                            _ => return false,
                         }
                     }
                 }
@@ -1452,27 +2186,29 @@ impl ComputedStyle {
             //
             // "Values: left | right | none | inherit"
             "float" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "left" => self.float = Some(FloatSide::Left),
-                        "right" => self.float = Some(FloatSide::Right),
-                        "none" => self.float = None,
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "left" => self.float = Some(FloatSide::Left),
+                    "right" => self.float = Some(FloatSide::Right),
+                    "none" => self.float = None,
+                    _ => return false,
                 }
             }
             // [§ 9.5.2 Controlling flow next to floats: the 'clear' property](https://www.w3.org/TR/CSS2/visuren.html#flow-control)
             //
             // "Values: left | right | both | none | inherit"
             "clear" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "left" => self.clear = Some(ClearSide::Left),
-                        "right" => self.clear = Some(ClearSide::Right),
-                        "both" => self.clear = Some(ClearSide::Both),
-                        "none" => self.clear = None,
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "left" => self.clear = Some(ClearSide::Left),
+                    "right" => self.clear = Some(ClearSide::Right),
+                    "both" => self.clear = Some(ClearSide::Both),
+                    "none" => self.clear = None,
+                    _ => return false,
                 }
             }
             // [§ 9.3.1 'position'](https://www.w3.org/TR/CSS2/visuren.html#choose-position)
@@ -1481,39 +2217,44 @@ impl ComputedStyle {
             // [CSS Positioned Layout Module Level 3 § 3](https://www.w3.org/TR/css-position-3/#position-property)
             // adds "sticky"
             "position" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "static" => self.position = Some(PositionType::Static),
-                        "relative" => self.position = Some(PositionType::Relative),
-                        "absolute" => self.position = Some(PositionType::Absolute),
-                        "fixed" => self.position = Some(PositionType::Fixed),
-                        "sticky" => self.position = Some(PositionType::Sticky),
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "static" => self.position = Some(PositionType::Static),
+                    "relative" => self.position = Some(PositionType::Relative),
+                    "absolute" => self.position = Some(PositionType::Absolute),
+                    "fixed" => self.position = Some(PositionType::Fixed),
+                    "sticky" => self.position = Some(PositionType::Sticky),
+                    _ => return false,
                 }
             }
             // [§ 9.3.2 Box offsets: 'top', 'right', 'bottom', 'left'](https://www.w3.org/TR/CSS2/visuren.html#position-props)
             //
             // "Values: <length> | <percentage> | auto | inherit"
             "top" => {
-                if let Some(al) = parse_auto_length_value(values) {
-                    self.top = Some(self.resolve_auto_length(al));
-                }
+                let Some(al) = parse_auto_length_value(values) else {
+                    return false;
+                };
+                self.top = Some(self.resolve_auto_length(al));
             }
             "right" => {
-                if let Some(al) = parse_auto_length_value(values) {
-                    self.right = Some(self.resolve_auto_length(al));
-                }
+                let Some(al) = parse_auto_length_value(values) else {
+                    return false;
+                };
+                self.right = Some(self.resolve_auto_length(al));
             }
             "bottom" => {
-                if let Some(al) = parse_auto_length_value(values) {
-                    self.bottom = Some(self.resolve_auto_length(al));
-                }
+                let Some(al) = parse_auto_length_value(values) else {
+                    return false;
+                };
+                self.bottom = Some(self.resolve_auto_length(al));
             }
             "left" => {
-                if let Some(al) = parse_auto_length_value(values) {
-                    self.left = Some(self.resolve_auto_length(al));
-                }
+                let Some(al) = parse_auto_length_value(values) else {
+                    return false;
+                };
+                self.left = Some(self.resolve_auto_length(al));
             }
             // [§ 3.1 'list-style-type'](https://www.w3.org/TR/css-lists-3/#list-style-type)
             //
@@ -1521,46 +2262,137 @@ impl ComputedStyle {
             // for the element's marker."
             // Values: disc | circle | square | decimal | lower-alpha | upper-alpha |
             //         lower-roman | upper-roman | none
-            "list-style-type" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "disc" => self.list_style_type = Some(ListStyleType::Disc),
-                        "circle" => self.list_style_type = Some(ListStyleType::Circle),
-                        "square" => self.list_style_type = Some(ListStyleType::Square),
-                        "decimal" => self.list_style_type = Some(ListStyleType::Decimal),
-                        "lower-alpha" => self.list_style_type = Some(ListStyleType::LowerAlpha),
-                        "upper-alpha" => self.list_style_type = Some(ListStyleType::UpperAlpha),
-                        "lower-roman" => self.list_style_type = Some(ListStyleType::LowerRoman),
-                        "upper-roman" => self.list_style_type = Some(ListStyleType::UpperRoman),
-                        "none" => self.list_style_type = Some(ListStyleType::None),
-                        _ => {}
+            // [§ 2 'content'](https://www.w3.org/TR/css-content-3/#content-property)
+            //
+            // "Value: normal | none | [ <content-replacement> |
+            // <content-list> ] [/ [ <string> | <counter> ]+ ]?"
+            //
+            // Strings, counter() and counters() are supported; anything else
+            // (url(), attr(), quotes) leaves the value unchanged.
+            "content" => {
+                if let [ComponentValue::Token(CSSToken::Ident(ident))] = values {
+                    if !ident.eq_ignore_ascii_case("normal") && !ident.eq_ignore_ascii_case("none")
+                    {
+                        return false;
                     }
+                    self.content = None;
+                } else if let Some(items) = Self::parse_content_list(values) {
+                    self.content = Some(items);
+                } else {
+                    return false;
                 }
             }
-            // [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
+            // [§ 4.2 Creating Counters](https://www.w3.org/TR/css-lists-3/#counter-reset)
+            //
+            // "Value: [ <counter-name> <integer>? | <reversed-counter-name>
+            // <integer>? ]+ | none"
+            //
+            // "If there is not an <integer> following a <counter-name>, then
+            // the counter is instantiated with a starting value of 0."
+            "counter-reset" => {
+                let Some(counters) = Self::parse_counter_list(values, 0) else {
+                    return false;
+                };
+                self.counter_reset = Some(counters);
+            }
+            // [§ 4.3 Manipulating Counter Values](https://www.w3.org/TR/css-lists-3/#increment-set)
+            //
+            // "Value: [ <counter-name> <integer>? ]+ | none"
+            //
+            // "If omitted, the <integer> defaults to 1 for counter-increment."
+            "counter-increment" => {
+                let Some(counters) = Self::parse_counter_list(values, 1) else {
+                    return false;
+                };
+                self.counter_increment = Some(counters);
+            }
+            "list-style-type" => {
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                let Some(list_style) = Self::parse_list_style_type(ident) else {
+                    return false;
+                };
+                self.list_style_type = Some(list_style);
+            }
+            // [§ 3 overflow-x, overflow-y](https://www.w3.org/TR/css-overflow-3/#overflow-properties)
             //
             // "Values: visible | hidden | scroll | auto"
-            "overflow" | "overflow-x" | "overflow-y" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "visible" => self.overflow = Some(Overflow::Visible),
-                        "hidden" => self.overflow = Some(Overflow::Hidden),
-                        "scroll" => self.overflow = Some(Overflow::Scroll),
-                        "auto" => self.overflow = Some(Overflow::Auto),
-                        _ => {}
-                    }
+            "overflow-x" => {
+                let Some(overflow) = values.first().and_then(Self::parse_overflow) else {
+                    return false;
+                };
+                self.overflow_x = Some(overflow);
+            }
+            "overflow-y" => {
+                let Some(overflow) = values.first().and_then(Self::parse_overflow) else {
+                    return false;
+                };
+                self.overflow_y = Some(overflow);
+            }
+            // [§ 3 overflow](https://www.w3.org/TR/css-overflow-3/#propdef-overflow)
+            //
+            // "The overflow property is a shorthand property that sets the
+            // specified values of overflow-x and overflow-y in that order.
+            // If the second value is omitted, it is copied from the first."
+            "overflow" => {
+                let parsed: Option<Vec<Overflow>> = values
+                    .iter()
+                    .filter(|v| !matches!(v, ComponentValue::Token(CSSToken::Whitespace)))
+                    .map(Self::parse_overflow)
+                    .collect();
+                let Some(&[x, ref rest @ ..]) = parsed.as_deref() else {
+                    return false;
+                };
+                if rest.len() > 1 {
+                    return false;
+                }
+                self.overflow_x = Some(x);
+                self.overflow_y = Some(rest.first().copied().unwrap_or(x));
+            }
+            // [§ 5.5 'object-fit'](https://www.w3.org/TR/css-images-3/#the-object-fit)
+            //
+            // "Values: fill | contain | cover | none | scale-down"
+            "object-fit" => {
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "fill" => self.object_fit = Some(ObjectFit::Fill),
+                    "contain" => self.object_fit = Some(ObjectFit::Contain),
+                    "cover" => self.object_fit = Some(ObjectFit::Cover),
+                    "none" => self.object_fit = Some(ObjectFit::None),
+                    "scale-down" => self.object_fit = Some(ObjectFit::ScaleDown),
+                    _ => return false,
+                }
+            }
+            // [§ 5.6 'object-position'](https://www.w3.org/TR/css-images-3/#the-object-position)
+            //
+            // "Value: <position>"
+            "object-position" => {
+                let tokens: Vec<&ComponentValue> = values
+                    .iter()
+                    .filter(|v| !matches!(v, ComponentValue::Token(CSSToken::Whitespace)))
+                    .collect();
+                let Some((position, consumed)) = parse_background_position(&tokens) else {
+                    return false;
+                };
+                if consumed != tokens.len() {
+                    return false;
                 }
+                self.object_position = Some(self.resolve_background_position(position));
             }
             // [§ 4.4 box-sizing](https://www.w3.org/TR/css-box-4/#box-sizing)
             //
             // "Values: content-box | border-box"
             "box-sizing" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "border-box" => self.box_sizing_border_box = Some(true),
-                        "content-box" => self.box_sizing_border_box = Some(false),
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "border-box" => self.box_sizing_border_box = Some(true),
+                    "content-box" => self.box_sizing_border_box = Some(false),
+                    _ => return false,
                 }
             }
             // [§ 16.6 'white-space'](https://www.w3.org/TR/CSS2/text.html#white-space-prop)
@@ -1568,40 +2400,287 @@ impl ComputedStyle {
             // "This property declares how white space inside the element is handled."
             // Values: normal | pre | nowrap | pre-wrap | pre-line
             "white-space" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "normal" => self.white_space = Some(WhiteSpace::Normal),
-                        "pre" => self.white_space = Some(WhiteSpace::Pre),
-                        "nowrap" => self.white_space = Some(WhiteSpace::Nowrap),
-                        "pre-wrap" => self.white_space = Some(WhiteSpace::PreWrap),
-                        "pre-line" => self.white_space = Some(WhiteSpace::PreLine),
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "normal" => self.white_space = Some(WhiteSpace::Normal),
+                    "pre" => self.white_space = Some(WhiteSpace::Pre),
+                    "nowrap" => self.white_space = Some(WhiteSpace::Nowrap),
+                    "pre-wrap" => self.white_space = Some(WhiteSpace::PreWrap),
+                    "pre-line" => self.white_space = Some(WhiteSpace::PreLine),
+                    _ => return false,
+                }
+            }
+            // [§ 5.2 'text-overflow'](https://www.w3.org/TR/css-ui-3/#text-overflow)
+            //
+            // "Value: clip | ellipsis"
+            "text-overflow" => {
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "clip" => self.text_overflow = Some(TextOverflow::Clip),
+                    "ellipsis" => self.text_overflow = Some(TextOverflow::Ellipsis),
+                    _ => return false,
+                }
+            }
+            // [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+            //
+            // "Value: baseline | sub | super | top | text-top | middle |
+            // bottom | text-bottom | <percentage> | <length> | inherit"
+            //
+            // "<percentage>: Raise (positive value) or lower (negative
+            // value) the box by this distance (a percentage of the
+            // 'line-height' value)."
+            //
+            // NOTE: Like `em` lengths elsewhere, a percentage resolves
+            // against the line height this element has when the
+            // declaration is applied.
+            "vertical-align" => {
+                let align = parse_vertical_align(values).or_else(|| {
+                    parse_length_value(values).map(|length| match length {
+                        LengthValue::Percent(percent) => {
+                            #[allow(clippy::cast_possible_truncation)]
+                            let fraction = (percent / 100.0) as f32;
+                            VerticalAlign::Length(fraction * self.line_height_px())
+                        }
+                        #[allow(clippy::cast_possible_truncation)]
+                        other => VerticalAlign::Length(self.resolve_length(other).to_px() as f32),
+                    })
+                });
+                let Some(align) = align else {
+                    return false;
+                };
+                self.vertical_align = Some(align);
+            }
+            // [§ 17.4.1 'caption-side'](https://www.w3.org/TR/CSS2/tables.html#caption-position)
+            //
+            // "Value: top | bottom | inherit"
+            "caption-side" => {
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "top" => self.caption_side = Some(CaptionSide::Top),
+                    "bottom" => self.caption_side = Some(CaptionSide::Bottom),
+                    _ => return false,
+                }
+            }
+            // [§ 17.5.2 'table-layout'](https://www.w3.org/TR/CSS2/tables.html#width-layout)
+            //
+            // "Value: auto | fixed | inherit"
+            "table-layout" => {
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "auto" => self.table_layout = Some(TableLayoutAlgorithm::Auto),
+                    "fixed" => self.table_layout = Some(TableLayoutAlgorithm::Fixed),
+                    _ => return false,
+                }
+            }
+            // [§ 17.6 'border-collapse'](https://www.w3.org/TR/CSS2/tables.html#borders)
+            //
+            // "Value: collapse | separate | inherit"
+            "border-collapse" => {
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "separate" => self.border_collapse = Some(BorderCollapse::Separate),
+                    "collapse" => self.border_collapse = Some(BorderCollapse::Collapse),
+                    _ => return false,
+                }
+            }
+            // [§ 17.6.1 'border-spacing'](https://www.w3.org/TR/CSS2/tables.html#separated-borders)
+            //
+            // "Value: <length> <length>? | inherit"
+            //
+            // "If one length is specified, it gives both the horizontal and
+            // vertical spacing. If two are specified, the first gives the
+            // horizontal spacing and the second the vertical spacing.
+            // Lengths may not be negative."
+            "border-spacing" => {
+                let lengths: Vec<LengthValue> = values
+                    .iter()
+                    .filter(|v| !matches!(v, ComponentValue::Token(CSSToken::Whitespace)))
+                    .map(parse_single_length)
+                    .collect::<Option<_>>()
+                    .unwrap_or_default();
+                let spacing = match lengths[..] {
+                    [both] => Some((both, both)),
+                    [horizontal, vertical] => Some((horizontal, vertical)),
+                    _ => None,
+                };
+                let Some((horizontal, vertical)) = spacing else {
+                    return false;
+                };
+                let horizontal = self.resolve_length(horizontal);
+                let vertical = self.resolve_length(vertical);
+                if horizontal.to_px() < 0.0 || vertical.to_px() < 0.0 {
+                    return false;
+                }
+                self.border_spacing = Some((horizontal, vertical));
+            }
+            // [§ 4.2 'tab-size'](https://www.w3.org/TR/css-text-3/#tab-size-property)
+            //
+            // "Value: <number [0,∞]> | <length [0,∞]>"
+            //
+            // "Integers represent the measure as multiples of the space
+            // character's advance width (U+0020) including its associated
+            // letter-spacing and word-spacing."
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            "tab-size" => {
+                let Some(ComponentValue::Token(CSSToken::Number { value, .. })) = values.first()
+                else {
+                    return false;
+                };
+                if *value < 0.0 {
+                    return false;
                 }
+                self.tab_size = Some(value.round() as u32);
             }
             // [§ 11.2 'visibility'](https://www.w3.org/TR/CSS2/visufx.html#visibility)
             //
             // "Values: visible | hidden | collapse"
             "visibility" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "visible" => self.visibility = Some(Visibility::Visible),
-                        "hidden" => self.visibility = Some(Visibility::Hidden),
-                        "collapse" => self.visibility = Some(Visibility::Collapse),
-                        _ => {}
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "visible" => self.visibility = Some(Visibility::Visible),
+                    "hidden" => self.visibility = Some(Visibility::Hidden),
+                    "collapse" => self.visibility = Some(Visibility::Collapse),
+                    _ => return false,
+                }
+            }
+            // [CSS Containment 2 § 4 'content-visibility'](https://www.w3.org/TR/css-contain-2/#content-visibility)
+            //
+            // "Value: visible | auto | hidden"
+            "content-visibility" => {
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "visible" => self.content_visibility = Some(ContentVisibility::Visible),
+                    "auto" => self.content_visibility = Some(ContentVisibility::Auto),
+                    "hidden" => self.content_visibility = Some(ContentVisibility::Hidden),
+                    _ => return false,
+                }
+            }
+            // [CSS Sizing 4 § 4.1 'contain-intrinsic-size'](https://www.w3.org/TR/css-sizing-4/#intrinsic-size-override)
+            //
+            // "Value: none | [ auto? [ none | <length [0,∞]> ] ]{1,2}"
+            //
+            // "If only one value is specified, it applies to both axes."
+            //
+            // NOTE: Last remembered sizes are not tracked, so the `auto`
+            // keyword is accepted and ignored.
+            "contain-intrinsic-size" => {
+                let values: Vec<&ComponentValue> = values
+                    .iter()
+                    .filter(|v| match v {
+                        ComponentValue::Token(CSSToken::Whitespace) => false,
+                        ComponentValue::Token(CSSToken::Ident(ident)) => {
+                            !ident.eq_ignore_ascii_case("auto")
+                        }
+                        _ => true,
+                    })
+                    .collect();
+                if let [ComponentValue::Token(CSSToken::Ident(ident))] = values[..]
+                    && ident.eq_ignore_ascii_case("none")
+                {
+                    self.contain_intrinsic_size = None;
+                } else {
+                    let lengths: Vec<LengthValue> = values
+                        .into_iter()
+                        .map(parse_single_length)
+                        .collect::<Option<_>>()
+                        .unwrap_or_default();
+                    let size = match lengths[..] {
+                        [both] => Some((both, both)),
+                        [width, height] => Some((width, height)),
+                        _ => None,
+                    };
+                    let Some((width, height)) = size else {
+                        return false;
+                    };
+                    let width = self.resolve_length(width);
+                    let height = self.resolve_length(height);
+                    if width.to_px() < 0.0 || height.to_px() < 0.0 {
+                        return false;
                     }
+                    self.contain_intrinsic_size = Some((width, height));
                 }
             }
+            // [§ 5.1 'cursor'](https://www.w3.org/TR/css-ui-4/#cursor)
+            //
+            // "Value: [ [ <url> [<x> <y>]? , ]* <cursor-predefined> ]"
+            //
+            // NOTE: Cursor images are not loaded, so the `<url>` entries are
+            // skipped and the keyword that ends the list is used.
+            "cursor" => {
+                let keyword = values.iter().rev().find_map(|v| match v {
+                    ComponentValue::Token(CSSToken::Ident(ident)) => Some(ident),
+                    _ => None,
+                });
+                let Some(cursor) =
+                    keyword.and_then(|ident| match ident.to_ascii_lowercase().as_str() {
+                        "auto" => Some(Cursor::Auto),
+                        "default" => Some(Cursor::Default),
+                        "none" => Some(Cursor::None),
+                        "context-menu" => Some(Cursor::ContextMenu),
+                        "help" => Some(Cursor::Help),
+                        "pointer" => Some(Cursor::Pointer),
+                        "progress" => Some(Cursor::Progress),
+                        "wait" => Some(Cursor::Wait),
+                        "cell" => Some(Cursor::Cell),
+                        "crosshair" => Some(Cursor::Crosshair),
+                        "text" => Some(Cursor::Text),
+                        "vertical-text" => Some(Cursor::VerticalText),
+                        "alias" => Some(Cursor::Alias),
+                        "copy" => Some(Cursor::Copy),
+                        "move" => Some(Cursor::Move),
+                        "no-drop" => Some(Cursor::NoDrop),
+                        "not-allowed" => Some(Cursor::NotAllowed),
+                        "grab" => Some(Cursor::Grab),
+                        "grabbing" => Some(Cursor::Grabbing),
+                        "e-resize" => Some(Cursor::EResize),
+                        "n-resize" => Some(Cursor::NResize),
+                        "ne-resize" => Some(Cursor::NeResize),
+                        "nw-resize" => Some(Cursor::NwResize),
+                        "s-resize" => Some(Cursor::SResize),
+                        "se-resize" => Some(Cursor::SeResize),
+                        "sw-resize" => Some(Cursor::SwResize),
+                        "w-resize" => Some(Cursor::WResize),
+                        "ew-resize" => Some(Cursor::EwResize),
+                        "ns-resize" => Some(Cursor::NsResize),
+                        "nesw-resize" => Some(Cursor::NeswResize),
+                        "nwse-resize" => Some(Cursor::NwseResize),
+                        "col-resize" => Some(Cursor::ColResize),
+                        "row-resize" => Some(Cursor::RowResize),
+                        "all-scroll" => Some(Cursor::AllScroll),
+                        "zoom-in" => Some(Cursor::ZoomIn),
+                        "zoom-out" => Some(Cursor::ZoomOut),
+                        _ => None,
+                    })
+                else {
+                    return false;
+                };
+                self.cursor = Some(cursor);
+            }
             // [§ 3.2 'opacity'](https://www.w3.org/TR/css-color-4/#transparency)
             //
             // "Value: <number>"
             // "Clamped to the range [0, 1]"
             #[allow(clippy::cast_possible_truncation)]
             "opacity" => {
-                if let Some(ComponentValue::Token(CSSToken::Number { value, .. })) = values.first()
-                {
-                    self.opacity = Some((*value as f32).clamp(0.0, 1.0));
-                }
+                let Some(ComponentValue::Token(CSSToken::Number { value, .. })) = values.first()
+                else {
+                    return false;
+                };
+                self.opacity = Some((*value as f32).clamp(0.0, 1.0));
             }
             // [§ 6.1 'box-shadow'](https://www.w3.org/TR/css-backgrounds-3/#box-shadow)
             //
@@ -1614,7 +2693,10 @@ impl ComputedStyle {
                 {
                     self.box_shadow = None;
                 } else {
-                    self.box_shadow = self.parse_box_shadow(values);
+                    let Some(shadows) = self.parse_box_shadow(values) else {
+                        return false;
+                    };
+                    self.box_shadow = Some(shadows);
                 }
             }
 
@@ -1628,60 +2710,92 @@ impl ComputedStyle {
             //   2 values: top-left/bottom-right, top-right/bottom-left
             //   3 values: top-left, top-right/bottom-left, bottom-right
             //   4 values: top-left, top-right, bottom-right, bottom-left
-            "border-radius" => {
-                self.apply_border_radius_shorthand(values);
+            "border-radius" => return self.apply_border_radius_shorthand(values),
+            // [CSS UI 4 § 5.1 'outline'](https://www.w3.org/TR/css-ui-4/#outline)
+            //
+            // "The outline property is a shorthand property, and sets all
+            // three of outline-style, outline-width, and outline-color."
+            //
+            // Syntax: <'outline-width'> || <'outline-style'> || <'outline-color'>
+            "outline" => {
+                let Some(outline) = self.parse_outline(values) else {
+                    return false;
+                };
+                self.outline = Some(outline);
+            }
+            // [CSS UI 4 § 5.2 'outline-width'](https://www.w3.org/TR/css-ui-4/#outline-width)
+            //
+            // "Value: <line-width>"
+            "outline-width" => {
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.ensure_outline().width = self.resolve_length(len);
+            }
+            // [CSS UI 4 § 5.3 'outline-style'](https://www.w3.org/TR/css-ui-4/#outline-style)
+            //
+            // "Value: auto | <outline-line-style>"
+            "outline-style" => {
+                let Some(s) = values.first().and_then(Self::parse_outline_style) else {
+                    return false;
+                };
+                self.ensure_outline().style = s;
+            }
+            // [CSS UI 4 § 5.4 'outline-color'](https://www.w3.org/TR/css-ui-4/#outline-color)
+            "outline-color" => {
+                let Some(color) = parse_color_value(values) else {
+                    return false;
+                };
+                self.ensure_outline().color = color;
+            }
+            // [CSS UI 4 § 5.5 'outline-offset'](https://www.w3.org/TR/css-ui-4/#outline-offset)
+            //
+            // "Value: <length>"
+            "outline-offset" => {
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.outline_offset = Some(self.resolve_length(len));
             }
             // [§ 5.1 'border-top-left-radius'](https://www.w3.org/TR/css-backgrounds-3/#border-top-left-radius)
             #[allow(clippy::cast_possible_truncation)]
             "border-top-left-radius" => {
-                if let Some(len) = parse_single_length(
-                    values
-                        .first()
-                        .unwrap_or(&ComponentValue::Token(CSSToken::Whitespace)),
-                ) {
-                    let resolved = self.resolve_length(len).to_px() as f32;
-                    let br = self.border_radius.get_or_insert_with(BorderRadius::default);
-                    br.top_left = resolved;
-                }
+                let Some(len) = values.first().and_then(parse_single_length) else {
+                    return false;
+                };
+                let resolved = self.resolve_length(len).to_px() as f32;
+                let br = self.border_radius.get_or_insert_with(BorderRadius::default);
+                br.top_left = resolved;
             }
             // [§ 5.2 'border-top-right-radius'](https://www.w3.org/TR/css-backgrounds-3/#border-top-right-radius)
             #[allow(clippy::cast_possible_truncation)]
             "border-top-right-radius" => {
-                if let Some(len) = parse_single_length(
-                    values
-                        .first()
-                        .unwrap_or(&ComponentValue::Token(CSSToken::Whitespace)),
-                ) {
-                    let resolved = self.resolve_length(len).to_px() as f32;
-                    let br = self.border_radius.get_or_insert_with(BorderRadius::default);
-                    br.top_right = resolved;
-                }
+                let Some(len) = values.first().and_then(parse_single_length) else {
+                    return false;
+                };
+                let resolved = self.resolve_length(len).to_px() as f32;
+                let br = self.border_radius.get_or_insert_with(BorderRadius::default);
+                br.top_right = resolved;
             }
             // [§ 5.3 'border-bottom-right-radius'](https://www.w3.org/TR/css-backgrounds-3/#border-bottom-right-radius)
             #[allow(clippy::cast_possible_truncation)]
             "border-bottom-right-radius" => {
-                if let Some(len) = parse_single_length(
-                    values
-                        .first()
-                        .unwrap_or(&ComponentValue::Token(CSSToken::Whitespace)),
-                ) {
-                    let resolved = self.resolve_length(len).to_px() as f32;
-                    let br = self.border_radius.get_or_insert_with(BorderRadius::default);
-                    br.bottom_right = resolved;
-                }
+                let Some(len) = values.first().and_then(parse_single_length) else {
+                    return false;
+                };
+                let resolved = self.resolve_length(len).to_px() as f32;
+                let br = self.border_radius.get_or_insert_with(BorderRadius::default);
+                br.bottom_right = resolved;
             }
             // [§ 5.4 'border-bottom-left-radius'](https://www.w3.org/TR/css-backgrounds-3/#border-bottom-left-radius)
             #[allow(clippy::cast_possible_truncation)]
             "border-bottom-left-radius" => {
-                if let Some(len) = parse_single_length(
-                    values
-                        .first()
-                        .unwrap_or(&ComponentValue::Token(CSSToken::Whitespace)),
-                ) {
-                    let resolved = self.resolve_length(len).to_px() as f32;
-                    let br = self.border_radius.get_or_insert_with(BorderRadius::default);
-                    br.bottom_left = resolved;
-                }
+                let Some(len) = values.first().and_then(parse_single_length) else {
+                    return false;
+                };
+                let resolved = self.resolve_length(len).to_px() as f32;
+                let br = self.border_radius.get_or_insert_with(BorderRadius::default);
+                br.bottom_left = resolved;
             }
 
             // ===== Grid layout properties =====
@@ -1691,39 +2805,44 @@ impl ComputedStyle {
             // "These properties specify, as a space-separated track list, the line
             // names and track sizing functions of the grid."
             "grid-template-columns" => {
-                if let Some(tl) = self.parse_track_list(values) {
-                    self.grid_template_columns = Some(tl);
-                }
+                let Some(tl) = self.parse_track_list(values) else {
+                    return false;
+                };
+                self.grid_template_columns = Some(tl);
             }
             // [§ 7.2 'grid-template-rows'](https://www.w3.org/TR/css-grid-1/#track-sizing)
             "grid-template-rows" => {
-                if let Some(tl) = self.parse_track_list(values) {
-                    self.grid_template_rows = Some(tl);
-                }
+                let Some(tl) = self.parse_track_list(values) else {
+                    return false;
+                };
+                self.grid_template_rows = Some(tl);
             }
             // [§ 7.6 'grid-auto-flow'](https://www.w3.org/TR/css-grid-1/#auto-placement-algo)
             //
             // "Values: row | column | row dense | column dense"
             "grid-auto-flow" => {
-                if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() {
-                    match ident.to_ascii_lowercase().as_str() {
-                        "row" => self.grid_auto_flow = Some(GridAutoFlow::Row),
-                        "column" => self.grid_auto_flow = Some(GridAutoFlow::Column),
-                        _ => {}
-                    }
+                let Some(ComponentValue::Token(CSSToken::Ident(ident))) = values.first() else {
+                    return false;
+                };
+                match ident.to_ascii_lowercase().as_str() {
+                    "row" => self.grid_auto_flow = Some(GridAutoFlow::Row),
+                    "column" => self.grid_auto_flow = Some(GridAutoFlow::Column),
+                    _ => return false,
                 }
             }
             // [§ 10.1 'row-gap'](https://www.w3.org/TR/css-align-3/#row-gap)
             "row-gap" | "grid-row-gap" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.row_gap = Some(self.resolve_length(len));
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.row_gap = Some(self.resolve_length(len));
             }
             // [§ 10.1 'column-gap'](https://www.w3.org/TR/css-align-3/#column-gap)
             "column-gap" | "grid-column-gap" => {
-                if let Some(len) = parse_length_value(values) {
-                    self.column_gap = Some(self.resolve_length(len));
-                }
+                let Some(len) = parse_length_value(values) else {
+                    return false;
+                };
+                self.column_gap = Some(self.resolve_length(len));
             }
             // [§ 10.1 'gap'](https://www.w3.org/TR/css-align-3/#gap-shorthand)
             //
@@ -1743,41 +2862,46 @@ impl ComputedStyle {
                         self.row_gap = Some(self.resolve_length(lengths[0]));
                         self.column_gap = Some(self.resolve_length(lengths[1]));
                     }
-                    _ => {}
+                    _ => return false,
                 }
             }
             // [§ 8.3 'grid-column-start'](https://www.w3.org/TR/css-grid-1/#line-placement)
             "grid-column-start" => {
-                if let Some(gl) = Self::parse_grid_line(values) {
-                    self.grid_column_start = Some(gl);
-                }
+                let Some(gl) = Self::parse_grid_line(values) else {
+                    return false;
+                };
+                self.grid_column_start = Some(gl);
             }
             // [§ 8.3 'grid-column-end'](https://www.w3.org/TR/css-grid-1/#line-placement)
             "grid-column-end" => {
-                if let Some(gl) = Self::parse_grid_line(values) {
-                    self.grid_column_end = Some(gl);
-                }
+                let Some(gl) = Self::parse_grid_line(values) else {
+                    return false;
+                };
+                self.grid_column_end = Some(gl);
             }
             // [§ 8.3 'grid-row-start'](https://www.w3.org/TR/css-grid-1/#line-placement)
             "grid-row-start" => {
-                if let Some(gl) = Self::parse_grid_line(values) {
-                    self.grid_row_start = Some(gl);
-                }
+                let Some(gl) = Self::parse_grid_line(values) else {
+                    return false;
+                };
+                self.grid_row_start = Some(gl);
             }
             // [§ 8.3 'grid-row-end'](https://www.w3.org/TR/css-grid-1/#line-placement)
             "grid-row-end" => {
-                if let Some(gl) = Self::parse_grid_line(values) {
-                    self.grid_row_end = Some(gl);
-                }
+                let Some(gl) = Self::parse_grid_line(values) else {
+                    return false;
+                };
+                self.grid_row_end = Some(gl);
             }
             // [§ 8.4 'grid-column' shorthand](https://www.w3.org/TR/css-grid-1/#propdef-grid-column)
             //
             // "Value: <grid-line> [ / <grid-line> ]?"
             "grid-column" => {
                 let (start, end) = Self::parse_grid_line_shorthand(values);
-                if let Some(s) = start {
-                    self.grid_column_start = Some(s);
-                }
+                let Some(start) = start else {
+                    return false;
+                };
+                self.grid_column_start = Some(start);
                 self.grid_column_end = Some(end.unwrap_or(GridLine::Auto));
             }
             // [§ 8.4 'grid-row' shorthand](https://www.w3.org/TR/css-grid-1/#propdef-grid-row)
@@ -1785,9 +2909,10 @@ impl ComputedStyle {
             // "Value: <grid-line> [ / <grid-line> ]?"
             "grid-row" => {
                 let (start, end) = Self::parse_grid_line_shorthand(values);
-                if let Some(s) = start {
-                    self.grid_row_start = Some(s);
-                }
+                let Some(start) = start else {
+                    return false;
+                };
+                self.grid_row_start = Some(start);
                 self.grid_row_end = Some(end.unwrap_or(GridLine::Auto));
             }
             // [§ 4 Font Shorthand](https://www.w3.org/TR/css-fonts-4/#font-prop)
@@ -1798,9 +2923,7 @@ impl ComputedStyle {
             //
             // "All subproperties of the font shorthand are first reset to their
             // initial values, including those not explicitly set."
-            "font" => {
-                self.parse_font_shorthand(values);
-            }
+            "font" => return self.parse_font_shorthand(values),
             unknown => {
                 // [§ 4.1.1 Declarations](https://www.w3.org/TR/css-syntax-3/#consume-declaration)
                 //
@@ -1817,8 +2940,10 @@ impl ComputedStyle {
                 if !crate::vendor_prefixes::is_silent_vendor_property(unknown) {
                     warn_once("CSS", &format!("unknown property '{unknown}'"));
                 }
+                return false;
             }
         }
+        true
     }
 
     /// [§ 2.3 Resolving Dependency Cycles](https://www.w3.org/TR/css-variables-1/#cycles)
@@ -1861,7 +2986,7 @@ impl ComputedStyle {
     ///
     /// "Value: `<margin-width>`{1,4} | inherit"
     /// "`<margin-width>` = `<length>` | `<percentage>` | auto"
-    fn apply_margin_shorthand(&mut self, values: &[ComponentValue]) {
+    fn apply_margin_shorthand(&mut self, values: &[ComponentValue]) -> bool {
         // STEP 1: Parse all <margin-width> values from the declaration.
         // [§ 8.3](https://www.w3.org/TR/CSS2/box.html#margin-properties)
         //
@@ -1910,12 +3035,13 @@ impl ComputedStyle {
                 self.margin_bottom = Some(self.resolve_auto_length(auto_lengths[2]));
                 self.margin_left = Some(self.resolve_auto_length(auto_lengths[3]));
             }
-            _ => {}
+            _ => return false,
         }
+        true
     }
 
     /// [§ 6.2 Padding](https://www.w3.org/TR/css-box-4/#paddings)
-    fn apply_padding_shorthand(&mut self, values: &[ComponentValue]) {
+    fn apply_padding_shorthand(&mut self, values: &[ComponentValue]) -> bool {
         let lengths: Vec<LengthValue> = values.iter().filter_map(parse_single_length).collect();
 
         match lengths.len() {
@@ -1943,8 +3069,9 @@ impl ComputedStyle {
                 self.padding_bottom = Some(self.resolve_length(lengths[2]));
                 self.padding_left = Some(self.resolve_length(lengths[3]));
             }
-            _ => {}
+            _ => return false,
         }
+        true
     }
 
     /// [§ 5 'border-radius'](https://www.w3.org/TR/css-backgrounds-3/#border-radius)
@@ -1960,7 +3087,7 @@ impl ComputedStyle {
     ///   3 values: top-left, top-right/bottom-left, bottom-right
     ///   4 values: top-left, top-right, bottom-right, bottom-left
     #[allow(clippy::cast_possible_truncation)]
-    fn apply_border_radius_shorthand(&mut self, values: &[ComponentValue]) {
+    fn apply_border_radius_shorthand(&mut self, values: &[ComponentValue]) -> bool {
         let lengths: Vec<f32> = values
             .iter()
             .filter_map(parse_single_length)
@@ -1996,21 +3123,24 @@ impl ComputedStyle {
                 bottom_right: lengths[2],
                 bottom_left: lengths[3],
             },
-            _ => return,
+            _ => return false,
         };
 
         self.border_radius = Some(br);
+        true
     }
 
     /// [§ 3.1 border shorthand](https://www.w3.org/TR/css-backgrounds-3/#the-border-shorthands)
     /// "border: 1px solid #ddd" sets all four borders
-    fn apply_border_shorthand(&mut self, values: &[ComponentValue]) {
-        if let Some(border) = self.parse_border_side(values) {
-            self.border_top = Some(border.clone());
-            self.border_right = Some(border.clone());
-            self.border_bottom = Some(border.clone());
-            self.border_left = Some(border);
-        }
+    fn apply_border_shorthand(&mut self, values: &[ComponentValue]) -> bool {
+        let Some(border) = self.parse_border_side(values) else {
+            return false;
+        };
+        self.border_top = Some(border.clone());
+        self.border_right = Some(border.clone());
+        self.border_bottom = Some(border.clone());
+        self.border_left = Some(border);
+        true
     }
 
     /// [§ 4 Borders](https://www.w3.org/TR/css-backgrounds-3/#borders)
@@ -2065,7 +3195,7 @@ impl ComputedStyle {
     /// "Value: <color>{1,4}"
     ///
     /// Shorthand following the same 1-4 value expansion as margin/padding.
-    fn apply_border_color_shorthand(&mut self, values: &[ComponentValue]) {
+    fn apply_border_color_shorthand(&mut self, values: &[ComponentValue]) -> bool {
         let colors: Vec<ColorValue> = values.iter().filter_map(parse_single_color).collect();
 
         match colors.len() {
@@ -2093,8 +3223,9 @@ impl ComputedStyle {
                 self.ensure_border_bottom().color = colors[2].clone();
                 self.ensure_border_left().color = colors[3].clone();
             }
-            _ => {}
+            _ => return false,
         }
+        true
     }
 
     /// [§ 4.3 'border-width'](https://www.w3.org/TR/css-backgrounds-3/#border-width)
@@ -2102,7 +3233,7 @@ impl ComputedStyle {
     /// "Value: <line-width>{1,4}"
     ///
     /// Shorthand following the same 1-4 value expansion as margin/padding.
-    fn apply_border_width_shorthand(&mut self, values: &[ComponentValue]) {
+    fn apply_border_width_shorthand(&mut self, values: &[ComponentValue]) -> bool {
         let lengths: Vec<LengthValue> = values.iter().filter_map(parse_single_length).collect();
 
         match lengths.len() {
@@ -2140,8 +3271,9 @@ impl ComputedStyle {
                 self.ensure_border_bottom().width = b;
                 self.ensure_border_left().width = l;
             }
-            _ => {}
+            _ => return false,
         }
+        true
     }
 
     /// [§ 4.2 'border-style'](https://www.w3.org/TR/css-backgrounds-3/#border-style)
@@ -2149,7 +3281,7 @@ impl ComputedStyle {
     /// "Value: <line-style>{1,4}"
     ///
     /// Shorthand following the same 1-4 value expansion as margin/padding.
-    fn apply_border_style_shorthand(&mut self, values: &[ComponentValue]) {
+    fn apply_border_style_shorthand(&mut self, values: &[ComponentValue]) -> bool {
         let styles: Vec<String> = values.iter().filter_map(Self::parse_border_style).collect();
 
         match styles.len() {
@@ -2177,8 +3309,9 @@ impl ComputedStyle {
                 self.ensure_border_bottom().style.clone_from(&styles[2]);
                 self.ensure_border_left().style.clone_from(&styles[3]);
             }
-            _ => {}
+            _ => return false,
         }
+        true
     }
 
     /// [§ 3.10 Background](https://www.w3.org/TR/css-backgrounds-3/#background)
@@ -2186,12 +3319,77 @@ impl ComputedStyle {
     /// "The 'background' property is a shorthand property for setting most
     /// background properties at the same place in the style sheet."
     ///
-    /// TODO: Currently only handles background-color. Full shorthand supports:
-    /// background-image, background-position, background-size, background-repeat,
-    /// background-attachment, background-origin, background-clip
-    fn apply_background_shorthand(&mut self, values: &[ComponentValue]) {
-        if let Some(color) = parse_color_value(values) {
-            self.background_color = Some(color);
+    /// "`<bg-layer>`# , `<final-bg-layer>`"
+    ///
+    /// "Given a valid declaration, for each layer the shorthand first sets the
+    /// corresponding layer of each of 'background-image', 'background-position',
+    /// 'background-size', 'background-repeat', 'background-origin',
+    /// 'background-clip' and 'background-attachment' to that property's
+    /// initial value, then assigns any explicit values specified for this
+    /// layer in the declaration. Finally 'background-color' is set to the
+    /// specified color, if any, else set to its initial value."
+    ///
+    /// NOTE: 'background-attachment', 'background-origin' and
+    /// 'background-clip' are accepted but not stored.
+    fn apply_background_shorthand(&mut self, values: &[ComponentValue]) -> bool {
+        // STEP 1: Split the value into layers at top-level commas.
+        let Some(layers) = split_background_layers(values) else {
+            return false;
+        };
+
+        // STEP 2: Parse every layer. Only the final layer may carry a color;
+        // any invalid layer invalidates the whole declaration.
+        let last = layers.len() - 1;
+        let mut parsed = Vec::with_capacity(layers.len());
+        let mut color = None;
+        for (i, layer) in layers.iter().enumerate() {
+            let Some((layer, layer_color)) = parse_background_layer(layer, i == last) else {
+                return false;
+            };
+            parsed.push(layer);
+            color = layer_color;
+        }
+
+        // STEP 3: Assign every longhand, with omitted values already reset
+        // to their initial values by the layer parser.
+        self.background_image = Some(parsed.iter().map(|l| l.image.clone()).collect());
+        self.background_repeat = Some(parsed.iter().map(|l| l.repeat).collect());
+        self.background_position = Some(
+            parsed
+                .iter()
+                .map(|l| self.resolve_background_position(l.position))
+                .collect(),
+        );
+        self.background_size = Some(
+            parsed
+                .iter()
+                .map(|l| self.resolve_background_size(l.size))
+                .collect(),
+        );
+
+        // STEP 4: "Finally 'background-color' is set to the specified color,
+        // if any, else set to its initial value" (transparent, represented
+        // as None).
+        self.background_color = color;
+        true
+    }
+
+    /// Resolve font-relative lengths in a `background-position` value.
+    fn resolve_background_position(&self, position: BackgroundPosition) -> BackgroundPosition {
+        BackgroundPosition {
+            x: self.resolve_length(position.x),
+            y: self.resolve_length(position.y),
+        }
+    }
+
+    /// Resolve font-relative lengths in a `background-size` value.
+    fn resolve_background_size(&self, size: BackgroundSize) -> BackgroundSize {
+        match size {
+            BackgroundSize::Explicit { width, height } => BackgroundSize::Explicit {
+                width: self.resolve_auto_length(width),
+                height: self.resolve_auto_length(height),
+            },
+            other => other,
         }
     }
 
@@ -2208,7 +3406,7 @@ impl ComputedStyle {
     /// "All subproperties of the font shorthand are first reset to their
     /// initial values, including those not explicitly set."
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-    fn parse_font_shorthand(&mut self, values: &[ComponentValue]) {
+    fn parse_font_shorthand(&mut self, values: &[ComponentValue]) -> bool {
         // STEP 1: Filter whitespace tokens.
         let tokens: Vec<&ComponentValue> = values
             .iter()
@@ -2216,7 +3414,7 @@ impl ComputedStyle {
             .collect();
 
         if tokens.is_empty() {
-            return;
+            return false;
         }
 
         // STEP 2: Check for system font keywords.
@@ -2232,7 +3430,7 @@ impl ComputedStyle {
                 lower.as_str(),
                 "caption" | "icon" | "menu" | "message-box" | "small-caption" | "status-bar"
             ) {
-                return;
+                return true;
             }
         }
 
@@ -2265,9 +3463,11 @@ impl ComputedStyle {
                 if parsed_weight.is_none()
                     && matches!(lower.as_str(), "bold" | "bolder" | "lighter")
                 {
+                    let inherited = self.parent_font_weight.unwrap_or(400);
                     parsed_weight = Some(match lower.as_str() {
-                        "bold" | "bolder" => 700,
-                        "lighter" => 300,
+                        "bold" => 700,
+                        "bolder" => bolder_font_weight(inherited),
+                        "lighter" => lighter_font_weight(inherited),
                         _ => unreachable!(),
                     });
                     i += 1;
@@ -2300,11 +3500,11 @@ impl ComputedStyle {
         // [§ 4](https://www.w3.org/TR/css-fonts-4/#font-prop)
         // "font-size is a required value"
         if i >= tokens.len() {
-            return;
+            return false;
         }
         let font_size = parse_single_length(tokens[i]);
         if font_size.is_none() {
-            return; // Invalid font-size — entire shorthand is invalid
+            return false; // Invalid font-size — entire shorthand is invalid
         }
         i += 1;
 
@@ -2316,25 +3516,10 @@ impl ComputedStyle {
         if i < tokens.len() && matches!(tokens[i], ComponentValue::Token(CSSToken::Delim('/'))) {
             i += 1;
             if i < tokens.len() {
-                // line-height can be a number, length, or "normal"
-                match tokens[i] {
-                    ComponentValue::Token(CSSToken::Number { value, .. }) => {
-                        parsed_line_height = Some(*value);
-                        i += 1;
-                    }
-                    ComponentValue::Token(CSSToken::Dimension { value, unit, .. })
-                        if unit.eq_ignore_ascii_case("px") =>
-                    {
-                        parsed_line_height = Some(*value / 16.0);
-                        i += 1;
-                    }
-                    ComponentValue::Token(CSSToken::Ident(ident))
-                        if ident.eq_ignore_ascii_case("normal") =>
-                    {
-                        // "normal" line-height — leave as None (initial value)
-                        i += 1;
-                    }
-                    _ => {}
+                // line-height can be a number, length, percentage or "normal"
+                if let Some(lh) = parse_line_height(std::slice::from_ref(tokens[i])) {
+                    parsed_line_height = Some(lh);
+                    i += 1;
                 }
             }
         }
@@ -2342,16 +3527,10 @@ impl ComputedStyle {
         // STEP 6: Everything remaining is font-family (required).
         // [§ 4](https://www.w3.org/TR/css-fonts-4/#font-prop)
         // "font-family is a required value"
-        let remaining = &tokens[i..];
-        let mut family = None;
-        for tok in remaining {
-            if let ComponentValue::Token(CSSToken::Ident(name) | CSSToken::String(name)) = tok {
-                family = Some(name.clone());
-                break;
-            }
-        }
+        let remaining: Vec<ComponentValue> = tokens[i..].iter().map(|&t| t.clone()).collect();
+        let family = parse_font_family(&remaining);
         if family.is_none() {
-            return; // Missing required font-family
+            return false; // Missing or invalid required font-family
         }
 
         // STEP 7: Apply values.
@@ -2363,16 +3542,16 @@ impl ComputedStyle {
         // what was parsed.
         self.font_style = Some(parsed_style.unwrap_or(FontStyle::Normal));
         self.font_weight = Some(parsed_weight.unwrap_or(400));
-        self.font_size = Some(self.resolve_length(font_size.unwrap()));
-        if let Some(lh) = parsed_line_height {
-            self.line_height = Some(lh);
-        } else {
-            self.line_height = None; // Reset to initial ("normal")
-        }
+        self.font_size = Some(self.resolve_font_size(font_size.unwrap()));
+        // The line height is resolved after the font size so that
+        // percentages and `em` use the size set by this shorthand.
+        self.line_height =
+            Some(parsed_line_height.map_or(LineHeight::Normal, |lh| self.resolve_line_height(lh)));
         self.font_family = family;
+        true
     }
 
-    /// Resolve relative length units (em) to absolute units (px).
+    /// Resolve relative length units (em, rem) to absolute units (px).
     /// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
     fn resolve_length(&self, len: LengthValue) -> LengthValue {
         match len {
@@ -2380,20 +3559,89 @@ impl ComputedStyle {
                 let base = self
                     .font_size
                     .as_ref()
-                    .map_or(DEFAULT_FONT_SIZE_PX, LengthValue::to_px);
+                    .map_or_else(|| self.root_font_size_px(), LengthValue::to_px);
                 LengthValue::Px(em * base)
             }
+            // "Equal to the computed value of font-size on the root element."
+            LengthValue::Rem(rem) => LengthValue::Px(rem * self.root_font_size_px()),
+            // [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+            // Physical units compute to their px equivalent.
+            LengthValue::Cm(_)
+            | LengthValue::Mm(_)
+            | LengthValue::Q(_)
+            | LengthValue::In(_)
+            | LengthValue::Pc(_)
+            | LengthValue::Pt(_) => LengthValue::Px(len.to_px()),
+            LengthValue::Math(function) => {
+                LengthValue::Math(function.map(|arg| self.resolve_length(arg)))
+            }
+            other => other,
+        }
+    }
+
+    /// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+    ///
+    /// "When specified in the font-size property of an element, the
+    /// font-relative lengths refer to the computed font metrics of the
+    /// parent element."
+    ///
+    /// Every other property resolves `em` against this element's own
+    /// font-size, through [`Self::resolve_length`].
+    fn resolve_font_size(&self, len: LengthValue) -> LengthValue {
+        match len {
+            LengthValue::Em(em) => LengthValue::Px(
+                em * self
+                    .parent_font_size
+                    .unwrap_or_else(|| self.root_font_size_px()),
+            ),
+            LengthValue::Math(function) => {
+                LengthValue::Math(function.map(|arg| self.resolve_font_size(arg)))
+            }
+            other => self.resolve_length(other),
+        }
+    }
+
+    /// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+    ///
+    /// "Computed value: the keyword normal or a number or an absolute
+    /// length"
+    fn resolve_line_height(&self, line_height: LineHeight) -> LineHeight {
+        match line_height {
+            LineHeight::Length(length) => LineHeight::Length(self.resolve_length(length)),
             other => other,
         }
     }
 
+    /// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+    ///
+    /// This element's line height in pixels so far. `normal` uses the
+    /// 1.2 ratio the default font metrics use.
+    fn line_height_px(&self) -> f32 {
+        #[allow(clippy::cast_possible_truncation)]
+        let font_size =
+            self.font_size
+                .as_ref()
+                .map_or_else(|| self.root_font_size_px(), LengthValue::to_px) as f32;
+        self.line_height
+            .unwrap_or_default()
+            .to_px(font_size, font_size * 1.2)
+    }
+
+    /// The font size `rem` lengths resolve against, in pixels.
+    ///
+    /// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+    #[must_use]
+    pub fn root_font_size_px(&self) -> f64 {
+        self.root_font_size.unwrap_or(DEFAULT_FONT_SIZE_PX)
+    }
+
     /// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
     ///
     /// Resolve relative length units (em) to absolute units (px) for `AutoLength`.
     /// 'auto' values are preserved unchanged.
     fn resolve_auto_length(&self, al: AutoLength) -> AutoLength {
         match al {
-            AutoLength::Auto => AutoLength::Auto,
+            AutoLength::Auto | AutoLength::Intrinsic(_) => al,
             AutoLength::Length(len) => AutoLength::Length(self.resolve_length(len)),
         }
     }
@@ -2603,6 +3851,63 @@ impl ComputedStyle {
         })
     }
 
+    /// [CSS UI 4 § 5.1 'outline'](https://www.w3.org/TR/css-ui-4/#outline)
+    ///
+    /// Parse the `outline` shorthand. Unlike the border shorthands, an
+    /// omitted style falls back to the initial `none`, so `outline: 2px red`
+    /// draws nothing.
+    fn parse_outline(&self, values: &[ComponentValue]) -> Option<BorderValue> {
+        let mut width = None;
+        let mut style = None;
+        let mut color = None;
+
+        for v in values {
+            if width.is_none()
+                && let Some(len) = parse_single_length(v)
+            {
+                width = Some(self.resolve_length(len));
+            } else if color.is_none()
+                && let Some(c) = parse_single_color(v)
+            {
+                color = Some(c);
+            } else if style.is_none()
+                && let Some(s) = Self::parse_outline_style(v)
+            {
+                style = Some(s);
+            }
+        }
+
+        (width.is_some() || style.is_some() || color.is_some()).then(|| {
+            let initial = self.default_border();
+            BorderValue {
+                width: width.unwrap_or(initial.width),
+                style: style.unwrap_or(initial.style),
+                color: color.unwrap_or(initial.color),
+            }
+        })
+    }
+
+    /// [CSS UI 4 § 5.3 'outline-style'](https://www.w3.org/TR/css-ui-4/#outline-style)
+    ///
+    /// "The outline-style property accepts the same values as border-style,
+    /// except that hidden is not a legal outline style."
+    fn parse_outline_style(v: &ComponentValue) -> Option<String> {
+        if let ComponentValue::Token(CSSToken::Ident(ident)) = v
+            && ident.eq_ignore_ascii_case("auto")
+        {
+            return Some("auto".to_string());
+        }
+        Self::parse_border_style(v).filter(|s| s != "hidden")
+    }
+
+    /// Get or create the `outline` value with its initial longhands.
+    fn ensure_outline(&mut self) -> &mut BorderValue {
+        if self.outline.is_none() {
+            self.outline = Some(self.default_border());
+        }
+        self.outline.as_mut().unwrap()
+    }
+
     /// Parse a border-style keyword.
     fn parse_border_style(v: &ComponentValue) -> Option<String> {
         if let ComponentValue::Token(CSSToken::Ident(ident)) = v {
@@ -2847,7 +4152,7 @@ impl ComputedStyle {
     ///   resulting in an item that receives the specified proportion of the
     ///   free space in the flex container."
     #[allow(clippy::cast_possible_truncation)]
-    fn parse_flex_shorthand(&mut self, values: &[ComponentValue]) {
+    fn parse_flex_shorthand(&mut self, values: &[ComponentValue]) -> bool {
         // Filter whitespace
         let tokens: Vec<&ComponentValue> = values
             .iter()
@@ -2855,7 +4160,7 @@ impl ComputedStyle {
             .collect();
 
         if tokens.is_empty() {
-            return;
+            return false;
         }
 
         // Check for keyword values
@@ -2870,7 +4175,7 @@ impl ComputedStyle {
                     self.flex_grow = Some(0.0);
                     self.flex_shrink = Some(0.0);
                     self.flex_basis = Some(AutoLength::Auto);
-                    return;
+                    return true;
                 }
                 // [§ 7.1.1](https://www.w3.org/TR/css-flexbox-1/#flex-common)
                 //
@@ -2879,7 +4184,7 @@ impl ComputedStyle {
                     self.flex_grow = Some(1.0);
                     self.flex_shrink = Some(1.0);
                     self.flex_basis = Some(AutoLength::Auto);
-                    return;
+                    return true;
                 }
                 // [§ 7.1.1](https://www.w3.org/TR/css-flexbox-1/#flex-common)
                 //
@@ -2888,9 +4193,9 @@ impl ComputedStyle {
                     self.flex_grow = Some(0.0);
                     self.flex_shrink = Some(1.0);
                     self.flex_basis = Some(AutoLength::Auto);
-                    return;
+                    return true;
                 }
-                _ => return,
+                _ => return false,
             }
         }
 
@@ -2909,20 +4214,32 @@ impl ComputedStyle {
                     numbers.push(*value as f32);
                 }
                 ComponentValue::Token(CSSToken::Dimension { .. } | CSSToken::Percentage { .. }) => {
-                    if let Some(auto_len) = parse_single_auto_length(token) {
-                        basis = Some(self.resolve_auto_length(auto_len));
-                    }
+                    let Some(auto_len) = parse_single_auto_length(token) else {
+                        return false;
+                    };
+                    basis = Some(self.resolve_auto_length(auto_len));
                 }
                 ComponentValue::Token(CSSToken::Ident(ident))
                     if ident.eq_ignore_ascii_case("auto") =>
                 {
                     basis = Some(AutoLength::Auto);
                 }
-                _ => {}
+                _ => return false,
             }
         }
 
         match numbers.len() {
+            // flex: <basis>
+            //
+            // "When omitted, [flex-grow] is set to 1."
+            0 => {
+                let Some(basis) = basis else {
+                    return false;
+                };
+                self.flex_grow = Some(1.0);
+                self.flex_shrink = Some(1.0);
+                self.flex_basis = Some(basis);
+            }
             // flex: <grow>  (or flex: <grow> <basis>)
             1 => {
                 self.flex_grow = Some(numbers[0]);
@@ -2947,7 +4264,206 @@ impl ComputedStyle {
                 self.flex_basis = basis
                     .or_else(|| Some(AutoLength::Length(LengthValue::Px(f64::from(numbers[2])))));
             }
-            _ => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// [§ 3 overflow-x, overflow-y](https://www.w3.org/TR/css-overflow-3/#overflow-properties)
+    ///
+    /// Parse one `overflow-x` or `overflow-y` keyword.
+    fn parse_overflow(value: &ComponentValue) -> Option<Overflow> {
+        let ComponentValue::Token(CSSToken::Ident(ident)) = value else {
+            return None;
+        };
+        Some(match ident.to_ascii_lowercase().as_str() {
+            "visible" => Overflow::Visible,
+            "hidden" => Overflow::Hidden,
+            "scroll" => Overflow::Scroll,
+            "auto" => Overflow::Auto,
+            _ => return None,
+        })
+    }
+
+    /// [§ 3.1 'list-style-type'](https://www.w3.org/TR/css-lists-3/#list-style-type)
+    ///
+    /// Parse a `list-style-type` keyword, which also names the counter
+    /// style of `counter()` and `counters()`.
+    fn parse_list_style_type(ident: &str) -> Option<ListStyleType> {
+        Some(match ident.to_ascii_lowercase().as_str() {
+            "disc" => ListStyleType::Disc,
+            "circle" => ListStyleType::Circle,
+            "square" => ListStyleType::Square,
+            "decimal" => ListStyleType::Decimal,
+            "lower-alpha" => ListStyleType::LowerAlpha,
+            "upper-alpha" => ListStyleType::UpperAlpha,
+            "lower-roman" => ListStyleType::LowerRoman,
+            "upper-roman" => ListStyleType::UpperRoman,
+            "none" => ListStyleType::None,
+            _ => return None,
+        })
+    }
+
+    /// [§ 4.2 Creating Counters](https://www.w3.org/TR/css-lists-3/#counter-reset)
+    ///
+    /// Parse a `[ <counter-name> <integer>? ]+ | none` value. A name
+    /// without an integer gets `default_value`. `none` parses to an empty
+    /// list.
+    fn parse_counter_list(
+        values: &[ComponentValue],
+        default_value: i32,
+    ) -> Option<Vec<(String, i32)>> {
+        let mut tokens = values
+            .iter()
+            .filter(|v| !matches!(v, ComponentValue::Token(CSSToken::Whitespace)))
+            .peekable();
+        if let Some(ComponentValue::Token(CSSToken::Ident(ident))) = tokens.peek()
+            && ident.eq_ignore_ascii_case("none")
+        {
+            let _ = tokens.next();
+            return tokens.next().is_none().then(Vec::new);
+        }
+
+        let mut counters = Vec::new();
+        while let Some(token) = tokens.next() {
+            // [§ 4.1 Introduction](https://www.w3.org/TR/css-lists-3/#counters)
+            //
+            // "Counters are referred to in CSS syntax using the <counter-name>
+            // type, which represents their name as a <custom-ident>. A
+            // <counter-name> name cannot match the keyword none."
+            let ComponentValue::Token(CSSToken::Ident(name)) = token else {
+                return None;
+            };
+            if name.eq_ignore_ascii_case("none") {
+                return None;
+            }
+            let value = match tokens.peek() {
+                Some(ComponentValue::Token(CSSToken::Number {
+                    int_value: Some(n), ..
+                })) => {
+                    let n = i32::try_from(*n).unwrap_or(if *n < 0 { i32::MIN } else { i32::MAX });
+                    let _ = tokens.next();
+                    n
+                }
+                _ => default_value,
+            };
+            counters.push((name.clone(), value));
+        }
+        (!counters.is_empty()).then_some(counters)
+    }
+
+    /// [§ 2 'content'](https://www.w3.org/TR/css-content-3/#content-property)
+    ///
+    /// Parse a `<content-list>` of strings, `counter()` and `counters()`.
+    /// Returns `None` if it is empty or has any other item.
+    fn parse_content_list(values: &[ComponentValue]) -> Option<Vec<ContentItem>> {
+        let mut items = Vec::new();
+        for value in values {
+            match value {
+                ComponentValue::Token(CSSToken::String(s)) => {
+                    items.push(ContentItem::String(s.clone()));
+                }
+                ComponentValue::Token(CSSToken::Whitespace) => {}
+                ComponentValue::Function { name, value } => {
+                    items.push(Self::parse_counter_function(name, value)?);
+                }
+                _ => return None,
+            }
+        }
+        (!items.is_empty()).then_some(items)
+    }
+
+    /// [§ 4.8 Counters in Generated Content](https://www.w3.org/TR/css-lists-3/#counter-functions)
+    ///
+    /// ```text
+    /// <counter()>  =  counter( <counter-name>, <counter-style>? )
+    /// <counters()> = counters( <counter-name>, <string>, <counter-style>? )
+    /// ```
+    fn parse_counter_function(function: &str, args: &[ComponentValue]) -> Option<ContentItem> {
+        let args: Vec<Vec<&ComponentValue>> = args
+            .split(|v| matches!(v, ComponentValue::Token(CSSToken::Comma)))
+            .map(|arg| {
+                arg.iter()
+                    .filter(|v| !matches!(v, ComponentValue::Token(CSSToken::Whitespace)))
+                    .collect()
+            })
+            .collect();
+        let ident = |arg: &[&ComponentValue]| match arg {
+            [ComponentValue::Token(CSSToken::Ident(ident))] => Some(ident.clone()),
+            _ => None,
+        };
+        let style = |arg: Option<&Vec<&ComponentValue>>| match arg {
+            None => Some(ListStyleType::Decimal),
+            Some(arg) => Self::parse_list_style_type(&ident(arg)?),
+        };
+
+        if function.eq_ignore_ascii_case("counter") && (1..=2).contains(&args.len()) {
+            Some(ContentItem::Counter {
+                name: ident(&args[0])?,
+                style: style(args.get(1))?,
+            })
+        } else if function.eq_ignore_ascii_case("counters") && (2..=3).contains(&args.len()) {
+            let [ComponentValue::Token(CSSToken::String(separator))] = args[1].as_slice() else {
+                return None;
+            };
+            Some(ContentItem::Counters {
+                name: ident(&args[0])?,
+                separator: separator.clone(),
+                style: style(args.get(2))?,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// [§ 3 'text-decoration-line'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
+    ///
+    /// "Values: none | [ underline || overline || line-through ]"
+    ///
+    /// Other tokens are skipped, so this also picks the line keywords out
+    /// of a `text-decoration` shorthand. `none` clears every line. Returns
+    /// `None` when there is no line keyword at all.
+    fn parse_text_decoration_line(values: &[ComponentValue]) -> Option<TextDecorationLine> {
+        let mut result = TextDecorationLine::default();
+        let mut found_none = false;
+        let mut found_line = false;
+        for val in values {
+            if let ComponentValue::Token(CSSToken::Ident(ident)) = val {
+                match ident.to_ascii_lowercase().as_str() {
+                    "none" => found_none = true,
+                    "underline" => result.underline = true,
+                    "overline" => result.overline = true,
+                    "line-through" => result.line_through = true,
+                    _ => continue,
+                }
+                found_line = true;
+            }
+        }
+        if found_none {
+            Some(TextDecorationLine::default())
+        } else {
+            found_line.then_some(result)
+        }
+    }
+
+    /// [§ 7.2 Ratios](https://www.w3.org/TR/css-values-4/#ratios)
+    ///
+    /// "<ratio> = <number [0,∞]> [ / <number [0,∞]> ]?"
+    ///
+    /// "The second <number> is optional, defaulting to 1."
+    ///
+    /// `tokens` must not contain whitespace.
+    fn parse_ratio(tokens: &[&ComponentValue]) -> Option<(f64, f64)> {
+        let number = |v: &ComponentValue| match v {
+            ComponentValue::Token(CSSToken::Number { value, .. }) if *value >= 0.0 => Some(*value),
+            _ => None,
+        };
+        match tokens {
+            [width] => Some((number(width)?, 1.0)),
+            [width, ComponentValue::Token(CSSToken::Delim('/')), height] => {
+                Some((number(width)?, number(height)?))
+            }
+            _ => None,
         }
     }
 }
@@ -0,0 +1,98 @@
+//! Differences between computed styles
+//!
+//! When styles are recomputed after a DOM mutation, comparing an element's
+//! old and new [`ComputedStyle`] tells the embedder how much work the change
+//! causes: a `color` change only needs the page repainted, while a `width`
+//! change moves boxes and needs a relayout first.
+
+use super::computed::ComputedStyle;
+
+/// How much of the rendering pipeline a style change invalidates.
+///
+/// Ordered by cost, so the damage of several changes is their maximum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StyleDamage {
+    /// Box geometry is unchanged; only the display list must be rebuilt.
+    Repaint,
+    /// Box geometry may change; layout must run again before painting.
+    Relayout,
+}
+
+/// A property whose computed value differs between two styles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChangedProperty {
+    /// The property name, e.g. `"color"`.
+    pub name: &'static str,
+    /// What the change invalidates.
+    pub damage: StyleDamage,
+}
+
+/// Properties that only affect how already laid-out boxes are painted.
+/// Every other property is assumed to affect layout.
+const PAINT_ONLY_PROPERTIES: &[&str] = &[
+    "background-color",
+    "background-image",
+    "background-position",
+    "background-repeat",
+    "background-size",
+    "border-radius",
+    "box-shadow",
+    "color",
+    "cursor",
+    "object-fit",
+    "object-position",
+    "opacity",
+    "outline",
+    "outline-offset",
+    "text-decoration-color",
+    "text-decoration-line",
+    "text-decoration-style",
+    "visibility",
+];
+
+impl ComputedStyle {
+    /// The properties whose computed values differ between `self` and
+    /// `other`. Properties `other` sets come first, in
+    /// [`Self::to_css_string`] order, followed by those only `self` sets.
+    ///
+    /// A property set on only one of the two styles counts as changed.
+    /// Fold the result's [`ChangedProperty::damage`] with `max` to decide
+    /// whether a restyle needs a relayout or just a repaint; an empty
+    /// result needs neither.
+    ///
+    /// NOTE: Values are compared in their serialized form, so custom
+    /// properties are not compared (the properties that reference them
+    /// hold the substituted values), and neither are the `::before` and
+    /// `::after` styles, which can be diffed on their own.
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Vec<ChangedProperty> {
+        let old = self.declarations();
+        let new = other.declarations();
+
+        let removed = old
+            .iter()
+            .map(|(name, _)| *name)
+            .filter(|name| value_of(&new, name).is_none());
+        new.iter()
+            .map(|(name, _)| *name)
+            .chain(removed)
+            .filter(|name| value_of(&old, name) != value_of(&new, name))
+            .map(|name| ChangedProperty {
+                name,
+                damage: if PAINT_ONLY_PROPERTIES.contains(&name) {
+                    StyleDamage::Repaint
+                } else {
+                    StyleDamage::Relayout
+                },
+            })
+            .collect()
+    }
+}
+
+/// The serialized value of `name` in `declarations`, if it is set.
+fn value_of<'a>(declarations: &'a [(&'static str, String)], name: &str) -> Option<&'a str> {
+    declarations
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, value)| value.as_str())
+}
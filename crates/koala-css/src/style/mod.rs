@@ -8,16 +8,22 @@
 //! - [CSS Logical Properties Level 1](https://drafts.csswg.org/css-logical-1/)
 
 pub mod computed;
+mod diff;
 mod display;
+mod serialize;
 pub mod substitute;
 pub(crate) mod values;
-mod writing_mode;
+pub(crate) mod writing_mode;
 
 // Re-export all public types
 pub use computed::ComputedStyle;
+pub use diff::{ChangedProperty, StyleDamage};
 pub use display::{DisplayValue, InnerDisplayType, OuterDisplayType};
 pub use values::{
-    AutoLength, BorderRadius, BorderValue, BoxShadow, ClearSide, ColorValue, DEFAULT_FONT_SIZE_PX,
-    FloatSide, FontStyle, LengthValue, PositionType, TextAlign, TextDecorationLine,
+    AutoLength, BackgroundImage, BackgroundLayer, BackgroundPosition, BackgroundRepeat,
+    BackgroundSize, BorderRadius, BorderValue, BoxShadow, ClearSide, ColorValue,
+    DEFAULT_FONT_SIZE_PX, FloatSide, FontFamily, FontStyle, GenericFontFamily, IntrinsicSize,
+    LengthUnit, LengthValue, LineHeight, MathFunction, MathFunctionKind, PositionType, RepeatStyle,
+    TextAlign, TextDecoration, TextDecorationLine, TextDecorationStyle,
 };
-pub use writing_mode::{PhysicalSide, WritingMode};
+pub use writing_mode::{Direction, PhysicalSide, WritingMode};
@@ -0,0 +1,892 @@
+//! Serialization of computed styles back to CSS text
+//!
+//! [CSSOM § 6.7.2 Serializing CSS Values](https://drafts.csswg.org/cssom/#serializing-css-values)
+//!
+//! Used for debugging output and as the basis for `getComputedStyle()`.
+
+use std::fmt::Write as _;
+
+use super::computed::{
+    AlignItems, AlignSelf, BorderCollapse, CaptionSide, ComputedStyle, ContentItem,
+    ContentVisibility, Cursor, FlexDirection, FlexWrap, GridAutoFlow, GridLine, JustifyContent,
+    ListStyleType, ObjectFit, Overflow, TableLayoutAlgorithm, TextOverflow, TrackList, TrackSize,
+    Visibility, WhiteSpace,
+};
+use super::display::{DisplayValue, InnerDisplayType, OuterDisplayType};
+use super::values::{
+    AutoLength, BackgroundImage, BackgroundPosition, BackgroundRepeat, BackgroundSize,
+    BorderRadius, BorderValue, BoxShadow, ClearSide, ColorValue, FloatSide, FontFamily, FontStyle,
+    GenericFontFamily, LengthValue, LineHeight, PositionType, RepeatStyle, TextAlign,
+    TextDecorationLine, TextDecorationStyle, VerticalAlign,
+};
+use super::writing_mode::{Direction, WritingMode};
+
+impl ComputedStyle {
+    /// Serialize every set property as a `property: value;` declaration.
+    ///
+    /// Properties are emitted in a fixed order (the field order of
+    /// [`ComputedStyle`]), separated by single spaces, so the output is
+    /// stable and can be compared directly in tests. Unset properties are
+    /// omitted. Colors serialize as `rgb()`/`rgba()` and lengths keep
+    /// their specified unit (`Npx`, `Nem`, `N%`, ...).
+    ///
+    /// NOTE: Custom properties are not included; their values are raw
+    /// component values with no computed form.
+    #[must_use]
+    pub fn to_css_string(&self) -> String {
+        self.declarations()
+            .iter()
+            .map(|(name, value)| format!("{name}: {value};"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Every set property as a (name, serialized value) pair, in the
+    /// order [`Self::to_css_string`] emits them.
+    pub(super) fn declarations(&self) -> Vec<(&'static str, String)> {
+        let mut out = Declarations::default();
+
+        if self.display_none {
+            out.push("display", "none");
+        } else if let Some(display) = self.display {
+            out.push("display", display_keyword(display));
+        }
+        if self.writing_mode != WritingMode::HorizontalTb {
+            out.push("writing-mode", writing_mode_keyword(self.writing_mode));
+        }
+        if self.direction == Direction::Rtl {
+            out.push("direction", "rtl");
+        }
+        if let Some(color) = &self.color {
+            out.push("color", color_string(color));
+        }
+        if let Some(families) = &self.font_family {
+            out.push(
+                "font-family",
+                comma_list(families.iter().map(font_family_string)),
+            );
+        }
+        if let Some(size) = self.font_size {
+            out.push("font-size", length_string(size));
+        }
+        if let Some(weight) = self.font_weight {
+            out.push("font-weight", weight);
+        }
+        if let Some(style) = self.font_style {
+            out.push("font-style", font_style_keyword(style));
+        }
+        if let Some(line) = self.text_decoration_line {
+            out.push("text-decoration-line", text_decoration_line_string(line));
+        }
+        if let Some(style) = self.text_decoration_style {
+            out.push(
+                "text-decoration-style",
+                text_decoration_style_keyword(style),
+            );
+        }
+        if let Some(color) = &self.text_decoration_color {
+            out.push("text-decoration-color", color_string(color));
+        }
+        if let Some(line_height) = self.line_height {
+            match line_height {
+                LineHeight::Normal => out.push("line-height", "normal"),
+                LineHeight::Number(factor) => out.push("line-height", factor),
+                LineHeight::Length(length) => out.push("line-height", length_string(length)),
+            }
+        }
+        if let Some(spacing) = self.letter_spacing {
+            out.push("letter-spacing", format!("{spacing}px"));
+        }
+        if let Some(align) = self.text_align {
+            out.push("text-align", text_align_keyword(align));
+        }
+        if let Some(color) = &self.background_color {
+            out.push("background-color", color_string(color));
+        }
+        if let Some(images) = &self.background_image {
+            out.push(
+                "background-image",
+                comma_list(images.iter().map(background_image_string)),
+            );
+        }
+        if let Some(repeats) = &self.background_repeat {
+            out.push(
+                "background-repeat",
+                comma_list(repeats.iter().copied().map(background_repeat_string)),
+            );
+        }
+        if let Some(positions) = &self.background_position {
+            out.push(
+                "background-position",
+                comma_list(positions.iter().copied().map(background_position_string)),
+            );
+        }
+        if let Some(sizes) = &self.background_size {
+            out.push(
+                "background-size",
+                comma_list(sizes.iter().copied().map(background_size_string)),
+            );
+        }
+
+        out.push_auto_length("margin-top", self.margin_top);
+        out.push_auto_length("margin-right", self.margin_right);
+        out.push_auto_length("margin-bottom", self.margin_bottom);
+        out.push_auto_length("margin-left", self.margin_left);
+        out.push_auto_length("margin-block-start", self.margin_block_start);
+        out.push_auto_length("margin-block-end", self.margin_block_end);
+        out.push_length("padding-top", self.padding_top);
+        out.push_length("padding-right", self.padding_right);
+        out.push_length("padding-bottom", self.padding_bottom);
+        out.push_length("padding-left", self.padding_left);
+        out.push_border("border-top", self.border_top.as_ref());
+        out.push_border("border-right", self.border_right.as_ref());
+        out.push_border("border-bottom", self.border_bottom.as_ref());
+        out.push_border("border-left", self.border_left.as_ref());
+        out.push_auto_length("width", self.width);
+        out.push_auto_length("height", self.height);
+        out.push_length("min-width", self.min_width);
+        out.push_length("max-width", self.max_width);
+        out.push_length("min-height", self.min_height);
+        out.push_length("max-height", self.max_height);
+        if let Some((width, height)) = self.aspect_ratio {
+            out.push("aspect-ratio", format!("{width} / {height}"));
+        }
+
+        if let Some(direction) = self.flex_direction {
+            out.push("flex-direction", flex_direction_keyword(direction));
+        }
+        if let Some(justify) = self.justify_content {
+            out.push("justify-content", justify_content_keyword(justify));
+        }
+        if let Some(align) = self.align_items {
+            out.push("align-items", align_items_keyword(align));
+        }
+        if let Some(align) = self.align_self {
+            out.push("align-self", align_self_keyword(align));
+        }
+        if let Some(grow) = self.flex_grow {
+            out.push("flex-grow", grow);
+        }
+        if let Some(shrink) = self.flex_shrink {
+            out.push("flex-shrink", shrink);
+        }
+        out.push_auto_length("flex-basis", self.flex_basis);
+        if let Some(wrap) = self.flex_wrap {
+            out.push("flex-wrap", flex_wrap_keyword(wrap));
+        }
+
+        if let Some(tracks) = &self.grid_template_columns {
+            out.push("grid-template-columns", track_list_string(tracks));
+        }
+        if let Some(tracks) = &self.grid_template_rows {
+            out.push("grid-template-rows", track_list_string(tracks));
+        }
+        if let Some(flow) = self.grid_auto_flow {
+            out.push("grid-auto-flow", grid_auto_flow_keyword(flow));
+        }
+        out.push_length("row-gap", self.row_gap);
+        out.push_length("column-gap", self.column_gap);
+        out.push_grid_line("grid-column-start", self.grid_column_start);
+        out.push_grid_line("grid-column-end", self.grid_column_end);
+        out.push_grid_line("grid-row-start", self.grid_row_start);
+        out.push_grid_line("grid-row-end", self.grid_row_end);
+
+        if let Some(position) = self.position {
+            out.push("position", position_keyword(position));
+        }
+        out.push_auto_length("top", self.top);
+        out.push_auto_length("right", self.right);
+        out.push_auto_length("bottom", self.bottom);
+        out.push_auto_length("left", self.left);
+        if let Some(float) = self.float {
+            out.push("float", float_keyword(float));
+        }
+        if let Some(clear) = self.clear {
+            out.push("clear", clear_keyword(clear));
+        }
+        if let Some(list_style) = self.list_style_type {
+            out.push("list-style-type", list_style_type_keyword(list_style));
+        }
+        if let Some(overflow) = self.overflow_x {
+            out.push("overflow-x", overflow_keyword(overflow));
+        }
+        if let Some(overflow) = self.overflow_y {
+            out.push("overflow-y", overflow_keyword(overflow));
+        }
+        if let Some(border_box) = self.box_sizing_border_box {
+            out.push(
+                "box-sizing",
+                if border_box {
+                    "border-box"
+                } else {
+                    "content-box"
+                },
+            );
+        }
+        if let Some(fit) = self.object_fit {
+            out.push("object-fit", object_fit_keyword(fit));
+        }
+        if let Some(position) = self.object_position {
+            out.push("object-position", background_position_string(position));
+        }
+        if let Some(white_space) = self.white_space {
+            out.push("white-space", white_space_keyword(white_space));
+        }
+        if let Some(tab_size) = self.tab_size {
+            out.push("tab-size", tab_size);
+        }
+        if let Some(text_overflow) = self.text_overflow {
+            out.push(
+                "text-overflow",
+                match text_overflow {
+                    TextOverflow::Clip => "clip",
+                    TextOverflow::Ellipsis => "ellipsis",
+                },
+            );
+        }
+        if let Some(align) = self.vertical_align {
+            out.push(
+                "vertical-align",
+                match align {
+                    VerticalAlign::Baseline => "baseline".to_string(),
+                    VerticalAlign::Middle => "middle".to_string(),
+                    VerticalAlign::Sub => "sub".to_string(),
+                    VerticalAlign::Super => "super".to_string(),
+                    VerticalAlign::TextTop => "text-top".to_string(),
+                    VerticalAlign::TextBottom => "text-bottom".to_string(),
+                    VerticalAlign::Top => "top".to_string(),
+                    VerticalAlign::Bottom => "bottom".to_string(),
+                    VerticalAlign::Length(px) => format!("{px}px"),
+                },
+            );
+        }
+        if let Some(caption_side) = self.caption_side {
+            out.push(
+                "caption-side",
+                match caption_side {
+                    CaptionSide::Top => "top",
+                    CaptionSide::Bottom => "bottom",
+                },
+            );
+        }
+        if let Some(border_collapse) = self.border_collapse {
+            out.push(
+                "border-collapse",
+                match border_collapse {
+                    BorderCollapse::Separate => "separate",
+                    BorderCollapse::Collapse => "collapse",
+                },
+            );
+        }
+        if let Some((horizontal, vertical)) = self.border_spacing {
+            out.push(
+                "border-spacing",
+                format!("{} {}", length_string(horizontal), length_string(vertical)),
+            );
+        }
+        if let Some(table_layout) = self.table_layout {
+            out.push(
+                "table-layout",
+                match table_layout {
+                    TableLayoutAlgorithm::Auto => "auto",
+                    TableLayoutAlgorithm::Fixed => "fixed",
+                },
+            );
+        }
+        if let Some(visibility) = self.visibility {
+            out.push("visibility", visibility_keyword(visibility));
+        }
+        if let Some(content_visibility) = self.content_visibility {
+            out.push(
+                "content-visibility",
+                match content_visibility {
+                    ContentVisibility::Visible => "visible",
+                    ContentVisibility::Auto => "auto",
+                    ContentVisibility::Hidden => "hidden",
+                },
+            );
+        }
+        if let Some((width, height)) = self.contain_intrinsic_size {
+            out.push(
+                "contain-intrinsic-size",
+                format!("{} {}", length_string(width), length_string(height)),
+            );
+        }
+        if let Some(cursor) = self.cursor {
+            out.push("cursor", cursor_keyword(cursor));
+        }
+        if let Some(opacity) = self.opacity {
+            out.push("opacity", opacity);
+        }
+        if let Some(shadows) = &self.box_shadow {
+            out.push("box-shadow", box_shadow_list_string(shadows));
+        }
+        if let Some(radius) = self.border_radius {
+            out.push("border-radius", border_radius_string(radius));
+        }
+        out.push_border("outline", self.outline.as_ref());
+        out.push_length("outline-offset", self.outline_offset);
+        if let Some(content) = &self.content {
+            out.push("content", content_list_string(content));
+        }
+        if let Some(counters) = &self.counter_reset {
+            out.push("counter-reset", counter_list_string(counters));
+        }
+        if let Some(counters) = &self.counter_increment {
+            out.push("counter-increment", counter_list_string(counters));
+        }
+
+        out.entries
+    }
+}
+
+/// Accumulates serialized `(property, value)` declarations.
+#[derive(Default)]
+struct Declarations {
+    entries: Vec<(&'static str, String)>,
+}
+
+impl Declarations {
+    fn push(&mut self, name: &'static str, value: impl std::fmt::Display) {
+        self.entries.push((name, value.to_string()));
+    }
+
+    fn push_length(&mut self, name: &'static str, value: Option<LengthValue>) {
+        if let Some(value) = value {
+            self.push(name, length_string(value));
+        }
+    }
+
+    fn push_auto_length(&mut self, name: &'static str, value: Option<AutoLength>) {
+        if let Some(value) = value {
+            self.push(name, auto_length_string(value));
+        }
+    }
+
+    fn push_border(&mut self, name: &'static str, value: Option<&BorderValue>) {
+        if let Some(border) = value {
+            self.push(
+                name,
+                format!(
+                    "{} {} {}",
+                    length_string(border.width),
+                    border.style,
+                    color_string(&border.color)
+                ),
+            );
+        }
+    }
+
+    fn push_grid_line(&mut self, name: &'static str, value: Option<GridLine>) {
+        if let Some(line) = value {
+            self.push(name, grid_line_string(line));
+        }
+    }
+}
+
+/// [CSS Color 4 § 15.2 Serializing sRGB values](https://www.w3.org/TR/css-color-4/#serializing-sRGB-values)
+///
+/// "If the alpha is exactly 1, the `rgb()` form is used ... otherwise the
+/// `rgba()` form is used."
+fn color_string(color: &ColorValue) -> String {
+    if color.a == 255 {
+        format!("rgb({}, {}, {})", color.r, color.g, color.b)
+    } else {
+        // NOTE: Alpha is stored as a byte; three decimal places are
+        // enough to round-trip every 0-255 value.
+        let alpha = (f64::from(color.a) / 255.0 * 1000.0).round() / 1000.0;
+        format!("rgba({}, {}, {}, {alpha})", color.r, color.g, color.b)
+    }
+}
+
+/// [CSSOM § 6.7.2](https://drafts.csswg.org/cssom/#serializing-css-values)
+///
+/// "`<length>`: The `<number>` component serialized as per `<number>`
+/// followed by the unit in its canonical form as defined in its respective
+/// specification."
+fn length_string(length: LengthValue) -> String {
+    match length {
+        LengthValue::Px(v) => format!("{v}px"),
+        LengthValue::Em(v) => format!("{v}em"),
+        LengthValue::Rem(v) => format!("{v}rem"),
+        LengthValue::Vw(v) => format!("{v}vw"),
+        LengthValue::Vh(v) => format!("{v}vh"),
+        LengthValue::Vmin(v) => format!("{v}vmin"),
+        LengthValue::Vmax(v) => format!("{v}vmax"),
+        LengthValue::Percent(v) => format!("{v}%"),
+        LengthValue::Ch(v) => format!("{v}ch"),
+        LengthValue::Cm(v) => format!("{v}cm"),
+        LengthValue::Mm(v) => format!("{v}mm"),
+        LengthValue::Q(v) => format!("{v}Q"),
+        LengthValue::In(v) => format!("{v}in"),
+        LengthValue::Pc(v) => format!("{v}pc"),
+        LengthValue::Pt(v) => format!("{v}pt"),
+        LengthValue::Math(function) => {
+            let args: Vec<String> = function.args().map(length_string).collect();
+            format!("{}({})", function.kind.name(), args.join(", "))
+        }
+    }
+}
+
+fn auto_length_string(length: AutoLength) -> String {
+    match length {
+        AutoLength::Auto => "auto".to_string(),
+        AutoLength::Length(length) => length_string(length),
+        AutoLength::Intrinsic(size) => size.keyword().to_string(),
+    }
+}
+
+fn comma_list(items: impl Iterator<Item = String>) -> String {
+    items.collect::<Vec<_>>().join(", ")
+}
+
+/// [CSS Display 3 § 2.1](https://www.w3.org/TR/css-display-3/#outer-role)
+///
+/// Prefer the single-keyword form where one exists, falling back to the
+/// two-value syntax otherwise.
+const fn display_keyword(display: DisplayValue) -> &'static str {
+    match (display.outer, display.inner) {
+        (OuterDisplayType::Block, InnerDisplayType::Flow) => "block",
+        (OuterDisplayType::Block, InnerDisplayType::FlowRoot) => "flow-root",
+        (OuterDisplayType::Block, InnerDisplayType::Table) => "table",
+        (OuterDisplayType::Block, InnerDisplayType::Flex) => "flex",
+        (OuterDisplayType::Block, InnerDisplayType::Grid) => "grid",
+        (OuterDisplayType::Inline, InnerDisplayType::Flow) => "inline",
+        (OuterDisplayType::Inline, InnerDisplayType::FlowRoot) => "inline-block",
+        (OuterDisplayType::Inline, InnerDisplayType::Table) => "inline-table",
+        (OuterDisplayType::Inline, InnerDisplayType::Flex) => "inline-flex",
+        (OuterDisplayType::Inline, InnerDisplayType::Grid) => "inline-grid",
+        (OuterDisplayType::RunIn, InnerDisplayType::Flow) => "run-in",
+        (OuterDisplayType::RunIn, InnerDisplayType::FlowRoot) => "run-in flow-root",
+        (OuterDisplayType::RunIn, InnerDisplayType::Table) => "run-in table",
+        (OuterDisplayType::RunIn, InnerDisplayType::Flex) => "run-in flex",
+        (OuterDisplayType::RunIn, InnerDisplayType::Grid) => "run-in grid",
+        (OuterDisplayType::ListItem, _) => "list-item",
+    }
+}
+
+const fn writing_mode_keyword(mode: WritingMode) -> &'static str {
+    match mode {
+        WritingMode::HorizontalTb => "horizontal-tb",
+        WritingMode::VerticalRl => "vertical-rl",
+        WritingMode::VerticalLr => "vertical-lr",
+    }
+}
+
+const fn font_style_keyword(style: FontStyle) -> &'static str {
+    match style {
+        FontStyle::Normal => "normal",
+        FontStyle::Italic => "italic",
+        FontStyle::Oblique => "oblique",
+    }
+}
+
+fn text_decoration_line_string(line: TextDecorationLine) -> String {
+    let mut parts = Vec::new();
+    if line.underline {
+        parts.push("underline");
+    }
+    if line.overline {
+        parts.push("overline");
+    }
+    if line.line_through {
+        parts.push("line-through");
+    }
+    if parts.is_empty() {
+        "none".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+const fn text_decoration_style_keyword(style: TextDecorationStyle) -> &'static str {
+    match style {
+        TextDecorationStyle::Solid => "solid",
+        TextDecorationStyle::Double => "double",
+        TextDecorationStyle::Dotted => "dotted",
+        TextDecorationStyle::Dashed => "dashed",
+        TextDecorationStyle::Wavy => "wavy",
+    }
+}
+
+const fn text_align_keyword(align: TextAlign) -> &'static str {
+    match align {
+        TextAlign::Left => "left",
+        TextAlign::Right => "right",
+        TextAlign::Center => "center",
+        TextAlign::Justify => "justify",
+    }
+}
+
+/// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+///
+/// A family name is written as a single identifier when it reads back as
+/// the same name, and as a string otherwise, so names with spaces and
+/// names that collide with a generic keyword stay quoted.
+fn font_family_string(family: &FontFamily) -> String {
+    match family {
+        FontFamily::Generic(generic) => generic.keyword().to_string(),
+        FontFamily::Named(name) => {
+            let is_ident = name
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if is_ident && GenericFontFamily::from_keyword(name).is_none() {
+                name.clone()
+            } else {
+                string_literal(name)
+            }
+        }
+    }
+}
+
+/// [CSSOM § 2.1 serialize a string](https://drafts.csswg.org/cssom/#serialize-a-string)
+///
+/// "To serialize a string means to create a string represented by '"'
+/// (U+0022), followed by the result of applying the rules below to each
+/// character of the given string, followed by '"' (U+0022)"
+fn string_literal(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            // "If the character is NULL (U+0000), then the REPLACEMENT
+            // CHARACTER (U+FFFD)."
+            '\0' => out.push('\u{FFFD}'),
+            // "If the character is '"' (U+0022) or '\' (U+005C), the
+            // escaped character."
+            '"' | '\\' => {
+                out.push('\\');
+                out.push(c);
+            }
+            // "If the character is in the range [\1-\1f] (U+0001 to
+            // U+001F) or is U+007F, the character escaped as code point."
+            '\u{1}'..='\u{1f}' | '\u{7f}' => {
+                let _ = write!(out, "\\{:x} ", u32::from(c));
+            }
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn background_image_string(image: &BackgroundImage) -> String {
+    match image {
+        BackgroundImage::None => "none".to_string(),
+        BackgroundImage::Url(url) => format!("url(\"{url}\")"),
+    }
+}
+
+const fn repeat_style_keyword(style: RepeatStyle) -> &'static str {
+    match style {
+        RepeatStyle::Repeat => "repeat",
+        RepeatStyle::Space => "space",
+        RepeatStyle::Round => "round",
+        RepeatStyle::NoRepeat => "no-repeat",
+    }
+}
+
+fn background_repeat_string(repeat: BackgroundRepeat) -> String {
+    match (repeat.x, repeat.y) {
+        (RepeatStyle::Repeat, RepeatStyle::NoRepeat) => "repeat-x".to_string(),
+        (RepeatStyle::NoRepeat, RepeatStyle::Repeat) => "repeat-y".to_string(),
+        (x, y) if x == y => repeat_style_keyword(x).to_string(),
+        (x, y) => format!("{} {}", repeat_style_keyword(x), repeat_style_keyword(y)),
+    }
+}
+
+fn background_position_string(position: BackgroundPosition) -> String {
+    format!(
+        "{} {}",
+        length_string(position.x),
+        length_string(position.y)
+    )
+}
+
+fn background_size_string(size: BackgroundSize) -> String {
+    match size {
+        BackgroundSize::Contain => "contain".to_string(),
+        BackgroundSize::Cover => "cover".to_string(),
+        BackgroundSize::Explicit { width, height } => format!(
+            "{} {}",
+            auto_length_string(width),
+            auto_length_string(height)
+        ),
+    }
+}
+
+const fn flex_direction_keyword(direction: FlexDirection) -> &'static str {
+    match direction {
+        FlexDirection::Row => "row",
+        FlexDirection::RowReverse => "row-reverse",
+        FlexDirection::Column => "column",
+        FlexDirection::ColumnReverse => "column-reverse",
+    }
+}
+
+const fn justify_content_keyword(justify: JustifyContent) -> &'static str {
+    match justify {
+        JustifyContent::FlexStart => "flex-start",
+        JustifyContent::FlexEnd => "flex-end",
+        JustifyContent::Center => "center",
+        JustifyContent::SpaceBetween => "space-between",
+        JustifyContent::SpaceAround => "space-around",
+    }
+}
+
+const fn align_items_keyword(align: AlignItems) -> &'static str {
+    match align {
+        AlignItems::FlexStart => "flex-start",
+        AlignItems::FlexEnd => "flex-end",
+        AlignItems::Center => "center",
+        AlignItems::Baseline => "baseline",
+        AlignItems::Stretch => "stretch",
+    }
+}
+
+const fn align_self_keyword(align: AlignSelf) -> &'static str {
+    match align {
+        AlignSelf::Auto => "auto",
+        AlignSelf::FlexStart => "flex-start",
+        AlignSelf::FlexEnd => "flex-end",
+        AlignSelf::Center => "center",
+        AlignSelf::Baseline => "baseline",
+        AlignSelf::Stretch => "stretch",
+    }
+}
+
+const fn flex_wrap_keyword(wrap: FlexWrap) -> &'static str {
+    match wrap {
+        FlexWrap::Nowrap => "nowrap",
+        FlexWrap::Wrap => "wrap",
+        FlexWrap::WrapReverse => "wrap-reverse",
+    }
+}
+
+fn track_list_string(tracks: &TrackList) -> String {
+    if tracks.sizes.is_empty() {
+        return "none".to_string();
+    }
+    tracks
+        .sizes
+        .iter()
+        .map(|size| match size {
+            TrackSize::Fixed(px) => format!("{px}px"),
+            TrackSize::Fr(fr) => format!("{fr}fr"),
+            TrackSize::Auto => "auto".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const fn grid_auto_flow_keyword(flow: GridAutoFlow) -> &'static str {
+    match flow {
+        GridAutoFlow::Row => "row",
+        GridAutoFlow::Column => "column",
+    }
+}
+
+fn grid_line_string(line: GridLine) -> String {
+    match line {
+        GridLine::Auto => "auto".to_string(),
+        GridLine::Line(n) => n.to_string(),
+        GridLine::Span(n) => format!("span {n}"),
+    }
+}
+
+const fn position_keyword(position: PositionType) -> &'static str {
+    match position {
+        PositionType::Static => "static",
+        PositionType::Relative => "relative",
+        PositionType::Absolute => "absolute",
+        PositionType::Fixed => "fixed",
+        PositionType::Sticky => "sticky",
+    }
+}
+
+const fn float_keyword(float: FloatSide) -> &'static str {
+    match float {
+        FloatSide::Left => "left",
+        FloatSide::Right => "right",
+    }
+}
+
+const fn clear_keyword(clear: ClearSide) -> &'static str {
+    match clear {
+        ClearSide::Left => "left",
+        ClearSide::Right => "right",
+        ClearSide::Both => "both",
+    }
+}
+
+/// [§ 2 'content'](https://www.w3.org/TR/css-content-3/#content-property)
+///
+/// Items are separated by single spaces. A counter function omits its
+/// counter style when it is the default `decimal`.
+fn content_list_string(items: &[ContentItem]) -> String {
+    let style_suffix = |style: ListStyleType| {
+        if style == ListStyleType::Decimal {
+            String::new()
+        } else {
+            format!(", {}", list_style_type_keyword(style))
+        }
+    };
+    let items: Vec<String> = items
+        .iter()
+        .map(|item| match item {
+            ContentItem::String(s) => string_literal(s),
+            ContentItem::Counter { name, style } => {
+                format!("counter({name}{})", style_suffix(*style))
+            }
+            ContentItem::Counters {
+                name,
+                separator,
+                style,
+            } => format!(
+                "counters({name}, {}{})",
+                string_literal(separator),
+                style_suffix(*style)
+            ),
+        })
+        .collect();
+    items.join(" ")
+}
+
+/// [§ 4.2 Creating Counters](https://www.w3.org/TR/css-lists-3/#counter-reset)
+///
+/// `name value` pairs separated by spaces, or `none` for an empty list.
+fn counter_list_string(counters: &[(String, i32)]) -> String {
+    if counters.is_empty() {
+        return "none".to_string();
+    }
+    let pairs: Vec<String> = counters
+        .iter()
+        .map(|(name, value)| format!("{name} {value}"))
+        .collect();
+    pairs.join(" ")
+}
+
+const fn list_style_type_keyword(list_style: ListStyleType) -> &'static str {
+    match list_style {
+        ListStyleType::Disc => "disc",
+        ListStyleType::Circle => "circle",
+        ListStyleType::Square => "square",
+        ListStyleType::Decimal => "decimal",
+        ListStyleType::LowerAlpha => "lower-alpha",
+        ListStyleType::UpperAlpha => "upper-alpha",
+        ListStyleType::LowerRoman => "lower-roman",
+        ListStyleType::UpperRoman => "upper-roman",
+        ListStyleType::None => "none",
+    }
+}
+
+const fn overflow_keyword(overflow: Overflow) -> &'static str {
+    match overflow {
+        Overflow::Visible => "visible",
+        Overflow::Hidden => "hidden",
+        Overflow::Scroll => "scroll",
+        Overflow::Auto => "auto",
+    }
+}
+
+const fn object_fit_keyword(fit: ObjectFit) -> &'static str {
+    match fit {
+        ObjectFit::Fill => "fill",
+        ObjectFit::Contain => "contain",
+        ObjectFit::Cover => "cover",
+        ObjectFit::None => "none",
+        ObjectFit::ScaleDown => "scale-down",
+    }
+}
+
+const fn white_space_keyword(white_space: WhiteSpace) -> &'static str {
+    match white_space {
+        WhiteSpace::Normal => "normal",
+        WhiteSpace::Pre => "pre",
+        WhiteSpace::Nowrap => "nowrap",
+        WhiteSpace::PreWrap => "pre-wrap",
+        WhiteSpace::PreLine => "pre-line",
+    }
+}
+
+const fn visibility_keyword(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Visible => "visible",
+        Visibility::Hidden => "hidden",
+        Visibility::Collapse => "collapse",
+    }
+}
+
+const fn cursor_keyword(cursor: Cursor) -> &'static str {
+    match cursor {
+        Cursor::Auto => "auto",
+        Cursor::Default => "default",
+        Cursor::None => "none",
+        Cursor::ContextMenu => "context-menu",
+        Cursor::Help => "help",
+        Cursor::Pointer => "pointer",
+        Cursor::Progress => "progress",
+        Cursor::Wait => "wait",
+        Cursor::Cell => "cell",
+        Cursor::Crosshair => "crosshair",
+        Cursor::Text => "text",
+        Cursor::VerticalText => "vertical-text",
+        Cursor::Alias => "alias",
+        Cursor::Copy => "copy",
+        Cursor::Move => "move",
+        Cursor::NoDrop => "no-drop",
+        Cursor::NotAllowed => "not-allowed",
+        Cursor::Grab => "grab",
+        Cursor::Grabbing => "grabbing",
+        Cursor::EResize => "e-resize",
+        Cursor::NResize => "n-resize",
+        Cursor::NeResize => "ne-resize",
+        Cursor::NwResize => "nw-resize",
+        Cursor::SResize => "s-resize",
+        Cursor::SeResize => "se-resize",
+        Cursor::SwResize => "sw-resize",
+        Cursor::WResize => "w-resize",
+        Cursor::EwResize => "ew-resize",
+        Cursor::NsResize => "ns-resize",
+        Cursor::NeswResize => "nesw-resize",
+        Cursor::NwseResize => "nwse-resize",
+        Cursor::ColResize => "col-resize",
+        Cursor::RowResize => "row-resize",
+        Cursor::AllScroll => "all-scroll",
+        Cursor::ZoomIn => "zoom-in",
+        Cursor::ZoomOut => "zoom-out",
+    }
+}
+
+fn box_shadow_list_string(shadows: &[BoxShadow]) -> String {
+    if shadows.is_empty() {
+        return "none".to_string();
+    }
+    comma_list(shadows.iter().map(|shadow| {
+        let mut text = String::new();
+        if shadow.inset {
+            text.push_str("inset ");
+        }
+        let _ = write!(
+            text,
+            "{}px {}px {}px {}px {}",
+            shadow.offset_x,
+            shadow.offset_y,
+            shadow.blur_radius,
+            shadow.spread_radius,
+            color_string(&shadow.color)
+        );
+        text
+    }))
+}
+
+fn border_radius_string(radius: BorderRadius) -> String {
+    format!(
+        "{}px {}px {}px {}px",
+        radius.top_left, radius.top_right, radius.bottom_right, radius.bottom_left
+    )
+}
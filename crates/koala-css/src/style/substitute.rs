@@ -99,9 +99,18 @@ pub fn substitute_var(
 
                 if let Some(prop_value) = custom_properties.get(&prop_name) {
                     // Step 2: Custom property exists — substitute its value.
-                    // The value is already resolved (var() substituted) at this
-                    // point for custom-property-to-custom-property references.
-                    result.extend(prop_value.iter().cloned());
+                    // Inherited values are already resolved, but a value
+                    // declared on the same element may still reference a
+                    // sibling custom property that hasn't been resolved yet
+                    // (resolution order follows map iteration), so resolve
+                    // any remaining var() here. The depth limit still
+                    // catches cycles.
+                    if contains_var(prop_value) {
+                        let resolved = substitute_var(prop_value, custom_properties, depth + 1)?;
+                        result.extend(resolved);
+                    } else {
+                        result.extend(prop_value.iter().cloned());
+                    }
                 } else if let Some(fb) = fallback {
                     // Step 3: Use fallback value, substituting any var() in it.
                     let resolved_fallback =
@@ -180,14 +189,20 @@ fn parse_var_arguments(
     }
 
     // Build fallback from everything after the first comma.
+    //
+    // Whitespace around the fallback is insignificant (`var(--a, red )`
+    // falls back to `red`), so trim both ends. Leaving a trailing
+    // whitespace token in place would make single-token value parsers
+    // (colors, keywords) reject the substituted value.
     let fallback = comma_idx.map(|ci| {
-        let fb: Vec<ComponentValue> = args[ci + 1..].to_vec();
-        // Trim leading whitespace from the fallback.
-        let start = fb
+        let fb = &args[ci + 1..];
+        let is_ws = |cv: &ComponentValue| matches!(cv, ComponentValue::Token(CSSToken::Whitespace));
+        let start = fb.iter().position(|cv| !is_ws(cv)).unwrap_or(fb.len());
+        let end = fb
             .iter()
-            .position(|cv| !matches!(cv, ComponentValue::Token(CSSToken::Whitespace)))
-            .unwrap_or(fb.len());
-        fb[start..].to_vec()
+            .rposition(|cv| !is_ws(cv))
+            .map_or(start, |i| i + 1);
+        fb[start..end].to_vec()
     });
 
     (prop_name, fallback)
@@ -304,6 +319,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_substitute_fallback_trims_whitespace() {
+        // var( --missing , blue ) → blue
+        let values = vec![var_fn(vec![
+            ws(),
+            ident("--missing"),
+            ws(),
+            comma(),
+            ws(),
+            ident("blue"),
+            ws(),
+        ])];
+        let props = HashMap::new();
+
+        let result = substitute_var(&values, &props, 0);
+        assert_eq!(result, Some(vec![ident("blue")]));
+    }
+
+    #[test]
+    fn test_substitute_two_level_fallback() {
+        // var(--a, var(--b, red)) with neither defined → red
+        let values = vec![var_fn(vec![
+            ident("--a"),
+            comma(),
+            ws(),
+            var_fn(vec![ident("--b"), comma(), ws(), ident("red"), ws()]),
+            ws(),
+        ])];
+        let props = HashMap::new();
+
+        let result = substitute_var(&values, &props, 0);
+        assert_eq!(result, Some(vec![ident("red")]));
+    }
+
+    #[test]
+    fn test_substitute_unresolved_property_value() {
+        // --a: var(--b) not yet resolved on this element; --b: green
+        let values = vec![var_fn(vec![ident("--a")])];
+        let mut props = HashMap::new();
+        let _ = props.insert("--a".to_string(), vec![var_fn(vec![ident("--b")])]);
+        let _ = props.insert("--b".to_string(), vec![ident("green")]);
+
+        let result = substitute_var(&values, &props, 0);
+        assert_eq!(result, Some(vec![ident("green")]));
+    }
+
+    #[test]
+    fn test_substitute_cycle_is_invalid() {
+        // --a: var(--b); --b: var(--a) → invalid
+        let values = vec![var_fn(vec![ident("--a")])];
+        let mut props = HashMap::new();
+        let _ = props.insert("--a".to_string(), vec![var_fn(vec![ident("--b")])]);
+        let _ = props.insert("--b".to_string(), vec![var_fn(vec![ident("--a")])]);
+
+        let result = substitute_var(&values, &props, 0);
+        assert_eq!(result, None);
+    }
+
     #[test]
     fn test_substitute_missing_no_fallback() {
         // var(--missing) → None (invalid at computed-value time)
@@ -0,0 +1,459 @@
+//! CSS Background values
+//!
+//! [CSS Backgrounds and Borders Level 3](https://www.w3.org/TR/css-backgrounds-3/)
+
+use serde::Serialize;
+
+use super::color::{ColorValue, parse_single_color};
+use super::helpers::url_value;
+use super::length::{AutoLength, LengthValue, parse_single_auto_length, parse_single_length};
+use crate::parser::ComponentValue;
+use crate::tokenizer::CSSToken;
+
+/// [§ 3.3 'background-image'](https://www.w3.org/TR/css-backgrounds-3/#background-image)
+///
+/// "Value: `<bg-image>`#"
+/// "`<bg-image>` = none | `<image>`"
+///
+/// Only `url()` images are supported; gradients are not.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub enum BackgroundImage {
+    /// "A value of 'none' counts as an image layer but draws nothing."
+    #[default]
+    None,
+    /// An image referenced by `url()`, stored unresolved.
+    Url(String),
+}
+
+/// [§ 3.4 'background-repeat'](https://www.w3.org/TR/css-backgrounds-3/#background-repeat)
+///
+/// "`<repeat-style>` = repeat-x | repeat-y | [repeat | space | round | no-repeat]{1,2}"
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum RepeatStyle {
+    /// "The image is repeated in this direction as often as needed to cover
+    /// the background painting area."
+    #[default]
+    Repeat,
+    /// "The image is repeated as often as will fit within the background
+    /// positioning area without being clipped and then the images are spaced
+    /// out to fill the area."
+    Space,
+    /// "The image is repeated as often as will fit within the background
+    /// positioning area. If it doesn't fit a whole number of times, it is
+    /// rescaled so that it does."
+    Round,
+    /// "The image is placed once and not repeated in this direction."
+    NoRepeat,
+}
+
+/// [§ 3.4 'background-repeat'](https://www.w3.org/TR/css-backgrounds-3/#background-repeat)
+///
+/// "If a `<repeat-style>` value has two keywords, the first one is for the
+/// horizontal direction, the second for the vertical one."
+///
+/// Initial: repeat (in both directions)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct BackgroundRepeat {
+    /// Horizontal repeat style.
+    pub x: RepeatStyle,
+    /// Vertical repeat style.
+    pub y: RepeatStyle,
+}
+
+/// [§ 3.6 'background-position'](https://www.w3.org/TR/css-backgrounds-3/#background-position)
+///
+/// Keywords are stored as their percentage equivalents: "'left' computes to
+/// 0%, 'center' to 50%, 'right' to 100%" (and likewise for top/bottom).
+///
+/// Initial: 0% 0%
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BackgroundPosition {
+    /// Horizontal offset within the background positioning area.
+    pub x: LengthValue,
+    /// Vertical offset within the background positioning area.
+    pub y: LengthValue,
+}
+
+impl BackgroundPosition {
+    /// `center`, i.e. `50% 50%`. The initial value of 'object-position'.
+    pub const CENTER: Self = Self {
+        x: LengthValue::Percent(50.0),
+        y: LengthValue::Percent(50.0),
+    };
+}
+
+impl Default for BackgroundPosition {
+    fn default() -> Self {
+        Self {
+            x: LengthValue::Percent(0.0),
+            y: LengthValue::Percent(0.0),
+        }
+    }
+}
+
+/// [§ 3.9 'background-size'](https://www.w3.org/TR/css-backgrounds-3/#background-size)
+///
+/// "`<bg-size>` = [ `<length-percentage [0,∞]>` | auto ]{1,2} | cover | contain"
+///
+/// Initial: auto
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum BackgroundSize {
+    /// "Scale the image, while preserving its intrinsic aspect ratio (if
+    /// any), to the largest size such that both its width and its height
+    /// can fit inside the background positioning area."
+    Contain,
+    /// "Scale the image, while preserving its intrinsic aspect ratio (if
+    /// any), to the smallest size such that both its width and its height
+    /// can completely cover the background positioning area."
+    Cover,
+    /// "The first value gives the width of the corresponding image, the
+    /// second value its height. If only one value is given the second is
+    /// assumed to be 'auto'."
+    Explicit {
+        /// Image width.
+        width: AutoLength,
+        /// Image height.
+        height: AutoLength,
+    },
+}
+
+impl Default for BackgroundSize {
+    fn default() -> Self {
+        Self::Explicit {
+            width: AutoLength::Auto,
+            height: AutoLength::Auto,
+        }
+    }
+}
+
+/// One layer of the `background` shorthand, with every omitted longhand
+/// already reset to its initial value.
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct BackgroundLayer {
+    /// [§ 3.3 'background-image'](https://www.w3.org/TR/css-backgrounds-3/#background-image)
+    pub image: BackgroundImage,
+    /// [§ 3.4 'background-repeat'](https://www.w3.org/TR/css-backgrounds-3/#background-repeat)
+    pub repeat: BackgroundRepeat,
+    /// [§ 3.6 'background-position'](https://www.w3.org/TR/css-backgrounds-3/#background-position)
+    pub position: BackgroundPosition,
+    /// [§ 3.9 'background-size'](https://www.w3.org/TR/css-backgrounds-3/#background-size)
+    pub size: BackgroundSize,
+}
+
+/// [§ 3.10 'background'](https://www.w3.org/TR/css-backgrounds-3/#background)
+///
+/// Split a comma-separated list of background layers into its layers,
+/// with whitespace tokens removed. Returns `None` if any layer is empty.
+#[must_use]
+pub fn split_background_layers(values: &[ComponentValue]) -> Option<Vec<Vec<&ComponentValue>>> {
+    let mut layers = vec![Vec::new()];
+    for v in values {
+        match v {
+            ComponentValue::Token(CSSToken::Comma) => layers.push(Vec::new()),
+            ComponentValue::Token(CSSToken::Whitespace) => {}
+            other => layers.last_mut()?.push(other),
+        }
+    }
+    if layers.iter().any(Vec::is_empty) {
+        return None;
+    }
+    Some(layers)
+}
+
+/// [§ 3.3 'background-image'](https://www.w3.org/TR/css-backgrounds-3/#background-image)
+///
+/// Parse a single `<bg-image>`: `none` or a `<url>` (see [`url_value`]).
+#[must_use]
+pub fn parse_background_image(v: &ComponentValue) -> Option<BackgroundImage> {
+    match v {
+        ComponentValue::Token(CSSToken::Ident(ident)) if ident.eq_ignore_ascii_case("none") => {
+            Some(BackgroundImage::None)
+        }
+        _ => url_value(v).map(BackgroundImage::Url),
+    }
+}
+
+/// [§ 3.4 'background-repeat'](https://www.w3.org/TR/css-backgrounds-3/#background-repeat)
+///
+/// Consume a `<repeat-style>` from the front of `tokens`, returning the
+/// value and the number of tokens consumed.
+#[must_use]
+pub fn parse_background_repeat(tokens: &[&ComponentValue]) -> Option<(BackgroundRepeat, usize)> {
+    let keyword = |v: &ComponentValue| match v {
+        ComponentValue::Token(CSSToken::Ident(ident)) => Some(ident.to_ascii_lowercase()),
+        _ => None,
+    };
+    let single = |name: &str| match name {
+        "repeat" => Some(RepeatStyle::Repeat),
+        "space" => Some(RepeatStyle::Space),
+        "round" => Some(RepeatStyle::Round),
+        "no-repeat" => Some(RepeatStyle::NoRepeat),
+        _ => None,
+    };
+
+    let first = keyword(tokens.first()?)?;
+    match first.as_str() {
+        // "'repeat-x' computes to 'repeat no-repeat'"
+        "repeat-x" => {
+            return Some((
+                BackgroundRepeat {
+                    x: RepeatStyle::Repeat,
+                    y: RepeatStyle::NoRepeat,
+                },
+                1,
+            ));
+        }
+        // "'repeat-y' computes to 'no-repeat repeat'"
+        "repeat-y" => {
+            return Some((
+                BackgroundRepeat {
+                    x: RepeatStyle::NoRepeat,
+                    y: RepeatStyle::Repeat,
+                },
+                1,
+            ));
+        }
+        _ => {}
+    }
+
+    let x = single(&first)?;
+    // "If only one keyword is given, it sets both directions."
+    let second = tokens
+        .get(1)
+        .and_then(|v| keyword(v))
+        .and_then(|k| single(&k));
+    let consumed = if second.is_some() { 2 } else { 1 };
+    Some((
+        BackgroundRepeat {
+            x,
+            y: second.unwrap_or(x),
+        },
+        consumed,
+    ))
+}
+
+/// A single `<bg-position>` component: a keyword mapped to its axis, or a
+/// length that takes its axis from its position in the value.
+#[derive(Clone, Copy)]
+enum PositionPart {
+    Horizontal(LengthValue),
+    Vertical(LengthValue),
+    Center,
+    Length(LengthValue),
+}
+
+fn parse_position_part(v: &ComponentValue) -> Option<PositionPart> {
+    if let ComponentValue::Token(CSSToken::Ident(ident)) = v {
+        return match ident.to_ascii_lowercase().as_str() {
+            "left" => Some(PositionPart::Horizontal(LengthValue::Percent(0.0))),
+            "right" => Some(PositionPart::Horizontal(LengthValue::Percent(100.0))),
+            "top" => Some(PositionPart::Vertical(LengthValue::Percent(0.0))),
+            "bottom" => Some(PositionPart::Vertical(LengthValue::Percent(100.0))),
+            "center" => Some(PositionPart::Center),
+            _ => None,
+        };
+    }
+    parse_single_length(v).map(PositionPart::Length)
+}
+
+/// [§ 3.6 'background-position'](https://www.w3.org/TR/css-backgrounds-3/#background-position)
+///
+/// Consume a one- or two-value `<bg-position>` from the front of `tokens`,
+/// returning the value and the number of tokens consumed.
+///
+/// "If only one value is specified, the second value is assumed to be
+/// 'center'."
+///
+/// NOTE: The three- and four-value forms with edge offsets
+/// (`right 10px bottom 5px`) are not supported.
+#[must_use]
+pub fn parse_background_position(
+    tokens: &[&ComponentValue],
+) -> Option<(BackgroundPosition, usize)> {
+    const CENTER: LengthValue = LengthValue::Percent(50.0);
+
+    let first = parse_position_part(tokens.first()?)?;
+    let second = tokens.get(1).and_then(|v| parse_position_part(v));
+
+    let position = match second {
+        None => match first {
+            PositionPart::Vertical(y) => BackgroundPosition { x: CENTER, y },
+            PositionPart::Horizontal(x) | PositionPart::Length(x) => {
+                BackgroundPosition { x, y: CENTER }
+            }
+            PositionPart::Center => BackgroundPosition {
+                x: CENTER,
+                y: CENTER,
+            },
+        },
+        Some(second) => {
+            // "top left" is the same as "left top": a pair of keywords may
+            // appear in either order, but a length fixes the axis order.
+            let swapped = matches!(first, PositionPart::Vertical(_))
+                || matches!(second, PositionPart::Horizontal(_));
+            let (horizontal, vertical) = if swapped {
+                if matches!(first, PositionPart::Length(_))
+                    || matches!(second, PositionPart::Length(_))
+                {
+                    return None;
+                }
+                (second, first)
+            } else {
+                (first, second)
+            };
+            let x = match horizontal {
+                PositionPart::Horizontal(x) | PositionPart::Length(x) => x,
+                PositionPart::Center => CENTER,
+                PositionPart::Vertical(_) => return None,
+            };
+            let y = match vertical {
+                PositionPart::Vertical(y) | PositionPart::Length(y) => y,
+                PositionPart::Center => CENTER,
+                PositionPart::Horizontal(_) => return None,
+            };
+            BackgroundPosition { x, y }
+        }
+    };
+
+    Some((position, if second.is_some() { 2 } else { 1 }))
+}
+
+/// [§ 3.9 'background-size'](https://www.w3.org/TR/css-backgrounds-3/#background-size)
+///
+/// Consume a `<bg-size>` from the front of `tokens`, returning the value
+/// and the number of tokens consumed.
+#[must_use]
+pub fn parse_background_size(tokens: &[&ComponentValue]) -> Option<(BackgroundSize, usize)> {
+    let first = tokens.first()?;
+    if let ComponentValue::Token(CSSToken::Ident(ident)) = first {
+        if ident.eq_ignore_ascii_case("cover") {
+            return Some((BackgroundSize::Cover, 1));
+        }
+        if ident.eq_ignore_ascii_case("contain") {
+            return Some((BackgroundSize::Contain, 1));
+        }
+    }
+
+    let width = parse_single_auto_length(first)?;
+    // "If only one value is given the second is assumed to be 'auto'."
+    let height = tokens.get(1).and_then(|v| parse_single_auto_length(v));
+    let consumed = if height.is_some() { 2 } else { 1 };
+    Some((
+        BackgroundSize::Explicit {
+            width,
+            height: height.unwrap_or(AutoLength::Auto),
+        },
+        consumed,
+    ))
+}
+
+/// [§ 3.10 'background'](https://www.w3.org/TR/css-backgrounds-3/#background)
+///
+/// "`<bg-layer>` = `<bg-image>` || `<bg-position>` [ / `<bg-size>` ]? ||
+/// `<repeat-style>` || `<attachment>` || `<visual-box>` || `<visual-box>`"
+///
+/// "`<final-bg-layer>` = `<bg-layer>` || `<'background-color'>`"
+///
+/// Parse one layer of the shorthand. Components may appear in any order but
+/// each at most once; a color is accepted only when `allow_color` is set.
+/// `<attachment>` and `<visual-box>` keywords are accepted and ignored.
+///
+/// Returns `None` if the layer is invalid, which invalidates the whole
+/// declaration.
+#[must_use]
+pub fn parse_background_layer(
+    tokens: &[&ComponentValue],
+    allow_color: bool,
+) -> Option<(BackgroundLayer, Option<ColorValue>)> {
+    let mut image = None;
+    let mut repeat = None;
+    let mut position = None;
+    let mut size = None;
+    let mut color = None;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let rest = &tokens[i..];
+
+        if image.is_none()
+            && let Some(img) = parse_background_image(rest[0])
+        {
+            image = Some(img);
+            i += 1;
+            continue;
+        }
+
+        if repeat.is_none()
+            && let Some((r, consumed)) = parse_background_repeat(rest)
+        {
+            repeat = Some(r);
+            i += consumed;
+            continue;
+        }
+
+        if position.is_none()
+            && let Some((p, consumed)) = parse_background_position(rest)
+        {
+            position = Some(p);
+            i += consumed;
+
+            // "<bg-position> [ / <bg-size> ]?" — a size may only follow a
+            // position, separated by a slash.
+            if matches!(
+                tokens.get(i),
+                Some(ComponentValue::Token(CSSToken::Delim('/')))
+            ) {
+                let (s, consumed) = parse_background_size(&tokens[i + 1..])?;
+                size = Some(s);
+                i += 1 + consumed;
+            }
+            continue;
+        }
+
+        if let ComponentValue::Token(CSSToken::Ident(ident)) = rest[0]
+            && matches!(
+                ident.to_ascii_lowercase().as_str(),
+                "scroll" | "fixed" | "local" | "border-box" | "padding-box" | "content-box"
+            )
+        {
+            i += 1;
+            continue;
+        }
+
+        if allow_color
+            && color.is_none()
+            && let Some(c) = parse_single_color(rest[0])
+        {
+            color = Some(c);
+            i += 1;
+            continue;
+        }
+
+        return None;
+    }
+
+    let layer = BackgroundLayer {
+        image: image.unwrap_or_default(),
+        repeat: repeat.unwrap_or_default(),
+        position: position.unwrap_or_default(),
+        size: size.unwrap_or_default(),
+    };
+    Some((layer, color))
+}
+
+/// [§ 3.1 Layering Multiple Background Images](https://www.w3.org/TR/css-backgrounds-3/#layering)
+///
+/// Parse a comma-separated longhand value (`background-repeat: repeat-x,
+/// no-repeat`) with `parse`, which must consume each layer entirely.
+pub fn parse_background_list<T>(
+    values: &[ComponentValue],
+    parse: impl Fn(&[&ComponentValue]) -> Option<(T, usize)>,
+) -> Option<Vec<T>> {
+    split_background_layers(values)?
+        .iter()
+        .map(|layer| match parse(layer) {
+            Some((value, consumed)) if consumed == layer.len() => Some(value),
+            _ => None,
+        })
+        .collect()
+}
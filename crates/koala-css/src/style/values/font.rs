@@ -5,6 +5,7 @@
 use serde::Serialize;
 
 use crate::parser::ComponentValue;
+use crate::style::values::length::{LengthValue, parse_single_length};
 use crate::tokenizer::CSSToken;
 
 /// [§ 3.3 'font-style'](https://www.w3.org/TR/css-fonts-4/#font-style-prop)
@@ -25,30 +26,205 @@ pub enum FontStyle {
     Oblique,
 }
 
-/// Parse font-family value.
+/// [§ 3.1.1 Generic font families](https://www.w3.org/TR/css-fonts-4/#generic-font-families)
+///
+/// "Generic font families are a fallback mechanism, a means of preserving
+/// some of the style sheet author's intent in the worst case when none of
+/// the specified fonts can be selected."
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum GenericFontFamily {
+    /// "Serif fonts represent the formal text style for a script."
+    Serif,
+    /// "Glyphs in sans-serif fonts, as the term is used in CSS, are
+    /// generally low contrast... and have stroke endings that are plain."
+    SansSerif,
+    /// "The sole criterion of a monospace font is that all glyphs have the
+    /// same fixed width."
+    Monospace,
+    /// "Glyphs in cursive fonts generally use a more informal script style."
+    Cursive,
+    /// "Fantasy fonts are primarily decorative or expressive fonts."
+    Fantasy,
+    /// "This generic font family lets text render with the default user
+    /// interface font on the platform on which the UA is running."
+    SystemUi,
+}
+
+impl GenericFontFamily {
+    /// The keyword naming this family, e.g. `"sans-serif"`.
+    #[must_use]
+    pub const fn keyword(self) -> &'static str {
+        match self {
+            Self::Serif => "serif",
+            Self::SansSerif => "sans-serif",
+            Self::Monospace => "monospace",
+            Self::Cursive => "cursive",
+            Self::Fantasy => "fantasy",
+            Self::SystemUi => "system-ui",
+        }
+    }
+
+    /// The generic family named by `keyword`, ASCII case-insensitively.
+    #[must_use]
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        [
+            Self::Serif,
+            Self::SansSerif,
+            Self::Monospace,
+            Self::Cursive,
+            Self::Fantasy,
+            Self::SystemUi,
+        ]
+        .into_iter()
+        .find(|generic| generic.keyword().eq_ignore_ascii_case(keyword))
+    }
+}
+
+/// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+///
+/// One entry of the family list: "Value: [ <family-name> | <generic-family> ]#"
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub enum FontFamily {
+    /// "The name of a font family of choice", e.g. `Times New Roman`.
+    Named(String),
+    /// A generic family keyword such as `monospace`.
+    Generic(GenericFontFamily),
+}
+
+impl FontFamily {
+    /// The family's name, or the keyword of a generic family.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Named(name) => name,
+            Self::Generic(generic) => generic.keyword(),
+        }
+    }
+}
+
+/// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+///
+/// "This property specifies a prioritized list of font family names or
+/// generic family names."
+///
+/// "Font family names must either be given quoted as strings, or unquoted
+/// as a sequence of one or more identifiers. This means most punctuation
+/// characters and digits at the start of each token must be escaped in
+/// unquoted font family names."
+///
+/// "If a sequence of identifiers is given as a <family-name>, the computed
+/// value is the name converted to a string by joining all the identifiers
+/// in the sequence by single spaces."
+///
+/// Returns `None`, making the declaration invalid, if any entry is empty
+/// or holds anything other than a string or identifiers.
 #[must_use]
-pub fn parse_font_family(values: &[ComponentValue]) -> Option<String> {
+pub fn parse_font_family(values: &[ComponentValue]) -> Option<Vec<FontFamily>> {
+    values
+        .split(|v| matches!(v, ComponentValue::Token(CSSToken::Comma)))
+        .map(parse_family_entry)
+        .collect()
+}
+
+/// Parse one comma-separated entry of a `font-family` list.
+fn parse_family_entry(values: &[ComponentValue]) -> Option<FontFamily> {
+    let mut tokens = Vec::new();
     for v in values {
-        if let ComponentValue::Token(CSSToken::Ident(name) | CSSToken::String(name)) = v {
-            return Some(name.clone());
+        match v {
+            ComponentValue::Token(CSSToken::Whitespace) => {}
+            ComponentValue::Token(token) => tokens.push(token),
+            _ => return None,
+        }
+    }
+
+    match tokens.as_slice() {
+        [CSSToken::String(name)] => Some(FontFamily::Named(name.clone())),
+        // "...font family names that happen to be the same as a keyword
+        // value (inherit, serif, sans-serif, monospace, fantasy, and
+        // cursive) must be quoted to prevent confusion with the keywords
+        // with the same names."
+        [CSSToken::Ident(name)] => GenericFontFamily::from_keyword(name).map_or_else(
+            || Some(FontFamily::Named(name.clone())),
+            |generic| Some(FontFamily::Generic(generic)),
+        ),
+        [] => None,
+        idents => idents
+            .iter()
+            .map(|t| match t {
+                CSSToken::Ident(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(|words| FontFamily::Named(words.join(" "))),
+    }
+}
+
+/// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+///
+/// "Computed value: the keyword normal or a number or an absolute length"
+///
+/// A `<number>` computes to itself and is inherited as a factor, so each
+/// descendant multiplies it by its own font size. A `<length-percentage>`
+/// computes to an absolute length on the element that declares it, and
+/// descendants inherit that length.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub enum LineHeight {
+    /// "Determine the preferred line height automatically based on font
+    /// metrics."
+    #[default]
+    Normal,
+    /// "The used value of the property is this number multiplied by the
+    /// element's computed font-size."
+    Number(f64),
+    /// "The specified length is used as the preferred line height.
+    /// Percentages are relative to the computed font-size of the element
+    /// itself."
+    ///
+    /// Until the cascade resolves it, a percentage is held as the
+    /// equivalent `em` length.
+    Length(LengthValue),
+}
+
+impl LineHeight {
+    /// The used line height in pixels for text at `font_size`. `normal`
+    /// is the font's own line height, which the caller supplies.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn to_px(self, font_size: f32, normal: f32) -> f32 {
+        match self {
+            Self::Normal => normal,
+            Self::Number(factor) => factor as f32 * font_size,
+            Self::Length(length) => length.to_px() as f32,
         }
     }
-    None
 }
 
-/// [§ 4.2 `line-height`](https://www.w3.org/TR/css-inline-3/#line-height-property)
-/// Parse `line-height` as a unitless number or length.
+/// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+///
+/// "Value: normal | <number [0,∞]> | <length-percentage [0,∞]>"
+///
+/// Lengths are returned as specified; font-relative units (and
+/// percentages, which become `em`) still need resolving against the
+/// element's font size.
 #[must_use]
-pub fn parse_line_height(values: &[ComponentValue]) -> Option<f64> {
+pub fn parse_line_height(values: &[ComponentValue]) -> Option<LineHeight> {
     for v in values {
         match v {
-            ComponentValue::Token(CSSToken::Number { value, .. }) => {
-                return Some(*value);
-            }
-            ComponentValue::Token(CSSToken::Dimension { value, unit, .. })
-                if unit.eq_ignore_ascii_case("px") =>
+            ComponentValue::Token(CSSToken::Ident(ident))
+                if ident.eq_ignore_ascii_case("normal") =>
             {
-                return Some(*value / 16.0);
+                return Some(LineHeight::Normal);
+            }
+            ComponentValue::Token(CSSToken::Number { value, .. }) if *value >= 0.0 => {
+                return Some(LineHeight::Number(*value));
+            }
+            ComponentValue::Token(CSSToken::Percentage { value, .. }) if *value >= 0.0 => {
+                return Some(LineHeight::Length(LengthValue::Em(*value / 100.0)));
+            }
+            ComponentValue::Token(CSSToken::Dimension { .. }) => {
+                return parse_single_length(v)
+                    .filter(|length| length.to_px() >= 0.0)
+                    .map(LineHeight::Length);
             }
             _ => {}
         }
@@ -56,28 +232,81 @@ pub fn parse_line_height(values: &[ComponentValue]) -> Option<f64> {
     None
 }
 
-// [§ 3.2 `font-weight`](https://www.w3.org/TR/css-fonts-4/#font-weight-prop)
+/// [§ 2.2 `font-weight`](https://www.w3.org/TR/css-fonts-4/#font-weight-prop)
+///
+/// "Value: <font-weight-absolute> | bolder | lighter"
+///
+/// "<font-weight-absolute> = [normal | bold | <number [1,1000]>]"
+///
+/// Returns the computed numeric weight. `inherited` is the parent's
+/// weight, which the relative keywords step from.
 #[must_use]
 #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-pub fn parse_font_weight(values: &[ComponentValue]) -> Option<u16> {
+pub fn parse_font_weight(values: &[ComponentValue], inherited: u16) -> Option<u16> {
     for v in values {
         match v {
-            ComponentValue::Token(CSSToken::Ident(ident))
-                if ident.eq_ignore_ascii_case("normal") =>
-            {
-                return Some(400);
-            }
-            ComponentValue::Token(CSSToken::Ident(ident)) if ident.eq_ignore_ascii_case("bold") => {
-                return Some(700);
-            }
-            ComponentValue::Token(CSSToken::Number { value, .. }) => {
-                let weight = *value as u16;
-                if (100..=900).contains(&weight) && weight.is_multiple_of(100) {
-                    return Some(weight);
+            ComponentValue::Token(CSSToken::Ident(ident)) => {
+                match ident.to_ascii_lowercase().as_str() {
+                    // "'normal': Same as '400'."
+                    "normal" => return Some(400),
+                    // "'bold': Same as '700'."
+                    "bold" => return Some(700),
+                    "bolder" => return Some(bolder_font_weight(inherited)),
+                    "lighter" => return Some(lighter_font_weight(inherited)),
+                    _ => {}
                 }
             }
+            ComponentValue::Token(CSSToken::Number { value, .. })
+                if (1.0..=1000.0).contains(value) =>
+            {
+                return Some(value.round() as u16);
+            }
             _ => {}
         }
     }
     None
 }
+
+/// [§ 2.2.1 Relative Weights](https://www.w3.org/TR/css-fonts-4/#relative-weights)
+///
+/// "Specifies a bolder weight than the inherited value."
+///
+/// | Inherited value (w) | bolder |
+/// |---------------------|--------|
+/// | w < 100             | 400    |
+/// | 100 ≤ w < 350       | 400    |
+/// | 350 ≤ w < 550       | 700    |
+/// | 550 ≤ w < 750       | 900    |
+/// | 750 ≤ w < 900       | 900    |
+/// | 900 ≤ w             | No change |
+#[must_use]
+pub const fn bolder_font_weight(inherited: u16) -> u16 {
+    match inherited {
+        0..350 => 400,
+        350..550 => 700,
+        550..900 => 900,
+        _ => inherited,
+    }
+}
+
+/// [§ 2.2.1 Relative Weights](https://www.w3.org/TR/css-fonts-4/#relative-weights)
+///
+/// "Specifies a lighter weight than the inherited value."
+///
+/// | Inherited value (w) | lighter |
+/// |---------------------|---------|
+/// | w < 100             | No change |
+/// | 100 ≤ w < 350       | 100     |
+/// | 350 ≤ w < 550       | 100     |
+/// | 550 ≤ w < 750       | 400     |
+/// | 750 ≤ w < 900       | 700     |
+/// | 900 ≤ w             | 700     |
+#[must_use]
+pub const fn lighter_font_weight(inherited: u16) -> u16 {
+    match inherited {
+        0..100 => inherited,
+        100..550 => 100,
+        550..750 => 400,
+        _ => 700,
+    }
+}
@@ -112,6 +112,35 @@ pub fn first_percentage(values: &[ComponentValue]) -> Option<f32> {
     })
 }
 
+/// [§ 4.5 Resource Locators: the `<url>` type](https://www.w3.org/TR/css-values-4/#urls)
+///
+/// "`<url> = url( <string> <url-modifier>* ) | src( <string> <url-modifier>* )`"
+///
+/// Returns the URL string of a single `<url>` component value. The
+/// unquoted form arrives from the tokenizer as a `<url-token>`; the quoted
+/// form is a `url(` (or `src(`) function whose first argument is a
+/// `<string-token>`, with any escapes already resolved. URL modifiers are
+/// ignored. A `<bad-url-token>`, or a function without a string argument,
+/// yields `None`.
+#[must_use]
+pub fn url_value(v: &ComponentValue) -> Option<String> {
+    match v {
+        ComponentValue::Token(CSSToken::Url(url)) => Some(url.clone()),
+        ComponentValue::Function { name, value }
+            if name.eq_ignore_ascii_case("url") || name.eq_ignore_ascii_case("src") =>
+        {
+            match value
+                .iter()
+                .find(|arg| !matches!(arg, ComponentValue::Token(CSSToken::Whitespace)))
+            {
+                Some(ComponentValue::Token(CSSToken::String(url))) => Some(url.clone()),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     //! Unit tests for the value-extraction helpers.
@@ -256,4 +285,34 @@ mod tests {
         let values = [number(50.0), px(50.0)];
         assert_eq!(first_percentage(&values), None);
     }
+
+    fn url_function(args: Vec<ComponentValue>) -> ComponentValue {
+        ComponentValue::Function {
+            name: "url".to_owned(),
+            value: args,
+        }
+    }
+
+    #[test]
+    fn url_value_reads_unquoted_url_token() {
+        let value = ComponentValue::Token(CSSToken::Url("a.png".to_owned()));
+        assert_eq!(url_value(&value).as_deref(), Some("a.png"));
+    }
+
+    #[test]
+    fn url_value_reads_quoted_string_past_whitespace() {
+        let value = url_function(vec![
+            ComponentValue::Token(CSSToken::Whitespace),
+            ComponentValue::Token(CSSToken::String("a b.png".to_owned())),
+            ComponentValue::Token(CSSToken::Whitespace),
+        ]);
+        assert_eq!(url_value(&value).as_deref(), Some("a b.png"));
+    }
+
+    #[test]
+    fn url_value_rejects_bad_url_and_non_string_arguments() {
+        assert_eq!(url_value(&ComponentValue::Token(CSSToken::BadUrl)), None);
+        assert_eq!(url_value(&url_function(vec![ident("a")])), None);
+        assert_eq!(url_value(&ident("url")), None);
+    }
 }
@@ -23,12 +23,23 @@ pub enum LengthValue {
     /// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
     /// "Equal to the computed value of the font-size property of the element"
     Em(f64),
+    /// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+    /// "Equal to the computed value of font-size on the root element. When
+    /// specified on the font-size property of the root element, the rem units
+    /// refer to the property's initial value."
+    Rem(f64),
     /// [§ 5.1.2 Viewport-percentage lengths](https://www.w3.org/TR/css-values-4/#viewport-relative-lengths)
     /// "1vw = 1% of viewport width"
     Vw(f64),
     /// [§ 5.1.2 Viewport-percentage lengths](https://www.w3.org/TR/css-values-4/#viewport-relative-lengths)
     /// "1vh = 1% of viewport height"
     Vh(f64),
+    /// [§ 5.1.2 Viewport-percentage lengths](https://www.w3.org/TR/css-values-4/#viewport-relative-lengths)
+    /// "1vmin = 1% of viewport's smaller dimension"
+    Vmin(f64),
+    /// [§ 5.1.2 Viewport-percentage lengths](https://www.w3.org/TR/css-values-4/#viewport-relative-lengths)
+    /// "1vmax = 1% of viewport's larger dimension"
+    Vmax(f64),
     /// [§ 4.3 Percentages](https://www.w3.org/TR/css-values-4/#percentages)
     /// "A <percentage> value is denoted by <percentage>, and consists of a
     /// <number> immediately followed by a percent sign '%'."
@@ -38,14 +49,31 @@ pub enum LengthValue {
     /// it is impossible or impractical to determine the measure of the '0'
     /// glyph, it must be assumed to be 0.5em wide."
     Ch(f64),
+    /// [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+    /// "1cm = 96px/2.54"
+    Cm(f64),
+    /// [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+    /// "1mm = 1/10th of 1cm"
+    Mm(f64),
+    /// [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+    /// "1Q = 1/40th of 1cm"
+    Q(f64),
+    /// [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+    /// "1in = 2.54cm = 96px"
+    In(f64),
+    /// [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+    /// "1pc = 1/6th of 1in"
+    Pc(f64),
+    /// [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+    /// "1pt = 1/72nd of 1in"
+    Pt(f64),
+    /// [§ 10.2 Comparison Functions](https://www.w3.org/TR/css-values-4/#comp-func)
+    /// A `min()`, `max()` or `clamp()` expression, resolved at layout time
+    /// once its percentage basis and the viewport are known.
+    Math(MathFunction),
     // TODO: Implement additional length units:
     //
-    // STEP 1: Add rem unit
-    // [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
-    // "Equal to the computed value of the font-size property of the root element."
-    // Rem(f64),
-    //
-    // STEP 2: Add calc() function support
+    // STEP 1: Add calc() function support
     // [§ 8.1 calc()](https://www.w3.org/TR/css-values-4/#calc-notation)
     // "The calc() function allows mathematical expressions with addition (+),
     // subtraction (-), multiplication (*), division (/), and parentheses."
@@ -53,11 +81,35 @@ pub enum LengthValue {
 }
 
 impl LengthValue {
+    /// [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+    ///
+    /// "The absolute length units are fixed in relation to each other and
+    /// anchored to some physical measurement."
+    ///
+    /// The value in pixels if this is an absolute length (`px`, `cm`, `mm`,
+    /// `Q`, `in`, `pc`, `pt`), using the CSS reference pixel of 1/96th of an
+    /// inch. Returns `None` for relative lengths and math functions.
+    #[must_use]
+    pub fn absolute_px(&self) -> Option<f64> {
+        let (value, px_per_unit) = match *self {
+            Self::Px(v) => (v, 1.0),
+            Self::Cm(v) => (v, 96.0 / 2.54),
+            Self::Mm(v) => (v, 96.0 / 25.4),
+            Self::Q(v) => (v, 96.0 / 101.6),
+            Self::In(v) => (v, 96.0),
+            Self::Pc(v) => (v, 16.0),
+            Self::Pt(v) => (v, 96.0 / 72.0),
+            _ => return None,
+        };
+        Some(value * px_per_unit)
+    }
+
     /// [§ 4.1 Lengths](https://www.w3.org/TR/css-values-4/#lengths)
     ///
     /// Get the value in pixels for non-viewport units.
     ///
-    /// NOTE: For viewport units (vw, vh), this returns 0.0 as a fallback.
+    /// NOTE: For viewport units (vw, vh, vmin, vmax), this returns 0.0 as a
+    /// fallback.
     /// Use `to_px_with_viewport()` instead when viewport dimensions are available.
     #[must_use]
     pub fn to_px(&self) -> f64 {
@@ -67,15 +119,25 @@ impl LengthValue {
             // [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
             // "Equal to the computed value of the font-size property of the element"
             Self::Em(em) => *em * DEFAULT_FONT_SIZE_PX,
+            // "Equal to the computed value of font-size on the root element."
+            // The cascade resolves rem against the actual root font-size;
+            // this fallback assumes the default.
+            Self::Rem(rem) => *rem * DEFAULT_FONT_SIZE_PX,
             // [§ 5.1.2 Viewport-percentage lengths](https://www.w3.org/TR/css-values-4/#viewport-relative-lengths)
             // Viewport units require viewport dimensions - return 0 as fallback.
             // The layout engine should use to_px_with_viewport() instead.
-            Self::Vw(_) | Self::Vh(_) |
+            Self::Vw(_) | Self::Vh(_) | Self::Vmin(_) | Self::Vmax(_) |
             // [§ 4.3 Percentages](https://www.w3.org/TR/css-values-4/#percentages)
             // Percentages require containing block dimensions - return 0 as fallback.
             // The layout engine should use to_px_with_containing_block() instead.
             Self::Percent(_) => 0.0,
             Self::Ch(ch) => *ch * DEFAULT_FONT_SIZE_PX * 0.5,
+            // [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+            // Physical units are fixed multiples of the reference pixel.
+            Self::Cm(_) | Self::Mm(_) | Self::Q(_) | Self::In(_) | Self::Pc(_) | Self::Pt(_) => {
+                self.absolute_px().unwrap_or(0.0)
+            }
+            Self::Math(function) => function.evaluate(|arg| arg.to_px()),
         }
     }
 
@@ -89,15 +151,26 @@ impl LengthValue {
         match self {
             Self::Px(px) => *px,
             Self::Em(em) => *em * DEFAULT_FONT_SIZE_PX,
+            Self::Rem(rem) => *rem * DEFAULT_FONT_SIZE_PX,
             // "1vw = 1% of viewport width"
             Self::Vw(vw) => *vw * viewport_width / 100.0,
             // "1vh = 1% of viewport height"
             Self::Vh(vh) => *vh * viewport_height / 100.0,
+            // "1vmin = 1% of viewport's smaller dimension"
+            Self::Vmin(v) => *v * viewport_width.min(viewport_height) / 100.0,
+            // "1vmax = 1% of viewport's larger dimension"
+            Self::Vmax(v) => *v * viewport_width.max(viewport_height) / 100.0,
             // [§ 4.3 Percentages](https://www.w3.org/TR/css-values-4/#percentages)
             // Percentages require containing block — return 0 as fallback.
             // Use to_px_with_containing_block() when containing block is available.
             Self::Percent(_) => 0.0,
             Self::Ch(ch) => *ch * DEFAULT_FONT_SIZE_PX * 0.5,
+            Self::Cm(_) | Self::Mm(_) | Self::Q(_) | Self::In(_) | Self::Pc(_) | Self::Pt(_) => {
+                self.absolute_px().unwrap_or(0.0)
+            }
+            Self::Math(function) => {
+                function.evaluate(|arg| arg.to_px_with_viewport(viewport_width, viewport_height))
+            }
         }
     }
 
@@ -121,10 +194,222 @@ impl LengthValue {
         match self {
             Self::Px(px) => *px,
             Self::Em(em) => *em * DEFAULT_FONT_SIZE_PX,
+            Self::Rem(rem) => *rem * DEFAULT_FONT_SIZE_PX,
             Self::Vw(vw) => *vw * viewport_width / 100.0,
             Self::Vh(vh) => *vh * viewport_height / 100.0,
+            Self::Vmin(v) => *v * viewport_width.min(viewport_height) / 100.0,
+            Self::Vmax(v) => *v * viewport_width.max(viewport_height) / 100.0,
             Self::Percent(pct) => *pct * cb_dimension / 100.0,
             Self::Ch(ch) => *ch * DEFAULT_FONT_SIZE_PX * 0.5,
+            Self::Cm(_) | Self::Mm(_) | Self::Q(_) | Self::In(_) | Self::Pc(_) | Self::Pt(_) => {
+                self.absolute_px().unwrap_or(0.0)
+            }
+            Self::Math(function) => function.evaluate(|arg| {
+                arg.to_px_with_containing_block(cb_dimension, viewport_width, viewport_height)
+            }),
+        }
+    }
+}
+
+/// The unit of a single `min()`/`max()`/`clamp()` argument.
+///
+/// Arguments are stored as a number and a unit rather than a nested
+/// [`LengthValue`] so that [`MathFunction`] stays `Copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum LengthUnit {
+    /// `px`
+    Px,
+    /// `em`
+    Em,
+    /// `rem`
+    Rem,
+    /// `vw`
+    Vw,
+    /// `vh`
+    Vh,
+    /// `vmin`
+    Vmin,
+    /// `vmax`
+    Vmax,
+    /// `%`
+    Percent,
+    /// `ch`
+    Ch,
+    /// `cm`
+    Cm,
+    /// `mm`
+    Mm,
+    /// `Q`
+    Q,
+    /// `in`
+    In,
+    /// `pc`
+    Pc,
+    /// `pt`
+    Pt,
+}
+
+impl LengthUnit {
+    /// The length of `value` in this unit.
+    #[must_use]
+    pub const fn with_value(self, value: f64) -> LengthValue {
+        match self {
+            Self::Px => LengthValue::Px(value),
+            Self::Em => LengthValue::Em(value),
+            Self::Rem => LengthValue::Rem(value),
+            Self::Vw => LengthValue::Vw(value),
+            Self::Vh => LengthValue::Vh(value),
+            Self::Vmin => LengthValue::Vmin(value),
+            Self::Vmax => LengthValue::Vmax(value),
+            Self::Percent => LengthValue::Percent(value),
+            Self::Ch => LengthValue::Ch(value),
+            Self::Cm => LengthValue::Cm(value),
+            Self::Mm => LengthValue::Mm(value),
+            Self::Q => LengthValue::Q(value),
+            Self::In => LengthValue::In(value),
+            Self::Pc => LengthValue::Pc(value),
+            Self::Pt => LengthValue::Pt(value),
+        }
+    }
+
+    /// Split a length into its number and unit, or `None` for a math
+    /// function.
+    #[must_use]
+    pub const fn split(length: LengthValue) -> Option<(f64, Self)> {
+        Some(match length {
+            LengthValue::Px(v) => (v, Self::Px),
+            LengthValue::Em(v) => (v, Self::Em),
+            LengthValue::Rem(v) => (v, Self::Rem),
+            LengthValue::Vw(v) => (v, Self::Vw),
+            LengthValue::Vh(v) => (v, Self::Vh),
+            LengthValue::Vmin(v) => (v, Self::Vmin),
+            LengthValue::Vmax(v) => (v, Self::Vmax),
+            LengthValue::Percent(v) => (v, Self::Percent),
+            LengthValue::Ch(v) => (v, Self::Ch),
+            LengthValue::Cm(v) => (v, Self::Cm),
+            LengthValue::Mm(v) => (v, Self::Mm),
+            LengthValue::Q(v) => (v, Self::Q),
+            LengthValue::In(v) => (v, Self::In),
+            LengthValue::Pc(v) => (v, Self::Pc),
+            LengthValue::Pt(v) => (v, Self::Pt),
+            LengthValue::Math(_) => return None,
+        })
+    }
+}
+
+/// [§ 10.2 Comparison Functions: min(), max(), and clamp()](https://www.w3.org/TR/css-values-4/#comp-func)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum MathFunctionKind {
+    /// "The `min()` or `max()` functions contain one or more comma-separated
+    /// calculations, and represent the smallest (most negative) or largest
+    /// (most positive) of them, respectively."
+    Min,
+    /// See [`MathFunctionKind::Min`].
+    Max,
+    /// "The `clamp()` function takes three calculations—a minimum value, a
+    /// central value, and a maximum value—and represents its central
+    /// calculation, clamped according to its min and max calculations,
+    /// favoring the min calculation if it conflicts with the max."
+    Clamp,
+}
+
+impl MathFunctionKind {
+    /// The function name as written in CSS.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Clamp => "clamp",
+        }
+    }
+}
+
+/// The most arguments a `min()` or `max()` expression may have.
+///
+/// NOTE: The arguments are stored inline so [`LengthValue`] stays `Copy`.
+/// Longer argument lists are rejected rather than truncated, so the
+/// declaration using them is ignored.
+const MAX_MATH_FUNCTION_ARGS: usize = 4;
+
+/// [§ 10.2 Comparison Functions: min(), max(), and clamp()](https://www.w3.org/TR/css-values-4/#comp-func)
+///
+/// A comparison function over plain lengths.
+///
+/// NOTE: Arguments must be single dimensions, percentages or `0`. Nested
+/// functions and `calc()` arithmetic are not supported, and declarations
+/// using them are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct MathFunction {
+    /// Which comparison the function performs.
+    pub kind: MathFunctionKind,
+    args: [(f64, LengthUnit); MAX_MATH_FUNCTION_ARGS],
+    len: usize,
+}
+
+impl MathFunction {
+    /// Build a function from its arguments.
+    ///
+    /// Returns `None` if an argument is itself a math function, or the
+    /// argument count is wrong: `clamp()` takes exactly three, `min()` and
+    /// `max()` between one and four.
+    #[must_use]
+    pub fn new(kind: MathFunctionKind, args: &[LengthValue]) -> Option<Self> {
+        let count_ok = match kind {
+            MathFunctionKind::Clamp => args.len() == 3,
+            MathFunctionKind::Min | MathFunctionKind::Max => {
+                (1..=MAX_MATH_FUNCTION_ARGS).contains(&args.len())
+            }
+        };
+        if !count_ok {
+            return None;
+        }
+        let mut function = Self {
+            kind,
+            args: [(0.0, LengthUnit::Px); MAX_MATH_FUNCTION_ARGS],
+            len: args.len(),
+        };
+        for (slot, arg) in function.args.iter_mut().zip(args) {
+            *slot = LengthUnit::split(*arg)?;
+        }
+        Some(function)
+    }
+
+    /// The function's arguments, in source order.
+    pub fn args(&self) -> impl Iterator<Item = LengthValue> + '_ {
+        self.args[..self.len]
+            .iter()
+            .map(|&(value, unit)| unit.with_value(value))
+    }
+
+    /// Replace each argument with `f(argument)`, e.g. to resolve font-relative
+    /// units once the font size is known.
+    ///
+    /// An argument that `f` turns into a math function is left unchanged.
+    #[must_use]
+    pub fn map(mut self, f: impl Fn(LengthValue) -> LengthValue) -> Self {
+        for slot in &mut self.args[..self.len] {
+            if let Some(resolved) = LengthUnit::split(f(slot.1.with_value(slot.0))) {
+                *slot = resolved;
+            }
+        }
+        self
+    }
+
+    /// Resolve each argument to pixels with `resolve` and combine them.
+    pub fn evaluate(&self, resolve: impl Fn(LengthValue) -> f64) -> f64 {
+        let mut values = self.args().map(resolve);
+        match self.kind {
+            MathFunctionKind::Min => values.fold(f64::INFINITY, f64::min),
+            MathFunctionKind::Max => values.fold(f64::NEG_INFINITY, f64::max),
+            // "given clamp(MIN, VAL, MAX), it represents exactly the same
+            // value as max(MIN, min(VAL, MAX))."
+            MathFunctionKind::Clamp => {
+                let min = values.next().unwrap_or(0.0);
+                let val = values.next().unwrap_or(0.0);
+                let max = values.next().unwrap_or(0.0);
+                min.max(val.min(max))
+            }
         }
     }
 }
@@ -159,6 +444,60 @@ pub enum AutoLength {
 
     /// A specific length value (px, em, etc.).
     Length(LengthValue),
+
+    /// [§ 3.1 Intrinsic Sizes](https://www.w3.org/TR/css-sizing-3/#sizing-values)
+    ///
+    /// An intrinsic sizing keyword. Only `width` and `height` accept these.
+    Intrinsic(IntrinsicSize),
+}
+
+/// [§ 3.1 New Keywords for width and height](https://www.w3.org/TR/css-sizing-3/#sizing-values)
+///
+/// "Value: auto | `<length-percentage [0,∞]>` | min-content | max-content |
+/// fit-content(`<length-percentage [0,∞]>`)"
+///
+/// NOTE: Only the bare `fit-content` keyword (from CSS Sizing 4) is
+/// supported, not the `fit-content()` function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum IntrinsicSize {
+    /// "Use the min-content inline size or min-content block size, as
+    /// appropriate to the writing mode."
+    MinContent,
+    /// "Use the max-content inline size or max-content block size, as
+    /// appropriate to the writing mode."
+    MaxContent,
+    /// [CSS Sizing 4 § 3.1](https://www.w3.org/TR/css-sizing-4/#sizing-values)
+    ///
+    /// "Use the fit-content inline size or fit-content block size, as
+    /// appropriate to the writing mode", i.e.
+    /// min(max-content, max(min-content, stretch-fit)).
+    FitContent,
+}
+
+impl IntrinsicSize {
+    /// Parse an intrinsic sizing keyword (ASCII case-insensitive).
+    #[must_use]
+    pub const fn from_keyword(keyword: &str) -> Option<Self> {
+        if keyword.eq_ignore_ascii_case("min-content") {
+            Some(Self::MinContent)
+        } else if keyword.eq_ignore_ascii_case("max-content") {
+            Some(Self::MaxContent)
+        } else if keyword.eq_ignore_ascii_case("fit-content") {
+            Some(Self::FitContent)
+        } else {
+            None
+        }
+    }
+
+    /// The CSS keyword for this size.
+    #[must_use]
+    pub const fn keyword(self) -> &'static str {
+        match self {
+            Self::MinContent => "min-content",
+            Self::MaxContent => "max-content",
+            Self::FitContent => "fit-content",
+        }
+    }
 }
 
 impl AutoLength {
@@ -168,7 +507,8 @@ impl AutoLength {
         matches!(self, Self::Auto)
     }
 
-    /// Get the length value in pixels, or 0.0 if 'auto'.
+    /// Get the length value in pixels, or 0.0 if 'auto' or an intrinsic
+    /// sizing keyword.
     ///
     /// NOTE: When 'auto', this returns 0.0 as a fallback. The actual
     /// resolved value depends on the layout algorithm (e.g., centering
@@ -176,7 +516,7 @@ impl AutoLength {
     #[must_use]
     pub fn to_px(&self) -> f64 {
         match self {
-            Self::Auto => 0.0,
+            Self::Auto | Self::Intrinsic(_) => 0.0,
             Self::Length(len) => len.to_px(),
         }
     }
@@ -204,12 +544,30 @@ pub fn parse_single_length(v: &ComponentValue) -> Option<LengthValue> {
                 Some(LengthValue::Px(*value))
             } else if unit.eq_ignore_ascii_case("em") {
                 Some(LengthValue::Em(*value))
+            } else if unit.eq_ignore_ascii_case("rem") {
+                Some(LengthValue::Rem(*value))
             } else if unit.eq_ignore_ascii_case("ch") {
                 Some(LengthValue::Ch(*value))
             } else if unit.eq_ignore_ascii_case("vw") {
                 Some(LengthValue::Vw(*value))
             } else if unit.eq_ignore_ascii_case("vh") {
                 Some(LengthValue::Vh(*value))
+            } else if unit.eq_ignore_ascii_case("vmin") {
+                Some(LengthValue::Vmin(*value))
+            } else if unit.eq_ignore_ascii_case("vmax") {
+                Some(LengthValue::Vmax(*value))
+            } else if unit.eq_ignore_ascii_case("cm") {
+                Some(LengthValue::Cm(*value))
+            } else if unit.eq_ignore_ascii_case("mm") {
+                Some(LengthValue::Mm(*value))
+            } else if unit.eq_ignore_ascii_case("q") {
+                Some(LengthValue::Q(*value))
+            } else if unit.eq_ignore_ascii_case("in") {
+                Some(LengthValue::In(*value))
+            } else if unit.eq_ignore_ascii_case("pc") {
+                Some(LengthValue::Pc(*value))
+            } else if unit.eq_ignore_ascii_case("pt") {
+                Some(LengthValue::Pt(*value))
             } else {
                 warn_once("CSS", &format!("unsupported unit '{unit}'"));
                 None
@@ -224,10 +582,46 @@ pub fn parse_single_length(v: &ComponentValue) -> Option<LengthValue> {
         ComponentValue::Token(CSSToken::Number { value, .. }) if *value == 0.0 => {
             Some(LengthValue::Px(0.0))
         }
+        ComponentValue::Function { name, value } => {
+            parse_math_function(name, value).map(LengthValue::Math)
+        }
         _ => None,
     }
 }
 
+/// [§ 10.2 Comparison Functions: min(), max(), and clamp()](https://www.w3.org/TR/css-values-4/#comp-func)
+///
+/// ```text
+/// <min()>   = min( <calc-sum># )
+/// <max()>   = max( <calc-sum># )
+/// <clamp()> = clamp( [ <calc-sum> | none ], <calc-sum>, [ <calc-sum> | none ] )
+/// ```
+///
+/// Each argument must be a single length; see [`MathFunction`].
+fn parse_math_function(name: &str, value: &[ComponentValue]) -> Option<MathFunction> {
+    let kind = if name.eq_ignore_ascii_case("min") {
+        MathFunctionKind::Min
+    } else if name.eq_ignore_ascii_case("max") {
+        MathFunctionKind::Max
+    } else if name.eq_ignore_ascii_case("clamp") {
+        MathFunctionKind::Clamp
+    } else {
+        return None;
+    };
+
+    let mut args = Vec::new();
+    for arg in value.split(|v| matches!(v, ComponentValue::Token(CSSToken::Comma))) {
+        let mut parts = arg
+            .iter()
+            .filter(|v| !matches!(v, ComponentValue::Token(CSSToken::Whitespace)));
+        let (Some(part), None) = (parts.next(), parts.next()) else {
+            return None;
+        };
+        args.push(parse_single_length(part)?);
+    }
+    MathFunction::new(kind, &args)
+}
+
 /// [§ 8.3 Margin properties](https://www.w3.org/TR/CSS2/box.html#margin-properties)
 /// Parse a value that can be either 'auto' or a length.
 #[must_use]
@@ -250,3 +644,17 @@ pub fn parse_single_auto_length(v: &ComponentValue) -> Option<AutoLength> {
     }
     parse_single_length(v).map(AutoLength::Length)
 }
+
+/// [§ 3.1 New Keywords for width and height](https://www.w3.org/TR/css-sizing-3/#sizing-values)
+///
+/// Parse a single component value as a `width`/`height` value: 'auto', a
+/// length, or an intrinsic sizing keyword.
+#[must_use]
+pub fn parse_single_size(v: &ComponentValue) -> Option<AutoLength> {
+    if let ComponentValue::Token(CSSToken::Ident(ident)) = v
+        && let Some(size) = IntrinsicSize::from_keyword(ident)
+    {
+        return Some(AutoLength::Intrinsic(size));
+    }
+    parse_single_auto_length(v)
+}
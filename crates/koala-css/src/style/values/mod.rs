@@ -8,6 +8,7 @@
 //! - [CSS Text Decoration Level 3](https://www.w3.org/TR/css-text-decoration-3/)
 //! - [CSS 2.1 Visual Formatting Model](https://www.w3.org/TR/CSS2/visuren.html)
 
+mod background;
 mod border;
 mod color;
 mod float;
@@ -17,16 +18,29 @@ mod length;
 mod position;
 mod text;
 
+pub use background::{
+    BackgroundImage, BackgroundLayer, BackgroundPosition, BackgroundRepeat, BackgroundSize,
+    RepeatStyle, parse_background_image, parse_background_layer, parse_background_list,
+    parse_background_position, parse_background_repeat, parse_background_size,
+    split_background_layers,
+};
 pub use border::{BorderRadius, BorderValue, BoxShadow};
 pub use color::{ColorValue, parse_color_value, parse_single_color};
 pub use float::{ClearSide, FloatSide};
-pub use font::{FontStyle, parse_font_family, parse_font_weight, parse_line_height};
+pub use font::{
+    FontFamily, FontStyle, GenericFontFamily, LineHeight, bolder_font_weight, lighter_font_weight,
+    parse_font_family, parse_font_weight, parse_line_height,
+};
 pub use helpers::{
     contains_keyword, first_keyword, first_number, first_percentage, first_px_length,
 };
 pub use length::{
-    AutoLength, DEFAULT_FONT_SIZE_PX, LengthValue, parse_auto_length_value, parse_length_value,
-    parse_single_auto_length, parse_single_length,
+    AutoLength, DEFAULT_FONT_SIZE_PX, IntrinsicSize, LengthUnit, LengthValue, MathFunction,
+    MathFunctionKind, parse_auto_length_value, parse_length_value, parse_single_auto_length,
+    parse_single_length, parse_single_size,
 };
 pub use position::PositionType;
-pub use text::{TextAlign, TextDecorationLine, parse_letter_spacing};
+pub use text::{
+    TextAlign, TextDecoration, TextDecorationLine, TextDecorationStyle, VerticalAlign,
+    parse_letter_spacing, parse_vertical_align,
+};
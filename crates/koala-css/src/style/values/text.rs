@@ -1,12 +1,15 @@
 //! Text-related CSS keyword values.
 //!
 //! - [CSS 2.1 § 16.2 `text-align`](https://www.w3.org/TR/CSS2/text.html#alignment-prop)
+//! - [CSS 2.1 § 10.8.1 `vertical-align`](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
 //! - [CSS Text Decoration Level 3 § 3 `text-decoration-line`](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
+//! - [CSS Text Decoration Level 3 § 2.2 `text-decoration-style`](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-style-property)
 
 use serde::Serialize;
 
+use super::ColorValue;
 use crate::ComponentValue;
-use crate::style::values::helpers::{contains_keyword, first_px_length};
+use crate::style::values::helpers::{contains_keyword, first_keyword, first_px_length};
 
 /// [§ 16.2 Alignment: the 'text-align' property](https://www.w3.org/TR/CSS2/text.html#alignment-prop)
 ///
@@ -63,6 +66,183 @@ pub struct TextDecorationLine {
     pub line_through: bool,
 }
 
+impl TextDecorationLine {
+    /// Whether any line is drawn (the value is not `none`).
+    #[must_use]
+    pub const fn any(self) -> bool {
+        self.underline || self.overline || self.line_through
+    }
+}
+
+/// [§ 2.2 Text Decoration Style](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-style-property)
+///
+/// "This property specifies the style of the line(s) drawn for text
+/// decoration specified on the element."
+///
+/// "Values: solid | double | dotted | dashed | wavy"
+///
+/// "Values have the same meaning as for the border styles" except `wavy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum TextDecorationStyle {
+    /// "A single line segment."
+    #[default]
+    Solid,
+    /// "Two parallel solid lines with some space between them."
+    Double,
+    /// "A series of round dots."
+    Dotted,
+    /// "A series of square-ended dashes."
+    Dashed,
+    /// "A wavy line."
+    Wavy,
+}
+
+impl TextDecorationStyle {
+    /// Parse a `text-decoration-style` keyword, ASCII case-insensitively.
+    #[must_use]
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword.to_ascii_lowercase().as_str() {
+            "solid" => Some(Self::Solid),
+            "double" => Some(Self::Double),
+            "dotted" => Some(Self::Dotted),
+            "dashed" => Some(Self::Dashed),
+            "wavy" => Some(Self::Wavy),
+            _ => None,
+        }
+    }
+}
+
+/// [§ 2 Line Decorations](https://www.w3.org/TR/css-text-decoration-3/#line-decoration)
+///
+/// The decoration a decorating box draws across its text: its
+/// `text-decoration-line`, `text-decoration-style` and used
+/// `text-decoration-color`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TextDecoration {
+    /// [§ 3 'text-decoration-line'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-line-property)
+    pub line: TextDecorationLine,
+    /// [§ 2.2 'text-decoration-style'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-style-property)
+    pub style: TextDecorationStyle,
+    /// [§ 2.3 'text-decoration-color'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-color-property)
+    ///
+    /// "This property specifies the color of text decoration (underlines,
+    /// overlines, and line-throughs) set on the element."
+    ///
+    /// Already resolved: `currentcolor` is the decorating box's `color`.
+    pub color: ColorValue,
+}
+
+impl Default for TextDecoration {
+    /// No lines; the style and color are their initial values, with
+    /// `currentcolor` falling back to black.
+    fn default() -> Self {
+        Self {
+            line: TextDecorationLine::default(),
+            style: TextDecorationStyle::default(),
+            color: ColorValue::BLACK,
+        }
+    }
+}
+
+/// [§ 10.8.1 Leading and half-leading](https://www.w3.org/TR/CSS2/visudet.html#leading)
+///
+/// "The 'vertical-align' property affects the vertical positioning inside
+/// a line box of the boxes generated by an inline-level element."
+///
+/// "Values for this property have different meanings in the context of
+/// tables. Please consult the section on vertical alignment in tables
+/// for details."
+///
+/// "Values (for inline elements) have the following meanings:
+///
+/// baseline
+///   Align the baseline of the box with the baseline of the parent box.
+///
+/// middle
+///   Align the vertical midpoint of the box with the baseline of the
+///   parent box plus half the x-height of the parent.
+///
+/// sub
+///   Lower the baseline of the box to the proper position for subscripts.
+///
+/// super
+///   Raise the baseline of the box to the proper position for superscripts.
+///
+/// text-top
+///   Align the top of the box with the top of the parent's content area.
+///
+/// text-bottom
+///   Align the bottom of the box with the bottom of the parent's content area.
+///
+/// top
+///   Align the top of the aligned subtree with the top of the line box.
+///
+/// bottom
+///   Align the bottom of the aligned subtree with the bottom of the line box.
+///
+/// `<percentage>`
+///   Raise (positive) or lower (negative) the box by this distance
+///   (a percentage of the 'line-height' value).
+///
+/// `<length>`
+///   Raise (positive) or lower (negative) the box by this distance."
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub enum VerticalAlign {
+    /// "Align the baseline of the box with the baseline of the parent box."
+    #[default]
+    Baseline,
+    /// "Align the vertical midpoint of the box with the baseline plus half x-height."
+    Middle,
+    /// "Lower the baseline of the box for subscripts."
+    Sub,
+    /// "Raise the baseline of the box for superscripts."
+    Super,
+    /// "Align the top with the top of the parent's content area."
+    TextTop,
+    /// "Align the bottom with the bottom of the parent's content area."
+    TextBottom,
+    /// "Align the top of the aligned subtree with the top of the line box."
+    Top,
+    /// "Align the bottom of the aligned subtree with the bottom of the line box."
+    Bottom,
+    /// "Raise (positive value) or lower (negative value) the box by this
+    /// distance." Stored in pixels.
+    Length(f32),
+}
+
+/// [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+///
+/// Parse `vertical-align` as one of its keywords. A `<length>` or
+/// `<percentage>` depends on the element's font-size and line-height, so
+/// [`crate::ComputedStyle`] resolves those itself.
+#[must_use]
+pub fn parse_vertical_align(values: &[ComponentValue]) -> Option<VerticalAlign> {
+    let keyword = first_keyword(
+        values,
+        &[
+            "baseline",
+            "middle",
+            "sub",
+            "super",
+            "text-top",
+            "text-bottom",
+            "top",
+            "bottom",
+        ],
+    );
+    match keyword {
+        Some("baseline") => Some(VerticalAlign::Baseline),
+        Some("middle") => Some(VerticalAlign::Middle),
+        Some("sub") => Some(VerticalAlign::Sub),
+        Some("super") => Some(VerticalAlign::Super),
+        Some("text-top") => Some(VerticalAlign::TextTop),
+        Some("text-bottom") => Some(VerticalAlign::TextBottom),
+        Some("top") => Some(VerticalAlign::Top),
+        Some("bottom") => Some(VerticalAlign::Bottom),
+        _ => None,
+    }
+}
+
 /// [§ 9.3 `letter-spacing`](https://www.w3.org/TR/css-text-3/#letter-spacing-property)
 ///
 /// Parse `letter-spacing` as either `normal` (zero additional space) or
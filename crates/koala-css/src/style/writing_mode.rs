@@ -106,8 +106,46 @@ impl WritingMode {
     }
 
     // NOTE: inline-start and inline-end also depend on `direction` (ltr/rtl).
-    // For now, we only implement block directions. Inline direction support
-    // will require adding the `direction` property.
+    // For now, we only implement block directions; logical inline
+    // properties do not yet consult `Direction`.
+}
+
+/// [§ 2.1 Specifying Directionality: the direction property](https://www.w3.org/TR/css-writing-modes-4/#direction)
+///
+/// "This property specifies the inline base direction or directionality of
+/// any bidi paragraph, embedding, isolate, or override established by the
+/// box."
+///
+/// Values: ltr | rtl
+/// Initial: ltr
+/// Inherited: yes
+///
+/// NOTE: Only the base direction of a block's lines is implemented: in
+/// `rtl` the inline content of a line is placed from the right edge. There
+/// is no bidi reordering of mixed-direction text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Default)]
+pub enum Direction {
+    /// "Left-to-right directionality."
+    #[default]
+    Ltr,
+    /// "Right-to-left directionality."
+    Rtl,
+}
+
+/// [§ 2.1](https://www.w3.org/TR/css-writing-modes-4/#direction)
+///
+/// Parse a direction value from component values.
+#[must_use]
+pub fn parse_direction(values: &[ComponentValue]) -> Option<Direction> {
+    values.iter().find_map(|v| match v {
+        ComponentValue::Token(CSSToken::Ident(ident)) if ident.eq_ignore_ascii_case("ltr") => {
+            Some(Direction::Ltr)
+        }
+        ComponentValue::Token(CSSToken::Ident(ident)) if ident.eq_ignore_ascii_case("rtl") => {
+            Some(Direction::Rtl)
+        }
+        _ => None,
+    })
 }
 
 /// [§ 2 Block Flow Direction](https://www.w3.org/TR/css-writing-modes-4/#block-flow)
@@ -0,0 +1,148 @@
+//! Feature queries.
+//!
+//! [CSS Conditional Rules Level 3 § 6](https://www.w3.org/TR/css-conditional-3/#at-supports)
+//!
+//! "The @supports rule is a conditional group rule whose condition tests
+//! whether the user agent supports CSS property:value pairs."
+//!
+//! A declaration is supported when the [`ComputedStyle`] property
+//! dispatcher recognizes both the property and the value.
+
+use crate::parser::{ComponentValue, Declaration};
+use crate::style::ComputedStyle;
+use crate::tokenizer::CSSToken;
+
+/// [§ 6.1 Definition of support](https://www.w3.org/TR/css-conditional-3/#support-definition)
+///
+/// Evaluate an `@supports` prelude. The rules in the block apply only when
+/// this is true.
+///
+/// A prelude that doesn't match the `<supports-condition>` grammar
+/// evaluates to false.
+#[must_use]
+pub fn supports_condition_matches(prelude: &[ComponentValue]) -> bool {
+    supports_condition(prelude).unwrap_or(false)
+}
+
+/// [§ 6 Feature queries](https://www.w3.org/TR/css-conditional-3/#at-supports)
+///
+/// ```text
+/// <supports-condition> = not <supports-in-parens>
+///                      | <supports-in-parens> [ and <supports-in-parens> ]*
+///                      | <supports-in-parens> [ or <supports-in-parens> ]*
+/// ```
+///
+/// Returns `None` when `values` doesn't match the grammar.
+fn supports_condition(values: &[ComponentValue]) -> Option<bool> {
+    let mut parts = values.iter().filter(|value| !is_whitespace(value));
+
+    let first = parts.next()?;
+    if ident(first).is_some_and(|keyword| keyword.eq_ignore_ascii_case("not")) {
+        // "not: The result is the negation of the <supports-in-parens> term."
+        let operand = supports_in_parens(parts.next()?)?;
+        return parts.next().is_none().then_some(!operand);
+    }
+
+    let mut result = supports_in_parens(first)?;
+    let mut combinator: Option<&str> = None;
+    while let Some(keyword) = parts.next() {
+        let keyword = ident(keyword)?;
+        // "To avoid confusion between and and or, the syntax requires that
+        // both and and or be specified explicitly (rather than, say, using
+        // commas or spaces for one of them). Likewise, to avoid confusion
+        // caused by precedence rules, the syntax does not allow and, or,
+        // and not operators to be mixed without a layer of parentheses."
+        if combinator.is_some_and(|previous| !previous.eq_ignore_ascii_case(keyword)) {
+            return None;
+        }
+        let operand = supports_in_parens(parts.next()?)?;
+        if keyword.eq_ignore_ascii_case("and") {
+            // "and: The result is true if all of the <supports-in-parens>
+            // child terms are true, and false otherwise."
+            result &= operand;
+        } else if keyword.eq_ignore_ascii_case("or") {
+            // "or: The result is false if all of the <supports-in-parens>
+            // child terms are false, and true otherwise."
+            result |= operand;
+        } else {
+            return None;
+        }
+        combinator = Some(keyword);
+    }
+    Some(result)
+}
+
+/// ```text
+/// <supports-in-parens> = ( <supports-condition> ) | <supports-feature>
+///                      | <general-enclosed>
+/// <supports-decl> = ( <declaration> )
+/// ```
+///
+/// `<general-enclosed>` terms, such as unknown functions, are well-formed
+/// but always evaluate to false.
+fn supports_in_parens(value: &ComponentValue) -> Option<bool> {
+    match value {
+        ComponentValue::Block { token: '(', value } => {
+            let start = value.iter().position(|v| !is_whitespace(v))?;
+            let inner = &value[start..];
+            let is_declaration = ident(&inner[0]).is_some()
+                && inner[1..]
+                    .iter()
+                    .find(|v| !is_whitespace(v))
+                    .is_some_and(|v| matches!(v, ComponentValue::Token(CSSToken::Colon)));
+            if is_declaration {
+                Some(declaration_is_supported(inner))
+            } else {
+                Some(supports_condition(inner).unwrap_or(false))
+            }
+        }
+        ComponentValue::Function { .. } => Some(false),
+        _ => None,
+    }
+}
+
+/// [§ 6.1 Definition of support](https://www.w3.org/TR/css-conditional-3/#support-definition)
+///
+/// "A CSS processor is considered to support a declaration (consisting of
+/// a property and value) if it accepts that declaration (rather than
+/// discarding it as a parse error)."
+///
+/// `values` is the body of a `( <declaration> )` term, starting with the
+/// property name.
+fn declaration_is_supported(values: &[ComponentValue]) -> bool {
+    let Some(name) = ident(&values[0]) else {
+        return false;
+    };
+    let Some(colon) = values
+        .iter()
+        .position(|v| matches!(v, ComponentValue::Token(CSSToken::Colon)))
+    else {
+        return false;
+    };
+    let value: Vec<ComponentValue> = values[colon + 1..]
+        .iter()
+        .skip_while(|v| is_whitespace(v))
+        .cloned()
+        .collect();
+    if value.iter().all(is_whitespace) {
+        return false;
+    }
+
+    ComputedStyle::default().try_apply_declaration(&Declaration {
+        name: name.to_string(),
+        value,
+        important: false,
+        source_order: 0,
+    })
+}
+
+const fn is_whitespace(value: &ComponentValue) -> bool {
+    matches!(value, ComponentValue::Token(CSSToken::Whitespace))
+}
+
+fn ident(value: &ComponentValue) -> Option<&str> {
+    match value {
+        ComponentValue::Token(CSSToken::Ident(name)) => Some(name),
+        _ => None,
+    }
+}
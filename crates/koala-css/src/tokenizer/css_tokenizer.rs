@@ -1,3 +1,5 @@
+use koala_common::warning::warn_once;
+
 use super::token::{CSSToken, HashType, NumericType};
 
 /// [§ 4.3 Tokenizer Algorithms](https://www.w3.org/TR/css-syntax-3/#tokenizer-algorithms)
@@ -23,6 +25,24 @@ impl CSSTokenizer {
         }
     }
 
+    /// Tokenize `input` in one call: `new`, [`run`](Self::run), then
+    /// [`into_tokens`](Self::into_tokens).
+    ///
+    /// ```
+    /// use koala_css::CSSTokenizer;
+    ///
+    /// let input = "p { color: red; }";
+    /// let mut tokenizer = CSSTokenizer::new(input);
+    /// tokenizer.run();
+    /// assert_eq!(CSSTokenizer::tokenize(input), tokenizer.into_tokens());
+    /// ```
+    #[must_use]
+    pub fn tokenize(input: &str) -> Vec<CSSToken> {
+        let mut tokenizer = Self::new(input);
+        tokenizer.run();
+        tokenizer.into_tokens()
+    }
+
     /// [§ 4.3.1 Consume a token](https://www.w3.org/TR/css-syntax-3/#consume-token)
     ///
     /// "This section describes how to consume a token from a stream of code points.
@@ -255,7 +275,13 @@ impl CSSTokenizer {
     /// "If the next two input code points are U+002F SOLIDUS (/) followed by
     /// U+002A ASTERISK (*), consume them and all following code points up to
     /// and including the first U+002A ASTERISK (*) followed by U+002F SOLIDUS (/),
-    /// or up to an EOF code point."
+    /// or up to an EOF code point. Return to the start of this step."
+    ///
+    /// "If the preceding paragraph ended by consuming an EOF code point,
+    /// this is a parse error."
+    ///
+    /// NOTE: An unterminated comment swallows the rest of the input and
+    /// produces no token; the next `consume_token` call sees EOF.
     fn consume_comments(&mut self) {
         while self.peek() == Some('/') && self.peek_at(1) == Some('*') {
             let _ = self.consume(); // /
@@ -268,7 +294,11 @@ impl CSSTokenizer {
                         break;
                     }
                     Some(_) => {}
-                    None => break, // EOF
+                    // "...or up to an EOF code point."
+                    None => {
+                        warn_once("CSS", "unterminated comment at end of input");
+                        break;
+                    }
                 }
             }
         }
@@ -459,7 +489,7 @@ impl CSSTokenizer {
                 // or "non-printable code point"
                 // "This is a parse error. Consume the remnants of a bad url, create a
                 // <bad-url-token>, and return it."
-                Some('"' | '\'' | '(') => {
+                Some(c) if matches!(c, '"' | '\'' | '(') || is_non_printable_code_point(c) => {
                     self.consume_bad_url_remnants();
                     return CSSToken::BadUrl;
                 }
@@ -738,6 +768,15 @@ const fn is_whitespace(c: char) -> bool {
     matches!(c, '\n' | '\t' | ' ' | '\r' | '\x0C')
 }
 
+/// [§ 4.2 Definitions - non-printable code point](https://www.w3.org/TR/css-syntax-3/#non-printable-code-point)
+///
+/// "A code point between U+0000 NULL and U+0008 BACKSPACE inclusive, or
+/// U+000B LINE TABULATION, or a code point between U+000E SHIFT OUT and
+/// U+001F INFORMATION SEPARATOR ONE inclusive, or U+007F DELETE."
+const fn is_non_printable_code_point(c: char) -> bool {
+    matches!(c, '\u{0}'..='\u{8}' | '\u{B}' | '\u{E}'..='\u{1F}' | '\u{7F}')
+}
+
 /// [§ 4.2 Definitions - ident-start code point](https://www.w3.org/TR/css-syntax-3/#ident-start-code-point)
 ///
 /// "A letter, a non-ASCII code point, or U+005F LOW LINE (_)."
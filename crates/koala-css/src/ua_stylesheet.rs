@@ -20,9 +20,10 @@ use crate::tokenizer::CSSTokenizer;
 
 /// [WHATWG HTML § 15.3 Rendering — Suggested default style sheet](https://html.spec.whatwg.org/multipage/rendering.html#the-css-user-agent-style-sheet-and-presentational-hints)
 ///
-/// Default CSS rules for HTML elements. This is a subset of the full UA
-/// stylesheet covering the elements Koala currently supports.
-const UA_CSS: &str = r#"
+/// The `display` defaults for HTML elements. These are all
+/// [`ua_stylesheet_minimal`] contains; the full [`ua_stylesheet`] adds
+/// [`UA_CSS`] on top.
+const UA_DISPLAY_CSS: &str = r#"
 /* [§ 15.3.1 Hidden elements](https://html.spec.whatwg.org/multipage/rendering.html#hidden-elements) */
 /* "The following elements must have their 'display' property set to 'none'." */
 area, base, basefont, datalist, head, link, meta, noembed,
@@ -46,6 +47,23 @@ li {
     display: list-item;
 }
 
+/* [§ 15.5.12–15.5.15 Form controls](https://html.spec.whatwg.org/multipage/rendering.html#the-input-element-as-a-form-control) */
+input, textarea, select, button {
+    display: inline-block;
+}
+
+/* [§ 15.3.10 Tables](https://html.spec.whatwg.org/multipage/rendering.html#tables-2) */
+table {
+    display: table;
+}
+"#;
+
+/// [WHATWG HTML § 15.3 Rendering — Suggested default style sheet](https://html.spec.whatwg.org/multipage/rendering.html#the-css-user-agent-style-sheet-and-presentational-hints)
+///
+/// Default CSS rules for HTML elements. This is a subset of the full UA
+/// stylesheet covering the elements Koala currently supports, apart from
+/// the `display` defaults in [`UA_DISPLAY_CSS`].
+const UA_CSS: &str = r#"
 /* [§ 15.3.6 Sections and headings](https://html.spec.whatwg.org/multipage/rendering.html#sections-and-headings) */
 
 /* "h1 { ... font-weight: bold; font-size: 2.00em; margin-block-start: 0.67em; margin-block-end: 0.67em; }" */
@@ -141,6 +159,11 @@ ol {
     list-style-type: decimal;
 }
 
+/* "ol, ul, menu { counter-reset: list-item; }" */
+ol, ul, menu {
+    counter-reset: list-item;
+}
+
 /* [§ 15.3.4 The page](https://html.spec.whatwg.org/multipage/rendering.html#the-page) */
 /* "body { margin: 8px; }" */
 body {
@@ -150,10 +173,8 @@ body {
 /* [§ 15.3.8 Text-level semantics](https://html.spec.whatwg.org/multipage/rendering.html#text-level-semantics) */
 
 /* "b, strong { font-weight: bolder; }" */
-/* NOTE: Using "bold" instead of "bolder" because our parse_font_weight()
-   does not yet handle relative keywords. Functionally equivalent here. */
 b, strong {
-    font-weight: bold;
+    font-weight: bolder;
 }
 
 /* "i, cite, em, var, dfn { font-style: italic; }" */
@@ -161,6 +182,15 @@ em, i, cite, dfn, var {
     font-style: italic;
 }
 
+/* "sub { vertical-align: sub; } sup { vertical-align: super; }" */
+sub {
+    vertical-align: sub;
+}
+
+sup {
+    vertical-align: super;
+}
+
 /* [§ 15.3.5 Grouping content](https://html.spec.whatwg.org/multipage/rendering.html#grouping-content) */
 /* "pre, listing, xmp { ... white-space: pre; }" */
 pre, listing, xmp {
@@ -185,9 +215,13 @@ a {
     color: #0000EE;
 }
 
+/* NOTE: Not in the rendering section; browsers show a pointer over links. */
+a:link {
+    cursor: pointer;
+}
+
 /* [§ 15.5.12–15.5.15 Form controls](https://html.spec.whatwg.org/multipage/rendering.html#the-input-element-as-a-form-control) */
 input, textarea, select, button {
-    display: inline-block;
     border: 2px inset;
     padding: 1px 2px;
 }
@@ -198,7 +232,12 @@ button {
 
 /* [§ 15.3.10 Tables](https://html.spec.whatwg.org/multipage/rendering.html#tables-2) */
 table {
-    display: table;
+    border-spacing: 2px;
+    border-collapse: separate;
+}
+
+caption {
+    text-align: center;
 }
 
 td, th {
@@ -221,9 +260,26 @@ th {
 pub fn ua_stylesheet() -> &'static Stylesheet {
     static STYLESHEET: OnceLock<Stylesheet> = OnceLock::new();
     STYLESHEET.get_or_init(|| {
-        let mut tokenizer = CSSTokenizer::new(UA_CSS.to_string());
-        tokenizer.run();
-        let mut parser = CSSParser::new(tokenizer.into_tokens());
-        parser.parse_stylesheet()
+        let mut rules = parse(UA_DISPLAY_CSS).rules;
+        rules.extend(parse(UA_CSS).rules);
+        Stylesheet { rules }
     })
 }
+
+/// Return a UA stylesheet holding only the `display` defaults, parsing
+/// only once.
+///
+/// Hidden elements stay hidden and block-level elements stay blocks, but
+/// there are no margins, fonts, list indents or form control borders —
+/// useful to embedders and tests that want to style a document from a
+/// blank slate. Pass it to [`crate::cascade::compute_styles`] in place of
+/// [`ua_stylesheet`].
+pub fn ua_stylesheet_minimal() -> &'static Stylesheet {
+    static STYLESHEET: OnceLock<Stylesheet> = OnceLock::new();
+    STYLESHEET.get_or_init(|| parse(UA_DISPLAY_CSS))
+}
+
+fn parse(css: &str) -> Stylesheet {
+    let mut parser = CSSParser::new(CSSTokenizer::tokenize(css));
+    parser.parse_stylesheet()
+}
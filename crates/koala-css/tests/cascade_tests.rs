@@ -8,10 +8,13 @@
     clippy::uninlined_format_args
 )]
 
-use koala_css::Stylesheet;
 use koala_css::cascade::compute_styles;
 use koala_css::parser::CSSParser;
-use koala_css::tokenizer::CSSTokenizer;
+use koala_css::tokenizer::{CSSToken, CSSTokenizer};
+use koala_css::{
+    BackgroundImage, BackgroundPosition, BackgroundRepeat, BackgroundSize, DEFAULT_FONT_SIZE_PX,
+    LineHeight, RepeatStyle, Stylesheet,
+};
 use koala_dom::{AttributesMap, DomTree, ElementData, NodeId, NodeType};
 
 fn parse_css(css: &str) -> Stylesheet {
@@ -64,7 +67,13 @@ fn test_compute_styles_simple() {
     let body_id = tree.alloc(make_element("body", None, &[]));
     tree.append_child(NodeId::ROOT, body_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     // Document has no style
     assert!(!styles.contains_key(&NodeId::ROOT));
@@ -89,7 +98,13 @@ fn test_compute_styles_inheritance() {
     tree.append_child(NodeId::ROOT, body_id);
     tree.append_child(body_id, p_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     // P should inherit color from body
     let p_style = styles.get(&p_id).unwrap();
@@ -108,7 +123,13 @@ fn test_compute_styles_specificity() {
     let p_id = tree.alloc(make_element("p", None, &["highlight"]));
     tree.append_child(NodeId::ROOT, p_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let p_style = styles.get(&p_id).unwrap();
     let color = p_style.color.as_ref().unwrap();
@@ -126,7 +147,13 @@ fn test_compute_styles_id_selector() {
     let div_id = tree.alloc(make_element("div", Some("main-content"), &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let div_style = styles.get(&div_id).unwrap();
     assert!(div_style.background_color.is_some());
@@ -146,7 +173,13 @@ fn test_background_color_not_inherited() {
     tree.append_child(NodeId::ROOT, body_id);
     tree.append_child(body_id, p_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     // Body should have background-color
     let body_style = styles.get(&body_id).unwrap();
@@ -159,6 +192,184 @@ fn test_background_color_not_inherited() {
     assert!(p_style.background_color.is_none());
 }
 
+#[test]
+fn test_nth_child_of_selector_keeps_the_rest_of_its_list() {
+    // [§ 4.13.1 :nth-child()](https://www.w3.org/TR/selectors-4/#the-nth-child-pseudo)
+    let stylesheet = parse_css("li:nth-child(2 of .x), li.y { color: #ff0000; }");
+
+    let mut tree = DomTree::new();
+    let ul_id = tree.alloc(make_element("ul", None, &[]));
+    let li_id = tree.alloc(make_element("li", None, &["y"]));
+    tree.append_child(NodeId::ROOT, ul_id);
+    tree.append_child(ul_id, li_id);
+
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
+    let color = styles.get(&li_id).unwrap().color.as_ref().unwrap();
+    assert_eq!((color.r, color.g, color.b), (0xff, 0, 0));
+}
+
+/// Compute the style of a lone `<div>` under the given author CSS.
+fn div_style(css: &str) -> koala_css::ComputedStyle {
+    let stylesheet = parse_css(css);
+    let mut tree = DomTree::new();
+    let div_id = tree.alloc(make_element("div", None, &[]));
+    tree.append_child(NodeId::ROOT, div_id);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
+    styles.get(&div_id).unwrap().clone()
+}
+
+#[test]
+fn test_background_shorthand_color_only() {
+    // [§ 3.10 background](https://www.w3.org/TR/css-backgrounds-3/#background)
+    // "for each layer the shorthand first sets the corresponding layer of
+    // each of ... to that property's initial value"
+    let style = div_style("div { background-image: url(old.png); background: #fff; }");
+
+    let bg = style.background_color.as_ref().unwrap();
+    assert_eq!((bg.r, bg.g, bg.b), (0xff, 0xff, 0xff));
+    assert_eq!(style.background_image, Some(vec![BackgroundImage::None]));
+    assert_eq!(
+        style.background_repeat,
+        Some(vec![BackgroundRepeat::default()])
+    );
+    assert_eq!(
+        style.background_position,
+        Some(vec![BackgroundPosition {
+            x: koala_css::LengthValue::Percent(0.0),
+            y: koala_css::LengthValue::Percent(0.0),
+        }])
+    );
+    assert_eq!(style.background_size, Some(vec![BackgroundSize::default()]));
+}
+
+#[test]
+fn test_background_shorthand_image_and_repeat() {
+    // "Finally 'background-color' is set to the specified color, if any,
+    // else set to its initial value."
+    let style = div_style("div { background-color: red; background: url(tile.png) repeat-x; }");
+
+    assert!(style.background_color.is_none());
+    assert_eq!(
+        style.background_image,
+        Some(vec![BackgroundImage::Url("tile.png".to_string())])
+    );
+    assert_eq!(
+        style.background_repeat,
+        Some(vec![BackgroundRepeat {
+            x: RepeatStyle::Repeat,
+            y: RepeatStyle::NoRepeat,
+        }])
+    );
+}
+
+#[test]
+fn test_background_image_quoted_url_with_spaces() {
+    // [§ 4.5 Resource Locators](https://www.w3.org/TR/css-values-4/#urls)
+    let style = div_style(r#"div { background-image: url( "a b.png" ); }"#);
+    assert_eq!(
+        style.background_image,
+        Some(vec![BackgroundImage::Url("a b.png".to_string())])
+    );
+}
+
+#[test]
+fn test_background_shorthand_position_slash_size() {
+    // "<bg-position> [ / <bg-size> ]?"
+    let style = div_style(r#"div { background: url("a.png") no-repeat center / cover #000; }"#);
+
+    let bg = style.background_color.as_ref().unwrap();
+    assert_eq!((bg.r, bg.g, bg.b), (0, 0, 0));
+    assert_eq!(
+        style.background_position,
+        Some(vec![BackgroundPosition {
+            x: koala_css::LengthValue::Percent(50.0),
+            y: koala_css::LengthValue::Percent(50.0),
+        }])
+    );
+    assert_eq!(style.background_size, Some(vec![BackgroundSize::Cover]));
+
+    let style = div_style("div { background: url(a.png) left top / 10px auto; }");
+    assert_eq!(
+        style.background_size,
+        Some(vec![BackgroundSize::Explicit {
+            width: koala_css::AutoLength::Length(koala_css::LengthValue::Px(10.0)),
+            height: koala_css::AutoLength::Auto,
+        }])
+    );
+}
+
+#[test]
+fn test_background_shorthand_layers() {
+    // [§ 3.1 Layering Multiple Background Images](https://www.w3.org/TR/css-backgrounds-3/#layering)
+    let style = div_style("div { background: url(a.png) no-repeat, url(b.png) 10px 20px blue; }");
+
+    assert_eq!(
+        style.background_image,
+        Some(vec![
+            BackgroundImage::Url("a.png".to_string()),
+            BackgroundImage::Url("b.png".to_string()),
+        ])
+    );
+    assert_eq!(
+        style.background_position.as_ref().unwrap()[1],
+        BackgroundPosition {
+            x: koala_css::LengthValue::Px(10.0),
+            y: koala_css::LengthValue::Px(20.0),
+        }
+    );
+    assert_eq!(style.background_color.as_ref().unwrap().b, 0xff);
+}
+
+#[test]
+fn test_background_shorthand_color_only_in_final_layer() {
+    // "<final-bg-layer> = <bg-layer> || <'background-color'>": a color in
+    // any other layer makes the declaration invalid, so it is ignored.
+    let style = div_style("div { background: #f00; background: red url(a.png), url(b.png); }");
+
+    let bg = style.background_color.as_ref().unwrap();
+    assert_eq!((bg.r, bg.g, bg.b), (0xff, 0, 0));
+    assert_eq!(style.background_image, Some(vec![BackgroundImage::None]));
+}
+
+#[test]
+fn test_object_position_parses_position() {
+    // [§ 5.6 'object-position'](https://www.w3.org/TR/css-images-3/#the-object-position)
+    // "Value: <position>"
+    let style = div_style("div { object-position: top; }");
+    assert_eq!(
+        style.object_position,
+        Some(BackgroundPosition {
+            x: koala_css::LengthValue::Percent(50.0),
+            y: koala_css::LengthValue::Percent(0.0),
+        })
+    );
+
+    let style = div_style("div { font-size: 10px; object-position: right 2em; }");
+    assert_eq!(
+        style.object_position,
+        Some(BackgroundPosition {
+            x: koala_css::LengthValue::Percent(100.0),
+            y: koala_css::LengthValue::Px(20.0),
+        })
+    );
+
+    // Not inherited, and invalid values are ignored.
+    let style = div_style("div { object-position: top top; }");
+    assert_eq!(style.object_position, None);
+}
+
 #[test]
 fn test_line_height_inherited() {
     // [§ 4.2 line-height](https://www.w3.org/TR/css-inline-3/#line-height-property)
@@ -172,12 +383,155 @@ fn test_line_height_inherited() {
     tree.append_child(NodeId::ROOT, body_id);
     tree.append_child(body_id, p_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     // P should inherit line-height from body
     let p_style = styles.get(&p_id).unwrap();
-    assert!(p_style.line_height.is_some());
-    assert!((p_style.line_height.unwrap() - 1.6).abs() < 0.01);
+    assert_eq!(p_style.line_height, Some(LineHeight::Number(1.6)));
+}
+
+/// The computed `line-height` of a `<p>` with `font-size: 20px` inside a
+/// `<body>` styled by `body_css`.
+fn child_line_height(body_css: &str) -> Option<LineHeight> {
+    let stylesheet = parse_css(&format!("body {{ {body_css} }} p {{ font-size: 20px; }}"));
+
+    let mut tree = DomTree::new();
+    let body_id = tree.alloc(make_element("body", None, &[]));
+    let p_id = tree.alloc(make_element("p", None, &[]));
+    tree.append_child(NodeId::ROOT, body_id);
+    tree.append_child(body_id, p_id);
+
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
+    styles.get(&p_id).unwrap().line_height
+}
+
+#[test]
+fn test_line_height_percentage_inherits_computed_length() {
+    // [§ 4.2 line-height](https://www.w3.org/TR/css-inline-3/#line-height-property)
+    // "Percentages are relative to the computed font-size of the element
+    // itself." The percentage computes to a length on body, and the child
+    // inherits that length.
+    assert_eq!(
+        child_line_height("font-size: 10px; line-height: 150%;"),
+        Some(LineHeight::Length(koala_css::LengthValue::Px(15.0)))
+    );
+    assert_eq!(
+        child_line_height("font-size: 10px; line-height: 2em;"),
+        Some(LineHeight::Length(koala_css::LengthValue::Px(20.0)))
+    );
+}
+
+#[test]
+fn test_line_height_number_inherits_factor() {
+    // [§ 4.2 line-height](https://www.w3.org/TR/css-inline-3/#line-height-property)
+    // "Computed value: the keyword normal or a number or an absolute
+    // length"
+    assert_eq!(
+        child_line_height("font-size: 10px; line-height: 1.5;"),
+        Some(LineHeight::Number(1.5))
+    );
+    assert_eq!(
+        child_line_height("font: 10px/150% serif;"),
+        Some(LineHeight::Length(koala_css::LengthValue::Px(15.0)))
+    );
+}
+
+/// Names of the declarations in each top-level style rule, in rule order.
+fn rule_declaration_names(stylesheet: &Stylesheet) -> Vec<Vec<String>> {
+    stylesheet
+        .rules
+        .iter()
+        .filter_map(|rule| match rule {
+            koala_css::Rule::Style(style_rule) => Some(
+                style_rule
+                    .declarations
+                    .iter()
+                    .map(|decl| decl.name.clone())
+                    .collect(),
+            ),
+            koala_css::Rule::At(_) => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_stylesheet_from_str_matches_parser() {
+    let css = "p { color: red; } @media screen { div { color: blue; } }";
+    assert_eq!(Stylesheet::from_str(css), parse_css(css));
+}
+
+#[test]
+fn test_stylesheet_merge_rule_order() {
+    let first = Stylesheet::from_str("p { color: red; margin: 0; }");
+    let second = Stylesheet::from_str("div { display: block; } span { padding: 1px; }");
+
+    let merged = first.merge(second);
+    assert_eq!(
+        rule_declaration_names(&merged),
+        vec![
+            vec!["color".to_string(), "margin".to_string()],
+            vec!["display".to_string()],
+            vec!["padding".to_string()],
+        ]
+    );
+
+    // [§ 6.1 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
+    // Declarations from the second sheet must come after every declaration
+    // from the first, as if the sheets were concatenated.
+    let orders: Vec<u32> = merged
+        .rules
+        .iter()
+        .filter_map(|rule| match rule {
+            koala_css::Rule::Style(style_rule) => Some(style_rule),
+            koala_css::Rule::At(_) => None,
+        })
+        .flat_map(|style_rule| style_rule.declarations.iter().map(|decl| decl.source_order))
+        .collect();
+    assert!(
+        orders.windows(2).all(|pair| pair[0] < pair[1]),
+        "source order should increase across merged sheets: {orders:?}"
+    );
+}
+
+#[test]
+fn test_stylesheet_merge_later_sheet_wins() {
+    // margin-top and margin-block-start compete by source order, so the
+    // merged second sheet must win even though its declaration was parsed
+    // first within its own sheet.
+    let first = Stylesheet::from_str("div { color: red; margin-top: 10px; }");
+    let second = Stylesheet::from_str("div { margin-block-start: 30px; }");
+    let stylesheet = first.merge(second);
+
+    let mut tree = DomTree::new();
+    let div_id = tree.alloc(make_element("div", None, &[]));
+    tree.append_child(NodeId::ROOT, div_id);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
+
+    let div_style = styles.get(&div_id).unwrap();
+    assert_eq!(
+        div_style.margin_top,
+        Some(koala_css::AutoLength::Length(koala_css::LengthValue::Px(
+            30.0
+        )))
+    );
 }
 
 #[test]
@@ -189,7 +543,13 @@ fn test_margin_and_padding_shorthand() {
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let div_style = styles.get(&div_id).unwrap();
 
@@ -232,7 +592,13 @@ fn test_font_size_inherited() {
     tree.append_child(body_id, h1_id);
     tree.append_child(h1_id, span_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     // Span inside h1 should inherit h1's font-size (32px)
     let span_style = styles.get(&span_id).unwrap();
@@ -251,7 +617,13 @@ fn test_border_parsing() {
     let div_id = tree.alloc(make_element("div", Some("box"), &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let div_style = styles.get(&div_id).unwrap();
 
@@ -273,18 +645,38 @@ fn test_border_parsing() {
         koala_css::LengthValue::Em(_) => {
             panic!("Expected border width in Px, got Em (should have been resolved)")
         }
+        koala_css::LengthValue::Rem(_) => {
+            panic!("Expected border width in Px, got Rem (should have been resolved)")
+        }
         koala_css::LengthValue::Vw(_) => {
             panic!("Expected border width in Px, got Vw (should have been resolved)")
         }
         koala_css::LengthValue::Vh(_) => {
             panic!("Expected border width in Px, got Vh (should have been resolved)")
         }
+        koala_css::LengthValue::Vmin(_) => {
+            panic!("Expected border width in Px, got Vmin (should have been resolved)")
+        }
+        koala_css::LengthValue::Vmax(_) => {
+            panic!("Expected border width in Px, got Vmax (should have been resolved)")
+        }
         koala_css::LengthValue::Percent(_) => {
             panic!("Expected border width in Px, got Percent (should have been resolved)")
         }
         koala_css::LengthValue::Ch(_) => {
             panic!("Expected border width in Px, got Ch (should have been resolved)")
         }
+        koala_css::LengthValue::Cm(_)
+        | koala_css::LengthValue::Mm(_)
+        | koala_css::LengthValue::Q(_)
+        | koala_css::LengthValue::In(_)
+        | koala_css::LengthValue::Pc(_)
+        | koala_css::LengthValue::Pt(_) => {
+            panic!("Expected border width in Px, got a physical unit (should have been resolved)")
+        }
+        koala_css::LengthValue::Math(_) => {
+            panic!("Expected border width in Px, got a math function")
+        }
     }
     assert_eq!(border.style, "solid");
     assert_eq!(border.color.r, 0xdd);
@@ -352,7 +744,13 @@ fn test_simple_html_full_pipeline() {
     let stylesheet = css_parser.parse_stylesheet();
 
     // Compute styles
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     // Should have styles for multiple elements
     assert!(!styles.is_empty(), "Should have computed styles");
@@ -409,7 +807,13 @@ fn test_logical_property_cascade_order() {
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
     let div_style = styles.get(&div_id).unwrap();
 
     // margin-top should be 20px (the later declaration wins)
@@ -440,7 +844,13 @@ fn test_logical_property_cascade_order_reversed() {
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
     let div_style = styles.get(&div_id).unwrap();
 
     // margin-top should be 10px (margin-block-start declared later wins)
@@ -469,7 +879,13 @@ fn test_logical_property_block_end_cascade() {
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
     let div_style = styles.get(&div_id).unwrap();
 
     // margin-bottom should be 15px (margin-block-end declared later wins)
@@ -497,7 +913,13 @@ fn color_from_css(property: &str, value: &str) -> Option<koala_css::ColorValue>
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
     let style = styles.get(&div_id)?;
     match property {
         "color" => style.color.clone(),
@@ -674,7 +1096,13 @@ fn test_inline_style_overrides_stylesheet() {
     ));
     tree.append_child(NodeId::ROOT, p_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let p_style = styles.get(&p_id).unwrap();
     let color = p_style.color.as_ref().unwrap();
@@ -704,7 +1132,13 @@ fn test_inline_style_standalone() {
     ));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &empty_stylesheet());
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &empty_stylesheet(),
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let div_style = styles.get(&div_id).unwrap();
     let bg = div_style.background_color.as_ref().unwrap();
@@ -714,6 +1148,142 @@ fn test_inline_style_standalone() {
     assert!(div_style.padding_top.is_some());
 }
 
+/// Helper: the computed `color` of a `<p>` with the given `style` attribute
+/// under the UA stylesheet `ua_css` and the author stylesheet `css`.
+fn inline_styled_color(ua_css: &str, css: &str, style_attr: &str) -> (u8, u8, u8) {
+    let mut tree = DomTree::new();
+    let p_id = tree.alloc(make_element_with_attrs(
+        "p",
+        Some("x"),
+        &["c"],
+        &[("style", style_attr)],
+    ));
+    tree.append_child(NodeId::ROOT, p_id);
+
+    let styles = compute_styles(
+        &tree,
+        &parse_css(ua_css),
+        None,
+        &parse_css(css),
+        DEFAULT_FONT_SIZE_PX,
+    );
+    let color = styles.get(&p_id).unwrap().color.as_ref().unwrap();
+    (color.r, color.g, color.b)
+}
+
+/// [§ 6.4 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
+///
+/// The style attribute beats even the most specific selector.
+#[test]
+fn test_inline_style_beats_any_selector_specificity() {
+    assert_eq!(
+        inline_styled_color("", "p { color: blue; }", "color:red"),
+        (255, 0, 0)
+    );
+    assert_eq!(
+        inline_styled_color("", "p#x.c { color: blue; }", "color:red"),
+        (255, 0, 0)
+    );
+}
+
+/// [§ 6.4.1 Cascade Origins and Importance](https://www.w3.org/TR/css-cascade-4/#cascade-origin)
+///
+/// "Important author declarations" win over "Normal author declarations",
+/// including the style attribute's, and an important inline declaration
+/// wins over an important stylesheet one.
+#[test]
+fn test_important_declarations_in_inline_style() {
+    // A normal inline declaration loses to an important stylesheet one.
+    assert_eq!(
+        inline_styled_color("", "p { color: blue !important; }", "color: red"),
+        (0, 0, 255)
+    );
+    // An important inline declaration wins over both.
+    assert_eq!(
+        inline_styled_color(
+            "",
+            "p#x { color: blue !important; }",
+            "color: red !important"
+        ),
+        (255, 0, 0)
+    );
+    // Within the attribute, importance beats order of appearance.
+    assert_eq!(
+        inline_styled_color("", "", "color: red !important; color: blue"),
+        (255, 0, 0)
+    );
+}
+
+/// [§ 6.4.1 Cascade Origins and Importance](https://www.w3.org/TR/css-cascade-4/#cascade-origin)
+///
+/// An important author declaration in a stylesheet beats a normal inline
+/// declaration, however specific the inline one is.
+#[test]
+fn test_important_author_rule_beats_normal_inline_declaration() {
+    assert_eq!(
+        inline_styled_color("", "p { color: blue !important; }", "color: red"),
+        (0, 0, 255)
+    );
+}
+
+/// [§ 6.4.1 Cascade Origins and Importance](https://www.w3.org/TR/css-cascade-4/#cascade-origin)
+///
+/// "Important user agent declarations" come before "Important author
+/// declarations": importance reverses the origin order.
+#[test]
+fn test_important_user_agent_rule_beats_important_author_rule() {
+    assert_eq!(
+        inline_styled_color(
+            "p { color: green !important; }",
+            "p#x { color: blue !important; }",
+            "color: red !important",
+        ),
+        (0, 128, 0)
+    );
+    // A normal UA declaration still loses to a normal author one.
+    assert_eq!(
+        inline_styled_color("p#x { color: green; }", "p { color: blue; }", ""),
+        (0, 0, 255)
+    );
+}
+
+/// [§ 6.1 Cascading Origins](https://www.w3.org/TR/css-cascade-4/#cascade-origin-user)
+///
+/// A user rule overrides the UA default, even a more specific one, but
+/// loses to an equally specific author rule. Importance reverses that.
+#[test]
+fn test_user_stylesheet_cascades_between_user_agent_and_author() {
+    let mut tree = DomTree::new();
+    let p_id = tree.alloc(make_element("p", Some("x"), &[]));
+    tree.append_child(NodeId::ROOT, p_id);
+    let ua = parse_css("p#x { color: green; }");
+
+    let color = |user_css: &str, author_css: &str| {
+        let styles = compute_styles(
+            &tree,
+            &ua,
+            Some(&parse_css(user_css)),
+            &parse_css(author_css),
+            DEFAULT_FONT_SIZE_PX,
+        );
+        let color = styles.get(&p_id).unwrap().color.clone().unwrap();
+        (color.r, color.g, color.b)
+    };
+
+    assert_eq!(color("p { color: blue; }", ""), (0, 0, 255));
+    assert_eq!(
+        color("p { color: blue; }", "p { color: red; }"),
+        (255, 0, 0)
+    );
+    assert_eq!(
+        color(
+            "p { color: blue !important; }",
+            "p { color: red !important; }"
+        ),
+        (0, 0, 255)
+    );
+}
+
 // Border longhand property tests
 // [§ 4 Borders](https://www.w3.org/TR/css-backgrounds-3/#borders)
 
@@ -730,7 +1300,13 @@ fn test_border_top_color_longhand() {
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
     let div_style = styles.get(&div_id).unwrap();
 
     let border = div_style
@@ -762,7 +1338,13 @@ fn test_border_longhand_overrides_shorthand() {
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
     let div_style = styles.get(&div_id).unwrap();
 
     // Top border should have overridden color but keep width/style from shorthand
@@ -792,7 +1374,13 @@ fn test_border_width_shorthand() {
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
     let div_style = styles.get(&div_id).unwrap();
 
     assert!((div_style.border_top.as_ref().unwrap().width.to_px() - 1.0).abs() < 0.01);
@@ -813,7 +1401,13 @@ fn test_border_style_shorthand() {
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
     let div_style = styles.get(&div_id).unwrap();
 
     assert_eq!(div_style.border_top.as_ref().unwrap().style, "solid");
@@ -834,7 +1428,13 @@ fn test_border_color_shorthand() {
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(NodeId::ROOT, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
     let div_style = styles.get(&div_id).unwrap();
 
     for border in [
@@ -870,7 +1470,13 @@ fn test_custom_property_basic_color() {
     let p_id = tree.alloc(make_element("p", None, &[]));
     tree.append_child(body_id, p_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let p_style = styles.get(&p_id).unwrap();
     let color = p_style.color.as_ref().expect("color should be set via var()");
@@ -896,7 +1502,13 @@ fn test_custom_property_fallback() {
     let p_id = tree.alloc(make_element("p", None, &[]));
     tree.append_child(body_id, p_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let p_style = styles.get(&p_id).unwrap();
     let color = p_style
@@ -924,7 +1536,13 @@ fn test_custom_property_inherited() {
     let div_id = tree.alloc(make_element("div", None, &[]));
     tree.append_child(body_id, div_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let div_style = styles.get(&div_id).unwrap();
     let bg = div_style
@@ -953,7 +1571,13 @@ fn test_custom_property_case_sensitive() {
     let p_id = tree.alloc(make_element("p", None, &[]));
     tree.append_child(body_id, p_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let p_style = styles.get(&p_id).unwrap();
     let color = p_style.color.as_ref().expect("color should be set");
@@ -980,7 +1604,13 @@ fn test_custom_property_references_another() {
     let p_id = tree.alloc(make_element("p", None, &[]));
     tree.append_child(body_id, p_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let p_style = styles.get(&p_id).unwrap();
     let color = p_style.color.as_ref().expect("color should be resolved");
@@ -1007,7 +1637,13 @@ fn test_custom_property_override_in_descendant() {
     let p_id = tree.alloc(make_element("p", None, &[]));
     tree.append_child(div_id, p_id);
 
-    let styles = compute_styles(&tree, &empty_stylesheet(), &stylesheet);
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
 
     let p_style = styles.get(&p_id).unwrap();
     let color = p_style.color.as_ref().expect("color should be set");
@@ -1016,3 +1652,937 @@ fn test_custom_property_override_in_descendant() {
     assert_eq!(color.g, 0x00);
     assert_eq!(color.b, 0xff);
 }
+
+/// [§ 2](https://www.w3.org/TR/css-variables-1/#defining-variables)
+///
+/// "Inherited: yes" — a `:root` variable reaches arbitrarily deep descendants.
+#[test]
+fn test_custom_property_root_reaches_deep_descendant() {
+    let css = ":root { --accent: #123456; } em { color: var(--accent); }";
+    let stylesheet = parse_css(css);
+
+    let mut tree = DomTree::new();
+    let html_id = tree.alloc(make_element("html", None, &[]));
+    tree.append_child(NodeId::ROOT, html_id);
+    let mut parent = html_id;
+    for tag in ["body", "main", "section", "article", "div", "p", "span"] {
+        let id = tree.alloc(make_element(tag, None, &[]));
+        tree.append_child(parent, id);
+        parent = id;
+    }
+    let em_id = tree.alloc(make_element("em", None, &[]));
+    tree.append_child(parent, em_id);
+
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
+
+    let color = styles
+        .get(&em_id)
+        .unwrap()
+        .color
+        .as_ref()
+        .expect("color should be set");
+    assert_eq!((color.r, color.g, color.b), (0x12, 0x34, 0x56));
+}
+
+/// [§ 2](https://www.w3.org/TR/css-variables-1/#defining-variables)
+///
+/// A mid-tree override shadows the `:root` value only within its own
+/// subtree; siblings outside it still see the `:root` value.
+#[test]
+fn test_custom_property_mid_tree_shadowing() {
+    let css = ":root { --c: #ff0000; } .themed { --c: #00ff00; } p { color: var(--c); }";
+    let stylesheet = parse_css(css);
+
+    let mut tree = DomTree::new();
+    let html_id = tree.alloc(make_element("html", None, &[]));
+    tree.append_child(NodeId::ROOT, html_id);
+    let body_id = tree.alloc(make_element("body", None, &[]));
+    tree.append_child(html_id, body_id);
+    let themed_id = tree.alloc(make_element("section", None, &["themed"]));
+    tree.append_child(body_id, themed_id);
+    let inner_div = tree.alloc(make_element("div", None, &[]));
+    tree.append_child(themed_id, inner_div);
+    let inner_p = tree.alloc(make_element("p", None, &[]));
+    tree.append_child(inner_div, inner_p);
+    let outer_p = tree.alloc(make_element("p", None, &[]));
+    tree.append_child(body_id, outer_p);
+
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
+
+    let inner = styles
+        .get(&inner_p)
+        .unwrap()
+        .color
+        .as_ref()
+        .expect("inner color");
+    assert_eq!((inner.r, inner.g, inner.b), (0x00, 0xff, 0x00));
+    let outer = styles
+        .get(&outer_p)
+        .unwrap()
+        .color
+        .as_ref()
+        .expect("outer color");
+    assert_eq!((outer.r, outer.g, outer.b), (0xff, 0x00, 0x00));
+}
+
+/// [§ 3](https://www.w3.org/TR/css-variables-1/#using-variables)
+///
+/// "If there are any var() references in the fallback, substitute them
+/// as well." A two-level fallback chain resolves to the final default,
+/// with whitespace around arguments ignored.
+#[test]
+fn test_custom_property_two_level_fallback() {
+    let css = "p { color: var( --a , var( --b , #0000ff ) ); }";
+    let stylesheet = parse_css(css);
+
+    let mut tree = DomTree::new();
+    let html_id = tree.alloc(make_element("html", None, &[]));
+    tree.append_child(NodeId::ROOT, html_id);
+    let p_id = tree.alloc(make_element("p", None, &[]));
+    tree.append_child(html_id, p_id);
+
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
+
+    let color = styles
+        .get(&p_id)
+        .unwrap()
+        .color
+        .as_ref()
+        .expect("color from fallback");
+    assert_eq!((color.r, color.g, color.b), (0x00, 0x00, 0xff));
+}
+
+/// [§ 2.3](https://www.w3.org/TR/css-variables-1/#cycles)
+///
+/// A `var()` sees custom properties declared on the same element even
+/// when they appear later in source order.
+#[test]
+fn test_custom_property_declared_after_use() {
+    let css = "p { color: var(--late); --late: #abcdef; }";
+    let stylesheet = parse_css(css);
+
+    let mut tree = DomTree::new();
+    let html_id = tree.alloc(make_element("html", None, &[]));
+    tree.append_child(NodeId::ROOT, html_id);
+    let p_id = tree.alloc(make_element("p", None, &[]));
+    tree.append_child(html_id, p_id);
+
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
+
+    let color = styles
+        .get(&p_id)
+        .unwrap()
+        .color
+        .as_ref()
+        .expect("color should be set");
+    assert_eq!((color.r, color.g, color.b), (0xab, 0xcd, 0xef));
+}
+
+/// Compute styles for `<html><div><p>` and return (html, div, p) styles.
+fn root_div_p_styles(
+    css: &str,
+    initial_font_size: f64,
+) -> (
+    koala_css::ComputedStyle,
+    koala_css::ComputedStyle,
+    koala_css::ComputedStyle,
+) {
+    root_div_p_styles_with(&parse_css(css), initial_font_size)
+}
+
+/// [`root_div_p_styles`] for an already-parsed author stylesheet.
+fn root_div_p_styles_with(
+    stylesheet: &Stylesheet,
+    initial_font_size: f64,
+) -> (
+    koala_css::ComputedStyle,
+    koala_css::ComputedStyle,
+    koala_css::ComputedStyle,
+) {
+    let mut tree = DomTree::new();
+    let html_id = tree.alloc(make_element("html", None, &[]));
+    tree.append_child(NodeId::ROOT, html_id);
+    let div_id = tree.alloc(make_element("div", None, &[]));
+    tree.append_child(html_id, div_id);
+    let p_id = tree.alloc(make_element("p", None, &[]));
+    tree.append_child(div_id, p_id);
+
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        stylesheet,
+        initial_font_size,
+    );
+    (
+        styles.get(&html_id).unwrap().clone(),
+        styles.get(&div_id).unwrap().clone(),
+        styles.get(&p_id).unwrap().clone(),
+    )
+}
+
+/// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+///
+/// `em` in other properties resolves against the element's own font-size,
+/// even when the font-size is declared after them.
+#[test]
+fn test_em_padding_uses_own_font_size() {
+    let (_, div, p) = root_div_p_styles(
+        "div { padding: 1em; font-size: 20px; } p { padding: 1em; font-size: 10px; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(div.padding_top, Some(koala_css::LengthValue::Px(20.0)));
+    assert_eq!(p.padding_left, Some(koala_css::LengthValue::Px(10.0)));
+
+    // A child that only inherits the font-size gets the same padding.
+    let (_, _, p) = root_div_p_styles(
+        "div { font-size: 20px; } p { padding: 1em; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.padding_top, Some(koala_css::LengthValue::Px(20.0)));
+}
+
+/// [§ 5.1.1 Font-relative lengths](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+///
+/// "When specified in the font-size property of an element, the
+/// font-relative lengths refer to the computed font metrics of the parent
+/// element."
+#[test]
+fn test_em_font_size_uses_parent_font_size() {
+    // Both rules match p; the later 2em refers to the parent's 10px, not
+    // to the 3em the first rule gave p.
+    let (_, _, p) = root_div_p_styles(
+        "div { font-size: 10px; } p { font-size: 3em; } div > p { font-size: 2em; margin-top: 1em; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.font_size, Some(koala_css::LengthValue::Px(20.0)));
+    assert_eq!(
+        p.margin_top,
+        Some(koala_css::AutoLength::Length(koala_css::LengthValue::Px(
+            20.0
+        )))
+    );
+}
+
+/// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+///
+/// "Font family names must either be given quoted as strings, or unquoted
+/// as a sequence of one or more identifiers."
+#[test]
+fn test_font_family_parses_fallback_list() {
+    use koala_css::{FontFamily, GenericFontFamily};
+
+    let style =
+        div_style("div { font-family: \"Fira Code\", DejaVu   Sans Mono, MONOSPACE, 'serif'; }");
+    assert_eq!(
+        style.font_family,
+        Some(vec![
+            FontFamily::Named("Fira Code".to_string()),
+            FontFamily::Named("DejaVu Sans Mono".to_string()),
+            FontFamily::Generic(GenericFontFamily::Monospace),
+            // A quoted keyword names a family, not the generic one.
+            FontFamily::Named("serif".to_string()),
+        ])
+    );
+
+    // The shorthand takes the same list after the size.
+    let style = div_style("div { font: italic 12px Georgia, sans-serif; }");
+    assert_eq!(
+        style.font_family,
+        Some(vec![
+            FontFamily::Named("Georgia".to_string()),
+            FontFamily::Generic(GenericFontFamily::SansSerif),
+        ])
+    );
+
+    // An empty entry or a non-identifier makes the declaration invalid.
+    assert_eq!(
+        div_style("div { font-family: Arial,, serif; }").font_family,
+        None
+    );
+    assert_eq!(div_style("div { font-family: 12px; }").font_family, None);
+}
+
+/// [§ 3.7 Shorthand font property](https://www.w3.org/TR/css-fonts-4/#font-prop)
+///
+/// "All subproperties of the font property are first reset to their
+/// initial values", so a later `font` shorthand overrides longhands from
+/// earlier declarations even though its font size is resolved early.
+#[test]
+fn test_font_shorthand_resets_earlier_longhands() {
+    let (_, _, p) = root_div_p_styles(
+        "p { font-weight: bold; line-height: 3; } div > p { font: 12px serif; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.font_weight, Some(400));
+    assert_eq!(p.line_height, Some(LineHeight::Normal));
+    assert_eq!(p.font_size, Some(koala_css::LengthValue::Px(12.0)));
+
+    // A later longhand still overrides the shorthand, and `em` lengths
+    // declared before the font size use the final size.
+    let (_, _, p) = root_div_p_styles(
+        "p { padding: 1em; font: 12px serif; font-weight: bold; font-size: 20px; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.font_weight, Some(700));
+    assert_eq!(p.font_size, Some(koala_css::LengthValue::Px(20.0)));
+    assert_eq!(p.padding_top, Some(koala_css::LengthValue::Px(20.0)));
+}
+
+/// [§ 2.2.1 Relative Weights](https://www.w3.org/TR/css-fonts-4/#relative-weights)
+///
+/// "bolder: Specifies a bolder weight than the inherited value."
+#[test]
+fn test_font_weight_bolder_steps_from_parent() {
+    let (_, div, p) = root_div_p_styles(
+        "div { font-weight: 400; } p { font-weight: bolder; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(div.font_weight, Some(400));
+    assert_eq!(p.font_weight, Some(700));
+
+    let (_, _, p) = root_div_p_styles(
+        "div { font-weight: bold; } p { font-weight: bolder; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.font_weight, Some(900));
+
+    // The font shorthand steps from the parent the same way.
+    let (_, _, p) = root_div_p_styles(
+        "div { font-weight: 700; } p { font: bolder 12px serif; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.font_weight, Some(900));
+
+    // An earlier declaration on the element itself is not the base.
+    let (_, _, p) = root_div_p_styles(
+        "div { font-weight: 400; } p { font-weight: 900; font-weight: bolder; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.font_weight, Some(700));
+}
+
+/// [§ 2.2.1 Relative Weights](https://www.w3.org/TR/css-fonts-4/#relative-weights)
+///
+/// "lighter: Specifies a lighter weight than the inherited value."
+#[test]
+fn test_font_weight_lighter_steps_from_parent() {
+    // No ancestor sets a weight, so the parent has the initial 'normal'.
+    let (_, _, p) = root_div_p_styles("p { font-weight: lighter; }", DEFAULT_FONT_SIZE_PX);
+    assert_eq!(p.font_weight, Some(100));
+
+    let (_, _, p) = root_div_p_styles(
+        "div { font-weight: 900; } p { font-weight: lighter; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.font_weight, Some(700));
+}
+
+/// [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+///
+/// "<percentage>: Raise (positive value) or lower (negative value) the box
+/// by this distance (a percentage of the 'line-height' value)."
+#[test]
+fn test_vertical_align_resolves_em_and_percentage() {
+    use koala_css::layout::VerticalAlign;
+
+    let (_, _, p) = root_div_p_styles(
+        "p { font-size: 20px; line-height: 30px; vertical-align: 50%; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.vertical_align, Some(VerticalAlign::Length(15.0)));
+
+    // `em` uses the element's own font size, wherever it is declared.
+    let (_, _, p) = root_div_p_styles(
+        "p { vertical-align: -0.5em; font-size: 20px; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.vertical_align, Some(VerticalAlign::Length(-10.0)));
+
+    // A 'normal' line height uses the default 1.2 ratio.
+    let (_, _, p) = root_div_p_styles(
+        "p { font-size: 10px; vertical-align: 100%; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.vertical_align, Some(VerticalAlign::Length(12.0)));
+
+    let (_, _, p) = root_div_p_styles("p { vertical-align: 4px; }", DEFAULT_FONT_SIZE_PX);
+    assert_eq!(p.vertical_align, Some(VerticalAlign::Length(4.0)));
+}
+
+/// [§ 2.2 font-weight](https://www.w3.org/TR/css-fonts-4/#font-weight-prop)
+///
+/// "<font-weight-absolute> = [normal | bold | <number [1,1000]>]"
+#[test]
+fn test_font_weight_numeric_range() {
+    let (_, div, p) = root_div_p_styles(
+        "div { font-weight: 550; } p { font-weight: 1000; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(div.font_weight, Some(550));
+    assert_eq!(p.font_weight, Some(1000));
+
+    // Out of range: the declaration is ignored and the weight inherits.
+    let (_, _, p) = root_div_p_styles(
+        "div { font-weight: 300; } p { font-weight: 1001; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.font_weight, Some(300));
+}
+
+/// [§ 5.1.1](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+///
+/// "rem unit: Equal to the computed value of font-size on the root element."
+#[test]
+fn test_rem_resolves_against_root_font_size() {
+    let (_, div, p) = root_div_p_styles(
+        "html { font-size: 20px; } div { font-size: 8px; } \
+         p { font-size: 3px; margin-top: 2rem; padding-left: 0.5rem; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(div.font_size, Some(koala_css::LengthValue::Px(8.0)));
+    assert_eq!(p.font_size, Some(koala_css::LengthValue::Px(3.0)));
+    assert_eq!(
+        p.margin_top,
+        Some(koala_css::AutoLength::Length(koala_css::LengthValue::Px(
+            40.0
+        )))
+    );
+    assert_eq!(p.padding_left, Some(koala_css::LengthValue::Px(10.0)));
+}
+
+#[test]
+fn test_rem_font_size_ignores_parent_font_size() {
+    let (_, _, p) = root_div_p_styles(
+        "html { font-size: 20px; } div { font-size: 50px; } p { font-size: 2rem; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(p.font_size, Some(koala_css::LengthValue::Px(40.0)));
+}
+
+/// [§ 5.1.1](https://www.w3.org/TR/css-values-4/#font-relative-lengths)
+///
+/// "When specified on the font-size property of the root element, the rem
+/// units refer to the property's initial value."
+#[test]
+fn test_rem_on_root_font_size_uses_initial_value() {
+    let (html, div, _) = root_div_p_styles("html { font-size: 2rem; } div { width: 1rem; }", 10.0);
+    assert_eq!(html.font_size, Some(koala_css::LengthValue::Px(20.0)));
+    assert_eq!(
+        div.width,
+        Some(koala_css::AutoLength::Length(koala_css::LengthValue::Px(
+            20.0
+        )))
+    );
+}
+
+#[test]
+fn test_configurable_initial_font_size() {
+    let (html, _, p) = root_div_p_styles("p { margin-top: 1rem; padding-top: 2em; }", 20.0);
+    assert_eq!(html.font_size, Some(koala_css::LengthValue::Px(20.0)));
+    assert_eq!(p.font_size, Some(koala_css::LengthValue::Px(20.0)));
+    assert_eq!(
+        p.margin_top,
+        Some(koala_css::AutoLength::Length(koala_css::LengthValue::Px(
+            20.0
+        )))
+    );
+    assert_eq!(p.padding_top, Some(koala_css::LengthValue::Px(40.0)));
+}
+
+// Stylesheet extraction tests
+
+/// Parse `html` and return the document's merged author stylesheet and its
+/// inline CSS text.
+fn document_stylesheet(html: &str) -> (Stylesheet, String) {
+    let tree = koala_html::HTMLParser::new(koala_html::HTMLTokenizer::tokenize(html)).run();
+    let sheet = koala_css::extract_all_stylesheets(&tree, None).into_merged_stylesheet();
+    (sheet, koala_css::extract_style_content(&tree))
+}
+
+/// The `(r, g, b)` of a computed `color`.
+fn rgb(style: &koala_css::ComputedStyle) -> Option<(u8, u8, u8)> {
+    style.color.as_ref().map(|c| (c.r, c.g, c.b))
+}
+
+/// [§ 4.12.3 The template element](https://html.spec.whatwg.org/multipage/scripting.html#the-template-element)
+///
+/// A `<style>` inside `<template>` content is inert and contributes no
+/// rules.
+#[test]
+fn test_template_style_contributes_no_rules() {
+    let (sheet, css_text) = document_stylesheet(
+        "<style>div { color: blue; }</style>\
+         <template><style>p { color: red; }</style></template>",
+    );
+    assert_eq!(sheet.rules, parse_css("div { color: blue; }").rules);
+    assert!(!css_text.contains("red"), "got {css_text:?}");
+}
+
+/// [§ 4.2.6 The style element](https://html.spec.whatwg.org/multipage/semantics.html#the-style-element)
+///
+/// `<style media="print">` doesn't apply to screen rendering, while
+/// `screen`, `all` and negated print queries do. The skipped sheet must
+/// not shift which `<style>` the later inline sources read.
+#[test]
+fn test_print_media_style_contributes_no_rules() {
+    let (sheet, css_text) = document_stylesheet(
+        "<style media=\"print\">p { color: red; }</style>\
+         <style media=\"screen and (min-width: 1px)\">p { color: green; }</style>\
+         <style media=\"not print\">p { color: blue; }</style>\
+         <style media=\"print, all\">p { color: black; }</style>",
+    );
+    assert_eq!(
+        sheet.rules,
+        parse_css("p { color: green; } p { color: blue; } p { color: black; }").rules
+    );
+    assert!(!css_text.contains("red"), "got {css_text:?}");
+}
+
+/// [§ 4.12.2 The noscript element](https://html.spec.whatwg.org/multipage/scripting.html#the-noscript-element)
+///
+/// Scripting is enabled, so styles inside `<noscript>` don't apply.
+#[test]
+fn test_noscript_style_contributes_no_rules() {
+    let (sheet, _) =
+        document_stylesheet("<body><noscript><style>p { color: red; }</style></noscript></body>");
+    assert!(sheet.rules.is_empty(), "got {:?}", sheet.rules);
+}
+
+// Unknown at-rule tests
+
+/// [§ 5.4.2 Consume an at-rule](https://www.w3.org/TR/css-syntax-3/#consume-at-rule)
+///
+/// An at-rule the cascade doesn't understand is consumed whole, prelude
+/// and block, so the style rules on either side of it still apply.
+#[test]
+fn test_unknown_at_rule_keeps_surrounding_rules() {
+    let css = "div { color: green; } @supports (x:y) { p { color: red; } } p { color: blue; }";
+    let sheet = parse_css(css);
+    assert_eq!(sheet.rules.len(), 3, "got {:?}", sheet.rules);
+
+    let (_, div, p) = root_div_p_styles(css, DEFAULT_FONT_SIZE_PX);
+    assert_eq!(rgb(&div), Some((0, 0x80, 0)));
+    assert_eq!(rgb(&p), Some((0, 0, 0xff)));
+}
+
+/// Skipped at-rules are reported by name, whether they end in a block or
+/// a semicolon.
+#[test]
+fn test_unknown_at_rule_recorded_as_issue() {
+    let mut parser = CSSParser::new(CSSTokenizer::tokenize(
+        "@layer base; div { color: green; } @layer base { p { color: red; } } \
+         @media screen { p { color: blue; } }",
+    ));
+    let (sheet, issues) = parser.parse_stylesheet_with_issues();
+    assert_eq!(sheet.rules.len(), 4);
+    let messages: Vec<_> = issues.iter().map(|issue| issue.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "unsupported at-rule '@layer' ignored",
+            "unsupported at-rule '@layer' ignored"
+        ]
+    );
+    assert_eq!(issues[0].token_index, 0);
+}
+
+/// [§ 5.4.6 Consume a declaration](https://www.w3.org/TR/css-syntax-3/#consume-declaration)
+///
+/// A declaration without a colon is dropped up to its `;`, recorded as an
+/// issue, and the declarations around it still apply.
+#[test]
+fn test_missing_colon_declaration_dropped_with_issue() {
+    let tokens = CSSTokenizer::tokenize("div { color: green; color red blue: x; margin-top: 4px }");
+    let color_index = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| matches!(token, CSSToken::Ident(name) if name == "color"))
+        .nth(1)
+        .map(|(index, _)| index);
+    let mut parser = CSSParser::new(tokens);
+    let (sheet, issues) = parser.parse_stylesheet_with_issues();
+
+    let koala_css::Rule::Style(rule) = &sheet.rules[0] else {
+        panic!("expected a style rule");
+    };
+    let names: Vec<_> = rule.declarations.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["color", "margin-top"]);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(
+        issues[0].message,
+        "declaration 'color' without a ':' dropped"
+    );
+    assert_eq!(Some(issues[0].token_index), color_index);
+}
+
+/// Empty values, stray tokens and rules without a block are reported;
+/// an empty custom property is not.
+#[test]
+fn test_invalid_declarations_and_rules_recorded_as_issues() {
+    let mut parser = CSSParser::new(CSSTokenizer::tokenize(
+        "div { width: ; --empty: ; 12px; height: 1px } p",
+    ));
+    let (sheet, issues) = parser.parse_stylesheet_with_issues();
+
+    assert_eq!(sheet.rules.len(), 1);
+    let koala_css::Rule::Style(rule) = &sheet.rules[0] else {
+        panic!("expected a style rule");
+    };
+    let names: Vec<_> = rule.declarations.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["--empty", "height"]);
+    let messages: Vec<_> = issues.iter().map(|issue| issue.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        [
+            "declaration 'width' with an empty value dropped",
+            "declaration not starting with a property name dropped",
+            "rule without a '{' block at the end of the stylesheet dropped",
+        ]
+    );
+}
+
+/// Rules nested in `@media` are counted, and a rule whose selector list
+/// includes an unsupported selector is reported once.
+#[test]
+fn test_stylesheet_stats_counts_rules_and_unsupported_selectors() {
+    let sheet = Stylesheet::from_str(
+        "p { color: red; margin: 0; } \
+         div:has(> img) { color: blue; } \
+         @media print { .note { Color: black; padding: 1px; } } \
+         h1, h2 { font-size: 2em; }",
+    );
+    assert_eq!(
+        sheet.stats(),
+        koala_css::StylesheetStats {
+            rules: 5,
+            declarations: 6,
+            distinct_properties: 4,
+            unsupported_selector_rules: 1,
+        }
+    );
+}
+
+// Feature query tests
+
+/// [§ 6 Feature queries](https://www.w3.org/TR/css-conditional-3/#at-supports)
+///
+/// `@supports (display: flex)` applies its block; a display value the
+/// engine doesn't understand does not.
+#[test]
+fn test_supports_applies_block_only_when_supported() {
+    let (_, div, p) = root_div_p_styles(
+        "@supports (display: flex) { div { color: green; } } \
+         @supports (display: ruby) { p { color: red; } }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(rgb(&div), Some((0, 0x80, 0)));
+    // Inherited from the div rather than set by the skipped block.
+    assert_eq!(rgb(&p), Some((0, 0x80, 0)));
+}
+
+/// [§ 6 Feature queries](https://www.w3.org/TR/css-conditional-3/#at-supports)
+///
+/// `not`, `and`, `or` and nested parentheses combine declaration tests;
+/// mixing `and` with `or` at one level, unknown properties and
+/// `<general-enclosed>` terms are all false.
+#[test]
+fn test_supports_condition_operators() {
+    for (condition, expected) in [
+        ("(color: red)", true),
+        ("(colour: red)", false),
+        ("(width: banana)", false),
+        ("not (display: ruby)", true),
+        ("(display: grid) and (color: red)", true),
+        ("(display: grid) and (display: ruby)", false),
+        ("(display: ruby) or (color: red)", true),
+        (
+            "((display: ruby) or (color: red)) and (not (float: up))",
+            true,
+        ),
+        ("(color: red) and (width: 1px) or (height: 1px)", false),
+        ("(direction: rtl)", true),
+        // Values equal to the initial value are still supported.
+        ("(display: inline)", true),
+        ("(writing-mode: horizontal-tb)", true),
+        ("(direction: ltr)", true),
+        ("(margin-top: 0)", true),
+        ("(box-shadow: banana)", false),
+        ("(flex: 10px)", true),
+        ("foo(bar)", false),
+    ] {
+        let (_, div, _) = root_div_p_styles(
+            &format!("@supports {condition} {{ div {{ color: green; }} }}"),
+            DEFAULT_FONT_SIZE_PX,
+        );
+        assert_eq!(rgb(&div).is_some(), expected, "@supports {condition}");
+    }
+}
+
+/// `@media` rules nested in `@supports` are still resolved for the
+/// requested medium.
+#[test]
+fn test_supports_nested_media_resolved_for_print() {
+    let sheet = parse_css("@supports (color: red) { @media print { p { color: red; } } }")
+        .for_media(koala_css::MediaType::Print);
+    let (_, _, p) = root_div_p_styles_with(&sheet, DEFAULT_FONT_SIZE_PX);
+    assert_eq!(rgb(&p), Some((0xff, 0, 0)));
+
+    let (_, _, p) = root_div_p_styles(
+        "@supports (color: red) { @media print { p { color: red; } } }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(rgb(&p), None);
+}
+
+// Media type tests
+
+const MEDIA_CSS: &str = "p { color: blue; } \
+    @media print { p { color: red; } div { color: red; } } \
+    @media screen { div { color: green; } }";
+
+/// [§ 2.3 Media Types](https://www.w3.org/TR/mediaqueries-4/#media-types)
+///
+/// Rendering defaults to `screen`: `@media print` rules are skipped and
+/// `@media screen` rules apply.
+#[test]
+fn test_media_print_rules_excluded_on_screen() {
+    let (_, div, p) = root_div_p_styles(MEDIA_CSS, DEFAULT_FONT_SIZE_PX);
+    assert_eq!(rgb(&p), Some((0, 0, 0xff)));
+    assert_eq!(rgb(&div), Some((0, 0x80, 0)));
+}
+
+/// [§ 2.3 Media Types](https://www.w3.org/TR/mediaqueries-4/#media-types)
+///
+/// Resolved for print, `@media print` rules apply in document order and
+/// `@media screen` rules don't.
+#[test]
+fn test_media_print_rules_included_for_print() {
+    let sheet = parse_css(MEDIA_CSS).for_media(koala_css::MediaType::Print);
+    let (_, div, p) = root_div_p_styles_with(&sheet, DEFAULT_FONT_SIZE_PX);
+    assert_eq!(rgb(&p), Some((0xff, 0, 0)));
+    assert_eq!(rgb(&div), Some((0xff, 0, 0)));
+}
+
+/// Rules inside `@media` keep their place in document order: a later
+/// unconditional rule still beats an earlier matching `@media` rule.
+#[test]
+fn test_media_rules_keep_document_order() {
+    let (_, _, p) = root_div_p_styles(
+        "@media screen, print { p { color: red; } } p { color: blue; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(rgb(&p), Some((0, 0, 0xff)));
+}
+
+/// [§ 4.2.6 The style element](https://html.spec.whatwg.org/multipage/semantics.html#the-style-element)
+///
+/// A `<style media="print">` sheet is dropped on screen but merged when
+/// the stylesheets are requested for print.
+#[test]
+fn test_print_media_style_included_for_print() {
+    let html = "<style media=\"print\">p { color: red; }</style>\
+                <style media=\"screen\">p { color: blue; }</style>";
+    let tree = koala_html::HTMLParser::new(koala_html::HTMLTokenizer::tokenize(html)).run();
+    let sheets = koala_css::extract_all_stylesheets(&tree, None);
+
+    let print = sheets
+        .clone()
+        .into_merged_stylesheet_for(koala_css::MediaType::Print);
+    assert_eq!(print.rules, parse_css("p { color: red; }").rules);
+    let screen = sheets.into_merged_stylesheet();
+    assert_eq!(screen.rules.len(), 1);
+}
+
+/// [§ 7.1 Page-based Properties](https://www.w3.org/TR/css-page-3/#page-properties)
+///
+/// `@page { margin: 2cm }` sets every page margin; 2cm is 96px / 2.54 * 2.
+#[test]
+fn test_page_rule_margin_shorthand() {
+    let pages = koala_css::page_rules(&parse_css("@page { margin: 2cm }"));
+    let two_cm = koala_css::LengthValue::Px(2.0 * 96.0 / 2.54);
+    assert_eq!(
+        pages,
+        [koala_css::PageRule {
+            selectors: vec![],
+            margin: koala_css::PageMargins {
+                top: Some(two_cm),
+                right: Some(two_cm),
+                bottom: Some(two_cm),
+                left: Some(two_cm),
+            },
+            size: None,
+        }]
+    );
+}
+
+/// [§ 4.1 Page Selectors and Page Context](https://www.w3.org/TR/css-page-3/#page-selectors)
+///
+/// "The :first pseudo-class matches the first page of the document."
+#[test]
+fn test_page_rule_first_selector_and_margin_top() {
+    let pages = koala_css::page_rules(&parse_css(
+        "p { color: red; } @page :first { margin-top: 4cm } @page wide:left, :right { size: A4 landscape }",
+    ));
+    assert_eq!(pages.len(), 2);
+
+    assert_eq!(
+        pages[0].selectors,
+        [koala_css::PageSelector {
+            name: None,
+            pseudo_classes: vec![koala_css::PagePseudoClass::First],
+        }]
+    );
+    assert_eq!(
+        pages[0].margin,
+        koala_css::PageMargins {
+            top: Some(koala_css::LengthValue::Px(4.0 * 96.0 / 2.54)),
+            ..koala_css::PageMargins::default()
+        }
+    );
+    assert_eq!(pages[0].size, None);
+
+    assert_eq!(
+        pages[1].selectors,
+        [
+            koala_css::PageSelector {
+                name: Some("wide".to_string()),
+                pseudo_classes: vec![koala_css::PagePseudoClass::Left],
+            },
+            koala_css::PageSelector {
+                name: None,
+                pseudo_classes: vec![koala_css::PagePseudoClass::Right],
+            },
+        ]
+    );
+    // "A4: Equivalent to the international paper size A4, 210mm wide and
+    // 297mm high", with the longer side horizontal.
+    let Some(koala_css::PageSize::Fixed { width, height }) = &pages[1].size else {
+        panic!("expected a fixed page size, got {:?}", pages[1].size);
+    };
+    assert!((width.to_px() - 297.0 * 96.0 / 25.4).abs() < 1e-9);
+    assert!((height.to_px() - 210.0 * 96.0 / 25.4).abs() < 1e-9);
+}
+
+/// [§ 7.2 Page size: the size property](https://www.w3.org/TR/css-page-3/#page-size-prop)
+///
+/// The block is parsed as a declaration list: `!important` is dropped
+/// from a value, an empty or malformed `size` is ignored, and a margin
+/// at-rule doesn't swallow the declarations after it.
+#[test]
+fn test_page_rule_declaration_list() {
+    let pages = koala_css::page_rules(&parse_css(
+        "@page { size: landscape !important; @top-center { content: 'x' } margin-left: 1in; size: ; size: 5px 6px 7px }",
+    ));
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].size, Some(koala_css::PageSize::Landscape));
+    assert_eq!(
+        pages[0].margin,
+        koala_css::PageMargins {
+            left: Some(koala_css::LengthValue::Px(96.0)),
+            ..koala_css::PageMargins::default()
+        }
+    );
+
+    let pages = koala_css::page_rules(&parse_css("@page { size: }"));
+    assert_eq!(pages[0].size, None);
+}
+
+/// `@page` rules never affect screen rendering.
+#[test]
+fn test_page_rule_does_not_style_elements() {
+    let stylesheet = parse_css("@page { margin: 2cm; color: red }");
+    let mut tree = DomTree::new();
+    let p_id = tree.alloc(make_element("p", None, &[]));
+    tree.append_child(NodeId::ROOT, p_id);
+
+    let styles = compute_styles(
+        &tree,
+        &empty_stylesheet(),
+        None,
+        &stylesheet,
+        DEFAULT_FONT_SIZE_PX,
+    );
+    let p_style = styles.get(&p_id).unwrap();
+    assert!(p_style.color.is_none());
+    assert!(p_style.margin_top.is_none());
+}
+
+/// [§ 2.1 'direction'](https://www.w3.org/TR/css-writing-modes-4/#direction)
+///
+/// "Inherited: yes". An unrecognized value is dropped, keeping the
+/// inherited direction.
+#[test]
+fn test_direction_inherits() {
+    let (html, div, p) = root_div_p_styles(
+        "div { direction: rtl; } p { direction: sideways; }",
+        DEFAULT_FONT_SIZE_PX,
+    );
+    assert_eq!(html.direction, koala_css::Direction::Ltr);
+    assert_eq!(div.direction, koala_css::Direction::Rtl);
+    assert_eq!(p.direction, koala_css::Direction::Rtl);
+}
+
+/// [§ 6.1 Cascade Sorting Order](https://www.w3.org/TR/css-cascade-4/#cascade-sort)
+///
+/// A stylesheet linked twice is applied once, at its first link, so a
+/// sheet linked in between still overrides it.
+#[test]
+fn test_duplicate_stylesheet_links_apply_once() {
+    let html = "<link rel=\"stylesheet\" href=\"data:text/css,p%7Bcolor:red%7D\">\
+                <style>p { color: blue; }</style>\
+                <link rel=\"stylesheet\" href=\"data:text/css,p%7Bcolor:red%7D\">";
+    let tree = koala_html::HTMLParser::new(koala_html::HTMLTokenizer::tokenize(html)).run();
+    let sheets = koala_css::extract_all_stylesheets(&tree, None);
+    assert_eq!(sheets.sheets.len(), 2);
+
+    let merged = sheets.into_merged_stylesheet();
+    assert_eq!(
+        merged.rules,
+        parse_css("p { color: red; } p { color: blue; }").rules
+    );
+}
+
+/// [§ 4.2.4 The link element](https://html.spec.whatwg.org/multipage/semantics.html#attr-link-media)
+///
+/// A sheet linked for print and then for every media still applies to the
+/// screen.
+#[test]
+fn test_stylesheet_linked_for_print_then_screen_applies_to_screen() {
+    let html = "<link rel=\"stylesheet\" href=\"data:text/css,p%7Bcolor:red%7D\" media=\"print\">\
+                <link rel=\"stylesheet\" href=\"data:text/css,p%7Bcolor:red%7D\">";
+    let tree = koala_html::HTMLParser::new(koala_html::HTMLTokenizer::tokenize(html)).run();
+    let sheets = koala_css::extract_all_stylesheets(&tree, None);
+    assert_eq!(sheets.sheets.len(), 2);
+    assert_eq!(sheets.sheets[0].media.as_deref(), Some("print"));
+    assert_eq!(sheets.sheets[1].media, None);
+
+    assert_eq!(
+        sheets.into_merged_stylesheet().rules,
+        parse_css("p { color: red; }").rules
+    );
+}
@@ -8,12 +8,61 @@
     clippy::uninlined_format_args
 )]
 
-use koala_css::layout::default_display_for_element;
+use koala_css::layout::{VerticalAlign, default_display_for_element};
 use koala_css::{
-    ApproximateFontMetrics, DisplayValue, FragmentContent, InnerDisplayType, LayoutBox,
-    OuterDisplayType, Rect, TextRun,
+    ApproximateFontMetrics, BoxDimensions, BoxType, ColorValue, DEFAULT_FONT_SIZE_PX, DisplayValue,
+    EdgeSizes, FontMetrics, FragmentContent, InnerDisplayType, LayoutBox, OuterDisplayType, Rect,
+    TextDecoration, TextDecorationLine, TextDecorationStyle, TextRun, measure_text,
+    measure_text_runs,
 };
 
+/// [§ 3 The CSS Box Model](https://www.w3.org/TR/css-box-3/#box-model)
+///
+/// The content, padding, border and margin boxes nest, each growing by its
+/// edge sizes.
+#[test]
+fn test_box_dimensions_rects_nest() {
+    let edges = |top, right, bottom, left| EdgeSizes {
+        top,
+        right,
+        bottom,
+        left,
+    };
+    let dims = BoxDimensions {
+        content: Rect {
+            x: 50.0,
+            y: 40.0,
+            width: 100.0,
+            height: 20.0,
+        },
+        padding: edges(1.0, 2.0, 3.0, 4.0),
+        border: edges(5.0, 6.0, 7.0, 8.0),
+        margin: edges(9.0, 10.0, 11.0, 12.0),
+    };
+    let rects = [
+        dims.content_box(),
+        dims.padding_box(),
+        dims.border_box(),
+        dims.margin_box(),
+    ];
+    let as_tuple = |r: Rect| (r.x, r.y, r.width, r.height);
+    assert_eq!(
+        rects.map(as_tuple),
+        [
+            (50.0, 40.0, 100.0, 20.0),
+            (46.0, 39.0, 106.0, 24.0),
+            (38.0, 34.0, 120.0, 36.0),
+            (26.0, 25.0, 142.0, 56.0),
+        ]
+    );
+    for pair in rects.windows(2) {
+        let (inner, outer) = (pair[0], pair[1]);
+        assert!(outer.x <= inner.x && outer.y <= inner.y);
+        assert!(outer.x + outer.width >= inner.x + inner.width);
+        assert!(outer.y + outer.height >= inner.y + inner.height);
+    }
+}
+
 #[test]
 fn test_default_display_block() {
     assert_eq!(
@@ -60,6 +109,11 @@ fn layout_html(html: &str) -> LayoutBox {
 
 /// Helper: parse HTML with a custom viewport width/height.
 fn layout_html_with_viewport(html: &str, vw: f32, vh: f32) -> LayoutBox {
+    layout_html_with_ua(html, koala_css::ua_stylesheet::ua_stylesheet(), vw, vh)
+}
+
+/// Helper: parse HTML and lay it out with the given UA stylesheet.
+fn layout_html_with_ua(html: &str, ua: &koala_css::Stylesheet, vw: f32, vh: f32) -> LayoutBox {
     use koala_css::cascade::compute_styles;
     use koala_css::{CSSParser, CSSTokenizer, Stylesheet};
     use koala_std::collections::HashMap;
@@ -79,8 +133,7 @@ fn layout_html_with_viewport(html: &str, vw: f32, vh: f32) -> LayoutBox {
         css_parser.parse_stylesheet()
     };
 
-    let ua = koala_css::ua_stylesheet::ua_stylesheet();
-    let styles = compute_styles(&dom, ua, &author);
+    let styles = compute_styles(&dom, ua, None, &author, DEFAULT_FONT_SIZE_PX);
 
     let image_dims = HashMap::new();
     let mut layout_tree = LayoutBox::build_layout_tree(&dom, &styles, dom.root(), &image_dims)
@@ -105,6 +158,38 @@ fn box_at_depth(root: &LayoutBox, depth: usize) -> &LayoutBox {
     box_at_depth(&root.children[0], depth - 1)
 }
 
+/// [§ 15.3.4 The page](https://html.spec.whatwg.org/multipage/rendering.html#the-page)
+///
+/// The full UA stylesheet gives `body` its 8px margin; the minimal one only
+/// has `display` defaults, so the same document lays out without it.
+#[test]
+fn test_minimal_ua_stylesheet_drops_body_margin() {
+    let html = "<html><body><p>Hello</p></body></html>";
+    let full = layout_html_with_ua(
+        html,
+        koala_css::ua_stylesheet::ua_stylesheet(),
+        800.0,
+        600.0,
+    );
+    let minimal = layout_html_with_ua(
+        html,
+        koala_css::ua_stylesheet::ua_stylesheet_minimal(),
+        800.0,
+        600.0,
+    );
+
+    let full_body = box_at_depth(&full, 2);
+    let minimal_body = box_at_depth(&minimal, 2);
+    assert_eq!(full_body.tag_name.as_deref(), Some("body"));
+    assert_eq!(minimal_body.tag_name.as_deref(), Some("body"));
+    assert!((full_body.dimensions.margin.left - 8.0).abs() < 0.01);
+    assert!(minimal_body.dimensions.margin.left.abs() < 0.01);
+
+    // Both sheets still make `p` a block.
+    let minimal_p = box_at_depth(&minimal, 3);
+    assert_eq!(minimal_p.display, DisplayValue::block());
+}
+
 /// [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
 ///
 /// Two adjacent siblings with positive margins: the gap between their border
@@ -310,6 +395,158 @@ fn test_parent_child_bottom_margin_collapsing() {
     );
 }
 
+/// Gap between the border-box bottom of `a` and the border-box top of `b`.
+fn border_gap(a: &LayoutBox, b: &LayoutBox) -> f32 {
+    let a_border = a.dimensions.border_box();
+    b.dimensions.border_box().y - (a_border.y + a_border.height)
+}
+
+/// Helper: lay out `body_html` under `body { margin: 0 }` plus `css`.
+fn layout_body(css: &str, body_html: &str) -> LayoutBox {
+    layout_html(&format!(
+        "<html><head><style>body {{ margin: 0; }} {css}</style></head>\
+         <body>{body_html}</body></html>"
+    ))
+}
+
+/// [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
+///
+/// "When two or more margins collapse, the resulting margin width is the
+/// maximum of the collapsing margins' widths."
+#[test]
+fn test_sibling_margin_collapsing_explicit_margins() {
+    let root = layout_body(
+        ".a { height: 10px; margin-bottom: 20px; } .b { height: 10px; margin-top: 10px; }",
+        "<div class='a'></div><div class='b'></div>",
+    );
+    let body = box_at_depth(&root, 2);
+    let gap = border_gap(&body.children[0], &body.children[1]);
+    assert!(
+        (gap - 20.0).abs() < 0.01,
+        "blocks should be 20px apart (collapsed), not 30px; got {gap:.1}"
+    );
+}
+
+/// [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
+///
+/// "In the case of negative margins, the maximum of the absolute values of
+/// the negative adjoining margins is deducted from the maximum of the
+/// positive adjoining margins. If there are no positive margins, the maximum
+/// of the absolute values of the adjoining margins is deducted from zero."
+#[test]
+fn test_sibling_margin_collapsing_negative_margins() {
+    for (bottom, top, expected) in [(20.0, -5.0, 15.0), (-20.0, -5.0, -20.0)] {
+        let root = layout_body(
+            &format!(
+                ".a {{ height: 10px; margin-bottom: {bottom}px; }} \
+                 .b {{ height: 10px; margin-top: {top}px; }}"
+            ),
+            "<div class='a'></div><div class='b'></div>",
+        );
+        let body = box_at_depth(&root, 2);
+        let gap = border_gap(&body.children[0], &body.children[1]);
+        assert!(
+            (gap - expected).abs() < 0.01,
+            "margins {bottom} and {top} should collapse to {expected}, got {gap:.1}"
+        );
+    }
+}
+
+/// [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
+///
+/// "If the top and bottom margins of a box are adjoining, then it is
+/// possible for margins to collapse through it."
+///
+/// The margins above, through and below an empty block all collapse into
+/// one: max(10, 30, 5, 15) = 30.
+#[test]
+fn test_empty_block_margins_collapse_through() {
+    let root = layout_body(
+        ".a { height: 10px; margin-bottom: 10px; } \
+         .empty { margin: 30px 0 5px; } \
+         .b { height: 10px; margin-top: 15px; }",
+        "<div class='a'></div><div class='empty'></div><div class='b'></div>",
+    );
+    let body = box_at_depth(&root, 2);
+    let gap = border_gap(&body.children[0], &body.children[2]);
+    assert!(
+        (gap - 30.0).abs() < 0.01,
+        "margins should collapse through the empty block to 30px, got {gap:.1}"
+    );
+}
+
+/// [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
+///
+/// The collapsed parent/first-child margin is applied above the parent, not
+/// dropped: the parent's border edge and the child's both end up at
+/// max(10, 25) below the top of the page.
+#[test]
+fn test_parent_child_top_margin_collapsing_explicit_margins() {
+    let root = layout_body(
+        ".parent { margin-top: 10px; } .child { height: 10px; margin-top: 25px; }",
+        "<div class='parent'><div class='child'></div></div>",
+    );
+    let body = box_at_depth(&root, 2);
+    let parent = &body.children[0];
+    let child = &parent.children[0];
+    assert!(
+        (parent.dimensions.border_box().y - 25.0).abs() < 0.01,
+        "parent should be placed by the collapsed margin, got y={:.1}",
+        parent.dimensions.border_box().y
+    );
+    assert!(
+        (child.dimensions.border_box().y - 25.0).abs() < 0.01,
+        "child should sit flush with the parent's content top, got y={:.1}",
+        child.dimensions.border_box().y
+    );
+}
+
+/// [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
+///
+/// "...if the element has no top border, no top padding..."
+///
+/// Padding separates the parent's margin from the child's, so both apply.
+#[test]
+fn test_parent_padding_prevents_top_margin_collapsing() {
+    let root = layout_body(
+        ".parent { margin-top: 10px; padding-top: 1px; } \
+         .child { height: 10px; margin-top: 25px; }",
+        "<div class='parent'><div class='child'></div></div>",
+    );
+    let body = box_at_depth(&root, 2);
+    let parent = &body.children[0];
+    let child = &parent.children[0];
+    assert!((parent.dimensions.border_box().y - 10.0).abs() < 0.01);
+    assert!(
+        (child.dimensions.border_box().y - 36.0).abs() < 0.01,
+        "child should be at 10 + 1 + 25 = 36, got y={:.1}",
+        child.dimensions.border_box().y
+    );
+}
+
+/// [§ 8.3.1](https://www.w3.org/TR/CSS2/box.html#collapsing-margins)
+///
+/// A last child's bottom margin that collapses into its parent's bottom
+/// margin still separates the parent from the parent's next sibling:
+/// max(10, 25) = 25.
+#[test]
+fn test_parent_child_bottom_margin_separates_next_sibling() {
+    let root = layout_body(
+        ".parent { margin-bottom: 10px; } \
+         .child { height: 10px; margin-bottom: 25px; } \
+         .next { height: 10px; }",
+        "<div class='parent'><div class='child'></div></div><div class='next'></div>",
+    );
+    let body = box_at_depth(&root, 2);
+    let parent = &body.children[0];
+    assert!((parent.dimensions.content.height - 10.0).abs() < 0.01);
+    let gap = border_gap(parent, &body.children[1]);
+    assert!(
+        (gap - 25.0).abs() < 0.01,
+        "collapsed bottom margin should be 25px, got {gap:.1}"
+    );
+}
+
 // Flexbox layout tests
 //
 // [§ 9 Flex Layout Algorithm](https://www.w3.org/TR/css-flexbox-1/#layout-algorithm)
@@ -1401,6 +1638,85 @@ fn test_min_height_expands() {
 }
 
 
+// aspect-ratio tests
+//
+// [§ 5.1 Preferred Aspect Ratios: the aspect-ratio property](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+
+/// [§ 5.1](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+///
+/// An auto height follows from the width and the preferred aspect ratio.
+#[test]
+fn test_aspect_ratio_derives_auto_height() {
+    let root = layout_html(
+        "<html><head><style>\
+         .box { width: 320px; aspect-ratio: 16 / 9; }\
+         </style></head>\
+         <body><div class='box'>Video</div></body></html>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let div = &body.children[0];
+
+    assert!((div.dimensions.content.width - 320.0).abs() < 0.01);
+    assert!(
+        (div.dimensions.content.height - 180.0).abs() < 0.01,
+        "height should be 320px / (16 / 9), got {:.1}",
+        div.dimensions.content.height
+    );
+}
+
+/// [§ 5.1](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+///
+/// An auto width follows from a specified height instead of filling the
+/// containing block.
+#[test]
+fn test_aspect_ratio_derives_auto_width() {
+    let root = layout_html(
+        "<html><head><style>\
+         .box { height: 90px; aspect-ratio: 16 / 9; }\
+         </style></head>\
+         <body><div class='box'>Video</div></body></html>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let div = &body.children[0];
+
+    assert!(
+        (div.dimensions.content.width - 160.0).abs() < 0.01,
+        "width should be 90px * (16 / 9), got {:.1}",
+        div.dimensions.content.width
+    );
+    assert!((div.dimensions.content.height - 90.0).abs() < 0.01);
+}
+
+/// [§ 5.1](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+///
+/// min/max constraints still apply: a clamped width drives the derived
+/// height, and a derived height is clamped afterwards.
+#[test]
+fn test_aspect_ratio_respects_min_max() {
+    let root = layout_html(
+        "<html><head><style>\
+         div { margin: 0; }\
+         .narrow { max-width: 160px; aspect-ratio: 16 / 9; }\
+         .short { width: 320px; max-height: 100px; aspect-ratio: 16 / 9; }\
+         .border { width: 200px; padding: 10px; box-sizing: border-box; aspect-ratio: 2; }\
+         </style></head>\
+         <body><div class='narrow'></div><div class='short'></div>\
+         <div class='border'></div></body></html>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let [narrow, short, border] = [&body.children[0], &body.children[1], &body.children[2]];
+
+    assert!((narrow.dimensions.content.width - 160.0).abs() < 0.01);
+    assert!((narrow.dimensions.content.height - 90.0).abs() < 0.01);
+    assert!((short.dimensions.content.height - 100.0).abs() < 0.01);
+    // The ratio applies to the 200x100 border box.
+    assert!((border.dimensions.content.width - 180.0).abs() < 0.01);
+    assert!((border.dimensions.content.height - 80.0).abs() < 0.01);
+}
+
 // Containing block ancestor walk tests
 //
 // [§ 10.1 Definition of "containing block"](https://www.w3.org/TR/CSS2/visudet.html#containing-block-details)
@@ -2070,6 +2386,229 @@ fn test_inline_block_line_wrapping() {
     );
 }
 
+/// [§ 9.2.4 Atomic inline-level boxes](https://www.w3.org/TR/css-display-3/#atomic-inline)
+///
+/// Adjacent inline-blocks with content keep their specified widths and
+/// sit edge to edge on the same line.
+#[test]
+fn test_inline_block_side_by_side_widths() {
+    let root = layout_html(
+        "<html><body><style>\
+         body { margin: 0; }\
+         .a { display: inline-block; width: 120px; }\
+         .b { display: inline-block; width: 80px; }\
+         </style>\
+         <div><span class='a'>A</span><span class='b'>B</span></div>\
+         </body></html>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let container = &body.children[0];
+    let a = &container.children[0];
+    let b = &container.children[1];
+
+    assert!((a.dimensions.content.width - 120.0).abs() < 0.1);
+    assert!((b.dimensions.content.width - 80.0).abs() < 0.1);
+    assert!(
+        (b.dimensions.content.x - (a.dimensions.content.x + 120.0)).abs() < 0.1,
+        "second inline-block should start where the first ends, a.x={:.1}, b.x={:.1}",
+        a.dimensions.content.x,
+        b.dimensions.content.x
+    );
+    assert!(
+        (a.dimensions.content.y - b.dimensions.content.y).abs() < 0.1,
+        "inline-blocks should share a line, a.y={:.1}, b.y={:.1}",
+        a.dimensions.content.y,
+        b.dimensions.content.y
+    );
+    assert_eq!(container.line_boxes.len(), 1);
+}
+
+/// [§ 10.8.1 Leading and half-leading](https://www.w3.org/TR/CSS2/visudet.html#leading)
+///
+/// "The baseline of an 'inline-block' is the baseline of its last line box
+/// in the normal flow, unless it has either no in-flow line boxes ... in
+/// which case the baseline is the bottom margin edge."
+///
+/// An empty inline-block therefore sits on the baseline with its bottom
+/// edge, level with the text baseline of its neighbour.
+#[test]
+fn test_inline_block_baseline_alignment() {
+    let root = layout_html(
+        "<html><body><style>\
+         body { margin: 0; }\
+         .empty { display: inline-block; width: 50px; height: 100px; }\
+         .text { display: inline-block; width: 60px; }\
+         </style>\
+         <div><span class='empty'></span><span class='text'>x</span></div>\
+         </body></html>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let container = &body.children[0];
+    let empty = &container.children[0];
+    let text = &container.children[1];
+
+    let empty_bottom = empty.dimensions.margin_box().y + empty.dimensions.margin_box().height;
+    let text_line = &text.line_boxes[0];
+    let text_baseline = text_line.bounds.y + text_line.baseline;
+    assert!(
+        (empty_bottom - text_baseline).abs() < 0.1,
+        "empty inline-block bottom ({empty_bottom:.1}) should align with the \
+         text baseline ({text_baseline:.1})"
+    );
+    // The text box hangs below the baseline by its descent, so the line is
+    // taller than the empty box alone.
+    assert!(container.dimensions.content.height > 100.0);
+}
+
+/// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+///
+/// The height of the first line box when a `div` with `font-size: 10px` and
+/// the given `line-height` holds a span of 20px text.
+fn inherited_line_box_height(line_height: &str) -> f32 {
+    let root = layout_html(&format!(
+        "<html><body><style>\
+         body {{ margin: 0; }}\
+         div {{ font-size: 10px; line-height: {line_height}; }}\
+         span {{ font-size: 20px; }}\
+         </style>\
+         <div><span>text</span></div>\
+         </body></html>"
+    ));
+    let container = &box_at_depth(&root, 2).children[0];
+    container.line_boxes[0].line_height
+}
+
+/// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+///
+/// A percentage is resolved against the div's own 10px font and the span
+/// inherits the 15px result, while a number is inherited as a factor and
+/// multiplied by the span's 20px font.
+#[test]
+fn test_line_height_percentage_and_number_inherit_differently() {
+    let from_percentage = inherited_line_box_height("150%");
+    let from_em = inherited_line_box_height("1.5em");
+    let from_number = inherited_line_box_height("1.5");
+
+    assert!(
+        (from_percentage - 15.0).abs() < 0.1,
+        "got {from_percentage:.1}"
+    );
+    assert!((from_em - 15.0).abs() < 0.1, "got {from_em:.1}");
+    assert!((from_number - 30.0).abs() < 0.1, "got {from_number:.1}");
+}
+
+/// [§ 4.2 'line-height'](https://www.w3.org/TR/css-inline-3/#line-height-property)
+///
+/// "normal: Determine the preferred line height automatically based on
+/// font metrics."
+#[test]
+fn test_line_height_normal_uses_font_metrics() {
+    let height = inherited_line_box_height("normal");
+    let expected = ApproximateFontMetrics.line_height(20.0);
+    assert!((height - expected).abs() < 0.1, "got {height:.1}");
+}
+
+/// [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+///
+/// "top: Align the top of the aligned subtree with the top of the line
+/// box."
+///
+/// A tall inline-block next to text starts at the top of the line box,
+/// and the line box grows to fit it.
+#[test]
+fn test_vertical_align_top_inline_block() {
+    let root = layout_html(
+        "<html><body><style>\
+         body { margin: 0; }\
+         .ib { display: inline-block; width: 40px; height: 80px; vertical-align: top; }\
+         </style>\
+         <div>text<span class='ib'></span></div>\
+         </body></html>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let container = &body.children[0];
+    let ib = &container.children[1];
+    let line = &container.line_boxes[0];
+
+    assert_eq!(ib.vertical_align, VerticalAlign::Top);
+    let ib_top = ib.dimensions.margin_box().y;
+    assert!(
+        (ib_top - line.bounds.y).abs() < 0.1,
+        "inline-block top ({ib_top:.1}) should be the line box top ({:.1})",
+        line.bounds.y
+    );
+    assert!((line.line_height - 80.0).abs() < 0.1);
+    // The text keeps its own baseline near the top of the line instead of
+    // sinking to the bottom of the inline-block.
+    assert!(line.baseline < 20.0, "baseline was {:.1}", line.baseline);
+}
+
+/// [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+///
+/// "middle: Align the vertical midpoint of the box with the baseline of
+/// the parent box plus half the x-height of the parent."
+///
+/// A tall inline-block is centered on the text baseline, and the line box
+/// is tall enough to hold both halves.
+#[test]
+fn test_vertical_align_middle_inline_block() {
+    let root = layout_html(
+        "<html><body><style>\
+         body { margin: 0; }\
+         .ib { display: inline-block; width: 40px; height: 80px; vertical-align: middle; }\
+         </style>\
+         <div>text<span class='ib'></span></div>\
+         </body></html>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let container = &body.children[0];
+    let ib = &container.children[1];
+    let line = &container.line_boxes[0];
+
+    let mb = ib.dimensions.margin_box();
+    let midpoint = mb.y + mb.height / 2.0;
+    let baseline = line.bounds.y + line.baseline;
+    assert!(
+        (midpoint - baseline).abs() < 0.1,
+        "inline-block midpoint ({midpoint:.1}) should sit on the baseline ({baseline:.1})"
+    );
+    assert!(mb.y >= line.bounds.y - 0.1);
+    assert!(mb.y + mb.height <= line.bounds.y + line.line_height + 0.1);
+}
+
+/// [§ 10.8.1 'vertical-align'](https://www.w3.org/TR/CSS2/visudet.html#propdef-vertical-align)
+///
+/// "<length>: Raise (positive value) or lower (negative value) the box by
+/// this distance."
+#[test]
+fn test_vertical_align_length_raises_inline_block() {
+    let html = |align: &str| {
+        format!(
+            "<html><body><style>\
+             body {{ margin: 0; }}\
+             .ib {{ display: inline-block; width: 40px; height: 20px; vertical-align: {align}; }}\
+             </style>\
+             <div>text<span class='ib'></span></div>\
+             </body></html>"
+        )
+    };
+    let baseline_root = layout_html(&html("baseline"));
+    let raised_root = layout_html(&html("10px"));
+
+    let bottom = |root: &LayoutBox| {
+        let container = &box_at_depth(root, 2).children[0];
+        let line = &container.line_boxes[0];
+        let mb = container.children[1].dimensions.margin_box();
+        mb.y + mb.height - (line.bounds.y + line.baseline)
+    };
+    assert!(bottom(&baseline_root).abs() < 0.1);
+    assert!((bottom(&raised_root) + 10.0).abs() < 0.1);
+}
+
 /// [§ 16.2 Alignment: the 'text-align' property](https://www.w3.org/TR/CSS2/text.html#alignment-prop)
 ///
 /// "Inline-level content is centered within the line box."
@@ -2243,6 +2782,11 @@ fn test_ol_start_attribute() {
 
 /// Helper: parse HTML, build layout + styles, paint, and return the display list.
 fn paint_html(html: &str) -> koala_css::DisplayList {
+    paint_html_with(html, false)
+}
+
+/// Helper: like `paint_html`, optionally building a retained display list.
+fn paint_html_with(html: &str, retained: bool) -> koala_css::DisplayList {
     use koala_css::cascade::compute_styles;
     use koala_css::{CSSParser, CSSTokenizer, DisplayListBuilder, Stylesheet};
     use koala_std::collections::HashMap;
@@ -2263,7 +2807,7 @@ fn paint_html(html: &str) -> koala_css::DisplayList {
     };
 
     let ua = koala_css::ua_stylesheet::ua_stylesheet();
-    let styles = compute_styles(&dom, ua, &author);
+    let styles = compute_styles(&dom, ua, None, &author, DEFAULT_FONT_SIZE_PX);
 
     let image_dims = HashMap::new();
     let mut layout_tree = LayoutBox::build_layout_tree(&dom, &styles, dom.root(), &image_dims)
@@ -2277,56 +2821,290 @@ fn paint_html(html: &str) -> koala_css::DisplayList {
     };
     layout_tree.layout(viewport, viewport, &ApproximateFontMetrics, viewport);
 
-    let builder = DisplayListBuilder::new(&styles);
+    let builder = DisplayListBuilder::new(&styles).retained(retained);
     builder.build(&layout_tree)
 }
 
 #[test]
-fn test_overflow_hidden_emits_push_pop_clip() {
-    // [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
-    //
-    // An element with `overflow: hidden` should emit PushClip before its
-    // content and PopClip after, with the clip rect matching its padding box.
-    use koala_css::DisplayCommand;
+fn test_retained_display_list_identical_layouts_have_empty_diff() {
+    let html = "<style>p { color: red; background-color: yellow; }</style><p>Hello</p>";
+    let previous = paint_html_with(html, true);
+    let next = paint_html_with(html, true);
 
-    let display_list = paint_html(
-        "<style>div { overflow: hidden; width: 100px; height: 50px; }</style>\
-         <div>Hello world</div>",
+    assert!(previous.is_retained());
+    assert_eq!(
+        previous.ids().map(<[_]>::len),
+        Some(previous.len()),
+        "every command should carry an ID"
+    );
+    assert_eq!(
+        previous.ids(),
+        next.ids(),
+        "IDs should be stable across frames"
     );
+    assert!(previous.diff(&next).is_empty());
+}
 
-    let commands = display_list.commands();
-    let push_clips: Vec<_> = commands
-        .iter()
-        .filter(|c| matches!(c, DisplayCommand::PushClip { .. }))
-        .collect();
-    let pop_clips: Vec<_> = commands
-        .iter()
-        .filter(|c| matches!(c, DisplayCommand::PopClip))
-        .collect();
+#[test]
+fn test_retained_display_list_color_change_diffs_single_command() {
+    use koala_css::DisplayCommand;
 
-    assert!(
-        !push_clips.is_empty(),
-        "overflow: hidden should produce at least one PushClip"
+    let previous = paint_html_with(
+        "<style>p { color: red; background-color: yellow; }</style><p>Hello</p>",
+        true,
     );
-    assert_eq!(
-        push_clips.len(),
-        pop_clips.len(),
-        "PushClip and PopClip should be balanced"
+    let next = paint_html_with(
+        "<style>p { color: blue; background-color: yellow; }</style><p>Hello</p>",
+        true,
     );
 
-    // Verify the PushClip dimensions match the div's width/height
-    if let DisplayCommand::PushClip { width, height, .. } = push_clips[0] {
-        assert!(
-            (*width - 100.0).abs() < 1.0,
-            "clip width should be ~100px, got {width}"
-        );
-        assert!(
-            (*height - 50.0).abs() < 1.0,
-            "clip height should be ~50px, got {height}"
-        );
+    let changed = previous.diff(&next);
+    assert_eq!(changed.len(), 1, "only the text should change: {changed:?}");
+    match &changed[0] {
+        DisplayCommand::DrawText { text, color, .. } => {
+            assert_eq!(text, "Hello");
+            assert_eq!((color.r, color.g, color.b), (0, 0, 255));
+        }
+        other => panic!("expected DrawText, got {other:?}"),
     }
 }
 
+#[test]
+fn test_display_list_diff_without_ids_compares_by_position() {
+    let previous = paint_html("<style>p { color: red; }</style><p>Hello</p>");
+    let same = paint_html("<style>p { color: red; }</style><p>Hello</p>");
+    let changed = paint_html("<style>p { color: green; }</style><p>Hello</p>");
+
+    assert!(!previous.is_retained());
+    assert!(previous.diff(&same).is_empty());
+    assert_eq!(previous.diff(&changed).len(), 1);
+}
+
+#[test]
+fn test_outline_is_drawn_outside_border_box_without_taking_space() {
+    // [CSS UI 4 § 5 Outline properties](https://www.w3.org/TR/css-ui-4/#outline-props)
+    //
+    // "Outlines do not take up space." The ring starts `outline-offset`
+    // outside the border edge and is `outline-width` thick.
+    use koala_css::DisplayCommand;
+
+    let base = "div { width: 100px; height: 50px; border: 1px solid black; }";
+    let outlined = "div { outline: 2px solid rgb(255, 0, 0); outline-offset: 3px; }";
+    let plain_root = layout_html(&format!("<style>{base}</style><div></div>"));
+    let html = format!("<style>{base} {outlined}</style><div></div>");
+    let root = layout_html(&html);
+
+    let plain = &box_at_depth(&plain_root, 3).dimensions;
+    let div = &box_at_depth(&root, 3).dimensions;
+    assert_eq!(
+        format!("{div:?}"),
+        format!("{plain:?}"),
+        "the outline must not change the box"
+    );
+    let border_box = div.border_box();
+    assert_eq!((border_box.width, border_box.height), (102.0, 52.0));
+
+    let display_list = paint_html(&html);
+    let outline_rects: Vec<_> = display_list
+        .commands()
+        .iter()
+        .filter_map(|c| match c {
+            DisplayCommand::FillRect {
+                x,
+                y,
+                width,
+                height,
+                color,
+                ..
+            } if (color.r, color.g, color.b) == (255, 0, 0) => Some((*x, *y, *width, *height)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        outline_rects.len(),
+        4,
+        "one rect per side: {outline_rects:?}"
+    );
+
+    let left = outline_rects
+        .iter()
+        .map(|r| r.0)
+        .fold(f32::INFINITY, f32::min);
+    let top = outline_rects
+        .iter()
+        .map(|r| r.1)
+        .fold(f32::INFINITY, f32::min);
+    let right = outline_rects
+        .iter()
+        .map(|r| r.0 + r.2)
+        .fold(f32::NEG_INFINITY, f32::max);
+    let bottom = outline_rects
+        .iter()
+        .map(|r| r.1 + r.3)
+        .fold(f32::NEG_INFINITY, f32::max);
+    // Offset (3px) plus width (2px) outside each border edge.
+    assert!((left - (border_box.x - 5.0)).abs() < 0.01, "left {left}");
+    assert!((top - (border_box.y - 5.0)).abs() < 0.01, "top {top}");
+    let border_right = border_box.x + border_box.width;
+    let border_bottom = border_box.y + border_box.height;
+    assert!((right - (border_right + 5.0)).abs() < 0.01, "right {right}");
+    assert!(
+        (bottom - (border_bottom + 5.0)).abs() < 0.01,
+        "bottom {bottom}"
+    );
+}
+
+#[test]
+fn test_overflow_hidden_emits_push_pop_clip() {
+    // [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
+    //
+    // An element with `overflow: hidden` should emit PushClip before its
+    // content and PopClip after, with the clip rect matching its padding box.
+    use koala_css::DisplayCommand;
+
+    let display_list = paint_html(
+        "<style>div { overflow: hidden; width: 100px; height: 50px; }</style>\
+         <div>Hello world</div>",
+    );
+
+    let commands = display_list.commands();
+    let push_clips: Vec<_> = commands
+        .iter()
+        .filter(|c| matches!(c, DisplayCommand::PushClip(..)))
+        .collect();
+    let pop_clips: Vec<_> = commands
+        .iter()
+        .filter(|c| matches!(c, DisplayCommand::PopClip))
+        .collect();
+
+    assert!(
+        !push_clips.is_empty(),
+        "overflow: hidden should produce at least one PushClip"
+    );
+    assert_eq!(
+        push_clips.len(),
+        pop_clips.len(),
+        "PushClip and PopClip should be balanced"
+    );
+
+    // Verify the PushClip dimensions match the div's width/height
+    if let DisplayCommand::PushClip(rect, radius) = push_clips[0] {
+        assert!(
+            (rect.width - 100.0).abs() < 1.0,
+            "clip width should be ~100px, got {}",
+            rect.width
+        );
+        assert!(
+            (rect.height - 50.0).abs() < 1.0,
+            "clip height should be ~50px, got {}",
+            rect.height
+        );
+        assert_eq!(*radius, None, "square box should clip without radii");
+    }
+}
+
+#[test]
+fn test_overflow_x_hidden_clips_only_horizontally() {
+    // [§ 3 overflow-x, overflow-y](https://www.w3.org/TR/css-overflow-3/#overflow-properties)
+    //
+    // With only `overflow-x` clipping, the clip rect spans the padding box
+    // horizontally and is unbounded vertically.
+    use koala_css::DisplayCommand;
+
+    let display_list = paint_html(
+        "<style>div { overflow-x: hidden; overflow-y: visible; width: 100px; height: 50px; }\
+         </style><div>Hello world</div>",
+    );
+    let clip = display_list.commands().iter().find_map(|c| match c {
+        DisplayCommand::PushClip(rect, _) => Some((rect.x, rect.y, rect.width, rect.height)),
+        _ => None,
+    });
+    let (x, y, width, height) = clip.expect("overflow-x: hidden should produce a PushClip");
+    assert!((width - 100.0).abs() < 1.0, "clip width {width}");
+    assert!(x > 0.0, "clip x {x}");
+    assert!(
+        y < -1.0e6 && y + height > 1.0e6,
+        "clip y {y}, height {height}"
+    );
+}
+
+#[test]
+fn test_overflow_hidden_clip_brackets_children() {
+    // [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
+    //
+    // The box's own background is painted unclipped; everything its
+    // descendants paint sits between its PushClip and the matching PopClip.
+    use koala_css::DisplayCommand;
+
+    let display_list = paint_html(
+        "<style>.clip { overflow: hidden; width: 100px; height: 50px; \
+         background-color: red; } .child { height: 80px; background-color: blue; }\
+         </style><div class=\"clip\"><div class=\"child\">inner</div></div>",
+    );
+    let commands = display_list.commands();
+
+    let push = commands
+        .iter()
+        .position(|c| matches!(c, DisplayCommand::PushClip(..)))
+        .expect("overflow: hidden should produce a PushClip");
+    let pop = commands
+        .iter()
+        .rposition(|c| matches!(c, DisplayCommand::PopClip))
+        .expect("overflow: hidden should produce a PopClip");
+    assert!(push < pop);
+
+    let fill_at = |color: u8| {
+        commands.iter().position(|c| {
+            matches!(c, DisplayCommand::FillRect { color: fill, .. }
+                if fill.r == color && fill.b == 255 - color)
+        })
+    };
+    let background = fill_at(255).expect("red background");
+    let child = fill_at(0).expect("blue child background");
+    let text = commands
+        .iter()
+        .position(|c| matches!(c, DisplayCommand::DrawText { text, .. } if text == "inner"))
+        .expect("child text");
+    assert!(background < push, "own background is outside the clip");
+    assert!(push < child && child < pop, "child background is clipped");
+    assert!(push < text && text < pop, "child text is clipped");
+    // Nothing else is clipped or left open.
+    assert_eq!(
+        commands
+            .iter()
+            .filter(|c| matches!(c, DisplayCommand::PushClip(..) | DisplayCommand::PopClip))
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn test_overflow_hidden_clip_follows_inner_border_radius() {
+    // [§ 5.3 Corner Clipping](https://www.w3.org/TR/css-backgrounds-3/#corner-clipping)
+    //
+    // 'overflow' clips to the padding edge curve, whose radius is the
+    // outer radius minus the border width.
+    use koala_css::{BorderRadius, DisplayCommand};
+
+    let display_list = paint_html(
+        "<style>div { overflow: hidden; width: 100px; height: 50px; \
+         border: 4px solid black; border-radius: 10px; }</style><div>Hello</div>",
+    );
+    let radius = display_list.commands().iter().find_map(|c| match c {
+        DisplayCommand::PushClip(_, radius) => Some(*radius),
+        _ => None,
+    });
+    assert_eq!(
+        radius,
+        Some(Some(BorderRadius {
+            top_left: 6.0,
+            top_right: 6.0,
+            bottom_right: 6.0,
+            bottom_left: 6.0,
+        }))
+    );
+}
+
 #[test]
 fn test_default_overflow_visible_no_clip() {
     // [§ 11.1.1 overflow](https://www.w3.org/TR/CSS2/visufx.html#overflow)
@@ -2344,7 +3122,7 @@ fn test_default_overflow_visible_no_clip() {
     let commands = display_list.commands();
     let has_clip = commands
         .iter()
-        .any(|c| matches!(c, DisplayCommand::PushClip { .. } | DisplayCommand::PopClip));
+        .any(|c| matches!(c, DisplayCommand::PushClip(..) | DisplayCommand::PopClip));
 
     assert!(
         !has_clip,
@@ -2368,7 +3146,7 @@ fn test_nested_overflow_hidden() {
     let commands = display_list.commands();
     let push_count = commands
         .iter()
-        .filter(|c| matches!(c, DisplayCommand::PushClip { .. }))
+        .filter(|c| matches!(c, DisplayCommand::PushClip(..)))
         .count();
     let pop_count = commands
         .iter()
@@ -2389,7 +3167,7 @@ fn test_nested_overflow_hidden() {
     let mut max_depth = 0i32;
     for cmd in commands {
         match cmd {
-            DisplayCommand::PushClip { .. } => {
+            DisplayCommand::PushClip(..) => {
                 depth += 1;
                 max_depth = max_depth.max(depth);
             }
@@ -3258,6 +4036,42 @@ fn test_table_explicit_width() {
     }
 }
 
+/// [§ 17.5.2.1 Fixed table layout](https://www.w3.org/TR/CSS2/tables.html#fixed-table-layout)
+///
+/// The first row's explicit cell width sets its column; the remaining
+/// column takes the rest of the table width, regardless of cell content.
+#[test]
+fn test_table_fixed_layout_column_widths() {
+    let root = layout_html(
+        "<html><body>
+            <style>
+                table { table-layout: fixed; width: 400px; }
+                td { padding: 0; }
+                .first { width: 100px; }
+            </style>
+            <table>
+                <tr><td class=first>A</td><td>B</td></tr>
+                <tr><td>A much longer cell that would widen an auto column</td><td>D</td></tr>
+            </table>
+        </body></html>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let table = &body.children[0];
+    let tbody = &table.children[0];
+    assert_eq!(tbody.tag_name.as_deref(), Some("tbody"));
+    let table_x = table.dimensions.content.x;
+
+    for row in &tbody.children {
+        let (left, right) = (&row.children[0], &row.children[1]);
+        // border-spacing is 2px: 2 + 100 + 2 + 294 + 2 = 400.
+        assert!((left.dimensions.content.x - (table_x + 2.0)).abs() < 0.01);
+        assert!((left.dimensions.content.width - 100.0).abs() < 0.01);
+        assert!((right.dimensions.content.x - (table_x + 104.0)).abs() < 0.01);
+        assert!((right.dimensions.content.width - 294.0).abs() < 0.01);
+    }
+}
+
 /// [§ 17.2.1](https://www.w3.org/TR/CSS2/tables.html#anonymous-boxes)
 ///
 /// Table with colspan: merged cells should span multiple columns.
@@ -3376,6 +4190,159 @@ fn test_table_row_groups() {
     );
 }
 
+/// [§ 4.9.12.1 Forming a table](https://html.spec.whatwg.org/multipage/tables.html#forming-a-table)
+///
+/// A `colspan=2` header covers both columns, and a `rowspan=2` cell keeps
+/// its column occupied so the next row's only cell lands in column two.
+#[test]
+fn test_table_colspan_and_rowspan_slots() {
+    let root = layout_html(
+        "<html><body>
+            <style>table { table-layout: fixed; width: 400px; } td { padding: 0; }
+                   .tall { height: 100px; }</style>
+            <table>
+                <tr><td colspan=2>Header</td></tr>
+                <tr><td rowspan=2 class=tall>Tall</td><td>B</td></tr>
+                <tr><td>C</td></tr>
+            </table>
+        </body></html>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let table = &body.children[0];
+    let rows = &table.children[0].children;
+    let table_x = table.dimensions.content.x;
+    let header = &rows[0].children[0].dimensions.content;
+    let tall = &rows[1].children[0].dimensions.content;
+    let b = &rows[1].children[1].dimensions.content;
+    let c = &rows[2].children[0].dimensions.content;
+
+    // Two 197px columns: 2 + 197 + 2 + 197 + 2 = 400.
+    assert!((header.x - (table_x + 2.0)).abs() < 0.01);
+    assert!((header.width - 396.0).abs() < 0.01);
+    assert!((tall.x - (table_x + 2.0)).abs() < 0.01);
+    assert!((b.x - (table_x + 201.0)).abs() < 0.01);
+
+    // C is pushed past the rowspan cell into the second column, one row
+    // below B.
+    assert!((c.x - b.x).abs() < 0.01, "C should share B's column: {c:?}");
+    assert!(c.y > b.y + b.height);
+
+    // The two rows the tall cell spans grow to fit it.
+    let third_row = &rows[2].dimensions.content;
+    assert!((tall.height - 100.0).abs() < 0.01);
+    assert!((third_row.y + third_row.height - (tall.y + tall.height)).abs() < 0.01);
+}
+
+/// [§ 17.4.1 Caption position and alignment](https://www.w3.org/TR/CSS2/tables.html#caption-position)
+///
+/// "top: Positions the caption box above the table box."
+/// "bottom: Positions the caption box below the table box."
+#[test]
+fn test_table_caption_side() {
+    let html = |caption_side: &str| {
+        format!(
+            "<html><head><style>caption {{ caption-side: {caption_side}; }}</style></head>\
+             <body><table><caption>Title</caption>\
+             <tr><td>A</td><td>B</td></tr></table></body></html>"
+        )
+    };
+    let parts = |root: &LayoutBox| {
+        let table = box_at_depth(root, 3).clone();
+        let caption = table
+            .children
+            .iter()
+            .find(|c| c.tag_name.as_deref() == Some("caption"))
+            .expect("caption box")
+            .clone();
+        let tbody = table
+            .children
+            .iter()
+            .find(|c| c.tag_name.as_deref() == Some("tbody"))
+            .expect("tbody box");
+        let first_cell = tbody.children[0].children[0].dimensions.border_box();
+        (table, caption.dimensions.border_box(), first_cell)
+    };
+
+    let root = layout_html(&html("top"));
+    let (table, caption, cell) = parts(&root);
+    assert!((caption.y - table.dimensions.content.y).abs() < 0.01);
+    assert!(caption.height > 0.0);
+    assert!(
+        cell.y >= caption.y + caption.height,
+        "first row should be below the caption: caption={caption:?}, cell={cell:?}"
+    );
+    assert!((caption.width - table.dimensions.content.width).abs() < 0.01);
+
+    let root = layout_html(&html("bottom"));
+    let (table, caption, cell) = parts(&root);
+    assert!(
+        caption.y >= cell.y + cell.height,
+        "caption should be below the last row: caption={caption:?}, cell={cell:?}"
+    );
+    let table_bottom = table.dimensions.content.y + table.dimensions.content.height;
+    assert!((caption.y + caption.height - table_bottom).abs() < 0.01);
+}
+
+/// [§ 17.6.1 The separated borders model](https://www.w3.org/TR/CSS2/tables.html#separated-borders)
+///
+/// "The 'border-spacing' property specifies the distance that separates
+/// adjoining cell borders."
+#[test]
+fn test_table_border_spacing_separates_cells() {
+    let root = layout_html(
+        "<html><head><style>table { border-spacing: 5px; } td { padding: 0; }</style></head>\
+         <body><table><tr><td>A</td><td>B</td></tr>\
+         <tr><td>C</td><td>D</td></tr></table></body></html>",
+    );
+    let table = box_at_depth(&root, 3);
+    let tbody = &table.children[0];
+    let a = tbody.children[0].children[0].dimensions.border_box();
+    let b = tbody.children[0].children[1].dimensions.border_box();
+    let c = tbody.children[1].children[0].dimensions.border_box();
+
+    assert!(
+        (b.x - (a.x + a.width) - 5.0).abs() < 0.01,
+        "a={a:?}, b={b:?}"
+    );
+    assert!(
+        (c.y - (a.y + a.height) - 5.0).abs() < 0.01,
+        "a={a:?}, c={c:?}"
+    );
+    assert!((a.x - table.dimensions.content.x - 5.0).abs() < 0.01);
+}
+
+/// [§ 17.6.2 The collapsing border model](https://www.w3.org/TR/CSS2/tables.html#collapsing-borders)
+///
+/// Collapsed tables ignore `border-spacing`, and each grid line takes the
+/// wider of the borders that meet on it.
+#[test]
+fn test_table_border_collapse_shares_edges() {
+    let root = layout_html(
+        "<html><head><style>\
+         table { border-collapse: collapse; border-spacing: 5px; }\
+         td { padding: 0; border: 1px solid black; }\
+         .wide { border-left: 3px solid black; }\
+         </style></head>\
+         <body><table><tr><td>A</td><td class=\"wide\">B</td></tr></table></body></html>",
+    );
+    let table = box_at_depth(&root, 3);
+    let tbody = &table.children[0];
+    let a = tbody.children[0].children[0].dimensions.border_box();
+    assert!((a.x - table.dimensions.content.x).abs() < 0.01, "a={a:?}");
+
+    let viewport = Rect {
+        x: 0.0,
+        y: 0.0,
+        width: 800.0,
+        height: 600.0,
+    };
+    assert_eq!(
+        koala_css::layout::collapsed_column_edges(table, viewport),
+        vec![1.0, 3.0, 1.0]
+    );
+}
+
 /// Table cells with nested content (paragraphs and text).
 #[test]
 fn test_table_nested_content() {
@@ -3838,16 +4805,16 @@ fn test_text_decoration_ua_link_underline() {
 
     let link_run = &runs[0];
     assert!(
-        link_run.text_decoration.underline,
+        link_run.text_decoration.line.underline,
         "UA stylesheet should give <a> underline decoration, got {:?}",
         link_run.text_decoration
     );
     assert!(
-        !link_run.text_decoration.line_through,
+        !link_run.text_decoration.line.line_through,
         "link should not have line-through"
     );
     assert!(
-        !link_run.text_decoration.overline,
+        !link_run.text_decoration.line.overline,
         "link should not have overline"
     );
 }
@@ -3865,7 +4832,7 @@ fn test_text_decoration_underline() {
 
     let run = &runs[0];
     assert!(
-        run.text_decoration.underline,
+        run.text_decoration.line.underline,
         "text-decoration: underline should set underline=true, got {:?}",
         run.text_decoration
     );
@@ -3884,12 +4851,12 @@ fn test_text_decoration_line_through() {
 
     let run = &runs[0];
     assert!(
-        run.text_decoration.line_through,
+        run.text_decoration.line.line_through,
         "text-decoration: line-through should set line_through=true, got {:?}",
         run.text_decoration
     );
     assert!(
-        !run.text_decoration.underline,
+        !run.text_decoration.line.underline,
         "line-through should not set underline"
     );
 }
@@ -3907,16 +4874,16 @@ fn test_text_decoration_overline() {
 
     let run = &runs[0];
     assert!(
-        run.text_decoration.overline,
+        run.text_decoration.line.overline,
         "text-decoration: overline should set overline=true, got {:?}",
         run.text_decoration
     );
     assert!(
-        !run.text_decoration.underline,
+        !run.text_decoration.line.underline,
         "overline should not set underline"
     );
     assert!(
-        !run.text_decoration.line_through,
+        !run.text_decoration.line.line_through,
         "overline should not set line_through"
     );
 }
@@ -3934,12 +4901,12 @@ fn test_text_decoration_none() {
 
     let run = &runs[0];
     assert!(
-        !run.text_decoration.underline,
+        !run.text_decoration.line.underline,
         "text-decoration: none should override UA underline, got {:?}",
         run.text_decoration
     );
-    assert!(!run.text_decoration.overline);
-    assert!(!run.text_decoration.line_through);
+    assert!(!run.text_decoration.line.overline);
+    assert!(!run.text_decoration.line.line_through);
 }
 
 /// Combined values: `text-decoration: underline line-through`.
@@ -3959,17 +4926,17 @@ fn test_text_decoration_combined() {
 
     let run = &runs[0];
     assert!(
-        run.text_decoration.underline,
+        run.text_decoration.line.underline,
         "combined should have underline, got {:?}",
         run.text_decoration
     );
     assert!(
-        run.text_decoration.line_through,
+        run.text_decoration.line.line_through,
         "combined should have line_through, got {:?}",
         run.text_decoration
     );
     assert!(
-        !run.text_decoration.overline,
+        !run.text_decoration.line.overline,
         "combined should not have overline"
     );
 }
@@ -3986,9 +4953,18 @@ fn test_text_decoration_line_longhand() {
     assert!(!runs.is_empty());
 
     let run = &runs[0];
-    assert!(run.text_decoration.underline, "longhand should set underline");
-    assert!(run.text_decoration.overline, "longhand should set overline");
-    assert!(!run.text_decoration.line_through, "longhand should not set line_through");
+    assert!(
+        run.text_decoration.line.underline,
+        "longhand should set underline"
+    );
+    assert!(
+        run.text_decoration.line.overline,
+        "longhand should set overline"
+    );
+    assert!(
+        !run.text_decoration.line.line_through,
+        "longhand should not set line_through"
+    );
 }
 
 /// `text-decoration` is NOT inherited — a child element without its own
@@ -4008,17 +4984,116 @@ fn test_text_decoration_not_inherited() {
     let child = &parent.children[0];
 
     assert!(
-        parent.text_decoration.underline,
+        parent.text_decoration.line.underline,
         "parent should have underline, got {:?}",
         parent.text_decoration
     );
     assert!(
-        !child.text_decoration.underline,
+        !child.text_decoration.line.underline,
         "child should NOT inherit text-decoration, got {:?}",
         child.text_decoration
     );
 }
 
+/// [§ 2.4 'text-decoration'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-property)
+///
+/// The shorthand sets the line, style and color. The decoration keeps its
+/// own color rather than the text color, and `currentcolor` (the initial
+/// value) resolves to the decorating element's `color`.
+#[test]
+fn test_text_decoration_shorthand_style_and_color() {
+    let root = layout_html(
+        "<style>\
+           span { color: blue; text-decoration: underline dotted red; }\
+           em { color: green; text-decoration: overline wavy; }\
+         </style>\
+         <p><span>Dotted</span> <em>Wavy</em></p>",
+    );
+    let body = box_at_depth(&root, 2);
+    let runs = collect_text_runs(body);
+    let run_for = |text: &str| runs.iter().find(|r| r.text.contains(text)).unwrap();
+
+    let dotted = run_for("Dotted");
+    assert_eq!(
+        dotted.text_decoration,
+        TextDecoration {
+            line: TextDecorationLine {
+                underline: true,
+                ..TextDecorationLine::default()
+            },
+            style: TextDecorationStyle::Dotted,
+            color: ColorValue::from_named("red").unwrap(),
+        }
+    );
+    assert_eq!(dotted.color, ColorValue::from_named("blue").unwrap());
+
+    let wavy = run_for("Wavy");
+    assert!(wavy.text_decoration.line.overline);
+    assert_eq!(wavy.text_decoration.style, TextDecorationStyle::Wavy);
+    assert_eq!(
+        wavy.text_decoration.color,
+        ColorValue::from_named("green").unwrap()
+    );
+}
+
+/// [§ 2.2 ::first-letter](https://www.w3.org/TR/css-pseudo-4/#first-letter-pseudo)
+///
+/// Only the first letter takes the pseudo-element's font size; leading
+/// punctuation joins it.
+#[test]
+fn test_first_letter_enlarges_only_the_first_glyph() {
+    let root = layout_html(
+        "<style>p::first-letter { font-size: 2em; color: red; }</style>\
+         <p>\"Hello world</p>",
+    );
+    let p = box_at_depth(&root, 3);
+    let runs = first_line_runs(p);
+
+    assert_eq!(runs[0].text, "\"H");
+    assert!((runs[0].font_size - 32.0).abs() < 0.01);
+    assert_eq!(runs[0].color, ColorValue::from_named("red").unwrap());
+    let rest: String = runs[1..].iter().map(|r| r.text.as_str()).collect();
+    assert_eq!(rest, "ello world");
+    assert!(runs[1..].iter().all(|r| (r.font_size - 16.0).abs() < 0.01));
+    assert!(runs[1..].iter().all(|r| r.color == ColorValue::BLACK));
+}
+
+/// [§ 2.1 ::first-line](https://www.w3.org/TR/css-pseudo-4/#first-line-pseudo)
+///
+/// The first line's runs take the pseudo-element's color, except those of
+/// a descendant with a color of its own; later lines keep the element's.
+#[test]
+fn test_first_line_colors_only_the_first_line() {
+    let root = layout_html(
+        "<style>\
+           p { width: 100px; }\
+           p::first-line { color: red; }\
+           span { color: blue; }\
+         </style>\
+         <p>one <span>two</span> three four five six</p>",
+    );
+    let p = box_at_depth(&root, 3);
+    assert!(p.line_boxes.len() > 1, "expected the text to wrap");
+
+    let red = ColorValue::from_named("red").unwrap();
+    let blue = ColorValue::from_named("blue").unwrap();
+    for run in first_line_runs(p) {
+        let expected = if run.text.contains("two") {
+            &blue
+        } else {
+            &red
+        };
+        assert_eq!(&run.color, expected, "run {:?}", run.text);
+    }
+    for line in &p.line_boxes[1..] {
+        for fragment in &line.fragments {
+            if let FragmentContent::Text(run) = &fragment.content {
+                assert_eq!(run.color, ColorValue::BLACK, "run {:?}", run.text);
+            }
+        }
+    }
+}
+
 /// The LayoutBox.text_decoration field on the element directly should reflect
 /// the computed style.
 #[test]
@@ -4030,17 +5105,17 @@ fn test_text_decoration_on_layout_box() {
     let div = &box_at_depth(&root, 2).children[0];
 
     assert!(
-        div.text_decoration.overline,
+        div.text_decoration.line.overline,
         "LayoutBox should have overline, got {:?}",
         div.text_decoration
     );
     assert!(
-        div.text_decoration.line_through,
+        div.text_decoration.line.line_through,
         "LayoutBox should have line_through, got {:?}",
         div.text_decoration
     );
     assert!(
-        !div.text_decoration.underline,
+        !div.text_decoration.line.underline,
         "LayoutBox should not have underline"
     );
 }
@@ -4262,44 +5337,367 @@ fn test_normal_whitespace_collapses_newlines() {
     );
 }
 
-// WebKit vendor-prefix aliases and no-op properties
-//
-// Real-world stylesheets routinely set `-webkit-text-decoration`,
-// `-webkit-appearance`, and `-webkit-text-size-adjust` alongside their
-// modern counterparts. We accept the prefixed forms (and their unprefixed
-// standards where applicable) without falling through to the unknown-
-// property warning path. These tests pin down that behaviour.
-
-/// `-webkit-text-decoration: underline` should behave identically to the
-/// unprefixed `text-decoration: underline` — both route to the same arm
-/// in the style computation, so the resulting `TextRun` carries the
-/// underline flag.
+/// [CSS Text 3 § 3](https://www.w3.org/TR/css-text-3/#white-space-property)
+///
+/// `pre-line` collapses runs of spaces like `normal`, but each preserved
+/// newline forces a line break and the spaces around it are removed.
 #[test]
-fn test_webkit_text_decoration_alias_underline() {
-    let root = layout_html(
-        "<style>span { -webkit-text-decoration: underline; }</style>\
-         <p><span>Underlined via prefix</span></p>",
-    );
-    let body = box_at_depth(&root, 2);
-    let runs = collect_text_runs(body);
-    assert!(!runs.is_empty(), "should have text runs");
+fn test_pre_line_collapses_spaces_and_keeps_newlines() {
+    let root = layout_html("<p style=\"white-space: pre-line\">  a   b  \n  c\t\td\n\ne</p>");
+    let p = box_at_depth(&root, 3);
 
-    let run = &runs[0];
-    assert!(
-        run.text_decoration.underline,
-        "-webkit-text-decoration: underline should alias to \
-         text-decoration: underline and set underline=true, got {:?}",
-        run.text_decoration
-    );
+    let lines: Vec<String> = p
+        .line_boxes
+        .iter()
+        .map(|line| {
+            line.fragments
+                .iter()
+                .filter_map(|f| match &f.content {
+                    FragmentContent::Text(run) => Some(run.text.as_str()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(lines, ["a b", "c d", "", "e"]);
 }
 
-/// `-webkit-text-decoration: line-through` aliases to `text-decoration:
-/// line-through` and sets the `line_through` flag on the resulting run.
-/// Locks in that the alias is not a partial implementation.
+/// The text of every run in the first line box of the body's first child,
+/// joined.
+fn first_line_text(body_html: &str) -> String {
+    let root = layout_html(body_html);
+    let block = box_at_depth(&root, 3);
+    first_line_runs(block)
+        .iter()
+        .map(|run| run.text.as_str())
+        .collect()
+}
+
+/// [CSS Text 3 § 4.1.1](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+///
+/// White space between two inline elements collapses to a single space that
+/// still renders; adjacent inline elements with nothing between them get
+/// no space.
 #[test]
-fn test_webkit_text_decoration_alias_line_through() {
-    let root = layout_html(
-        "<style>.del { -webkit-text-decoration: line-through; }</style>\
+fn test_whitespace_between_inline_elements_is_preserved() {
+    assert_eq!(first_line_text("<p><b>a</b> <b>b</b></p>"), "a b");
+    assert_eq!(first_line_text("<p><b>a</b><b>b</b></p>"), "ab");
+}
+
+/// [CSS Text 3 § 4.1.1](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+///
+/// White space at the edge of an inline element still separates the
+/// inline content found past the element's boundary.
+#[test]
+fn test_whitespace_at_inline_element_edge_is_preserved() {
+    // The space is the first child of the `<span>`.
+    assert_eq!(first_line_text("<p>x<span> <b>y</b></span></p>"), "x y");
+    // The space is the only child of the `<span>`.
+    assert_eq!(first_line_text("<p>a<span> </span>b</p>"), "a b");
+    // Nothing inline follows the `<span>`.
+    assert_eq!(first_line_text("<p>a<span> </span></p>"), "a");
+}
+
+/// [CSS Text 3 § 4.1.1](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+///
+/// "Any collapsible space immediately following another collapsible
+/// space—even one outside the boundary of the inline containing that
+/// space ... is collapsed to have zero advance width."
+#[test]
+fn test_whitespace_collapses_across_inline_boundaries() {
+    assert_eq!(first_line_text("<p><b>a </b>  \n <b> b</b></p>"), "a b");
+    assert_eq!(first_line_text("<p>a   \n  b</p>"), "a b");
+}
+
+/// Text runs in the first line box of `block`.
+fn first_line_runs(block: &LayoutBox) -> Vec<&TextRun> {
+    block.line_boxes[0]
+        .fragments
+        .iter()
+        .filter_map(|f| match &f.content {
+            FragmentContent::Text(run) => Some(run),
+            _ => None,
+        })
+        .collect()
+}
+
+/// [§ 4.2 'tab-size'](https://www.w3.org/TR/css-text-3/#tab-size-property)
+///
+/// A tab at column 0 advances to the first tab stop, column 8 by default.
+#[test]
+fn test_pre_tab_expands_to_default_tab_size() {
+    let root = layout_html("<pre>\tX</pre>");
+    let pre = box_at_depth(&root, 3);
+    let runs = first_line_runs(pre);
+    assert_eq!(runs.len(), 1, "expected one run, got {runs:?}");
+
+    let run = runs[0];
+    assert_eq!(run.text, "        X");
+    let column = ApproximateFontMetrics.text_width(" ", run.font_size, 0.0, &[]);
+    assert!(
+        (run.width / column - 9.0).abs() < 0.01,
+        "tab should advance 8 columns before X, got width {}",
+        run.width
+    );
+}
+
+/// [§ 4.2 'tab-size'](https://www.w3.org/TR/css-text-3/#tab-size-property)
+///
+/// `tab-size: 4` moves the first tab stop to column 4, and a tab after
+/// other text on the line only advances to the next stop.
+#[test]
+fn test_pre_tab_size_property() {
+    let root = layout_html("<style>pre { tab-size: 4; }</style><pre>\tX\nab\tY</pre>");
+    let pre = box_at_depth(&root, 3);
+    let runs = first_line_runs(pre);
+    assert_eq!(runs[0].text, "    X");
+    let column = ApproximateFontMetrics.text_width(" ", runs[0].font_size, 0.0, &[]);
+    assert!(
+        (runs[0].width / column - 5.0).abs() < 0.01,
+        "tab should advance 4 columns before X, got width {}",
+        runs[0].width
+    );
+
+    let second: String = pre.line_boxes[1]
+        .fragments
+        .iter()
+        .filter_map(|f| match &f.content {
+            FragmentContent::Text(run) => Some(run.text.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(second, "ab  Y");
+}
+
+/// The text of each line box in `block`, one string per line.
+fn line_texts(block: &LayoutBox) -> Vec<String> {
+    block
+        .line_boxes
+        .iter()
+        .map(|line| {
+            line.fragments
+                .iter()
+                .filter_map(|f| match &f.content {
+                    FragmentContent::Text(run) => Some(run.text.as_str()),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// [§ 5.1 Line Breaking Details](https://www.w3.org/TR/css-text-3/#line-break-details)
+///
+/// A no-break space is not a soft wrap opportunity: where a regular space
+/// lets "cccc" wrap on its own, `&nbsp;` carries "bbbb" down with it.
+#[test]
+fn test_nbsp_prevents_line_break() {
+    // 100px fits ten 9.6px glyphs: "aaaa bbbb" but not "aaaa bbbb cccc".
+    let root = layout_html("<style>p { width: 100px; }</style><p>aaaa bbbb cccc</p>");
+    let p = box_at_depth(&root, 3);
+    assert_eq!(line_texts(p), ["aaaa bbbb", "cccc"]);
+
+    let root = layout_html("<style>p { width: 100px; }</style><p>aaaa bbbb&nbsp;cccc</p>");
+    let p = box_at_depth(&root, 3);
+    assert_eq!(line_texts(p), ["aaaa", "bbbb\u{a0}cccc"]);
+}
+
+/// [§ 4 White Space Processing](https://www.w3.org/TR/css-text-3/#white-space-processing)
+///
+/// U+00A0 is not document white space, so it is neither trimmed at the
+/// end of a line nor dropped as a whitespace-only text run.
+#[test]
+fn test_nbsp_is_not_collapsed() {
+    let root = layout_html("<style>p { width: 60px; }</style><p>aaaa&nbsp; bbbb</p>");
+    let p = box_at_depth(&root, 3);
+    assert_eq!(line_texts(p), ["aaaa\u{a0}", "bbbb"]);
+
+    let root = layout_html("<p>&nbsp;</p>");
+    let p = box_at_depth(&root, 3);
+    assert_eq!(line_texts(p), ["\u{a0}"]);
+}
+
+/// [§ 5.1.2 Viewport-percentage lengths](https://www.w3.org/TR/css-values-4/#viewport-relative-lengths)
+///
+/// "The viewport-percentage lengths are relative to the size of the
+/// initial containing block."
+#[test]
+fn test_viewport_units_resolve_against_viewport() {
+    let root = layout_html_with_viewport(
+        "<style>div { width: 50vw; height: 100vh; }</style><div></div>",
+        1280.0,
+        720.0,
+    );
+    let div = box_at_depth(&root, 3);
+    assert!((div.dimensions.content.width - 640.0).abs() < 0.01);
+    assert!((div.dimensions.content.height - 720.0).abs() < 0.01);
+
+    let root = layout_html_with_viewport(
+        "<style>div { width: 50vmax; height: 50vmin; }</style><div></div>",
+        1280.0,
+        720.0,
+    );
+    let div = box_at_depth(&root, 3);
+    assert!((div.dimensions.content.width - 640.0).abs() < 0.01);
+    assert!((div.dimensions.content.height - 360.0).abs() < 0.01);
+}
+
+/// [§ 10.2 Comparison Functions](https://www.w3.org/TR/css-values-4/#comp-func)
+///
+/// `min()` picks the smaller argument once the percentage basis (the
+/// containing block width) is known.
+#[test]
+fn test_min_width_picks_smaller_argument() {
+    let html = "<style>div { width: min(50%, 300px); }</style><div></div>";
+
+    // 50% of the 784px body is 392px, so 300px wins.
+    let root = layout_html(html);
+    let div = box_at_depth(&root, 3);
+    assert!((div.dimensions.content.width - 300.0).abs() < 0.01);
+
+    // 50% of the 384px body is 192px.
+    let root = layout_html_with_viewport(html, 400.0, 600.0);
+    let div = box_at_depth(&root, 3);
+    assert!((div.dimensions.content.width - 192.0).abs() < 0.01);
+}
+
+/// [§ 10.2 Comparison Functions](https://www.w3.org/TR/css-values-4/#comp-func)
+///
+/// "given clamp(MIN, VAL, MAX), it represents exactly the same value as
+/// max(MIN, min(VAL, MAX))."
+#[test]
+fn test_clamp_bounds_at_both_ends() {
+    let html = "<style>div { width: clamp(200px, 50vw, 500px); }</style><div></div>";
+
+    // 50vw = 150px, below the minimum.
+    let root = layout_html_with_viewport(html, 300.0, 600.0);
+    let div = box_at_depth(&root, 3);
+    assert!((div.dimensions.content.width - 200.0).abs() < 0.01);
+
+    // 50vw = 400px, between the bounds.
+    let root = layout_html_with_viewport(html, 800.0, 600.0);
+    let div = box_at_depth(&root, 3);
+    assert!((div.dimensions.content.width - 400.0).abs() < 0.01);
+
+    // 50vw = 640px, above the maximum.
+    let root = layout_html_with_viewport(html, 1280.0, 600.0);
+    let div = box_at_depth(&root, 3);
+    assert!((div.dimensions.content.width - 500.0).abs() < 0.01);
+}
+
+/// [§ 5.2 'text-overflow'](https://www.w3.org/TR/css-ui-3/#text-overflow)
+///
+/// A `nowrap` label in a narrow clipped box is truncated so the run ends in
+/// an ellipsis that still fits inside the content box.
+#[test]
+fn test_text_overflow_ellipsis_truncates_run() {
+    let root = layout_html(
+        "<style>p { width: 100px; white-space: nowrap; overflow: hidden; \
+         text-overflow: ellipsis; }</style><p>A rather long label</p>",
+    );
+    let p = box_at_depth(&root, 3);
+    let texts = line_texts(p);
+    assert_eq!(texts.len(), 1);
+    assert!(texts[0].ends_with('\u{2026}'), "got {texts:?}");
+    assert!(
+        texts[0].len() < "A rather long label".len(),
+        "got {texts:?}"
+    );
+
+    let content = p.dimensions.content;
+    let frag = &p.line_boxes[0].fragments[0];
+    assert!(frag.bounds.x + frag.bounds.width <= content.x + content.width + 0.01);
+}
+
+/// `text-overflow` has no effect without an overflow clip, and `clip`
+/// leaves overflowing text untouched.
+#[test]
+fn test_text_overflow_requires_clipping_overflow() {
+    let label = "A rather long label";
+    for css in [
+        "p { width: 100px; white-space: nowrap; text-overflow: ellipsis; }",
+        "p { width: 100px; white-space: nowrap; overflow: hidden; text-overflow: clip; }",
+    ] {
+        let root = layout_html(&format!("<style>{css}</style><p>{label}</p>"));
+        let p = box_at_depth(&root, 3);
+        assert_eq!(line_texts(p), [label], "{css}");
+    }
+}
+
+/// [§ 2.1 'direction'](https://www.w3.org/TR/css-writing-modes-4/#direction)
+///
+/// In an `rtl` block the initial `text-align` acts as `right`, so the first
+/// inline fragment ends at the right edge of the content box.
+#[test]
+fn test_rtl_places_first_fragment_at_right_edge() {
+    let root = layout_html("<style>p { direction: rtl; width: 200px; }</style><p>abc</p>");
+    let p = box_at_depth(&root, 3);
+    let content = p.dimensions.content;
+    let frag = &p.line_boxes[0].fragments[0];
+    assert!(
+        (frag.bounds.x + frag.bounds.width - (content.x + content.width)).abs() < 0.01,
+        "fragment {:?} should end at the content box's right edge {}",
+        frag.bounds,
+        content.x + content.width
+    );
+}
+
+/// Fragments on an `rtl` line run from right to left in content order,
+/// and an explicit `text-align: left` moves the whole run to the left.
+#[test]
+fn test_rtl_orders_fragments_right_to_left() {
+    let root = layout_html(
+        "<style>p { direction: rtl; width: 300px; text-align: left; }</style>\
+         <p>one <b>two</b></p>",
+    );
+    let p = box_at_depth(&root, 3);
+    let frags = &p.line_boxes[0].fragments;
+    assert_eq!(frags.len(), 2, "expected two fragments, got {frags:?}");
+    assert!(
+        frags[1].bounds.x < frags[0].bounds.x,
+        "second fragment should sit left of the first: {frags:?}"
+    );
+    assert!((frags[1].bounds.x - p.dimensions.content.x).abs() < 0.01);
+}
+
+// WebKit vendor-prefix aliases and no-op properties
+//
+// Real-world stylesheets routinely set `-webkit-text-decoration`,
+// `-webkit-appearance`, and `-webkit-text-size-adjust` alongside their
+// modern counterparts. We accept the prefixed forms (and their unprefixed
+// standards where applicable) without falling through to the unknown-
+// property warning path. These tests pin down that behaviour.
+
+/// `-webkit-text-decoration: underline` should behave identically to the
+/// unprefixed `text-decoration: underline` — both route to the same arm
+/// in the style computation, so the resulting `TextRun` carries the
+/// underline flag.
+#[test]
+fn test_webkit_text_decoration_alias_underline() {
+    let root = layout_html(
+        "<style>span { -webkit-text-decoration: underline; }</style>\
+         <p><span>Underlined via prefix</span></p>",
+    );
+    let body = box_at_depth(&root, 2);
+    let runs = collect_text_runs(body);
+    assert!(!runs.is_empty(), "should have text runs");
+
+    let run = &runs[0];
+    assert!(
+        run.text_decoration.line.underline,
+        "-webkit-text-decoration: underline should alias to \
+         text-decoration: underline and set underline=true, got {:?}",
+        run.text_decoration
+    );
+}
+
+/// `-webkit-text-decoration: line-through` aliases to `text-decoration:
+/// line-through` and sets the `line_through` flag on the resulting run.
+/// Locks in that the alias is not a partial implementation.
+#[test]
+fn test_webkit_text_decoration_alias_line_through() {
+    let root = layout_html(
+        "<style>.del { -webkit-text-decoration: line-through; }</style>\
          <p><span class='del'>Deleted via prefix</span></p>",
     );
     let body = box_at_depth(&root, 2);
@@ -4308,7 +5706,7 @@ fn test_webkit_text_decoration_alias_line_through() {
 
     let run = &runs[0];
     assert!(
-        run.text_decoration.line_through,
+        run.text_decoration.line.line_through,
         "-webkit-text-decoration: line-through should set line_through=true, \
          got {:?}",
         run.text_decoration
@@ -4535,3 +5933,672 @@ fn test_letter_spacing_child_overrides_parent() {
         runs[0].width,
     );
 }
+
+// object-fit tests
+//
+// [§ 5.5 'object-fit'](https://www.w3.org/TR/css-images-3/#the-object-fit)
+
+/// A 200×100 content box at (10, 20), wider than it is tall.
+const WIDE_BOX: Rect = Rect {
+    x: 10.0,
+    y: 20.0,
+    width: 200.0,
+    height: 100.0,
+};
+
+#[test]
+fn test_object_fit_contain_square_image_in_wide_box() {
+    // "The replaced content is scaled to maintain its aspect ratio while
+    // fitting within the element's content box."
+    use koala_css::{BackgroundPosition, ObjectFit, object_fit_rect};
+
+    let r = object_fit_rect(
+        ObjectFit::Contain,
+        BackgroundPosition::CENTER,
+        WIDE_BOX,
+        50.0,
+        50.0,
+    );
+    assert_eq!((r.width, r.height), (100.0, 100.0));
+    // Centered horizontally, flush vertically.
+    assert_eq!((r.x, r.y), (60.0, 20.0));
+}
+
+#[test]
+fn test_object_fit_cover_square_image_in_wide_box() {
+    // "The replaced content is sized to maintain its aspect ratio while
+    // filling the element's entire content box."
+    use koala_css::{BackgroundPosition, ObjectFit, object_fit_rect};
+
+    let r = object_fit_rect(
+        ObjectFit::Cover,
+        BackgroundPosition::CENTER,
+        WIDE_BOX,
+        50.0,
+        50.0,
+    );
+    assert_eq!((r.width, r.height), (200.0, 200.0));
+    // Overflows the box equally above and below.
+    assert_eq!((r.x, r.y), (10.0, -30.0));
+}
+
+#[test]
+fn test_object_fit_fill_none_and_scale_down() {
+    use koala_css::{BackgroundPosition, ObjectFit, object_fit_rect};
+
+    let fill = object_fit_rect(
+        ObjectFit::Fill,
+        BackgroundPosition::CENTER,
+        WIDE_BOX,
+        50.0,
+        50.0,
+    );
+    assert_eq!((fill.width, fill.height), (200.0, 100.0));
+
+    let none = object_fit_rect(
+        ObjectFit::None,
+        BackgroundPosition::CENTER,
+        WIDE_BOX,
+        50.0,
+        50.0,
+    );
+    assert_eq!(
+        (none.x, none.y, none.width, none.height),
+        (85.0, 45.0, 50.0, 50.0)
+    );
+
+    // Smaller than the box: scale-down behaves like none.
+    let small = object_fit_rect(
+        ObjectFit::ScaleDown,
+        BackgroundPosition::CENTER,
+        WIDE_BOX,
+        50.0,
+        50.0,
+    );
+    assert_eq!((small.width, small.height), (50.0, 50.0));
+
+    // Larger than the box: scale-down behaves like contain.
+    let large = object_fit_rect(
+        ObjectFit::ScaleDown,
+        BackgroundPosition::CENTER,
+        WIDE_BOX,
+        400.0,
+        400.0,
+    );
+    assert_eq!((large.width, large.height), (100.0, 100.0));
+}
+
+const SQUARE_BOX: Rect = Rect {
+    x: 0.0,
+    y: 0.0,
+    width: 100.0,
+    height: 100.0,
+};
+
+#[test]
+fn test_object_position_defaults_to_centered() {
+    // [§ 5.6 'object-position'](https://www.w3.org/TR/css-images-3/#the-object-position)
+    // "Initial: 50% 50%"
+    use koala_css::{BackgroundPosition, ObjectFit, object_fit_rect};
+
+    // A 2:1 landscape image fitted into a square box leaves 50px of free
+    // space, split evenly above and below.
+    let r = object_fit_rect(
+        ObjectFit::Contain,
+        BackgroundPosition::CENTER,
+        SQUARE_BOX,
+        200.0,
+        100.0,
+    );
+    assert_eq!((r.x, r.y, r.width, r.height), (0.0, 25.0, 100.0, 50.0));
+}
+
+#[test]
+fn test_object_position_top_aligns_contained_image() {
+    // [§ 5.6 'object-position'](https://www.w3.org/TR/css-images-3/#the-object-position)
+    // "The 'object-position' property determines the alignment of the
+    // replaced element inside its box."
+    use koala_css::{BackgroundPosition, LengthValue, ObjectFit, object_fit_rect};
+
+    // `object-position: top` is `50% 0%`.
+    let top = BackgroundPosition {
+        x: LengthValue::Percent(50.0),
+        y: LengthValue::Percent(0.0),
+    };
+    let r = object_fit_rect(ObjectFit::Contain, top, SQUARE_BOX, 200.0, 100.0);
+    assert_eq!((r.x, r.y, r.width, r.height), (0.0, 0.0, 100.0, 50.0));
+
+    // A length is an offset from the box's edge rather than a share of the
+    // free space.
+    let offset = BackgroundPosition {
+        x: LengthValue::Px(0.0),
+        y: LengthValue::Px(10.0),
+    };
+    let r = object_fit_rect(ObjectFit::None, offset, SQUARE_BOX, 20.0, 20.0);
+    assert_eq!((r.x, r.y), (0.0, 10.0));
+}
+
+// Replaced element sizing tests
+//
+// [§ 10.3.2 Inline, replaced elements](https://www.w3.org/TR/CSS2/visudet.html#inline-replaced-width)
+// [§ 10.6.2 Inline, replaced elements](https://www.w3.org/TR/CSS2/visudet.html#inline-replaced-height)
+
+/// Helper: lay out `html` with the first `<img>` given the natural size
+/// `(width, height)`, and return that image's content box.
+fn layout_img_content(html: &str, natural: (f32, f32)) -> Rect {
+    use koala_css::cascade::compute_styles;
+    use koala_css::{BoxType, CSSParser, CSSTokenizer, Stylesheet};
+    use koala_std::collections::HashMap;
+
+    fn find_box(layout_box: &LayoutBox, id: koala_dom::NodeId) -> Option<&LayoutBox> {
+        if matches!(layout_box.box_type, BoxType::Principal(node) if node == id) {
+            return Some(layout_box);
+        }
+        layout_box.children.iter().find_map(|c| find_box(c, id))
+    }
+
+    let mut tokenizer = koala_html::HTMLTokenizer::new(html.to_string());
+    tokenizer.run();
+    let parser = koala_html::HTMLParser::new(tokenizer.into_tokens());
+    let (dom, _) = parser.run_with_issues();
+
+    let css_text = koala_css::extract_style_content(&dom);
+    let author = if css_text.is_empty() {
+        Stylesheet { rules: vec![] }
+    } else {
+        let mut css_tok = CSSTokenizer::new(css_text);
+        css_tok.run();
+        let mut css_parser = CSSParser::new(css_tok.into_tokens());
+        css_parser.parse_stylesheet()
+    };
+
+    let ua = koala_css::ua_stylesheet::ua_stylesheet();
+    let styles = compute_styles(&dom, ua, None, &author, DEFAULT_FONT_SIZE_PX);
+
+    let img_id = dom
+        .iter_all()
+        .find(|&id| dom.as_element(id).is_some_and(|e| e.tag_name == "img"))
+        .expect("document should contain an <img>");
+    let mut image_dims = HashMap::new();
+    let _ = image_dims.insert(img_id, natural);
+
+    let mut layout_tree = LayoutBox::build_layout_tree(&dom, &styles, dom.root(), &image_dims)
+        .expect("should produce a layout tree");
+    let viewport = Rect {
+        x: 0.0,
+        y: 0.0,
+        width: 800.0,
+        height: 600.0,
+    };
+    layout_tree.layout(viewport, viewport, &ApproximateFontMetrics, viewport);
+
+    find_box(&layout_tree, img_id)
+        .expect("<img> should generate a box")
+        .dimensions
+        .content
+}
+
+#[test]
+fn test_img_width_attribute_preserves_aspect_ratio() {
+    // [§ 15.4.3](https://html.spec.whatwg.org/multipage/rendering.html#attributes-for-embedded-content-and-images)
+    // The width attribute maps to 'width'; height is derived from the
+    // natural 2:1 ratio.
+    let content = layout_img_content(r#"<img src="a.png" width="100">"#, (200.0, 100.0));
+    assert_eq!((content.width, content.height), (100.0, 50.0));
+}
+
+#[test]
+fn test_img_height_attribute_preserves_aspect_ratio() {
+    let content = layout_img_content(r#"<img src="a.png" height="25">"#, (200.0, 100.0));
+    assert_eq!((content.width, content.height), (50.0, 25.0));
+}
+
+#[test]
+fn test_img_without_dimensions_uses_natural_size() {
+    let content = layout_img_content(r#"<img src="a.png">"#, (200.0, 100.0));
+    assert_eq!((content.width, content.height), (200.0, 100.0));
+}
+
+#[test]
+fn test_img_css_width_overrides_attribute() {
+    // Presentational hints sort before every author rule, so the CSS
+    // width wins and the height still follows the natural ratio.
+    let content = layout_img_content(
+        r#"<style>img { width: 40px; }</style><img src="a.png" width="100">"#,
+        (200.0, 100.0),
+    );
+    assert_eq!((content.width, content.height), (40.0, 20.0));
+}
+
+#[test]
+fn test_img_both_attributes_stretch() {
+    let content = layout_img_content(
+        r#"<img src="a.png" width="30" height="90">"#,
+        (200.0, 100.0),
+    );
+    assert_eq!((content.width, content.height), (30.0, 90.0));
+}
+
+// Generated content tests
+//
+// [§ 4.1 ::before and ::after](https://www.w3.org/TR/css-pseudo-4/#generated-content)
+
+/// Text of an anonymous inline box, or `None` for any other box.
+fn anonymous_text(b: &LayoutBox) -> Option<&str> {
+    match &b.box_type {
+        BoxType::AnonymousInline(text) => Some(text),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_before_content_generates_leading_inline_box() {
+    let root = layout_html(
+        "<style>li::before { content: \"\u{2022} \"; }</style>\
+         <ul><li>One</li><li>Two</li></ul>",
+    );
+
+    // Document > html > body > ul > [li, li]
+    let body = box_at_depth(&root, 2);
+    let ul = &body.children[0];
+    let items: Vec<&LayoutBox> = ul.children.iter().collect();
+    assert_eq!(items.len(), 2);
+    for (li, text) in items.iter().zip(["One", "Two"]) {
+        let texts: Vec<_> = li.children.iter().map(anonymous_text).collect();
+        assert_eq!(texts, [Some("\u{2022} "), Some(text)]);
+    }
+}
+
+#[test]
+fn test_after_content_generates_trailing_inline_box() {
+    let root = layout_html("<style>p:after { content: \" (\" \"end)\"; }</style><p>Text</p>");
+
+    let body = box_at_depth(&root, 2);
+    let p = &body.children[0];
+    let texts: Vec<_> = p.children.iter().map(anonymous_text).collect();
+    assert_eq!(texts, [Some("Text"), Some(" (end)")]);
+}
+
+#[test]
+fn test_pseudo_element_without_content_generates_no_box() {
+    // `content: none` and a rule that never sets content both leave the
+    // pseudo-element without a box, and pseudo-element rules never style
+    // the originating element itself.
+    let root = layout_html(
+        "<style>p::before { content: \"x\"; } p.none::before { content: none; }\
+         p::after { color: red; }</style><p class=\"none\">Text</p>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let p = &body.children[0];
+    let texts: Vec<_> = p.children.iter().map(anonymous_text).collect();
+    assert_eq!(texts, [Some("Text")]);
+    assert_ne!((p.color.r, p.color.g, p.color.b), (255, 0, 0));
+}
+
+// Counter tests
+//
+// [§ 4 Automatic Numbering With Counters](https://www.w3.org/TR/css-lists-3/#auto-numbering)
+
+#[test]
+fn test_counter_in_before_content_numbers_list_items() {
+    let root = layout_html(
+        "<style>ol { counter-reset: item; list-style-type: none; } \
+         li::before { counter-increment: item; content: counter(item) \". \"; }</style>\
+         <ol><li>A</li><li>B</li><li>C</li></ol>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let ol = &body.children[0];
+    let markers: Vec<_> = ol
+        .children
+        .iter()
+        .map(|li| anonymous_text(&li.children[0]))
+        .collect();
+    assert_eq!(markers, [Some("1. "), Some("2. "), Some("3. ")]);
+}
+
+#[test]
+fn test_counters_joins_nested_counter_values() {
+    // Each nested <ol> instantiates a new `item` counter inside the outer
+    // one, and the outer numbering resumes after the nested list.
+    let root = layout_html(
+        "<style>ol { counter-reset: item; } \
+         li::before { counter-increment: item; content: counters(item, \".\") \" \"; }</style>\
+         <ol><li>A<ol><li>x</li><li>y</li></ol></li><li>B</li></ol>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let ol = &body.children[0];
+    let first = &ol.children[0];
+    // The first item mixes inline text with a nested list, so its text is
+    // wrapped in an anonymous block.
+    assert_eq!(anonymous_text(&first.children[0].children[0]), Some("1 "));
+    let nested: Vec<_> = first
+        .children
+        .iter()
+        .find(|child| child.tag_name.as_deref() == Some("ol"))
+        .expect("nested <ol> should generate a box")
+        .children
+        .iter()
+        .map(|li| anonymous_text(&li.children[0]))
+        .collect();
+    assert_eq!(nested, [Some("1.1 "), Some("1.2 ")]);
+    assert_eq!(anonymous_text(&ol.children[1].children[0]), Some("2 "));
+}
+
+#[test]
+fn test_list_item_counter_drives_markers() {
+    // [§ 4.5 The Implicit list-item Counter](https://www.w3.org/TR/css-lists-3/#list-item-counter)
+    //
+    // The marker reads the `list-item` counter, so `value` restarts the
+    // numbering, a nested list starts its own count, and an explicit
+    // `counter-increment: list-item 2` replaces the implicit increment.
+    let root = layout_html(
+        "<style>.skip { counter-increment: list-item 2; }</style>\
+         <ol><li>A<ol><li>x</li></ol></li><li value=\"10\">B</li><li>C</li>\
+         <li class=\"skip\">D</li></ol>",
+    );
+
+    let body = box_at_depth(&root, 2);
+    let ol = &body.children[0];
+    let markers: Vec<_> = ol
+        .children
+        .iter()
+        .map(|li| li.marker_text.as_deref())
+        .collect();
+    assert_eq!(
+        markers,
+        [Some("1. "), Some("10. "), Some("11. "), Some("13. ")]
+    );
+    let nested = ol.children[0]
+        .children
+        .iter()
+        .find(|child| child.tag_name.as_deref() == Some("ol"))
+        .expect("nested <ol> should generate a box");
+    assert_eq!(nested.children[0].marker_text.as_deref(), Some("1. "));
+}
+
+// Hit testing
+//
+// [CSS 2.1 Appendix E.2 Painting order](https://www.w3.org/TR/CSS2/zindex.html#painting-order)
+
+/// Helper: the `NodeId` of the first principal box generated for `tag`.
+fn node_for_tag(layout_box: &LayoutBox, tag: &str) -> Option<koala_dom::NodeId> {
+    if let BoxType::Principal(id) = layout_box.box_type
+        && layout_box.tag_name.as_deref() == Some(tag)
+    {
+        return Some(id);
+    }
+    layout_box
+        .children
+        .iter()
+        .find_map(|child| node_for_tag(child, tag))
+}
+
+#[test]
+fn test_hit_test_returns_innermost_principal_box() {
+    let root = layout_html(
+        "<style>body { margin: 0; }\
+         section { padding: 20px; }\
+         article { height: 50px; }</style>\
+         <section><article>Text</article></section>",
+    );
+    let section = node_for_tag(&root, "section").expect("section box");
+    let article = node_for_tag(&root, "article").expect("article box");
+
+    // Inside the article, which is nested inside the section.
+    assert_eq!(root.hit_test((30.0, 30.0)), Some(article));
+    // In the section's padding, outside the article.
+    assert_eq!(root.hit_test((30.0, 10.0)), Some(section));
+    assert_eq!(root.hit_test((30.0, 80.0)), Some(section));
+}
+
+#[test]
+fn test_hit_test_prefers_positioned_box_over_in_flow_sibling() {
+    let root = layout_html(
+        "<style>body { margin: 0; }\
+         nav { position: absolute; top: 0; left: 0; width: 100px; height: 100px; }\
+         main { height: 200px; }</style>\
+         <nav></nav><main></main>",
+    );
+    let nav = node_for_tag(&root, "nav").expect("nav box");
+    let main = node_for_tag(&root, "main").expect("main box");
+
+    // The absolutely positioned box comes first in tree order but is
+    // painted above the in-flow box it overlaps.
+    assert_eq!(root.hit_test((50.0, 50.0)), Some(nav));
+    assert_eq!(root.hit_test((150.0, 50.0)), Some(main));
+}
+
+#[test]
+fn test_hit_test_skips_hidden_boxes() {
+    let root = layout_html(
+        "<style>body { margin: 0; }\
+         aside { visibility: hidden; height: 40px; }</style>\
+         <aside></aside>",
+    );
+    let body = node_for_tag(&root, "body").expect("body box");
+    assert_eq!(root.hit_test((10.0, 10.0)), Some(body));
+}
+
+/// [§ 3.1 New Keywords for width and height](https://www.w3.org/TR/css-sizing-3/#sizing-values)
+///
+/// "max-content: Use the max-content inline size" — the box is as wide as
+/// its text laid out on a single line.
+#[test]
+fn test_max_content_width_hugs_text() {
+    let root = layout_body(
+        "div { width: max-content; padding: 0 5px; }",
+        "<div>hello wonderful world</div>",
+    );
+    let div = box_at_depth(&root, 3);
+    let text_width = ApproximateFontMetrics.text_width("hello wonderful world", 16.0, 0.0, &[]);
+    assert!(
+        (div.dimensions.content.width - text_width).abs() < 0.01,
+        "max-content width should be the text width {text_width}, got {}",
+        div.dimensions.content.width
+    );
+}
+
+/// [§ 3.1 New Keywords for width and height](https://www.w3.org/TR/css-sizing-3/#sizing-values)
+///
+/// "min-content: Use the min-content inline size" — taking every soft wrap
+/// opportunity leaves the box as wide as its longest word.
+#[test]
+fn test_min_content_width_is_longest_word() {
+    let root = layout_body(
+        "div { width: min-content; padding: 0 5px; }",
+        "<div>hello wonderful world</div>",
+    );
+    let div = box_at_depth(&root, 3);
+    let word_width = ApproximateFontMetrics.text_width("wonderful", 16.0, 0.0, &[]);
+    assert!(
+        (div.dimensions.content.width - word_width).abs() < 0.01,
+        "min-content width should be the longest word {word_width}, got {}",
+        div.dimensions.content.width
+    );
+}
+
+/// [§ 4.1.1 Phase I: Collapsing and Transformation](https://www.w3.org/TR/css-text-3/#white-space-phase-1)
+///
+/// A no-break space is not collapsible white space and offers no soft
+/// wrap opportunity, so `a\u{a0}b` is one unbreakable word.
+#[test]
+fn test_min_content_width_keeps_no_break_space_words_together() {
+    let root = layout_body("div { width: min-content; }", "<div>a\u{a0}b c</div>");
+    let div = box_at_depth(&root, 3);
+    let word_width = ApproximateFontMetrics.text_width("a\u{a0}b", 16.0, 0.0, &[]);
+    assert!(
+        (div.dimensions.content.width - word_width).abs() < 0.01,
+        "min-content width should span the no-break space {word_width}, got {}",
+        div.dimensions.content.width
+    );
+}
+
+/// [CSS Sizing 4 § 3.1](https://www.w3.org/TR/css-sizing-4/#sizing-values)
+///
+/// fit-content is max-content when that fits, and the available width
+/// otherwise.
+#[test]
+fn test_fit_content_width_clamps_to_available_width() {
+    let root = layout_body(
+        ".outer { width: 100px; } .fit { width: fit-content; }",
+        "<div class='fit'>short</div>\
+         <div class='outer'><div class='fit'>hello wonderful world</div></div>",
+    );
+    let body = box_at_depth(&root, 2);
+    let short = &body.children[0];
+    let clamped = &body.children[1].children[0];
+    let short_width = ApproximateFontMetrics.text_width("short", 16.0, 0.0, &[]);
+    assert!((short.dimensions.content.width - short_width).abs() < 0.01);
+    assert!((clamped.dimensions.content.width - 100.0).abs() < 0.01);
+}
+
+/// With the approximate metrics every character has the same advance, so
+/// text width grows linearly with both character count and font size.
+#[test]
+fn test_measure_text_scales_with_length_and_font_size() {
+    let metrics = ApproximateFontMetrics;
+    let one = measure_text("a", 10.0, 0.0, &[], &metrics);
+    assert!(one > 0.0);
+    assert_eq!(measure_text("", 10.0, 0.0, &[], &metrics), 0.0);
+    let four = measure_text("abcd", 10.0, 0.0, &[], &metrics);
+    assert!((four / one - 4.0).abs() < 0.01);
+    assert!((measure_text("a", 30.0, 0.0, &[], &metrics) / one - 3.0).abs() < 0.01);
+
+    // [§ 8.2 Tracking: the letter-spacing property](https://www.w3.org/TR/css-text-3/#letter-spacing-property)
+    //
+    // Letter spacing is added between the characters.
+    assert!((measure_text("abcd", 10.0, 2.0, &[], &metrics) - four - 6.0).abs() < 0.01);
+}
+
+/// `measure_text_runs` sums each run measured in its own style, matching
+/// the widths inline layout gave the runs.
+#[test]
+fn test_measure_text_runs_sums_laid_out_runs() {
+    let root = layout_body(
+        "span { font-size: 32px; }",
+        "<p>small <span>large</span></p>",
+    );
+    let runs: Vec<TextRun> = collect_text_runs(&root).into_iter().cloned().collect();
+    assert_eq!(runs.len(), 2);
+    let laid_out: f32 = runs.iter().map(|run| run.width).sum();
+    assert!((measure_text_runs(&runs, &ApproximateFontMetrics) - laid_out).abs() < 0.01);
+    assert_eq!(measure_text_runs(&[], &ApproximateFontMetrics), 0.0);
+}
+
+/// [CSS Containment 2 § 4.1 Using content-visibility: auto](https://www.w3.org/TR/css-contain-2/#using-cv-auto)
+///
+/// An off-screen `content-visibility: auto` box skips its contents and is
+/// sized by `contain-intrinsic-size`; once a later layout's viewport
+/// reaches it, its contents are laid out.
+#[test]
+fn test_content_visibility_auto_skips_offscreen_contents_until_visible() {
+    let mut root = layout_body(
+        ".spacer { height: 2000px; } \
+         .lazy { content-visibility: auto; contain-intrinsic-size: 100px 50px; }",
+        "<div class='spacer'></div><div class='lazy'><p>far away</p></div>",
+    );
+    let lazy = &box_at_depth(&root, 2).children[1];
+    assert!(lazy.children.is_empty(), "off-screen contents are skipped");
+    assert_eq!(lazy.skipped_children.len(), 1);
+    assert!((lazy.dimensions.content.y - 2000.0).abs() < 0.01);
+    assert!((lazy.dimensions.content.height - 50.0).abs() < 0.01);
+
+    // Scroll so the box enters the viewport and lay out again.
+    let initial_containing_block = Rect {
+        x: 0.0,
+        y: 0.0,
+        width: 800.0,
+        height: 600.0,
+    };
+    let scrolled = Rect {
+        y: 1800.0,
+        ..initial_containing_block
+    };
+    root.layout(
+        initial_containing_block,
+        scrolled,
+        &ApproximateFontMetrics,
+        initial_containing_block,
+    );
+    let lazy = &box_at_depth(&root, 2).children[1];
+    assert!(lazy.skipped_children.is_empty());
+    assert_eq!(lazy.children.len(), 1, "the <p> is laid out on demand");
+    assert!(lazy.children[0].dimensions.content.height > 0.0);
+    assert!(
+        (lazy.dimensions.content.height - lazy.children[0].dimensions.content.height).abs() < 0.01
+    );
+}
+
+/// [§ 4.4 Relevant To The User](https://www.w3.org/TR/css-contain-2/#relevant-to-the-user)
+///
+/// "...intersects with the viewport, or a user-agent defined margin
+/// around the viewport." A box just below the fold is still laid out.
+#[test]
+fn test_content_visibility_auto_lays_out_contents_within_margin() {
+    use koala_css::VIEWPORT_MARGIN;
+
+    let root = layout_body(
+        &format!(
+            ".spacer {{ height: {}px; }} \
+             .lazy {{ content-visibility: auto; contain-intrinsic-size: 100px 50px; }}",
+            600.0 + VIEWPORT_MARGIN - 10.0
+        ),
+        "<div class='spacer'></div><div class='lazy'><p>near</p></div>",
+    );
+    let lazy = &box_at_depth(&root, 2).children[1];
+    assert!(lazy.skipped_children.is_empty());
+    assert_eq!(lazy.children.len(), 1);
+}
+
+/// [CSS Containment 2 § 4 'content-visibility'](https://www.w3.org/TR/css-contain-2/#content-visibility)
+///
+/// "hidden: The element skips its contents", even on-screen.
+#[test]
+fn test_content_visibility_hidden_skips_contents() {
+    let root = layout_body(
+        ".hidden { content-visibility: hidden; contain-intrinsic-size: 30px; } \
+         .visible { content-visibility: visible; }",
+        "<div class='hidden'><p>secret</p></div><div class='visible'><p>shown</p></div>",
+    );
+    let body = box_at_depth(&root, 2);
+    let hidden = &body.children[0];
+    assert!(hidden.children.is_empty());
+    assert!((hidden.dimensions.content.height - 30.0).abs() < 0.01);
+    assert_eq!(body.children[1].children.len(), 1);
+}
+
+/// [§ 3.1 'font-family'](https://www.w3.org/TR/css-fonts-4/#font-family-prop)
+///
+/// Each text run is drawn with the family list of the box its text
+/// belongs to, so inline `<code>` keeps the UA's `monospace`.
+#[test]
+fn test_draw_text_carries_font_family_of_its_box() {
+    use koala_css::{DisplayCommand, FontFamily, GenericFontFamily};
+
+    let display_list = paint_html(
+        "<style>p { font-family: \"No Such Font\", serif; }</style>\
+         <p>plain <code>mono</code></p>",
+    );
+    let family_of = |wanted: &str| {
+        display_list.commands().iter().find_map(|c| match c {
+            DisplayCommand::DrawText {
+                text, font_family, ..
+            } if text.trim() == wanted => Some(font_family.clone()),
+            _ => None,
+        })
+    };
+    assert_eq!(
+        family_of("plain"),
+        Some(vec![
+            FontFamily::Named("No Such Font".to_string()),
+            FontFamily::Generic(GenericFontFamily::Serif),
+        ])
+    );
+    assert_eq!(
+        family_of("mono"),
+        Some(vec![FontFamily::Generic(GenericFontFamily::Monospace)])
+    );
+}
@@ -2,15 +2,14 @@
 
 #![allow(clippy::doc_markdown, clippy::inefficient_to_string)]
 
-use koala_std::collections::HashMap;
-
 use koala_css::selector::{
-    AttributeSelector, Combinator, PseudoClass, SimpleSelector, Specificity, parse_selector,
+    AnPlusB, AttributeSelector, Combinator, MatchContext, PseudoClass, PseudoElement,
+    SimpleSelector, Specificity, parse_selector,
 };
 use koala_dom::{AttributesMap, DomTree, ElementData, NodeId, NodeType};
 
 fn make_element(tag: &str, id: Option<&str>, classes: &[&str]) -> ElementData {
-    let mut attrs = HashMap::new();
+    let mut attrs = AttributesMap::new();
     if let Some(id_val) = id {
         let _ = attrs.insert("id".to_string(), id_val.to_string());
     }
@@ -502,9 +501,20 @@ fn test_matches_complex_combinator_chain() {
 
 #[test]
 fn test_parse_hover_pseudo_class() {
-    // :hover → interactive pseudo-class → NeverMatch
+    // :hover → user-action pseudo-class matched against a MatchContext
     let selector = parse_selector(":hover").unwrap();
     assert_eq!(selector.complex.subject.simple_selectors.len(), 1);
+    assert!(matches!(
+        &selector.complex.subject.simple_selectors[0],
+        SimpleSelector::PseudoClass(PseudoClass::Hover)
+    ));
+}
+
+#[test]
+fn test_parse_active_pseudo_class() {
+    // :active → interactive pseudo-class → NeverMatch
+    let selector = parse_selector(":active").unwrap();
+    assert_eq!(selector.complex.subject.simple_selectors.len(), 1);
     assert!(matches!(
         &selector.complex.subject.simple_selectors[0],
         SimpleSelector::NeverMatch
@@ -513,38 +523,75 @@ fn test_parse_hover_pseudo_class() {
 
 #[test]
 fn test_parse_pseudo_element_before() {
-    // ::before → pseudo-element → NeverMatch
+    // ::before → universal originating element + PseudoElement::Before
     let selector = parse_selector("::before").unwrap();
     assert_eq!(selector.complex.subject.simple_selectors.len(), 1);
     assert!(matches!(
         &selector.complex.subject.simple_selectors[0],
-        SimpleSelector::NeverMatch
+        SimpleSelector::Universal
     ));
+    assert_eq!(selector.pseudo_element, Some(PseudoElement::Before));
 }
 
 #[test]
 fn test_parse_pseudo_element_after() {
-    // ::after → pseudo-element → NeverMatch
-    let selector = parse_selector("::after").unwrap();
+    // li::after → [Type("li")] + PseudoElement::After
+    let selector = parse_selector("li::after").unwrap();
     assert!(matches!(
         &selector.complex.subject.simple_selectors[0],
-        SimpleSelector::NeverMatch
+        SimpleSelector::Type(name) if name == "li"
     ));
+    assert_eq!(selector.pseudo_element, Some(PseudoElement::After));
+    // [§ 17] pseudo-elements count toward C, like type selectors.
+    assert_eq!(selector.specificity, Specificity::new(0, 0, 2));
 }
 
 #[test]
 fn test_parse_legacy_pseudo_element_before() {
-    // :before (single colon, legacy syntax) → NeverMatch
+    // :before (single colon, legacy syntax) is the same pseudo-element
     let selector = parse_selector(":before").unwrap();
+    assert_eq!(selector.pseudo_element, Some(PseudoElement::Before));
+}
+
+#[test]
+fn test_parse_typographic_pseudo_elements() {
+    // [§ 2 Typographic Pseudo-elements](https://www.w3.org/TR/css-pseudo-4/#typographic-pseudos)
+    let selector = parse_selector("p::first-line").unwrap();
+    assert_eq!(selector.pseudo_element, Some(PseudoElement::FirstLine));
+    let selector = parse_selector("p:first-letter").unwrap();
+    assert_eq!(selector.pseudo_element, Some(PseudoElement::FirstLetter));
+}
+
+#[test]
+fn test_parse_unsupported_pseudo_element_never_matches() {
+    // ::placeholder is not generated → NeverMatch, no pseudo-element
+    let selector = parse_selector("input::placeholder").unwrap();
+    assert_eq!(selector.pseudo_element, None);
     assert!(matches!(
-        &selector.complex.subject.simple_selectors[0],
+        &selector.complex.subject.simple_selectors[1],
         SimpleSelector::NeverMatch
     ));
 }
 
+#[test]
+fn test_pseudo_element_selector_matches_only_the_pseudo_element() {
+    let mut tree = DomTree::new();
+    let li_id = tree.alloc(make_element_type("li", None, &[]));
+    tree.append_child(NodeId::ROOT, li_id);
+
+    let selector = parse_selector("li::before").unwrap();
+    assert!(!selector.matches_in_tree(&tree, li_id));
+    assert!(selector.matches_pseudo_element_in_tree(&tree, li_id, PseudoElement::Before));
+    assert!(!selector.matches_pseudo_element_in_tree(&tree, li_id, PseudoElement::After));
+
+    // Anything after the pseudo-element makes the selector unmatchable.
+    let trailing = parse_selector("li::before.x").unwrap();
+    assert!(!trailing.matches_pseudo_element_in_tree(&tree, li_id, PseudoElement::Before));
+}
+
 #[test]
 fn test_parse_btn_hover_compound() {
-    // .btn:hover → [Class("btn"), NeverMatch]
+    // .btn:hover → [Class("btn"), PseudoClass(Hover)]
     let selector = parse_selector(".btn:hover").unwrap();
     assert_eq!(selector.complex.subject.simple_selectors.len(), 2);
     assert!(matches!(
@@ -553,7 +600,7 @@ fn test_parse_btn_hover_compound() {
     ));
     assert!(matches!(
         &selector.complex.subject.simple_selectors[1],
-        SimpleSelector::NeverMatch
+        SimpleSelector::PseudoClass(PseudoClass::Hover)
     ));
 }
 
@@ -637,8 +684,8 @@ fn test_parse_structural_pseudo_classes() {
 
 #[test]
 fn test_parse_functional_pseudo_class() {
-    // :nth-child(2) → NeverMatch (functional pseudo-class, consumed but not evaluated)
-    let selector = parse_selector(":nth-child(2)").unwrap();
+    // :has(> img) → NeverMatch (functional pseudo-class, consumed but not evaluated)
+    let selector = parse_selector(":has(> img)").unwrap();
     assert!(matches!(
         &selector.complex.subject.simple_selectors[0],
         SimpleSelector::NeverMatch
@@ -779,7 +826,7 @@ fn test_parse_complex_selector_with_pseudo_and_attr() {
         SimpleSelector::Attribute(AttributeSelector::Equals(name, val))
             if name == "attr" && val == "val"
     ));
-    // Combinator chain: Descendant with compound [Type("div"), Class("class"), NeverMatch]
+    // Combinator chain: Descendant with compound [Type("div"), Class("class"), Hover]
     assert_eq!(selector.complex.combinators.len(), 1);
     assert_eq!(selector.complex.combinators[0].0, Combinator::Descendant);
     assert_eq!(selector.complex.combinators[0].1.simple_selectors.len(), 3);
@@ -844,6 +891,110 @@ fn test_matches_first_of_type() {
     assert!(!selector.matches_in_tree(&tree, inner_div_id)); // wrong tag
 }
 
+#[test]
+fn test_parse_an_plus_b() {
+    let parsed = |s| AnPlusB::parse(s).map(|an_b| (an_b.a, an_b.b));
+    assert_eq!(parsed("odd"), Some((2, 1)));
+    assert_eq!(parsed("EVEN"), Some((2, 0)));
+    assert_eq!(parsed("3"), Some((0, 3)));
+    assert_eq!(parsed("-n+3"), Some((-1, 3)));
+    assert_eq!(parsed("2n - 1"), Some((2, -1)));
+    assert_eq!(parsed("+n"), Some((1, 0)));
+    assert_eq!(parsed("2 n"), None);
+    assert_eq!(parsed("n+-1"), None);
+    assert_eq!(parsed("li"), None);
+    assert!(parse_selector("li:nth-child(foo)").is_none());
+}
+
+#[test]
+fn test_nth_child_of_selector_never_matches() {
+    // [§ 4.13.1 :nth-child()](https://www.w3.org/TR/selectors-4/#the-nth-child-pseudo)
+    //
+    // "The :nth-child(An+B [of S]? ) pseudo-class notation represents
+    // elements that are among An+Bth elements from the list composed of
+    // their inclusive siblings that match the selector list S."
+    let mut tree = DomTree::new();
+    let ul_id = tree.alloc(make_element_type("ul", None, &[]));
+    tree.append_child(NodeId::ROOT, ul_id);
+    let li1_id = tree.alloc(make_element_type("li", None, &["x"]));
+    let li2_id = tree.alloc(make_element_type("li", None, &["x"]));
+    tree.append_child(ul_id, li1_id);
+    tree.append_child(ul_id, li2_id);
+
+    // Filtering by S is unsupported, so the selector parses but never
+    // matches rather than dropping its whole selector list.
+    for raw in ["li:nth-child(2 of .x)", "li:nth-last-child(-n + 1 OF .x)"] {
+        let selector = parse_selector(raw).unwrap();
+        assert!(!selector.matches_in_tree(&tree, li1_id));
+        assert!(!selector.matches_in_tree(&tree, li2_id));
+    }
+    assert!(parse_selector("li:nth-child(foo of .x)").is_none());
+    assert!(parse_selector("li:nth-of-type(2 of .x)").is_none());
+}
+
+#[test]
+fn test_matches_nth_of_type() {
+    // Build: <div><p/><div/><div/><p/><p/></div>
+    let mut tree = DomTree::new();
+    let parent_id = tree.alloc(make_element_type("div", None, &[]));
+    tree.append_child(NodeId::ROOT, parent_id);
+    let children: Vec<_> = ["p", "div", "div", "p", "p"]
+        .iter()
+        .map(|tag| {
+            let id = tree.alloc(make_element_type(tag, None, &[]));
+            tree.append_child(parent_id, id);
+            id
+        })
+        .collect();
+
+    // The second <p> is the fourth child
+    let selector = parse_selector("p:nth-of-type(2)").unwrap();
+    let matched: Vec<_> = children
+        .iter()
+        .map(|&id| selector.matches_in_tree(&tree, id))
+        .collect();
+    assert_eq!(matched, [false, false, false, true, false]);
+
+    let selector = parse_selector(":nth-last-of-type(2)").unwrap();
+    let matched: Vec<_> = children
+        .iter()
+        .map(|&id| selector.matches_in_tree(&tree, id))
+        .collect();
+    assert_eq!(matched, [false, true, false, true, false]);
+
+    let selector = parse_selector(":nth-child(odd)").unwrap();
+    let matched: Vec<_> = children
+        .iter()
+        .map(|&id| selector.matches_in_tree(&tree, id))
+        .collect();
+    assert_eq!(matched, [true, false, true, false, true]);
+}
+
+#[test]
+fn test_nth_last_child_one_matches_like_last_child() {
+    // Build: <ul><li/>text<li/><li/></ul>
+    let mut tree = DomTree::new();
+    let ul_id = tree.alloc(make_element_type("ul", None, &[]));
+    tree.append_child(NodeId::ROOT, ul_id);
+    let li1_id = tree.alloc(make_element_type("li", None, &[]));
+    let text_id = tree.alloc(NodeType::Text("text".to_string()));
+    let li2_id = tree.alloc(make_element_type("li", None, &[]));
+    let li3_id = tree.alloc(make_element_type("li", None, &[]));
+    for id in [li1_id, text_id, li2_id, li3_id] {
+        tree.append_child(ul_id, id);
+    }
+
+    let nth_last = parse_selector("li:nth-last-child(1)").unwrap();
+    let last = parse_selector("li:last-child").unwrap();
+    for id in [li1_id, li2_id, li3_id] {
+        assert_eq!(
+            nth_last.matches_in_tree(&tree, id),
+            last.matches_in_tree(&tree, id)
+        );
+    }
+    assert!(nth_last.matches_in_tree(&tree, li3_id));
+}
+
 #[test]
 fn test_matches_root() {
     // Build: Document → html → body
@@ -905,7 +1056,7 @@ fn test_matches_link() {
     let mut tree = DomTree::new();
     let div_id = tree.alloc(make_element_type("div", None, &[]));
 
-    let mut a_attrs = HashMap::new();
+    let mut a_attrs = AttributesMap::new();
     let _ = a_attrs.insert("href".to_string(), "https://example.com".to_string());
     let a_with_href = tree.alloc(NodeType::Element(ElementData {
         tag_name: "a".to_string(),
@@ -922,12 +1073,216 @@ fn test_matches_link() {
     assert!(!selector.matches_in_tree(&tree, a_without_href));
 }
 
+#[test]
+fn test_matches_only_of_type() {
+    // Build: <div><p/><h1/><p/></div>
+    let mut tree = DomTree::new();
+    let div_id = tree.alloc(make_element_type("div", None, &[]));
+    let p1_id = tree.alloc(make_element_type("p", None, &[]));
+    let h1_id = tree.alloc(make_element_type("h1", None, &[]));
+    let p2_id = tree.alloc(make_element_type("p", None, &[]));
+    tree.append_child(NodeId::ROOT, div_id);
+    tree.append_child(div_id, p1_id);
+    tree.append_child(div_id, h1_id);
+    tree.append_child(div_id, p2_id);
+
+    let selector = parse_selector(":only-of-type").unwrap();
+    assert!(selector.matches_in_tree(&tree, h1_id));
+    assert!(!selector.matches_in_tree(&tree, p1_id));
+    assert!(!selector.matches_in_tree(&tree, p2_id));
+}
+
+#[test]
+fn test_matches_target_from_url_fragment() {
+    // <section id="intro"><h2 id="details"></h2></section>
+    let mut tree = DomTree::new();
+    let section = tree.alloc(make_element_type("section", Some("intro"), &[]));
+    let heading = tree.alloc(make_element_type("h2", Some("details"), &[]));
+    tree.append_child(NodeId::ROOT, section);
+    tree.append_child(section, heading);
+
+    let selector = parse_selector(":target").unwrap();
+    let ctx = MatchContext::for_url("https://example.com/page.html#details");
+    assert_eq!(ctx.target.as_deref(), Some("details"));
+    assert!(selector.matches_in_tree_ctx(&tree, heading, &ctx));
+    assert!(!selector.matches_in_tree_ctx(&tree, section, &ctx));
+
+    // Without a fragment, or with an empty one, nothing is the target.
+    for url in [
+        "https://example.com/page.html",
+        "https://example.com/page.html#",
+    ] {
+        let ctx = MatchContext::for_url(url);
+        assert!(!selector.matches_in_tree_ctx(&tree, heading, &ctx));
+        assert!(!selector.matches_in_tree_ctx(&tree, section, &ctx));
+    }
+}
+
+#[test]
+fn test_matches_focus_only_on_focused_node() {
+    // <form><input><input></form> with the second input focused
+    let mut tree = DomTree::new();
+    let form_id = tree.alloc(make_element_type("form", None, &[]));
+    let first = tree.alloc(make_element_type("input", None, &[]));
+    let second = tree.alloc(make_element_type("input", None, &[]));
+    tree.append_child(NodeId::ROOT, form_id);
+    tree.append_child(form_id, first);
+    tree.append_child(form_id, second);
+
+    let selector = parse_selector("input:focus").unwrap();
+    let ctx = MatchContext {
+        focused: Some(second),
+        ..MatchContext::default()
+    };
+    assert!(selector.matches_in_tree_ctx(&tree, second, &ctx));
+    assert!(!selector.matches_in_tree_ctx(&tree, first, &ctx));
+    assert!(!selector.matches_in_tree_ctx(&tree, form_id, &ctx));
+    // Headless matching treats nothing as focused.
+    assert!(!selector.matches_in_tree(&tree, second));
+
+    // :focus-visible additionally needs keyboard focus.
+    let visible = parse_selector("input:focus-visible").unwrap();
+    assert!(!visible.matches_in_tree_ctx(&tree, second, &ctx));
+    let keyboard = MatchContext {
+        focus_visible: true,
+        ..ctx.clone()
+    };
+    assert!(visible.matches_in_tree_ctx(&tree, second, &keyboard));
+    assert!(!visible.matches_in_tree_ctx(&tree, first, &keyboard));
+
+    // A focused descendant makes `form :focus` match through the combinator.
+    let descendant = parse_selector("form :focus").unwrap();
+    assert!(descendant.matches_in_tree_ctx(&tree, second, &ctx));
+}
+
+#[test]
+fn test_matches_hover_on_hovered_node_and_ancestors() {
+    // <ul><li><a></a></li><li></li></ul> with the pointer over the link
+    let mut tree = DomTree::new();
+    let ul = tree.alloc(make_element_type("ul", None, &[]));
+    let first = tree.alloc(make_element_type("li", None, &[]));
+    let link = tree.alloc(make_element_type("a", None, &[]));
+    let second = tree.alloc(make_element_type("li", None, &[]));
+    tree.append_child(NodeId::ROOT, ul);
+    tree.append_child(ul, first);
+    tree.append_child(first, link);
+    tree.append_child(ul, second);
+
+    let selector = parse_selector(":hover").unwrap();
+    let mut ctx = MatchContext::default();
+    let _ = ctx.hovered.insert(link);
+
+    assert!(selector.matches_in_tree_ctx(&tree, link, &ctx));
+    // Ancestors of the hovered element match too.
+    assert!(selector.matches_in_tree_ctx(&tree, first, &ctx));
+    assert!(selector.matches_in_tree_ctx(&tree, ul, &ctx));
+    assert!(!selector.matches_in_tree_ctx(&tree, second, &ctx));
+    // Without a context nothing is hovered.
+    assert!(!selector.matches_in_tree(&tree, link));
+    assert!(!selector.matches_in_tree(&tree, ul));
+}
+
+#[test]
+fn test_matches_checked_from_attribute_and_context() {
+    let mut tree = DomTree::new();
+    let form = tree.alloc(make_element_type("form", None, &[]));
+    let checked_box = tree.alloc(NodeType::Element(make_element_with_attrs(
+        "input",
+        &[("type", "checkbox"), ("checked", "")],
+    )));
+    let radio = tree.alloc(NodeType::Element(make_element_with_attrs(
+        "input",
+        &[("type", "radio")],
+    )));
+    let text = tree.alloc(NodeType::Element(make_element_with_attrs(
+        "input",
+        &[("checked", "")],
+    )));
+    let option = tree.alloc(NodeType::Element(make_element_with_attrs(
+        "option",
+        &[("selected", "")],
+    )));
+    for child in [checked_box, radio, text, option] {
+        tree.append_child(form, child);
+    }
+    tree.append_child(NodeId::ROOT, form);
+
+    // The no-context path uses each control's default checkedness.
+    let selector = parse_selector(":checked").unwrap();
+    assert!(selector.matches_in_tree(&tree, checked_box));
+    assert!(!selector.matches_in_tree(&tree, radio));
+    // A text input has no checkedness, whatever its attributes say.
+    assert!(!selector.matches_in_tree(&tree, text));
+    assert!(selector.matches_in_tree(&tree, option));
+
+    // The context records user toggles, which override the defaults.
+    let mut ctx = MatchContext::default();
+    let _ = ctx.checked.insert(checked_box, false);
+    let _ = ctx.checked.insert(radio, true);
+    assert!(!selector.matches_in_tree_ctx(&tree, checked_box, &ctx));
+    assert!(selector.matches_in_tree_ctx(&tree, radio, &ctx));
+    assert!(selector.matches_in_tree_ctx(&tree, option, &ctx));
+}
+
+#[test]
+fn test_matches_lang_from_ancestor_attribute() {
+    fn page(lang: &str) -> (DomTree, NodeId) {
+        let mut tree = DomTree::new();
+        let html = tree.alloc(NodeType::Element(make_element_with_attrs(
+            "html",
+            &[("lang", lang)],
+        )));
+        let body = tree.alloc(make_element_type("body", None, &[]));
+        let p = tree.alloc(make_element_type("p", None, &[]));
+        tree.append_child(NodeId::ROOT, html);
+        tree.append_child(html, body);
+        tree.append_child(body, p);
+        (tree, p)
+    }
+
+    let en = parse_selector(":lang(en)").unwrap();
+    let (tree, p) = page("en-US");
+    assert!(en.matches_in_tree(&tree, p));
+    assert!(
+        parse_selector(":lang(EN-us)")
+            .unwrap()
+            .matches_in_tree(&tree, p)
+    );
+    assert!(
+        !parse_selector(":lang(fr)")
+            .unwrap()
+            .matches_in_tree(&tree, p)
+    );
+    // The range must end at a subtag boundary.
+    assert!(
+        !parse_selector(":lang(en-U)")
+            .unwrap()
+            .matches_in_tree(&tree, p)
+    );
+
+    let (tree, p) = page("fr");
+    assert!(!en.matches_in_tree(&tree, p));
+}
+
+#[test]
+fn test_parse_lang_pseudo_class() {
+    assert_eq!(
+        parse_selector(":lang(en)")
+            .unwrap()
+            .complex
+            .subject
+            .simple_selectors[0],
+        SimpleSelector::PseudoClass(PseudoClass::Lang("en".to_string()))
+    );
+    assert!(parse_selector(":lang()").is_none());
+}
+
 // =============================================================================
 // Attribute Selector Matching Tests
 // =============================================================================
 
 fn make_element_with_attrs(tag: &str, attrs: &[(&str, &str)]) -> ElementData {
-    let mut attr_map = HashMap::new();
+    let mut attr_map = AttributesMap::new();
     for (k, v) in attrs {
         let _ = attr_map.insert(k.to_string(), v.to_string());
     }
@@ -1013,8 +1368,8 @@ fn test_match_attribute_substring() {
 
 #[test]
 fn test_never_match_doesnt_match() {
-    // .btn:hover → NeverMatch makes the whole compound fail
-    let selector = parse_selector(".btn:hover").unwrap();
+    // .btn:active → NeverMatch makes the whole compound fail
+    let selector = parse_selector(".btn:active").unwrap();
     let btn = make_element("div", None, &["btn"]);
     assert!(!selector.matches(&btn));
 }
@@ -1040,9 +1395,9 @@ fn test_specificity_attribute() {
 
 #[test]
 fn test_specificity_btn_hover() {
-    // .btn:hover → Class(0,1,0) + NeverMatch(0,0,0) = (0,1,0)
+    // .btn:hover → Class(0,1,0) + pseudo-class(0,1,0) = (0,2,0)
     let selector = parse_selector(".btn:hover").unwrap();
-    assert_eq!(selector.specificity, Specificity(0, 1, 0));
+    assert_eq!(selector.specificity, Specificity(0, 2, 0));
 }
 
 #[test]
@@ -1061,7 +1416,7 @@ fn test_specificity_type_with_attribute() {
 
 #[test]
 fn test_specificity_pseudo_element() {
-    // ::before → NeverMatch = (0,0,0) (pseudo-element would be C but we use NeverMatch)
+    // ::before → pseudo-element counts toward C = (0,0,1)
     let selector = parse_selector("::before").unwrap();
-    assert_eq!(selector.specificity, Specificity(0, 0, 0));
+    assert_eq!(selector.specificity, Specificity(0, 0, 1));
 }
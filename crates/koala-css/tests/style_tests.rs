@@ -2,7 +2,7 @@
 
 #![allow(clippy::float_cmp, clippy::similar_names)]
 
-use koala_css::{AutoLength, ColorValue, LengthValue};
+use koala_css::{AutoLength, ColorValue, IntrinsicSize, LengthValue, Overflow};
 
 #[test]
 fn test_color_from_hex_6() {
@@ -126,6 +126,77 @@ fn test_viewport_units() {
     // Test 100vh = full viewport height
     let full_vh = LengthValue::Vh(100.0);
     assert_eq!(full_vh.to_px_with_viewport(1280.0, 720.0), 720.0);
+
+    // "1vmin = 1% of viewport's smaller dimension"
+    // "1vmax = 1% of viewport's larger dimension"
+    assert_eq!(
+        LengthValue::Vmin(50.0).to_px_with_viewport(1280.0, 720.0),
+        360.0
+    );
+    assert_eq!(
+        LengthValue::Vmax(50.0).to_px_with_viewport(1280.0, 720.0),
+        640.0
+    );
+    assert_eq!(
+        LengthValue::Vmin(10.0).to_px_with_viewport(600.0, 900.0),
+        60.0
+    );
+    assert_eq!(
+        LengthValue::Vmax(10.0).to_px_with_viewport(600.0, 900.0),
+        90.0
+    );
+}
+
+#[test]
+fn test_math_function_parsing() {
+    use koala_css::parse_single_length;
+    use koala_css::{CSSParser, CSSTokenizer, MathFunctionKind};
+
+    let parse = |css: &str| {
+        let values = CSSParser::new(CSSTokenizer::tokenize(css)).parse_component_values();
+        parse_single_length(&values[0])
+    };
+
+    let Some(LengthValue::Math(min)) = parse("min(50%, 300px)") else {
+        panic!("expected min() to parse");
+    };
+    assert_eq!(min.kind, MathFunctionKind::Min);
+    assert_eq!(
+        min.args().collect::<Vec<_>>(),
+        [LengthValue::Percent(50.0), LengthValue::Px(300.0)]
+    );
+    assert_eq!(
+        LengthValue::Math(min).to_px_with_containing_block(400.0, 0.0, 0.0),
+        200.0
+    );
+
+    // clamp() takes exactly three arguments, and nesting isn't supported.
+    assert_eq!(parse("clamp(1px, 2px)"), None);
+    assert_eq!(parse("max(1px, min(2px, 3px))"), None);
+    assert_eq!(parse("max(1px 2px)"), None);
+
+    // Past four arguments the function is rejected, not truncated: the
+    // fifth argument would be the minimum here.
+    assert!(parse("min(5px, 4px, 3px, 2px)").is_some());
+    assert_eq!(parse("min(5px, 4px, 3px, 2px, 1px)"), None);
+}
+
+/// Helper: a default `ComputedStyle` with the declaration list `css`
+/// applied in order.
+fn style_from_declarations(css: &str) -> koala_css::ComputedStyle {
+    use koala_css::{CSSParser, CSSTokenizer};
+
+    let mut style = koala_css::ComputedStyle::default();
+    for declaration in &CSSParser::new(CSSTokenizer::tokenize(css)).parse_declaration_list() {
+        style.apply_declaration(declaration);
+    }
+    style
+}
+
+#[test]
+fn test_math_function_with_too_many_arguments_is_ignored() {
+    let style = style_from_declarations("width: 10px; width: min(50px, 40px, 30px, 20px, 1px);");
+    assert_eq!(style.width, Some(AutoLength::Length(LengthValue::Px(10.0))));
 }
 
 #[test]
@@ -214,6 +285,54 @@ fn test_ch_resolves_the_same_via_viewport_and_containing_block() {
     assert_eq!(ch.to_px_with_containing_block(500.0, 1280.0, 720.0), 80.0);
 }
 
+// Physical units
+// [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+// "1in = 2.54cm = 96px"
+
+fn parse_dimension(value: f64, unit: &str) -> Option<LengthValue> {
+    use koala_css::parse_single_length;
+    use koala_css::parser::ComponentValue;
+    use koala_css::tokenizer::CSSToken;
+
+    parse_single_length(&ComponentValue::Token(CSSToken::Dimension {
+        value,
+        int_value: None,
+        unit: unit.to_owned(),
+        numeric_type: koala_css::tokenizer::NumericType::Number,
+    }))
+}
+
+#[test]
+fn test_one_inch_is_96px() {
+    let length = parse_dimension(1.0, "in").unwrap();
+    assert_eq!(length, LengthValue::In(1.0));
+    assert!((length.to_px() - 96.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_72pt_is_96px() {
+    let length = parse_dimension(72.0, "pt").unwrap();
+    assert_eq!(length, LengthValue::Pt(72.0));
+    assert!((length.to_px() - 96.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_2_54cm_is_96px() {
+    let length = parse_dimension(2.54, "cm").unwrap();
+    assert_eq!(length, LengthValue::Cm(2.54));
+    assert!((length.to_px() - 96.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_physical_units_resolve_without_viewport_or_containing_block() {
+    for (value, unit) in [(25.4, "mm"), (101.6, "Q"), (6.0, "PC")] {
+        let length = parse_dimension(value, unit).unwrap();
+        assert!((length.to_px() - 96.0).abs() < 1e-9, "{value}{unit}");
+        assert!((length.to_px_with_viewport(1280.0, 720.0) - 96.0).abs() < 1e-9);
+        assert!((length.to_px_with_containing_block(500.0, 1280.0, 720.0) - 96.0).abs() < 1e-9);
+    }
+}
+
 // letter-spacing parser
 //
 // [§ 9.3 letter-spacing](https://www.w3.org/TR/css-text-3/#letter-spacing-property)
@@ -290,3 +409,307 @@ fn test_letter_spacing_rejects_unknown_keyword() {
     let values = [ComponentValue::Token(CSSToken::Ident("wide".to_owned()))];
     assert_eq!(parse_letter_spacing(&values), None);
 }
+
+#[test]
+fn test_computed_style_to_css_string() {
+    let style = koala_css::ComputedStyle {
+        color: Some(ColorValue {
+            r: 255,
+            g: 0,
+            b: 0,
+            a: 255,
+        }),
+        font_size: Some(LengthValue::Px(20.0)),
+        margin_top: Some(AutoLength::Length(LengthValue::Px(10.0))),
+        margin_right: Some(AutoLength::Auto),
+        margin_bottom: Some(AutoLength::Length(LengthValue::Px(1.5))),
+        margin_left: Some(AutoLength::Length(LengthValue::Percent(5.0))),
+        ..Default::default()
+    };
+    assert_eq!(
+        style.to_css_string(),
+        "color: rgb(255, 0, 0); font-size: 20px; margin-top: 10px; margin-right: auto; \
+         margin-bottom: 1.5px; margin-left: 5%;"
+    );
+}
+
+#[test]
+fn test_computed_style_to_css_string_translucent_color() {
+    let style = koala_css::ComputedStyle {
+        background_color: Some(ColorValue {
+            r: 0,
+            g: 0,
+            b: 255,
+            a: 128,
+        }),
+        ..Default::default()
+    };
+    assert_eq!(
+        style.to_css_string(),
+        "background-color: rgba(0, 0, 255, 0.502);"
+    );
+}
+
+#[test]
+fn test_computed_style_to_css_string_escapes_content() {
+    let style = koala_css::ComputedStyle {
+        content: Some(vec![koala_css::ContentItem::String(
+            "say \"hi\"".to_string(),
+        )]),
+        ..Default::default()
+    };
+    assert_eq!(style.to_css_string(), r#"content: "say \"hi\"";"#);
+}
+
+#[test]
+fn test_computed_style_to_css_string_font_family_list() {
+    use koala_css::{FontFamily, GenericFontFamily};
+
+    let style = koala_css::ComputedStyle {
+        font_family: Some(vec![
+            FontFamily::Named("Helvetica Neue".to_string()),
+            FontFamily::Named("Arial".to_string()),
+            FontFamily::Named("monospace".to_string()),
+            FontFamily::Generic(GenericFontFamily::SansSerif),
+        ]),
+        ..Default::default()
+    };
+    assert_eq!(
+        style.to_css_string(),
+        r#"font-family: "Helvetica Neue", Arial, "monospace", sans-serif;"#
+    );
+}
+
+#[test]
+fn test_computed_style_to_css_string_quarter_millimeters() {
+    // [§ 6.1 Absolute lengths](https://www.w3.org/TR/css-values-4/#absolute-lengths)
+    //
+    // The canonical form of the quarter-millimeter unit is "Q".
+    let style = koala_css::ComputedStyle {
+        font_size: Some(LengthValue::Q(40.0)),
+        ..Default::default()
+    };
+    assert_eq!(style.to_css_string(), "font-size: 40Q;");
+}
+
+#[test]
+fn test_counter_properties_parse_and_serialize() {
+    // [§ 4.2 Creating Counters](https://www.w3.org/TR/css-lists-3/#counter-reset)
+    // [§ 4.8 Counters in Generated Content](https://www.w3.org/TR/css-lists-3/#counter-functions)
+    let style = style_from_declarations(
+        "counter-reset: chapter section 3; counter-increment: section -2; \
+         content: counter(chapter, upper-roman) \".\" counters(section, \"-\");",
+    );
+
+    assert_eq!(
+        style.counter_reset,
+        Some(vec![("chapter".to_string(), 0), ("section".to_string(), 3)])
+    );
+    assert_eq!(
+        style.counter_increment,
+        Some(vec![("section".to_string(), -2)])
+    );
+    assert_eq!(
+        style.to_css_string(),
+        "content: counter(chapter, upper-roman) \".\" counters(section, \"-\"); \
+         counter-reset: chapter 0 section 3; counter-increment: section -2;"
+    );
+}
+
+#[test]
+fn test_aspect_ratio_parsing() {
+    // [§ 5.1 'aspect-ratio'](https://www.w3.org/TR/css-sizing-4/#aspect-ratio)
+    let aspect_ratio = |css: &str| style_from_declarations(css).aspect_ratio;
+
+    assert_eq!(aspect_ratio("aspect-ratio: 16 / 9"), Some((16.0, 9.0)));
+    assert_eq!(aspect_ratio("aspect-ratio: 16/9"), Some((16.0, 9.0)));
+    assert_eq!(aspect_ratio("aspect-ratio: auto 2"), Some((2.0, 1.0)));
+    assert_eq!(
+        aspect_ratio("aspect-ratio: 1 / 1; aspect-ratio: auto"),
+        None
+    );
+    // "If the <ratio> is degenerate, the property instead behaves as auto."
+    assert_eq!(
+        aspect_ratio("aspect-ratio: 1 / 1; aspect-ratio: 0 / 1"),
+        None
+    );
+    // Invalid values are ignored.
+    assert_eq!(
+        aspect_ratio("aspect-ratio: 1 / 1; aspect-ratio: -1"),
+        Some((1.0, 1.0))
+    );
+
+    let style = koala_css::ComputedStyle {
+        aspect_ratio: Some((16.0, 9.0)),
+        ..Default::default()
+    };
+    assert_eq!(style.to_css_string(), "aspect-ratio: 16 / 9;");
+}
+
+#[test]
+fn test_text_decoration_shorthand_resets_omitted_longhands() {
+    // [§ 2.4 'text-decoration'](https://www.w3.org/TR/css-text-decoration-3/#text-decoration-property)
+    //
+    // "Omitted values are set to their initial values."
+    let style = style_from_declarations(
+        "text-decoration-color: red; text-decoration-style: wavy; \
+         text-decoration: underline;",
+    );
+    assert_eq!(style.text_decoration_color, None);
+    assert_eq!(
+        style.to_css_string(),
+        "text-decoration-line: underline; text-decoration-style: solid;"
+    );
+
+    let style = style_from_declarations("text-decoration: dashed #00ff00 line-through");
+    assert_eq!(
+        style.to_css_string(),
+        "text-decoration-line: line-through; text-decoration-style: dashed; \
+         text-decoration-color: rgb(0, 255, 0);"
+    );
+}
+
+#[test]
+fn test_overflow_shorthand_sets_both_axes() {
+    // [§ 3 overflow](https://www.w3.org/TR/css-overflow-3/#propdef-overflow)
+    //
+    // "If the second value is omitted, it is copied from the first."
+    let style = style_from_declarations("overflow: hidden");
+    assert_eq!(
+        (style.overflow_x, style.overflow_y),
+        (Some(Overflow::Hidden), Some(Overflow::Hidden))
+    );
+
+    let style = style_from_declarations("overflow: hidden auto");
+    assert_eq!(
+        (style.overflow_x, style.overflow_y),
+        (Some(Overflow::Hidden), Some(Overflow::Auto))
+    );
+
+    // The longhands are independent, and an invalid shorthand is ignored.
+    let style =
+        style_from_declarations("overflow-x: hidden; overflow-y: visible; overflow: hidden bogus");
+    assert_eq!(
+        (style.overflow_x, style.overflow_y),
+        (Some(Overflow::Hidden), Some(Overflow::Visible))
+    );
+    assert_eq!(
+        style.to_css_string(),
+        "overflow-x: hidden; overflow-y: visible;"
+    );
+}
+
+#[test]
+fn test_width_and_height_accept_intrinsic_sizing_keywords() {
+    // [§ 3.1 New Keywords for width and height](https://www.w3.org/TR/css-sizing-3/#sizing-values)
+    let style = style_from_declarations("width: MAX-content; height: min-content;");
+    assert_eq!(
+        style.width,
+        Some(AutoLength::Intrinsic(IntrinsicSize::MaxContent))
+    );
+    assert_eq!(
+        style.height,
+        Some(AutoLength::Intrinsic(IntrinsicSize::MinContent))
+    );
+    assert_eq!(
+        style.to_css_string(),
+        "width: max-content; height: min-content;"
+    );
+
+    // Margins do not take the keywords.
+    let style = style_from_declarations("width: fit-content; margin-left: min-content;");
+    assert_eq!(
+        style.width,
+        Some(AutoLength::Intrinsic(IntrinsicSize::FitContent))
+    );
+    assert_eq!(style.margin_left, None);
+}
+
+#[test]
+fn test_content_visibility_and_contain_intrinsic_size() {
+    use koala_css::ContentVisibility;
+
+    // [CSS Containment 2 § 4](https://www.w3.org/TR/css-contain-2/#content-visibility)
+    let style =
+        style_from_declarations("content-visibility: AUTO; contain-intrinsic-size: auto 10px 20px");
+    assert_eq!(style.content_visibility, Some(ContentVisibility::Auto));
+    assert_eq!(
+        style.contain_intrinsic_size,
+        Some((LengthValue::Px(10.0), LengthValue::Px(20.0)))
+    );
+    assert_eq!(
+        style.to_css_string(),
+        "content-visibility: auto; contain-intrinsic-size: 10px 20px;"
+    );
+
+    // "If only one value is specified, it applies to both axes."
+    let style = style_from_declarations("contain-intrinsic-size: 5px; content-visibility: bogus");
+    assert_eq!(style.content_visibility, None);
+    assert_eq!(
+        style.contain_intrinsic_size,
+        Some((LengthValue::Px(5.0), LengthValue::Px(5.0)))
+    );
+    let style =
+        style_from_declarations("contain-intrinsic-size: 5px; contain-intrinsic-size: none");
+    assert_eq!(style.contain_intrinsic_size, None);
+}
+
+#[test]
+fn test_style_diff_color_change_needs_only_repaint() {
+    use koala_css::{ChangedProperty, StyleDamage};
+
+    let old = style_from_declarations("color: red; width: 100px;");
+    let new = style_from_declarations("color: blue; width: 100px;");
+
+    assert_eq!(
+        old.diff(&new),
+        vec![ChangedProperty {
+            name: "color",
+            damage: StyleDamage::Repaint,
+        }]
+    );
+    assert!(old.diff(&old.clone()).is_empty());
+}
+
+#[test]
+fn test_style_diff_width_change_needs_relayout() {
+    use koala_css::StyleDamage;
+
+    let old = style_from_declarations("color: red; width: 100px;");
+    let new = style_from_declarations("color: blue; width: 200px; margin-top: 4px;");
+
+    let changes = old.diff(&new);
+    let names: Vec<&str> = changes.iter().map(|c| c.name).collect();
+    assert_eq!(names, ["color", "margin-top", "width"]);
+    assert_eq!(
+        changes.iter().map(|c| c.damage).max(),
+        Some(StyleDamage::Relayout)
+    );
+
+    // Unsetting a property is a change too.
+    let unset = old.diff(&style_from_declarations("color: red;"));
+    assert_eq!(unset.len(), 1);
+    assert_eq!(unset[0].name, "width");
+}
+
+#[test]
+fn test_computed_style_to_css_string_empty() {
+    assert_eq!(koala_css::ComputedStyle::default().to_css_string(), "");
+}
+
+#[test]
+fn test_rem_parses_from_dimension_token() {
+    use koala_css::parse_single_length;
+    use koala_css::parser::ComponentValue;
+    use koala_css::tokenizer::CSSToken;
+
+    let value = ComponentValue::Token(CSSToken::Dimension {
+        value: 1.5,
+        int_value: None,
+        unit: "REM".to_owned(),
+        numeric_type: koala_css::tokenizer::NumericType::Number,
+    });
+    assert_eq!(parse_single_length(&value), Some(LengthValue::Rem(1.5)));
+    // Without a cascade, rem falls back to the default root font size.
+    assert_eq!(LengthValue::Rem(2.0).to_px(), 32.0);
+}
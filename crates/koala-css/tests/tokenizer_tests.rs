@@ -3,6 +3,7 @@
 #![allow(clippy::approx_constant, clippy::float_cmp)]
 
 use koala_css::tokenizer::{CSSToken, CSSTokenizer, HashType, NumericType};
+use koala_css::{CSSParser, Rule};
 
 /// Helper to tokenize a string and return the tokens
 fn tokenize(input: &str) -> Vec<CSSToken> {
@@ -279,6 +280,71 @@ fn test_comment() {
     }
 }
 
+#[test]
+fn test_comment_between_idents() {
+    // The whitespace on either side of the comment produces two separate
+    // whitespace tokens; the comment itself produces none.
+    let tokens = tokenize("a /* x */ b");
+    assert_eq!(tokens.len(), 5); // ident + whitespace + whitespace + ident + EOF
+    assert_eq!(tokens[0], CSSToken::Ident("a".to_string()));
+    assert!(matches!(tokens[1], CSSToken::Whitespace));
+    assert!(matches!(tokens[2], CSSToken::Whitespace));
+    assert_eq!(tokens[3], CSSToken::Ident("b".to_string()));
+    assert!(matches!(tokens[4], CSSToken::EOF));
+}
+
+#[test]
+fn test_comment_inside_declaration() {
+    let tokens = tokenize("color:/* c */red");
+    assert_eq!(tokens.len(), 4); // ident + colon + ident + EOF
+    assert_eq!(tokens[0], CSSToken::Ident("color".to_string()));
+    assert!(matches!(tokens[1], CSSToken::Colon));
+    assert_eq!(tokens[2], CSSToken::Ident("red".to_string()));
+    assert!(matches!(tokens[3], CSSToken::EOF));
+}
+
+#[test]
+fn test_comment_splits_ident() {
+    // A comment ends the ident it interrupts rather than being folded in.
+    let tokens = tokenize("a/**/b");
+    assert_eq!(tokens.len(), 3);
+    assert_eq!(tokens[0], CSSToken::Ident("a".to_string()));
+    assert_eq!(tokens[1], CSSToken::Ident("b".to_string()));
+}
+
+#[test]
+fn test_unterminated_comment_consumes_to_eof() {
+    let tokens = tokenize("color /* never closed * / red;");
+    assert_eq!(tokens.len(), 3); // ident + whitespace + EOF
+    assert_eq!(tokens[0], CSSToken::Ident("color".to_string()));
+    assert!(matches!(tokens[1], CSSToken::Whitespace));
+    assert!(matches!(tokens[2], CSSToken::EOF));
+}
+
+#[test]
+fn test_unterminated_comment_alone() {
+    let tokens = tokenize("/*");
+    assert_eq!(tokens.len(), 1);
+    assert!(matches!(tokens[0], CSSToken::EOF));
+
+    let tokens = tokenize("/* trailing *");
+    assert_eq!(tokens.len(), 1);
+    assert!(matches!(tokens[0], CSSToken::EOF));
+}
+
+#[test]
+fn test_comments_between_declarations() {
+    let tokens = tokenize("p { color: red; /* one */ /* two */ margin: 0 }");
+    let mut parser = CSSParser::new(tokens);
+    let stylesheet = parser.parse_stylesheet();
+    assert_eq!(stylesheet.rules.len(), 1);
+    let Rule::Style(rule) = &stylesheet.rules[0] else {
+        panic!("Expected style rule, got {:?}", stylesheet.rules[0]);
+    };
+    let names: Vec<&str> = rule.declarations.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(names, ["color", "margin"]);
+}
+
 #[test]
 fn test_cdo_cdc() {
     let tokens = tokenize("<!-- -->");
@@ -298,6 +364,53 @@ fn test_url_unquoted() {
     }
 }
 
+#[test]
+fn test_url_quoted_with_whitespace() {
+    // A quoted url( is a function token; the string keeps its spaces.
+    let tokens = tokenize(r#"url( "a b.png" )"#);
+    match &tokens[0] {
+        CSSToken::Function(name) => assert_eq!(name, "url"),
+        other => panic!("Expected Function token, got {other:?}"),
+    }
+    match &tokens[1] {
+        CSSToken::String(value) => assert_eq!(value, "a b.png"),
+        other => panic!("Expected String token, got {other:?}"),
+    }
+    assert!(matches!(tokens[2], CSSToken::Whitespace));
+    assert!(matches!(tokens[3], CSSToken::RightParen));
+}
+
+#[test]
+fn test_url_unquoted_escape() {
+    let tokens = tokenize(r"url( a\)b.png )");
+    assert_eq!(tokens.len(), 2);
+    match &tokens[0] {
+        CSSToken::Url(value) => assert_eq!(value, "a)b.png"),
+        other => panic!("Expected Url token, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_bad_url_recovers() {
+    // A quote inside an unquoted url is a parse error; the remnants up to
+    // the closing parenthesis are consumed and tokenizing carries on.
+    let tokens = tokenize(r#"url(a"b) red"#);
+    assert_eq!(tokens.len(), 4);
+    assert!(matches!(tokens[0], CSSToken::BadUrl));
+    assert!(matches!(tokens[1], CSSToken::Whitespace));
+    match &tokens[2] {
+        CSSToken::Ident(name) => assert_eq!(name, "red"),
+        other => panic!("Expected Ident token, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_bad_url_whitespace_inside() {
+    let tokens = tokenize("url(a b) red");
+    assert!(matches!(tokens[0], CSSToken::BadUrl));
+    assert!(matches!(&tokens[2], CSSToken::Ident(name) if name == "red"));
+}
+
 #[test]
 fn test_simple_rule() {
     let tokens = tokenize("color: red;");
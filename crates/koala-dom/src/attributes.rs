@@ -0,0 +1,145 @@
+//! Element attribute list.
+//!
+//! [§ 4.9 Interface Element](https://dom.spec.whatwg.org/#interface-element)
+
+/// Ordered map of attribute names to values for an element.
+///
+/// [§ 4.9.1 Interface Attr](https://dom.spec.whatwg.org/#interface-attr)
+/// "An Attr object represents an attribute of an Element object."
+///
+/// [§ 4.9.2 Interface `NamedNodeMap`](https://dom.spec.whatwg.org/#interface-namednodemap)
+/// "A `NamedNodeMap` has an associated element (an element)."
+///
+/// [§ 4.9 Interface Element](https://dom.spec.whatwg.org/#interface-element)
+/// "Elements also have an attribute list, which is a list exposed through a
+/// `NamedNodeMap`. Unless explicitly given when an element is created, its
+/// attribute list is empty."
+///
+/// Attributes are kept in insertion order, as the spec's attribute *list*
+/// requires: "To append an attribute attribute to an element element ...
+/// Append attribute to element's attribute list." Iteration, and therefore
+/// serialization, follows source order. Replacing an existing attribute's
+/// value keeps its position; removing one shifts later attributes down.
+///
+/// Elements rarely carry more than a handful of attributes, so lookups are
+/// a linear scan over a `Vec`.
+///
+/// NOTE: This is a simplified representation. Full DOM spec compliance would require:
+/// - Namespace URI and namespace prefix per attribute
+/// - Attr node objects with ownerElement references
+/// - `NamedNodeMap` interface with getNamedItem/setNamedItem methods
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttributesMap {
+    entries: Vec<(String, String)>,
+}
+
+impl AttributesMap {
+    /// Create an empty attribute list.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Number of attributes.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the element has no attributes.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// [§ 4.9 get an attribute by name](https://dom.spec.whatwg.org/#concept-element-attributes-get-by-name)
+    ///
+    /// Returns the value of the attribute named `name`, if any.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&String> {
+        self.entries
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Mutable access to the value of the attribute named `name`, if any.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut String> {
+        self.entries
+            .iter_mut()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Whether an attribute named `name` is present.
+    #[must_use]
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.entries.iter().any(|(key, _)| key == name)
+    }
+
+    /// [§ 4.9 set an attribute value](https://dom.spec.whatwg.org/#concept-element-attributes-set-value)
+    ///
+    /// "If attribute is null, create an attribute ... and then append this
+    /// attribute to element, and then return."
+    /// "Change attribute to value."
+    ///
+    /// Sets `name` to `value`, appending it if absent. Returns the previous
+    /// value when the attribute already existed.
+    pub fn insert(&mut self, name: String, value: String) -> Option<String> {
+        if let Some(existing) = self.get_mut(&name) {
+            return Some(std::mem::replace(existing, value));
+        }
+        self.entries.push((name, value));
+        None
+    }
+
+    /// [§ 4.9 remove an attribute by name](https://dom.spec.whatwg.org/#concept-element-attributes-remove-by-name)
+    ///
+    /// Removes the attribute named `name`, returning its value.
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        let index = self.entries.iter().position(|(key, _)| key == name)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Iterate over `(name, value)` pairs in attribute-list order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.entries.iter().map(|(name, value)| (name, value))
+    }
+
+    /// Iterate over attribute names in attribute-list order.
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(name, _)| name)
+    }
+}
+
+impl FromIterator<(String, String)> for AttributesMap {
+    /// Later duplicates replace earlier values in place, like repeated
+    /// calls to [`AttributesMap::insert`].
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl Extend<(String, String)> for AttributesMap {
+    fn extend<I: IntoIterator<Item = (String, String)>>(&mut self, iter: I) {
+        for (name, value) in iter {
+            let _ = self.insert(name, value);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a AttributesMap {
+    type Item = (&'a String, &'a String);
+    type IntoIter = std::iter::Map<
+        std::slice::Iter<'a, (String, String)>,
+        fn(&'a (String, String)) -> (&'a String, &'a String),
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.iter().map(|(name, value)| (name, value))
+    }
+}
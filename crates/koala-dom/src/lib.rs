@@ -8,23 +8,11 @@
 //! The tree uses arena allocation with [`NodeId`] indices for all relationships,
 //! providing O(1) access and traversal without borrow checker issues.
 
-use koala_std::collections::{HashMap, HashSet};
+use koala_std::collections::HashSet;
 
-/// Map of attribute names to values for an element.
-///
-/// [§ 4.9.1 Interface Attr](https://dom.spec.whatwg.org/#interface-attr)
-/// "An Attr object represents an attribute of an Element object."
-///
-/// [§ 4.9.2 Interface `NamedNodeMap`](https://dom.spec.whatwg.org/#interface-namednodemap)
-/// "A `NamedNodeMap` has an associated element (an element)."
-///
-/// NOTE: This is a simplified representation. Full DOM spec compliance would require:
-/// - Namespace URI and namespace prefix per attribute
-/// - Attr node objects with ownerElement references
-/// - `NamedNodeMap` interface with getNamedItem/setNamedItem methods
-///
-/// We use a simple String->String map since we don't currently need namespace support.
-pub type AttributesMap = HashMap<String, String>;
+mod attributes;
+
+pub use attributes::AttributesMap;
 
 /// A type-safe index into the DOM tree.
 ///
@@ -89,6 +77,20 @@ pub enum NodeType {
     /// [§ 4.7 Interface Comment](https://dom.spec.whatwg.org/#interface-comment)
     /// "Comment nodes are known as comments."
     Comment(String),
+    /// [§ 4.6 Interface DocumentType](https://dom.spec.whatwg.org/#interface-documenttype)
+    /// "Doctypes have an associated name, public ID, and system ID."
+    ///
+    /// "When a doctype is created, its name is always given. Unless explicitly
+    /// given when a doctype is created, its public ID and system ID are the
+    /// empty string."
+    DocumentType {
+        /// The doctype's name, e.g. `"html"`.
+        name: String,
+        /// The doctype's public ID.
+        public_id: String,
+        /// The doctype's system ID.
+        system_id: String,
+    },
 }
 
 /// Element-specific data.
@@ -123,11 +125,20 @@ impl ElementData {
     /// Per [§ 3.2.6 Global attributes](https://html.spec.whatwg.org/multipage/dom.html#global-attributes):
     /// "The class attribute, if specified, must have a value that is a set of
     /// space-separated tokens representing the various classes that the element belongs to."
+    ///
+    /// Per [§ 2.3.7 Space-separated tokens](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#space-separated-tokens):
+    /// "A set of space-separated tokens is a string containing zero or more
+    /// words (known as tokens) separated by one or more ASCII whitespace"
+    ///
+    /// Splitting on any run of ASCII whitespace means tabs, newlines and
+    /// leading/trailing spaces never produce empty class names.
     #[must_use]
     pub fn classes(&self) -> HashSet<&str> {
         self.attrs
             .get("class")
-            .map_or_else(HashSet::new, |classlist| classlist.split(' ').collect())
+            .map_or_else(HashSet::new, |classlist| {
+                classlist.split_ascii_whitespace().collect()
+            })
     }
 }
 
@@ -368,6 +379,25 @@ impl DomTree {
         }
     }
 
+    /// The number of ancestors of `id`: 0 for the root, 1 for its
+    /// children, and so on.
+    ///
+    /// Computed by walking [`Self::ancestors`], so it costs one step per
+    /// level.
+    #[must_use]
+    pub fn depth(&self, id: NodeId) -> usize {
+        self.ancestors(id).count()
+    }
+
+    /// [§ 4.2.5 Ancestor](https://dom.spec.whatwg.org/#concept-tree-ancestor)
+    ///
+    /// `id` followed by its [`Self::ancestors`]: the node itself, its
+    /// parent, and so on up to the root.
+    #[must_use]
+    pub fn path_to_root(&self, id: NodeId) -> Vec<NodeId> {
+        std::iter::once(id).chain(self.ancestors(id)).collect()
+    }
+
     /// [§ 4.2.5 Previous sibling](https://dom.spec.whatwg.org/#concept-tree-previous-sibling)
     ///
     /// "An object A's previous sibling is the object immediately preceding A
@@ -384,6 +414,68 @@ impl DomTree {
         }
     }
 
+    /// [§ 4.2.6 Mixin ParentNode](https://dom.spec.whatwg.org/#dom-parentnode-children)
+    ///
+    /// "The children getter steps are to return an `HTMLCollection` collection
+    /// rooted at this matching only element children."
+    ///
+    /// The element children of `id` in tree order, skipping text, comment
+    /// and other non-element nodes.
+    pub fn element_children(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.children(id)
+            .iter()
+            .copied()
+            .filter(|&child| self.as_element(child).is_some())
+    }
+
+    /// [§ 4.2.6 Mixin ParentNode](https://dom.spec.whatwg.org/#dom-parentnode-firstelementchild)
+    ///
+    /// "The firstElementChild getter steps are to return the first child
+    /// that is an element; otherwise null."
+    #[must_use]
+    pub fn first_element_child(&self, id: NodeId) -> Option<NodeId> {
+        self.element_children(id).next()
+    }
+
+    /// [§ 4.2.6 Mixin ParentNode](https://dom.spec.whatwg.org/#dom-parentnode-lastelementchild)
+    ///
+    /// "The lastElementChild getter steps are to return the last child that
+    /// is an element; otherwise null."
+    #[must_use]
+    pub fn last_element_child(&self, id: NodeId) -> Option<NodeId> {
+        self.children(id)
+            .iter()
+            .rev()
+            .copied()
+            .find(|&child| self.as_element(child).is_some())
+    }
+
+    /// [§ 4.2.7 Mixin NonDocumentTypeChildNode](https://dom.spec.whatwg.org/#dom-nondocumenttypechildnode-nextelementsibling)
+    ///
+    /// "The nextElementSibling getter steps are to return the first
+    /// following sibling of this that is an element; otherwise null."
+    #[must_use]
+    pub fn next_element_sibling(&self, id: NodeId) -> Option<NodeId> {
+        let mut current = self.next_sibling(id);
+        while let Some(sibling) = current {
+            if self.as_element(sibling).is_some() {
+                return Some(sibling);
+            }
+            current = self.next_sibling(sibling);
+        }
+        None
+    }
+
+    /// [§ 4.2.7 Mixin NonDocumentTypeChildNode](https://dom.spec.whatwg.org/#dom-nondocumenttypechildnode-previouselementsibling)
+    ///
+    /// "The previousElementSibling getter steps are to return the first
+    /// preceding sibling of this that is an element; otherwise null."
+    #[must_use]
+    pub fn previous_element_sibling(&self, id: NodeId) -> Option<NodeId> {
+        self.preceding_siblings(id)
+            .find(|&sibling| self.as_element(sibling).is_some())
+    }
+
     /// Get element data if this node is an element.
     #[must_use]
     pub fn as_element(&self, id: NodeId) -> Option<&ElementData> {
@@ -562,6 +654,117 @@ impl DomTree {
         }
     }
 
+    /// [§ 4.9 Insert adjacent](https://dom.spec.whatwg.org/#insert-adjacent)
+    ///
+    /// "afterend: If element's parent is null, return null. Return the
+    /// result of pre-inserting node into element's parent before element's
+    /// next sibling."
+    ///
+    /// Inserts `new_child` into `parent`'s children list immediately after
+    /// `reference`. As with [`Self::insert_before`], `new_child` must not
+    /// already be in the tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `reference` is not found in `parent`'s children list.
+    pub fn insert_after(&mut self, parent: NodeId, new_child: NodeId, reference: NodeId) {
+        assert!(
+            self.nodes[parent.0].children.contains(&reference),
+            "insert_after: reference not found in parent's children"
+        );
+        match self.nodes[reference.0].next_sibling {
+            Some(next) => self.insert_before(parent, new_child, next),
+            // The reference is the last child, so "before null" appends.
+            None => self.append_child(parent, new_child),
+        }
+    }
+
+    /// [§ 4.2.3 Replace](https://dom.spec.whatwg.org/#concept-node-replace)
+    ///
+    /// "To replace a child with node within a parent, run these steps:"
+    ///
+    /// Puts `new_child` in `old_child`'s slot in `parent`'s children list
+    /// and detaches `old_child`, leaving it with no parent or siblings.
+    /// `new_child` must not already be in the tree.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `old_child` is not found in `parent`'s children list.
+    pub fn replace_child(&mut self, parent: NodeId, new_child: NodeId, old_child: NodeId) {
+        // STEP 1: Find old_child's position in parent's children list.
+        // "If child's parent is not parent, then throw a "NotFoundError"
+        // DOMException."
+        let pos = self.nodes[parent.0]
+            .children
+            .iter()
+            .position(|&id| id == old_child)
+            .expect("replace_child: old child not found in parent's children");
+
+        // STEP 2: Take over old_child's sibling links.
+        let prev = self.nodes[old_child.0].prev_sibling;
+        let next = self.nodes[old_child.0].next_sibling;
+        self.nodes[parent.0].children[pos] = new_child;
+        self.nodes[new_child.0].parent = Some(parent);
+        self.nodes[new_child.0].prev_sibling = prev;
+        self.nodes[new_child.0].next_sibling = next;
+        if let Some(prev_id) = prev {
+            self.nodes[prev_id.0].next_sibling = Some(new_child);
+        }
+        if let Some(next_id) = next {
+            self.nodes[next_id.0].prev_sibling = Some(new_child);
+        }
+
+        // STEP 3: Clear old_child's relationship pointers.
+        self.nodes[old_child.0].parent = None;
+        self.nodes[old_child.0].prev_sibling = None;
+        self.nodes[old_child.0].next_sibling = None;
+    }
+
+    /// [§ 4.4 Clone a node](https://dom.spec.whatwg.org/#concept-node-clone)
+    ///
+    /// "To clone a node, with a document and an optional clone children flag..."
+    ///
+    /// Deep-copies `id` and all of its descendants into freshly allocated
+    /// nodes and returns the `NodeId` of the copy. The copy is detached:
+    /// it has no parent and no siblings, so the caller decides where (or
+    /// whether) to insert it. Used by `cloneNode(true)` and template
+    /// instantiation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is out of range.
+    pub fn clone_subtree(&mut self, id: NodeId) -> NodeId {
+        // STEP 1: "Let copy be a node that implements the same interfaces
+        // as node..."
+        //
+        // "If node is an element: ... For each attribute of node's
+        // attribute list: Let copyAttribute be the result of cloning
+        // attribute. Append copyAttribute to copy."
+        //
+        // "Otherwise, set copy's data to that of node."
+        //
+        // NOTE: `NodeType` owns its element data and text, so cloning the
+        // node type duplicates attributes and character data in one step.
+        let node_type = self.nodes[id.0].node_type.clone();
+        let copy = self.alloc(node_type);
+
+        // STEP 2: "If the clone children flag is set, then for each child
+        // child of node, in tree order: append the result of cloning child
+        // with document and the clone children flag set, to copy."
+        //
+        // NOTE: The children list is copied up front because cloning a
+        // child allocates, which would otherwise alias the borrow.
+        // `append_child` keeps the clone's sibling links consistent.
+        let children = self.nodes[id.0].children.clone();
+        for child in children {
+            let child_copy = self.clone_subtree(child);
+            self.append_child(copy, child_copy);
+        }
+
+        // STEP 3: "Return copy."
+        copy
+    }
+
     /// Move all children of `from` to become children of `to`.
     ///
     /// Children are appended to `to`'s existing children list.
@@ -625,6 +828,96 @@ impl DomTree {
             })
             .copied()
     }
+
+    /// [§ 4.2.4 NonElementParentNode.getElementById](https://dom.spec.whatwg.org/#dom-nonelementparentnode-getelementbyid)
+    ///
+    /// "The getElementById(elementId) method steps are to return the first
+    /// element, in tree order, within this's descendants, whose ID is
+    /// elementId; otherwise, if there is no such element, null."
+    ///
+    /// An empty `element_id` never matches, since an element's ID is never
+    /// the empty string.
+    #[must_use]
+    pub fn get_element_by_id(&self, element_id: &str) -> Option<NodeId> {
+        if element_id.is_empty() {
+            return None;
+        }
+        self.descendants(NodeId::ROOT).find(|&id| {
+            self.as_element(id)
+                .and_then(ElementData::id)
+                .is_some_and(|value| value == element_id)
+        })
+    }
+
+    /// [§ 4.5 Document.getElementsByClassName](https://dom.spec.whatwg.org/#dom-document-getelementsbyclassname)
+    ///
+    /// "The list of elements with class names `classNames` for a node root is
+    /// the `HTMLCollection` returned by the following algorithm:
+    ///   1. Let classes be the result of running the ordered set parser on
+    ///      `classNames`.
+    ///   2. If classes is the empty set, return an empty `HTMLCollection`.
+    ///   3. Return a `HTMLCollection` rooted at root, whose filter matches
+    ///      descendant elements that have all their classes in classes."
+    ///
+    /// `class_names` may hold several whitespace-separated classes; an
+    /// element must have every one of them. Returns the matching elements
+    /// in tree order.
+    ///
+    /// NOTE: Class names are compared case-sensitively, as in no-quirks
+    /// mode.
+    #[must_use]
+    pub fn get_elements_by_class_name(&self, class_names: &str) -> Vec<NodeId> {
+        // STEP 1: "Let classes be the result of running the ordered set
+        //          parser on `classNames`."
+        let classes: Vec<&str> = class_names.split_ascii_whitespace().collect();
+
+        // STEP 2: "If classes is the empty set, return an empty `HTMLCollection`."
+        if classes.is_empty() {
+            return Vec::new();
+        }
+
+        // STEP 3: "...whose filter matches descendant elements that have all
+        //          their classes in classes."
+        self.descendants(NodeId::ROOT)
+            .filter(|&id| {
+                self.as_element(id).is_some_and(|element| {
+                    let element_classes = element.classes();
+                    classes.iter().all(|class| element_classes.contains(class))
+                })
+            })
+            .collect()
+    }
+
+    /// [§ 4.5 Document.getElementsByTagName](https://dom.spec.whatwg.org/#dom-document-getelementsbytagname)
+    ///
+    /// "The list of elements with qualified name `qualifiedName` for a node
+    /// root is the `HTMLCollection` returned by the following algorithm:
+    ///   1. If `qualifiedName` is U+002A (*), return a `HTMLCollection` rooted at
+    ///      root, whose filter matches only descendant elements.
+    ///   2. Otherwise, if root's node document is an HTML document, return a
+    ///      `HTMLCollection` rooted at root, whose filter matches the following
+    ///      descendant elements: [...] Whose namespace is the HTML namespace
+    ///      and whose qualified name is `qualifiedName`, in ASCII lowercase."
+    ///
+    /// Returns the matching elements in tree order.
+    ///
+    /// NOTE: Every element is treated as an HTML element, so tag names are
+    /// compared ASCII case-insensitively.
+    #[must_use]
+    pub fn get_elements_by_tag_name(&self, qualified_name: &str) -> Vec<NodeId> {
+        self.descendants(NodeId::ROOT)
+            .filter(|&id| {
+                self.as_element(id).is_some_and(|element| {
+                    // STEP 1: "If `qualifiedName` is U+002A (*), ...matches
+                    //          only descendant elements."
+                    qualified_name == "*"
+                        // STEP 2: "...whose qualified name is `qualifiedName`,
+                        //          in ASCII lowercase."
+                        || element.tag_name.eq_ignore_ascii_case(qualified_name)
+                })
+            })
+            .collect()
+    }
 }
 
 impl Default for DomTree {
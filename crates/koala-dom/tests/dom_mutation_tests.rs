@@ -1,4 +1,5 @@
-//! Tests for DOM tree mutation methods: remove_child, insert_before, move_children.
+//! Tests for DOM tree mutation methods: remove_child, insert_before, insert_after,
+//! replace_child, move_children.
 
 #![allow(clippy::default_trait_access, clippy::doc_markdown)]
 
@@ -138,6 +139,100 @@ fn test_insert_before_middle() {
     assert_eq!(tree.prev_sibling(c), Some(b));
 }
 
+// ========== insert_after ==========
+
+#[test]
+fn test_insert_after_last_child() {
+    let mut tree = DomTree::new();
+    let parent = alloc_element(&mut tree, "div");
+    tree.append_child(NodeId::ROOT, parent);
+
+    let a = alloc_element(&mut tree, "a");
+    let b = alloc_element(&mut tree, "b");
+    tree.append_child(parent, a);
+    tree.append_child(parent, b);
+
+    let c = alloc_element(&mut tree, "c");
+    tree.insert_after(parent, c, b);
+
+    assert_eq!(tree.children(parent), &[a, b, c]);
+    assert_eq!(tree.parent(c), Some(parent));
+    assert_eq!(tree.next_sibling(b), Some(c));
+    assert_eq!(tree.prev_sibling(c), Some(b));
+    assert_eq!(tree.next_sibling(c), None);
+    assert_eq!(tree.last_child(parent), Some(c));
+}
+
+#[test]
+fn test_insert_after_middle() {
+    let mut tree = DomTree::new();
+    let parent = alloc_element(&mut tree, "div");
+    tree.append_child(NodeId::ROOT, parent);
+
+    let a = alloc_element(&mut tree, "a");
+    let c = alloc_element(&mut tree, "c");
+    tree.append_child(parent, a);
+    tree.append_child(parent, c);
+
+    let b = alloc_element(&mut tree, "b");
+    tree.insert_after(parent, b, a);
+
+    assert_eq!(tree.children(parent), &[a, b, c]);
+    assert_eq!(tree.next_sibling(a), Some(b));
+    assert_eq!(tree.prev_sibling(b), Some(a));
+    assert_eq!(tree.next_sibling(b), Some(c));
+    assert_eq!(tree.prev_sibling(c), Some(b));
+}
+
+// ========== replace_child ==========
+
+#[test]
+fn test_replace_child_middle_of_three() {
+    let mut tree = DomTree::new();
+    let parent = alloc_element(&mut tree, "div");
+    tree.append_child(NodeId::ROOT, parent);
+
+    let a = alloc_element(&mut tree, "a");
+    let b = alloc_element(&mut tree, "b");
+    let c = alloc_element(&mut tree, "c");
+    tree.append_child(parent, a);
+    tree.append_child(parent, b);
+    tree.append_child(parent, c);
+
+    let x = alloc_element(&mut tree, "x");
+    tree.replace_child(parent, x, b);
+
+    // x takes b's slot
+    assert_eq!(tree.children(parent), &[a, x, c]);
+    assert_eq!(tree.parent(x), Some(parent));
+    assert_eq!(tree.next_sibling(a), Some(x));
+    assert_eq!(tree.prev_sibling(x), Some(a));
+    assert_eq!(tree.next_sibling(x), Some(c));
+    assert_eq!(tree.prev_sibling(c), Some(x));
+
+    // b is fully detached
+    assert_eq!(tree.parent(b), None);
+    assert_eq!(tree.prev_sibling(b), None);
+    assert_eq!(tree.next_sibling(b), None);
+}
+
+#[test]
+fn test_replace_only_child() {
+    let mut tree = DomTree::new();
+    let parent = alloc_element(&mut tree, "div");
+    tree.append_child(NodeId::ROOT, parent);
+
+    let old = alloc_element(&mut tree, "p");
+    tree.append_child(parent, old);
+    let new = alloc_element(&mut tree, "span");
+    tree.replace_child(parent, new, old);
+
+    assert_eq!(tree.children(parent), &[new]);
+    assert_eq!(tree.prev_sibling(new), None);
+    assert_eq!(tree.next_sibling(new), None);
+    assert_eq!(tree.parent(old), None);
+}
+
 // ========== move_children ==========
 
 #[test]
@@ -199,3 +294,83 @@ fn test_move_children_empty_source() {
     assert_eq!(tree.children(from).len(), 0);
     assert_eq!(tree.children(to).len(), 0);
 }
+
+// ========== clone_subtree ==========
+
+#[test]
+fn test_clone_subtree_copies_structure() {
+    let mut tree = DomTree::new();
+    let root = alloc_element(&mut tree, "div");
+    tree.append_child(NodeId::ROOT, root);
+    let _ = tree
+        .as_element_mut(root)
+        .unwrap()
+        .attrs
+        .insert("class".to_string(), "box".to_string());
+
+    let p = alloc_element(&mut tree, "p");
+    let text = tree.alloc(NodeType::Text("hello".to_string()));
+    let span = alloc_element(&mut tree, "span");
+    tree.append_child(root, p);
+    tree.append_child(p, text);
+    tree.append_child(root, span);
+
+    let copy = tree.clone_subtree(root);
+
+    // The clone is a fresh, detached node.
+    assert_ne!(copy, root);
+    assert_eq!(tree.parent(copy), None);
+    assert_eq!(tree.prev_sibling(copy), None);
+    assert_eq!(tree.next_sibling(copy), None);
+    assert_eq!(tree.children(NodeId::ROOT), &[root]);
+
+    let copy_el = tree.as_element(copy).unwrap();
+    assert_eq!(copy_el.tag_name, "div");
+    assert_eq!(copy_el.attrs.get("class").map(String::as_str), Some("box"));
+
+    // Children mirror the original's shape with correct links.
+    let kids = tree.children(copy).to_vec();
+    assert_eq!(kids.len(), 2);
+    assert_ne!(kids[0], p);
+    assert_ne!(kids[1], span);
+    assert_eq!(tree.as_element(kids[0]).unwrap().tag_name, "p");
+    assert_eq!(tree.as_element(kids[1]).unwrap().tag_name, "span");
+    assert_eq!(tree.parent(kids[0]), Some(copy));
+    assert_eq!(tree.parent(kids[1]), Some(copy));
+    assert_eq!(tree.prev_sibling(kids[0]), None);
+    assert_eq!(tree.next_sibling(kids[0]), Some(kids[1]));
+    assert_eq!(tree.prev_sibling(kids[1]), Some(kids[0]));
+    assert_eq!(tree.next_sibling(kids[1]), None);
+
+    let copy_text = tree.first_child(kids[0]).unwrap();
+    assert_ne!(copy_text, text);
+    assert_eq!(tree.as_text(copy_text), Some("hello"));
+    assert_eq!(tree.parent(copy_text), Some(kids[0]));
+}
+
+#[test]
+fn test_clone_subtree_is_independent() {
+    let mut tree = DomTree::new();
+    let root = alloc_element(&mut tree, "div");
+    tree.append_child(NodeId::ROOT, root);
+    let child = alloc_element(&mut tree, "p");
+    tree.append_child(root, child);
+
+    let copy = tree.clone_subtree(root);
+    let copy_child = tree.first_child(copy).unwrap();
+
+    // Mutate the clone: attributes and structure.
+    let _ = tree
+        .as_element_mut(copy)
+        .unwrap()
+        .attrs
+        .insert("id".to_string(), "cloned".to_string());
+    let extra = alloc_element(&mut tree, "em");
+    tree.append_child(copy, extra);
+    tree.remove_child(copy, copy_child);
+
+    // The original is untouched.
+    assert!(tree.as_element(root).unwrap().attrs.get("id").is_none());
+    assert_eq!(tree.children(root), &[child]);
+    assert_eq!(tree.parent(child), Some(root));
+}
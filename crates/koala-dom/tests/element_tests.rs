@@ -0,0 +1,107 @@
+//! Tests for ElementData attribute accessors and the AttributesMap list.
+
+#![allow(clippy::doc_markdown)]
+
+use koala_dom::{AttributesMap, ElementData};
+
+/// Helper to create a div with the given attributes.
+fn element_with_attrs(attrs: &[(&str, &str)]) -> ElementData {
+    let mut map = AttributesMap::new();
+    for (name, value) in attrs {
+        let _ = map.insert((*name).to_string(), (*value).to_string());
+    }
+    ElementData {
+        tag_name: "div".to_string(),
+        attrs: map,
+    }
+}
+
+// ========== classes ==========
+
+#[test]
+fn test_classes_single_spaces() {
+    let element = element_with_attrs(&[("class", "a b c")]);
+    let classes = element.classes();
+    assert_eq!(classes.len(), 3);
+    assert!(classes.contains("a"));
+    assert!(classes.contains("b"));
+    assert!(classes.contains("c"));
+}
+
+#[test]
+fn test_classes_mixed_ascii_whitespace() {
+    let element = element_with_attrs(&[("class", "a\n b\t\tc\r\x0Cd")]);
+    let classes = element.classes();
+    assert_eq!(classes.len(), 4);
+    for name in ["a", "b", "c", "d"] {
+        assert!(classes.contains(name), "missing class {name}");
+    }
+    assert!(!classes.contains(""));
+}
+
+#[test]
+fn test_classes_leading_and_trailing_spaces() {
+    let element = element_with_attrs(&[("class", "   a  b   ")]);
+    let classes = element.classes();
+    assert_eq!(classes.len(), 2);
+    assert!(classes.contains("a"));
+    assert!(classes.contains("b"));
+    assert!(!classes.contains(""));
+}
+
+#[test]
+fn test_classes_whitespace_only_or_missing() {
+    assert!(
+        element_with_attrs(&[("class", " \n\t ")])
+            .classes()
+            .is_empty()
+    );
+    assert!(element_with_attrs(&[]).classes().is_empty());
+}
+
+// ========== id ==========
+
+#[test]
+fn test_id_is_exact() {
+    let element = element_with_attrs(&[("id", "Main")]);
+    assert_eq!(element.id().map(String::as_str), Some("Main"));
+    assert_ne!(element.id().map(String::as_str), Some("main"));
+}
+
+// ========== AttributesMap ==========
+
+#[test]
+fn test_attributes_iterate_in_insertion_order() {
+    let element = element_with_attrs(&[("href", "x"), ("id", "y"), ("class", "z")]);
+    let names: Vec<&str> = element.attrs.keys().map(String::as_str).collect();
+    assert_eq!(names, ["href", "id", "class"]);
+}
+
+#[test]
+fn test_attributes_insert_existing_keeps_position() {
+    let mut attrs: AttributesMap = [("a", "1"), ("b", "2"), ("c", "3")]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    assert_eq!(
+        attrs.insert("b".to_string(), "20".to_string()),
+        Some("2".to_string())
+    );
+    assert_eq!(attrs.insert("d".to_string(), "4".to_string()), None);
+    let pairs: Vec<(&str, &str)> = attrs
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    assert_eq!(pairs, [("a", "1"), ("b", "20"), ("c", "3"), ("d", "4")]);
+}
+
+#[test]
+fn test_attributes_remove() {
+    let mut attrs = element_with_attrs(&[("a", "1"), ("b", "2"), ("c", "3")]).attrs;
+    assert_eq!(attrs.remove("b"), Some("2".to_string()));
+    assert_eq!(attrs.remove("b"), None);
+    assert!(!attrs.contains_key("b"));
+    assert_eq!(attrs.len(), 2);
+    let names: Vec<&str> = attrs.keys().map(String::as_str).collect();
+    assert_eq!(names, ["a", "c"]);
+}
@@ -0,0 +1,149 @@
+//! Tests for traversal helpers: depth, path_to_root, element-only navigation,
+//! and collections of elements by class and tag name.
+
+#![allow(clippy::default_trait_access, clippy::doc_markdown)]
+
+use koala_dom::{DomTree, ElementData, NodeId, NodeType};
+
+/// Helper to create an element node and return its NodeId.
+fn alloc_element(tree: &mut DomTree, tag: &str) -> NodeId {
+    tree.alloc(NodeType::Element(ElementData {
+        tag_name: tag.to_string(),
+        attrs: Default::default(),
+    }))
+}
+
+/// Build Document > html > body > div > p and return (html, body, div, p).
+fn nested_tree(tree: &mut DomTree) -> (NodeId, NodeId, NodeId, NodeId) {
+    let html = alloc_element(tree, "html");
+    tree.append_child(NodeId::ROOT, html);
+    let body = alloc_element(tree, "body");
+    tree.append_child(html, body);
+    let div = alloc_element(tree, "div");
+    tree.append_child(body, div);
+    let p = alloc_element(tree, "p");
+    tree.append_child(div, p);
+    (html, body, div, p)
+}
+
+#[test]
+fn test_depth_of_nested_node() {
+    let mut tree = DomTree::new();
+    let (html, body, _, p) = nested_tree(&mut tree);
+
+    assert_eq!(tree.depth(NodeId::ROOT), 0);
+    assert_eq!(tree.depth(html), 1);
+    assert_eq!(tree.depth(body), 2);
+    assert_eq!(tree.depth(p), 4);
+}
+
+#[test]
+fn test_depth_of_detached_node() {
+    let mut tree = DomTree::new();
+    let orphan = alloc_element(&mut tree, "span");
+    assert_eq!(tree.depth(orphan), 0);
+}
+
+#[test]
+fn test_path_to_root_order() {
+    let mut tree = DomTree::new();
+    let (html, body, div, p) = nested_tree(&mut tree);
+
+    assert_eq!(tree.path_to_root(p), [p, div, body, html, NodeId::ROOT]);
+    assert_eq!(tree.path_to_root(NodeId::ROOT), [NodeId::ROOT]);
+}
+
+#[test]
+fn test_element_only_navigation_skips_text_and_comments() {
+    // <ul>" "<li/>"text"<!--c--><li/>" "</ul>
+    let mut tree = DomTree::new();
+    let ul = alloc_element(&mut tree, "ul");
+    tree.append_child(NodeId::ROOT, ul);
+    let leading = tree.alloc(NodeType::Text(" ".to_string()));
+    let first = alloc_element(&mut tree, "li");
+    let text = tree.alloc(NodeType::Text("text".to_string()));
+    let comment = tree.alloc(NodeType::Comment("c".to_string()));
+    let second = alloc_element(&mut tree, "li");
+    let trailing = tree.alloc(NodeType::Text(" ".to_string()));
+    for child in [leading, first, text, comment, second, trailing] {
+        tree.append_child(ul, child);
+    }
+
+    assert_eq!(
+        tree.element_children(ul).collect::<Vec<_>>(),
+        [first, second]
+    );
+    assert_eq!(tree.first_element_child(ul), Some(first));
+    assert_eq!(tree.last_element_child(ul), Some(second));
+    assert_eq!(tree.next_element_sibling(first), Some(second));
+    assert_eq!(tree.previous_element_sibling(second), Some(first));
+    assert_eq!(tree.next_element_sibling(second), None);
+    assert_eq!(tree.previous_element_sibling(first), None);
+    assert_eq!(tree.first_element_child(first), None);
+}
+
+// ========== get_elements_by_class_name / get_elements_by_tag_name ==========
+
+/// Helper to create an element with a `class` attribute.
+fn alloc_element_with_class(tree: &mut DomTree, tag: &str, class: &str) -> NodeId {
+    let id = alloc_element(tree, tag);
+    let _ = tree
+        .as_element_mut(id)
+        .unwrap()
+        .attrs
+        .insert("class".to_string(), class.to_string());
+    id
+}
+
+#[test]
+fn test_get_elements_by_class_name_matches_elements_with_several_classes() {
+    let mut tree = DomTree::new();
+    let (_, body, div, _) = nested_tree(&mut tree);
+    let first = alloc_element_with_class(&mut tree, "span", "note  warning");
+    tree.append_child(div, first);
+    let second = alloc_element_with_class(&mut tree, "em", "warning");
+    tree.append_child(body, second);
+    let other = alloc_element_with_class(&mut tree, "em", "Warning");
+    tree.append_child(body, other);
+
+    assert_eq!(
+        tree.get_elements_by_class_name("warning"),
+        vec![first, second]
+    );
+    assert_eq!(tree.get_elements_by_class_name("note"), vec![first]);
+}
+
+#[test]
+fn test_get_elements_by_class_name_requires_every_listed_class() {
+    let mut tree = DomTree::new();
+    let (_, body, _, _) = nested_tree(&mut tree);
+    let both = alloc_element_with_class(&mut tree, "p", "a b c");
+    tree.append_child(body, both);
+    let one = alloc_element_with_class(&mut tree, "p", "a");
+    tree.append_child(body, one);
+
+    assert_eq!(tree.get_elements_by_class_name(" b\ta "), vec![both]);
+    assert!(tree.get_elements_by_class_name("   ").is_empty());
+}
+
+#[test]
+fn test_get_elements_by_tag_name_is_case_insensitive() {
+    let mut tree = DomTree::new();
+    let (_, body, div, _) = nested_tree(&mut tree);
+    let upper = alloc_element(&mut tree, "DIV");
+    tree.append_child(body, upper);
+
+    assert_eq!(tree.get_elements_by_tag_name("div"), vec![div, upper]);
+    assert_eq!(tree.get_elements_by_tag_name("Div"), vec![div, upper]);
+    assert!(tree.get_elements_by_tag_name("section").is_empty());
+}
+
+#[test]
+fn test_get_elements_by_tag_name_wildcard_returns_all_elements() {
+    let mut tree = DomTree::new();
+    let (html, body, div, p) = nested_tree(&mut tree);
+    let text = tree.alloc(NodeType::Text("hi".to_string()));
+    tree.append_child(p, text);
+
+    assert_eq!(tree.get_elements_by_tag_name("*"), vec![html, body, div, p]);
+}
@@ -4,7 +4,7 @@
 //!
 //! This crate implements:
 //! - **HTML Tokenizer** ([WHATWG § 13.2.5](https://html.spec.whatwg.org/multipage/parsing.html#tokenization))
-//!   - Data, RCDATA, RAWTEXT, and tag states
+//!   - Data, RCDATA, RAWTEXT, PLAINTEXT, and tag states
 //!   - DOCTYPE, comment, and character reference handling
 //!   - Attribute parsing
 //!
@@ -12,6 +12,8 @@
 //!   - Insertion modes: `Initial`, `BeforeHtml`, `BeforeHead`, `InHead`, `AfterHead`, `InBody`, `Text`, `AfterBody`, `AfterAfterBody`
 //!   - Implicit tag handling and stack of open elements
 //!
+//! - **HTML Serializer** ([WHATWG § 13.3](https://html.spec.whatwg.org/multipage/parsing.html#serialising-html-fragments))
+//!
 //! # Not Yet Implemented
 //!
 //! - Script data states
@@ -23,8 +25,11 @@
 
 /// HTML parser and tree construction.
 pub mod parser;
+/// HTML fragment serialization.
+pub mod serializer;
 /// HTML tokenizer for converting input into tokens.
 pub mod tokenizer;
 
-pub use parser::{HTMLParser, InsertionMode, ParseIssue, print_tree};
+pub use parser::{HTMLParser, InsertionMode, ModeTransition, ParseIssue, print_tree, write_tree};
+pub use serializer::serialize_html;
 pub use tokenizer::{Attribute, HTMLTokenizer, Token};
@@ -169,8 +169,20 @@ pub struct HTMLParser {
     /// "The form element pointer points to the last form element that was opened
     /// and whose end tag has not yet been seen."
     form_element_pointer: Option<NodeId>,
+
+    /// Insertion-mode transitions, recorded only while tracing (see
+    /// [`HTMLParser::run_with_mode_trace`]).
+    mode_trace: Option<Vec<ModeTransition>>,
 }
 
+/// A point during tree construction where the parser began handling tokens
+/// in a new insertion mode: the index of the first token dispatched in
+/// that mode, and the mode itself.
+///
+/// A token that is reprocessed in several modes produces one transition
+/// per mode, all with the same token index.
+pub type ModeTransition = (usize, InsertionMode);
+
 impl HTMLParser {
     /// Create a new parser from a token stream.
     #[must_use]
@@ -191,6 +203,7 @@ impl HTMLParser {
             foster_parenting: false,
             pending_table_character_tokens: Vec::new(),
             form_element_pointer: None,
+            mode_trace: None,
         }
     }
 
@@ -201,6 +214,15 @@ impl HTMLParser {
         self
     }
 
+    /// Seed the issue list with parse errors recorded by the tokenizer
+    /// (see [`HTMLTokenizer::into_tokens_and_issues`](crate::HTMLTokenizer::into_tokens_and_issues)),
+    /// so that [`Self::run_with_issues`] reports both stages in token order.
+    #[must_use]
+    pub fn with_tokenizer_issues(mut self, issues: Vec<ParseIssue>) -> Self {
+        self.issues = issues;
+        self
+    }
+
     /// Get all parse issues (errors and warnings) encountered during parsing.
     #[must_use]
     pub fn get_issues(&self) -> &[ParseIssue] {
@@ -220,6 +242,31 @@ impl HTMLParser {
         });
     }
 
+    /// [§ 13.2.2 Parse errors](https://html.spec.whatwg.org/multipage/parsing.html#parse-errors)
+    ///
+    /// Record a parse error at the current token.
+    fn parse_error(&mut self, message: &str) {
+        warn_once("HTML Parser", message);
+        self.issues.push(ParseIssue {
+            message: message.to_string(),
+            token_index: self.token_index,
+            is_error: true,
+        });
+    }
+
+    /// [§ 13.2.7 The end](https://html.spec.whatwg.org/multipage/parsing.html#stop-parsing)
+    ///
+    /// "Once the user agent stops parsing the document, the user agent must
+    /// run the following steps:"
+    ///
+    /// NOTE: Only the tree-affecting step is implemented; scripts, events
+    /// and the document's readiness are outside the parser's scope.
+    fn stop_parsing(&mut self) {
+        // STEP 4: "Pop all the nodes off the stack of open elements."
+        self.stack_of_open_elements.clear();
+        self.stopped = true;
+    }
+
     /// Run the parser and return the DOM tree.
     ///
     /// The returned `DomTree` preserves parent/sibling relationships
@@ -232,11 +279,7 @@ impl HTMLParser {
     /// `InTemplate`, `InFrameset`).
     #[must_use]
     pub fn run(mut self) -> DomTree {
-        while !self.stopped && self.token_index < self.tokens.len() {
-            let token = self.tokens[self.token_index].clone();
-            self.process_token(&token);
-            self.token_index += 1;
-        }
+        self.process_all_tokens();
         self.tree
     }
 
@@ -249,13 +292,95 @@ impl HTMLParser {
     /// `InTemplate`, `InFrameset`).
     #[must_use]
     pub fn run_with_issues(mut self) -> (DomTree, Vec<ParseIssue>) {
+        self.process_all_tokens();
+        let issues = self.take_sorted_issues();
+        (self.tree, issues)
+    }
+
+    /// Run the parser like [`Self::run_with_issues`], additionally recording
+    /// every insertion-mode transition for debugging tree construction.
+    ///
+    /// The trace starts with `(0, InsertionMode::Initial)` and has one entry
+    /// each time a token is dispatched in a mode different from the
+    /// previous one (see [`ModeTransition`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parser encounters an unimplemented insertion mode
+    /// (e.g., `InColumnGroup`, `InTemplate`, `InFrameset`).
+    #[must_use]
+    pub fn run_with_mode_trace(mut self) -> (DomTree, Vec<ParseIssue>, Vec<ModeTransition>) {
+        self.mode_trace = Some(Vec::new());
+        self.process_all_tokens();
+        let issues = self.take_sorted_issues();
+        let trace = self.mode_trace.take().unwrap_or_default();
+        (self.tree, issues, trace)
+    }
+
+    /// Create a parser for incremental use: instead of handing over the
+    /// whole token stream up front, push tokens one at a time with
+    /// [`Self::feed_token`] and collect the result with [`Self::finish`].
+    ///
+    /// This lets tree construction run alongside tokenization, and is
+    /// equivalent to [`Self::new`] followed by [`Self::run_with_issues`].
+    #[must_use]
+    pub fn streaming() -> Self {
+        Self::new(Vec::new())
+    }
+
+    /// [§ 13.2.6 Tree construction](https://html.spec.whatwg.org/multipage/parsing.html#tree-construction)
+    ///
+    /// "As each token is emitted from the tokenizer, the user agent must
+    /// follow the appropriate steps from the following list, known as the
+    /// tree construction dispatcher"
+    ///
+    /// Tokens fed after parsing has stopped (after the end-of-file token)
+    /// are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parser encounters an unimplemented insertion mode.
+    #[allow(clippy::needless_pass_by_value)] // tokens are handed over as the tokenizer emits them
+    pub fn feed_token(&mut self, token: Token) {
+        if self.stopped {
+            return;
+        }
+        self.process_token(&token);
+        self.token_index += 1;
+    }
+
+    /// Finish a [streaming](Self::streaming) parse and return the `DomTree`
+    /// and any parse issues, as [`Self::run_with_issues`] does.
+    ///
+    /// If the end-of-file token was never fed, it is processed here so the
+    /// tree is completed (implied end tags, the stack of open elements
+    /// popped) exactly as in the batch path.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the parser encounters an unimplemented insertion mode.
+    #[must_use]
+    pub fn finish(mut self) -> (DomTree, Vec<ParseIssue>) {
+        self.process_all_tokens();
+        self.feed_token(Token::EndOfFile);
+        let issues = self.take_sorted_issues();
+        (self.tree, issues)
+    }
+
+    fn process_all_tokens(&mut self) {
         while !self.stopped && self.token_index < self.tokens.len() {
             let token = self.tokens[self.token_index].clone();
             self.process_token(&token);
             self.token_index += 1;
         }
-        let issues = std::mem::take(&mut self.issues);
-        (self.tree, issues)
+    }
+
+    fn take_sorted_issues(&mut self) -> Vec<ParseIssue> {
+        let mut issues = std::mem::take(&mut self.issues);
+        // Tokenizer and tree construction issues were recorded separately;
+        // present them in the order their tokens were encountered.
+        issues.sort_by_key(|issue| issue.token_index);
+        issues
     }
 
     /// [§ 13.2.6 Tree construction](https://html.spec.whatwg.org/multipage/parsing.html#tree-construction-dispatcher)
@@ -264,6 +389,14 @@ impl HTMLParser {
     ///
     /// Panics if the parser encounters an unimplemented insertion mode.
     fn process_token(&mut self, token: &Token) {
+        if let Some(trace) = self.mode_trace.as_mut()
+            && trace
+                .last()
+                .is_none_or(|&(_, mode)| mode != self.insertion_mode)
+        {
+            trace.push((self.token_index, self.insertion_mode));
+        }
+
         match self.insertion_mode {
             InsertionMode::Initial => self.handle_initial_mode(token),
             InsertionMode::BeforeHtml => self.handle_before_html_mode(token),
@@ -300,7 +433,9 @@ impl HTMLParser {
 
             // STEP 3: InCaption mode - handles content inside <caption>
             //   [§ 13.2.6.4.11](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-incaption)
-            InsertionMode::InCaption => todo!("InCaption mode - see STEP 3 above"),
+            //   - Most tokens use InBody rules
+            //   - </caption> or a table-structure tag closes the caption
+            InsertionMode::InCaption => self.handle_in_caption_mode(token),
 
             // STEP 4: InColumnGroup mode - handles <col> elements
             //   [§ 13.2.6.4.12](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-incolumngroup)
@@ -473,6 +608,32 @@ impl HTMLParser {
         }))
     }
 
+    /// [§ 13.2.6.4.7 The "in body" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody)
+    ///
+    /// "for each attribute on the token, check to see if the attribute is
+    /// already present on [the element]. If it is not, add the attribute and
+    /// its corresponding value to that element."
+    ///
+    /// Used for the duplicate `<html>` and `<body>` start tags, whose
+    /// attributes merge onto the existing element.
+    fn merge_attributes(&mut self, element: NodeId, attributes: &[Attribute]) {
+        let Some(data) = self.tree.as_element_mut(element) else {
+            return;
+        };
+        for attr in attributes {
+            if !data.attrs.contains_key(&attr.name) {
+                let _ = data.attrs.insert(attr.name.clone(), attr.value.clone());
+            }
+        }
+    }
+
+    /// Whether there is a `template` element on the stack of open elements.
+    fn has_template_on_stack(&self) -> bool {
+        self.stack_of_open_elements
+            .iter()
+            .any(|&id| self.get_tag_name(id) == Some("template"))
+    }
+
     /// [§ 13.2.6.1 Insert a character](https://html.spec.whatwg.org/multipage/parsing.html#insert-a-character)
     ///
     /// Create a Text node with the given data.
@@ -1686,9 +1847,27 @@ impl HTMLParser {
             // then there is a parse error."
             // ...
             // "Then, switch the insertion mode to "before html"."
-            Token::Doctype { .. } => {
-                // NOTE: We skip creating a DocumentType node for simplicity.
-                // The full spec requires appending a DocumentType node to the Document.
+            //
+            // "Append a DocumentType node to the Document node, with its name set to the
+            // name given in the DOCTYPE token, or the empty string if the name was missing;
+            // its public ID set to the public identifier given in the DOCTYPE token, or the
+            // empty string if the public identifier was missing; and its system ID set to
+            // the system identifier given in the DOCTYPE token, or the empty string if the
+            // system identifier was missing."
+            //
+            // NOTE: The parse error and quirks-mode checks are not implemented.
+            Token::Doctype {
+                name,
+                public_identifier,
+                system_identifier,
+                ..
+            } => {
+                let doctype_id = self.tree.alloc(NodeType::DocumentType {
+                    name: name.clone().unwrap_or_default(),
+                    public_id: public_identifier.clone().unwrap_or_default(),
+                    system_id: system_identifier.clone().unwrap_or_default(),
+                });
+                self.append_child(NodeId::ROOT, doctype_id);
                 self.insertion_mode = InsertionMode::BeforeHtml;
             }
 
@@ -2104,12 +2283,12 @@ impl HTMLParser {
             // "Pop the current node off the stack of open elements."
             // "Switch the insertion mode to the original insertion mode and reprocess the token."
             Token::EndOfFile => {
-                // Parse error (logged implicitly)
+                self.parse_error("unexpected end of file in text content");
                 let _ = self.stack_of_open_elements.pop();
                 self.insertion_mode = self
                     .original_insertion_mode
                     .unwrap_or(InsertionMode::InBody);
-                // NOTE: Spec says to reprocess, but EOF is terminal so we just switch mode.
+                self.reprocess_token(token);
             }
 
             // "An end tag whose tag name is "script""
@@ -2166,6 +2345,26 @@ impl HTMLParser {
                 self.insertion_mode = InsertionMode::InBody;
             }
 
+            // "A start tag whose tag name is one of: "base", "basefont", "bgsound", "link",
+            // "meta", "noframes", "script", "style", "template", "title""
+            Token::StartTag { name, .. }
+                if matches!(
+                    name.as_str(),
+                    "base"
+                        | "basefont"
+                        | "bgsound"
+                        | "link"
+                        | "meta"
+                        | "noframes"
+                        | "script"
+                        | "style"
+                        | "template"
+                        | "title"
+                ) =>
+            {
+                self.handle_after_head_head_element(token);
+            }
+
             // "A start tag whose tag name is "head""
             // "Parse error. Ignore the token."
             Token::StartTag { name, .. } if name == "head" => {}
@@ -2176,6 +2375,12 @@ impl HTMLParser {
                 self.handle_after_head_anything_else(token);
             }
 
+            // "An end tag whose tag name is "template""
+            // "Process the token using the rules for the "in head" insertion mode."
+            Token::EndTag { name, .. } if name == "template" => {
+                self.handle_in_head_mode(token);
+            }
+
             // "A DOCTYPE token"
             // "Parse error. Ignore the token."
             //
@@ -2190,6 +2395,45 @@ impl HTMLParser {
         }
     }
 
+    /// [§ 13.2.6.4.6 The "after head" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#the-after-head-insertion-mode)
+    ///
+    /// A head-only start tag (`<link>`, `<meta>`, `<script>`, ...) after `</head>`:
+    /// "Parse error."
+    /// "Push the node pointed to by the head element pointer onto the stack of open elements."
+    /// "Process the token using the rules for the "in head" insertion mode."
+    /// "Remove the node pointed to by the head element pointer from the stack of open
+    /// elements. (It might not be the current node at this point.)"
+    ///
+    /// NOTE: For `<script>`, `<style>` and `<title>` the element itself is still open when
+    /// the head is removed; the "text" insertion mode pops it and returns to "after head".
+    fn handle_after_head_head_element(&mut self, token: &Token) {
+        self.parse_error("head-only element after </head>");
+
+        // The head element pointer is always set by the time "after head" is reached:
+        // both "before head" paths insert a head element.
+        let Some(head_idx) = self.head_element_pointer else {
+            self.handle_after_head_anything_else(token);
+            return;
+        };
+
+        // STEP 1: "Push the node pointed to by the head element pointer onto the stack
+        // of open elements."
+        self.stack_of_open_elements.push(head_idx);
+
+        // STEP 2: "Process the token using the rules for the "in head" insertion mode."
+        self.handle_in_head_mode(token);
+
+        // STEP 3: "Remove the node pointed to by the head element pointer from the
+        // stack of open elements."
+        if let Some(pos) = self
+            .stack_of_open_elements
+            .iter()
+            .rposition(|&id| id == head_idx)
+        {
+            let _ = self.stack_of_open_elements.remove(pos);
+        }
+    }
+
     /// [§ 13.2.6.4.6 The "after head" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#the-after-head-insertion-mode)
     ///
     /// "Anything else":
@@ -2277,8 +2521,44 @@ impl HTMLParser {
             // "Otherwise, for each attribute on the token, check to see if the attribute is already
             // present on the top element of the stack of open elements. If it is not, add the
             // attribute and its corresponding value to that element."
-            Token::StartTag { name, .. } if name == "html" => {
-                // Parse error. Simplified: ignore attribute merging.
+            Token::StartTag {
+                name, attributes, ..
+            } if name == "html" => {
+                self.parse_error("unexpected html start tag");
+                if !self.has_template_on_stack() {
+                    // NOTE: The spec's "top element" of the stack is the
+                    // first one pushed, i.e. the html element.
+                    if let Some(&html) = self.stack_of_open_elements.first() {
+                        self.merge_attributes(html, attributes);
+                    }
+                }
+            }
+
+            // "A start tag whose tag name is "body""
+            // "Parse error."
+            // "If the stack of open elements has only one node on it, if the second element on
+            // the stack of open elements is not a body element, or if there is a template
+            // element on the stack of open elements, then ignore the token. (fragment case or
+            // there is a template element on the stack)"
+            // "Otherwise, set the frameset-ok flag to "not ok"; then, for each attribute on the
+            // token, check to see if the attribute is already present on the body element (the
+            // second element) on the stack of open elements, and if it is not, add the attribute
+            // and its corresponding value to that element."
+            Token::StartTag {
+                name, attributes, ..
+            } if name == "body" => {
+                self.parse_error("unexpected body start tag");
+                let body = self
+                    .stack_of_open_elements
+                    .get(1)
+                    .copied()
+                    .filter(|&id| self.get_tag_name(id) == Some("body"));
+                if let Some(body) = body
+                    && !self.has_template_on_stack()
+                {
+                    // TODO: self.frameset_ok = false;
+                    self.merge_attributes(body, attributes);
+                }
             }
 
             // "A start tag whose tag name is one of: "address", "article", "aside", "blockquote",
@@ -2687,6 +2967,37 @@ impl HTMLParser {
                 self.insertion_mode = InsertionMode::Text;
             }
 
+            // [§ 13.2.6.4.7 "in body" - Start tag "xmp"](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody)
+            // "A start tag whose tag name is "xmp""
+            // "If the stack of open elements has a p element in button scope, then close a p element."
+            // "Reconstruct the active formatting elements, if any."
+            // "Set the frameset-ok flag to "not ok"."
+            // "Follow the generic raw text element parsing algorithm."
+            // NOTE: Tokenizer state switching handled by tokenizer based on tag name.
+            Token::StartTag { name, .. } if name == "xmp" => {
+                self.close_element_if_in_scope("p");
+                self.reconstruct_active_formatting_elements();
+                let _ = self.insert_html_element(token);
+                self.original_insertion_mode = Some(self.insertion_mode);
+                self.insertion_mode = InsertionMode::Text;
+            }
+
+            // [§ 13.2.6.4.7 "in body" - Start tag "plaintext"](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody)
+            // "A start tag whose tag name is "plaintext""
+            // "If the stack of open elements has a p element in button scope, then close a p element."
+            // "Insert an HTML element for the token."
+            // "Switch the tokenizer to the PLAINTEXT state."
+            //
+            // NOTE: Once a start tag token with the tag name "plaintext" has been
+            // seen, that will be the last token ever seen other than character
+            // tokens (and the end-of-file token), because there is no way to
+            // switch out of the PLAINTEXT state. The tokenizer makes the switch
+            // based on the tag name.
+            Token::StartTag { name, .. } if name == "plaintext" => {
+                self.close_element_if_in_scope("p");
+                let _ = self.insert_html_element(token);
+            }
+
             // [§ 13.2.6.4.7 "in body" - Start tag "textarea"](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody)
             // "A start tag whose tag name is "textarea""
             // "Insert an HTML element for the token."
@@ -3137,7 +3448,34 @@ impl HTMLParser {
             //     then this is a parse error."
             // 2. "Stop parsing."
             Token::EndOfFile => {
-                self.stopped = true;
+                let has_unclosed_element = self.stack_of_open_elements.iter().any(|&id| {
+                    !matches!(
+                        self.get_tag_name(id),
+                        Some(
+                            "dd" | "dt"
+                                | "li"
+                                | "optgroup"
+                                | "option"
+                                | "p"
+                                | "rb"
+                                | "rp"
+                                | "rt"
+                                | "rtc"
+                                | "tbody"
+                                | "td"
+                                | "tfoot"
+                                | "th"
+                                | "thead"
+                                | "tr"
+                                | "body"
+                                | "html"
+                        )
+                    )
+                });
+                if has_unclosed_element {
+                    self.parse_error("unexpected end of file with unclosed elements");
+                }
+                self.stop_parsing();
             }
 
             // ===== FOREIGN CONTENT (SVG and MathML) =====
@@ -3464,11 +3802,7 @@ impl HTMLParser {
             //    pointer to point to the element created."
             //   "Pop that form element off the stack of open elements."
             Token::StartTag { name, .. } if name == "form" => {
-                let has_template = self
-                    .stack_of_open_elements
-                    .iter()
-                    .any(|&id| self.get_tag_name(id) == Some("template"));
-                if has_template || self.form_element_pointer.is_some() {
+                if self.has_template_on_stack() || self.form_element_pointer.is_some() {
                     // Parse error. Ignore the token.
                 } else {
                     let form_id = self.insert_html_element(token);
@@ -3537,15 +3871,19 @@ impl HTMLParser {
                             }
                         }
                     } else {
-                        // "Otherwise, this is a parse error. Enable foster
-                        //  parenting, process the pending table character tokens
-                        //  using the rules for the "in body" insertion mode, and
-                        //  disable foster parenting."
-                        self.foster_parenting = true;
+                        // "If any of the tokens in the pending table character
+                        //  tokens list are character tokens that are not ASCII
+                        //  whitespace, then this is a parse error: reprocess the
+                        //  character tokens in the pending table character
+                        //  tokens list using the rules given in the "anything
+                        //  else" entry in the "in table" insertion mode."
+                        //
+                        // NOTE: The whole list is foster parented, including
+                        // any whitespace around the text.
+                        self.parse_error("non-whitespace text in table context");
                         for tok in &pending {
-                            self.handle_in_body_mode(tok);
+                            self.handle_in_table_anything_else(tok);
                         }
-                        self.foster_parenting = false;
                     }
                 }
 
@@ -3559,6 +3897,112 @@ impl HTMLParser {
         }
     }
 
+    /// [§ 13.2.6.4.11 The "in caption" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-incaption)
+    fn handle_in_caption_mode(&mut self, token: &Token) {
+        match token {
+            // "An end tag whose tag name is "caption""
+            // "If the stack of open elements does not have a caption element
+            //  in table scope, this is a parse error; ignore the token.
+            //  (fragment case)"
+            // "Otherwise:"
+            //   "Generate implied end tags."
+            //   "Now, if the current node is not a caption element, then this
+            //    is a parse error."
+            //   "Pop elements from this stack until a caption element has
+            //    been popped from the stack."
+            //   "Clear the list of active formatting elements up to the last
+            //    marker."
+            //   "Switch the insertion mode to "in table"."
+            Token::EndTag { name, .. } if name == "caption" => {
+                let _ = self.close_the_caption();
+            }
+
+            // "A start tag whose tag name is one of: "caption", "col",
+            //  "colgroup", "tbody", "td", "tfoot", "th", "thead", "tr""
+            // "An end tag whose tag name is "table""
+            // "If the stack of open elements does not have a caption element
+            //  in table scope, this is a parse error; ignore the token.
+            //  (fragment case)"
+            // "Otherwise: [close the caption as above], then reprocess the
+            //  token."
+            Token::StartTag { name, .. }
+                if matches!(
+                    name.as_str(),
+                    "caption"
+                        | "col"
+                        | "colgroup"
+                        | "tbody"
+                        | "td"
+                        | "tfoot"
+                        | "th"
+                        | "thead"
+                        | "tr"
+                ) =>
+            {
+                if self.close_the_caption() {
+                    self.reprocess_token(token);
+                }
+            }
+            Token::EndTag { name, .. } if name == "table" => {
+                if self.close_the_caption() {
+                    self.reprocess_token(token);
+                }
+            }
+
+            // "An end tag whose tag name is one of: "body", "col", "colgroup",
+            //  "html", "tbody", "td", "tfoot", "th", "thead", "tr""
+            // "Parse error. Ignore the token."
+            Token::EndTag { name, .. }
+                if matches!(
+                    name.as_str(),
+                    "body"
+                        | "col"
+                        | "colgroup"
+                        | "html"
+                        | "tbody"
+                        | "td"
+                        | "tfoot"
+                        | "th"
+                        | "thead"
+                        | "tr"
+                ) => {}
+
+            // "Anything else"
+            // "Process the token using the rules for the "in body" insertion mode."
+            _ => {
+                self.handle_in_body_mode(token);
+            }
+        }
+    }
+
+    /// [§ 13.2.6.4.11](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-incaption)
+    ///
+    /// The steps shared by the "in caption" tokens that close the caption.
+    /// Returns `false` (and does nothing) when there is no caption element
+    /// in table scope, in which case the token is ignored.
+    fn close_the_caption(&mut self) -> bool {
+        if !self.has_element_in_table_scope("caption") {
+            return false;
+        }
+        // "Generate implied end tags."
+        self.generate_implied_end_tags();
+        // "Now, if the current node is not a caption element, then this is
+        //  a parse error."
+        if self
+            .current_node()
+            .and_then(|id| self.get_tag_name(id))
+            .is_some_and(|tag| tag != "caption")
+        {
+            self.parse_error("caption closed with unclosed elements");
+        }
+        // "Pop elements from this stack until a caption element has been
+        //  popped from the stack."
+        self.pop_until_tag("caption");
+        self.clear_active_formatting_elements_to_last_marker();
+        self.insertion_mode = InsertionMode::InTable;
+        true
+    }
+
     /// [§ 13.2.6.4.13 The "in table body" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-intablebody)
     fn handle_in_table_body_mode(&mut self, token: &Token) {
         match token {
@@ -3911,7 +4355,7 @@ impl HTMLParser {
             // "An end-of-file token"
             // "Stop parsing."
             Token::EndOfFile => {
-                self.stopped = true;
+                self.stop_parsing();
             }
 
             // "Anything else"
@@ -3952,7 +4396,7 @@ impl HTMLParser {
             // "An end-of-file token"
             // "Stop parsing."
             Token::EndOfFile => {
-                self.stopped = true;
+                self.stop_parsing();
             }
 
             // "Anything else"
@@ -3966,16 +4410,42 @@ impl HTMLParser {
 }
 
 /// Print a DOM tree for debugging.
+///
+/// `id` is printed at `indent` levels of indentation and each descendant
+/// one level further per [`DomTree::depth`] below `id`. See [`write_tree`]
+/// to write the tree somewhere other than stdout.
+///
+/// # Panics
+///
+/// Panics if writing to stdout fails, like `println!`.
 pub fn print_tree(tree: &DomTree, id: NodeId, indent: usize) {
-    let prefix = "  ".repeat(indent);
-    if let Some(node) = tree.get(id) {
+    write_tree(&mut std::io::stdout().lock(), tree, id, indent).expect("failed printing to stdout");
+}
+
+/// Write a DOM tree in the format of [`print_tree`] to `w`.
+///
+/// # Errors
+///
+/// Returns the first error `w` reports.
+pub fn write_tree<W: std::io::Write>(
+    w: &mut W,
+    tree: &DomTree,
+    id: NodeId,
+    indent: usize,
+) -> std::io::Result<()> {
+    let base_depth = tree.depth(id);
+    for node_id in std::iter::once(id).chain(tree.descendants(id)) {
+        let Some(node) = tree.get(node_id) else {
+            continue;
+        };
+        let prefix = "  ".repeat(indent + tree.depth(node_id) - base_depth);
         match &node.node_type {
             NodeType::Document => {
-                println!("{prefix}Document");
+                writeln!(w, "{prefix}Document")?;
             }
             NodeType::Element(data) => {
                 if data.attrs.is_empty() {
-                    println!("{prefix}<{}>", data.tag_name);
+                    writeln!(w, "{prefix}<{}>", data.tag_name)?;
                 } else {
                     let attrs: Vec<String> = data
                         .attrs
@@ -3988,19 +4458,20 @@ pub fn print_tree(tree: &DomTree, id: NodeId, indent: usize) {
                             }
                         })
                         .collect();
-                    println!("{prefix}<{} {}>", data.tag_name, attrs.join(" "));
+                    writeln!(w, "{prefix}<{} {}>", data.tag_name, attrs.join(" "))?;
                 }
             }
             NodeType::Text(data) => {
                 let display = data.replace('\n', "\\n").replace(' ', "\u{00B7}");
-                println!("{prefix}\"{display}\"");
+                writeln!(w, "{prefix}\"{display}\"")?;
             }
             NodeType::Comment(data) => {
-                println!("{prefix}<!-- {data} -->");
+                writeln!(w, "{prefix}<!-- {data} -->")?;
+            }
+            NodeType::DocumentType { name, .. } => {
+                writeln!(w, "{prefix}<!DOCTYPE {name}>")?;
             }
-        }
-        for &child_id in tree.children(id) {
-            print_tree(tree, child_id, indent + 1);
         }
     }
+    Ok(())
 }
@@ -6,4 +6,4 @@ pub mod foreign_content;
 /// HTML parser implementation.
 pub mod core;
 
-pub use core::{HTMLParser, InsertionMode, ParseIssue, print_tree};
+pub use core::{HTMLParser, InsertionMode, ModeTransition, ParseIssue, print_tree, write_tree};
@@ -0,0 +1,186 @@
+//! HTML fragment serialization.
+//!
+//! [§ 13.3 Serializing HTML fragments](https://html.spec.whatwg.org/multipage/parsing.html#serialising-html-fragments)
+
+use koala_dom::{DomTree, NodeId, NodeType};
+
+/// [§ 13.3 Serializing HTML fragments](https://html.spec.whatwg.org/multipage/parsing.html#serialising-html-fragments)
+///
+/// "The algorithm takes as input a DOM Element, Document, or
+/// `DocumentFragment` referred to as the node ... and returns a string."
+///
+/// Serializes the children of `node` (not `node` itself), the same
+/// string `innerHTML` would return. Attributes are emitted in the
+/// element's attribute-list order, so output is deterministic.
+#[must_use]
+pub fn serialize_html(tree: &DomTree, node: NodeId) -> String {
+    let mut out = String::new();
+    serialize_children(tree, node, &mut out);
+    out
+}
+
+fn serialize_children(tree: &DomTree, node: NodeId, out: &mut String) {
+    // "For each node current node in the list of children of node, in tree
+    // order, run the following steps:"
+    for &child in tree.children(node) {
+        let Some(current) = tree.get(child) else {
+            continue;
+        };
+        match &current.node_type {
+            // "If current node is an Element"
+            NodeType::Element(data) => {
+                // "Append a U+003C LESS-THAN SIGN character (<), followed by
+                // tagname."
+                out.push('<');
+                out.push_str(&data.tag_name);
+
+                // "For each attribute that the element has, append a U+0020
+                // SPACE character, the attribute's serialized name as
+                // described below, a U+003D EQUALS SIGN character (=), a
+                // U+0022 QUOTATION MARK character ("), the attribute's value,
+                // escaped as described below in attribute mode, and a second
+                // U+0022 QUOTATION MARK character (")."
+                for (name, value) in &data.attrs {
+                    out.push(' ');
+                    out.push_str(name);
+                    out.push_str("=\"");
+                    escape_string(value, true, out);
+                    out.push('"');
+                }
+
+                // "Append a U+003E GREATER-THAN SIGN character (>)."
+                out.push('>');
+
+                // "If current node serializes as void, then continue on to
+                // the next child node at this point."
+                if is_void_element(&data.tag_name) {
+                    continue;
+                }
+
+                // "Append the value of running the HTML fragment
+                // serialization algorithm on the current node element ...
+                // followed by a U+003C LESS-THAN SIGN character (<), a U+002F
+                // SOLIDUS character (/), tagname again, and finally a U+003E
+                // GREATER-THAN SIGN character (>)."
+                serialize_children(tree, child, out);
+                out.push_str("</");
+                out.push_str(&data.tag_name);
+                out.push('>');
+            }
+            // "If current node is a Text node"
+            NodeType::Text(text) => {
+                // "If the parent of current node is a style, script, xmp,
+                // iframe, noembed, noframes, or plaintext element, or if the
+                // parent of current node is a noscript element and scripting
+                // is enabled for the node, then append the value of current
+                // node's data IDL attribute literally."
+                //
+                // NOTE: Scripting is treated as disabled, so noscript
+                // content is escaped.
+                let literal = tree.as_element(node).is_some_and(|parent| {
+                    matches!(
+                        parent.tag_name.as_str(),
+                        "style"
+                            | "script"
+                            | "xmp"
+                            | "iframe"
+                            | "noembed"
+                            | "noframes"
+                            | "plaintext"
+                    )
+                });
+                if literal {
+                    out.push_str(text);
+                } else {
+                    // "Otherwise, append the value of current node's data
+                    // IDL attribute, escaped as described below."
+                    escape_string(text, false, out);
+                }
+            }
+            // "If current node is a Comment"
+            NodeType::Comment(data) => {
+                // "Append the literal string "<!--" (U+003C LESS-THAN SIGN,
+                // U+0021 EXCLAMATION MARK, U+002D HYPHEN-MINUS, U+002D
+                // HYPHEN-MINUS), followed by the value of current node's
+                // data IDL attribute, followed by the literal string "-->"
+                // (U+002D HYPHEN-MINUS, U+002D HYPHEN-MINUS, U+003E
+                // GREATER-THAN SIGN)."
+                out.push_str("<!--");
+                out.push_str(data);
+                out.push_str("-->");
+            }
+            // "If current node is a DocumentType"
+            NodeType::DocumentType { name, .. } => {
+                // "Append the literal string "<!DOCTYPE" (U+003C LESS-THAN
+                // SIGN, U+0021 EXCLAMATION MARK, U+0044 LATIN CAPITAL LETTER
+                // D, U+004F LATIN CAPITAL LETTER O, U+0043 LATIN CAPITAL
+                // LETTER C, U+0054 LATIN CAPITAL LETTER T, U+0059 LATIN
+                // CAPITAL LETTER Y, U+0050 LATIN CAPITAL LETTER P, U+0045
+                // LATIN CAPITAL LETTER E), followed by a space (U+0020
+                // SPACE), followed by the value of current node's name IDL
+                // attribute, followed by the literal string ">" (U+003E
+                // GREATER-THAN SIGN)."
+                out.push_str("<!DOCTYPE ");
+                out.push_str(name);
+                out.push('>');
+            }
+            NodeType::Document => {}
+        }
+    }
+}
+
+/// [§ 13.3](https://html.spec.whatwg.org/multipage/parsing.html#serializes-as-void)
+///
+/// "For the purposes of the following algorithm, an element serializes as
+/// void if its element type is one of the void elements, or is basefont,
+/// bgsound, frame, keygen, or param."
+fn is_void_element(tag_name: &str) -> bool {
+    matches!(
+        tag_name,
+        "area"
+            | "base"
+            | "br"
+            | "col"
+            | "embed"
+            | "hr"
+            | "img"
+            | "input"
+            | "link"
+            | "meta"
+            | "source"
+            | "track"
+            | "wbr"
+            | "basefont"
+            | "bgsound"
+            | "frame"
+            | "keygen"
+            | "param"
+    )
+}
+
+/// [§ 13.3](https://html.spec.whatwg.org/multipage/parsing.html#escapingString)
+///
+/// "Escaping a string (for the purposes of the algorithm above) consists of
+/// running the following steps:"
+fn escape_string(input: &str, attribute_mode: bool, out: &mut String) {
+    for c in input.chars() {
+        match c {
+            // "Replace any occurrence of the "&" character by the string
+            // "&amp;"."
+            '&' => out.push_str("&amp;"),
+            // "Replace any occurrences of the U+00A0 NO-BREAK SPACE character
+            // by the string "&nbsp;"."
+            '\u{00A0}' => out.push_str("&nbsp;"),
+            // "If the algorithm was invoked in the attribute mode, then
+            // replace any occurrences of the """ character by the string
+            // "&quot;"."
+            '"' if attribute_mode => out.push_str("&quot;"),
+            // "Replace any occurrences of the "<" character by the string
+            // "&lt;"." / "Replace any occurrences of the ">" character by
+            // the string "&gt;"."
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            _ => out.push(c),
+        }
+    }
+}
@@ -1,6 +1,9 @@
+use std::ops::Range;
+
 use strum_macros::Display;
 
 use super::token::Token;
+use crate::parser::ParseIssue;
 
 /// [§ 13.2.5 Tokenization](https://html.spec.whatwg.org/multipage/parsing.html#tokenization)
 ///
@@ -201,6 +204,28 @@ pub struct HTMLTokenizer {
     /// "Set the character reference code to zero (0)."
     /// Accumulates the code point value during decimal/hexadecimal character reference parsing.
     pub(super) character_reference_code: u32,
+
+    /// [§ 13.2.2 Parse errors](https://html.spec.whatwg.org/multipage/parsing.html#parse-errors)
+    ///
+    /// Parse errors recorded while tokenizing, for errors the tree builder
+    /// cannot detect on its own (e.g. input truncated mid-tag).
+    pub(super) issues: Vec<ParseIssue>,
+
+    /// Byte range of the input each emitted token came from, parallel to
+    /// `token_stream`. `None` unless enabled with
+    /// [`with_source_spans`](Self::with_source_spans).
+    pub(super) spans: Option<Vec<Range<usize>>>,
+
+    /// Where the next recorded span starts: the end of the previous one.
+    pub(super) span_start: usize,
+
+    /// [§ 13.2.4.1 The stack of open elements](https://html.spec.whatwg.org/multipage/parsing.html#adjusted-current-node)
+    ///
+    /// The elements opened since the outermost open `<svg>` or `<math>`,
+    /// each with whether it is a foreign element, so the markup
+    /// declaration open state can tell whether the adjusted current node
+    /// is in the HTML namespace. Empty outside foreign content.
+    pub(super) foreign_elements: Vec<(String, bool)>,
 }
 impl HTMLTokenizer {
     /// Create a new tokenizer for the given input.
@@ -226,9 +251,59 @@ impl HTMLTokenizer {
             last_start_tag_name: None,
             temporary_buffer: String::new(),
             character_reference_code: 0,
+            issues: Vec::new(),
+            spans: None,
+            span_start: 0,
+            foreign_elements: Vec::new(),
         }
     }
 
+    /// Record the source span of every emitted token, for mapping tokens
+    /// (and the nodes built from them) back to the input.
+    ///
+    /// Spans are byte ranges into the input and tile it: each one starts
+    /// where the previous one ended, so the markup between tokens (such
+    /// as the `<` and `>` of a tag) belongs to the token it introduces.
+    /// When one input character produces several tokens, as when `</`
+    /// is flushed back out of an RCDATA element, the first token gets
+    /// the whole range and the rest get empty ones.
+    ///
+    /// ```
+    /// use koala_html::HTMLTokenizer;
+    ///
+    /// let input = "<b>hi</b>";
+    /// let mut tokenizer = HTMLTokenizer::new(input.to_string()).with_source_spans();
+    /// tokenizer.run();
+    /// let (_, spans) = tokenizer.into_tokens_and_spans();
+    /// assert_eq!(&input[spans[0].clone()], "<b>");
+    /// ```
+    #[must_use]
+    pub fn with_source_spans(mut self) -> Self {
+        self.spans = Some(Vec::new());
+        self
+    }
+
+    /// Tokenize `input` in one call: `new`, [`run`](Self::run), then
+    /// [`into_tokens`](Self::into_tokens).
+    ///
+    /// Use the stateful API instead when the tokenizer's parse errors are
+    /// needed as well.
+    ///
+    /// ```
+    /// use koala_html::HTMLTokenizer;
+    ///
+    /// let input = "<p class=intro>Hello</p>";
+    /// let mut tokenizer = HTMLTokenizer::new(input.to_string());
+    /// tokenizer.run();
+    /// assert_eq!(HTMLTokenizer::tokenize(input), tokenizer.into_tokens());
+    /// ```
+    #[must_use]
+    pub fn tokenize(input: &str) -> Vec<Token> {
+        let mut tokenizer = Self::new(input.to_string());
+        tokenizer.run();
+        tokenizer.into_tokens()
+    }
+
     /// Consume the tokenizer and return the token stream.
     /// Call this after `run()` to get the tokens for the parser.
     #[must_use]
@@ -236,6 +311,28 @@ impl HTMLTokenizer {
         self.token_stream
     }
 
+    /// Consume the tokenizer and return the token stream together with
+    /// the parse errors recorded while tokenizing.
+    ///
+    /// Each issue's `token_index` is the index of the token that was
+    /// about to be emitted when the error occurred, so the issues can be
+    /// handed to [`HTMLParser::with_tokenizer_issues`](crate::HTMLParser::with_tokenizer_issues)
+    /// and interleaved with tree construction errors.
+    #[must_use]
+    pub fn into_tokens_and_issues(self) -> (Vec<Token>, Vec<ParseIssue>) {
+        (self.token_stream, self.issues)
+    }
+
+    /// Consume the tokenizer and return the token stream together with
+    /// the source span of each token.
+    ///
+    /// The spans are empty unless the tokenizer was created
+    /// [`with_source_spans`](Self::with_source_spans).
+    #[must_use]
+    pub fn into_tokens_and_spans(self) -> (Vec<Token>, Vec<Range<usize>>) {
+        (self.token_stream, self.spans.unwrap_or_default())
+    }
+
     /// [§ 13.2.5.1 Data state](https://html.spec.whatwg.org/multipage/parsing.html#data-state)
     fn handle_data_state(&mut self) {
         match self.current_input_character {
@@ -252,7 +349,7 @@ impl HTMLTokenizer {
             // "U+0000 NULL - This is an unexpected-null-character parse error.
             // Emit the current input character as a character token."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 self.emit_character_token('\0');
                 self.switch_to(TokenizerState::Data);
             }
@@ -286,7 +383,7 @@ impl HTMLTokenizer {
             // "This is an unexpected-null-character parse error. Emit a U+FFFD REPLACEMENT
             // CHARACTER character token."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 self.emit_character_token('\u{FFFD}');
             }
             // "EOF"
@@ -418,7 +515,7 @@ impl HTMLTokenizer {
             // "This is an unexpected-null-character parse error. Emit a U+FFFD REPLACEMENT
             // CHARACTER character token."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 self.emit_character_token('\u{FFFD}');
             }
             // "EOF"
@@ -434,6 +531,34 @@ impl HTMLTokenizer {
             }
         }
     }
+    /// [§ 13.2.5.5 PLAINTEXT state](https://html.spec.whatwg.org/multipage/parsing.html#plaintext-state)
+    ///
+    /// NOTE: There is no transition out of this state; everything up to the
+    /// end of the input is text.
+    fn handle_plaintext_state(&mut self) {
+        // "Consume the next input character:"
+        match self.current_input_character {
+            // "U+0000 NULL"
+            // "This is an unexpected-null-character parse error. Emit a U+FFFD
+            // REPLACEMENT CHARACTER character token."
+            Some('\0') => {
+                self.unexpected_null_character();
+                self.emit_character_token('\u{FFFD}');
+            }
+            // "EOF"
+            // "Emit an end-of-file token."
+            None => {
+                self.emit_eof_token();
+                self.at_eof = true;
+            }
+            // "Anything else"
+            // "Emit the current input character as a character token."
+            Some(c) => {
+                self.emit_character_token(c);
+            }
+        }
+    }
+
     /// [§ 13.2.5.4 Script data state](https://html.spec.whatwg.org/multipage/parsing.html#script-data-state)
     fn handle_script_data_state(&mut self) {
         // "Consume the next input character:"
@@ -446,7 +571,7 @@ impl HTMLTokenizer {
             // "U+0000 NULL"
             // "This is an unexpected-null-character parse error. Emit a U+FFFD REPLACEMENT CHARACTER character token."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 self.emit_character_token('\u{FFFD}');
             }
             // "EOF"
@@ -743,14 +868,25 @@ impl HTMLTokenizer {
             self.consume_string("DOCTYPE");
             self.switch_to(TokenizerState::DOCTYPE);
         }
-        // "Otherwise, if there is an adjusted current node and it is not an element
-        // in the HTML namespace and the next seven characters are a case-sensitive match
-        // for the string '[CDATA[', then consume those characters and switch to the
-        // CDATA section state."
+        // "Otherwise, if the next seven characters are a case-sensitive match for the
+        // string "[CDATA[" ..., then consume those characters. If there is an adjusted
+        // current node and it is not an element in the HTML namespace, then switch to
+        // the CDATA section state. Otherwise, this is a cdata-in-html-content parse
+        // error. Create a comment token whose data is the "[CDATA[" string. Switch to
+        // the bogus comment state."
         else if self.next_few_characters_are("[CDATA[") {
-            // TODO: Check adjusted current node condition
             self.consume_string("[CDATA[");
-            self.switch_to(TokenizerState::CDATASection);
+            if self.adjusted_current_node_is_foreign() {
+                self.switch_to(TokenizerState::CDATASection);
+            } else {
+                self.record_parse_error("cdata-in-html-content");
+                let mut comment = Token::new_comment();
+                for c in "[CDATA[".chars() {
+                    comment.append_to_comment(c);
+                }
+                self.current_token = Some(comment);
+                self.switch_to(TokenizerState::BogusComment);
+            }
         }
         // "Otherwise, this is an incorrectly-opened-comment parse error. Create a
         // comment token whose data is the empty string. Switch to the bogus comment state
@@ -812,7 +948,7 @@ impl HTMLTokenizer {
             // DOCTYPE token. Set the token's name to a U+FFFD REPLACEMENT CHARACTER. Switch to
             // the DOCTYPE name state."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 let mut token = Token::new_doctype();
                 token.append_to_doctype_name('\u{FFFD}');
                 self.current_token = Some(token);
@@ -873,7 +1009,7 @@ impl HTMLTokenizer {
             // "U+0000 NULL - This is an unexpected-null-character parse error. Append a
             // U+FFFD REPLACEMENT CHARACTER to the current DOCTYPE token's name."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_doctype_name('\u{FFFD}');
                 }
@@ -1088,7 +1224,7 @@ impl HTMLTokenizer {
             // U+FFFD REPLACEMENT CHARACTER to the current DOCTYPE token's
             // public identifier."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_public_identifier('\u{FFFD}');
                 }
@@ -1132,7 +1268,7 @@ impl HTMLTokenizer {
             }
             // "U+0000 NULL - parse error. Append U+FFFD to public identifier."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_public_identifier('\u{FFFD}');
                 }
@@ -1387,7 +1523,7 @@ impl HTMLTokenizer {
             }
             // "U+0000 NULL - parse error. Append U+FFFD to system identifier."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_system_identifier('\u{FFFD}');
                 }
@@ -1431,7 +1567,7 @@ impl HTMLTokenizer {
             }
             // "U+0000 NULL - parse error. Append U+FFFD to system identifier."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_system_identifier('\u{FFFD}');
                 }
@@ -1507,7 +1643,7 @@ impl HTMLTokenizer {
             }
             // "U+0000 NULL - unexpected-null-character parse error. Ignore."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
             }
             // "EOF - Emit the DOCTYPE token. Emit end-of-file token."
             None => {
@@ -1547,7 +1683,7 @@ impl HTMLTokenizer {
             // "U+0000 NULL - This is an unexpected-null-character parse error. Append a
             // U+FFFD REPLACEMENT CHARACTER to the current tag token's tag name."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_tag_name('\u{FFFD}');
                 }
@@ -1693,7 +1829,7 @@ impl HTMLTokenizer {
             // "U+0000 NULL - This is an unexpected-null-character parse error. Append a
             // U+FFFD REPLACEMENT CHARACTER to the current attribute's name."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_current_attribute_name('\u{FFFD}');
                 }
@@ -1800,7 +1936,7 @@ impl HTMLTokenizer {
             // "U+0000 NULL - This is an unexpected-null-character parse error. Append a
             // U+FFFD REPLACEMENT CHARACTER to the current attribute's value."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_current_attribute_value('\u{FFFD}');
                 }
@@ -1836,7 +1972,7 @@ impl HTMLTokenizer {
             // "U+0000 NULL - This is an unexpected-null-character parse error. Append a
             // U+FFFD REPLACEMENT CHARACTER to the current attribute's value."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_current_attribute_value('\u{FFFD}');
                 }
@@ -1878,7 +2014,7 @@ impl HTMLTokenizer {
             // "U+0000 NULL - This is an unexpected-null-character parse error. Append a
             // U+FFFD REPLACEMENT CHARACTER to the current attribute's value."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_current_attribute_value('\u{FFFD}');
                 }
@@ -2012,7 +2148,7 @@ impl HTMLTokenizer {
             // "U+0000 NULL - This is an unexpected-null-character parse error. Append a
             // U+FFFD REPLACEMENT CHARACTER character to the comment token's data."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_comment('\u{FFFD}');
                 }
@@ -2231,7 +2367,7 @@ impl HTMLTokenizer {
             // "U+0000 NULL - This is an unexpected-null-character parse error. Append a
             // U+FFFD REPLACEMENT CHARACTER character to the comment token's data."
             Some('\0') => {
-                self.log_parse_error();
+                self.unexpected_null_character();
                 if let Some(ref mut token) = self.current_token {
                     token.append_to_comment('\u{FFFD}');
                 }
@@ -2245,6 +2381,66 @@ impl HTMLTokenizer {
         }
     }
 
+    /// [§ 13.2.5.69 CDATA section state](https://html.spec.whatwg.org/multipage/parsing.html#cdata-section-state)
+    fn handle_cdata_section_state(&mut self) {
+        match self.current_input_character {
+            // "U+005D RIGHT SQUARE BRACKET (]) - Switch to the CDATA section bracket state."
+            Some(']') => {
+                self.switch_to(TokenizerState::CDATASectionBracket);
+            }
+            // "EOF - This is an eof-in-cdata parse error. Emit an end-of-file token."
+            None => {
+                self.log_parse_error();
+                self.emit_eof_token();
+                self.at_eof = true;
+            }
+            // "Anything else - Emit the current input character as a character token."
+            //
+            // NOTE: "U+0000 NULL characters are handled in the tree construction
+            // stage, as part of the in foreign content insertion mode, which is the
+            // only place where CDATA sections can appear."
+            Some(c) => {
+                self.emit_character_token(c);
+            }
+        }
+    }
+
+    /// [§ 13.2.5.70 CDATA section bracket state](https://html.spec.whatwg.org/multipage/parsing.html#cdata-section-bracket-state)
+    fn handle_cdata_section_bracket_state(&mut self) {
+        // "U+005D RIGHT SQUARE BRACKET (]) - Switch to the CDATA section end state."
+        if self.current_input_character == Some(']') {
+            self.switch_to(TokenizerState::CDATASectionEnd);
+        }
+        // "Anything else - Emit a U+005D RIGHT SQUARE BRACKET character token.
+        // Reconsume in the CDATA section state."
+        else {
+            self.emit_character_token(']');
+            self.reconsume_in(TokenizerState::CDATASection);
+        }
+    }
+
+    /// [§ 13.2.5.71 CDATA section end state](https://html.spec.whatwg.org/multipage/parsing.html#cdata-section-end-state)
+    fn handle_cdata_section_end_state(&mut self) {
+        match self.current_input_character {
+            // "U+005D RIGHT SQUARE BRACKET (]) - Emit a U+005D RIGHT SQUARE BRACKET
+            // character token."
+            Some(']') => {
+                self.emit_character_token(']');
+            }
+            // "U+003E GREATER-THAN SIGN character - Switch to the data state."
+            Some('>') => {
+                self.switch_to(TokenizerState::Data);
+            }
+            // "Anything else - Emit two U+005D RIGHT SQUARE BRACKET character tokens.
+            // Reconsume in the CDATA section state."
+            _ => {
+                self.emit_character_token(']');
+                self.emit_character_token(']');
+                self.reconsume_in(TokenizerState::CDATASection);
+            }
+        }
+    }
+
     /// [§ 13.2.5.72 Character reference state](https://html.spec.whatwg.org/multipage/parsing.html#character-reference-state)
     ///
     /// # Panics
@@ -2508,8 +2704,7 @@ impl HTMLTokenizer {
     ///
     /// # Panics
     ///
-    /// Panics if the tokenizer encounters an unimplemented state (e.g., PLAINTEXT,
-    /// script data escape states, DOCTYPE identifier states, CDATA states).
+    /// Panics if the tokenizer encounters an unimplemented state.
     pub fn run(&mut self) {
         loop {
             // Each state begins by consuming the next input character,
@@ -2524,6 +2719,7 @@ impl HTMLTokenizer {
             if self.current_input_character.is_none() && self.at_eof {
                 break;
             }
+            let emitted_before = self.token_stream.len();
             match self.state {
                 TokenizerState::Data => {
                     self.handle_data_state();
@@ -2538,20 +2734,7 @@ impl HTMLTokenizer {
                     self.handle_script_data_state();
                 }
                 TokenizerState::PLAINTEXT => {
-                    // [§ 13.2.5.5 PLAINTEXT state](https://html.spec.whatwg.org/multipage/parsing.html#plaintext-state)
-                    //
-                    // "Consume the next input character:"
-                    //
-                    // "U+0000 NULL"
-                    //   "This is an unexpected-null-character parse error. Emit a U+FFFD
-                    //    REPLACEMENT CHARACTER character token."
-                    //
-                    // "EOF"
-                    //   "Emit an end-of-file token."
-                    //
-                    // "Anything else"
-                    //   "Emit the current input character as a character token."
-                    todo!("PLAINTEXT state")
+                    self.handle_plaintext_state();
                 }
                 TokenizerState::TagOpen => {
                     self.handle_tag_open_state();
@@ -2648,6 +2831,7 @@ impl HTMLTokenizer {
                         //   "This is an unexpected-null-character parse error. Emit a U+FFFD
                         //    REPLACEMENT CHARACTER character token."
                         Some('\0') => {
+                            self.unexpected_null_character();
                             self.emit_character_token('\u{FFFD}');
                         }
                         // "EOF"
@@ -2685,6 +2869,7 @@ impl HTMLTokenizer {
                         //    script data escaped state. Emit a U+FFFD REPLACEMENT CHARACTER
                         //    character token."
                         Some('\0') => {
+                            self.unexpected_null_character();
                             self.switch_to(TokenizerState::ScriptDataEscaped);
                             self.emit_character_token('\u{FFFD}');
                         }
@@ -2730,6 +2915,7 @@ impl HTMLTokenizer {
                         //    script data escaped state. Emit a U+FFFD REPLACEMENT CHARACTER
                         //    character token."
                         Some('\0') => {
+                            self.unexpected_null_character();
                             self.switch_to(TokenizerState::ScriptDataEscaped);
                             self.emit_character_token('\u{FFFD}');
                         }
@@ -2940,6 +3126,7 @@ impl HTMLTokenizer {
                         //   "This is an unexpected-null-character parse error. Emit a U+FFFD
                         //    REPLACEMENT CHARACTER character token."
                         Some('\0') => {
+                            self.unexpected_null_character();
                             self.emit_character_token('\u{FFFD}');
                         }
                         // "EOF"
@@ -2979,6 +3166,7 @@ impl HTMLTokenizer {
                         //    script data double escaped state. Emit a U+FFFD REPLACEMENT
                         //    CHARACTER character token."
                         Some('\0') => {
+                            self.unexpected_null_character();
                             self.switch_to(TokenizerState::ScriptDataDoubleEscaped);
                             self.emit_character_token('\u{FFFD}');
                         }
@@ -3026,6 +3214,7 @@ impl HTMLTokenizer {
                         //    script data double escaped state. Emit a U+FFFD REPLACEMENT
                         //    CHARACTER character token."
                         Some('\0') => {
+                            self.unexpected_null_character();
                             self.switch_to(TokenizerState::ScriptDataDoubleEscaped);
                             self.emit_character_token('\u{FFFD}');
                         }
@@ -3228,23 +3417,14 @@ impl HTMLTokenizer {
                 //
                 // CDATA sections are only valid in foreign content (SVG/MathML):
                 //   <![CDATA[ ... ]]>
-                //
-                // TODO: Implement CDATA parsing:
-                //
-                // STEP 5: CDATASection - consume characters until "]]>"
-                //   [§ 13.2.5.69](https://html.spec.whatwg.org/multipage/parsing.html#cdata-section-state)
                 TokenizerState::CDATASection => {
-                    todo!("CDATASection state - see STEP 5")
+                    self.handle_cdata_section_state();
                 }
-                // STEP 6: CDATASectionBracket - saw first ']'
-                //   [§ 13.2.5.70](https://html.spec.whatwg.org/multipage/parsing.html#cdata-section-bracket-state)
                 TokenizerState::CDATASectionBracket => {
-                    todo!("CDATASectionBracket state - see STEP 6")
+                    self.handle_cdata_section_bracket_state();
                 }
-                // STEP 7: CDATASectionEnd - saw "]]", looking for '>'
-                //   [§ 13.2.5.71](https://html.spec.whatwg.org/multipage/parsing.html#cdata-section-end-state)
                 TokenizerState::CDATASectionEnd => {
-                    todo!("CDATASectionEnd state - see STEP 7")
+                    self.handle_cdata_section_end_state();
                 }
                 // ===== CHARACTER REFERENCE STATES =====
                 // [§ 13.2.5.72-80](https://html.spec.whatwg.org/multipage/parsing.html#character-reference-state)
@@ -3452,6 +3632,7 @@ impl HTMLTokenizer {
                     self.switch_to(return_state);
                 }
             }
+            self.record_spans(emitted_before);
         }
     }
 }
@@ -13,6 +13,7 @@ use koala_common::warning::warn_once;
 
 use super::core::{HTMLTokenizer, TokenizerState};
 use super::token::Token;
+use crate::parser::ParseIssue;
 
 // =============================================================================
 // State Transition Helpers
@@ -52,15 +53,43 @@ impl HTMLTokenizer {
     ///
     /// Returns the character at the current position and advances the position.
     /// Returns None if we've reached the end of input.
+    ///
+    /// Newlines are normalized as the input is consumed (see
+    /// [`Self::normalize_newline`]), so the states never see a U+000D CR.
     pub(super) fn consume(&mut self) -> Option<char> {
         if let Some(c) = self.input[self.current_pos..].chars().next() {
             self.current_pos += c.len_utf8();
-            Some(c)
+            Some(self.normalize_newline(c))
         } else {
             None
         }
     }
 
+    /// [§ 13.2.3.5 Preprocessing the input stream](https://html.spec.whatwg.org/multipage/parsing.html#preprocessing-the-input-stream)
+    ///
+    /// "Before the tokenizer stage, the input stream must be preprocessed by
+    /// normalizing newlines. Thus, newlines in HTML DOMs are represented by
+    /// U+000A LF characters, and there are never any U+000D CR characters in
+    /// the input to the tokenization stage."
+    ///
+    /// [Infra § 4.6](https://infra.spec.whatwg.org/#normalize-newlines):
+    /// "To normalize newlines in a string, replace every U+000D CR U+000A LF
+    /// code point pair with a single U+000A LF code point, and then replace
+    /// every remaining U+000D CR code point with a U+000A LF code point."
+    ///
+    /// NOTE: The input string is left untouched so that source spans keep
+    /// pointing into it; `c` has just been consumed, and the LF of a CRLF
+    /// pair is consumed along with it.
+    fn normalize_newline(&mut self, c: char) -> char {
+        if c != '\r' {
+            return c;
+        }
+        if self.input[self.current_pos..].starts_with('\n') {
+            self.current_pos += 1;
+        }
+        '\n'
+    }
+
     /// [§ 13.2.5 Tokenization](https://html.spec.whatwg.org/multipage/parsing.html#tokenization)
     ///
     /// Peek at a codepoint at the given offset from the current position without
@@ -150,6 +179,8 @@ impl HTMLTokenizer {
     // "Emit the current token" - adds the token to the output stream.
     pub fn emit_token(&mut self) {
         if let Some(token) = self.current_token.take() {
+            self.track_foreign_content(&token);
+
             // Track the last start tag name for RCDATA/RAWTEXT end tag detection
             if let Token::StartTag { ref name, .. } = token {
                 self.last_start_tag_name = Some(name.clone());
@@ -162,6 +193,7 @@ impl HTMLTokenizer {
                 //
                 // RCDATA elements: "title", "textarea"
                 // RAWTEXT elements: "style", "xmp", "iframe", "noembed", "noframes"
+                // PLAINTEXT elements: "plaintext"
                 // Script data: "script" (more complex, not yet implemented)
                 match name.as_str() {
                     // "A start tag whose tag name is "title""
@@ -182,6 +214,14 @@ impl HTMLTokenizer {
                         self.switch_to(TokenizerState::RAWTEXT);
                         return;
                     }
+                    // "A start tag whose tag name is "plaintext""
+                    // [§ 13.2.6.4.7](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody)
+                    // "Switch the tokenizer to the PLAINTEXT state."
+                    "plaintext" => {
+                        self.token_stream.push(token);
+                        self.switch_to(TokenizerState::PLAINTEXT);
+                        return;
+                    }
                     // "A start tag whose tag name is \"script\""
                     // "Follow the generic script element parsing algorithm."
                     // [§ 13.2.6.4](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inhead)
@@ -198,6 +238,51 @@ impl HTMLTokenizer {
         }
     }
 
+    /// [§ 13.2.6.5 The rules for parsing tokens in foreign content](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inforeign)
+    ///
+    /// NOTE: Per spec, the tree builder's stack of open elements decides
+    /// this. Since we run the tokenizer before the parser, we follow `<svg>`
+    /// and `<math>` subtrees here instead, the same way `emit_token`
+    /// follows RCDATA elements. Elements inside an SVG `foreignObject`,
+    /// `desc` or `title`, or a `MathML` text integration point, are HTML.
+    /// HTML start tags that break out of foreign content are not handled.
+    fn track_foreign_content(&mut self, token: &Token) {
+        match token {
+            Token::StartTag {
+                name, self_closing, ..
+            } if !self_closing => {
+                let foreign = matches!(name.as_str(), "svg" | "math")
+                    || self
+                        .foreign_elements
+                        .last()
+                        .is_some_and(|(parent, foreign)| *foreign && !is_integration_point(parent));
+                if foreign || !self.foreign_elements.is_empty() {
+                    self.foreign_elements.push((name.clone(), foreign));
+                }
+            }
+            Token::EndTag { name, .. } => {
+                if let Some(index) = self
+                    .foreign_elements
+                    .iter()
+                    .rposition(|(open, _)| open == name)
+                {
+                    self.foreign_elements.truncate(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// [§ 13.2.4.1](https://html.spec.whatwg.org/multipage/parsing.html#adjusted-current-node)
+    ///
+    /// Whether there is an adjusted current node and it is not an element
+    /// in the HTML namespace.
+    pub(super) fn adjusted_current_node_is_foreign(&self) -> bool {
+        self.foreign_elements
+            .last()
+            .is_some_and(|(_, foreign)| *foreign)
+    }
+
     /// "Emit the current input character as a character token."
     ///
     /// Emits a character token directly without going through `current_token`.
@@ -207,12 +292,47 @@ impl HTMLTokenizer {
     }
 
     /// "Emit an end-of-file token."
+    ///
+    /// If the tokenizer is in the middle of a tag, comment, DOCTYPE or
+    /// CDATA section, the corresponding EOF parse error is recorded first
+    /// (see [`Self::eof_parse_error_code`]).
     pub fn emit_eof_token(&mut self) {
+        if let Some(code) = self.eof_parse_error_code() {
+            self.record_parse_error(code);
+        }
         let token = Token::new_eof();
         self.token_stream.push(token);
     }
 }
 
+// =============================================================================
+// Source Span Helpers
+// =============================================================================
+
+impl HTMLTokenizer {
+    /// Record spans for the tokens emitted since `emitted_before`, if span
+    /// recording is enabled.
+    ///
+    /// Called once per step of the state machine. A character that is about
+    /// to be reconsumed belongs to whatever token comes next, so it is left
+    /// out of the spans recorded now.
+    pub(super) fn record_spans(&mut self, emitted_before: usize) {
+        let Some(spans) = &mut self.spans else {
+            return;
+        };
+        let mut end = self.current_pos;
+        if self.reconsume
+            && let Some(c) = self.current_input_character
+        {
+            end -= c.len_utf8();
+        }
+        for _ in emitted_before..self.token_stream.len() {
+            spans.push(self.span_start..end);
+            self.span_start = end;
+        }
+    }
+}
+
 // =============================================================================
 // RCDATA/RAWTEXT Helpers
 // =============================================================================
@@ -368,4 +488,102 @@ impl HTMLTokenizer {
         let pos = self.current_pos;
         warn_once("HTML Tokenizer", &format!("parse error at position {pos}"));
     }
+
+    /// [§ 13.2.2 Parse errors](https://html.spec.whatwg.org/multipage/parsing.html#parse-error-unexpected-null-character)
+    ///
+    /// "unexpected-null-character: This error occurs if the parser
+    /// encounters a U+0000 NULL code point in the input stream in certain
+    /// positions. In general, such code points are either ignored or, for
+    /// security reasons, replaced with a U+FFFD REPLACEMENT CHARACTER."
+    pub(super) fn unexpected_null_character(&mut self) {
+        self.log_parse_error();
+        self.record_parse_error("unexpected-null-character");
+    }
+
+    /// Record a parse error so it is reported alongside tree construction
+    /// errors (see [`HTMLTokenizer::into_tokens_and_issues`]).
+    pub(super) fn record_parse_error(&mut self, code: &str) {
+        self.issues.push(ParseIssue {
+            message: format!("{code} at position {}", self.current_pos),
+            token_index: self.token_stream.len(),
+            is_error: true,
+        });
+    }
+
+    /// [§ 13.2.2 Parse errors](https://html.spec.whatwg.org/multipage/parsing.html#parse-errors)
+    ///
+    /// The parse error the spec raises when EOF is reached in the current
+    /// state, or `None` if EOF is expected there.
+    ///
+    /// States that reconsume EOF elsewhere (e.g. before attribute value,
+    /// comment start) never emit the end-of-file token themselves, so only
+    /// the states that do are listed.
+    pub(super) const fn eof_parse_error_code(&self) -> Option<&'static str> {
+        match self.state {
+            // "eof-before-tag-name: This error occurs if the parser encounters
+            // the end of the input stream where a tag name is expected."
+            TokenizerState::TagOpen | TokenizerState::EndTagOpen => Some("eof-before-tag-name"),
+            // "eof-in-tag: This error occurs if the parser encounters the end
+            // of the input stream in a start tag or an end tag."
+            TokenizerState::TagName
+            | TokenizerState::BeforeAttributeName
+            | TokenizerState::AttributeName
+            | TokenizerState::AfterAttributeName
+            | TokenizerState::AttributeValueDoubleQuoted
+            | TokenizerState::AttributeValueSingleQuoted
+            | TokenizerState::AttributeValueUnquoted
+            | TokenizerState::AfterAttributeValueQuoted
+            | TokenizerState::SelfClosingStartTag => Some("eof-in-tag"),
+            // "eof-in-comment: This error occurs if the parser encounters the
+            // end of the input stream in a comment."
+            TokenizerState::CommentStartDash
+            | TokenizerState::Comment
+            | TokenizerState::CommentEndDash
+            | TokenizerState::CommentEnd
+            | TokenizerState::CommentEndBang => Some("eof-in-comment"),
+            // "eof-in-doctype: This error occurs if the parser encounters the
+            // end of the input stream in a DOCTYPE."
+            TokenizerState::DOCTYPE
+            | TokenizerState::BeforeDOCTYPEName
+            | TokenizerState::DOCTYPEName
+            | TokenizerState::AfterDOCTYPEName
+            | TokenizerState::AfterDOCTYPEPublicKeyword
+            | TokenizerState::BeforeDOCTYPEPublicIdentifier
+            | TokenizerState::DOCTYPEPublicIdentifierDoubleQuoted
+            | TokenizerState::DOCTYPEPublicIdentifierSingleQuoted
+            | TokenizerState::AfterDOCTYPEPublicIdentifier
+            | TokenizerState::BetweenDOCTYPEPublicAndSystemIdentifiers
+            | TokenizerState::AfterDOCTYPESystemKeyword
+            | TokenizerState::BeforeDOCTYPESystemIdentifier
+            | TokenizerState::DOCTYPESystemIdentifierDoubleQuoted
+            | TokenizerState::DOCTYPESystemIdentifierSingleQuoted
+            | TokenizerState::AfterDOCTYPESystemIdentifier => Some("eof-in-doctype"),
+            // "eof-in-cdata: This error occurs if the parser encounters the end
+            // of the input stream in a CDATA section."
+            TokenizerState::CDATASection => Some("eof-in-cdata"),
+            // "eof-in-script-html-comment-like-text: This error occurs if the
+            // parser encounters the end of the input stream in text that
+            // resembles an HTML comment inside script element content."
+            TokenizerState::ScriptDataEscaped
+            | TokenizerState::ScriptDataEscapedDash
+            | TokenizerState::ScriptDataEscapedDashDash
+            | TokenizerState::ScriptDataDoubleEscaped
+            | TokenizerState::ScriptDataDoubleEscapedDash
+            | TokenizerState::ScriptDataDoubleEscapedDashDash => {
+                Some("eof-in-script-html-comment-like-text")
+            }
+            _ => None,
+        }
+    }
+}
+
+/// [§ 13.2.6.5](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inforeign)
+///
+/// Whether `name` is an SVG HTML integration point or a `MathML` text
+/// integration point, whose children are parsed as HTML.
+fn is_integration_point(name: &str) -> bool {
+    matches!(
+        name,
+        "foreignobject" | "desc" | "title" | "mi" | "mo" | "mn" | "ms" | "mtext"
+    )
 }
@@ -75,6 +75,31 @@ fn test_document_structure() {
     assert!(body_id.is_some());
 }
 
+#[test]
+fn test_doctype_creates_document_type_node() {
+    // [§ 13.2.6.4.1 The "initial" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#the-initial-insertion-mode)
+    //
+    // "Append a DocumentType node to the Document node..."
+    let tree = parse(
+        r#"<!DOCTYPE html PUBLIC "-//W3C//DTD HTML 4.01//EN" "http://www.w3.org/TR/html4/strict.dtd"><html></html>"#,
+    );
+    let children = tree.children(NodeId::ROOT);
+    assert_eq!(children.len(), 2);
+
+    let NodeType::DocumentType {
+        name,
+        public_id,
+        system_id,
+    } = &get_node(&tree, children[0]).node_type
+    else {
+        panic!("Expected DocumentType as the document's first child");
+    };
+    assert_eq!(name, "html");
+    assert_eq!(public_id, "-//W3C//DTD HTML 4.01//EN");
+    assert_eq!(system_id, "http://www.w3.org/TR/html4/strict.dtd");
+    assert_eq!(tree.document_element(), Some(children[1]));
+}
+
 #[test]
 fn test_text_node() {
     let tree = parse("<html><body>Hello World</body></html>");
@@ -162,6 +187,53 @@ fn test_meta_element() {
     }
 }
 
+#[test]
+fn test_link_after_head_is_moved_into_head() {
+    // [§ 13.2.6.4.6 The "after head" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#the-after-head-insertion-mode)
+    //
+    // A `<link>` between `</head>` and `<body>` is processed "in head" with the
+    // head element pushed back onto the stack, so it lands in the head and does
+    // not open the body early.
+    let tree = parse(
+        r#"<html><head><title>T</title></head><link rel="stylesheet" href="a.css"><body><p>Hi</p></body></html>"#,
+    );
+    let html_id = find_element(&tree, NodeId::ROOT, "html").unwrap();
+    let head_id = find_element(&tree, html_id, "head").unwrap();
+    let body_id = find_element(&tree, html_id, "body").unwrap();
+    let link_id = find_element(&tree, NodeId::ROOT, "link").unwrap();
+
+    assert_eq!(tree.parent(link_id), Some(head_id));
+    assert_eq!(tree.children(html_id), &[head_id, body_id]);
+    let body_tags: Vec<_> = tree
+        .children(body_id)
+        .iter()
+        .filter_map(|&id| tree.as_element(id).map(|e| e.tag_name.as_str()))
+        .collect();
+    assert_eq!(body_tags, ["p"]);
+    assert_eq!(text_content(&tree, body_id), "Hi");
+}
+
+#[test]
+fn test_script_after_head_stays_in_head_and_body_follows() {
+    // A raw-text element after `</head>` is closed by the "text" insertion
+    // mode, which returns to "after head" so the implicit body is still created
+    // by the first real body content.
+    let tree = parse(
+        r#"<html><head></head><meta charset="UTF-8"><script>var x = 1;</script><div>after</div></html>"#,
+    );
+    let head_id = find_element(&tree, NodeId::ROOT, "head").unwrap();
+    let body_id = find_element(&tree, NodeId::ROOT, "body").unwrap();
+    let meta_id = find_element(&tree, NodeId::ROOT, "meta").unwrap();
+    let script_id = find_element(&tree, NodeId::ROOT, "script").unwrap();
+    let div_id = find_element(&tree, NodeId::ROOT, "div").unwrap();
+
+    assert_eq!(tree.parent(meta_id), Some(head_id));
+    assert_eq!(tree.parent(script_id), Some(head_id));
+    assert_eq!(text_content(&tree, script_id), "var x = 1;");
+    assert_eq!(tree.parent(div_id), Some(body_id));
+    assert_eq!(text_content(&tree, div_id), "after");
+}
+
 #[test]
 fn test_whitespace_preserved_in_text() {
     let tree = parse("<html><body>  hello  world  </body></html>");
@@ -618,3 +690,500 @@ fn test_ol_end_tag_scope_checking() {
         "text should still appear after stray </ol>"
     );
 }
+
+// =============================================================================
+// Truncated input
+// =============================================================================
+
+/// Helper to parse HTML and return both the DOM tree and every recorded
+/// parse issue (tokenizer and tree construction).
+fn parse_with_issues(html: &str) -> (DomTree, Vec<koala_html::ParseIssue>) {
+    let mut tokenizer = HTMLTokenizer::new(html.to_string());
+    tokenizer.run();
+    let (tokens, tokenizer_issues) = tokenizer.into_tokens_and_issues();
+    HTMLParser::new(tokens)
+        .with_tokenizer_issues(tokenizer_issues)
+        .run_with_issues()
+}
+
+/// Assert the implied `html`, `head` and `body` elements were created.
+fn assert_document_skeleton(tree: &DomTree) {
+    for tag in ["html", "head", "body"] {
+        assert!(
+            find_element(tree, NodeId::ROOT, tag).is_some(),
+            "truncated input should still produce a <{tag}> element"
+        );
+    }
+}
+
+fn has_issue(issues: &[koala_html::ParseIssue], code: &str) -> bool {
+    issues
+        .iter()
+        .any(|issue| issue.is_error && issue.message.starts_with(code))
+}
+
+#[test]
+fn test_eof_in_tag_name() {
+    // [§ 13.2.5.8 Tag name state](https://html.spec.whatwg.org/multipage/parsing.html#tag-name-state)
+    //
+    // "EOF: This is an eof-in-tag parse error. Emit an end-of-file token."
+    let (tree, issues) = parse_with_issues("<p>kept<div");
+    assert_document_skeleton(&tree);
+    assert!(find_element(&tree, NodeId::ROOT, "div").is_none());
+    let p = find_element(&tree, NodeId::ROOT, "p").unwrap();
+    assert_eq!(text_content(&tree, p), "kept");
+    assert!(has_issue(&issues, "eof-in-tag"), "issues: {issues:?}");
+}
+
+#[test]
+fn test_eof_in_attribute_value() {
+    // [§ 13.2.5.36 Attribute value (double-quoted) state](https://html.spec.whatwg.org/multipage/parsing.html#attribute-value-(double-quoted)-state)
+    //
+    // "EOF: This is an eof-in-tag parse error. Emit an end-of-file token."
+    let (tree, issues) = parse_with_issues("text<div class=\"");
+    assert_document_skeleton(&tree);
+    let body = find_element(&tree, NodeId::ROOT, "body").unwrap();
+    assert_eq!(text_content(&tree, body), "text");
+    assert!(has_issue(&issues, "eof-in-tag"), "issues: {issues:?}");
+}
+
+#[test]
+fn test_eof_in_comment() {
+    // [§ 13.2.5.45 Comment state](https://html.spec.whatwg.org/multipage/parsing.html#comment-state)
+    //
+    // "EOF: This is an eof-in-comment parse error. Emit the current comment
+    // token. Emit an end-of-file token."
+    let (tree, issues) = parse_with_issues("<!--");
+    assert_document_skeleton(&tree);
+    assert!(has_issue(&issues, "eof-in-comment"), "issues: {issues:?}");
+
+    let (tree, _) = parse_with_issues("<body><!--partial");
+    let body = find_element(&tree, NodeId::ROOT, "body").unwrap();
+    let comment = tree
+        .children(body)
+        .iter()
+        .find_map(|&id| match &tree.get(id)?.node_type {
+            NodeType::Comment(data) => Some(data.clone()),
+            _ => None,
+        });
+    assert_eq!(comment.as_deref(), Some("partial"));
+}
+
+#[test]
+fn test_eof_in_doctype() {
+    // [§ 13.2.5.53 DOCTYPE state](https://html.spec.whatwg.org/multipage/parsing.html#doctype-state)
+    //
+    // "EOF: This is an eof-in-doctype parse error. Create a new DOCTYPE token.
+    // Set its force-quirks flag to on. Emit the current token. Emit an
+    // end-of-file token."
+    let (tree, issues) = parse_with_issues("<!DOCTYPE");
+    assert_document_skeleton(&tree);
+    assert!(has_issue(&issues, "eof-in-doctype"), "issues: {issues:?}");
+}
+
+#[test]
+fn test_eof_before_tag_name() {
+    // [§ 13.2.5.6 Tag open state](https://html.spec.whatwg.org/multipage/parsing.html#tag-open-state)
+    //
+    // "EOF: This is an eof-before-tag-name parse error. Emit a U+003C
+    // LESS-THAN SIGN character token and an end-of-file token."
+    let (tree, issues) = parse_with_issues("a<");
+    let body = find_element(&tree, NodeId::ROOT, "body").unwrap();
+    assert_eq!(text_content(&tree, body), "a<");
+    assert!(
+        has_issue(&issues, "eof-before-tag-name"),
+        "issues: {issues:?}"
+    );
+}
+
+#[test]
+fn test_eof_in_cdata() {
+    // [§ 13.2.5.69 CDATA section state](https://html.spec.whatwg.org/multipage/parsing.html#cdata-section-state)
+    //
+    // "EOF: This is an eof-in-cdata parse error. Emit an end-of-file token."
+    //
+    // CDATA sections only exist in foreign content.
+    let (tree, issues) = parse_with_issues("<body><svg><![CDATA[x]y");
+    assert_document_skeleton(&tree);
+    let svg = find_element(&tree, NodeId::ROOT, "svg").unwrap();
+    assert_eq!(text_content(&tree, svg), "x]y");
+    assert!(has_issue(&issues, "eof-in-cdata"), "issues: {issues:?}");
+}
+
+#[test]
+fn test_cdata_in_html_content_is_a_bogus_comment() {
+    // [§ 13.2.5.42 Markup declaration open state](https://html.spec.whatwg.org/multipage/parsing.html#markup-declaration-open-state)
+    //
+    // "Otherwise, this is a cdata-in-html-content parse error. Create a
+    // comment token whose data is the "[CDATA[" string. Switch to the bogus
+    // comment state."
+    let (tree, issues) = parse_with_issues("<body><![CDATA[x]y");
+    let body = find_element(&tree, NodeId::ROOT, "body").unwrap();
+    let comments: Vec<&str> = tree
+        .children(body)
+        .iter()
+        .filter_map(|&child| match &tree.get(child)?.node_type {
+            NodeType::Comment(data) => Some(data.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(comments, ["[CDATA[x]y"]);
+    assert_eq!(text_content(&tree, body), "");
+    assert!(
+        has_issue(&issues, "cdata-in-html-content"),
+        "issues: {issues:?}"
+    );
+
+    // Back in HTML content after the <svg> closes, and in an HTML element
+    // inside an SVG integration point.
+    for html in [
+        "<svg></svg><![CDATA[x]]>",
+        "<svg><foreignObject><p><![CDATA[x]]></p></foreignObject></svg>",
+    ] {
+        let (_, issues) = parse_with_issues(html);
+        assert!(
+            has_issue(&issues, "cdata-in-html-content"),
+            "{html}: {issues:?}"
+        );
+    }
+}
+
+#[test]
+fn test_eof_with_unclosed_elements() {
+    // [§ 13.2.6.4.7 The "in body" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody)
+    //
+    // "An end-of-file token: ... If there is a node in the stack of open
+    // elements that is not either a dd element, a dt element, ... then this
+    // is a parse error. Stop parsing."
+    let (tree, issues) = parse_with_issues("<div><span>open");
+    let span = find_element(&tree, NodeId::ROOT, "span").unwrap();
+    assert_eq!(text_content(&tree, span), "open");
+    assert!(issues.iter().any(|issue| issue.is_error));
+
+    // Elements whose end tags may be omitted are not an error.
+    let (_, issues) = parse_with_issues("<p>para");
+    assert!(
+        issues.iter().all(|issue| !issue.is_error),
+        "issues: {issues:?}"
+    );
+}
+
+#[test]
+fn test_eof_in_text_element() {
+    // [§ 13.2.6.4.8 The "text" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-incdata)
+    //
+    // "An end-of-file token: Parse error. ... Pop the current node off the
+    // stack of open elements. Switch the insertion mode to the original
+    // insertion mode and reprocess the token."
+    let (tree, issues) = parse_with_issues("<title>unterminated");
+    assert_document_skeleton(&tree);
+    let title = find_element(&tree, NodeId::ROOT, "title").unwrap();
+    assert_eq!(text_content(&tree, title), "unterminated");
+    assert!(issues.iter().any(|issue| issue.is_error));
+}
+
+// =============================================================================
+// Insertion-mode trace
+// =============================================================================
+
+#[test]
+fn test_mode_trace_simple_document() {
+    use koala_html::InsertionMode;
+
+    let tokens = HTMLTokenizer::tokenize("<html><head></head><body></body></html>");
+    let (_, _, trace) = HTMLParser::new(tokens).run_with_mode_trace();
+
+    // The html start tag has no DOCTYPE before it, so it is reprocessed in
+    // "before html"; every following token switches to the next mode.
+    assert_eq!(
+        trace,
+        [
+            (0, InsertionMode::Initial),
+            (0, InsertionMode::BeforeHtml),
+            (1, InsertionMode::BeforeHead),
+            (2, InsertionMode::InHead),
+            (3, InsertionMode::AfterHead),
+            (4, InsertionMode::InBody),
+            (5, InsertionMode::AfterBody),
+            (6, InsertionMode::AfterAfterBody),
+        ]
+    );
+}
+
+#[test]
+fn test_mode_trace_records_text_mode_round_trip() {
+    use koala_html::InsertionMode;
+
+    // <title> switches to "text" and back to the original mode.
+    let tokens = HTMLTokenizer::tokenize("<!DOCTYPE html><head><title>x</title></head>");
+    let (_, _, trace) = HTMLParser::new(tokens).run_with_mode_trace();
+    let modes: Vec<InsertionMode> = trace.iter().map(|&(_, mode)| mode).collect();
+    assert_eq!(
+        modes[..5],
+        [
+            InsertionMode::Initial,
+            InsertionMode::BeforeHtml,
+            InsertionMode::BeforeHead,
+            InsertionMode::InHead,
+            InsertionMode::Text,
+        ]
+    );
+    assert_eq!(modes[5], InsertionMode::InHead);
+}
+
+/// [§ 13.2.6.4.10 The "in table text" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-intabletext)
+///
+/// Text with non-whitespace characters directly inside a table is foster
+/// parented, whitespace and all, in front of the table.
+#[test]
+fn test_table_text_foster_parented_before_table() {
+    let tokens = HTMLTokenizer::tokenize("<!DOCTYPE html><body><table> hi </table></body>");
+    let (tree, issues) = HTMLParser::new(tokens).run_with_issues();
+    let body = tree.body().expect("body");
+    let table = find_element(&tree, body, "table").expect("table");
+
+    let children = tree.children(body);
+    assert_eq!(children.len(), 2);
+    assert_eq!(tree.as_text(children[0]), Some(" hi "));
+    assert_eq!(children[1], table);
+    assert!(tree.children(table).is_empty());
+    assert!(
+        issues
+            .iter()
+            .any(|issue| issue.message == "non-whitespace text in table context")
+    );
+}
+
+/// [§ 13.2.6.4.10 The "in table text" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-intabletext)
+///
+/// Whitespace-only runs stay inside the table, and text split by a table
+/// element is buffered and flushed once per run.
+#[test]
+fn test_table_whitespace_text_stays_in_table() {
+    let tree = parse("<!DOCTYPE html><table>\n  <tr> <td>x</td> </tr>\n</table>");
+    let body = tree.body().expect("body");
+    let table = find_element(&tree, body, "table").expect("table");
+    assert_eq!(tree.children(body), [table]);
+    assert_eq!(tree.as_text(tree.children(table)[0]), Some("\n  "));
+
+    let tree = parse("<!DOCTYPE html><table>a<tr>b<td>c</td>d</tr>e</table>");
+    let body = tree.body().expect("body");
+    assert_eq!(text_content(&tree, tree.children(body)[0]), "abde");
+    let table = find_element(&tree, body, "table").expect("table");
+    assert_eq!(text_content(&tree, table), "c");
+}
+
+/// [§ 13.2.6.4.11 The "in caption" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-incaption)
+///
+/// A `<caption>` is a child of its table, and a row start tag implicitly
+/// closes it.
+#[test]
+fn test_caption_is_closed_by_table_content() {
+    let tree = parse("<!DOCTYPE html><table><caption>Title<tr><td>x</td></tr></table>");
+    let body = tree.body().expect("body");
+    let table = find_element(&tree, body, "table").expect("table");
+    let caption = find_element(&tree, table, "caption").expect("caption");
+    let tbody = find_element(&tree, table, "tbody").expect("tbody");
+
+    assert_eq!(tree.children(table), [caption, tbody]);
+    assert_eq!(text_content(&tree, caption), "Title");
+    assert_eq!(text_content(&tree, tbody), "x");
+}
+
+/// [§ 13.2.6.4.11 The "in caption" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-incaption)
+///
+/// "Generate implied end tags. Now, if the current node is not a caption
+/// element, then this is a parse error."
+#[test]
+fn test_caption_closed_with_open_elements_is_a_parse_error() {
+    let (tree, issues) =
+        parse_with_issues("<!DOCTYPE html><table><caption><div>Title</caption></table>");
+    let body = tree.body().expect("body");
+    let caption = find_element(&tree, body, "caption").expect("caption");
+    assert_eq!(text_content(&tree, caption), "Title");
+    assert!(
+        issues.iter().any(|issue| issue.is_error),
+        "issues: {issues:?}"
+    );
+
+    // Elements with implied end tags close silently.
+    let (_, issues) =
+        parse_with_issues("<!DOCTYPE html><table><caption><p>Title</caption></table>");
+    assert!(
+        issues.iter().all(|issue| !issue.is_error),
+        "issues: {issues:?}"
+    );
+}
+
+/// [§ 13.2.6.4.7 The "in body" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody)
+///
+/// A second `<body>` or `<html>` start tag adds its new attributes to the
+/// existing element instead of creating another one.
+#[test]
+fn test_duplicate_body_and_html_tags_merge_attributes() {
+    let tree = parse(
+        "<!DOCTYPE html><html lang=en><body class=a><p>x</p>\
+         <body id=b class=c><html lang=fr dir=rtl></body>",
+    );
+    let html = find_element(&tree, NodeId::ROOT, "html").expect("html");
+    let body = tree.body().expect("body");
+    assert_eq!(
+        tree.descendants(NodeId::ROOT)
+            .filter(|&id| tree.as_element(id).is_some_and(|el| el.tag_name == "body"))
+            .count(),
+        1
+    );
+
+    let body_attrs = &tree.as_element(body).unwrap().attrs;
+    assert_eq!(body_attrs.get("class").map(String::as_str), Some("a"));
+    assert_eq!(body_attrs.get("id").map(String::as_str), Some("b"));
+
+    let html_attrs = &tree.as_element(html).unwrap().attrs;
+    assert_eq!(html_attrs.get("lang").map(String::as_str), Some("en"));
+    assert_eq!(html_attrs.get("dir").map(String::as_str), Some("rtl"));
+}
+
+/// Feeding tokens one at a time through the streaming API builds the same
+/// tree, and reports the same issues, as the batch path.
+#[test]
+fn test_streaming_feed_tokens_matches_batch_parse() {
+    let html = "<!DOCTYPE html><html><head><title>T</title></head>\
+                <body><p class=a>one<b>two<i>three</b>four</i></p>\
+                <table><tr><td>cell</table><!-- done --></body></html>";
+    let mut tokenizer = HTMLTokenizer::new(html.to_string());
+    tokenizer.run();
+    let tokens = tokenizer.into_tokens();
+
+    let (batch_tree, batch_issues) = HTMLParser::new(tokens.clone()).run_with_issues();
+
+    let mut parser = HTMLParser::streaming();
+    for token in tokens {
+        parser.feed_token(token);
+    }
+    let (streamed_tree, streamed_issues) = parser.finish();
+
+    assert_eq!(format!("{streamed_tree:?}"), format!("{batch_tree:?}"));
+    assert_eq!(
+        koala_html::serialize_html(&streamed_tree, NodeId::ROOT),
+        koala_html::serialize_html(&batch_tree, NodeId::ROOT)
+    );
+    assert_eq!(streamed_issues.len(), batch_issues.len());
+}
+
+/// [`HTMLParser::finish`] processes the end-of-file token if it was never
+/// fed, closing any open elements.
+#[test]
+fn test_streaming_finish_without_eof_token() {
+    let mut tokenizer = HTMLTokenizer::new("<p>unterminated".to_string());
+    tokenizer.run();
+    let mut parser = HTMLParser::streaming();
+    for token in tokenizer.into_tokens() {
+        if !token.is_eof() {
+            parser.feed_token(token);
+        }
+    }
+    let (tree, _) = parser.finish();
+    let p = find_element(&tree, NodeId::ROOT, "p").expect("p");
+    assert_eq!(text_content(&tree, p), "unterminated");
+}
+
+/// [§ 13.2.6.4.7 The "in body" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody)
+///
+/// Everything after a `<plaintext>` start tag is literal text.
+#[test]
+fn test_plaintext_content_is_literal_text() {
+    let tree = parse("<p>before<plaintext><b>not bold");
+    let plaintext = find_element(&tree, NodeId::ROOT, "plaintext").expect("plaintext");
+    assert_eq!(text_content(&tree, plaintext), "<b>not bold");
+    assert!(find_element(&tree, NodeId::ROOT, "b").is_none());
+
+    // "If the stack of open elements has a p element in button scope, then
+    // close a p element."
+    let body = tree.body().expect("body");
+    assert_eq!(tree.parent(plaintext), Some(body));
+}
+
+/// [§ 13.2.6.4.7 The "in body" insertion mode](https://html.spec.whatwg.org/multipage/parsing.html#parsing-main-inbody)
+///
+/// `<xmp>` and `<noframes>` follow the generic raw text element parsing
+/// algorithm, so markup inside them stays text.
+#[test]
+fn test_xmp_and_noframes_content_is_raw_text() {
+    let tree = parse("<body><xmp></b></xmp><noframes><i>x</i></noframes><p>after</p>");
+    let xmp = find_element(&tree, NodeId::ROOT, "xmp").expect("xmp");
+    assert_eq!(text_content(&tree, xmp), "</b>");
+
+    let noframes = find_element(&tree, NodeId::ROOT, "noframes").expect("noframes");
+    assert_eq!(text_content(&tree, noframes), "<i>x</i>");
+    assert!(find_element(&tree, NodeId::ROOT, "i").is_none());
+
+    let p = find_element(&tree, NodeId::ROOT, "p").expect("p");
+    assert_eq!(text_content(&tree, p), "after");
+}
+
+// =============================================================================
+// Input stream preprocessing
+// =============================================================================
+
+/// [§ 13.2.3.5 Preprocessing the input stream](https://html.spec.whatwg.org/multipage/parsing.html#preprocessing-the-input-stream)
+///
+/// CRLF pairs and lone CRs reach the DOM as a single LF.
+#[test]
+fn test_crlf_and_cr_are_normalized_to_lf() {
+    let tree = parse("<pre>one\r\ntwo\rthree\r\n\r\nfour</pre>\r\n<p\r\nclass=x>a\rb</p>");
+    let pre = find_element(&tree, NodeId::ROOT, "pre").expect("pre");
+    assert_eq!(text_content(&tree, pre), "one\ntwo\nthree\n\nfour");
+
+    let p = find_element(&tree, NodeId::ROOT, "p").expect("p");
+    assert_eq!(text_content(&tree, p), "a\nb");
+    assert_eq!(
+        tree.as_element(p)
+            .unwrap()
+            .attrs
+            .get("class")
+            .map(String::as_str),
+        Some("x")
+    );
+    assert!(!text_content(&tree, NodeId::ROOT).contains('\r'));
+}
+
+/// [§ 13.2.5.2 RCDATA state](https://html.spec.whatwg.org/multipage/parsing.html#rcdata-state)
+///
+/// "U+0000 NULL: This is an unexpected-null-character parse error. Emit a
+/// U+FFFD REPLACEMENT CHARACTER character token."
+#[test]
+fn test_null_character_becomes_replacement_character() {
+    let (tree, issues) = parse_with_issues("<title>a\0b</title><div data-x=\"c\0d\"></div>");
+    let title = find_element(&tree, NodeId::ROOT, "title").expect("title");
+    assert_eq!(text_content(&tree, title), "a\u{FFFD}b");
+
+    let div = find_element(&tree, NodeId::ROOT, "div").expect("div");
+    assert_eq!(
+        tree.as_element(div)
+            .unwrap()
+            .attrs
+            .get("data-x")
+            .map(String::as_str),
+        Some("c\u{FFFD}d")
+    );
+    assert_eq!(
+        issues
+            .iter()
+            .filter(|issue| issue.message.starts_with("unexpected-null-character"))
+            .count(),
+        2
+    );
+}
+
+#[test]
+fn test_write_tree_renders_into_buffer() {
+    let tree = parse("<p class=\"a\">Hi there<!--note--></p>");
+    let body = find_element(&tree, NodeId::ROOT, "body").expect("body");
+
+    let mut out = Vec::new();
+    koala_html::write_tree(&mut out, &tree, body, 1).expect("writing to a Vec cannot fail");
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        "  <body>\n    <p class=\"a\">\n      \"Hi\u{00B7}there\"\n      <!-- note -->\n"
+    );
+}
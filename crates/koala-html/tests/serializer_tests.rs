@@ -0,0 +1,89 @@
+//! Integration tests for HTML fragment serialization.
+
+use koala_dom::{DomTree, NodeId};
+use koala_html::{HTMLParser, HTMLTokenizer, serialize_html};
+
+fn parse(html: &str) -> DomTree {
+    let mut tokenizer = HTMLTokenizer::new(html.to_string());
+    tokenizer.run();
+    HTMLParser::new(tokenizer.into_tokens()).run()
+}
+
+/// Serialize the contents of `<body>`.
+fn body_html(html: &str) -> String {
+    let tree = parse(html);
+    let body = tree.body().expect("document has a body");
+    serialize_html(&tree, body)
+}
+
+#[test]
+fn test_serialize_preserves_attribute_order() {
+    assert_eq!(
+        body_html(r#"<a href="x" id="y" class="z">link</a>"#),
+        r#"<a href="x" id="y" class="z">link</a>"#
+    );
+    // Not alphabetical, so a hash-ordered map would scramble it.
+    assert_eq!(
+        body_html(r#"<div zeta="1" alpha="2" mid="3" beta="4"></div>"#),
+        r#"<div zeta="1" alpha="2" mid="3" beta="4"></div>"#
+    );
+}
+
+#[test]
+fn test_serialize_is_deterministic() {
+    let html = r#"<p data-a="1" data-b="2" data-c="3" data-d="4" data-e="5">x</p>"#;
+    let first = body_html(html);
+    for _ in 0..10 {
+        assert_eq!(body_html(html), first);
+    }
+}
+
+#[test]
+fn test_serialize_void_elements_and_nesting() {
+    assert_eq!(
+        body_html("<p>a<br>b<img src=\"i.png\"></p><ul><li>one</li></ul>"),
+        "<p>a<br>b<img src=\"i.png\"></p><ul><li>one</li></ul>"
+    );
+}
+
+#[test]
+fn test_serialize_escapes_text_and_attributes() {
+    assert_eq!(
+        body_html("<p title='say \"hi\" &amp; bye'>1 &lt; 2 &amp;&nbsp;3</p>"),
+        "<p title=\"say &quot;hi&quot; &amp; bye\">1 &lt; 2 &amp;&nbsp;3</p>"
+    );
+}
+
+#[test]
+fn test_serialize_raw_text_and_comments() {
+    let tree = parse("<head><script>if (a < b && c) {}</script></head><body><!-- note --></body>");
+    let head = tree
+        .children(tree.document_element().expect("html"))
+        .first()
+        .copied()
+        .expect("head");
+    assert_eq!(
+        serialize_html(&tree, head),
+        "<script>if (a < b && c) {}</script>"
+    );
+    let body = tree.body().expect("body");
+    assert_eq!(serialize_html(&tree, body), "<!-- note -->");
+}
+
+#[test]
+fn test_serialize_document() {
+    let tree = parse("<p>hi</p>");
+    assert_eq!(
+        serialize_html(&tree, NodeId::ROOT),
+        "<html><head></head><body><p>hi</p></body></html>"
+    );
+}
+
+#[test]
+fn test_serialize_doctype_round_trips() {
+    let html = "<!DOCTYPE html><html><head></head><body><p>x</p></body></html>";
+    let tree = parse(html);
+    let serialized = serialize_html(&tree, NodeId::ROOT);
+    assert_eq!(serialized, html);
+    assert_eq!(serialize_html(&parse(&serialized), NodeId::ROOT), html);
+}
@@ -371,6 +371,31 @@ fn test_xmp_element_rawtext() {
     assert_eq!(content, "<html>is text</html>");
 }
 
+/// [§ 13.2.5.5 PLAINTEXT state](https://html.spec.whatwg.org/multipage/parsing.html#plaintext-state)
+///
+/// There is no way out of the PLAINTEXT state: even a `</plaintext>` end
+/// tag is emitted as characters.
+#[test]
+fn test_plaintext_element_never_exits() {
+    let tokens = tokenize("<plaintext><b>x</b></plaintext>");
+
+    assert!(matches!(&tokens[0], Token::StartTag { name, .. } if name == "plaintext"));
+
+    let content: String = tokens[1..tokens.len() - 1]
+        .iter()
+        .filter_map(|t| {
+            if let Token::Character { data } = t {
+                Some(*data)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    assert_eq!(content, "<b>x</b></plaintext>");
+    assert!(tokens[tokens.len() - 1].is_eof());
+}
+
 #[test]
 fn test_iframe_element_rawtext() {
     let tokens = tokenize("<iframe>some content</iframe>");
@@ -489,3 +514,44 @@ fn test_named_character_reference_in_attribute() {
         _ => panic!("Expected StartTag token"),
     }
 }
+
+#[test]
+fn test_source_spans_are_off_by_default() {
+    let mut tokenizer = HTMLTokenizer::new("<p>Hi</p>".to_string());
+    tokenizer.run();
+    let (tokens, spans) = tokenizer.into_tokens_and_spans();
+    assert_eq!(tokens.len(), 5);
+    assert!(spans.is_empty());
+}
+
+#[test]
+fn test_source_spans_round_trip_to_input() {
+    let input = "<p class=\"intro\">Hello &amp; bye</p>";
+    let mut tokenizer = HTMLTokenizer::new(input.to_string()).with_source_spans();
+    tokenizer.run();
+    let (tokens, spans) = tokenizer.into_tokens_and_spans();
+    assert_eq!(tokens.len(), spans.len());
+
+    // The start tag covers its whole markup.
+    assert!(matches!(&tokens[0], Token::StartTag { name, .. } if name == "p"));
+    assert_eq!(&input[spans[0].clone()], "<p class=\"intro\">");
+
+    // The text run "Hello" is the five character tokens that follow.
+    let text = &spans[1..6];
+    assert_eq!(&input[text[0].start..text[4].end], "Hello");
+    assert!(text.iter().all(|span| span.len() == 1));
+
+    // A character reference maps back to the whole reference.
+    let amp = tokens
+        .iter()
+        .position(|t| matches!(t, Token::Character { data: '&' }))
+        .unwrap();
+    assert_eq!(&input[spans[amp].clone()], "&amp;");
+
+    let end_tag = tokens
+        .iter()
+        .position(|t| matches!(t, Token::EndTag { .. }))
+        .unwrap();
+    assert_eq!(&input[spans[end_tag].clone()], "</p>");
+    assert_eq!(spans.last(), Some(&(input.len()..input.len())));
+}
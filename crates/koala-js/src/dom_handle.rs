@@ -138,6 +138,11 @@ pub(crate) fn mark_dirty() {
 /// can happen if a closure leaks past the [`JsRuntime`] that
 /// installed it (shouldn't, but the API stays safe either way).
 ///
+/// Also returns `None` instead of panicking when the tree is
+/// already mutably borrowed — by the host holding a borrow of the
+/// shared handle across a script call, or by a re-entrant callback
+/// — so the DOM call fails quietly rather than aborting the host.
+///
 /// The closure receives `&DomTree`; for mutation see
 /// [`with_dom_mut`].
 ///
@@ -147,24 +152,24 @@ where
     F: FnOnce(&DomTree) -> R,
 {
     CURRENT.with(|cell| {
-        cell.borrow()
-            .as_ref()
-            .map(|ctx| f(&ctx.handle.borrow()))
+        let current = cell.borrow();
+        let tree = current.as_ref()?.handle.try_borrow().ok()?;
+        Some(f(&tree))
     })
 }
 
 /// Like [`with_dom`] but exclusive-borrows the tree for mutation.
-/// Panics if a read borrow is already outstanding on the same
-/// thread — which would indicate a re-entrancy bug in a JS-callable
-/// closure that's holding a borrow across a nested script call.
+/// Returns `None` if any other borrow is outstanding on the same
+/// thread — e.g. a JS-callable closure holding a borrow across a
+/// nested script call — rather than panicking.
 pub(crate) fn with_dom_mut<R, F>(f: F) -> Option<R>
 where
     F: FnOnce(&mut DomTree) -> R,
 {
     CURRENT.with(|cell| {
-        cell.borrow()
-            .as_ref()
-            .map(|ctx| f(&mut ctx.handle.borrow_mut()))
+        let current = cell.borrow();
+        let mut tree = current.as_ref()?.handle.try_borrow_mut().ok()?;
+        Some(f(&mut tree))
     })
 }
 
@@ -217,6 +222,22 @@ mod tests {
         assert!(with_dom(|_| ()).is_none(), "binding restored on drop");
     }
 
+    #[test]
+    fn conflicting_borrows_return_none_instead_of_panicking() {
+        let tree = Rc::new(RefCell::new(DomTree::new()));
+        let _g = guard(Rc::clone(&tree));
+
+        // A re-entrant mutation while a read is in progress.
+        let nested = with_dom(|_| with_dom_mut(|_| ())).unwrap();
+        assert!(nested.is_none());
+
+        // The host holding a mutable borrow across a DOM call.
+        let host = tree.borrow_mut();
+        assert!(with_dom(|_| ()).is_none());
+        drop(host);
+        assert!(with_dom_mut(|_| ()).is_some());
+    }
+
     #[test]
     fn dirty_flag_isolated_per_guard() {
         let outer = Rc::new(RefCell::new(DomTree::new()));
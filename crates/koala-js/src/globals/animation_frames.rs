@@ -0,0 +1,173 @@
+//! Animation frames — `requestAnimationFrame` and
+//! `cancelAnimationFrame`, plus the read side of the hidden callback
+//! array used by [`crate::JsRuntime::run_animation_frame`].
+//!
+//! [§ 8.10 Animation frames](https://html.spec.whatwg.org/multipage/imagebitmap-and-animations.html#animation-frames)
+//!
+//! ### Storage model
+//!
+//! Mirrors the timers module: `requestAnimationFrame(fn)` pushes
+//! `fn` onto the hidden global array [`ANIMATION_FRAMES_KEY`] so
+//! Boa's GC keeps it alive, and returns `array_index + 1` as the
+//! handle. `cancelAnimationFrame(handle)` nulls the slot.
+//!
+//! After each frame the slots it ran are dropped from the front of
+//! the array, so a `requestAnimationFrame(step)` loop keeps it one
+//! slot long instead of growing it by one slot per frame. The hidden
+//! [`ANIMATION_FRAME_BASE_KEY`] counts the dropped slots and is added
+//! to every handle, so handles keep increasing and a stale handle
+//! never cancels a newer callback.
+//!
+//! There is no scheduler entry: nothing fires on its own. The
+//! embedder decides when a frame happens and calls
+//! [`crate::JsRuntime::run_animation_frame`], which runs every slot
+//! requested since the previous frame. Callbacks requested while a
+//! frame runs land past the frame's end index and wait for the next
+//! one.
+
+use boa_engine::{
+    Context, JsError, JsNativeError, JsResult, JsValue, NativeFunction, js_string,
+    object::builtins::JsArray, property::Attribute,
+};
+
+/// Hidden global where requested animation frame callbacks are
+/// parked until the frame that runs them. Ran and cancelled slots
+/// are set to `null`.
+const ANIMATION_FRAMES_KEY: &str = "__koala_animation_frames__";
+
+/// Hidden global holding how many slots earlier frames have dropped
+/// from the front of [`ANIMATION_FRAMES_KEY`]. The handle of the slot
+/// at index `i` is `base + i + 1`.
+const ANIMATION_FRAME_BASE_KEY: &str = "__koala_animation_frame_base__";
+
+/// Register `requestAnimationFrame`, `cancelAnimationFrame`, and the
+/// [`ANIMATION_FRAMES_KEY`] backing array on the given context.
+pub fn register_animation_frames(context: &mut Context) {
+    let arr = JsArray::new(context);
+    context
+        .register_global_property(js_string!(ANIMATION_FRAMES_KEY), arr, Attribute::all())
+        .expect("__koala_animation_frames__ should not already exist");
+    context
+        .register_global_property(js_string!(ANIMATION_FRAME_BASE_KEY), 0, Attribute::all())
+        .expect("__koala_animation_frame_base__ should not already exist");
+
+    context
+        .register_global_callable(
+            js_string!("requestAnimationFrame"),
+            1,
+            NativeFunction::from_copy_closure(request_animation_frame),
+        )
+        .expect("requestAnimationFrame should not already be registered");
+    context
+        .register_global_callable(
+            js_string!("cancelAnimationFrame"),
+            1,
+            NativeFunction::from_copy_closure(cancel_animation_frame),
+        )
+        .expect("cancelAnimationFrame should not already be registered");
+}
+
+/// [§ 8.10 `requestAnimationFrame(callback)`](https://html.spec.whatwg.org/multipage/imagebitmap-and-animations.html#dom-animationframeprovider-requestanimationframe)
+///
+/// "Set target's map of animation frame callbacks[handle] to
+/// callback. Return handle."
+///
+/// The callback argument is a `WebIDL` callback function, so anything
+/// not callable is a `TypeError`.
+fn request_animation_frame(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let callback = args
+        .first()
+        .filter(|v| v.as_object().is_some_and(|o| o.is_callable()))
+        .ok_or_else(|| {
+            JsError::from_native(
+                JsNativeError::typ()
+                    .with_message("requestAnimationFrame requires a callback function"),
+            )
+        })?;
+
+    let frames = animation_frames_array(context)?;
+    let index = frames.length(context)?;
+    let _ = frames.push(callback.clone(), context)?;
+
+    // Like timer ids, handles start at 1 so `0` is never a live one.
+    let handle = u32::try_from(animation_frame_base(context)? + index + 1).map_err(|_| {
+        JsError::from_native(
+            JsNativeError::range().with_message("requestAnimationFrame: too many callbacks"),
+        )
+    })?;
+    Ok(JsValue::from(handle))
+}
+
+/// [§ 8.10 `cancelAnimationFrame(handle)`](https://html.spec.whatwg.org/multipage/imagebitmap-and-animations.html#animationframeprovider-cancelanimationframe)
+///
+/// "Remove target's map of animation frame callbacks[handle]."
+///
+/// Unknown, already-run and already-cancelled handles are ignored.
+fn cancel_animation_frame(
+    _this: &JsValue,
+    args: &[JsValue],
+    context: &mut Context,
+) -> JsResult<JsValue> {
+    let handle = args.first().map_or(0, |v| v.to_u32(context).unwrap_or(0));
+    let frames = animation_frames_array(context)?;
+    // Handles at or below the base belong to slots already dropped.
+    let base = animation_frame_base(context)?;
+    if let Some(index) = u64::from(handle).checked_sub(base + 1)
+        && index < frames.length(context)?
+    {
+        let _ = frames.set(index, JsValue::null(), false, context)?;
+    }
+    Ok(JsValue::undefined())
+}
+
+/// Drop the first `count` slots — the ones a frame just ran — from
+/// the `__koala_animation_frames__` array, keeping the callbacks
+/// requested while the frame ran, and advance the handle base past
+/// them.
+pub(crate) fn retire_animation_frames(context: &mut Context, count: u64) -> JsResult<()> {
+    let frames = animation_frames_array(context)?;
+    let length = frames.length(context)?;
+    let remaining = JsArray::new(context);
+    for index in count..length {
+        let callback = frames.get(index, context)?;
+        let _ = remaining.push(callback, context)?;
+    }
+
+    let base = animation_frame_base(context)? + count;
+    let global = context.global_object();
+    let _ = global.set(js_string!(ANIMATION_FRAMES_KEY), remaining, false, context)?;
+    #[allow(clippy::cast_precision_loss)] // a JS number; exact well past any u32 handle
+    let _ = global.set(
+        js_string!(ANIMATION_FRAME_BASE_KEY),
+        base as f64,
+        false,
+        context,
+    )?;
+    Ok(())
+}
+
+/// Read the `__koala_animation_frame_base__` global.
+fn animation_frame_base(context: &mut Context) -> JsResult<u64> {
+    let global = context.global_object();
+    let value = global.get(js_string!(ANIMATION_FRAME_BASE_KEY), context)?;
+    value.to_length(context)
+}
+
+/// Get the `__koala_animation_frames__` global as a [`JsArray`]
+/// handle. Used by `requestAnimationFrame` and the runtime's frame
+/// step.
+pub(crate) fn animation_frames_array(context: &mut Context) -> JsResult<JsArray> {
+    let global = context.global_object();
+    let value = global.get(js_string!(ANIMATION_FRAMES_KEY), context)?;
+    let object = value.as_object().ok_or_else(|| {
+        JsError::from_native(
+            JsNativeError::typ()
+                .with_message("__koala_animation_frames__ is missing or not an object"),
+        )
+    })?;
+    JsArray::from_object(object)
+}
@@ -4,12 +4,92 @@
 //!
 //! This module implements the `console` global object with `log`, `warn`,
 //! and `error` methods that output to stdout/stderr.
+//!
+//! Every logged line is also appended to a per-thread capture buffer
+//! so host code and tests can assert on script output via
+//! [`JsRuntime::take_console_output`](crate::JsRuntime::take_console_output).
+//! The buffer lives in a thread-local for the same reason the DOM
+//! handle does (see [`crate::dom_handle`]): the console methods are
+//! `from_copy_closure` functions and can't capture an `Rc`.
+
+use std::cell::RefCell;
 
 use boa_engine::{
     Context, JsResult, JsValue, NativeFunction, js_string, object::ObjectInitializer,
     property::Attribute,
 };
 
+/// Captured console output for the calling thread, plus whether each
+/// line is also echoed to the terminal.
+struct ConsoleSink {
+    lines: Vec<String>,
+    echo: bool,
+}
+
+thread_local! {
+    static SINK: RefCell<Option<ConsoleSink>> = const { RefCell::new(None) };
+}
+
+/// Install an empty capture buffer for the calling thread, returning a
+/// [`ConsoleGuard`] that restores the previous one on drop. Mirrors
+/// [`crate::scheduler::guard`]; echoing to the terminal starts enabled.
+#[must_use = "the guard tears down the capture buffer on drop; bind to `_guard`"]
+pub(crate) fn guard() -> ConsoleGuard {
+    let previous = SINK.with(|cell| {
+        cell.borrow_mut().replace(ConsoleSink {
+            lines: Vec::new(),
+            echo: true,
+        })
+    });
+    ConsoleGuard { previous }
+}
+
+pub(crate) struct ConsoleGuard {
+    previous: Option<ConsoleSink>,
+}
+
+impl Drop for ConsoleGuard {
+    fn drop(&mut self) {
+        let prev = self.previous.take();
+        SINK.with(|cell| {
+            *cell.borrow_mut() = prev;
+        });
+    }
+}
+
+/// Drain the lines captured since the last call. Empty when no
+/// buffer is installed.
+pub(crate) fn take_output() -> Vec<String> {
+    SINK.with(|cell| {
+        cell.borrow_mut()
+            .as_mut()
+            .map(|sink| std::mem::take(&mut sink.lines))
+            .unwrap_or_default()
+    })
+}
+
+/// Turn terminal echoing of console output on or off. Lines are
+/// captured either way.
+pub(crate) fn set_echo(echo: bool) {
+    SINK.with(|cell| {
+        if let Some(sink) = cell.borrow_mut().as_mut() {
+            sink.echo = echo;
+        }
+    });
+}
+
+/// Append `line` to the capture buffer and report whether the caller
+/// should also print it. With no buffer installed the line is only
+/// printed.
+fn capture(line: &str) -> bool {
+    SINK.with(|cell| {
+        cell.borrow_mut().as_mut().is_none_or(|sink| {
+            sink.lines.push(line.to_string());
+            sink.echo
+        })
+    })
+}
+
 /// Register the console global object on the context.
 ///
 /// [§ 1.1 Logging](https://console.spec.whatwg.org/#logging)
@@ -19,6 +99,9 @@ use boa_engine::{
 /// - `console.warn(...args)` - Logs to stdout with warning prefix
 /// - `console.error(...args)` - Logs to stderr
 ///
+/// All three also append the formatted line to the capture buffer
+/// installed by [`guard`].
+///
 /// # Not Yet Implemented
 ///
 /// The following Console Standard methods are not yet implemented:
@@ -74,7 +157,9 @@ pub fn register_console(context: &mut Context) {
 /// "Perform Logger("log", data)."
 fn console_log(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
     let output = format_console_args(args, context)?;
-    println!("[JS] {output}");
+    if capture(&output) {
+        println!("[JS] {output}");
+    }
     Ok(JsValue::undefined())
 }
 
@@ -85,7 +170,9 @@ fn console_log(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsRe
 /// "Perform Logger("warn", data)."
 fn console_warn(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
     let output = format_console_args(args, context)?;
-    println!("[JS WARN] {output}");
+    if capture(&output) {
+        println!("[JS WARN] {output}");
+    }
     Ok(JsValue::undefined())
 }
 
@@ -96,7 +183,9 @@ fn console_warn(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsR
 /// "Perform Logger("error", data)."
 fn console_error(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
     let output = format_console_args(args, context)?;
-    eprintln!("[JS ERROR] {output}");
+    if capture(&output) {
+        eprintln!("[JS ERROR] {output}");
+    }
     Ok(JsValue::undefined())
 }
 
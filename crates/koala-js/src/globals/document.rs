@@ -146,14 +146,7 @@ fn get_element_by_id(
         return Ok(JsValue::null());
     }
 
-    let node = with_dom(|dom| {
-        dom.iter_all().find(|&id| {
-            dom.as_element(id)
-                .and_then(|e| e.id())
-                .is_some_and(|got| got == &id_arg)
-        })
-    })
-    .flatten();
+    let node = with_dom(|dom| dom.get_element_by_id(&id_arg)).flatten();
 
     match node {
         Some(node_id) => make_element_object(context, node_id),
@@ -214,6 +207,9 @@ fn query_selector_all(
 /// element. Tag name comparison is ASCII case-insensitive for HTML
 /// documents.
 ///
+/// NOTE: The spec returns a live `HTMLCollection`; this returns an
+/// array snapshotting the tree at the time of the call.
+///
 /// [§ 4.5 Document.getElementsByTagName](https://dom.spec.whatwg.org/#dom-document-getelementsbytagname)
 fn get_elements_by_tag_name(
     _this: &JsValue,
@@ -221,25 +217,16 @@ fn get_elements_by_tag_name(
     context: &mut Context,
 ) -> JsResult<JsValue> {
     let name = required_string_arg(args, 0, "getElementsByTagName", "qualifiedName", context)?;
-    let wildcard = name == "*";
-    let needle = name.to_ascii_lowercase();
 
-    let ids: Vec<NodeId> = with_dom(|dom| {
-        dom.iter_all()
-            .filter(|&id| {
-                dom.as_element(id)
-                    .is_some_and(|e| wildcard || e.tag_name.eq_ignore_ascii_case(&needle))
-            })
-            .collect()
-    })
-    .unwrap_or_default();
+    let ids = with_dom(|dom| dom.get_elements_by_tag_name(&name)).unwrap_or_default();
 
     array_of_element_objects(ids, context)
 }
 
 /// `document.getElementsByClassName(classNames)` — all elements
 /// whose class set contains *every* class in the space-separated
-/// `classNames` argument.
+/// `classNames` argument. Like `getElementsByTagName`, the result
+/// is a snapshot array rather than a live collection.
 ///
 /// [§ 4.5 Document.getElementsByClassName](https://dom.spec.whatwg.org/#dom-document-getelementsbyclassname)
 fn get_elements_by_class_name(
@@ -248,22 +235,8 @@ fn get_elements_by_class_name(
     context: &mut Context,
 ) -> JsResult<JsValue> {
     let raw = required_string_arg(args, 0, "getElementsByClassName", "classNames", context)?;
-    let needle: Vec<String> = raw.split_whitespace().map(str::to_string).collect();
-    if needle.is_empty() {
-        return Ok(boa_engine::object::builtins::JsArray::new(context).into());
-    }
 
-    let ids: Vec<NodeId> = with_dom(|dom| {
-        dom.iter_all()
-            .filter(|&id| {
-                dom.as_element(id).is_some_and(|e| {
-                    let classes = e.classes();
-                    needle.iter().all(|c| classes.contains(c.as_str()))
-                })
-            })
-            .collect()
-    })
-    .unwrap_or_default();
+    let ids = with_dom(|dom| dom.get_elements_by_class_name(&raw)).unwrap_or_default();
 
     array_of_element_objects(ids, context)
 }
@@ -302,14 +302,8 @@ pub(super) fn children_get(
     context: &mut Context,
 ) -> JsResult<JsValue> {
     let node_id = node_id_from_this(this, context)?;
-    let child_ids: Vec<NodeId> = with_dom(|dom| {
-        dom.children(node_id)
-            .iter()
-            .copied()
-            .filter(|&id| dom.as_element(id).is_some())
-            .collect()
-    })
-    .unwrap_or_default();
+    let child_ids: Vec<NodeId> =
+        with_dom(|dom| dom.element_children(node_id).collect()).unwrap_or_default();
     array_of_element_objects(child_ids, context)
 }
 
@@ -319,13 +313,7 @@ pub(super) fn first_element_child_get(
     context: &mut Context,
 ) -> JsResult<JsValue> {
     let node_id = node_id_from_this(this, context)?;
-    let first = with_dom(|dom| {
-        dom.children(node_id)
-            .iter()
-            .copied()
-            .find(|&id| dom.as_element(id).is_some())
-    })
-    .flatten();
+    let first = with_dom(|dom| dom.first_element_child(node_id)).flatten();
     match first {
         Some(id) => make_element_object(context, id),
         None => Ok(JsValue::null()),
@@ -338,14 +326,7 @@ pub(super) fn last_element_child_get(
     context: &mut Context,
 ) -> JsResult<JsValue> {
     let node_id = node_id_from_this(this, context)?;
-    let last = with_dom(|dom| {
-        dom.children(node_id)
-            .iter()
-            .copied()
-            .rev()
-            .find(|&id| dom.as_element(id).is_some())
-    })
-    .flatten();
+    let last = with_dom(|dom| dom.last_element_child(node_id)).flatten();
     match last {
         Some(id) => make_element_object(context, id),
         None => Ok(JsValue::null()),
@@ -358,17 +339,7 @@ pub(super) fn next_element_sibling_get(
     context: &mut Context,
 ) -> JsResult<JsValue> {
     let node_id = node_id_from_this(this, context)?;
-    let next = with_dom(|dom| {
-        let mut cur = dom.next_sibling(node_id);
-        while let Some(id) = cur {
-            if dom.as_element(id).is_some() {
-                return Some(id);
-            }
-            cur = dom.next_sibling(id);
-        }
-        None
-    })
-    .flatten();
+    let next = with_dom(|dom| dom.next_element_sibling(node_id)).flatten();
     match next {
         Some(id) => make_element_object(context, id),
         None => Ok(JsValue::null()),
@@ -381,17 +352,7 @@ pub(super) fn previous_element_sibling_get(
     context: &mut Context,
 ) -> JsResult<JsValue> {
     let node_id = node_id_from_this(this, context)?;
-    let prev = with_dom(|dom| {
-        let mut cur = dom.prev_sibling(node_id);
-        while let Some(id) = cur {
-            if dom.as_element(id).is_some() {
-                return Some(id);
-            }
-            cur = dom.prev_sibling(id);
-        }
-        None
-    })
-    .flatten();
+    let prev = with_dom(|dom| dom.previous_element_sibling(node_id)).flatten();
     match prev {
         Some(id) => make_element_object(context, id),
         None => Ok(JsValue::null()),
@@ -439,13 +400,7 @@ pub(super) fn child_element_count_get(
     context: &mut Context,
 ) -> JsResult<JsValue> {
     let node_id = node_id_from_this(this, context)?;
-    let count = with_dom(|dom| {
-        dom.children(node_id)
-            .iter()
-            .filter(|&&id| dom.as_element(id).is_some())
-            .count()
-    })
-    .unwrap_or(0);
+    let count = with_dom(|dom| dom.element_children(node_id).count()).unwrap_or(0);
     Ok(JsValue::from(count as f64))
 }
 
@@ -1,15 +1,20 @@
-//! `location` global — the minimal subset of the
+//! `location` global — the subset of the
 //! [`Location`](https://html.spec.whatwg.org/multipage/nav-history-apis.html#the-location-interface)
-//! interface that testharness.js relies on for self-identification:
-//! `.href`, `.search`, `.pathname`.
+//! interface that scripts commonly read: `.href`, `.protocol`,
+//! `.host`, `.pathname`, `.search`, `.hash`.
 //!
 //! [§ 7.7.1 The Location interface](https://html.spec.whatwg.org/multipage/nav-history-apis.html#the-location-interface)
 //!
 //! The full interface has 13+ accessor pairs, `assign` / `replace`
 //! / `reload` methods, origin policy checks, and round-trips
-//! through the URL parser. We expose three read-only string
-//! properties. Anything testharness.js touches beyond these is
-//! deferred until a real test demands it.
+//! through the URL parser. We expose six string properties, of
+//! which only `href` is writable. Anything beyond these is
+//! deferred until a real page demands it.
+//!
+//! Assigning `location.href` doesn't navigate — koala-js has no
+//! way to load a new document. The requested URL is recorded
+//! instead, for the embedder to pick up through
+//! [`crate::JsRuntime::take_pending_navigation`].
 //!
 //! ### Where the URL comes from
 //!
@@ -35,7 +40,12 @@
 //! Output rules match what major browsers expose:
 //! - `pathname` is `/` when the authority has no path. Opaque
 //!   `data:` / `mailto:` URLs return an empty pathname.
+//! - `protocol` is the scheme followed by `:`.
+//! - `host` is the authority minus any `user:pass@` prefix, or the
+//!   empty string for opaque URLs.
 //! - `search` includes the leading `?`, or is the empty string.
+//! - `hash` includes the leading `#`, or is the empty string when
+//!   the fragment is missing or empty.
 //! - `href` round-trips the input verbatim.
 
 use boa_engine::{
@@ -57,10 +67,16 @@ pub(crate) const HREF_KEY: &str = "__koala_location_href__";
 /// Document" state.
 pub(crate) const DEFAULT_HREF: &str = "about:blank";
 
+/// Hidden global slot that holds the URL most recently assigned to
+/// `location.href`, or `undefined` when no navigation is pending.
+/// Drained by [`crate::JsRuntime::take_pending_navigation`].
+pub(crate) const PENDING_NAVIGATION_KEY: &str = "__koala_pending_navigation__";
+
 /// Register the `location` global. Called from
 /// [`super::register_globals`] once at runtime construction; the
 /// underlying `href` slot is later mutated by
 /// [`crate::JsRuntime::set_location`] without re-registering.
+#[allow(clippy::similar_names)] // href_{getter,setter} and host_/hash_getter are intentional
 pub(super) fn register_location(context: &mut Context) {
     context
         .register_global_property(
@@ -71,8 +87,12 @@ pub(super) fn register_location(context: &mut Context) {
         .expect("__koala_location_href__ should not already exist");
 
     let href_getter = getter(context, href_get);
+    let href_setter = getter(context, href_set);
+    let protocol_getter = getter(context, protocol_get);
+    let host_getter = getter(context, host_get);
     let search_getter = getter(context, search_get);
     let pathname_getter = getter(context, pathname_get);
+    let hash_getter = getter(context, hash_get);
     let to_string_fn = NativeFunction::from_copy_closure(to_string_native);
 
     let accessor_attrs = Attribute::CONFIGURABLE | Attribute::ENUMERABLE;
@@ -81,9 +101,16 @@ pub(super) fn register_location(context: &mut Context) {
         .accessor(
             js_string!("href"),
             Some(href_getter),
+            Some(href_setter),
+            accessor_attrs,
+        )
+        .accessor(
+            js_string!("protocol"),
+            Some(protocol_getter),
             None,
             accessor_attrs,
         )
+        .accessor(js_string!("host"), Some(host_getter), None, accessor_attrs)
         .accessor(
             js_string!("search"),
             Some(search_getter),
@@ -96,6 +123,7 @@ pub(super) fn register_location(context: &mut Context) {
             None,
             accessor_attrs,
         )
+        .accessor(js_string!("hash"), Some(hash_getter), None, accessor_attrs)
         .function(to_string_fn, js_string!("toString"), 0)
         .build();
 
@@ -118,6 +146,38 @@ fn href_get(
     Ok(js_string_value(&read_href(context)?))
 }
 
+/// `location.href` (setter) — record `value` as a pending
+/// navigation. The current document keeps running and `href`
+/// keeps reporting the document's own URL.
+fn href_set(_this: &JsValue, args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let url = args
+        .first()
+        .cloned()
+        .unwrap_or_default()
+        .to_string(context)?;
+    let global = context.global_object();
+    let _ = global.set(js_string!(PENDING_NAVIGATION_KEY), url, false, context)?;
+    Ok(JsValue::undefined())
+}
+
+/// `location.protocol` — the URL's scheme followed by `:`.
+fn protocol_get(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let href = read_href(context)?;
+    Ok(js_string_value(&parse_protocol(&href)))
+}
+
+/// `location.host` — the URL's host and port.
+fn host_get(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let href = read_href(context)?;
+    Ok(js_string_value(parse_host(&href)))
+}
+
+/// `location.hash` — the fragment, including the leading `#`.
+fn hash_get(_this: &JsValue, _args: &[JsValue], context: &mut Context) -> JsResult<JsValue> {
+    let href = read_href(context)?;
+    Ok(js_string_value(parse_hash(&href)))
+}
+
 /// `location.search` — the query string portion of the URL,
 /// including the leading `?`. Empty when there is no query.
 fn search_get(
@@ -176,6 +236,48 @@ pub(crate) fn parse_search(href: &str) -> &str {
     }
 }
 
+/// [§ 7.7.1 `protocol`](https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-protocol)
+///
+/// "return this's url's scheme, followed by ":"." Empty when `href`
+/// has no recognisable scheme.
+pub(crate) fn parse_protocol(href: &str) -> String {
+    if !has_scheme_prefix(href) {
+        return String::new();
+    }
+    href.split_once(':')
+        .map(|(scheme, _)| format!("{}:", scheme.to_ascii_lowercase()))
+        .unwrap_or_default()
+}
+
+/// [§ 7.7.1 `host`](https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-host)
+///
+/// The authority of a `scheme://authority/...` URL with any
+/// `user:pass@` credentials removed. Empty for opaque URLs and for
+/// `file:///path`, whose authority is empty.
+pub(crate) fn parse_host(href: &str) -> &str {
+    let Some(after_scheme) = scheme_slash_slash_remainder(href) else {
+        return "";
+    };
+    let authority = after_scheme
+        .find(['/', '?', '#'])
+        .map_or(after_scheme, |i| &after_scheme[..i]);
+    authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host)
+}
+
+/// [§ 7.7.1 `hash`](https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-hash)
+///
+/// "If this's url's fragment is either null or the empty string,
+/// return the empty string." Otherwise the fragment with its
+/// leading `#`.
+pub(crate) fn parse_hash(href: &str) -> &str {
+    match href.find('#') {
+        Some(i) if i + 1 < href.len() => &href[i..],
+        _ => "",
+    }
+}
+
 /// Extract the `pathname` portion of `href`. The rules differ by
 /// scheme shape and are spelled out in the module-level comment.
 pub(crate) fn parse_pathname(href: &str) -> &str {
@@ -288,6 +390,31 @@ mod tests {
         assert_eq!(parse_search("about:blank"), "");
     }
 
+    #[test]
+    fn protocol_is_the_lowercased_scheme_with_a_colon() {
+        assert_eq!(parse_protocol("HTTPS://example.com/"), "https:");
+        assert_eq!(parse_protocol("about:blank"), "about:");
+        assert_eq!(parse_protocol("/no/scheme"), "");
+    }
+
+    #[test]
+    fn host_strips_credentials_and_keeps_the_port() {
+        assert_eq!(
+            parse_host("http://user:pw@example.com:8080/a"),
+            "example.com:8080"
+        );
+        assert_eq!(parse_host("http://example.com?q"), "example.com");
+        assert_eq!(parse_host("file:///tmp/index.html"), "");
+        assert_eq!(parse_host("about:blank"), "");
+    }
+
+    #[test]
+    fn hash_is_empty_for_missing_or_empty_fragment() {
+        assert_eq!(parse_hash("http://example.com/a#top"), "#top");
+        assert_eq!(parse_hash("http://example.com/a#"), "");
+        assert_eq!(parse_hash("http://example.com/a"), "");
+    }
+
     #[test]
     fn search_strips_fragment_before_extracting_query() {
         // Fragments come AFTER queries in the URL — but if the
@@ -15,10 +15,11 @@
 //!   — plus a minimal `Event` constructor. Dispatch is
 //!   strict-target-only for now; see [`events`] for the
 //!   deferred-bubbling note.
+//! - `location` — [§ 7.7.1 The Location interface](https://html.spec.whatwg.org/multipage/nav-history-apis.html#the-location-interface)
+//!   (URL components of the document address; see [`location`])
 //!
 //! # Not Yet Implemented
 //!
-//! - `location` — [§ 7.7.1 The Location interface](https://html.spec.whatwg.org/multipage/nav-history-apis.html#the-location-interface)
 //! - `navigator` — [§ 8.8 The Navigator object](https://html.spec.whatwg.org/multipage/system-state.html#the-navigator-object)
 //! - Event-handler IDL attributes (`window.onload = fn`,
 //!   `document.onreadystatechange`, …)
@@ -30,7 +31,8 @@
 #[macro_use]
 pub(crate) mod macros;
 
-mod console;
+pub(crate) mod animation_frames;
+pub(crate) mod console;
 pub(crate) mod document;
 pub(crate) mod dom_exception;
 pub(crate) mod element;
@@ -78,6 +80,7 @@ pub fn register_globals(context: &mut Context) {
     console::register_console(context);
     document::register_document(context);
     timers::register_timers(context);
+    animation_frames::register_animation_frames(context);
     events::register_events(context);
     location::register_location(context);
     window::register_event_target(context);
@@ -22,7 +22,7 @@
 //!
 //! # Not yet implemented
 //!
-//! - `window.location`, `window.navigator`, `window.history`, …
+//! - `window.navigator`, `window.history`, …
 //! - Event-handler IDL attributes (`window.onload`, …) — today
 //!   listeners are registered via `addEventListener`, not by
 //!   assigning to `on*` properties.
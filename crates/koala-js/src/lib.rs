@@ -41,6 +41,8 @@
 //!   - `setTimeout`, `clearTimeout`
 //!   - `setInterval`, `clearInterval` (shared id pool with the
 //!     timeout variants)
+//! - `requestAnimationFrame` / `cancelAnimationFrame`, stepped by
+//!   the embedder through [`JsRuntime::run_animation_frame`]
 //! - EventTarget (Phase 3 chunk 3):
 //!   - `addEventListener` / `removeEventListener` /
 //!     `dispatchEvent` on `window`, `document`, and `Element`
@@ -51,7 +53,8 @@
 //!     [`JsRuntime::dispatch_load`]
 //! - testharness.js dependency stubs (Phase 5 chunk 2):
 //!   - `self` alias for `window`
-//!   - `location` with `.href`, `.search`, `.pathname` plumbed
+//!   - `location` with `.href`, `.protocol`, `.host`, `.pathname`,
+//!     `.search`, `.hash` plumbed
 //!     through [`JsRuntime::set_location`]
 //!   - `setTimeout` / `setInterval` trailing-args forwarding
 //!   - `'error'` event dispatch via
@@ -82,7 +85,7 @@ pub use dom_handle::DomHandle;
 use std::cell::Cell;
 use std::time::{Duration, Instant};
 
-use boa_engine::{Context, JsError, JsString, JsValue, Source, js_string};
+use boa_engine::{Context, JsError, JsObject, JsString, JsValue, Source, js_string};
 
 /// JavaScript runtime for a document.
 ///
@@ -116,6 +119,12 @@ pub struct JsRuntime {
     /// a live scheduler.
     #[allow(dead_code)] // RAII only; the compiler can't see Drop as a "read"
     scheduler_guard: scheduler::SchedulerGuard,
+    /// Installs the console capture buffer in the per-thread slot
+    /// for the life of this runtime, the same way `scheduler_guard`
+    /// does for timers. Drained by
+    /// [`take_console_output`](Self::take_console_output).
+    #[allow(dead_code)] // RAII only; the compiler can't see Drop as a "read"
+    console_guard: globals::console::ConsoleGuard,
 }
 
 impl JsRuntime {
@@ -134,6 +143,7 @@ impl JsRuntime {
         // scheduler instance handles every script + pump cycle for
         // this runtime.
         let scheduler_guard = scheduler::guard();
+        let console_guard = globals::console::guard();
         let mut context = Context::default();
         globals::register_globals(&mut context);
         Self {
@@ -141,6 +151,7 @@ impl JsRuntime {
             dom,
             dom_dirty: Cell::new(false),
             scheduler_guard,
+            console_guard,
         }
     }
 
@@ -372,6 +383,66 @@ impl JsRuntime {
         Ok(())
     }
 
+    /// Run the animation frame callbacks requested since the previous
+    /// frame, passing each `timestamp_ms`.
+    ///
+    /// [§ 8.10 Run the animation frame callbacks](https://html.spec.whatwg.org/multipage/imagebitmap-and-animations.html#run-the-animation-frame-callbacks)
+    ///
+    /// "1. Let callbacks be target's map of animation frame callbacks.
+    ///  2. Let callbackHandles be the result of getting the keys of
+    ///     callbacks.
+    ///  3. For each handle in callbackHandles, if handle exists in
+    ///     callbacks: 1. Let callback be callbacks[handle]. 2. Remove
+    ///     callbacks[handle]. 3. Invoke callback with « now » and
+    ///     "report"."
+    ///
+    /// Koala has no rendering loop of its own, so the embedder (or a
+    /// test) decides when a frame happens by calling this. Callbacks
+    /// requested while the frame runs are not in `callbackHandles`
+    /// and wait for the next call — the usual
+    /// `requestAnimationFrame(step)` loop runs once per frame.
+    ///
+    /// Mutations performed inside the callbacks accumulate into
+    /// `dom_dirty` the same way they do for `execute` calls.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first `JsError` thrown by a callback. Per
+    /// "report", a throwing callback does not stop the rest of the
+    /// frame's callbacks from running.
+    pub fn run_animation_frame(&mut self, timestamp_ms: f64) -> Result<(), JsError> {
+        let dom_guard = dom_handle::guard(self.dom.clone());
+        let frames = globals::animation_frames::animation_frames_array(&mut self.context)?;
+
+        // STEP 2: Snapshot the handles — the array's current length
+        // is the end of this frame.
+        let end = frames.length(&mut self.context)?;
+        let global = JsValue::from(self.context.global_object());
+        let args = [JsValue::from(timestamp_ms)];
+        let mut first_error = None;
+
+        // STEP 3: Cancelled slots were nulled, so "if handle exists"
+        // is a callable check.
+        for index in 0..end {
+            let callback = frames.get(index, &mut self.context)?;
+            let _ = frames.set(index, JsValue::null(), false, &mut self.context)?;
+            let Some(callback) = callback.as_object().filter(JsObject::is_callable) else {
+                continue;
+            };
+            if let Err(err) = callback.call(&global, &args, &mut self.context) {
+                let _ = first_error.get_or_insert(err);
+            }
+        }
+        globals::animation_frames::retire_animation_frames(&mut self.context, end)?;
+
+        self.context.run_jobs();
+        if dom_guard.dirty_seen() {
+            self.dom_dirty.set(true);
+        }
+        drop(dom_guard);
+        first_error.map_or(Ok(()), Err)
+    }
+
     /// Look up a timer callback by id, call it with `this = window`,
     /// and for one-shots clear the array slot so the closure can be
     /// collected. Interval slots stay live because the same id is
@@ -441,8 +512,32 @@ impl JsRuntime {
         self.dom_dirty.replace(false)
     }
 
-    /// Update the URL exposed through `location.href` /
-    /// `location.search` / `location.pathname`.
+    /// Drain the lines logged through `console.log` / `warn` /
+    /// `error` since the last call.
+    ///
+    /// [§ 2.1 Formatter](https://console.spec.whatwg.org/#formatter)
+    ///
+    /// Each entry is one call's arguments joined with spaces, without
+    /// the `[JS]` terminal prefix — `console.log('hi', 42)` captures
+    /// `"hi 42"`.
+    #[allow(clippy::unused_self)] // the buffer is per-thread but belongs to this runtime
+    pub fn take_console_output(&self) -> Vec<String> {
+        globals::console::take_output()
+    }
+
+    /// Choose whether console output is also printed to the terminal.
+    ///
+    /// Echoing is on by default. Output is captured for
+    /// [`take_console_output`](Self::take_console_output) either way,
+    /// so tests can turn echoing off to keep their output quiet.
+    #[allow(clippy::unused_self)] // the buffer is per-thread but belongs to this runtime
+    pub fn set_console_echo(&self, echo: bool) {
+        globals::console::set_echo(echo);
+    }
+
+    /// Update the URL exposed through `location.href` and the
+    /// components derived from it (`protocol`, `host`, `pathname`,
+    /// `search`, `hash`).
     ///
     /// [§ 7.7.1 The Location interface](https://html.spec.whatwg.org/multipage/nav-history-apis.html#the-location-interface)
     ///
@@ -466,6 +561,26 @@ impl JsRuntime {
         );
     }
 
+    /// Take the URL a script last assigned to `location.href`, if
+    /// any.
+    ///
+    /// [§ 7.7.1 The Location interface](https://html.spec.whatwg.org/multipage/nav-history-apis.html#dom-location-href)
+    ///
+    /// koala-js can't navigate on its own, so the assignment is
+    /// only recorded; a front end that can load documents calls
+    /// this after scripts run and follows the URL. Clears the
+    /// pending navigation.
+    pub fn take_pending_navigation(&mut self) -> Option<String> {
+        let global = self.context.global_object();
+        let key = js_string!(globals::location::PENDING_NAVIGATION_KEY);
+        let value = global.get(key.clone(), &mut self.context).ok()?;
+        if value.is_undefined() {
+            return None;
+        }
+        let _ = global.set(key, JsValue::undefined(), false, &mut self.context);
+        value.as_string().map(|url| url.to_std_string_escaped())
+    }
+
     /// Run `f` with mutable access to the embedded Boa
     /// [`Context`]. Returns whatever `f` returns.
     ///
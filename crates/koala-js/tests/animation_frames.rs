@@ -0,0 +1,95 @@
+//! `requestAnimationFrame` integration tests.
+//!
+//! Animation frames only run when the host steps them through
+//! `JsRuntime::run_animation_frame`, so these tests drive frames by
+//! hand with explicit timestamps.
+
+use koala_js::JsRuntime;
+
+mod common;
+use common::list_fixture;
+
+#[test]
+fn request_animation_frame_runs_once_per_stepped_frame() {
+    let mut rt = JsRuntime::new(list_fixture());
+    let _ = rt
+        .execute(
+            "globalThis.frames = 0;\
+             globalThis.last = 0;\
+             function step(now) {\
+                 globalThis.frames += 1;\
+                 globalThis.last = now;\
+                 requestAnimationFrame(step);\
+             }\
+             requestAnimationFrame(step);",
+        )
+        .unwrap();
+    // Nothing runs until the host steps a frame.
+    assert_eq!(rt.eval_to_string("globalThis.frames").unwrap(), "0");
+
+    rt.run_animation_frame(16.0).unwrap();
+    // The re-registered callback waits for the next frame.
+    assert_eq!(rt.eval_to_string("globalThis.frames").unwrap(), "1");
+    assert_eq!(rt.eval_to_string("globalThis.last").unwrap(), "16");
+
+    rt.run_animation_frame(32.0).unwrap();
+    assert_eq!(rt.eval_to_string("globalThis.frames").unwrap(), "2");
+    assert_eq!(rt.eval_to_string("globalThis.last").unwrap(), "32");
+}
+
+#[test]
+fn cancel_animation_frame_prevents_the_callback() {
+    let mut rt = JsRuntime::new(list_fixture());
+    let _ = rt
+        .execute(
+            "globalThis.fired = false;\
+             const handle = requestAnimationFrame(function() { globalThis.fired = true; });\
+             cancelAnimationFrame(handle);",
+        )
+        .unwrap();
+    rt.run_animation_frame(16.0).unwrap();
+    assert_eq!(rt.eval_to_string("globalThis.fired").unwrap(), "false");
+}
+
+#[test]
+fn request_animation_frame_rejects_non_callable_callbacks() {
+    let mut rt = JsRuntime::new(list_fixture());
+    assert!(rt.execute("requestAnimationFrame(42)").is_err());
+}
+
+#[test]
+fn ran_animation_frames_are_dropped_and_handles_stay_unique() {
+    let mut rt = JsRuntime::new(list_fixture());
+    let _ = rt
+        .execute(
+            "globalThis.handles = [];\
+             function step() { globalThis.handles.push(requestAnimationFrame(step)); }\
+             globalThis.handles.push(requestAnimationFrame(step));",
+        )
+        .unwrap();
+    for frame in 1..=3 {
+        rt.run_animation_frame(f64::from(frame) * 16.0).unwrap();
+    }
+    // Only the callback requested by the last frame is still parked.
+    assert_eq!(
+        rt.eval_to_string("__koala_animation_frames__.length")
+            .unwrap(),
+        "1"
+    );
+    assert_eq!(
+        rt.eval_to_string("globalThis.handles.join()").unwrap(),
+        "1,2,3,4"
+    );
+
+    // A handle from an earlier frame must not cancel the pending one.
+    let _ = rt
+        .execute(
+            "globalThis.fired = false;\
+             cancelAnimationFrame(1);\
+             requestAnimationFrame(function() { globalThis.fired = true; });",
+        )
+        .unwrap();
+    rt.run_animation_frame(64.0).unwrap();
+    assert_eq!(rt.eval_to_string("globalThis.fired").unwrap(), "true");
+    assert_eq!(rt.eval_to_string("globalThis.handles.length").unwrap(), "5");
+}
@@ -86,6 +86,56 @@ pub fn list_fixture() -> DomHandle {
     Rc::new(RefCell::new(tree))
 }
 
+/// Like [`list_fixture`], but the `<ul>` also holds whitespace text and a
+/// comment between its items, as parsed markup would:
+/// `<ul id="list">\n  <li id="a">A</li>\n  <!-- b -->\n  <li id="b">B</li>\n</ul>`.
+pub fn mixed_list_fixture() -> DomHandle {
+    let mut tree = DomTree::new();
+    let root = tree.root();
+    let html = tree.alloc(NodeType::Element(ElementData {
+        tag_name: "html".to_string(),
+        attrs: AttributesMap::new(),
+    }));
+    tree.append_child(root, html);
+    let body = tree.alloc(NodeType::Element(ElementData {
+        tag_name: "body".to_string(),
+        attrs: AttributesMap::new(),
+    }));
+    tree.append_child(html, body);
+
+    let mut list_attrs = AttributesMap::new();
+    let _ = list_attrs.insert("id".into(), "list".into());
+    let list = tree.alloc(NodeType::Element(ElementData {
+        tag_name: "ul".into(),
+        attrs: list_attrs,
+    }));
+    tree.append_child(body, list);
+
+    for id in ["a", "b"] {
+        let whitespace = tree.alloc(NodeType::Text("\n  ".into()));
+        tree.append_child(list, whitespace);
+        if id == "b" {
+            let comment = tree.alloc(NodeType::Comment(" b ".into()));
+            tree.append_child(list, comment);
+            let whitespace = tree.alloc(NodeType::Text("\n  ".into()));
+            tree.append_child(list, whitespace);
+        }
+        let mut attrs = AttributesMap::new();
+        let _ = attrs.insert("id".into(), id.into());
+        let li = tree.alloc(NodeType::Element(ElementData {
+            tag_name: "li".into(),
+            attrs,
+        }));
+        tree.append_child(list, li);
+        let text = tree.alloc(NodeType::Text(id.to_ascii_uppercase()));
+        tree.append_child(li, text);
+    }
+    let trailing = tree.alloc(NodeType::Text("\n".into()));
+    tree.append_child(list, trailing);
+
+    Rc::new(RefCell::new(tree))
+}
+
 /// `<html><head><title>koala test page</title></head><body></body></html>`.
 /// Used by `document.head` and `document.title` tests.
 pub fn fixture_with_head() -> DomHandle {
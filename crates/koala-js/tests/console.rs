@@ -0,0 +1,31 @@
+//! Console capture tests.
+//!
+//! `console.log` / `warn` / `error` append each formatted line to the
+//! runtime's capture buffer; `take_console_output` drains it.
+
+use koala_js::JsRuntime;
+
+mod common;
+use common::fixture;
+
+#[test]
+fn console_log_output_is_captured() {
+    let mut rt = JsRuntime::new(fixture());
+    rt.set_console_echo(false);
+    let _ = rt.execute("console.log('hi', 42)").unwrap();
+    assert_eq!(rt.take_console_output(), vec!["hi 42".to_string()]);
+}
+
+#[test]
+fn take_console_output_drains_the_buffer() {
+    let mut rt = JsRuntime::new(fixture());
+    rt.set_console_echo(false);
+    let _ = rt
+        .execute("console.warn('careful'); console.error('broken', true)")
+        .unwrap();
+    assert_eq!(
+        rt.take_console_output(),
+        vec!["careful".to_string(), "broken true".to_string()]
+    );
+    assert!(rt.take_console_output().is_empty());
+}
@@ -1,7 +1,7 @@
 //! Phase-5 chunk-2 testharness.js dependency integration tests.
 //!
 //! Covers the four shims that testharness.js leans on:
-//! `self === window`, `location.href` / `.search` / `.pathname`,
+//! `self === window`, `location` and its URL components,
 //! `setTimeout` trailing-args forwarding, and `'error'` event
 //! dispatch on uncaught script errors via
 //! [`JsRuntime::dispatch_error`].
@@ -60,6 +60,41 @@ fn set_location_updates_href_and_derived_components() {
     );
 }
 
+#[test]
+fn location_exposes_every_url_component() {
+    let mut rt = JsRuntime::new(list_fixture());
+    rt.set_location("http://example.com/a/b?c#d");
+    assert_eq!(rt.eval_to_string("location.pathname").unwrap(), "/a/b");
+    assert_eq!(rt.eval_to_string("location.protocol").unwrap(), "http:");
+    assert_eq!(rt.eval_to_string("location.host").unwrap(), "example.com");
+    assert_eq!(rt.eval_to_string("location.search").unwrap(), "?c");
+    assert_eq!(rt.eval_to_string("location.hash").unwrap(), "#d");
+    assert_eq!(
+        rt.eval_to_string("window.location === location").unwrap(),
+        "true",
+    );
+}
+
+#[test]
+fn assigning_location_href_records_a_pending_navigation() {
+    let mut rt = JsRuntime::new(list_fixture());
+    rt.set_location("http://example.com/a");
+    assert_eq!(rt.take_pending_navigation(), None);
+    let _ = rt
+        .execute("location.href = 'http://example.com/next'")
+        .unwrap();
+    // The current document keeps its own URL.
+    assert_eq!(
+        rt.eval_to_string("location.href").unwrap(),
+        "http://example.com/a",
+    );
+    assert_eq!(
+        rt.take_pending_navigation().as_deref(),
+        Some("http://example.com/next"),
+    );
+    assert_eq!(rt.take_pending_navigation(), None);
+}
+
 #[test]
 fn location_to_string_returns_href() {
     let mut rt = JsRuntime::new(list_fixture());
@@ -10,7 +10,7 @@ use koala_dom::NodeId;
 use koala_js::JsRuntime;
 
 mod common;
-use common::{fixture, fixture_with_head, list_fixture};
+use common::{fixture, fixture_with_head, list_fixture, mixed_list_fixture};
 
 #[test]
 fn document_is_a_global() {
@@ -235,6 +235,48 @@ fn next_and_previous_element_sibling() {
     assert!(first_prev.is_null());
 }
 
+#[test]
+fn element_navigation_skips_text_and_comment_nodes() {
+    let mut rt = JsRuntime::new(mixed_list_fixture());
+    assert_eq!(
+        rt.eval_to_string(
+            "var list = document.getElementById('list');\
+             var names = [];\
+             for (var i = 0; i < list.children.length; i++) {\
+                 names.push(list.children[i].tagName + '#' + list.children[i].id);\
+             }\
+             names.join(',')",
+        )
+        .unwrap(),
+        "LI#a,LI#b",
+    );
+    assert_eq!(
+        rt.eval_to_string("document.getElementById('list').childElementCount")
+            .unwrap(),
+        "2",
+    );
+    assert_eq!(
+        rt.eval_to_string("document.getElementById('list').firstElementChild.id")
+            .unwrap(),
+        "a",
+    );
+    assert_eq!(
+        rt.eval_to_string("document.getElementById('list').lastElementChild.id")
+            .unwrap(),
+        "b",
+    );
+    assert_eq!(
+        rt.eval_to_string("document.getElementById('a').nextElementSibling.id")
+            .unwrap(),
+        "b",
+    );
+    assert_eq!(
+        rt.eval_to_string("document.getElementById('b').previousElementSibling.id")
+            .unwrap(),
+        "a",
+    );
+}
+
 #[test]
 fn document_body_head_and_document_element() {
     let mut rt = JsRuntime::new(list_fixture());
@@ -446,6 +488,27 @@ fn get_elements_by_tag_name_returns_an_array() {
     assert!(all.parse::<usize>().unwrap() >= 5, "wildcard count was {all}");
 }
 
+#[test]
+fn get_elements_by_tag_name_is_case_insensitive_snapshot() {
+    let mut rt = JsRuntime::new(list_fixture());
+    assert_eq!(
+        rt.eval_to_string("document.getElementsByTagName('LI').length")
+            .unwrap(),
+        "3",
+    );
+    // The array is a snapshot: appending an element afterwards doesn't
+    // change an earlier result, but a fresh call sees it.
+    assert_eq!(
+        rt.eval_to_string(
+            "const items = document.getElementsByTagName('li'); \
+             document.getElementById('list').appendChild(document.createElement('li')); \
+             `${items.length} ${document.getElementsByTagName('li').length}`",
+        )
+        .unwrap(),
+        "3 4",
+    );
+}
+
 #[test]
 fn get_elements_by_class_name_matches_all_classes() {
     let mut rt = JsRuntime::new(fixture());
@@ -12,8 +12,8 @@
 //! `bench_diff.rs`).
 //!
 //! Only compiled when the `bench` feature is enabled. The
-//! `tracing` spans themselves live in `koala-browser` and
-//! `koala-cli::render` and are always emitted; without a
+//! `tracing` spans themselves live in `koala-browser` and are
+//! always emitted; without a
 //! subscriber registered, dispatch is a few atomic loads and a
 //! function-pointer call — so non-bench builds carry the spans
 //! but pay no measurable cost.
@@ -130,7 +130,7 @@ pub(crate) fn run(
         doc = Some(loaded);
     }
 
-    let doc = doc.expect("setup_iterations clamped to >= 1, so the loop ran");
+    let mut doc = doc.expect("setup_iterations clamped to >= 1, so the loop ran");
     let setup_us = stats(&setup_us_samples);
     let setup_stages: BTreeMap<String, StageStats> = setup_stage_samples
         .into_iter()
@@ -146,7 +146,7 @@ pub(crate) fn run(
     let _ = take_events();
 
     for _ in 0..warmup {
-        let _ = render_document_once(&doc, width, height, &font_provider)?;
+        let _ = render_document_once(&mut doc, width, height, &font_provider)?;
         let _ = take_events();
     }
 
@@ -161,7 +161,7 @@ pub(crate) fn run(
     for _ in 0..iterations {
         let alloc_before = snapshot();
         reset_peak();
-        let _ = render_document_once(&doc, width, height, &font_provider)?;
+        let _ = render_document_once(&mut doc, width, height, &font_provider)?;
         // Snapshot before draining timing events so the drain's own
         // allocations don't land in this iteration's render delta.
         alloc_samples.push(AllocDelta::between(alloc_before, snapshot()));
@@ -5,6 +5,7 @@
 #[cfg(feature = "bench")]
 mod bench;
 mod render;
+mod screenshot_diff;
 
 // Heap accounting for `--bench` mode. Installed only under the
 // `bench` feature so the shipping renderer keeps the system
@@ -16,7 +17,7 @@ static GLOBAL: koala_common::alloc_count::CountingAllocator =
 
 mod wpt_protocol;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
 use koala_browser::{FontProvider, LoadedDocument, load_document, parse_html_string};
 use koala_css::LayoutBox;
@@ -80,6 +81,28 @@ struct Cli {
     #[arg(long, default_value = "720")]
     height: u32,
 
+    /// Render the page and compare it pixel by pixel with a baseline
+    /// PNG, for visual regression testing. The page is rendered at the
+    /// baseline's size. Exits nonzero when more than `--diff-threshold`
+    /// percent of the pixels differ.
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["layout", "screenshot"])]
+    diff_screenshot: Option<PathBuf>,
+
+    /// With `--diff-screenshot`, write an image highlighting the
+    /// differing pixels in red to this file.
+    #[arg(long, value_name = "FILE", requires = "diff_screenshot")]
+    diff_out: Option<PathBuf>,
+
+    /// With `--diff-screenshot`, the percentage of differing pixels
+    /// tolerated before failing (default: 0).
+    #[arg(
+        long,
+        default_value = "0",
+        value_name = "PERCENT",
+        requires = "diff_screenshot"
+    )]
+    diff_threshold: f64,
+
     /// Run in WPT protocol mode: read JSON-line commands from
     /// stdin, emit JSON-line events on stdout. Used by the
     /// wptrunner browser plugin to drive koala under upstream WPT.
@@ -98,6 +121,19 @@ struct Cli {
     #[arg(long, value_name = "FILE")]
     hosts_file: Option<PathBuf>,
 
+    /// Path to a CSS file to apply as the user stylesheet. Its rules
+    /// override the browser defaults but lose to the page's own rules,
+    /// unless marked `!important`.
+    #[arg(long, value_name = "FILE")]
+    user_css: Option<PathBuf>,
+
+    /// Largest response body, in bytes, accepted for any fetched
+    /// resource (the page, stylesheets, scripts and images). Bigger
+    /// responses fail to load instead of being read into memory.
+    /// Default 64 MiB.
+    #[arg(long, default_value = "67108864", value_name = "N")]
+    max_fetch_bytes: u64,
+
     /// Perf-harness mode: load the page once, render it N+warmup
     /// times, emit a per-stage timing JSON report on stdout.
     /// Requires the `bench` cargo feature (which enables the
@@ -179,6 +215,7 @@ fn main() -> Result<()> {
         koala_browser::hosts::set_from_file(path)
             .map_err(|e| anyhow::anyhow!("failed to load --hosts-file '{}': {e}", path.display()))?;
     }
+    let _fetch_limit = koala_browser::net::install_fetch_limit(cli.max_fetch_bytes);
 
     // Protocol mode owns its own input loop and rendering pipeline;
     // dispatch before any CLI-style argument validation runs.
@@ -261,8 +298,15 @@ fn main() -> Result<()> {
         }
     }
 
+    // Read the user stylesheet before fetching, so a bad path fails fast.
+    let user_stylesheet = cli
+        .user_css
+        .as_deref()
+        .map(load_user_stylesheet)
+        .transpose()?;
+
     // Determine the document source
-    let doc = if let Some(html_string) = cli.html {
+    let mut doc = if let Some(html_string) = cli.html {
         parse_html_string(&html_string)
     } else if let Some(path) = cli.path {
         load_document(&path)?
@@ -270,10 +314,23 @@ fn main() -> Result<()> {
         // clap should prevent this, but just in case
         anyhow::bail!("Either a file/URL path or --html must be provided");
     };
+    if let Some(stylesheet) = user_stylesheet {
+        doc.set_user_stylesheet(stylesheet);
+    }
+
+    // Handle visual regression mode
+    if let Some(ref baseline_path) = cli.diff_screenshot {
+        return screenshot_diff::run(
+            &doc,
+            baseline_path,
+            cli.diff_out.as_deref(),
+            cli.diff_threshold,
+        );
+    }
 
     // Handle screenshot mode
     if let Some(ref output_path) = cli.screenshot {
-        take_screenshot(&doc, output_path, cli.width, cli.height)?;
+        take_screenshot(&mut doc, output_path, cli.width, cli.height)?;
         println!("Screenshot saved to: {}", output_path.display());
         return Ok(());
     }
@@ -287,9 +344,17 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Read and parse the `--user-css` file.
+fn load_user_stylesheet(path: &Path) -> Result<koala_css::Stylesheet> {
+    let css = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read --user-css '{}'", path.display()))?;
+    let mut parser = koala_css::CSSParser::new(koala_css::CSSTokenizer::tokenize(&css));
+    Ok(parser.parse_stylesheet())
+}
+
 /// Take a screenshot of the rendered page and save to file.
 fn take_screenshot(
-    doc: &LoadedDocument,
+    doc: &mut LoadedDocument,
     output_path: &Path,
     width: u32,
     height: u32,
@@ -312,7 +377,9 @@ fn print_subheader(text: &str) {
 /// Print document information to stdout.
 fn print_document(doc: &LoadedDocument) {
     print_header("DOM Tree");
-    print_dom_tree(&doc.dom, doc.dom.root(), 0);
+    let dom = doc.dom.borrow();
+    print_dom_tree(&dom, dom.root(), 0);
+    drop(dom);
 
     print_header("Stylesheet");
     let rule_count = doc.stylesheet.rules.len();
@@ -408,6 +475,9 @@ fn print_dom_tree(tree: &DomTree, id: NodeId, indent: usize) {
                 " -->".dimmed()
             );
         }
+        NodeType::DocumentType { name, .. } => {
+            println!("{}{}", prefix, format!("<!DOCTYPE {name}>").dimmed());
+        }
     }
 
     for &child_id in tree.children(id) {
@@ -439,6 +509,9 @@ fn print_layout(doc: &LoadedDocument) {
         };
         let font_provider = FontProvider::load();
         let font_metrics = font_provider.metrics();
+        // NOTE: The layout is printed as seen from the top of the page, so
+        // `content-visibility: auto` boxes are only relevant to the user
+        // within this initial viewport.
         layout.layout(viewport, viewport, &*font_metrics, viewport);
 
         print_layout_box(&layout, 0, doc);
@@ -455,11 +528,11 @@ fn print_layout_box(layout_box: &LayoutBox, depth: usize, doc: &LoadedDocument)
     let dims = &layout_box.dimensions;
 
     // Get box name with tag if available
+    let dom = doc.dom.borrow();
     let name = match &layout_box.box_type {
-        koala_css::BoxType::Principal(node_id) => doc.dom.as_element(*node_id).map_or_else(
+        koala_css::BoxType::Principal(node_id) => dom.as_element(*node_id).map_or_else(
             || {
-                if doc
-                    .dom
+                if dom
                     .get(*node_id)
                     .is_some_and(|n| matches!(n.node_type, NodeType::Document))
                 {
@@ -563,8 +636,9 @@ fn print_layout_box(layout_box: &LayoutBox, depth: usize, doc: &LoadedDocument)
 fn print_computed_styles(doc: &LoadedDocument) {
     use koala_css::AutoLength;
 
+    let dom = doc.dom.borrow();
     for (node_id, style) in &doc.styles {
-        let Some(element) = doc.dom.as_element(*node_id) else {
+        let Some(element) = dom.as_element(*node_id) else {
             continue;
         };
 
@@ -0,0 +1,174 @@
+//! `--diff-screenshot` visual regression mode.
+//!
+//! Renders the page with [`koala_browser::render_to_rgba`], compares it
+//! pixel by pixel against a baseline PNG, and reports how many pixels
+//! differ. The page is rendered at the baseline's size so the two images
+//! always line up; `--width`/`--height` are ignored in this mode.
+
+use anyhow::{Context, Result};
+use image::{Rgba, RgbaImage};
+use koala_browser::LoadedDocument;
+use std::path::Path;
+
+/// Pixel counts from comparing two images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PixelDiff {
+    /// Pixels whose RGBA values differ.
+    pub(crate) differing: u64,
+    /// Pixels compared.
+    pub(crate) total: u64,
+}
+
+impl PixelDiff {
+    /// The share of differing pixels, from 0 to 100.
+    #[allow(clippy::cast_precision_loss)] // pixel counts fit comfortably in an f64
+    pub(crate) fn percent(self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.differing as f64 * 100.0 / self.total as f64
+        }
+    }
+}
+
+/// Compare `actual` with `baseline` and build a visualization of the
+/// differences: differing pixels are painted solid red over a faded copy
+/// of the baseline.
+///
+/// Pixels that only one of the images covers (when their sizes differ)
+/// count as differing.
+pub(crate) fn diff_images(actual: &RgbaImage, baseline: &RgbaImage) -> (PixelDiff, RgbaImage) {
+    let width = actual.width().max(baseline.width());
+    let height = actual.height().max(baseline.height());
+    let mut visualization = RgbaImage::new(width, height);
+    let mut differing = 0;
+
+    for (x, y, out) in visualization.enumerate_pixels_mut() {
+        let expected = baseline.get_pixel_checked(x, y);
+        let same = expected.is_some() && expected == actual.get_pixel_checked(x, y);
+        *out = match expected {
+            Some(Rgba([r, g, b, _])) if same => {
+                // Fade identical pixels toward white so the red
+                // differences stand out.
+                let luma = (u16::from(*r) + u16::from(*g) + u16::from(*b)) / 3;
+                let faded = u8::try_from(192 + luma / 4).unwrap_or(u8::MAX);
+                Rgba([faded, faded, faded, 255])
+            }
+            _ => {
+                differing += 1;
+                Rgba([255, 0, 0, 255])
+            }
+        };
+    }
+
+    let diff = PixelDiff {
+        differing,
+        total: u64::from(width) * u64::from(height),
+    };
+    (diff, visualization)
+}
+
+/// Render `doc`, compare it to the PNG at `baseline_path`, and print the
+/// result. If `diff_out` is set, the difference visualization is written
+/// there.
+///
+/// # Errors
+///
+/// Returns an error if the baseline can't be read, the visualization
+/// can't be written, or more than `threshold` percent of the pixels
+/// differ.
+pub(crate) fn run(
+    doc: &LoadedDocument,
+    baseline_path: &Path,
+    diff_out: Option<&Path>,
+    threshold: f64,
+) -> Result<()> {
+    let baseline = image::open(baseline_path)
+        .with_context(|| {
+            format!(
+                "while attempting to load baseline '{}'",
+                baseline_path.display()
+            )
+        })?
+        .into_rgba8();
+
+    let (pixels, width, height) =
+        koala_browser::render_to_rgba(doc, baseline.width(), baseline.height());
+    let actual = RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| anyhow::anyhow!("rendered buffer does not match its dimensions"))?;
+
+    let (diff, visualization) = diff_images(&actual, &baseline);
+    if let Some(path) = diff_out {
+        visualization
+            .save(path)
+            .with_context(|| format!("while attempting to save diff image '{}'", path.display()))?;
+        println!("Diff image saved to: {}", path.display());
+    }
+
+    println!(
+        "{} of {} pixels differ ({:.3}%)",
+        diff.differing,
+        diff.total,
+        diff.percent()
+    );
+    if diff.percent() > threshold {
+        anyhow::bail!(
+            "screenshot differs from '{}' by {:.3}%, above the {threshold}% threshold",
+            baseline_path.display(),
+            diff.percent()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PixelDiff, diff_images};
+    use image::{Rgba, RgbaImage};
+
+    fn checkerboard() -> RgbaImage {
+        RgbaImage::from_fn(8, 8, |x, y| {
+            if (x + y) % 2 == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn identical_images_have_no_differences() {
+        let image = checkerboard();
+        let (diff, _) = diff_images(&image, &image);
+        assert_eq!(
+            diff,
+            PixelDiff {
+                differing: 0,
+                total: 64
+            }
+        );
+        assert!(diff.percent().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn modified_pixels_are_counted_and_highlighted() {
+        let baseline = checkerboard();
+        let mut actual = baseline.clone();
+        actual.put_pixel(1, 2, Rgba([0, 128, 0, 255]));
+        actual.put_pixel(5, 5, Rgba([0, 0, 255, 255]));
+
+        let (diff, visualization) = diff_images(&actual, &baseline);
+        assert_eq!(diff.differing, 2);
+        assert!((diff.percent() - 3.125).abs() < 1e-9);
+        assert_eq!(*visualization.get_pixel(1, 2), Rgba([255, 0, 0, 255]));
+        assert_ne!(*visualization.get_pixel(0, 0), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn size_mismatch_counts_uncovered_pixels() {
+        let baseline = checkerboard();
+        let actual = RgbaImage::from_fn(8, 4, |x, y| *baseline.get_pixel(x, y));
+        let (diff, _) = diff_images(&actual, &baseline);
+        assert_eq!(diff.differing, 32);
+    }
+}
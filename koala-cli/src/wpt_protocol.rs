@@ -272,8 +272,8 @@ fn render_url(
     height: u32,
     font_provider: &FontProvider,
 ) -> Result<()> {
-    let doc = load_document(url).context("while attempting to load document")?;
-    render_document_to_path(&doc, output_path, width, height, font_provider)
+    let mut doc = load_document(url).context("while attempting to load document")?;
+    render_document_to_path(&mut doc, output_path, width, height, font_provider)
 }
 
 /// Load `url`, run its scripts through the koala-wpt testharness
@@ -32,36 +32,17 @@
 use std::any::Any;
 use std::panic::{AssertUnwindSafe, catch_unwind};
 use std::sync::mpsc::{self, Receiver, Sender};
-use std::sync::{Arc, OnceLock};
+use std::sync::{Arc, Mutex};
 use std::thread::{self, JoinHandle};
 
-use koala_browser::css::{
-    ComputedStyle, DisplayListBuilder, LayoutBox, Rect, canvas_background,
-};
+use koala_browser::css::{ComputedStyle, LayoutBox};
 use koala_browser::dom::{DomTree, NodeId};
 use koala_browser::{
-    FontProvider, LoadedDocument, LoadedImage, Renderer, RendererFonts, load_document,
-    parse_html_string,
+    FontProvider, LoadedDocument, LoadedImage, RenderInput, RendererFonts, build_layout_tree,
+    fetch_images_near_viewport, load_document, origin_viewport, parse_html_string, render_page,
 };
 use slint::{Image, Rgba8Pixel, SharedPixelBuffer};
 
-// Process-wide `RendererFonts` cache. Loading four font files from
-// disk costs ~250 ms on macOS; doing it per render was the dominant
-// cost before the rasterizer rework. Now loaded exactly once, shared
-// across every `BrowserPage` and every render via `Arc` clones.
-fn cached_fonts() -> &'static RendererFonts {
-    static FONTS: OnceLock<RendererFonts> = OnceLock::new();
-    FONTS.get_or_init(RendererFonts::from_system)
-}
-
-// Process-wide `FontProvider` for layout-time metrics. Separate from
-// `RendererFonts` because koala-css's `FontMetrics` trait is keyed on
-// a single `fontdue::Font` handle that `FontProvider` owns.
-fn cached_font_provider() -> &'static FontProvider {
-    static PROVIDER: OnceLock<FontProvider> = OnceLock::new();
-    PROVIDER.get_or_init(FontProvider::load)
-}
-
 /// Summary returned by `try_take_load_result`. The GUI side uses
 /// `state_swapped` to decide whether to trigger a fresh render, and
 /// `load_finished` to toggle any loading indicator (success and
@@ -102,26 +83,47 @@ struct RenderResult {
 struct PageState {
     dom: DomTree,
     styles: koala_std::collections::HashMap<NodeId, ComputedStyle>,
-    layout_tree: LayoutBox,
-    images: koala_std::collections::HashMap<String, LoadedImage>,
+    // The URL each `<img>` selected, and the base URL they resolve
+    // against, for fetching the lazy images in `loaded`.
+    current_src: koala_std::collections::HashMap<NodeId, String>,
+    base_url: Option<String>,
+    // The parts that change as lazy images arrive. Only the render
+    // worker touches them; the mutex is what lets the state stay
+    // behind an `Arc`.
+    loaded: Mutex<LoadedParts>,
     // The document's `<title>` text content, trimmed. Empty when
     // the document has no `<title>` element or its text is
     // whitespace-only. Used to set the tab label.
     title: String,
 }
 
+/// The layout tree and images of a `PageState`, along with the
+/// `<img loading="lazy">` elements not fetched yet. Loading one
+/// rebuilds the layout tree with its dimensions.
+struct LoadedParts {
+    layout_tree: LayoutBox,
+    images: koala_std::collections::HashMap<String, LoadedImage>,
+    deferred_images: Vec<NodeId>,
+}
+
 impl PageState {
     /// Destructure a freshly-parsed `LoadedDocument` into the
     /// render-relevant fields, dropping the JS runtime and other
     /// parse-time debris. Returns `None` if the document produced
     /// no layout tree (happens only on pathological input).
     fn from_document(doc: LoadedDocument) -> Option<Self> {
-        let title = extract_title(&doc.dom);
+        let dom = doc.dom.take();
+        let title = extract_title(&dom);
         doc.layout_tree.map(|layout_tree| Self {
-            dom: doc.dom,
+            dom,
             styles: doc.styles,
-            layout_tree,
-            images: doc.images,
+            current_src: doc.current_src,
+            base_url: doc.base_url,
+            loaded: Mutex::new(LoadedParts {
+                layout_tree,
+                images: doc.images,
+                deferred_images: doc.deferred_images,
+            }),
             title,
         })
     }
@@ -697,36 +699,60 @@ fn error_state(url: &str, message: &str) -> Arc<PageState> {
 
 /// The full layout → paint → rasterize pipeline, taking a borrowed
 /// `PageState` rather than `&self` so it can run off the main thread.
+///
+/// Goes through `koala_browser::render_page` like the CLI, with the
+/// process-wide font caches: loading the font files from disk costs
+/// ~250 ms on macOS, so they are loaded once and shared by every
+/// `BrowserPage` and every render.
+///
+/// Every render is at a new viewport size (the render worker only
+/// runs on load and resize), so it first fetches the lazy images that
+/// came near the viewport and rebuilds the layout tree if any loaded.
+///
+/// NOTE: The page has no scrolling yet and is always shown from its
+/// top, so the origin viewport is the only one lazy images and
+/// `content-visibility: auto` boxes are tested against.
 fn render_state(state: &PageState, width: u32, height: u32) -> Vec<u8> {
-    let viewport = Rect {
-        x: 0.0,
-        y: 0.0,
-        width: width as f32,
-        height: height as f32,
-    };
-
-    let mut layout = state.layout_tree.clone();
-    let font_metrics = cached_font_provider().metrics();
-    layout.layout(viewport, viewport, &*font_metrics, viewport);
-
-    let builder = DisplayListBuilder::new(&state.styles);
-    let display_list = builder.build(&layout);
+    let mut loaded = state
+        .loaded
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let LoadedParts {
+        layout_tree,
+        images,
+        deferred_images,
+    } = &mut *loaded;
+    if !deferred_images.is_empty() {
+        let viewport = origin_viewport(width, height);
+        let font_metrics = FontProvider::shared().metrics();
+        let mut laid_out = layout_tree.clone();
+        laid_out.layout(viewport, viewport, &*font_metrics, viewport);
+        if fetch_images_near_viewport(
+            &laid_out,
+            deferred_images,
+            &state.current_src,
+            state.base_url.as_deref(),
+            images,
+            viewport,
+        ) && let Some(rebuilt) =
+            build_layout_tree(&state.dom, &state.styles, images, &state.current_src)
+        {
+            *layout_tree = rebuilt;
+        }
+    }
 
-    let mut renderer = Renderer::new_with_fonts(
+    let input = RenderInput {
+        dom: &state.dom,
+        styles: &state.styles,
+        layout_tree: Some(&*layout_tree),
+        images: &*images,
+    };
+    let renderer = render_page(
+        input,
         width,
         height,
-        state.images.clone(),
-        cached_fonts().clone(),
+        RendererFonts::shared(),
+        FontProvider::shared(),
     );
-
-    // Propagate the canvas background (CSS 2.1 § 14.2) so regions
-    // of the viewport not covered by painted content still show the
-    // html/body background colour instead of Renderer's default
-    // white fill.
-    if let Some(bg) = canvas_background(&state.dom, &state.styles) {
-        renderer.set_canvas_background(&bg);
-    }
-
-    renderer.render(&display_list);
     renderer.rgba_bytes().to_vec()
 }